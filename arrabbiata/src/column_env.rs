@@ -1 +1,104 @@
 //! This module will be used by the prover to evaluate at a certain point.
+
+use ark_ff::FftField;
+use ark_poly::{Evaluations, Radix2EvaluationDomain};
+use kimchi::circuits::{
+    domains::{Domain, EvaluationDomains},
+    expr::{ColumnEnvironment as TColumnEnvironment, Constants},
+};
+use strum::IntoEnumIterator;
+
+use crate::{
+    columns::{ChallengeTerm, Challenges, Column, Gadget},
+    proof::Columns,
+    NUMBER_OF_COLUMNS, NUMBER_OF_PUBLIC_INPUTS, NUMBER_OF_SELECTORS,
+};
+
+type Evals<F> = Evaluations<F, Radix2EvaluationDomain<F>>;
+
+/// The collection of polynomials (all in evaluation form) and constants
+/// required to evaluate an expression as a polynomial.
+///
+/// All are evaluations.
+pub struct ColumnEnvironment<'a, F: FftField> {
+    /// The columns of the circuit, i.e. the witness, the public inputs and the
+    /// selectors.
+    pub columns: &'a Columns<Evals<F>>,
+    /// The value `prod_{j != 1} (1 - ω^j)`, used for efficiently
+    /// computing the evaluations of the unnormalized Lagrange basis
+    /// polynomials.
+    pub l0_1: F,
+    /// Constant values required
+    pub constants: Constants<F>,
+    /// Challenges from the IOP.
+    pub challenges: Challenges<F>,
+    /// The domains used in the PLONK argument.
+    pub domain: EvaluationDomains<F>,
+}
+
+/// Return all the columns of the circuit, in the order used by the prover to
+/// commit, evaluate and open them.
+pub fn get_all_columns() -> Vec<Column> {
+    let mut cols = Vec::<Column>::with_capacity(
+        NUMBER_OF_COLUMNS + NUMBER_OF_PUBLIC_INPUTS + NUMBER_OF_SELECTORS,
+    );
+    for i in 0..NUMBER_OF_COLUMNS {
+        cols.push(Column::X(i));
+    }
+    for i in 0..NUMBER_OF_PUBLIC_INPUTS {
+        cols.push(Column::PublicInput(i));
+    }
+    for gadget in Gadget::iter() {
+        cols.push(Column::Selector(gadget));
+    }
+    cols
+}
+
+impl<T> Columns<T> {
+    pub fn get_column(&self, col: &Column) -> Option<&T> {
+        match *col {
+            Column::X(i) => self.witness.get(i),
+            Column::PublicInput(i) => self.public_inputs.get(i),
+            Column::Selector(gadget) => self.selectors.get(gadget as usize),
+        }
+    }
+}
+
+impl<'a, F: FftField> TColumnEnvironment<'a, F, ChallengeTerm, Challenges<F>>
+    for ColumnEnvironment<'a, F>
+{
+    type Column = Column;
+
+    fn get_column(&self, col: &Self::Column) -> Option<&'a Evals<F>> {
+        self.columns.get_column(col)
+    }
+
+    fn get_domain(&self, d: Domain) -> Radix2EvaluationDomain<F> {
+        match d {
+            Domain::D1 => self.domain.d1,
+            Domain::D2 => self.domain.d2,
+            Domain::D4 => self.domain.d4,
+            Domain::D8 => self.domain.d8,
+        }
+    }
+
+    fn column_domain(&self, _col: &Self::Column) -> Domain {
+        Domain::D8
+    }
+
+    fn get_constants(&self) -> &Constants<F> {
+        &self.constants
+    }
+
+    fn get_challenges(&self) -> &Challenges<F> {
+        &self.challenges
+    }
+
+    fn vanishes_on_zero_knowledge_and_previous_rows(&self) -> &'a Evals<F> {
+        panic!("Zero-knowledge is not supported for now")
+    }
+
+    fn l0_1(&self) -> F {
+        self.l0_1
+    }
+}
@@ -15,7 +15,7 @@ use strum_macros::{EnumCount as EnumCountMacro, EnumIter};
 // It might not be that obvious to do so, as the Instruction enum could be
 // defining operations that are not "fixed" in the circuit, but rather
 // depend on runtime values (e.g. in a zero-knowledge virtual machine).
-#[derive(Debug, Clone, Copy, PartialEq, EnumCountMacro, EnumIter)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumCountMacro, EnumIter)]
 pub enum Gadget {
     App,
     // Elliptic curve related gadgets
@@ -29,7 +29,7 @@ pub enum Gadget {
     Poseidon,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Column {
     Selector(Gadget),
     PublicInput(usize),
@@ -51,7 +51,7 @@ pub struct Challenges<F: Field> {
     pub r: F,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ChallengeTerm {
     /// Challenge used to aggregate the constraints
     Alpha,
@@ -146,6 +146,10 @@ impl<Fp: PrimeField> InterpreterEnv for Env<Fp> {
         self.read_position(pos)
     }
 
+    fn fetch_app_public_input(&mut self, pos: Self::Position) -> Self::Variable {
+        self.read_position(pos)
+    }
+
     fn reset(&mut self) {
         self.idx_var = 0;
         self.idx_var_next_row = 0;
@@ -298,7 +302,7 @@ impl<Fp: PrimeField> InterpreterEnv for Env<Fp> {
             let two_y1 = y1.clone() + y1.clone();
             lambda.clone() * two_y1 - (three_x1_square + self.constant(self.a.clone()))
         };
-        let res = is_same_point.clone() * lhs + (self.one() - is_same_point.clone()) * rhs;
+        let res = (self.one() - is_same_point.clone()) * lhs + is_same_point.clone() * rhs;
         self.assert_zero(res);
         lambda
     }
@@ -369,4 +373,46 @@ impl<F: PrimeField> Env<F> {
 
         constraints
     }
+
+    /// Get all the constraints for the IVC circuit and the application, each
+    /// constraint being multiplied by the selector of the gadget it belongs
+    /// to.
+    ///
+    /// These are the constraints the prover must verify on every row of the
+    /// circuit. The multiplication by the selector increases the degree of the
+    /// constraints by one.
+    pub fn get_all_constraints_with_selectors(&self) -> Vec<E<F>> {
+        let mut env = self.clone();
+        env.reset();
+
+        let mut constraints = vec![];
+
+        let mut add_constraints_with_selector = |env: &mut Self| {
+            let gadget = env
+                .activated_gadget
+                .expect("A gadget must be activated to build the constraints");
+            let selector = Expr::Atom(ExprInner::Cell(Variable {
+                col: Column::Selector(gadget),
+                row: CurrOrNext::Curr,
+            }));
+            constraints.extend(env.constraints.iter().map(|c| selector.clone() * c.clone()));
+            env.reset();
+        };
+
+        [
+            Instruction::Poseidon(0),
+            Instruction::EllipticCurveScaling(0, 0),
+            Instruction::EllipticCurveAddition(0),
+        ]
+        .into_iter()
+        .for_each(|instr| {
+            interpreter::run_ivc(&mut env, instr);
+            add_constraints_with_selector(&mut env);
+        });
+
+        interpreter::run_app(&mut env);
+        add_constraints_with_selector(&mut env);
+
+        constraints
+    }
 }
@@ -0,0 +1,658 @@
+//! Accumulation of the instances of the IVC circuit, using the [folding]
+//! library.
+//!
+//! The instances built at the even iterations are over the scalar field of the
+//! first curve, and they are accumulated in a relaxed instance over the first
+//! curve. The instances built at the odd iterations are accumulated over the
+//! second curve. The witness columns and the public inputs are folded, while
+//! the selectors are the structure of the circuit, shared by all the instances.
+//!
+//! The accumulated instances are proven to be satisfied by the decider, see
+//! [crate::prover::prove_decider].
+
+use ark_ff::{Field, One, PrimeField, Zero};
+use ark_poly::{Evaluations, Radix2EvaluationDomain as D};
+use folding::{
+    expressions::{folding_expression, FoldingColumnTrait, FoldingCompatibleExprInner},
+    instance_witness::{Foldable, RelaxableInstance, RelaxableWitness},
+    Alphas, ExpExtension, FoldingCompatibleExpr, FoldingConfig, FoldingEnv, FoldingOutput,
+    FoldingScheme, Instance, RelaxedInstance, RelaxedWitness, Side, Witness,
+};
+use kimchi::{
+    circuits::{
+        domains::EvaluationDomains,
+        expr::{ConstantExprInner, ConstantTerm, ExprInner, Operations, Variable},
+        gate::CurrOrNext,
+    },
+    curve::KimchiCurve,
+};
+use mina_poseidon::FqSponge;
+use num_bigint::BigInt;
+use poly_commitment::{commitment::CommitmentCurve, ipa::SRS, PolyComm, SRS as _};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use std::marker::PhantomData;
+
+use crate::{
+    columns::{ChallengeTerm, Column, E},
+    proof::{AccumulatedInstance, Columns, FoldingStep},
+    prover::{get_constraints, witness_to_columns},
+    witness::Env,
+    NUMBER_OF_COLUMNS,
+};
+
+type Evals<F> = Evaluations<F, D<F>>;
+
+impl FoldingColumnTrait for Column {
+    fn is_witness(&self) -> bool {
+        // The public inputs change with the iteration, they are folded with
+        // the witness. The selectors are fixed by the circuit.
+        !matches!(self, Column::Selector(_))
+    }
+}
+
+/// An instance of the IVC circuit, as seen by the folding scheme.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldingInstance<G: CommitmentCurve> {
+    /// Commitments to the witness columns, followed by the commitments to the
+    /// public inputs
+    pub commitments: Vec<G>,
+    /// The challenges combining the constraints
+    pub alphas: Alphas<G::ScalarField>,
+    /// Blinder used to commit to the columns added by quadraticization
+    pub blinder: G::ScalarField,
+}
+
+impl<G: CommitmentCurve> Foldable<G::ScalarField> for FoldingInstance<G> {
+    fn combine(a: Self, b: Self, challenge: G::ScalarField) -> Self {
+        FoldingInstance {
+            commitments: a
+                .commitments
+                .into_iter()
+                .zip(b.commitments)
+                .map(|(a, b)| (a + b.mul(challenge)).into())
+                .collect(),
+            alphas: Alphas::combine(a.alphas, b.alphas, challenge),
+            blinder: a.blinder + challenge * b.blinder,
+        }
+    }
+}
+
+impl<G: CommitmentCurve> Instance<G> for FoldingInstance<G> {
+    fn to_absorb(&self) -> (Vec<G::ScalarField>, Vec<G>) {
+        (self.alphas.clone().powers(), self.commitments.clone())
+    }
+
+    fn get_alphas(&self) -> &Alphas<G::ScalarField> {
+        &self.alphas
+    }
+
+    fn get_blinder(&self) -> G::ScalarField {
+        self.blinder
+    }
+}
+
+/// The witness of an instance of the IVC circuit, as seen by the folding
+/// scheme.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldingWitness<F: PrimeField> {
+    /// Evaluations of the witness columns
+    pub witness: Vec<Evals<F>>,
+    /// Evaluations of the public inputs
+    pub public_inputs: Vec<Evals<F>>,
+}
+
+impl<F: PrimeField> FoldingWitness<F> {
+    /// Return the evaluations of the given column, if it is folded.
+    pub fn get_column(&self, col: &Column) -> Option<&Evals<F>> {
+        match *col {
+            Column::X(i) => self.witness.get(i),
+            Column::PublicInput(i) => self.public_inputs.get(i),
+            Column::Selector(_) => None,
+        }
+    }
+
+    /// Iterate over the columns, in the order of the commitments of
+    /// [FoldingInstance].
+    pub fn iter(&self) -> impl Iterator<Item = &Evals<F>> {
+        self.witness.iter().chain(self.public_inputs.iter())
+    }
+}
+
+impl<F: PrimeField> Foldable<F> for FoldingWitness<F> {
+    fn combine(a: Self, b: Self, challenge: F) -> Self {
+        let combine = |a: Vec<Evals<F>>, b: Vec<Evals<F>>| -> Vec<Evals<F>> {
+            a.into_iter()
+                .zip(b)
+                .map(|(mut a, b)| {
+                    a.evals
+                        .iter_mut()
+                        .zip(b.evals)
+                        .for_each(|(a, b)| *a += b * challenge);
+                    a
+                })
+                .collect()
+        };
+        FoldingWitness {
+            witness: combine(a.witness, b.witness),
+            public_inputs: combine(a.public_inputs, b.public_inputs),
+        }
+    }
+}
+
+impl<G: CommitmentCurve> Witness<G> for FoldingWitness<G::ScalarField> {}
+
+/// The structure of the IVC circuit, shared by all the instances: the
+/// evaluations of the selectors of the gadgets.
+#[derive(Debug, Clone)]
+pub struct FoldingStructure<F: PrimeField> {
+    pub selectors: Vec<Vec<F>>,
+}
+
+/// The environment used by the folding scheme to compute the cross terms.
+pub struct FoldingEnvironment<G: CommitmentCurve> {
+    /// The selectors, at ω
+    curr_selectors: Vec<Vec<G::ScalarField>>,
+    /// The selectors, at ζω. This is `curr_selectors` left shifted by 1
+    next_selectors: Vec<Vec<G::ScalarField>>,
+    /// Corresponds to the evaluations at ω, for both sides
+    curr_witnesses: [FoldingWitness<G::ScalarField>; 2],
+    /// Corresponds to the evaluations at ζω, for both sides.
+    /// This is `curr_witnesses` left shifted by 1
+    next_witnesses: [FoldingWitness<G::ScalarField>; 2],
+}
+
+impl<G: CommitmentCurve>
+    FoldingEnv<
+        G::ScalarField,
+        FoldingInstance<G>,
+        FoldingWitness<G::ScalarField>,
+        Column,
+        ChallengeTerm,
+        (),
+    > for FoldingEnvironment<G>
+{
+    type Structure = FoldingStructure<G::ScalarField>;
+
+    fn new(
+        structure: &Self::Structure,
+        _instances: [&FoldingInstance<G>; 2],
+        witnesses: [&FoldingWitness<G::ScalarField>; 2],
+    ) -> Self {
+        let curr_witnesses = [witnesses[0].clone(), witnesses[1].clone()];
+        let mut next_witnesses = curr_witnesses.clone();
+        for side in next_witnesses.iter_mut() {
+            for col in side.witness.iter_mut().chain(side.public_inputs.iter_mut()) {
+                col.evals.rotate_left(1);
+            }
+        }
+        let curr_selectors = structure.selectors.clone();
+        let mut next_selectors = curr_selectors.clone();
+        for selector in next_selectors.iter_mut() {
+            selector.rotate_left(1);
+        }
+        FoldingEnvironment {
+            curr_selectors,
+            next_selectors,
+            curr_witnesses,
+            next_witnesses,
+        }
+    }
+
+    fn col(&self, col: Column, curr_or_next: CurrOrNext, side: Side) -> &[G::ScalarField] {
+        match (col, curr_or_next) {
+            (Column::Selector(gadget), CurrOrNext::Curr) => &self.curr_selectors[gadget as usize],
+            (Column::Selector(gadget), CurrOrNext::Next) => &self.next_selectors[gadget as usize],
+            (_, CurrOrNext::Curr) => {
+                &self.curr_witnesses[side as usize]
+                    .get_column(&col)
+                    .unwrap()
+                    .evals
+            }
+            (_, CurrOrNext::Next) => {
+                &self.next_witnesses[side as usize]
+                    .get_column(&col)
+                    .unwrap()
+                    .evals
+            }
+        }
+    }
+
+    fn challenge(&self, _challenge: ChallengeTerm, _side: Side) -> G::ScalarField {
+        unreachable!("The constraints of the IVC circuit do not use any challenge")
+    }
+
+    fn selector(&self, _s: &(), _side: Side) -> &[G::ScalarField] {
+        unreachable!("The selectors of the IVC circuit are columns of the structure")
+    }
+}
+
+/// The configuration of the folding scheme accumulating the instances over
+/// the curve `G`.
+#[derive(Debug)]
+pub struct IVCFoldingConfig<G>(PhantomData<G>);
+
+impl<G: KimchiCurve> FoldingConfig for IVCFoldingConfig<G>
+where
+    G::BaseField: PrimeField,
+{
+    type Column = Column;
+    type Selector = ();
+    type Challenge = ChallengeTerm;
+    type Curve = G;
+    type Srs = SRS<G>;
+    type Instance = FoldingInstance<G>;
+    type Witness = FoldingWitness<G::ScalarField>;
+    type Structure = FoldingStructure<G::ScalarField>;
+    type Env = FoldingEnvironment<G>;
+}
+
+/// Convert an expression, given how to convert its atoms.
+fn convert<T, C: FoldingConfig>(
+    expr: &Operations<T>,
+    atom: &impl Fn(&T) -> FoldingCompatibleExpr<C>,
+) -> FoldingCompatibleExpr<C> {
+    let rec = |expr: &Operations<T>| Box::new(convert(expr, atom));
+    match expr {
+        Operations::Atom(inner) => atom(inner),
+        Operations::Pow(e, p) => FoldingCompatibleExpr::Pow(rec(e), *p),
+        Operations::Add(x, y) => FoldingCompatibleExpr::Add(rec(x), rec(y)),
+        Operations::Mul(x, y) => FoldingCompatibleExpr::Mul(rec(x), rec(y)),
+        Operations::Sub(x, y) => FoldingCompatibleExpr::Sub(rec(x), rec(y)),
+        Operations::Double(x) => FoldingCompatibleExpr::Double(rec(x)),
+        Operations::Square(x) => FoldingCompatibleExpr::Square(rec(x)),
+        Operations::Cache(_, e) => convert(e, atom),
+        Operations::IfFeature(_, _, _) => {
+            panic!("The constraints of the IVC circuit do not use optional features")
+        }
+    }
+}
+
+/// Convert a constraint of the IVC circuit into an expression the folding
+/// scheme can handle. The constraints only use literal constants and cells.
+pub fn to_folding_expression<G: KimchiCurve>(
+    expr: &E<G::ScalarField>,
+) -> FoldingCompatibleExpr<IVCFoldingConfig<G>>
+where
+    G::BaseField: PrimeField,
+{
+    convert(expr, &|inner| match inner {
+        ExprInner::Constant(constant) => convert(constant, &|inner| match inner {
+            ConstantExprInner::Constant(ConstantTerm::Literal(c)) => {
+                FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Constant(*c))
+            }
+            _ => panic!("The constraints of the IVC circuit only use literal constants"),
+        }),
+        ExprInner::Cell(var) => FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Cell(*var)),
+        _ => panic!("The constraints of the IVC circuit only use constants and cells"),
+    })
+}
+
+/// Return the constraint satisfied by the accumulated instances, i.e. the
+/// relaxed constraints of the circuit, quadraticized and combined with the
+/// alphas.
+pub fn final_constraint<G: KimchiCurve>(
+    constraints: &[E<G::ScalarField>],
+) -> FoldingCompatibleExpr<IVCFoldingConfig<G>>
+where
+    G::BaseField: PrimeField,
+{
+    let (expression, _, _) =
+        folding_expression(constraints.iter().map(to_folding_expression).collect());
+    expression.final_expression()
+}
+
+/// Evaluate the final constraint of the folding scheme, given the values of
+/// its atoms. It is used by the decider to evaluate the constraint on each row
+/// of the accumulated witness, and at the evaluation point.
+///
+/// The alphas must be as many as given by
+/// [FoldingCompatibleExpr::number_of_alphas], and `extended`
+/// must be defined on all the columns added by quadraticization.
+pub fn evaluate_final_constraint<G: KimchiCurve>(
+    expr: &FoldingCompatibleExpr<IVCFoldingConfig<G>>,
+    u: G::ScalarField,
+    alphas: &[G::ScalarField],
+    error: G::ScalarField,
+    extended: &impl Fn(usize) -> G::ScalarField,
+    column: &impl Fn(Variable<Column>) -> G::ScalarField,
+) -> G::ScalarField
+where
+    G::BaseField: PrimeField,
+{
+    let eval = |expr: &FoldingCompatibleExpr<IVCFoldingConfig<G>>| {
+        evaluate_final_constraint(expr, u, alphas, error, extended, column)
+    };
+    match expr {
+        FoldingCompatibleExpr::Atom(atom) => match atom {
+            FoldingCompatibleExprInner::Constant(c) => *c,
+            FoldingCompatibleExprInner::Cell(var) => column(*var),
+            FoldingCompatibleExprInner::Extensions(ExpExtension::U) => u,
+            FoldingCompatibleExprInner::Extensions(ExpExtension::Error) => error,
+            FoldingCompatibleExprInner::Extensions(ExpExtension::ExtendedWitness(i)) => {
+                extended(*i)
+            }
+            FoldingCompatibleExprInner::Extensions(ExpExtension::Alpha(i)) => alphas[*i],
+            FoldingCompatibleExprInner::Challenge(_)
+            | FoldingCompatibleExprInner::Extensions(ExpExtension::Selector(_)) => {
+                unreachable!("The constraints of the IVC circuit do not use challenges nor dynamic selectors")
+            }
+        },
+        FoldingCompatibleExpr::Double(e) => eval(e).double(),
+        FoldingCompatibleExpr::Square(e) => eval(e).square(),
+        FoldingCompatibleExpr::Add(e1, e2) => eval(e1) + eval(e2),
+        FoldingCompatibleExpr::Sub(e1, e2) => eval(e1) - eval(e2),
+        FoldingCompatibleExpr::Mul(e1, e2) => eval(e1) * eval(e2),
+        FoldingCompatibleExpr::Pow(e, k) => eval(e).pow([*k]),
+    }
+}
+
+/// A relaxed instance over the curve `G` in which the instances of the IVC
+/// circuit have been folded, with its witness.
+pub struct Accumulator<G: KimchiCurve>
+where
+    G::BaseField: PrimeField,
+{
+    pub instance: RelaxedInstance<G, FoldingInstance<G>>,
+    pub witness: RelaxedWitness<G, FoldingWitness<G::ScalarField>>,
+    /// The blinder of the commitments to the columns added by
+    /// quadraticization. The columns of a fresh instance are committed with a
+    /// blinder of one.
+    pub extended_blinder: G::ScalarField,
+    /// The blinder of the commitment to the error column. The cross terms are
+    /// committed with a blinder of one, so that folding with the challenge `r`
+    /// gives `b_left - r - r^2 + r^3 b_right`.
+    pub error_blinder: G::ScalarField,
+    /// The folding steps leading to the accumulator, replayed by the verifier
+    pub steps: Vec<FoldingStep<G>>,
+}
+
+impl<G: KimchiCurve> Accumulator<G>
+where
+    G::BaseField: PrimeField,
+{
+    /// Relax a fresh instance.
+    fn new(
+        instance: FoldingInstance<G>,
+        witness: FoldingWitness<G::ScalarField>,
+        zero_vec: &Evals<G::ScalarField>,
+    ) -> Self {
+        Accumulator {
+            extended_blinder: instance.blinder,
+            instance: RelaxableInstance::relax(instance),
+            witness: RelaxableWitness::relax(witness, zero_vec),
+            error_blinder: G::ScalarField::zero(),
+            steps: vec![],
+        }
+    }
+
+    /// Return the accumulated commitments to the witness columns, absorbed by
+    /// the IVC circuit of the next iteration.
+    pub fn witness_commitments(&self) -> Vec<PolyComm<G>> {
+        witness_commitments(&self.instance)
+    }
+
+    /// Return the accumulated instance, as given to the verifier.
+    pub fn to_accumulated_instance(&self) -> AccumulatedInstance<G> {
+        to_accumulated_instance(&self.instance)
+    }
+}
+
+/// Return the commitments to the witness columns of a relaxed instance.
+pub fn witness_commitments<G: CommitmentCurve>(
+    instance: &RelaxedInstance<G, FoldingInstance<G>>,
+) -> Vec<PolyComm<G>> {
+    instance.extended_instance.instance.commitments[..NUMBER_OF_COLUMNS]
+        .iter()
+        .map(|commitment| PolyComm::new(vec![*commitment]))
+        .collect()
+}
+
+/// Return a relaxed instance as given to the verifier.
+pub fn to_accumulated_instance<G: KimchiCurve>(
+    instance: &RelaxedInstance<G, FoldingInstance<G>>,
+) -> AccumulatedInstance<G> {
+    let extended_instance = &instance.extended_instance;
+    let to_poly_comm = |commitments: &[G]| -> Vec<PolyComm<G>> {
+        commitments
+            .iter()
+            .map(|commitment| PolyComm::new(vec![*commitment]))
+            .collect()
+    };
+    let commitments = &extended_instance.instance.commitments;
+    AccumulatedInstance {
+        witness: to_poly_comm(&commitments[..NUMBER_OF_COLUMNS]),
+        public_inputs: to_poly_comm(&commitments[NUMBER_OF_COLUMNS..]),
+        extended: extended_instance.extended.clone(),
+        error: instance.error_commitment.clone(),
+        u: instance.u,
+        alphas: extended_instance.instance.alphas.clone().powers(),
+    }
+}
+
+/// The accumulators of the IVC, one for each curve. The instances of the even
+/// iterations are accumulated over `E1`, the ones of the odd iterations over
+/// `E2`.
+pub struct Accumulators<E1: KimchiCurve, E2: KimchiCurve>
+where
+    E1::BaseField: PrimeField,
+    E2::BaseField: PrimeField,
+{
+    pub e1: Option<Accumulator<E1>>,
+    pub e2: Option<Accumulator<E2>>,
+    /// The outputs of the application after each iteration
+    pub outputs: Vec<BigInt>,
+}
+
+impl<E1: KimchiCurve, E2: KimchiCurve> Default for Accumulators<E1, E2>
+where
+    E1::BaseField: PrimeField,
+    E2::BaseField: PrimeField,
+{
+    fn default() -> Self {
+        Accumulators {
+            e1: None,
+            e2: None,
+            outputs: vec![],
+        }
+    }
+}
+
+/// Fold the instance built by the environment at the current iteration into
+/// the accumulator of the curve used by the iteration, and update the
+/// accumulator absorbed by the IVC circuit of the next iteration.
+///
+/// The environment is expected to contain the witness of the iteration
+/// `env.current_iteration`, as for [crate::prover::prove].
+pub fn accumulate<
+    Fp: PrimeField,
+    Fq: PrimeField,
+    E1: KimchiCurve<ScalarField = Fp, BaseField = Fq>,
+    E2: KimchiCurve<ScalarField = Fq, BaseField = Fp>,
+    EFqSponge1: FqSponge<Fq, E1, Fp>,
+    EFqSponge2: FqSponge<Fp, E2, Fq>,
+>(
+    env: &mut Env<Fp, Fq, E1, E2>,
+    accumulators: &mut Accumulators<E1, E2>,
+) where
+    <E1::Params as ark_ec::CurveConfig>::BaseField: PrimeField,
+    <E2::Params as ark_ec::CurveConfig>::BaseField: PrimeField,
+{
+    if env.current_iteration % 2 == 0 {
+        let columns = witness_to_columns::<Fp, _, _, _, _>(env);
+        let constraints = get_constraints::<Fp, E2>(env.current_iteration);
+        let accumulator = fold::<E1, EFqSponge1>(
+            env.domain_fp,
+            &env.srs_e1,
+            &constraints,
+            columns,
+            accumulators.e1.take(),
+        );
+        env.ivc_accumulator_e1 = accumulator.witness_commitments();
+        accumulators.e1 = Some(accumulator);
+    } else {
+        let columns = witness_to_columns::<Fq, _, _, _, _>(env);
+        let constraints = get_constraints::<Fq, E1>(env.current_iteration);
+        let accumulator = fold::<E2, EFqSponge2>(
+            env.domain_fq,
+            &env.srs_e2,
+            &constraints,
+            columns,
+            accumulators.e2.take(),
+        );
+        env.ivc_accumulator_e2 = accumulator.witness_commitments();
+        accumulators.e2 = Some(accumulator);
+    }
+    accumulators.outputs.push(env.output.clone());
+}
+
+/// Fold the instance given by its columns into the accumulator. The first
+/// instance over a curve is folded with itself, for the accumulator to always
+/// be extended with the columns added by quadraticization.
+fn fold<G, EFqSponge>(
+    domain: EvaluationDomains<G::ScalarField>,
+    srs: &SRS<G>,
+    constraints: &[E<G::ScalarField>],
+    columns: Columns<Vec<G::ScalarField>>,
+    accumulator: Option<Accumulator<G>>,
+) -> Accumulator<G>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>,
+{
+    let Columns {
+        witness,
+        public_inputs,
+        selectors,
+    } = columns;
+    let to_evals = |evals: Vec<G::ScalarField>| Evaluations::from_vec_and_domain(evals, domain.d1);
+    let structure = FoldingStructure { selectors };
+    let witness = FoldingWitness {
+        witness: witness.into_iter().map(to_evals).collect(),
+        public_inputs: public_inputs.into_iter().map(to_evals).collect(),
+    };
+
+    let constraints = constraints.iter().map(to_folding_expression).collect();
+    let (scheme, final_constraint) =
+        FoldingScheme::<IVCFoldingConfig<G>>::new(constraints, srs, domain.d1, &structure);
+
+    // The commitments are the same than the ones absorbed by the IVC circuit,
+    // i.e. without blinder.
+    let commitments: Vec<G> = witness
+        .iter()
+        .collect::<Vec<_>>()
+        .par_iter()
+        .map(|evals| {
+            srs.commit_evaluations_non_hiding(domain.d1, evals)
+                .get_first_chunk()
+        })
+        .collect();
+    let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
+    fq_sponge.absorb_g(&commitments);
+    let alpha = fq_sponge.challenge();
+    let instance = FoldingInstance {
+        commitments,
+        alphas: Alphas::new_sized(alpha, final_constraint.number_of_alphas()),
+        blinder: G::ScalarField::one(),
+    };
+
+    let step_witness = instance.commitments[..NUMBER_OF_COLUMNS]
+        .iter()
+        .map(|commitment| PolyComm::new(vec![*commitment]))
+        .collect();
+    let left = accumulator
+        .unwrap_or_else(|| Accumulator::new(instance.clone(), witness.clone(), &scheme.zero_vec));
+    let (left_extended_blinder, left_error_blinder) = (left.extended_blinder, left.error_blinder);
+    let mut steps = left.steps;
+    let FoldingOutput {
+        folded_instance,
+        folded_witness,
+        t_0,
+        t_1,
+        relaxed_extended_left_instance,
+        relaxed_extended_right_instance,
+        ..
+    } = scheme.fold_instance_witness_pair(
+        (left.instance, left.witness),
+        (instance, witness),
+        &mut fq_sponge,
+    );
+    // The folding challenge r is such that u = u_left + r * u_right
+    let r =
+        (folded_instance.u - relaxed_extended_left_instance.u) / relaxed_extended_right_instance.u;
+    steps.push(FoldingStep {
+        witness: step_witness,
+        extended: relaxed_extended_right_instance.extended_instance.extended,
+        t_0,
+        t_1,
+    });
+    // The right instance is fresh: its extended columns are committed with a
+    // blinder of one, and its error column is zero.
+    Accumulator {
+        instance: folded_instance,
+        witness: folded_witness,
+        extended_blinder: left_extended_blinder + r,
+        error_blinder: left_error_blinder - r - r.square(),
+        steps,
+    }
+}
+
+/// Fold, on the verifier side, the instance of an iteration into the
+/// accumulator, as done by the prover in [fold]. The commitments to the public
+/// inputs of the instance are computed by the verifier, the other commitments
+/// are given by the folding step. The first instance over a curve is folded
+/// with itself.
+///
+/// Return `None` if the folding step does not have the expected shape.
+pub fn verify_folding_step<G, EFqSponge>(
+    scheme: &FoldingScheme<IVCFoldingConfig<G>>,
+    n_alphas: usize,
+    public_input_commitments: &[PolyComm<G>],
+    step: &FoldingStep<G>,
+    accumulator: Option<RelaxedInstance<G, FoldingInstance<G>>>,
+) -> Option<RelaxedInstance<G, FoldingInstance<G>>>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>,
+{
+    // The commitments must not be chunked
+    let has_one_chunk = |commitments: &[PolyComm<G>]| commitments.iter().all(|c| c.len() == 1);
+    if step.witness.len() != NUMBER_OF_COLUMNS
+        || step.extended.len() != scheme.get_number_of_additional_columns()
+        || !has_one_chunk(&step.witness)
+        || !has_one_chunk(&step.extended)
+        || !has_one_chunk(public_input_commitments)
+        || !has_one_chunk(&[step.t_0.clone(), step.t_1.clone()])
+    {
+        return None;
+    }
+    let commitments: Vec<G> = step
+        .witness
+        .iter()
+        .chain(public_input_commitments)
+        .map(|commitment| commitment.get_first_chunk())
+        .collect();
+    let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
+    fq_sponge.absorb_g(&commitments);
+    let alpha = fq_sponge.challenge();
+    let instance = FoldingInstance {
+        commitments,
+        alphas: Alphas::new_sized(alpha, n_alphas),
+        blinder: G::ScalarField::one(),
+    };
+    let mut right: RelaxedInstance<G, FoldingInstance<G>> = RelaxableInstance::relax(instance);
+    right.extended_instance.extended = step.extended.clone();
+    let left = accumulator.unwrap_or_else(|| right.clone());
+    // The accumulator must have been extended by the previous folding steps
+    if left.extended_instance.extended.len() != step.extended.len() {
+        return None;
+    }
+    Some(scheme.verify_fold(
+        left,
+        right,
+        step.t_0.clone(),
+        step.t_1.clone(),
+        &mut fq_sponge,
+    ))
+}
@@ -338,8 +338,8 @@
 //! there.
 
 use crate::{
-    columns::Gadget, MAXIMUM_FIELD_SIZE_IN_BITS, NUMBER_OF_COLUMNS, POSEIDON_ROUNDS_FULL,
-    POSEIDON_STATE_SIZE,
+    columns::Gadget, MAXIMUM_FIELD_SIZE_IN_BITS, NUMBER_OF_COLUMNS,
+    NUMBER_OF_VALUES_TO_ABSORB_PUBLIC_IO, POSEIDON_ROUNDS_FULL, POSEIDON_STATE_SIZE,
 };
 use ark_ff::{One, Zero};
 use log::debug;
@@ -376,6 +376,58 @@ pub enum Instruction {
     NoOp,
 }
 
+/// Return the instruction executed after `instr` in the IVC circuit, given the
+/// number of values of the public IO already absorbed. The control-flow is
+/// described in [crate::witness::Env::fetch_next_instruction].
+///
+/// The verifier uses it to build the layout of the circuit, see
+/// [crate::setup].
+pub fn next_instruction(instr: Instruction, idx_values_to_absorb: usize) -> Instruction {
+    match instr {
+        Instruction::Poseidon(i) => {
+            if i < POSEIDON_ROUNDS_FULL - 5 {
+                Instruction::Poseidon(i + 5)
+            } else if idx_values_to_absorb < NUMBER_OF_VALUES_TO_ABSORB_PUBLIC_IO {
+                // We continue absorbing the public IO
+                Instruction::Poseidon(0)
+            } else {
+                // All the public IO has been absorbed, we can now
+                // accumulate the commitments, starting with the scaling
+                // by the folding combiner.
+                Instruction::EllipticCurveScaling(0, 0)
+            }
+        }
+        Instruction::EllipticCurveScaling(i_comm, bit) => {
+            // TODO: we still need to substract (or not?) the blinder.
+            // Maybe we can avoid this by aggregating them.
+            // TODO: we also need to aggregate the cross-terms.
+            // Therefore i_comm must also take into the account the number
+            // of cross-terms.
+            // Until the folding is checked by the circuit, the verifier
+            // replays it, see [crate::verifier::verify].
+            assert!(i_comm < NUMBER_OF_COLUMNS, "Maximum number of columns reached ({NUMBER_OF_COLUMNS}), increase the number of columns");
+            assert!(bit < MAXIMUM_FIELD_SIZE_IN_BITS, "Maximum number of bits reached ({MAXIMUM_FIELD_SIZE_IN_BITS}), increase the number of bits");
+            if bit < MAXIMUM_FIELD_SIZE_IN_BITS - 1 {
+                Instruction::EllipticCurveScaling(i_comm, bit + 1)
+            } else if i_comm < NUMBER_OF_COLUMNS - 1 {
+                Instruction::EllipticCurveScaling(i_comm + 1, 0)
+            } else {
+                // We have computed all the bits for all the columns, we
+                // can add the results to the accumulators
+                Instruction::EllipticCurveAddition(0)
+            }
+        }
+        Instruction::EllipticCurveAddition(i_comm) => {
+            if i_comm < NUMBER_OF_COLUMNS - 1 {
+                Instruction::EllipticCurveAddition(i_comm + 1)
+            } else {
+                Instruction::NoOp
+            }
+        }
+        Instruction::NoOp => Instruction::NoOp,
+    }
+}
+
 /// Define the side of the temporary accumulator.
 /// When computing G1 + G2, the interpreter will load G1 and after that G2.
 /// This enum is used to decide which side fetching into the cells.
@@ -463,10 +515,18 @@ pub trait InterpreterEnv {
         position: Self::Position,
     ) -> Self::Variable;
 
-    /// Fetch an input of the application
+    /// Fetch the input of the application on the row of the given position,
+    /// i.e. the input of the iteration on the first row of the application,
+    /// and the output of the previous row otherwise. See [run_app].
     // Witness-only
     fn fetch_input(&mut self, res: Self::Position) -> Self::Variable;
 
+    /// Fetch a public input of the application, binding its rows to the input
+    /// and the output of the iteration. The public inputs are allocated in the
+    /// order given by [crate::setup::AppPublicInput].
+    // Witness-only
+    fn fetch_app_public_input(&mut self, pos: Self::Position) -> Self::Variable;
+
     /// Reset the environment to build the next row
     fn reset(&mut self);
 
@@ -605,16 +665,40 @@ pub trait InterpreterEnv {
     );
 }
 
-/// Run the application
+/// Run a row of the application.
+///
+/// The application squares its input on each of its rows: the first row takes
+/// the input of the iteration, each other row takes the output of the previous
+/// one, and the output of the last row is the output of the iteration. The
+/// public inputs of the rows, computed by the verifier (see
+/// [crate::setup::public_inputs]), give the input on the first row and the
+/// output on the last row. They are the values absorbed by the IVC circuit of
+/// the iteration and of the next one.
 pub fn run_app<E: InterpreterEnv>(env: &mut E) {
-    let x1 = {
+    env.activate_gadget(Gadget::App);
+    let [input, is_first_row, output, is_last_row]: [E::Variable; 4] = std::array::from_fn(|_| {
+        let pos = env.allocate_public_input();
+        env.fetch_app_public_input(pos)
+    });
+    let x = {
         let pos = env.allocate();
         env.fetch_input(pos)
     };
-    let _x1_square = {
+    let x_square = {
         let res = env.allocate();
-        env.square(res, x1.clone())
+        env.square(res, x.clone())
     };
+    let next_x = {
+        let pos = env.allocate_next_row();
+        env.fetch_input(pos)
+    };
+    // The first row starts from the input of the iteration
+    env.assert_zero(is_first_row * (x - input));
+    // Each row, except the last one, gives its output to the next one
+    let one = env.one();
+    env.assert_zero((one - is_last_row.clone()) * (next_x - x_square.clone()));
+    // The last row gives the output of the iteration
+    env.assert_zero(is_last_row * (x_square - output));
 }
 
 /// Run an iteration of the IVC scheme
@@ -783,6 +867,13 @@ pub fn run_ivc<E: InterpreterEnv>(env: &mut E, instr: Instruction) {
                     + (env.one() - bit.clone()) * res_y.clone();
                 env.write_column(next_row_res_col_y, res)
             };
+            // When processing the last bit, the result has been written in the
+            // "next row". As the next gadget will load its values in the
+            // current row, we move to a fresh row to avoid overwriting the
+            // values the constraints of this row refer to.
+            if processing_bit == MAXIMUM_FIELD_SIZE_IN_BITS - 1 {
+                env.reset();
+            }
         }
         Instruction::EllipticCurveAddition(i_comm) => {
             env.activate_gadget(Gadget::EllipticCurveAddition);
@@ -3,12 +3,14 @@ use strum::EnumCount as _;
 pub mod column_env;
 pub mod columns;
 pub mod constraints;
+pub mod folding;
 pub mod interpreter;
 pub mod logup;
 pub mod poseidon_3_60_0_5_5_fp;
 pub mod poseidon_3_60_0_5_5_fq;
 pub mod proof;
 pub mod prover;
+pub mod setup;
 pub mod verifier;
 pub mod witness;
 
@@ -20,6 +22,10 @@ pub const MAX_DEGREE: u64 = 5;
 /// Requiring at least 2^16 to perform 16bits range checks.
 pub const MIN_SRS_LOG2_SIZE: usize = 16;
 
+/// The maximum SRS size supported by the binary, in base 2.
+/// It bounds the size of the SRS a verifier is asked to build from a proof.
+pub const MAX_SRS_LOG2_SIZE: usize = 20;
+
 /// The number of rows the IVC circuit requires.
 // FIXME: that might change. We use a vertical layout for now.
 pub const IVC_CIRCUIT_SIZE: usize = 1 << 13;
@@ -54,14 +60,12 @@ pub const MAXIMUM_FIELD_SIZE_IN_BITS: u64 = 255;
 /// Define the number of values we must absorb when computating the hash to the
 /// public IO.
 ///
-/// FIXME:
-/// For now, it is the number of columns as we are only absorbing the
-/// accumulators, which consists of 2 native field elements. However, it doesn't
-/// make the protocol sound. We must absorb, in addition to that the index,
-/// the application inputs/outputs.
-/// It is left for the future as at this time, we're still sketching the IVC
-/// circuit.
-pub const NUMBER_OF_VALUES_TO_ABSORB_PUBLIC_IO: usize = NUMBER_OF_COLUMNS * 2;
+/// It is the two coordinates of the accumulators of each column, followed by
+/// the initial input `z0`, the current output `zi` and the index of the
+/// iteration, see [crate::setup::values_to_absorb]. As two values are absorbed
+/// per permutation, the last permutation absorbs a zero in addition to the
+/// index of the iteration.
+pub const NUMBER_OF_VALUES_TO_ABSORB_PUBLIC_IO: usize = NUMBER_OF_COLUMNS * 2 + 3;
 
 /// The number of selectors used in the circuit.
 pub const NUMBER_OF_SELECTORS: usize = columns::Gadget::COUNT;
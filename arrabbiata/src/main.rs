@@ -1,14 +1,28 @@
 use arrabbiata::{
+    folding::{self, Accumulators},
     interpreter::{self, InterpreterEnv},
+    proof::Proof,
+    prover, verifier,
     witness::Env,
-    IVC_CIRCUIT_SIZE, MIN_SRS_LOG2_SIZE, POSEIDON_STATE_SIZE,
+    IVC_CIRCUIT_SIZE, MAX_SRS_LOG2_SIZE, MIN_SRS_LOG2_SIZE, POSEIDON_STATE_SIZE,
+};
+use kimchi::circuits::domains::EvaluationDomains;
+use log::{debug, error, info};
+use mina_curves::pasta::{Fp, Fq, Pallas, PallasParameters, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
 };
-use log::{debug, info};
-use mina_curves::pasta::{Fp, Fq, Pallas, Vesta};
 use num_bigint::BigInt;
-use std::time::Instant;
+use poly_commitment::{ipa::SRS, SRS as _};
+use std::{fs::File, io::BufWriter, process::ExitCode, time::Instant};
+
+type VestaBaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type VestaScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+type PallasBaseSponge = DefaultFqSponge<PallasParameters, PlonkSpongeConstantsKimchi>;
+type PallasScalarSponge = DefaultFrSponge<Fq, PlonkSpongeConstantsKimchi>;
 
-pub fn main() {
+pub fn main() -> ExitCode {
     // See https://github.com/rust-lang/log
     env_logger::init();
 
@@ -18,6 +32,12 @@ pub fn main() {
     let arg_srs_size = clap::arg!(--"srs-size" <U64> "Size of the SRS in base 2")
         .value_parser(clap::value_parser!(usize));
 
+    let arg_output = clap::arg!(--"output" <FILE> "File to write the proof to")
+        .value_parser(clap::value_parser!(String));
+
+    let arg_proof = clap::arg!(--"proof" <FILE> "File containing the proof to verify")
+        .value_parser(clap::value_parser!(String));
+
     let cmd = clap::Command::new("cargo")
         .bin_name("cargo")
         .subcommand_required(true)
@@ -25,13 +45,100 @@ pub fn main() {
             clap::Command::new("square-root")
                 .arg(arg_n)
                 .arg(arg_srs_size)
+                .arg(arg_output)
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            clap::Command::new("verify")
+                .arg(arg_proof)
                 .arg_required_else_help(true),
         );
     let matches = cmd.get_matches();
-    let matches = match matches.subcommand() {
-        Some(("square-root", matches)) => matches,
+    match matches.subcommand() {
+        Some(("square-root", matches)) => square_root(matches),
+        Some(("verify", matches)) => verify(matches),
         _ => unreachable!("clap should ensure we don't get here"),
+    }
+}
+
+/// The number of rows used by the application, in a circuit of
+/// `2^srs_log2_size` rows. The remaining rows are used by the IVC circuit.
+fn number_of_app_rows(srs_log2_size: usize) -> usize {
+    (1 << srs_log2_size) - IVC_CIRCUIT_SIZE
+}
+
+/// Verify a proof previously generated by the subcommand `square-root`.
+fn verify(matches: &clap::ArgMatches) -> ExitCode {
+    let proof_file = matches.get_one::<String>("proof").unwrap();
+    let file = match File::open(proof_file) {
+        Ok(file) => file,
+        Err(err) => {
+            error!("Could not open {proof_file}: {err}");
+            return ExitCode::FAILURE;
+        }
     };
+    let proof: Proof<Vesta, Pallas> = match rmp_serde::from_read(file) {
+        Ok(proof) => proof,
+        Err(err) => {
+            error!("Error deserializing the proof in {proof_file}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // The size of the SRS comes from an untrusted file, we must bound it
+    // before building anything from it.
+    if !(MIN_SRS_LOG2_SIZE..=MAX_SRS_LOG2_SIZE).contains(&proof.srs_log2_size) {
+        error!(
+            "The proof uses an SRS of size 2^{srs_log2_size}, but it must be between 2^{MIN_SRS_LOG2_SIZE} and 2^{MAX_SRS_LOG2_SIZE}",
+            srs_log2_size = proof.srs_log2_size
+        );
+        return ExitCode::FAILURE;
+    }
+    let srs_size = 1 << proof.srs_log2_size;
+    info!(
+        "Verifying a proof of {n} iterations with SRS of size 2^{srs_log2_size}",
+        n = proof.iteration + 1,
+        srs_log2_size = proof.srs_log2_size
+    );
+    let domain_fp = EvaluationDomains::<Fp>::create(srs_size).unwrap();
+    let domain_fq = EvaluationDomains::<Fq>::create(srs_size).unwrap();
+    let srs_e1: SRS<Vesta> = SRS::create(srs_size);
+    let srs_e2: SRS<Pallas> = SRS::create(srs_size);
+
+    let start = Instant::now();
+    let res = verifier::verify::<
+        Fp,
+        Fq,
+        Vesta,
+        Pallas,
+        VestaBaseSponge,
+        VestaScalarSponge,
+        PallasBaseSponge,
+        PallasScalarSponge,
+    >(
+        domain_fp,
+        domain_fq,
+        &srs_e1,
+        &srs_e2,
+        number_of_app_rows(proof.srs_log2_size),
+        &proof,
+    );
+    debug!(
+        "Proof verified in {elapsed} μs",
+        elapsed = start.elapsed().as_micros()
+    );
+    if res {
+        info!("The proof is valid");
+        ExitCode::SUCCESS
+    } else {
+        error!("The proof is invalid");
+        ExitCode::FAILURE
+    }
+}
+
+fn square_root(matches: &clap::ArgMatches) -> ExitCode {
+    let mut rng = rand::thread_rng();
+
     let n_iteration = matches.get_one::<u64>("n").unwrap();
     let srs_log2_size = matches
         .get_one::<usize>("srs-size")
@@ -41,6 +148,10 @@ pub fn main() {
         *srs_log2_size >= MIN_SRS_LOG2_SIZE,
         "SRS size must be at least 2^{MIN_SRS_LOG2_SIZE} to support IVC"
     );
+    assert!(
+        *srs_log2_size <= MAX_SRS_LOG2_SIZE,
+        "SRS size must be at most 2^{MAX_SRS_LOG2_SIZE}"
+    );
 
     info!("Instantiating environment to execute square-root {n_iteration} times with SRS of size 2^{srs_log2_size}");
 
@@ -56,7 +167,8 @@ pub fn main() {
         sponge_e1.clone(),
     );
 
-    let n_iteration_per_fold = domain_size - IVC_CIRCUIT_SIZE;
+    let n_iteration_per_fold = number_of_app_rows(*srs_log2_size);
+    let mut accumulators = Accumulators::default();
 
     while env.current_iteration < *n_iteration {
         let start_iteration = Instant::now();
@@ -69,10 +181,12 @@ pub fn main() {
             interpreter::run_app(&mut env);
             env.reset();
         }
+        env.compute_output();
 
         info!("Building the IVC circuit");
-        // Build the IVC circuit
-        for _i in 0..IVC_CIRCUIT_SIZE {
+        // Build the IVC circuit. Some gadgets use more than one row, therefore
+        // we fill the rows until the end of the domain.
+        while env.current_row < domain_size {
             let instr = env.fetch_instruction();
             interpreter::run_ivc(&mut env, instr);
             env.current_instruction = env.fetch_next_instruction();
@@ -85,48 +199,13 @@ pub fn main() {
             elapsed = start_iteration.elapsed().as_micros()
         );
 
-        // FIXME:
-        // update current instance with the previous "next" commitments (i.e.
-        // env.next_commitments)
-        // update next instance with current commitments
-        // FIXME: Check twice the updated commitments
         env.compute_and_update_previous_commitments();
 
-        // FIXME:
-        // Absorb all commitments in the sponge.
-
-        // FIXME:
-        // Coin chalenges β and γ for the permutation argument
-
-        // FIXME:
-        // Compute the accumulator for the permutation argument
-
-        // FIXME:
-        // Compute the cross-terms
-
-        // FIXME:
-        // Absorb the cross-terms
-
-        // FIXME:
-        // Coin challenge r to fold
-
-        // FIXME:
-        // Compute the accumulated witness
-
-        // FIXME:
-        // Compute the accumulation of the commitments to the witness columns
-
-        // FIXME:
-        // Compute the accumulation of the challenges
-
-        // FIXME:
-        // Compute the accumulation of the public inputs/selectors
-
-        // FIXME:
-        // Compute the accumulation of the blinders for the PCS
-
-        // FIXME:
-        // Compute the accumulated error
+        info!("Accumulating the instance");
+        folding::accumulate::<_, _, _, _, VestaBaseSponge, PallasBaseSponge>(
+            &mut env,
+            &mut accumulators,
+        );
 
         debug!(
             "Iteration {i} fully proven in {elapsed} μs",
@@ -134,7 +213,36 @@ pub fn main() {
             elapsed = start_iteration.elapsed().as_micros()
         );
 
+        if env.current_iteration == *n_iteration - 1 {
+            info!("Proving the last iteration");
+            let start_proof = Instant::now();
+            let proof = prover::prove::<
+                Fp,
+                Fq,
+                Vesta,
+                Pallas,
+                VestaBaseSponge,
+                VestaScalarSponge,
+                PallasBaseSponge,
+                PallasScalarSponge,
+                _,
+            >(&env, &accumulators, &mut rng)
+            .unwrap_or_else(|err| panic!("Could not make a proof: {err}"));
+            debug!(
+                "Proof generated in {elapsed} μs",
+                elapsed = start_proof.elapsed().as_micros()
+            );
+            if let Some(output) = matches.get_one::<String>("output") {
+                let file =
+                    File::create(output).unwrap_or_else(|_| panic!("Could not create {output}"));
+                rmp_serde::encode::write(&mut BufWriter::new(file), &proof)
+                    .unwrap_or_else(|_| panic!("Could not write the proof in {output}"));
+                info!("Proof written in {output}");
+            }
+        }
+
         env.reset_for_next_iteration();
         env.current_iteration += 1;
     }
+    ExitCode::SUCCESS
 }
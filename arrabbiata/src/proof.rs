@@ -1,4 +1,208 @@
-/// FIXME: a proof for the Nova recursive SNARK
-// FIXME: type over curves
-// FIXME: add a (de-)serializer to publish it somewhere
-pub struct Proof {}
+//! A proof for the Nova recursive SNARK.
+//!
+//! A proof consists of the state of the IVC after the last iteration (the
+//! accumulated instances for both curves, the inputs/outputs of the
+//! application), the folding steps leading to the accumulated instances, a
+//! SNARK, over the curve used by the last iteration, proving that the witness
+//! of the last iteration satisfies the constraints of the circuit, and a SNARK
+//! for each curve proving that the accumulated instance is satisfied, i.e. the
+//! decider.
+//!
+//! The proof can be serialized using [serde] to be published and verified by
+//! another process.
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use kimchi::{curve::KimchiCurve, proof::PointEvaluations};
+use num_bigint::BigInt;
+use poly_commitment::{ipa::OpeningProof, PolyComm};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+/// All the columns of an instance of the circuit: the private witness, the
+/// public inputs and the selectors of the gadgets.
+///
+/// The columns are ordered following the index used in
+/// [crate::columns::Column].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Columns<T> {
+    /// Private columns, see [crate::columns::Column::X]
+    pub witness: Vec<T>,
+    /// Public inputs, see [crate::columns::Column::PublicInput]
+    pub public_inputs: Vec<T>,
+    /// Selectors of the gadgets, see [crate::columns::Column::Selector]
+    pub selectors: Vec<T>,
+}
+
+impl<T> Columns<T> {
+    /// Iterate over all the columns, in the order the prover absorbs them.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.witness
+            .iter()
+            .chain(self.public_inputs.iter())
+            .chain(self.selectors.iter())
+    }
+
+    /// Apply `f` on each column
+    pub fn map<U, F: Fn(&T) -> U>(&self, f: F) -> Columns<U> {
+        Columns {
+            witness: self.witness.iter().map(&f).collect(),
+            public_inputs: self.public_inputs.iter().map(&f).collect(),
+            selectors: self.selectors.iter().map(&f).collect(),
+        }
+    }
+}
+
+/// A proof that the witness of an instance of the circuit, over the curve `G`,
+/// satisfies the constraints of the circuit.
+///
+/// Only the commitments to the witness are given by the prover. The selectors
+/// and the public inputs are fixed by the circuit and the state of the IVC, and
+/// the verifier commits to them itself, see [crate::setup].
+// FIXME: should we blind the commitments?
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "G: CanonicalDeserialize + CanonicalSerialize")]
+pub struct InstanceProof<G: KimchiCurve> {
+    /// Commitments to the witness columns of the instance
+    pub witness_commitments: Vec<PolyComm<G>>,
+    /// Evaluations of the columns at ζ and ζω
+    pub evaluations: Columns<PointEvaluations<Vec<G::ScalarField>>>,
+    /// Commitment to the quotient polynomial, split in chunks
+    pub quotient_commitment: PolyComm<G>,
+    /// Evaluations of the chunks of the quotient polynomial at ζ and ζω
+    pub quotient_evaluations: PointEvaluations<Vec<G::ScalarField>>,
+    /// IPA opening proof
+    pub opening_proof: OpeningProof<G>,
+}
+
+/// A relaxed instance of the circuit over the curve `G`, in which the
+/// instances of the iterations over `G` have been accumulated, see
+/// [crate::folding].
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "G: CanonicalDeserialize + CanonicalSerialize")]
+pub struct AccumulatedInstance<G: KimchiCurve> {
+    /// Accumulated commitments to the witness columns. They are absorbed by
+    /// the IVC circuit of the next iteration over the other curve.
+    pub witness: Vec<PolyComm<G>>,
+    /// Accumulated commitments to the public inputs
+    pub public_inputs: Vec<PolyComm<G>>,
+    /// Accumulated commitments to the columns added by quadraticization
+    pub extended: Vec<PolyComm<G>>,
+    /// Commitment to the error column
+    pub error: PolyComm<G>,
+    /// The scalar used to homogenize the constraints
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub u: G::ScalarField,
+    /// The accumulated challenges combining the constraints
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub alphas: Vec<G::ScalarField>,
+}
+
+/// The evaluations at ζ and ζω of the polynomials opened by the decider.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "PointEvaluations<Vec<F>>: Serialize + for<'a> Deserialize<'a>")]
+pub struct DeciderEvaluations<F> {
+    /// The columns of the accumulated witness, and the selectors
+    pub columns: Columns<PointEvaluations<Vec<F>>>,
+    /// The columns added by quadraticization
+    pub extended: Vec<PointEvaluations<Vec<F>>>,
+    /// The error column
+    pub error: PointEvaluations<Vec<F>>,
+    /// The chunks of the quotient polynomial
+    pub quotient: PointEvaluations<Vec<F>>,
+}
+
+impl<F> DeciderEvaluations<F> {
+    /// Iterate over all the evaluations, in the order the prover absorbs them.
+    pub fn iter(&self) -> impl Iterator<Item = &PointEvaluations<Vec<F>>> {
+        self.columns
+            .iter()
+            .chain(self.extended.iter())
+            .chain([&self.error, &self.quotient])
+    }
+}
+
+/// A proof that an accumulated instance over the curve `G` is satisfied, i.e.
+/// that the final constraint of the folding scheme, evaluated on the
+/// accumulated witness, vanishes on the domain.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "G: CanonicalDeserialize + CanonicalSerialize")]
+pub struct DeciderProof<G: KimchiCurve> {
+    /// Commitment to the quotient polynomial, split in chunks
+    pub quotient_commitment: PolyComm<G>,
+    /// Evaluations of the polynomials at ζ and ζω
+    pub evaluations: DeciderEvaluations<G::ScalarField>,
+    /// IPA opening proof
+    pub opening_proof: OpeningProof<G>,
+}
+
+/// The folding of the instance of an iteration into the accumulator over the
+/// curve `G`. The first instance over a curve is folded with itself.
+///
+/// The circuit does not check the folding yet, therefore the verifier replays
+/// all the folding steps, see [crate::verifier::verify].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "G: CanonicalDeserialize + CanonicalSerialize")]
+pub struct FoldingStep<G: KimchiCurve> {
+    /// Commitments to the witness columns of the instance, without blinder.
+    /// The commitments to the public inputs are computed by the verifier.
+    pub witness: Vec<PolyComm<G>>,
+    /// Commitments to the columns of the instance added by quadraticization
+    pub extended: Vec<PolyComm<G>>,
+    /// Commitment to the cross terms of degree 1
+    pub t_0: PolyComm<G>,
+    /// Commitment to the cross terms of degree 2
+    pub t_1: PolyComm<G>,
+}
+
+/// The accumulated instance over the curve `G` and the proof that it is
+/// satisfied.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "G: CanonicalDeserialize + CanonicalSerialize")]
+pub struct AccumulatorProof<G: KimchiCurve> {
+    pub instance: AccumulatedInstance<G>,
+    pub decider: DeciderProof<G>,
+}
+
+/// The proof of the instance computed at the last iteration. The curve depends
+/// on the parity of the iteration.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "E1: CanonicalDeserialize + CanonicalSerialize, \
+                 E2: CanonicalDeserialize + CanonicalSerialize")]
+pub enum LastInstanceProof<E1: KimchiCurve, E2: KimchiCurve> {
+    /// The last iteration is even, and the witness is over the scalar field of
+    /// `E1`.
+    E1(InstanceProof<E1>),
+    /// The last iteration is odd, and the witness is over the scalar field of
+    /// `E2`.
+    E2(InstanceProof<E2>),
+}
+
+/// A proof for the Nova recursive SNARK
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "E1: CanonicalDeserialize + CanonicalSerialize, \
+                 E2: CanonicalDeserialize + CanonicalSerialize")]
+pub struct Proof<E1: KimchiCurve, E2: KimchiCurve> {
+    /// The size of the SRS, in base 2, used to build the circuit.
+    pub srs_log2_size: usize,
+    /// The index of the last iteration of the IVC
+    pub iteration: u64,
+    /// The initial input of the application
+    pub z0: BigInt,
+    /// The output of the application after the last iteration
+    pub zi: BigInt,
+    /// The outputs of the application after each iteration but the last one,
+    /// i.e. the inputs of the iterations after the first one
+    pub intermediate_outputs: Vec<BigInt>,
+    /// The folding steps of the even iterations, over `E1`
+    pub folding_e1: Vec<FoldingStep<E1>>,
+    /// The folding steps of the odd iterations, over `E2`
+    pub folding_e2: Vec<FoldingStep<E2>>,
+    /// The instances of the even iterations, accumulated over `E1`
+    pub accumulator_e1: AccumulatorProof<E1>,
+    /// The instances of the odd iterations, accumulated over `E2`. There is
+    /// none when only the first iteration has been run.
+    pub accumulator_e2: Option<AccumulatorProof<E2>>,
+    /// The proof of the instance computed at the last iteration
+    pub last_instance: LastInstanceProof<E1, E2>,
+}
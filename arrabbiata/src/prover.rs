@@ -1,21 +1,726 @@
 //! A prover for the folding/accumulation scheme
 
-use crate::proof::Proof;
-use ark_ec::AffineRepr;
-use ark_ff::PrimeField;
+use crate::{
+    column_env::ColumnEnvironment,
+    columns::{Challenges, Column, E},
+    constraints,
+    folding::{self, Accumulator, Accumulators},
+    poseidon_3_60_0_5_5_fp, poseidon_3_60_0_5_5_fq,
+    proof::{
+        AccumulatedInstance, AccumulatorProof, Columns, DeciderEvaluations, DeciderProof,
+        InstanceProof, LastInstanceProof, Proof,
+    },
+    MAX_DEGREE,
+};
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ff::{One, PrimeField, Zero};
+use ark_poly::{univariate::DensePolynomial, Evaluations, Polynomial, Radix2EvaluationDomain as D};
+use kimchi::{
+    circuits::{
+        domains::EvaluationDomains,
+        expr::{l0_1, Constants, Variable},
+        gate::CurrOrNext,
+    },
+    curve::KimchiCurve,
+    groupmap::GroupMap,
+    plonk_sponge::FrSponge,
+    proof::PointEvaluations,
+};
+use log::debug;
+use mina_poseidon::{sponge::ScalarChallenge, FqSponge};
+use num_bigint::BigInt;
+use o1_utils::{field_helpers::FieldHelpers, ExtendedDensePolynomial};
+use poly_commitment::{
+    commitment::{absorb_commitment, CommitmentCurve, PolyComm},
+    ipa::{OpeningProof, SRS},
+    utils::DensePolynomialOrEvaluations,
+    OpenProof as _, SRS as _,
+};
+use rand::{CryptoRng, RngCore};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
 use crate::witness::Env;
 
+/// The number of chunks of the quotient polynomial.
+/// The constraints are of degree at most [MAX_DEGREE], and are multiplied by
+/// the selector of their gadget. Therefore, the quotient polynomial has a degree
+/// strictly less than `MAX_DEGREE * N`, `N` being the size of the domain.
+pub const QUOTIENT_POLYNOMIAL_CHUNKS: usize = MAX_DEGREE as usize;
+
+/// The number of chunks of the quotient polynomial of the decider.
+/// The final constraint of the folding scheme is quadratic in the accumulated
+/// columns, and multiplied by the selector of the gadgets. Therefore, the
+/// quotient polynomial has a degree strictly less than `2 * N`, `N` being the
+/// size of the domain.
+pub const DECIDER_QUOTIENT_POLYNOMIAL_CHUNKS: usize = 2;
+
+/// Return the constraints, including the selectors, verified by the witness
+/// built at an iteration of the given parity.
+///
+/// The witness of the even iterations is over the scalar field of the first
+/// curve, and the elliptic curve operations are performed over the second curve
+/// `C` whose base field is `F`. It is the opposite for the odd iterations.
+pub fn get_constraints<F: PrimeField, C: CommitmentCurve<BaseField = F>>(
+    iteration: u64,
+) -> Vec<E<F>>
+where
+    <C::Params as ark_ec::CurveConfig>::BaseField: PrimeField,
+{
+    let mds: Vec<Vec<F>> = if iteration % 2 == 0 {
+        poseidon_3_60_0_5_5_fp::static_params()
+            .mds
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|x| F::from_biguint(&x.to_biguint()).unwrap())
+                    .collect()
+            })
+            .collect()
+    } else {
+        poseidon_3_60_0_5_5_fq::static_params()
+            .mds
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|x| F::from_biguint(&x.to_biguint()).unwrap())
+                    .collect()
+            })
+            .collect()
+    };
+    let a: BigInt = C::Params::COEFF_A.to_biguint().into();
+    constraints::Env::<F>::new(mds, a).get_all_constraints_with_selectors()
+}
+
 /// Generate a proof for the IVC circuit.
 /// All the information to make a proof is available in the environment given in
-/// parameter.
+/// parameter, and in the accumulators.
+///
+/// The environment is expected to contain the witness of the iteration
+/// `env.current_iteration`, i.e. the function must be called after the witness
+/// has been built and accumulated, see [crate::folding::accumulate], and before
+/// the environment is reset for the next iteration.
+///
+/// The proof contains the current state of the IVC (the accumulated instances
+/// and the inputs/outputs), the folding steps leading to the accumulated
+/// instances, a proof that the witness of the current iteration
+/// satisfies the constraints of the circuit, and a proof that each accumulated
+/// instance is satisfied, i.e. the decider.
 pub fn prove<
     Fp: PrimeField,
     Fq: PrimeField,
-    E1: AffineRepr<ScalarField = Fp, BaseField = Fq>,
-    E2: AffineRepr<ScalarField = Fq, BaseField = Fp>,
+    E1: KimchiCurve<ScalarField = Fp, BaseField = Fq>,
+    E2: KimchiCurve<ScalarField = Fq, BaseField = Fp>,
+    EFqSponge1: FqSponge<Fq, E1, Fp> + Clone,
+    EFrSponge1: FrSponge<Fp>,
+    EFqSponge2: FqSponge<Fp, E2, Fq> + Clone,
+    EFrSponge2: FrSponge<Fq>,
+    RNG: RngCore + CryptoRng,
+>(
+    env: &Env<Fp, Fq, E1, E2>,
+    accumulators: &Accumulators<E1, E2>,
+    rng: &mut RNG,
+) -> Result<Proof<E1, E2>, String>
+where
+    <E1::Params as ark_ec::CurveConfig>::BaseField: PrimeField,
+    <E2::Params as ark_ec::CurveConfig>::BaseField: PrimeField,
+{
+    let last_instance = if env.current_iteration % 2 == 0 {
+        debug!("Prover: proving the instance over the first curve");
+        let columns = witness_to_columns::<Fp, _, _, _, _>(env);
+        let constraints = get_constraints::<Fp, E2>(env.current_iteration);
+        let proof = prove_instance::<E1, EFqSponge1, EFrSponge1, RNG>(
+            env.domain_fp,
+            &env.srs_e1,
+            columns,
+            &constraints,
+            rng,
+        )?;
+        LastInstanceProof::E1(proof)
+    } else {
+        debug!("Prover: proving the instance over the second curve");
+        let columns = witness_to_columns::<Fq, _, _, _, _>(env);
+        let constraints = get_constraints::<Fq, E1>(env.current_iteration);
+        let proof = prove_instance::<E2, EFqSponge2, EFrSponge2, RNG>(
+            env.domain_fq,
+            &env.srs_e2,
+            columns,
+            &constraints,
+            rng,
+        )?;
+        LastInstanceProof::E2(proof)
+    };
+
+    debug!("Prover: proving the instance accumulated over the first curve");
+    let accumulator_e1 = accumulators
+        .e1
+        .as_ref()
+        .ok_or("No instance has been accumulated over the first curve")?;
+    let accumulator_e1 = prove_decider::<E1, EFqSponge1, EFrSponge1, RNG>(
+        env.domain_fp,
+        &env.srs_e1,
+        &get_constraints::<Fp, E2>(0),
+        selectors_to_columns(&env.selectors),
+        accumulator_e1,
+        rng,
+    )?;
+
+    let accumulator_e2 = accumulators
+        .e2
+        .as_ref()
+        .map(|accumulator_e2| {
+            debug!("Prover: proving the instance accumulated over the second curve");
+            prove_decider::<E2, EFqSponge2, EFrSponge2, RNG>(
+                env.domain_fq,
+                &env.srs_e2,
+                &get_constraints::<Fq, E1>(1),
+                selectors_to_columns(&env.selectors),
+                accumulator_e2,
+                rng,
+            )
+        })
+        .transpose()?;
+
+    // The last output is the one of the current iteration
+    let Some((zi, intermediate_outputs)) = accumulators.outputs.split_last() else {
+        return Err("No iteration has been accumulated".to_string());
+    };
+    if intermediate_outputs.len() as u64 != env.current_iteration {
+        return Err("The current iteration has not been accumulated".to_string());
+    }
+
+    Ok(Proof {
+        srs_log2_size: env.domain_fp.d1.log_size_of_group as usize,
+        iteration: env.current_iteration,
+        z0: env.z0.clone(),
+        zi: zi.clone(),
+        intermediate_outputs: intermediate_outputs.to_vec(),
+        folding_e1: accumulators
+            .e1
+            .as_ref()
+            .map_or(vec![], |accumulator| accumulator.steps.clone()),
+        folding_e2: accumulators
+            .e2
+            .as_ref()
+            .map_or(vec![], |accumulator| accumulator.steps.clone()),
+        accumulator_e1,
+        accumulator_e2,
+        last_instance,
+    })
+}
+
+/// Convert the selectors of the gadgets into field elements.
+pub(crate) fn selectors_to_columns<F: PrimeField>(selectors: &[Vec<bool>]) -> Vec<Vec<F>> {
+    selectors
+        .iter()
+        .map(|selector| {
+            selector
+                .iter()
+                .map(|s| if *s { F::one() } else { F::zero() })
+                .collect()
+        })
+        .collect()
+}
+
+/// Convert the witness, the public inputs and the selectors saved in the
+/// environment into field elements.
+pub(crate) fn witness_to_columns<
+    F: PrimeField,
+    Fp: PrimeField,
+    Fq: PrimeField,
+    E1: CommitmentCurve<ScalarField = Fp, BaseField = Fq>,
+    E2: CommitmentCurve<ScalarField = Fq, BaseField = Fp>,
+>(
+    env: &Env<Fp, Fq, E1, E2>,
+) -> Columns<Vec<F>> {
+    let to_field = |column: &Vec<BigInt>| -> Vec<F> {
+        column
+            .par_iter()
+            .map(|x| F::from_biguint(&x.to_biguint().unwrap()).unwrap())
+            .collect()
+    };
+    Columns {
+        witness: env.witness.iter().map(to_field).collect(),
+        public_inputs: env.public_inputs.iter().map(to_field).collect(),
+        selectors: selectors_to_columns(&env.selectors),
+    }
+}
+
+/// Make a PlonKish proof for the given instance. As inputs, we get the columns
+/// of the instance, consisting of evaluations of polynomials over the domain
+/// `domain`.
+///
+/// The proof is made of the following steps:
+/// 1. For each column, we create a commitment and absorb it in the sponge.
+/// 2. We compute the quotient polynomial.
+/// 3. We evaluate each polynomial (columns + quotient) to two challenges ζ and ζω.
+/// 4. We make a batch opening proof using the IPA PCS.
+///
+/// The final proof consists of the opening proof, the commitments and the
+/// evaluations at ζ and ζω.
+pub fn prove_instance<
+    G: KimchiCurve,
+    EFqSponge: FqSponge<G::BaseField, G, G::ScalarField> + Clone,
+    EFrSponge: FrSponge<G::ScalarField>,
+    RNG: RngCore + CryptoRng,
+>(
+    domain: EvaluationDomains<G::ScalarField>,
+    srs: &SRS<G>,
+    columns: Columns<Vec<G::ScalarField>>,
+    constraints: &[E<G::ScalarField>],
+    rng: &mut RNG,
+) -> Result<InstanceProof<G>, String>
+where
+    G::BaseField: PrimeField,
+{
+    let omega = domain.d1.group_gen;
+
+    let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Round 1: Creating and absorbing column commitments
+    ////////////////////////////////////////////////////////////////////////////
+
+    debug!("Prover: interpolating all columns, including the selectors");
+    let evaluations: Columns<Evaluations<G::ScalarField, D<G::ScalarField>>> =
+        columns.map(|evals| Evaluations::from_vec_and_domain(evals.clone(), domain.d1));
+    let polys: Columns<DensePolynomial<G::ScalarField>> =
+        evaluations.map(|evals| evals.interpolate_by_ref());
+
+    debug!("Prover: committing to all columns, including the selectors");
+    // The commitments are the same than the ones computed by the environment
+    // while accumulating, i.e. without blinder.
+    let commitments: Columns<PolyComm<G>> =
+        evaluations.map(|evals| srs.commit_evaluations_non_hiding(domain.d1, evals));
+
+    // Absorbing the commitments - Fiat Shamir
+    // We do not parallelize as we need something deterministic.
+    commitments
+        .iter()
+        .for_each(|comm| absorb_commitment(&mut fq_sponge, comm));
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Round 2: Creating and committing to the quotient polynomial
+    ////////////////////////////////////////////////////////////////////////////
+
+    debug!("Prover: evaluating all columns, including the selectors, on d8");
+    // The constraints are of degree at most MAX_DEGREE + 1 = 6 when including
+    // the selectors. Therefore, we do evaluate on d8.
+    let evaluations_d8: Columns<Evaluations<G::ScalarField, D<G::ScalarField>>> =
+        polys.map(|poly| poly.evaluate_over_domain_by_ref(domain.d8));
+
+    let (_, endo_r) = G::endos();
+
+    // Constraints combiner
+    let alpha: G::ScalarField = fq_sponge.challenge();
+
+    let column_env: ColumnEnvironment<'_, G::ScalarField> = ColumnEnvironment {
+        constants: Constants {
+            endo_coefficient: *endo_r,
+            mds: &G::sponge_params().mds,
+            zk_rows: 0,
+        },
+        challenges: Challenges {
+            alpha,
+            // No permutation argument for the moment
+            beta: G::ScalarField::zero(),
+            gamma: G::ScalarField::zero(),
+            // The instances are not relaxed
+            homogenous_challenge: G::ScalarField::one(),
+            r: G::ScalarField::zero(),
+        },
+        columns: &evaluations_d8,
+        l0_1: l0_1(domain.d1),
+        domain,
+    };
+
+    debug!("Prover: computing the quotient polynomial");
+    let quotient_poly: DensePolynomial<G::ScalarField> = {
+        // Compute ∑ α^i constraint_i as an expression
+        let combined_expr =
+            E::combine_constraints(0..(constraints.len() as u32), constraints.to_vec());
+
+        // We want to compute the quotient polynomial, i.e.
+        // t(X) = (∑ α^i constraint_i(X)) / Z_H(X).
+        let expr_evaluation: Evaluations<G::ScalarField, D<G::ScalarField>> =
+            combined_expr.evaluations(&column_env);
+
+        let expr_evaluation_interpolated = expr_evaluation.interpolate();
+
+        // We compute the polynomial t(X) by dividing the constraints polynomial
+        // by the vanishing polynomial, i.e. Z_H(X).
+        let (quotient, rem) = expr_evaluation_interpolated
+            .divide_by_vanishing_poly(domain.d1)
+            .ok_or("Fail division by vanishing poly")?;
+        // As the constraints must be verified on H, the rest of the division
+        // must be equal to 0 as the constraints polynomial and Z_H(X) are both
+        // equal on H.
+        if !rem.is_zero() {
+            return Err(
+                "The constraints are not satisifed since the remainder is not zero".to_string(),
+            );
+        }
+
+        quotient
+    };
+
+    let quotient_commitment = srs.commit_non_hiding(&quotient_poly, QUOTIENT_POLYNOMIAL_CHUNKS);
+    absorb_commitment(&mut fq_sponge, &quotient_commitment);
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Round 3: Evaluations at ζ and ζω
+    ////////////////////////////////////////////////////////////////////////////
+
+    debug!("Prover: evaluating all columns, including the selectors, at ζ and ζω");
+    let zeta_chal = ScalarChallenge(fq_sponge.challenge());
+
+    let zeta = zeta_chal.to_field(endo_r);
+    let zeta_omega = zeta * omega;
+
+    let evaluations: Columns<PointEvaluations<Vec<G::ScalarField>>> =
+        polys.map(|poly| PointEvaluations {
+            zeta: vec![poly.evaluate(&zeta)],
+            zeta_omega: vec![poly.evaluate(&zeta_omega)],
+        });
+
+    let chunked_quotient =
+        quotient_poly.to_chunked_polynomial(QUOTIENT_POLYNOMIAL_CHUNKS, domain.d1.size as usize);
+    let quotient_evaluations = PointEvaluations {
+        zeta: chunked_quotient
+            .polys
+            .iter()
+            .map(|p| p.evaluate(&zeta))
+            .collect::<Vec<_>>(),
+        zeta_omega: chunked_quotient
+            .polys
+            .iter()
+            .map(|p| p.evaluate(&zeta_omega))
+            .collect(),
+    };
+
+    // Absorbing evaluations with a sponge for the other field
+    // We initialize the state with the previous state of the fq_sponge
+    let fq_sponge_before_evaluations = fq_sponge.clone();
+    let mut fr_sponge = EFrSponge::new(G::sponge_params());
+    fr_sponge.absorb(&fq_sponge.digest());
+
+    evaluations.iter().for_each(|evals| {
+        fr_sponge.absorb_multiple(&evals.zeta);
+        fr_sponge.absorb_multiple(&evals.zeta_omega);
+    });
+    fr_sponge.absorb_multiple(&quotient_evaluations.zeta);
+    fr_sponge.absorb_multiple(&quotient_evaluations.zeta_omega);
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Round 4: Opening proof w/o linearization polynomial
+    ////////////////////////////////////////////////////////////////////////////
+
+    // Preparing the polynomials for the opening proof. We do not have any
+    // blinder, therefore we set them to 0.
+    let mut polynomials: Vec<_> = polys
+        .iter()
+        .map(|poly| {
+            (
+                DensePolynomialOrEvaluations::DensePolynomial(poly),
+                PolyComm::new(vec![G::ScalarField::zero()]),
+            )
+        })
+        .collect();
+    // The quotient polynomial has as many blinders as chunks.
+    polynomials.push((
+        DensePolynomialOrEvaluations::DensePolynomial(&quotient_poly),
+        PolyComm::new(vec![G::ScalarField::zero(); QUOTIENT_POLYNOMIAL_CHUNKS]),
+    ));
+
+    // poly scale
+    let v_chal = fr_sponge.challenge();
+    let v = v_chal.to_field(endo_r);
+    // eval scale
+    let u_chal = fr_sponge.challenge();
+    let u = u_chal.to_field(endo_r);
+
+    let group_map = G::Map::setup();
+
+    debug!("Prover: computing the (batched) opening proof using the IPA PCS");
+    let opening_proof = OpeningProof::open::<_, _, D<G::ScalarField>>(
+        srs,
+        &group_map,
+        polynomials.as_slice(),
+        &[zeta, zeta_omega],
+        v,
+        u,
+        fq_sponge_before_evaluations,
+        rng,
+    );
+
+    Ok(InstanceProof {
+        witness_commitments: commitments.witness,
+        evaluations,
+        quotient_commitment,
+        quotient_evaluations,
+        opening_proof,
+    })
+}
+
+/// Absorb an accumulated instance, as the first step of the decider.
+pub fn absorb_accumulated_instance<G: KimchiCurve, EFqSponge>(
+    fq_sponge: &mut EFqSponge,
+    instance: &AccumulatedInstance<G>,
+) where
+    G::BaseField: PrimeField,
+    EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>,
+{
+    instance
+        .witness
+        .iter()
+        .chain(instance.public_inputs.iter())
+        .chain(instance.extended.iter())
+        .chain([&instance.error])
+        .for_each(|comm| absorb_commitment(fq_sponge, comm));
+    let scalars: Vec<G::ScalarField> = std::iter::once(instance.u)
+        .chain(instance.alphas.iter().copied())
+        .collect();
+    fq_sponge.absorb_fr(&scalars);
+}
+
+/// Make a proof that an accumulated instance is satisfied, i.e. that the final
+/// constraint of the folding scheme, evaluated on the accumulated witness, the
+/// columns added by quadraticization and the error column, vanishes on the
+/// domain. The selectors are the ones of the circuit, shared by all the
+/// accumulated instances.
+///
+/// The proof follows the same steps than [prove_instance], with the final
+/// constraint instead of the constraints of the circuit. The columns added by
+/// quadraticization and the error column are committed with a blinder, which is
+/// tracked by the accumulator.
+pub fn prove_decider<
+    G: KimchiCurve,
+    EFqSponge: FqSponge<G::BaseField, G, G::ScalarField> + Clone,
+    EFrSponge: FrSponge<G::ScalarField>,
+    RNG: RngCore + CryptoRng,
 >(
-    _env: &Env<Fp, Fq, E1, E2>,
-) -> Result<Proof, String> {
-    unimplemented!()
+    domain: EvaluationDomains<G::ScalarField>,
+    srs: &SRS<G>,
+    constraints: &[E<G::ScalarField>],
+    selectors: Vec<Vec<G::ScalarField>>,
+    accumulator: &Accumulator<G>,
+    rng: &mut RNG,
+) -> Result<AccumulatorProof<G>, String>
+where
+    G::BaseField: PrimeField,
+{
+    let omega = domain.d1.group_gen;
+    let instance = accumulator.to_accumulated_instance();
+
+    let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Round 1: Absorbing the accumulated instance
+    ////////////////////////////////////////////////////////////////////////////
+
+    debug!("Decider: interpolating the accumulated columns and the selectors");
+    let accumulated_witness = &accumulator.witness.extended_witness;
+    let polys: Columns<DensePolynomial<G::ScalarField>> = Columns {
+        witness: accumulated_witness
+            .witness
+            .witness
+            .iter()
+            .map(|evals| evals.interpolate_by_ref())
+            .collect(),
+        public_inputs: accumulated_witness
+            .witness
+            .public_inputs
+            .iter()
+            .map(|evals| evals.interpolate_by_ref())
+            .collect(),
+        selectors: selectors
+            .into_iter()
+            .map(|evals| Evaluations::from_vec_and_domain(evals, domain.d1).interpolate())
+            .collect(),
+    };
+    // The columns added by quadraticization are indexed from zero, in the
+    // order of their commitments.
+    let extended_polys: Vec<DensePolynomial<G::ScalarField>> = accumulated_witness
+        .extended
+        .values()
+        .map(|evals| evals.interpolate_by_ref())
+        .collect();
+    let error_poly = accumulator.witness.error_vec.interpolate_by_ref();
+
+    // The selectors are fixed by the circuit, but we absorb them as the
+    // instance prover does.
+    let selector_commitments: Vec<PolyComm<G>> = polys
+        .selectors
+        .iter()
+        .map(|poly| srs.commit_non_hiding(poly, 1))
+        .collect();
+    absorb_accumulated_instance(&mut fq_sponge, &instance);
+    selector_commitments
+        .iter()
+        .for_each(|comm| absorb_commitment(&mut fq_sponge, comm));
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Round 2: Creating and committing to the quotient polynomial
+    ////////////////////////////////////////////////////////////////////////////
+
+    debug!("Decider: evaluating all columns, including the selectors, on d4");
+    // The final constraint is of degree 3 when including the selectors.
+    // Therefore, we do evaluate on d4.
+    let evaluations_d4: Columns<Evaluations<G::ScalarField, D<G::ScalarField>>> =
+        polys.map(|poly| poly.evaluate_over_domain_by_ref(domain.d4));
+    let extended_d4: Vec<Evaluations<G::ScalarField, D<G::ScalarField>>> = extended_polys
+        .iter()
+        .map(|poly| poly.evaluate_over_domain_by_ref(domain.d4))
+        .collect();
+    let error_d4 = error_poly.evaluate_over_domain_by_ref(domain.d4);
+
+    debug!("Decider: computing the quotient polynomial");
+    let final_constraint = folding::final_constraint::<G>(constraints);
+    let size_d4 = domain.d4.size as usize;
+    // The next row on d1 is four rows further on d4.
+    let shift = (domain.d4.size / domain.d1.size) as usize;
+    let expr_evaluation: Vec<G::ScalarField> = (0..size_d4)
+        .into_par_iter()
+        .map(|i| {
+            let column = |var: Variable<Column>| {
+                let row = match var.row {
+                    CurrOrNext::Curr => i,
+                    CurrOrNext::Next => (i + shift) % size_d4,
+                };
+                evaluations_d4.get_column(&var.col).unwrap().evals[row]
+            };
+            folding::evaluate_final_constraint::<G>(
+                &final_constraint,
+                instance.u,
+                &instance.alphas,
+                error_d4.evals[i],
+                &|j| extended_d4[j].evals[i],
+                &column,
+            )
+        })
+        .collect();
+    let quotient_poly: DensePolynomial<G::ScalarField> = {
+        let expr_evaluation_interpolated =
+            Evaluations::from_vec_and_domain(expr_evaluation, domain.d4).interpolate();
+        let (quotient, rem) = expr_evaluation_interpolated
+            .divide_by_vanishing_poly(domain.d1)
+            .ok_or("Fail division by vanishing poly")?;
+        if !rem.is_zero() {
+            return Err(
+                "The accumulated instance is not satisfied since the remainder is not zero"
+                    .to_string(),
+            );
+        }
+        quotient
+    };
+
+    let quotient_commitment =
+        srs.commit_non_hiding(&quotient_poly, DECIDER_QUOTIENT_POLYNOMIAL_CHUNKS);
+    absorb_commitment(&mut fq_sponge, &quotient_commitment);
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Round 3: Evaluations at ζ and ζω
+    ////////////////////////////////////////////////////////////////////////////
+
+    debug!("Decider: evaluating all polynomials at ζ and ζω");
+    let (_, endo_r) = G::endos();
+    let zeta_chal = ScalarChallenge(fq_sponge.challenge());
+    let zeta = zeta_chal.to_field(endo_r);
+    let zeta_omega = zeta * omega;
+
+    let evaluate = |poly: &DensePolynomial<G::ScalarField>| PointEvaluations {
+        zeta: vec![poly.evaluate(&zeta)],
+        zeta_omega: vec![poly.evaluate(&zeta_omega)],
+    };
+    let chunked_quotient = quotient_poly
+        .to_chunked_polynomial(DECIDER_QUOTIENT_POLYNOMIAL_CHUNKS, domain.d1.size as usize);
+    let evaluations = DeciderEvaluations {
+        columns: polys.map(evaluate),
+        extended: extended_polys.iter().map(evaluate).collect(),
+        error: evaluate(&error_poly),
+        quotient: PointEvaluations {
+            zeta: chunked_quotient
+                .polys
+                .iter()
+                .map(|p| p.evaluate(&zeta))
+                .collect(),
+            zeta_omega: chunked_quotient
+                .polys
+                .iter()
+                .map(|p| p.evaluate(&zeta_omega))
+                .collect(),
+        },
+    };
+
+    let fq_sponge_before_evaluations = fq_sponge.clone();
+    let mut fr_sponge = EFrSponge::new(G::sponge_params());
+    fr_sponge.absorb(&fq_sponge.digest());
+    evaluations.iter().for_each(|evals| {
+        fr_sponge.absorb_multiple(&evals.zeta);
+        fr_sponge.absorb_multiple(&evals.zeta_omega);
+    });
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Round 4: Opening proof w/o linearization polynomial
+    ////////////////////////////////////////////////////////////////////////////
+
+    // The accumulated columns are committed without blinder, the columns added
+    // by quadraticization and the error column with the blinders tracked by
+    // the accumulator.
+    let no_blinder = || PolyComm::new(vec![G::ScalarField::zero()]);
+    let mut polynomials: Vec<_> = polys
+        .iter()
+        .map(|poly| {
+            (
+                DensePolynomialOrEvaluations::DensePolynomial(poly),
+                no_blinder(),
+            )
+        })
+        .collect();
+    polynomials.extend(extended_polys.iter().map(|poly| {
+        (
+            DensePolynomialOrEvaluations::DensePolynomial(poly),
+            PolyComm::new(vec![accumulator.extended_blinder]),
+        )
+    }));
+    polynomials.push((
+        DensePolynomialOrEvaluations::DensePolynomial(&error_poly),
+        PolyComm::new(vec![accumulator.error_blinder]),
+    ));
+    polynomials.push((
+        DensePolynomialOrEvaluations::DensePolynomial(&quotient_poly),
+        PolyComm::new(vec![
+            G::ScalarField::zero();
+            DECIDER_QUOTIENT_POLYNOMIAL_CHUNKS
+        ]),
+    ));
+
+    // poly scale
+    let v_chal = fr_sponge.challenge();
+    let v = v_chal.to_field(endo_r);
+    // eval scale
+    let u_chal = fr_sponge.challenge();
+    let u = u_chal.to_field(endo_r);
+
+    let group_map = G::Map::setup();
+
+    debug!("Decider: computing the (batched) opening proof using the IPA PCS");
+    let opening_proof = OpeningProof::open::<_, _, D<G::ScalarField>>(
+        srs,
+        &group_map,
+        polynomials.as_slice(),
+        &[zeta, zeta_omega],
+        v,
+        u,
+        fq_sponge_before_evaluations,
+        rng,
+    );
+
+    Ok(AccumulatorProof {
+        instance,
+        decider: DeciderProof {
+            quotient_commitment,
+            evaluations,
+            opening_proof,
+        },
+    })
 }
@@ -0,0 +1,199 @@
+//! The fixed layout of the IVC circuit.
+//!
+//! The selectors and the public inputs of an instance only depend on the size
+//! of the circuit, the number of rows used by the application, the parity of
+//! the iteration and the values absorbed to compute the hash of the public IO.
+//! This module computes them without running the interpreter, for the verifier
+//! to commit to them itself instead of trusting the commitments of the prover.
+//! The [witness environment](crate::witness::Env) must build the same columns.
+
+use ark_ff::PrimeField;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use o1_utils::field_helpers::FieldHelpers;
+use poly_commitment::{commitment::CommitmentCurve, PolyComm};
+
+use crate::{
+    columns::Gadget,
+    interpreter::{next_instruction, Instruction},
+    poseidon_3_60_0_5_5_fp, poseidon_3_60_0_5_5_fq,
+    witness::IVC_STARTING_INSTRUCTION,
+    MAXIMUM_FIELD_SIZE_IN_BITS, NUMBER_OF_PUBLIC_INPUTS, NUMBER_OF_SELECTORS, POSEIDON_ROUNDS_FULL,
+    POSEIDON_STATE_SIZE,
+};
+
+/// The number of rounds of the Poseidon hash computed per row, see
+/// [Gadget::Poseidon].
+const POSEIDON_ROUNDS_PER_ROW: usize = 5;
+
+/// The public inputs of the rows of the application, in the order they are
+/// allocated by [crate::interpreter::run_app].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AppPublicInput {
+    /// The input of the iteration, on the first row
+    Input,
+    /// One on the first row
+    IsFirstRow,
+    /// The output of the iteration, on the last row
+    Output,
+    /// One on the last row
+    IsLastRow,
+}
+
+/// Return the number of rows used by an instruction of the IVC circuit. The
+/// last rounds of the Poseidon hash and the last bit of a scalar multiplication
+/// write their result on the next row, which is left without gadget.
+fn number_of_rows(instr: Instruction) -> usize {
+    match instr {
+        Instruction::Poseidon(round) if round + POSEIDON_ROUNDS_PER_ROW == POSEIDON_ROUNDS_FULL => {
+            2
+        }
+        Instruction::EllipticCurveScaling(_, bit) if bit == MAXIMUM_FIELD_SIZE_IN_BITS - 1 => 2,
+        _ => 1,
+    }
+}
+
+/// Return the instructions of the IVC circuit, with the row they start at and
+/// the number of values of the public IO absorbed before them. The IVC circuit
+/// starts after the `n_app_rows` rows of the application, and the rows after
+/// the last instruction are left without gadget.
+fn ivc_instructions(domain_size: usize, n_app_rows: usize) -> Vec<(usize, Instruction, usize)> {
+    let mut instructions = vec![];
+    let mut row = n_app_rows;
+    let mut instr = IVC_STARTING_INSTRUCTION;
+    let mut idx_values_to_absorb = 0;
+    while row < domain_size {
+        if let Instruction::NoOp = instr {
+            break;
+        }
+        instructions.push((row, instr, idx_values_to_absorb));
+        if let Instruction::Poseidon(0) = instr {
+            idx_values_to_absorb += POSEIDON_STATE_SIZE - 1;
+        }
+        row += number_of_rows(instr);
+        instr = next_instruction(instr, idx_values_to_absorb);
+    }
+    instructions
+}
+
+/// Return the selectors of the gadgets on each row of a circuit of
+/// `domain_size` rows, whose `n_app_rows` first rows run the application.
+pub fn selectors(domain_size: usize, n_app_rows: usize) -> Vec<Vec<bool>> {
+    let mut selectors = vec![vec![false; domain_size]; NUMBER_OF_SELECTORS];
+    selectors[Gadget::App as usize][..n_app_rows]
+        .iter_mut()
+        .for_each(|s| *s = true);
+    ivc_instructions(domain_size, n_app_rows)
+        .into_iter()
+        .for_each(|(row, instr, _)| {
+            let gadget = match instr {
+                Instruction::Poseidon(_) => Gadget::Poseidon,
+                Instruction::EllipticCurveScaling(_, _) => Gadget::EllipticCurveScaling,
+                Instruction::EllipticCurveAddition(_) => Gadget::EllipticCurveAddition,
+                Instruction::NoOp => return,
+            };
+            selectors[gadget as usize][row] = true;
+        });
+    selectors
+}
+
+/// Return the public inputs on each row of a circuit of `domain_size` rows,
+/// whose `n_app_rows` first rows run the application, at the given iteration.
+///
+/// The application gets the input of the iteration on its first row, and the
+/// output on its last row, see [AppPublicInput]. The Poseidon gadget gets the
+/// two values to absorb on the first row of each permutation, followed by the
+/// round constants of the rounds computed on the row. The values to absorb are
+/// given by [values_to_absorb], and are padded with zero.
+pub fn public_inputs<F: PrimeField>(
+    domain_size: usize,
+    n_app_rows: usize,
+    iteration: u64,
+    values_to_absorb: &[F],
+    input: F,
+    output: F,
+) -> Vec<Vec<F>> {
+    let round_constants: Vec<Vec<F>> = if iteration % 2 == 0 {
+        poseidon_3_60_0_5_5_fp::static_params()
+            .round_constants
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|x| F::from_biguint(&x.to_biguint()).unwrap())
+                    .collect()
+            })
+            .collect()
+    } else {
+        poseidon_3_60_0_5_5_fq::static_params()
+            .round_constants
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|x| F::from_biguint(&x.to_biguint()).unwrap())
+                    .collect()
+            })
+            .collect()
+    };
+    let mut public_inputs = vec![vec![F::zero(); domain_size]; NUMBER_OF_PUBLIC_INPUTS];
+    if n_app_rows > 0 {
+        public_inputs[AppPublicInput::Input as usize][0] = input;
+        public_inputs[AppPublicInput::IsFirstRow as usize][0] = F::one();
+        public_inputs[AppPublicInput::Output as usize][n_app_rows - 1] = output;
+        public_inputs[AppPublicInput::IsLastRow as usize][n_app_rows - 1] = F::one();
+    }
+    ivc_instructions(domain_size, n_app_rows)
+        .into_iter()
+        .for_each(|(row, instr, idx_values_to_absorb)| {
+            let Instruction::Poseidon(curr_round) = instr else {
+                return;
+            };
+            if curr_round == 0 {
+                (0..POSEIDON_STATE_SIZE - 1).for_each(|i| {
+                    public_inputs[i][row] = values_to_absorb
+                        .get(idx_values_to_absorb + i)
+                        .copied()
+                        .unwrap_or(F::zero());
+                });
+            }
+            (0..POSEIDON_ROUNDS_PER_ROW).for_each(|idx_round| {
+                (0..POSEIDON_STATE_SIZE).for_each(|i| {
+                    let idx = POSEIDON_STATE_SIZE - 1 + POSEIDON_STATE_SIZE * idx_round + i;
+                    public_inputs[idx][row] = round_constants[curr_round + idx_round][i];
+                });
+            });
+        });
+    public_inputs
+}
+
+/// Return the values absorbed to compute the hash of the public IO by an
+/// iteration whose circuit is over the base field of `G`: the coordinates of
+/// the accumulators of the columns over `G`, followed by the initial input
+/// `z0`, the current output `zi` and the index of the iteration.
+///
+/// Return `None` if an accumulator is the point at infinity.
+pub fn values_to_absorb<G: CommitmentCurve>(
+    accumulators: &[PolyComm<G>],
+    z0: &BigInt,
+    zi: &BigInt,
+    iteration: u64,
+) -> Option<Vec<G::BaseField>>
+where
+    G::BaseField: PrimeField,
+{
+    let mut values = Vec::with_capacity(accumulators.len() * 2 + 3);
+    for accumulator in accumulators {
+        let (x, y) = accumulator.get_first_chunk().to_coordinates()?;
+        values.push(x);
+        values.push(y);
+    }
+    values.push(to_field(z0));
+    values.push(to_field(zi));
+    values.push(G::BaseField::from(iteration));
+    Some(values)
+}
+
+/// Reduce an input or an output of the application in the field `F`.
+pub fn to_field<F: PrimeField>(x: &BigInt) -> F {
+    let modulus: BigInt = F::modulus_biguint().into();
+    F::from_biguint(&x.mod_floor(&modulus).to_biguint().unwrap()).unwrap()
+}
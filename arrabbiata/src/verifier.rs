@@ -1 +1,751 @@
 //! A verifier for the folding/accumulation scheme
+
+use ::folding::{FoldingScheme, RelaxedInstance};
+use ark_ec::AffineRepr;
+use ark_ff::{Field, One, PrimeField, Zero};
+use ark_poly::Evaluations;
+use kimchi::{
+    circuits::{
+        domains::EvaluationDomains,
+        expr::{ColumnEvaluations, Constants, Expr, ExprError, PolishToken},
+        gate::CurrOrNext,
+    },
+    curve::KimchiCurve,
+    groupmap::GroupMap,
+    plonk_sponge::FrSponge,
+    proof::PointEvaluations,
+};
+use log::debug;
+use mina_poseidon::{sponge::ScalarChallenge, FqSponge};
+use poly_commitment::{
+    commitment::{
+        absorb_commitment, combined_inner_product, BatchEvaluationProof, Evaluation, PolyComm,
+    },
+    ipa::{OpeningProof, SRS},
+    OpenProof, SRS as _,
+};
+use rand::thread_rng;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{
+    column_env::get_all_columns,
+    columns::{Challenges, Column, E},
+    folding::{self, to_folding_expression, FoldingInstance, FoldingStructure, IVCFoldingConfig},
+    proof::{AccumulatorProof, Columns, DeciderProof, InstanceProof, LastInstanceProof, Proof},
+    prover::{
+        absorb_accumulated_instance, get_constraints, selectors_to_columns,
+        DECIDER_QUOTIENT_POLYNOMIAL_CHUNKS, QUOTIENT_POLYNOMIAL_CHUNKS,
+    },
+    setup, NUMBER_OF_COLUMNS, NUMBER_OF_PUBLIC_INPUTS, NUMBER_OF_SELECTORS,
+};
+
+struct ColumnEval<'a, F> {
+    evaluations: &'a Columns<PointEvaluations<Vec<F>>>,
+}
+
+impl<F: Field> ColumnEvaluations<F> for ColumnEval<'_, F> {
+    type Column = Column;
+    fn evaluate(&self, col: Self::Column) -> Result<PointEvaluations<F>, ExprError<Self::Column>> {
+        let evals = self
+            .evaluations
+            .get_column(&col)
+            .ok_or(ExprError::MissingEvaluation(col, CurrOrNext::Curr))?;
+        match (evals.zeta.as_slice(), evals.zeta_omega.as_slice()) {
+            ([zeta], [zeta_omega]) => Ok(PointEvaluations {
+                zeta: *zeta,
+                zeta_omega: *zeta_omega,
+            }),
+            ([_], _) => Err(ExprError::MissingEvaluation(col, CurrOrNext::Next)),
+            _ => Err(ExprError::MissingEvaluation(col, CurrOrNext::Curr)),
+        }
+    }
+}
+
+/// Verify a proof for the IVC circuit, whose `n_app_rows` first rows run the
+/// application.
+///
+/// The SRS and the domains must have the size given by
+/// [Proof::srs_log2_size].
+///
+/// The selectors and the public inputs of the instances are computed by the
+/// verifier, see [crate::setup]. The public inputs bind each instance to the
+/// input and the output of the application at its iteration, and to the
+/// accumulator of the other curve.
+///
+/// The verifier replays the folding of the instances of all the iterations,
+/// checks that it leads to the accumulated instances given by the prover, and
+/// decides them. The cost of the verifier is therefore linear in the number of
+/// iterations, until the folding is checked by the IVC circuit.
+pub fn verify<
+    Fp: PrimeField,
+    Fq: PrimeField,
+    E1: KimchiCurve<ScalarField = Fp, BaseField = Fq>,
+    E2: KimchiCurve<ScalarField = Fq, BaseField = Fp>,
+    EFqSponge1: FqSponge<Fq, E1, Fp> + Clone,
+    EFrSponge1: FrSponge<Fp>,
+    EFqSponge2: FqSponge<Fp, E2, Fq> + Clone,
+    EFrSponge2: FrSponge<Fq>,
+>(
+    domain_fp: EvaluationDomains<Fp>,
+    domain_fq: EvaluationDomains<Fq>,
+    srs_e1: &SRS<E1>,
+    srs_e2: &SRS<E2>,
+    n_app_rows: usize,
+    proof: &Proof<E1, E2>,
+) -> bool
+where
+    <E1::Params as ark_ec::CurveConfig>::BaseField: PrimeField,
+    <E2::Params as ark_ec::CurveConfig>::BaseField: PrimeField,
+{
+    if proof.srs_log2_size != domain_fp.d1.log_size_of_group as usize
+        || proof.srs_log2_size != domain_fq.d1.log_size_of_group as usize
+    {
+        debug!("Verifier: the domains do not have the size used by the prover");
+        return false;
+    }
+    let domain_size = domain_fp.d1.size as usize;
+    // The application must have a row to get the input and give the output
+    if n_app_rows == 0 || n_app_rows > domain_size {
+        debug!("Verifier: the application uses no row, or more rows than the circuit has");
+        return false;
+    }
+    // The instances of the odd iterations are accumulated over the second
+    // curve, there is none after the first iteration.
+    if proof.accumulator_e2.is_some() != (proof.iteration > 0) {
+        debug!("Verifier: the number of accumulators does not match the iteration");
+        return false;
+    }
+
+    let selectors = setup::selectors(domain_size, n_app_rows);
+    let selector_commitments_e1 =
+        commit_columns(domain_fp, srs_e1, selectors_to_columns(&selectors));
+    let selector_commitments_e2 =
+        commit_columns(domain_fq, srs_e2, selectors_to_columns(&selectors));
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Replaying the folding steps
+    ////////////////////////////////////////////////////////////////////////////
+
+    // The folding is not checked by the circuit yet, therefore the verifier
+    // recomputes the accumulated instances from the instance of each iteration.
+    // The public inputs of an instance are computed by the verifier: they bind
+    // the instance to the input and the output of the application at the
+    // iteration, and to the accumulator of the other curve.
+    if proof.intermediate_outputs.len() as u64 != proof.iteration
+        || proof.folding_e1.len() as u64 != proof.iteration / 2 + 1
+        || proof.folding_e2.len() as u64 != proof.iteration / 2 + proof.iteration % 2
+    {
+        debug!("Verifier: the number of folding steps does not match the iteration");
+        return false;
+    }
+    let structure_e1 = FoldingStructure {
+        selectors: selectors_to_columns(&selectors),
+    };
+    let constraints_e1 = get_constraints::<Fp, E2>(0);
+    let (scheme_e1, final_constraint_e1) = FoldingScheme::<IVCFoldingConfig<E1>>::new(
+        constraints_e1.iter().map(to_folding_expression).collect(),
+        srs_e1,
+        domain_fp.d1,
+        &structure_e1,
+    );
+    let structure_e2 = FoldingStructure {
+        selectors: selectors_to_columns(&selectors),
+    };
+    let constraints_e2 = get_constraints::<Fq, E1>(1);
+    let (scheme_e2, final_constraint_e2) = FoldingScheme::<IVCFoldingConfig<E2>>::new(
+        constraints_e2.iter().map(to_folding_expression).collect(),
+        srs_e2,
+        domain_fq.d1,
+        &structure_e2,
+    );
+    // Before the first iteration over a curve, the accumulator absorbed by the
+    // other curve is the initial one of the environment.
+    let initial_accumulator_e1 = vec![PolyComm::new(vec![srs_e1.h]); NUMBER_OF_COLUMNS];
+    let initial_accumulator_e2 = vec![PolyComm::new(vec![srs_e2.h]); NUMBER_OF_COLUMNS];
+
+    let mut accumulator_e1: Option<RelaxedInstance<E1, FoldingInstance<E1>>> = None;
+    let mut accumulator_e2: Option<RelaxedInstance<E2, FoldingInstance<E2>>> = None;
+    let (mut steps_e1, mut steps_e2) = (proof.folding_e1.iter(), proof.folding_e2.iter());
+    let inputs = std::iter::once(&proof.z0).chain(&proof.intermediate_outputs);
+    let outputs = proof.intermediate_outputs.iter().chain([&proof.zi]);
+    for (iteration, (input, output)) in (0..).zip(inputs.zip(outputs)) {
+        if iteration % 2 == 0 {
+            let other_accumulator = accumulator_e2
+                .as_ref()
+                .map_or(initial_accumulator_e2.clone(), folding::witness_commitments);
+            accumulator_e1 =
+                setup::values_to_absorb(&other_accumulator, &proof.z0, input, iteration)
+                    .zip(steps_e1.next())
+                    .and_then(|(values_to_absorb, step)| {
+                        let public_inputs = setup::public_inputs(
+                            domain_size,
+                            n_app_rows,
+                            iteration,
+                            &values_to_absorb,
+                            setup::to_field(input),
+                            setup::to_field(output),
+                        );
+                        folding::verify_folding_step::<E1, EFqSponge1>(
+                            &scheme_e1,
+                            final_constraint_e1.number_of_alphas(),
+                            &commit_columns(domain_fp, srs_e1, public_inputs),
+                            step,
+                            accumulator_e1.take(),
+                        )
+                    });
+            if accumulator_e1.is_none() {
+                debug!("Verifier: the folding step of the iteration {iteration} is invalid");
+                return false;
+            }
+        } else {
+            let other_accumulator = accumulator_e1
+                .as_ref()
+                .map_or(initial_accumulator_e1.clone(), folding::witness_commitments);
+            accumulator_e2 =
+                setup::values_to_absorb(&other_accumulator, &proof.z0, input, iteration)
+                    .zip(steps_e2.next())
+                    .and_then(|(values_to_absorb, step)| {
+                        let public_inputs = setup::public_inputs(
+                            domain_size,
+                            n_app_rows,
+                            iteration,
+                            &values_to_absorb,
+                            setup::to_field(input),
+                            setup::to_field(output),
+                        );
+                        folding::verify_folding_step::<E2, EFqSponge2>(
+                            &scheme_e2,
+                            final_constraint_e2.number_of_alphas(),
+                            &commit_columns(domain_fq, srs_e2, public_inputs),
+                            step,
+                            accumulator_e2.take(),
+                        )
+                    });
+            if accumulator_e2.is_none() {
+                debug!("Verifier: the folding step of the iteration {iteration} is invalid");
+                return false;
+            }
+        }
+    }
+
+    // The accumulated instances given by the prover must be the ones obtained
+    // by folding all the instances.
+    let accumulator_e1 = accumulator_e1.map(|acc| folding::to_accumulated_instance(&acc));
+    let accumulator_e2 = accumulator_e2.map(|acc| folding::to_accumulated_instance(&acc));
+    if accumulator_e1.as_ref() != Some(&proof.accumulator_e1.instance)
+        || accumulator_e2.as_ref() != proof.accumulator_e2.as_ref().map(|acc| &acc.instance)
+    {
+        debug!("Verifier: the accumulated instances are not the folding of the instances");
+        return false;
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Deciding the accumulated instances
+    ////////////////////////////////////////////////////////////////////////////
+
+    if !verify_decider::<E1, EFqSponge1, EFrSponge1>(
+        domain_fp,
+        srs_e1,
+        &constraints_e1,
+        &selector_commitments_e1,
+        &proof.accumulator_e1,
+    ) {
+        debug!("Verifier: the instance accumulated over the first curve is not satisfied");
+        return false;
+    }
+    if let Some(accumulator_e2) = &proof.accumulator_e2 {
+        if !verify_decider::<E2, EFqSponge2, EFrSponge2>(
+            domain_fq,
+            srs_e2,
+            &constraints_e2,
+            &selector_commitments_e2,
+            accumulator_e2,
+        ) {
+            debug!("Verifier: the instance accumulated over the second curve is not satisfied");
+            return false;
+        }
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Verifying the last instance
+    ////////////////////////////////////////////////////////////////////////////
+
+    // The last instance is the one folded by the last folding step, and its
+    // public inputs are computed as during the replay. Its witness must be the
+    // one committed to in the last folding step.
+    let input = proof.intermediate_outputs.last().unwrap_or(&proof.z0);
+    match &proof.last_instance {
+        LastInstanceProof::E1(instance_proof) if proof.iteration % 2 == 0 => {
+            if proof.folding_e1.last().map(|step| &step.witness)
+                != Some(&instance_proof.witness_commitments)
+            {
+                debug!("Verifier: the last instance is not the one folded last");
+                return false;
+            }
+            let accumulator_e2 = match &proof.accumulator_e2 {
+                Some(accumulator_e2) => accumulator_e2.instance.witness.clone(),
+                None => initial_accumulator_e2,
+            };
+            let Some(values_to_absorb) =
+                setup::values_to_absorb(&accumulator_e2, &proof.z0, input, proof.iteration)
+            else {
+                debug!("Verifier: an accumulator is the point at infinity");
+                return false;
+            };
+            let public_inputs = setup::public_inputs(
+                domain_size,
+                n_app_rows,
+                proof.iteration,
+                &values_to_absorb,
+                setup::to_field(input),
+                setup::to_field(&proof.zi),
+            );
+            let constraints = get_constraints::<Fp, E2>(proof.iteration);
+            verify_instance::<E1, EFqSponge1, EFrSponge1>(
+                domain_fp,
+                srs_e1,
+                &constraints,
+                commit_columns(domain_fp, srs_e1, public_inputs),
+                selector_commitments_e1,
+                instance_proof,
+            )
+        }
+        LastInstanceProof::E2(instance_proof) if proof.iteration % 2 == 1 => {
+            if proof.folding_e2.last().map(|step| &step.witness)
+                != Some(&instance_proof.witness_commitments)
+            {
+                debug!("Verifier: the last instance is not the one folded last");
+                return false;
+            }
+            let Some(values_to_absorb) = setup::values_to_absorb(
+                &proof.accumulator_e1.instance.witness,
+                &proof.z0,
+                input,
+                proof.iteration,
+            ) else {
+                debug!("Verifier: an accumulator is the point at infinity");
+                return false;
+            };
+            let public_inputs = setup::public_inputs(
+                domain_size,
+                n_app_rows,
+                proof.iteration,
+                &values_to_absorb,
+                setup::to_field(input),
+                setup::to_field(&proof.zi),
+            );
+            let constraints = get_constraints::<Fq, E1>(proof.iteration);
+            verify_instance::<E2, EFqSponge2, EFrSponge2>(
+                domain_fq,
+                srs_e2,
+                &constraints,
+                commit_columns(domain_fq, srs_e2, public_inputs),
+                selector_commitments_e2,
+                instance_proof,
+            )
+        }
+        _ => {
+            debug!("Verifier: the instance is not over the curve used by the last iteration");
+            false
+        }
+    }
+}
+
+/// Commit to columns computed by the verifier, given by their evaluations
+/// over the domain.
+fn commit_columns<G: KimchiCurve>(
+    domain: EvaluationDomains<G::ScalarField>,
+    srs: &SRS<G>,
+    columns: Vec<Vec<G::ScalarField>>,
+) -> Vec<PolyComm<G>>
+where
+    G::BaseField: PrimeField,
+{
+    columns
+        .into_par_iter()
+        .map(|evals| {
+            let evals = Evaluations::from_vec_and_domain(evals, domain.d1);
+            srs.commit_evaluations_non_hiding(domain.d1, &evals)
+        })
+        .collect()
+}
+
+/// Verify the proof that an instance of the circuit, over the curve `G`,
+/// satisfies the given constraints. The commitments to the public inputs and
+/// to the selectors are computed by the verifier.
+pub fn verify_instance<
+    G: KimchiCurve,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+>(
+    domain: EvaluationDomains<G::ScalarField>,
+    srs: &SRS<G>,
+    constraints: &[E<G::ScalarField>],
+    public_input_commitments: Vec<PolyComm<G>>,
+    selector_commitments: Vec<PolyComm<G>>,
+    proof: &InstanceProof<G>,
+) -> bool
+where
+    <G as AffineRepr>::BaseField: PrimeField,
+{
+    let InstanceProof {
+        witness_commitments,
+        evaluations,
+        quotient_commitment,
+        quotient_evaluations,
+        opening_proof,
+    } = proof;
+
+    // Checking the shape of the proof before using it
+    {
+        let expected_sizes = [
+            NUMBER_OF_COLUMNS,
+            NUMBER_OF_PUBLIC_INPUTS,
+            NUMBER_OF_SELECTORS,
+        ];
+        let commitments_sizes = [
+            witness_commitments.len(),
+            public_input_commitments.len(),
+            selector_commitments.len(),
+        ];
+        let evaluations_sizes = [
+            evaluations.witness.len(),
+            evaluations.public_inputs.len(),
+            evaluations.selectors.len(),
+        ];
+        if commitments_sizes != expected_sizes
+            || evaluations_sizes != expected_sizes
+            || quotient_commitment.len() != QUOTIENT_POLYNOMIAL_CHUNKS
+            || quotient_evaluations.zeta.len() != QUOTIENT_POLYNOMIAL_CHUNKS
+            || quotient_evaluations.zeta_omega.len() != QUOTIENT_POLYNOMIAL_CHUNKS
+        {
+            debug!("Verifier: the proof does not have the expected shape");
+            return false;
+        }
+    }
+
+    let commitments = Columns {
+        witness: witness_commitments.clone(),
+        public_inputs: public_input_commitments,
+        selectors: selector_commitments,
+    };
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Absorbing all the commitments to the columns
+    ////////////////////////////////////////////////////////////////////////////
+
+    let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
+    commitments
+        .iter()
+        .for_each(|comm| absorb_commitment(&mut fq_sponge, comm));
+
+    // Sample α with the Fq-Sponge.
+    let alpha = fq_sponge.challenge();
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Quotient polynomial
+    ////////////////////////////////////////////////////////////////////////////
+
+    absorb_commitment(&mut fq_sponge, quotient_commitment);
+
+    // -- Preparing for opening proof verification
+    let zeta_chal = ScalarChallenge(fq_sponge.challenge());
+    let (_, endo_r) = G::endos();
+    let zeta: G::ScalarField = zeta_chal.to_field(endo_r);
+    let omega = domain.d1.group_gen;
+    let zeta_omega = zeta * omega;
+
+    // -- Absorb all evaluations
+    let fq_sponge_before_evaluations = fq_sponge.clone();
+    let mut fr_sponge = EFrSponge::new(G::sponge_params());
+    fr_sponge.absorb(&fq_sponge.digest());
+
+    evaluations.iter().for_each(|evals| {
+        fr_sponge.absorb_multiple(&evals.zeta);
+        fr_sponge.absorb_multiple(&evals.zeta_omega);
+    });
+    fr_sponge.absorb_multiple(&quotient_evaluations.zeta);
+    fr_sponge.absorb_multiple(&quotient_evaluations.zeta_omega);
+
+    let challenges = Challenges {
+        alpha,
+        // No permutation argument for the moment
+        beta: G::ScalarField::zero(),
+        gamma: G::ScalarField::zero(),
+        // The instances are not relaxed
+        homogenous_challenge: G::ScalarField::one(),
+        r: G::ScalarField::zero(),
+    };
+
+    let constants = Constants {
+        endo_coefficient: *endo_r,
+        mds: &G::sponge_params().mds,
+        zk_rows: 0,
+    };
+
+    let column_eval = ColumnEval { evaluations };
+
+    let combined_expr =
+        Expr::combine_constraints(0..(constraints.len() as u32), constraints.to_vec());
+
+    let numerator_zeta = match PolishToken::evaluate(
+        combined_expr.to_polish().as_slice(),
+        domain.d1,
+        zeta,
+        &column_eval,
+        &constants,
+        &challenges,
+    ) {
+        Ok(res) => res,
+        Err(err) => {
+            debug!("Verifier: could not evaluate the constraints at ζ: {err}");
+            return false;
+        }
+    };
+
+    // Check the quotient polynomial is consistent with the constraints
+    let quotient_zeta = {
+        let zeta_n = zeta.pow([domain.d1.size]);
+        quotient_evaluations
+            .zeta
+            .iter()
+            .rev()
+            .fold(G::ScalarField::zero(), |acc, chunk| acc * zeta_n + chunk)
+    };
+    let vanishing_zeta = zeta.pow([domain.d1.size]) - G::ScalarField::one();
+    if quotient_zeta * vanishing_zeta != numerator_zeta {
+        debug!("Verifier: the quotient polynomial is not consistent with the constraints");
+        return false;
+    }
+
+    let v_chal = fr_sponge.challenge();
+    let v = v_chal.to_field(endo_r);
+    let u_chal = fr_sponge.challenge();
+    let u = u_chal.to_field(endo_r);
+
+    let mut evaluations: Vec<_> = get_all_columns()
+        .into_iter()
+        .map(|column| {
+            // The shape of the proof has been checked above
+            let commitment: PolyComm<G> = commitments.get_column(&column).unwrap().clone();
+            let evals = evaluations.get_column(&column).unwrap();
+            Evaluation {
+                commitment,
+                evaluations: vec![evals.zeta.clone(), evals.zeta_omega.clone()],
+            }
+        })
+        .collect();
+
+    evaluations.push(Evaluation {
+        commitment: quotient_commitment.clone(),
+        evaluations: vec![
+            quotient_evaluations.zeta.clone(),
+            quotient_evaluations.zeta_omega.clone(),
+        ],
+    });
+
+    let combined_inner_product = {
+        let es: Vec<_> = evaluations
+            .iter()
+            .map(|Evaluation { evaluations, .. }| evaluations.clone())
+            .collect();
+
+        combined_inner_product(&v, &u, es.as_slice())
+    };
+
+    let batch = BatchEvaluationProof {
+        sponge: fq_sponge_before_evaluations,
+        evaluations,
+        evaluation_points: vec![zeta, zeta_omega],
+        polyscale: v,
+        evalscale: u,
+        opening: opening_proof,
+        combined_inner_product,
+    };
+
+    let group_map = G::Map::setup();
+
+    OpeningProof::verify(srs, &group_map, &mut [batch], &mut thread_rng())
+}
+
+/// Verify the proof that an accumulated instance over the curve `G` is
+/// satisfied, i.e. that the final constraint of the folding scheme built from
+/// the given constraints vanishes on the domain. The commitments to the
+/// selectors are computed by the verifier.
+pub fn verify_decider<
+    G: KimchiCurve,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+>(
+    domain: EvaluationDomains<G::ScalarField>,
+    srs: &SRS<G>,
+    constraints: &[E<G::ScalarField>],
+    selector_commitments: &[PolyComm<G>],
+    proof: &AccumulatorProof<G>,
+) -> bool
+where
+    <G as AffineRepr>::BaseField: PrimeField,
+{
+    let AccumulatorProof {
+        instance,
+        decider:
+            DeciderProof {
+                quotient_commitment,
+                evaluations,
+                opening_proof,
+            },
+    } = proof;
+
+    let final_constraint = folding::final_constraint::<G>(constraints);
+
+    // Checking the shape of the proof before using it
+    {
+        let number_of_extended_columns = final_constraint.number_of_extended_columns();
+        let expected_sizes = [
+            NUMBER_OF_COLUMNS,
+            NUMBER_OF_PUBLIC_INPUTS,
+            NUMBER_OF_SELECTORS,
+            number_of_extended_columns,
+            final_constraint.number_of_alphas(),
+        ];
+        let commitments_sizes = [
+            instance.witness.len(),
+            instance.public_inputs.len(),
+            selector_commitments.len(),
+            instance.extended.len(),
+            instance.alphas.len(),
+        ];
+        let evaluations_sizes = [
+            evaluations.columns.witness.len(),
+            evaluations.columns.public_inputs.len(),
+            evaluations.columns.selectors.len(),
+            evaluations.extended.len(),
+            instance.alphas.len(),
+        ];
+        let single_chunks = instance
+            .witness
+            .iter()
+            .chain(instance.public_inputs.iter())
+            .chain(instance.extended.iter())
+            .chain([&instance.error])
+            .all(|comm| comm.len() == 1);
+        let single_evaluations = evaluations
+            .columns
+            .iter()
+            .chain(evaluations.extended.iter())
+            .chain([&evaluations.error])
+            .all(|evals| evals.zeta.len() == 1 && evals.zeta_omega.len() == 1);
+        if commitments_sizes != expected_sizes
+            || evaluations_sizes != expected_sizes
+            || !single_chunks
+            || !single_evaluations
+            || quotient_commitment.len() != DECIDER_QUOTIENT_POLYNOMIAL_CHUNKS
+            || evaluations.quotient.zeta.len() != DECIDER_QUOTIENT_POLYNOMIAL_CHUNKS
+            || evaluations.quotient.zeta_omega.len() != DECIDER_QUOTIENT_POLYNOMIAL_CHUNKS
+        {
+            debug!("Verifier: the decider proof does not have the expected shape");
+            return false;
+        }
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Absorbing the accumulated instance and the quotient polynomial
+    ////////////////////////////////////////////////////////////////////////////
+
+    let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
+    absorb_accumulated_instance(&mut fq_sponge, instance);
+    selector_commitments
+        .iter()
+        .for_each(|comm| absorb_commitment(&mut fq_sponge, comm));
+    absorb_commitment(&mut fq_sponge, quotient_commitment);
+
+    // -- Preparing for opening proof verification
+    let zeta_chal = ScalarChallenge(fq_sponge.challenge());
+    let (_, endo_r) = G::endos();
+    let zeta: G::ScalarField = zeta_chal.to_field(endo_r);
+    let omega = domain.d1.group_gen;
+    let zeta_omega = zeta * omega;
+
+    // -- Absorb all evaluations
+    let fq_sponge_before_evaluations = fq_sponge.clone();
+    let mut fr_sponge = EFrSponge::new(G::sponge_params());
+    fr_sponge.absorb(&fq_sponge.digest());
+    evaluations.iter().for_each(|evals| {
+        fr_sponge.absorb_multiple(&evals.zeta);
+        fr_sponge.absorb_multiple(&evals.zeta_omega);
+    });
+
+    // Check the quotient polynomial is consistent with the final constraint.
+    // The shape of the proof has been checked above.
+    let numerator_zeta = folding::evaluate_final_constraint::<G>(
+        &final_constraint,
+        instance.u,
+        &instance.alphas,
+        evaluations.error.zeta[0],
+        &|i| evaluations.extended[i].zeta[0],
+        &|var| {
+            let evals = evaluations.columns.get_column(&var.col).unwrap();
+            match var.row {
+                CurrOrNext::Curr => evals.zeta[0],
+                CurrOrNext::Next => evals.zeta_omega[0],
+            }
+        },
+    );
+    let quotient_zeta = {
+        let zeta_n = zeta.pow([domain.d1.size]);
+        evaluations
+            .quotient
+            .zeta
+            .iter()
+            .rev()
+            .fold(G::ScalarField::zero(), |acc, chunk| acc * zeta_n + chunk)
+    };
+    let vanishing_zeta = zeta.pow([domain.d1.size]) - G::ScalarField::one();
+    if quotient_zeta * vanishing_zeta != numerator_zeta {
+        debug!("Verifier: the quotient polynomial is not consistent with the final constraint");
+        return false;
+    }
+
+    let v_chal = fr_sponge.challenge();
+    let v = v_chal.to_field(endo_r);
+    let u_chal = fr_sponge.challenge();
+    let u = u_chal.to_field(endo_r);
+
+    let commitments = instance
+        .witness
+        .iter()
+        .chain(instance.public_inputs.iter())
+        .chain(selector_commitments.iter())
+        .chain(instance.extended.iter())
+        .chain([&instance.error, quotient_commitment]);
+    let evaluations: Vec<_> = commitments
+        .zip(evaluations.iter())
+        .map(|(commitment, evals)| Evaluation {
+            commitment: commitment.clone(),
+            evaluations: vec![evals.zeta.clone(), evals.zeta_omega.clone()],
+        })
+        .collect();
+
+    let combined_inner_product = {
+        let es: Vec<_> = evaluations
+            .iter()
+            .map(|Evaluation { evaluations, .. }| evaluations.clone())
+            .collect();
+
+        combined_inner_product(&v, &u, es.as_slice())
+    };
+
+    let batch = BatchEvaluationProof {
+        sponge: fq_sponge_before_evaluations,
+        evaluations,
+        evaluation_points: vec![zeta, zeta_omega],
+        polyscale: v,
+        evalscale: u,
+        opening: opening_proof,
+        combined_inner_product,
+    };
+
+    let group_map = G::Map::setup();
+
+    OpeningProof::verify(srs, &group_map, &mut [batch], &mut thread_rng())
+}
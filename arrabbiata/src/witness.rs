@@ -12,10 +12,11 @@ use std::time::Instant;
 
 use crate::{
     columns::{Column, Gadget},
-    interpreter::{Instruction, InterpreterEnv, Side},
-    poseidon_3_60_0_5_5_fp, poseidon_3_60_0_5_5_fq, MAXIMUM_FIELD_SIZE_IN_BITS, NUMBER_OF_COLUMNS,
-    NUMBER_OF_PUBLIC_INPUTS, NUMBER_OF_SELECTORS, NUMBER_OF_VALUES_TO_ABSORB_PUBLIC_IO,
-    POSEIDON_ALPHA, POSEIDON_ROUNDS_FULL, POSEIDON_STATE_SIZE,
+    interpreter::{next_instruction, Instruction, InterpreterEnv, Side},
+    poseidon_3_60_0_5_5_fp, poseidon_3_60_0_5_5_fq,
+    setup::{self, AppPublicInput},
+    MAXIMUM_FIELD_SIZE_IN_BITS, NUMBER_OF_COLUMNS, NUMBER_OF_PUBLIC_INPUTS, NUMBER_OF_SELECTORS,
+    NUMBER_OF_VALUES_TO_ABSORB_PUBLIC_IO, POSEIDON_ALPHA, POSEIDON_STATE_SIZE,
 };
 
 pub const IVC_STARTING_INSTRUCTION: Instruction = Instruction::Poseidon(0);
@@ -160,6 +161,13 @@ pub struct Env<
     /// column when committing to the witness.
     pub witness: Vec<Vec<BigInt>>,
 
+    /// The values of the public inputs of the current instance of the circuit.
+    /// The size of the outer vector must be equal to the number of public
+    /// inputs in the circuit.
+    /// The size of the inner vector must be equal to the number of rows in
+    /// the circuit.
+    pub public_inputs: Vec<Vec<BigInt>>,
+
     // --------------
     // Inputs
     /// Initial input
@@ -167,6 +175,10 @@ pub struct Env<
 
     /// Current input
     pub zi: BigInt,
+
+    /// Output of the application at the current iteration, i.e. the input of
+    /// the next iteration. It is set by [Env::compute_output].
+    pub output: BigInt,
     // ---------------
 
     // ---------------
@@ -304,8 +316,7 @@ where
 
     fn square(&mut self, pos: Self::Position, x: Self::Variable) -> Self::Variable {
         let res = x.clone() * x.clone();
-        self.write_column(pos, res.clone());
-        res
+        self.write_column(pos, res)
     }
 
     /// Flagged as unsafe as it does require an additional range check
@@ -331,13 +342,36 @@ where
         }
     }
 
-    // FIXME: for now, we use the row number and compute the square.
-    // This is only for testing purposes, and having something to build the
-    // witness.
+    // The input of the next row is the output of the current row, computed
+    // in the second column by [crate::interpreter::run_app].
     fn fetch_input(&mut self, pos: Self::Position) -> Self::Variable {
-        let x = BigInt::from(self.current_row as u64);
-        self.write_column(pos, x.clone());
-        x
+        let (_, row) = pos;
+        let x = match row {
+            CurrOrNext::Curr if self.current_row == 0 => self.zi.clone(),
+            CurrOrNext::Curr => self.read_position(pos),
+            CurrOrNext::Next => self.state[1].clone(),
+        };
+        self.write_column(pos, x)
+    }
+
+    // The output of the iteration, and the flag of the last row of the
+    // application, are only known once all the rows of the application have
+    // been built. They are set by [Env::compute_output].
+    fn fetch_app_public_input(&mut self, pos: Self::Position) -> Self::Variable {
+        let (col, _) = pos;
+        let Column::PublicInput(idx) = col else {
+            panic!("Only works for public inputs")
+        };
+        let value = if self.current_row != 0 {
+            BigInt::from(0_usize)
+        } else if idx == AppPublicInput::Input as usize {
+            self.zi.clone()
+        } else if idx == AppPublicInput::IsFirstRow as usize {
+            BigInt::from(1_usize)
+        } else {
+            BigInt::from(0_usize)
+        };
+        self.write_public_input(pos, value)
     }
 
     /// Reset the environment to build the next row
@@ -346,6 +380,10 @@ where
         self.state.iter().enumerate().for_each(|(i, x)| {
             self.witness[i][self.current_row] = x.clone();
         });
+        // And the public inputs used by the current row
+        self.public_state.iter().enumerate().for_each(|(i, x)| {
+            self.public_inputs[i][self.current_row] = x.clone();
+        });
         // We increment the row
         // TODO: should we check that we are not going over the domain size?
         self.current_row += 1;
@@ -357,6 +395,8 @@ where
         self.state = self.next_state.clone();
         // And we reset the next state
         self.next_state = std::array::from_fn(|_| BigInt::from(0_usize));
+        // Public inputs are only valid for the row they have been written in
+        self.public_state = std::array::from_fn(|_| BigInt::from(0_usize));
     }
 
     /// FIXME: check if we need to pick the left or right sponge
@@ -424,15 +464,10 @@ where
         }
     }
 
-    // The following values are expected to be absorbed in order:
-    // - z0
-    // - z1
-    // - acc[0]
-    // - acc[1]
-    // - ...
-    // - acc[N_COL - 1]
-    // FIXME: for now, we will only absorb the accumulators as z0 and z1 are not
-    // updated yet.
+    // The values are absorbed in the order given by
+    // [crate::setup::values_to_absorb]: the coordinates of the accumulators of
+    // the other curve, z0, zi and the index of the iteration. The last
+    // permutation is padded with zero.
     unsafe fn fetch_value_to_absorb(
         &mut self,
         pos: Self::Position,
@@ -446,38 +481,30 @@ where
         if curr_round != 0 {
             self.write_public_input(pos, self.zero())
         } else {
-            // FIXME: we must absorb z0, z1 and i!
-            // We multiply by 2 as we have two coordinates
             let idx = self.idx_values_to_absorb;
-            let res = if idx < 2 * NUMBER_OF_COLUMNS {
-                let idx_col = idx / 2;
-                debug!("Absorbing the accumulator for the column index {idx_col}. After this, there will still be {} elements to absorb", NUMBER_OF_VALUES_TO_ABSORB_PUBLIC_IO - idx - 1);
-                if self.current_iteration % 2 == 0 {
-                    let (pt_x, pt_y) = self.ivc_accumulator_e2[idx_col]
-                        .get_first_chunk()
-                        .to_coordinates()
-                        .unwrap();
-                    if idx % 2 == 0 {
-                        self.write_public_input(pos, pt_x.to_biguint().into())
-                    } else {
-                        self.write_public_input(pos, pt_y.to_biguint().into())
-                    }
-                } else {
-                    let (pt_x, pt_y) = self.ivc_accumulator_e1[idx_col]
-                        .get_first_chunk()
-                        .to_coordinates()
-                        .unwrap();
-                    if idx % 2 == 0 {
-                        self.write_public_input(pos, pt_x.to_biguint().into())
-                    } else {
-                        self.write_public_input(pos, pt_y.to_biguint().into())
-                    }
-                }
+            let value: Option<BigInt> = if self.current_iteration % 2 == 0 {
+                setup::values_to_absorb(
+                    &self.ivc_accumulator_e2,
+                    &self.z0,
+                    &self.zi,
+                    self.current_iteration,
+                )
+                .expect("The accumulators are not the point at infinity")
+                .get(idx)
+                .map(|x| x.to_biguint().into())
             } else {
-                unimplemented!(
-                    "We only absorb the accumulators for now. Of course, this is not sound."
+                setup::values_to_absorb(
+                    &self.ivc_accumulator_e1,
+                    &self.z0,
+                    &self.zi,
+                    self.current_iteration,
                 )
+                .expect("The accumulators are not the point at infinity")
+                .get(idx)
+                .map(|x| x.to_biguint().into())
             };
+            debug!("Absorbing the value {idx} of the public IO. After this, there will still be {} elements to absorb", NUMBER_OF_VALUES_TO_ABSORB_PUBLIC_IO.saturating_sub(idx + 1));
+            let res = self.write_public_input(pos, value.unwrap_or(BigInt::from(0_usize)));
             self.idx_values_to_absorb += 1;
             res
         }
@@ -544,7 +571,7 @@ where
                                 let pt = self.srs_e1.h;
                                 let (pt_x, pt_y) = pt.to_coordinates().unwrap();
                                 let pt_x = self.write_column(pos_x, pt_x.to_biguint().into());
-                                let pt_y = self.write_column(pos_x, pt_y.to_biguint().into());
+                                let pt_y = self.write_column(pos_y, pt_y.to_biguint().into());
                                 (pt_x, pt_y)
                             }
                         }
@@ -813,6 +840,10 @@ impl<
             (0..NUMBER_OF_COLUMNS).for_each(|_| witness.push(vec.clone()));
         };
 
+        let public_inputs: Vec<Vec<BigInt>> = (0..NUMBER_OF_PUBLIC_INPUTS)
+            .map(|_| vec![BigInt::from(0_usize); srs_size])
+            .collect();
+
         let mut selectors: Vec<Vec<bool>> = Vec::with_capacity(NUMBER_OF_SELECTORS);
         {
             let mut vec: Vec<bool> = Vec::with_capacity(srs_size);
@@ -881,10 +912,12 @@ impl<
             // Used to allocate variables
             // Witness builder related
             witness,
+            public_inputs,
             // ------
             // Inputs
             z0: z0.clone(),
-            zi: z0,
+            zi: z0.clone(),
+            output: z0,
             // ------
             _marker: std::marker::PhantomData,
         }
@@ -898,6 +931,12 @@ impl<
         self.idx_var = 0;
         self.current_instruction = IVC_STARTING_INSTRUCTION;
         self.idx_values_to_absorb = 0;
+        // The output of the application is the input of the next iteration
+        self.zi = self.output.clone();
+        // The selectors are set again while building the next witness
+        self.selectors
+            .iter_mut()
+            .for_each(|selector| selector.iter_mut().for_each(|s| *s = false));
     }
 
     /// The blinder used to commit, to avoid committing to the zero polynomial
@@ -945,11 +984,21 @@ impl<
         }
     }
 
-    /// Compute the output of the application on the previous output
-    // TODO: we should compute the hash of the previous commitments, only on
-    // CPU?
+    /// Set the output of the application at the current iteration, computed by
+    /// its last row, i.e. the row before the current one, and write it in the
+    /// public inputs of the row, see [crate::interpreter::run_app].
+    ///
+    /// It must be called once all the rows of the application have been
+    /// built, and before building the IVC circuit.
     pub fn compute_output(&mut self) {
-        self.zi = BigInt::from(42_usize)
+        assert!(
+            self.current_row > 0,
+            "The application must be run before computing its output"
+        );
+        let last_row = self.current_row - 1;
+        self.output = self.witness[1][last_row].clone();
+        self.public_inputs[AppPublicInput::Output as usize][last_row] = self.output.clone();
+        self.public_inputs[AppPublicInput::IsLastRow as usize][last_row] = BigInt::from(1_usize);
     }
 
     pub fn fetch_instruction(&self) -> Instruction {
@@ -1013,40 +1062,6 @@ impl<
     /// hash' = H(i + 1, acc'_1, ..., acc'_17, z_0, z_(i + 1))
     /// ```
     pub fn fetch_next_instruction(&mut self) -> Instruction {
-        match self.current_instruction {
-            Instruction::Poseidon(i) => {
-                if i < POSEIDON_ROUNDS_FULL - 5 {
-                    Instruction::Poseidon(i + 5)
-                } else {
-                    // FIXME: we continue absorbing
-                    Instruction::Poseidon(0)
-                }
-            }
-            Instruction::EllipticCurveScaling(i_comm, bit) => {
-                // TODO: we still need to substract (or not?) the blinder.
-                // Maybe we can avoid this by aggregating them.
-                // TODO: we also need to aggregate the cross-terms.
-                // Therefore i_comm must also take into the account the number
-                // of cross-terms.
-                assert!(i_comm < NUMBER_OF_COLUMNS, "Maximum number of columns reached ({NUMBER_OF_COLUMNS}), increase the number of columns");
-                assert!(bit < MAXIMUM_FIELD_SIZE_IN_BITS, "Maximum number of bits reached ({MAXIMUM_FIELD_SIZE_IN_BITS}), increase the number of bits");
-                if bit < MAXIMUM_FIELD_SIZE_IN_BITS - 1 {
-                    Instruction::EllipticCurveScaling(i_comm, bit + 1)
-                } else if i_comm < NUMBER_OF_COLUMNS - 1 {
-                    Instruction::EllipticCurveScaling(i_comm + 1, 0)
-                } else {
-                    // We have computed all the bits for all the columns
-                    Instruction::NoOp
-                }
-            }
-            Instruction::EllipticCurveAddition(i_comm) => {
-                if i_comm < NUMBER_OF_COLUMNS - 1 {
-                    Instruction::EllipticCurveAddition(i_comm + 1)
-                } else {
-                    Instruction::NoOp
-                }
-            }
-            Instruction::NoOp => Instruction::NoOp,
-        }
+        next_instruction(self.current_instruction, self.idx_values_to_absorb)
     }
 }
@@ -0,0 +1,248 @@
+use arrabbiata::{
+    folding::{self, Accumulators},
+    interpreter::{self, InterpreterEnv},
+    proof::{LastInstanceProof, Proof},
+    prover, setup, verifier,
+    witness::Env,
+    POSEIDON_STATE_SIZE,
+};
+use mina_curves::pasta::{Fp, Fq, Pallas, PallasParameters, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use num_bigint::BigInt;
+use o1_utils::field_helpers::FieldHelpers;
+
+type VestaBaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type VestaScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+type PallasBaseSponge = DefaultFqSponge<PallasParameters, PlonkSpongeConstantsKimchi>;
+type PallasScalarSponge = DefaultFrSponge<Fq, PlonkSpongeConstantsKimchi>;
+
+// The IVC circuit requires a bit less than 2^12 rows. We use the remaining rows
+// for the application.
+const SRS_LOG2_SIZE: usize = 12;
+const N_APP_ROWS: usize = 16;
+
+type Accs = Accumulators<Vesta, Pallas>;
+
+fn build_witness(env: &mut Env<Fp, Fq, Vesta, Pallas>) {
+    let domain_size = 1 << SRS_LOG2_SIZE;
+    for _i in 0..N_APP_ROWS {
+        interpreter::run_app(env);
+        env.reset();
+    }
+    env.compute_output();
+    while env.current_row < domain_size {
+        let instr = env.fetch_instruction();
+        interpreter::run_ivc(env, instr);
+        env.current_instruction = env.fetch_next_instruction();
+        env.reset();
+    }
+    env.compute_and_update_previous_commitments();
+}
+
+fn accumulate(env: &mut Env<Fp, Fq, Vesta, Pallas>, accumulators: &mut Accs) {
+    folding::accumulate::<_, _, _, _, VestaBaseSponge, PallasBaseSponge>(env, accumulators)
+}
+
+fn prove(env: &Env<Fp, Fq, Vesta, Pallas>, accumulators: &Accs) -> Proof<Vesta, Pallas> {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    prover::prove::<
+        Fp,
+        Fq,
+        Vesta,
+        Pallas,
+        VestaBaseSponge,
+        VestaScalarSponge,
+        PallasBaseSponge,
+        PallasScalarSponge,
+        _,
+    >(env, accumulators, &mut rng)
+    .unwrap()
+}
+
+fn verify(env: &Env<Fp, Fq, Vesta, Pallas>, proof: &Proof<Vesta, Pallas>) -> bool {
+    verify_with_app_rows(env, N_APP_ROWS, proof)
+}
+
+fn verify_with_app_rows(
+    env: &Env<Fp, Fq, Vesta, Pallas>,
+    n_app_rows: usize,
+    proof: &Proof<Vesta, Pallas>,
+) -> bool {
+    verifier::verify::<
+        Fp,
+        Fq,
+        Vesta,
+        Pallas,
+        VestaBaseSponge,
+        VestaScalarSponge,
+        PallasBaseSponge,
+        PallasScalarSponge,
+    >(
+        env.domain_fp,
+        env.domain_fq,
+        &env.srs_e1,
+        &env.srs_e2,
+        n_app_rows,
+        proof,
+    )
+}
+
+#[test]
+fn test_setup_matches_the_witness() {
+    let sponge: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
+        SRS_LOG2_SIZE,
+        BigInt::from(2u64),
+        sponge.clone(),
+        sponge.clone(),
+    );
+    build_witness(&mut env);
+
+    let domain_size = 1 << SRS_LOG2_SIZE;
+    assert_eq!(env.selectors, setup::selectors(domain_size, N_APP_ROWS));
+
+    let values_to_absorb =
+        setup::values_to_absorb(&env.ivc_accumulator_e2, &env.z0, &env.zi, 0).unwrap();
+    let public_inputs: Vec<Vec<Fp>> = env
+        .public_inputs
+        .iter()
+        .map(|column| {
+            column
+                .iter()
+                .map(|x| Fp::from_biguint(&x.to_biguint().unwrap()).unwrap())
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        public_inputs,
+        setup::public_inputs::<Fp>(
+            domain_size,
+            N_APP_ROWS,
+            0,
+            &values_to_absorb,
+            setup::to_field(&env.zi),
+            setup::to_field(&env.output),
+        )
+    );
+
+    // The application squares its input on each of its rows
+    let output = (0..N_APP_ROWS).fold(env.zi.clone(), |x, _| x.clone() * x);
+    assert_eq!(setup::to_field::<Fp>(&env.output), setup::to_field(&output));
+}
+
+#[test]
+fn heavy_test_prove_and_verify_two_iterations() {
+    let sponge: [BigInt; POSEIDON_STATE_SIZE] = std::array::from_fn(|_i| BigInt::from(42u64));
+    let mut env = Env::<Fp, Fq, Vesta, Pallas>::new(
+        SRS_LOG2_SIZE,
+        BigInt::from(2u64),
+        sponge.clone(),
+        sponge.clone(),
+    );
+    let mut accumulators = Accs::default();
+
+    // First iteration, over the first curve
+    build_witness(&mut env);
+    accumulate(&mut env, &mut accumulators);
+    let proof = prove(&env, &accumulators);
+    assert!(matches!(proof.last_instance, LastInstanceProof::E1(_)));
+    assert!(proof.accumulator_e2.is_none());
+    assert!(
+        verify(&env, &proof),
+        "The proof of the first iteration is invalid"
+    );
+
+    // The proof can be published and verified by another process
+    let serialized = rmp_serde::to_vec(&proof).unwrap();
+    let deserialized: Proof<Vesta, Pallas> = rmp_serde::from_slice(&serialized).unwrap();
+    assert!(verify(&env, &deserialized));
+
+    // Tampering with an evaluation makes the proof invalid
+    {
+        let mut tampered = proof.clone();
+        let LastInstanceProof::E1(instance) = &mut tampered.last_instance else {
+            unreachable!()
+        };
+        instance.evaluations.witness[0].zeta[0] += Fp::from(1u64);
+        assert!(!verify(&env, &tampered));
+    }
+
+    // Claiming a different iteration makes the proof invalid
+    {
+        let mut tampered = proof.clone();
+        tampered.iteration += 1;
+        assert!(!verify(&env, &tampered));
+    }
+
+    // The inputs/outputs are bound to the instances by the public inputs
+    {
+        let mut tampered = proof.clone();
+        tampered.z0 += 1;
+        assert!(!verify(&env, &tampered));
+    }
+    {
+        let mut tampered = proof.clone();
+        tampered.zi += 1;
+        assert!(!verify(&env, &tampered));
+    }
+
+    // The selectors are computed by the verifier from the layout of the
+    // circuit
+    assert!(!verify_with_app_rows(&env, N_APP_ROWS + 1, &proof));
+
+    // Tampering with a folding step makes the replay of the folding fail
+    {
+        let mut tampered = proof.clone();
+        tampered.folding_e1[0].t_0 = tampered.folding_e1[0].t_1.clone();
+        assert!(!verify(&env, &tampered));
+    }
+
+    // Tampering with the accumulated instance makes the verification fail
+    {
+        let mut tampered = proof.clone();
+        tampered.accumulator_e1.instance.u += Fp::from(1u64);
+        assert!(!verify(&env, &tampered));
+    }
+    {
+        let mut tampered = proof.clone();
+        tampered.accumulator_e1.decider.evaluations.error.zeta[0] += Fp::from(1u64);
+        assert!(!verify(&env, &tampered));
+    }
+
+    env.reset_for_next_iteration();
+    env.current_iteration += 1;
+
+    // Second iteration, over the second curve
+    build_witness(&mut env);
+    accumulate(&mut env, &mut accumulators);
+    let proof = prove(&env, &accumulators);
+    assert!(matches!(proof.last_instance, LastInstanceProof::E2(_)));
+    assert!(proof.accumulator_e2.is_some());
+    assert!(
+        verify(&env, &proof),
+        "The proof of the second iteration is invalid"
+    );
+
+    // The accumulator over the first curve is absorbed by the last instance
+    {
+        let mut tampered = proof.clone();
+        tampered.accumulator_e2 = None;
+        assert!(!verify(&env, &tampered));
+    }
+
+    // The output of the first iteration is the input of the second one
+    assert_eq!(proof.intermediate_outputs.len(), 1);
+    {
+        let mut tampered = proof.clone();
+        tampered.intermediate_outputs[0] += 1;
+        assert!(!verify(&env, &tampered));
+    }
+    {
+        let mut tampered = proof.clone();
+        tampered.folding_e1.clear();
+        assert!(!verify(&env, &tampered));
+    }
+}
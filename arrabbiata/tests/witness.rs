@@ -209,8 +209,9 @@ where
         env.reset();
     });
 
-    let res_x: BigInt = env.state[0].clone();
-    let res_y: BigInt = env.state[1].clone();
+    // The result is written on the row following the last bit
+    let res_x: BigInt = env.witness[0][env.current_row - 1].clone();
+    let res_y: BigInt = env.witness[1][env.current_row - 1].clone();
 
     let p1_proj: ProjectivePallas = p1.into();
     // @volhovm TODO check if mul_bigint is what was intended
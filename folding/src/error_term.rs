@@ -433,7 +433,16 @@ impl<CF: FoldingConfig> ExtendedEnv<CF> {
 
         // FIXME: use parallelisation
         let blinder = PolyComm::new(vec![relaxed_instance.blinder]);
-        for (expected_i, (i, wit)) in relaxed_witness.extended_witness.extended.iter().enumerate() {
+        // The commitments of an instance that has already been extended (e.g.
+        // an accumulator) are kept, only the missing ones are computed.
+        let n_committed = relaxed_instance.extended_instance.extended.len();
+        for (expected_i, (i, wit)) in relaxed_witness
+            .extended_witness
+            .extended
+            .iter()
+            .enumerate()
+            .skip(n_committed)
+        {
             // in case any where to be missing for some reason
             assert_eq!(*i, expected_i);
             // Blinding the commitments to support the case the witness is zero.
@@ -679,6 +679,46 @@ impl<C: FoldingConfig> FoldingCompatibleExpr<C> {
     }
 }
 
+impl<C: FoldingConfig> FoldingCompatibleExpr<C> {
+    /// Return one more than the largest index returned by `index` on the
+    /// extensions of the expression, or zero if there is none.
+    fn count_extensions(&self, index: &impl Fn(&ExpExtension<C>) -> Option<usize>) -> usize {
+        match self {
+            FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Extensions(ext)) => {
+                index(ext).map_or(0, |i| i + 1)
+            }
+            FoldingCompatibleExpr::Atom(_) => 0,
+            FoldingCompatibleExpr::Pow(e, _)
+            | FoldingCompatibleExpr::Double(e)
+            | FoldingCompatibleExpr::Square(e) => e.count_extensions(index),
+            FoldingCompatibleExpr::Add(e1, e2)
+            | FoldingCompatibleExpr::Sub(e1, e2)
+            | FoldingCompatibleExpr::Mul(e1, e2) => {
+                e1.count_extensions(index).max(e2.count_extensions(index))
+            }
+        }
+    }
+
+    /// Return the number of alphas used to combine the constraints of the
+    /// expression, i.e. the number of alphas each instance must carry. It is
+    /// meant to be called on the final expression of the folding scheme.
+    pub fn number_of_alphas(&self) -> usize {
+        self.count_extensions(&|ext| match ext {
+            ExpExtension::Alpha(i) => Some(*i),
+            _ => None,
+        })
+    }
+
+    /// Return the number of columns added by quadraticization used by the
+    /// expression.
+    pub fn number_of_extended_columns(&self) -> usize {
+        self.count_extensions(&|ext| match ext {
+            ExpExtension::ExtendedWitness(i) => Some(*i),
+            _ => None,
+        })
+    }
+}
+
 impl<C: FoldingConfig> FoldingExp<C> {
     /// Compute the degree of a folding expression.
     /// Only constants are of degree `0`, the rest is of degree `1`.
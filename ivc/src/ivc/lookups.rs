@@ -103,6 +103,9 @@ impl<Ff> MPrism for IVCFECLookupLens<Ff> {
             feclookup::LookupTable::RangeCheckFfHighest(p) => {
                 IVCLookupTable::SerLookupTable(serlookup::LookupTable::RangeCheckFfHighest(p))
             }
+            feclookup::LookupTable::ScalarMulBus => {
+                panic!("The IVC circuit does not use the FEC scalar multiplication bus")
+            }
         }
     }
 }
@@ -285,6 +285,7 @@ pub fn heavy_test_simple_add() {
                 c @ LookupPartialSum(_) => c,
                 c @ LookupMultiplicity(_) => c,
                 c @ LookupFixedTable(_) => c,
+                c @ LookupRuntimeTable(_) => c,
                 c @ LookupAggregation => c,
             };
             Variable { col: new_col, row }
@@ -109,7 +109,7 @@ impl BenchmarkCtx {
                 public_input: public,
             })
             .collect();
-        batch_verify::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+        batch_verify::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>, COLUMNS>(
             &self.group_map,
             &batch,
         )
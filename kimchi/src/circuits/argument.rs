@@ -16,7 +16,6 @@ use super::{
     berkeley_columns::{BerkeleyChallengeTerm, BerkeleyChallenges},
     expr::{constraints::ExprOps, Cache, ConstantExpr, ConstantTerm, Constants},
     gate::{CurrOrNext, GateType},
-    polynomial::COLUMNS,
 };
 use CurrOrNext::{Curr, Next};
 
@@ -155,12 +154,13 @@ pub struct ArgumentData<F: 'static> {
     pub challenges: BerkeleyChallenges<F>,
 }
 
-/// Witness data for a argument
+/// Witness data for a argument. Each row holds one cell per witness column,
+/// which are more than [COLUMNS](super::wires::COLUMNS) for the Keccak gates.
 pub struct ArgumentWitness<T> {
     /// Witness for current row
-    pub curr: [T; COLUMNS],
+    pub curr: Vec<T>,
     /// Witness for next row
-    pub next: [T; COLUMNS],
+    pub next: Vec<T>,
 }
 
 impl<T> std::ops::Index<(CurrOrNext, usize)> for ArgumentWitness<T> {
@@ -164,7 +164,7 @@ impl FormattedOutput for Column {
     }
 }
 
-impl<F: Copy> ColumnEvaluations<F> for ProofEvaluations<PointEvaluations<F>> {
+impl<F: Copy, const W: usize> ColumnEvaluations<F> for ProofEvaluations<PointEvaluations<F>, W> {
     type Column = Column;
     fn evaluate(&self, col: Self::Column) -> Result<PointEvaluations<F>, ExprError<Self::Column>> {
         use Column::*;
@@ -205,6 +205,24 @@ impl<F: Copy> ColumnEvaluations<F> for ProofEvaluations<PointEvaluations<F>> {
             Index(GateType::Rot64) => self
                 .rot_selector
                 .ok_or(ExprError::MissingIndexEvaluation(col)),
+            Index(GateType::CairoClaim) => self
+                .cairo_claim_selector
+                .ok_or(ExprError::MissingIndexEvaluation(col)),
+            Index(GateType::CairoInstruction) => self
+                .cairo_instruction_selector
+                .ok_or(ExprError::MissingIndexEvaluation(col)),
+            Index(GateType::CairoFlags) => self
+                .cairo_flags_selector
+                .ok_or(ExprError::MissingIndexEvaluation(col)),
+            Index(GateType::CairoTransition) => self
+                .cairo_transition_selector
+                .ok_or(ExprError::MissingIndexEvaluation(col)),
+            Index(GateType::KeccakRound) => self
+                .keccak_round_selector
+                .ok_or(ExprError::MissingIndexEvaluation(col)),
+            Index(GateType::KeccakSponge) => self
+                .keccak_sponge_selector
+                .ok_or(ExprError::MissingIndexEvaluation(col)),
             Permutation(i) => Ok(self.s[i]),
             Coefficient(i) => Ok(self.coefficients[i]),
             LookupKindIndex(LookupPattern::Xor) => self
@@ -219,6 +237,9 @@ impl<F: Copy> ColumnEvaluations<F> for ProofEvaluations<PointEvaluations<F>> {
             LookupKindIndex(LookupPattern::ForeignFieldMul) => self
                 .foreign_field_mul_lookup_selector
                 .ok_or(ExprError::MissingIndexEvaluation(col)),
+            LookupKindIndex(LookupPattern::CairoMemory) => self
+                .cairo_memory_lookup_selector
+                .ok_or(ExprError::MissingIndexEvaluation(col)),
             LookupRuntimeSelector => self
                 .runtime_lookup_table_selector
                 .ok_or(ExprError::MissingIndexEvaluation(col)),
@@ -227,8 +248,9 @@ impl<F: Copy> ColumnEvaluations<F> for ProofEvaluations<PointEvaluations<F>> {
     }
 }
 
-impl<'a, F: FftField> ColumnEnvironment<'a, F, BerkeleyChallengeTerm, BerkeleyChallenges<F>>
-    for Environment<'a, F>
+impl<'a, F: FftField, const W: usize>
+    ColumnEnvironment<'a, F, BerkeleyChallengeTerm, BerkeleyChallenges<F>>
+    for Environment<'a, F, W>
 {
     type Column = Column;
 
@@ -309,11 +331,11 @@ pub struct LookupEnvironment<'a, F: FftField> {
 /// required to evaluate an expression as a polynomial.
 ///
 /// All are evaluations.
-pub struct Environment<'a, F: FftField> {
+pub struct Environment<'a, F: FftField, const W: usize = COLUMNS> {
     /// The witness column polynomials
-    pub witness: &'a [Evaluations<F, D<F>>; COLUMNS],
+    pub witness: &'a [Evaluations<F, D<F>>; W],
     /// The coefficient column polynomials
-    pub coefficient: &'a [Evaluations<F, D<F>>; COLUMNS],
+    pub coefficient: &'a [Evaluations<F, D<F>>; W],
     /// The polynomial that vanishes on the zero-knowledge rows and the row before.
     pub vanishes_on_zero_knowledge_and_previous_rows: &'a Evaluations<F, D<F>>,
     /// The permutation aggregation polynomial.
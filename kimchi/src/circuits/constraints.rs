@@ -13,6 +13,7 @@ use crate::{
         polynomial::{WitnessEvals, WitnessOverDomains, WitnessShifts},
         polynomials::permutation::Shifts,
         wires::*,
+        witness_report::{FailureKind, WitnessFailure, WitnessReport},
     },
     curve::KimchiCurve,
     error::{DomainCreationError, SetupError},
@@ -54,6 +55,10 @@ pub struct FeatureFlags {
     pub xor: bool,
     /// ROT gate
     pub rot: bool,
+    /// Cairo gates
+    pub cairo: bool,
+    /// Keccak gates
+    pub keccak: bool,
     /// Lookup features
     pub lookup_features: LookupFeatures,
 }
@@ -70,6 +75,7 @@ impl Default for FeatureFlags {
                     lookup: false,
                     range_check: false,
                     foreign_field_mul: false,
+                    cairo_memory: false,
                 },
                 joint_lookup_used: false,
                 uses_runtime_tables: false,
@@ -78,6 +84,8 @@ impl Default for FeatureFlags {
             foreign_field_mul: false,
             xor: false,
             rot: false,
+            cairo: false,
+            keccak: false,
         }
     }
 }
@@ -85,14 +93,14 @@ impl Default for FeatureFlags {
 /// The polynomials representing evaluated columns, in coefficient form.
 #[serde_as]
 #[derive(Clone, Serialize, Deserialize, Debug)]
-pub struct EvaluatedColumnCoefficients<F: PrimeField> {
+pub struct EvaluatedColumnCoefficients<F: PrimeField, const W: usize = COLUMNS> {
     /// permutation coefficients
     #[serde_as(as = "[o1_utils::serialization::SerdeAs; PERMUTS]")]
     pub permutation_coefficients: [DP<F>; PERMUTS],
 
     /// gate coefficients
-    #[serde_as(as = "[o1_utils::serialization::SerdeAs; COLUMNS]")]
-    pub coefficients: [DP<F>; COLUMNS],
+    #[serde_as(as = "[o1_utils::serialization::SerdeAs; W]")]
+    pub coefficients: [DP<F>; W],
 
     /// generic gate selector
     #[serde_as(as = "o1_utils::serialization::SerdeAs")]
@@ -107,14 +115,14 @@ pub struct EvaluatedColumnCoefficients<F: PrimeField> {
 /// The evaluations are expanded to the domain size required for their constraints.
 #[serde_as]
 #[derive(Clone, Serialize, Deserialize, Debug)]
-pub struct ColumnEvaluations<F: PrimeField> {
+pub struct ColumnEvaluations<F: PrimeField, const W: usize = COLUMNS> {
     /// permutation coefficients over domain d8
     #[serde_as(as = "[o1_utils::serialization::SerdeAs; PERMUTS]")]
     pub permutation_coefficients8: [E<F, D<F>>; PERMUTS],
 
     /// coefficients over domain d8
-    #[serde_as(as = "[o1_utils::serialization::SerdeAs; COLUMNS]")]
-    pub coefficients8: [E<F, D<F>>; COLUMNS],
+    #[serde_as(as = "[o1_utils::serialization::SerdeAs; W]")]
+    pub coefficients8: [E<F, D<F>>; W],
 
     /// generic selector over domain d4
     #[serde_as(as = "o1_utils::serialization::SerdeAs")]
@@ -163,6 +171,30 @@ pub struct ColumnEvaluations<F: PrimeField> {
     /// Rot gate selector over domain d8
     #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
     pub rot_selector8: Option<E<F, D<F>>>,
+
+    /// Cairo claim gate selector over domain d8
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub cairo_claim_selector8: Option<E<F, D<F>>>,
+
+    /// Cairo instruction gate selector over domain d8
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub cairo_instruction_selector8: Option<E<F, D<F>>>,
+
+    /// Cairo flags gate selector over domain d8
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub cairo_flags_selector8: Option<E<F, D<F>>>,
+
+    /// Cairo transition gate selector over domain d8
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub cairo_transition_selector8: Option<E<F, D<F>>>,
+
+    /// Keccak round gate selector over domain d8
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub keccak_round_selector8: Option<E<F, D<F>>>,
+
+    /// Keccak sponge gate selector over domain d8
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub keccak_sponge_selector8: Option<E<F, D<F>>>,
 }
 
 #[serde_as]
@@ -315,17 +347,21 @@ impl<F: PrimeField> ConstraintSystem<F> {
     }
 }
 
-impl<F: PrimeField, G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>
-    ProverIndex<G, OpeningProof>
+impl<
+        F: PrimeField,
+        G: KimchiCurve<ScalarField = F>,
+        OpeningProof: OpenProof<G>,
+        const W: usize,
+    > ProverIndex<G, OpeningProof, W>
 {
     /// This function verifies the consistency of the wire
     /// assignments (witness) against the constraints
     ///     witness: wire assignment witness
     ///     RETURN: verification status
-    pub fn verify(&self, witness: &[Vec<F>; COLUMNS], public: &[F]) -> Result<(), GateError> {
+    pub fn verify(&self, witness: &[Vec<F>; W], public: &[F]) -> Result<(), GateError> {
         // pad the witness
         let pad = vec![F::zero(); self.cs.domain.d1.size() - witness[0].len()];
-        let witness: [Vec<F>; COLUMNS] = array::from_fn(|i| {
+        let witness: [Vec<F>; W] = array::from_fn(|i| {
             let mut w = witness[i].to_vec();
             w.extend_from_slice(&pad);
             w
@@ -370,15 +406,94 @@ impl<F: PrimeField, G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>
     }
 }
 
+impl<F: PrimeField, G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>
+    ProverIndex<G, OpeningProof>
+{
+    /// Same as [ConstraintSystem::check_witness], with the constraint system
+    /// of the index.
+    pub fn check_witness(&self, witness: &[Vec<F>; COLUMNS], public: &[F]) -> WitnessReport<F> {
+        self.cs.check_witness::<G>(witness, public)
+    }
+}
+
+impl<F: PrimeField> ConstraintSystem<F> {
+    /// Checks the witness against every wire and every gate of the circuit,
+    /// and reports all the failures instead of stopping at the first one like
+    /// [ProverIndex::verify]. This is meant for debugging circuits.
+    ///
+    /// Unlike [ProverIndex::verify], the gates are checked with the same
+    /// constraints as the prover, see [CircuitGate::constraint_evaluations].
+    /// Lookups are not checked.
+    pub fn check_witness<G: KimchiCurve<ScalarField = F>>(
+        &self,
+        witness: &[Vec<F>; COLUMNS],
+        public: &[F],
+    ) -> WitnessReport<F> {
+        // pad the witness
+        let pad = vec![F::zero(); self.domain.d1.size() - witness[0].len()];
+        let witness: [Vec<F>; COLUMNS] = array::from_fn(|i| {
+            let mut w = witness[i].to_vec();
+            w.extend_from_slice(&pad);
+            w
+        });
+
+        let mut failures = vec![];
+        for (row, gate) in self.gates.iter().enumerate() {
+            let mut report = |kind| {
+                failures.push(WitnessFailure {
+                    row,
+                    typ: gate.typ,
+                    kind,
+                    curr: array::from_fn(|col| witness[col][row]),
+                    next: array::from_fn(|col| {
+                        witness[col].get(row + 1).copied().unwrap_or_else(F::zero)
+                    }),
+                    labels: vec![],
+                })
+            };
+
+            // check the wiring
+            for col in 0..PERMUTS {
+                let wire = gate.wires[col];
+                if wire.col >= PERMUTS {
+                    report(FailureKind::WireColumn(col));
+                } else if witness[col][row] != witness[wire.col][wire.row] {
+                    report(FailureKind::CopyConstraint {
+                        src: Wire { row, col },
+                        dst: wire,
+                    });
+                }
+            }
+
+            // for public gates, only the left wire is toggled
+            if row < self.public && gate.coeffs.first() != Some(&F::one()) {
+                report(FailureKind::IncorrectPublic);
+            }
+
+            // check every constraint of the gate
+            let evaluations = gate
+                .constraint_evaluations::<G>(row, &witness, self, public)
+                .expect("the witness has one column per register");
+            for (i, evaluation) in evaluations.iter().enumerate() {
+                if !evaluation.is_zero() {
+                    report(FailureKind::Constraint(i + 1));
+                }
+            }
+        }
+
+        WitnessReport { failures }
+    }
+}
+
 impl<F: PrimeField> ConstraintSystem<F> {
     /// evaluate witness polynomials over domains
-    pub fn evaluate(&self, w: &[DP<F>; COLUMNS], z: &DP<F>) -> WitnessOverDomains<F> {
+    pub fn evaluate<const W: usize>(&self, w: &[DP<F>; W], z: &DP<F>) -> WitnessOverDomains<F, W> {
         // compute shifted witness polynomials
-        let w8: [E<F, D<F>>; COLUMNS] =
+        let w8: [E<F, D<F>>; W] =
             array::from_fn(|i| w[i].evaluate_over_domain_by_ref(self.domain.d8));
         let z8 = z.evaluate_over_domain_by_ref(self.domain.d8);
 
-        let w4: [E<F, D<F>>; COLUMNS] = array::from_fn(|i| {
+        let w4: [E<F, D<F>>; W] = array::from_fn(|i| {
             E::<F, D<F>>::from_vec_and_domain(
                 (0..self.domain.d4.size)
                     .map(|j| w8[i].evals[2 * j as usize])
@@ -410,7 +525,9 @@ impl<F: PrimeField> ConstraintSystem<F> {
         }
     }
 
-    pub(crate) fn evaluated_column_coefficients(&self) -> EvaluatedColumnCoefficients<F> {
+    pub(crate) fn evaluated_column_coefficients<const W: usize>(
+        &self,
+    ) -> EvaluatedColumnCoefficients<F, W> {
         // compute permutation polynomials
         let shifts = Shifts::new(&self.domain.d1);
 
@@ -472,7 +589,7 @@ impl<F: PrimeField> ConstraintSystem<F> {
         .interpolate();
 
         // coefficient polynomial
-        let coefficients: [_; COLUMNS] = array::from_fn(|i| {
+        let coefficients: [_; W] = array::from_fn(|i| {
             let padded = self
                 .gates
                 .iter()
@@ -490,10 +607,10 @@ impl<F: PrimeField> ConstraintSystem<F> {
         }
     }
 
-    pub(crate) fn column_evaluations(
+    pub(crate) fn column_evaluations<const W: usize>(
         &self,
-        evaluated_column_coefficients: &EvaluatedColumnCoefficients<F>,
-    ) -> ColumnEvaluations<F> {
+        evaluated_column_coefficients: &EvaluatedColumnCoefficients<F, W>,
+    ) -> ColumnEvaluations<F, W> {
         let permutation_coefficients8 = array::from_fn(|i| {
             evaluated_column_coefficients.permutation_coefficients[i]
                 .evaluate_over_domain_by_ref(self.domain.d8)
@@ -628,6 +745,42 @@ impl<F: PrimeField> ConstraintSystem<F> {
             }
         };
 
+        // Cairo constraint selector polynomials
+        let cairo_selector8 = |typ| {
+            if !self.feature_flags.cairo {
+                None
+            } else {
+                Some(selector_polynomial(
+                    typ,
+                    &self.gates,
+                    &self.domain,
+                    &self.domain.d8,
+                    self.disable_gates_checks,
+                ))
+            }
+        };
+        let cairo_claim_selector8 = cairo_selector8(GateType::CairoClaim);
+        let cairo_instruction_selector8 = cairo_selector8(GateType::CairoInstruction);
+        let cairo_flags_selector8 = cairo_selector8(GateType::CairoFlags);
+        let cairo_transition_selector8 = cairo_selector8(GateType::CairoTransition);
+
+        // Keccak constraint selector polynomials
+        let keccak_selector8 = |typ| {
+            if !self.feature_flags.keccak {
+                None
+            } else {
+                Some(selector_polynomial(
+                    typ,
+                    &self.gates,
+                    &self.domain,
+                    &self.domain.d8,
+                    self.disable_gates_checks,
+                ))
+            }
+        };
+        let keccak_round_selector8 = keccak_selector8(GateType::KeccakRound);
+        let keccak_sponge_selector8 = keccak_selector8(GateType::KeccakSponge);
+
         // TODO: This doesn't need to be degree 8 but that would require some changes in expr
         let coefficients8 = array::from_fn(|i| {
             evaluated_column_coefficients.coefficients[i]
@@ -649,6 +802,12 @@ impl<F: PrimeField> ConstraintSystem<F> {
             foreign_field_mul_selector8,
             xor_selector8,
             rot_selector8,
+            cairo_claim_selector8,
+            cairo_instruction_selector8,
+            cairo_flags_selector8,
+            cairo_transition_selector8,
+            keccak_round_selector8,
+            keccak_sponge_selector8,
         }
     }
 }
@@ -686,6 +845,8 @@ impl FeatureFlags {
             foreign_field_mul: false,
             xor: false,
             rot: false,
+            cairo: false,
+            keccak: false,
         };
 
         for gate in gates {
@@ -696,6 +857,11 @@ impl FeatureFlags {
                 GateType::ForeignFieldMul => feature_flags.foreign_field_mul = true,
                 GateType::Xor16 => feature_flags.xor = true,
                 GateType::Rot64 => feature_flags.rot = true,
+                GateType::CairoClaim
+                | GateType::CairoInstruction
+                | GateType::CairoFlags
+                | GateType::CairoTransition => feature_flags.cairo = true,
+                GateType::KeccakRound | GateType::KeccakSponge => feature_flags.keccak = true,
                 _ => (),
             }
         }
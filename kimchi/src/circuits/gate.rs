@@ -6,7 +6,9 @@ use crate::{
         berkeley_columns::BerkeleyChallenges,
         constraints::ConstraintSystem,
         polynomials::{
-            complete_add, endomul_scalar, endosclmul, foreign_field_add, foreign_field_mul, keccak,
+            complete_add, endomul_scalar, endosclmul, foreign_field_add, foreign_field_mul,
+            generic::{self, DOUBLE_GENERIC_COEFFS},
+            keccak::{self, constants::KECCAK_COLS},
             poseidon, range_check, rot, turshi, varbasemul, xor,
         },
         wires::*,
@@ -174,11 +176,28 @@ impl<F: PrimeField> CircuitGate<F> {
     /// # Errors
     ///
     /// Will give error if verify process returns error.
-    pub fn verify<G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>(
+    pub fn verify<G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>, const W: usize>(
         &self,
         row: usize,
-        witness: &[Vec<F>; COLUMNS],
-        index: &ProverIndex<G, OpeningProof>,
+        witness: &[Vec<F>],
+        index: &ProverIndex<G, OpeningProof, W>,
+        public: &[F],
+    ) -> Result<(), String> {
+        self.verify_with_cs::<G>(row, witness, &index.cs, public)
+    }
+
+    /// Same as [CircuitGate::verify], with the constraint system of the
+    /// circuit instead of its prover index. The witness can have more than
+    /// [COLUMNS] columns, as for the Keccak gates.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if verify process returns error.
+    pub fn verify_with_cs<G: KimchiCurve<ScalarField = F>>(
+        &self,
+        row: usize,
+        witness: &[Vec<F>],
+        cs: &ConstraintSystem<F>,
         public: &[F],
     ) -> Result<(), String> {
         use GateType::*;
@@ -188,34 +207,34 @@ impl<F: PrimeField> CircuitGate<F> {
             Poseidon => self.verify_poseidon::<G>(row, witness),
             CompleteAdd => self.verify_complete_add(row, witness),
             VarBaseMul => self.verify_vbmul(row, witness),
-            EndoMul => self.verify_endomul::<G>(row, witness, &index.cs),
-            EndoMulScalar => self.verify_endomul_scalar::<G>(row, witness, &index.cs),
+            EndoMul => self.verify_endomul::<G>(row, witness, cs),
+            EndoMulScalar => self.verify_endomul_scalar::<G>(row, witness, cs),
             // TODO: implement the verification for the lookup gate
             // See https://github.com/MinaProtocol/mina/issues/14011
             Lookup => Ok(()),
-            CairoClaim | CairoInstruction | CairoFlags | CairoTransition => {
-                self.verify_cairo_gate::<G>(row, witness, &index.cs)
-            }
+            CairoClaim | CairoInstruction | CairoFlags | CairoTransition => self
+                .verify_witness::<G>(row, witness, cs, public)
+                .map_err(|e| e.to_string()),
             RangeCheck0 | RangeCheck1 => self
-                .verify_witness::<G>(row, witness, &index.cs, public)
+                .verify_witness::<G>(row, witness, cs, public)
                 .map_err(|e| e.to_string()),
             ForeignFieldAdd => self
-                .verify_witness::<G>(row, witness, &index.cs, public)
+                .verify_witness::<G>(row, witness, cs, public)
                 .map_err(|e| e.to_string()),
             ForeignFieldMul => self
-                .verify_witness::<G>(row, witness, &index.cs, public)
+                .verify_witness::<G>(row, witness, cs, public)
                 .map_err(|e| e.to_string()),
             Xor16 => self
-                .verify_witness::<G>(row, witness, &index.cs, public)
+                .verify_witness::<G>(row, witness, cs, public)
                 .map_err(|e| e.to_string()),
             Rot64 => self
-                .verify_witness::<G>(row, witness, &index.cs, public)
+                .verify_witness::<G>(row, witness, cs, public)
                 .map_err(|e| e.to_string()),
             KeccakRound => self
-                .verify_witness::<G>(row, witness, &index.cs, public)
+                .verify_witness::<G>(row, witness, cs, public)
                 .map_err(|e| e.to_string()),
             KeccakSponge => self
-                .verify_witness::<G>(row, witness, &index.cs, public)
+                .verify_witness::<G>(row, witness, cs, public)
                 .map_err(|e| e.to_string()),
         }
     }
@@ -224,34 +243,10 @@ impl<F: PrimeField> CircuitGate<F> {
     pub fn verify_witness<G: KimchiCurve<ScalarField = F>>(
         &self,
         row: usize,
-        witness: &[Vec<F>; COLUMNS],
+        witness: &[Vec<F>],
         cs: &ConstraintSystem<F>,
-        _public: &[F],
+        public: &[F],
     ) -> CircuitGateResult<()> {
-        // Grab the relevant part of the witness
-        let argument_witness = self.argument_witness(row, witness)?;
-        // Set up the constants.  Note that alpha, beta, gamma and joint_combiner
-        // are one because this function is not running the prover.
-        let constants = expr::Constants {
-            endo_coefficient: cs.endo,
-            mds: &G::sponge_params().mds,
-            zk_rows: cs.zk_rows,
-        };
-        //TODO : use generic challenges, since we do not need those here
-        let challenges = BerkeleyChallenges {
-            alpha: F::one(),
-            beta: F::one(),
-            gamma: F::one(),
-            joint_combiner: F::one(),
-        };
-        // Create the argument environment for the constraints over field elements
-        let env = ArgumentEnv::<F, F>::create(
-            argument_witness,
-            self.coeffs.clone(),
-            constants,
-            challenges,
-        );
-
         // Check the wiring (i.e. copy constraints) for this gate
         // Note: Gates can operated on row Curr or Curr and Next.
         //       It could be nice for gates to know this and then
@@ -274,16 +269,76 @@ impl<F: PrimeField> CircuitGate<F> {
             }
         }
 
-        let mut cache = expr::Cache::default();
+        if self.typ == GateType::Generic {
+            // TODO: implement the verification for the generic gate
+            return Ok(());
+        }
 
         // Perform witness verification on each constraint for this gate
+        let results = self.constraint_evaluations::<G>(row, witness, cs, public)?;
+
+        // Check for failed constraints
+        for (i, result) in results.iter().enumerate() {
+            if !result.is_zero() {
+                // Pinpoint failed constraint
+                return Err(CircuitGateError::Constraint(self.typ, i + 1));
+            }
+        }
+
+        // TODO: implement generic plookup witness verification
+
+        Ok(())
+    }
+
+    /// Evaluates the constraints of this gate on the witness at the given row,
+    /// without checking the wiring. The constraint `i + 1` reported by
+    /// [CircuitGateError::Constraint] holds iff the `i`-th evaluation is zero.
+    ///
+    /// Unlike [CircuitGate::verify_witness], the generic gate is evaluated too,
+    /// with the public input subtracted from the first generic gate of a row.
+    pub fn constraint_evaluations<G: KimchiCurve<ScalarField = F>>(
+        &self,
+        row: usize,
+        witness: &[Vec<F>],
+        cs: &ConstraintSystem<F>,
+        public: &[F],
+    ) -> CircuitGateResult<Vec<F>> {
+        // Grab the relevant part of the witness
+        let argument_witness = self.argument_witness(row, witness)?;
+        // Set up the constants.  Note that alpha, beta, gamma and joint_combiner
+        // are one because this function is not running the prover.
+        let constants = expr::Constants {
+            endo_coefficient: cs.endo,
+            mds: &G::sponge_params().mds,
+            zk_rows: cs.zk_rows,
+        };
+        //TODO : use generic challenges, since we do not need those here
+        let challenges = BerkeleyChallenges {
+            alpha: F::one(),
+            beta: F::one(),
+            gamma: F::one(),
+            joint_combiner: F::one(),
+        };
+        // Trailing zero coefficients of generic gates can be omitted
+        let mut coeffs = self.coeffs.clone();
+        if self.typ == GateType::Generic && coeffs.len() < DOUBLE_GENERIC_COEFFS {
+            coeffs.resize(DOUBLE_GENERIC_COEFFS, F::zero());
+        }
+        // Create the argument environment for the constraints over field elements
+        let env = ArgumentEnv::<F, F>::create(argument_witness, coeffs, constants, challenges);
+
+        let mut cache = expr::Cache::default();
+
         let results = match self.typ {
             GateType::Zero => {
                 vec![]
             }
             GateType::Generic => {
-                // TODO: implement the verification for the generic gate
-                vec![]
+                let mut results = generic::Generic::constraint_checks(&env, &mut cache);
+                if let Some(public) = public.get(row) {
+                    results[0] -= public;
+                }
+                results
             }
             GateType::Poseidon => poseidon::Poseidon::constraint_checks(&env, &mut cache),
             GateType::CompleteAdd => complete_add::CompleteAdd::constraint_checks(&env, &mut cache),
@@ -327,40 +382,28 @@ impl<F: PrimeField> CircuitGate<F> {
             }
         };
 
-        // Check for failed constraints
-        for (i, result) in results.iter().enumerate() {
-            if !result.is_zero() {
-                // Pinpoint failed constraint
-                return Err(CircuitGateError::Constraint(self.typ, i + 1));
-            }
-        }
-
-        // TODO: implement generic plookup witness verification
-
-        Ok(())
+        Ok(results)
     }
 
     // Return the part of the witness relevant to this gate at the given row offset
     fn argument_witness(
         &self,
         row: usize,
-        witness: &[Vec<F>; COLUMNS],
+        witness: &[Vec<F>],
     ) -> CircuitGateResult<ArgumentWitness<F>> {
         // Get the part of the witness relevant to this gate
-        let witness_curr: [F; COLUMNS] = (0..witness.len())
-            .map(|col| witness[col][row])
-            .collect::<Vec<F>>()
-            .try_into()
-            .map_err(|_| CircuitGateError::FailedToGetWitnessForRow(self.typ, row))?;
-        let witness_next: [F; COLUMNS] = if witness[0].len() > row + 1 {
-            (0..witness.len())
-                .map(|col| witness[col][row + 1])
-                .collect::<Vec<F>>()
-                .try_into()
-                .map_err(|_| CircuitGateError::FailedToGetWitnessForRow(self.typ, row))?
-        } else {
-            [F::zero(); COLUMNS]
+        let columns = match self.typ {
+            GateType::KeccakRound | GateType::KeccakSponge => KECCAK_COLS,
+            _ => COLUMNS,
         };
+        if witness.len() < columns || witness.iter().any(|col| col.len() <= row) {
+            return Err(CircuitGateError::FailedToGetWitnessForRow(self.typ, row));
+        }
+        let witness_curr: Vec<F> = witness.iter().map(|col| col[row]).collect();
+        let witness_next: Vec<F> = witness
+            .iter()
+            .map(|col| col.get(row + 1).copied().unwrap_or_else(F::zero))
+            .collect();
 
         Ok(ArgumentWitness::<F> {
             curr: witness_curr,
@@ -6,7 +6,6 @@ use crate::{
         lookup::lookups::{
             JointLookup, JointLookupSpec, JointLookupValue, LocalPosition, LookupInfo,
         },
-        wires::COLUMNS,
     },
     error::ProverError,
 };
@@ -89,7 +88,7 @@ pub fn sorted<F: PrimeField>(
     joint_lookup_table_d8: &Evaluations<F, D<F>>,
     d1: D<F>,
     gates: &[CircuitGate<F>],
-    witness: &[Vec<F>; COLUMNS],
+    witness: &[Vec<F>],
     joint_combiner: F,
     table_id_combiner: F,
     lookup_info: &LookupInfo,
@@ -231,7 +230,7 @@ pub fn aggregation<R, F>(
     joint_lookup_table_d8: &Evaluations<F, D<F>>,
     d1: D<F>,
     gates: &[CircuitGate<F>],
-    witness: &[Vec<F>; COLUMNS],
+    witness: &[Vec<F>],
     joint_combiner: &F,
     table_id_combiner: &F,
     beta: F,
@@ -689,7 +688,7 @@ pub fn verify<F: PrimeField, I: Iterator<Item = F>, TABLE: Fn() -> I>(
     lookup_table_entries: usize,
     d1: D<F>,
     gates: &[CircuitGate<F>],
-    witness: &[Vec<F>; COLUMNS],
+    witness: &[Vec<F>],
     joint_combiner: &F,
     table_id_combiner: &F,
     sorted: &[Evaluations<F, D<F>>],
@@ -47,6 +47,8 @@ pub struct LookupSelectors<T> {
     pub range_check: Option<T>,
     /// Foreign field multiplication pattern lookup selector
     pub ffmul: Option<T>,
+    /// Cairo memory pattern lookup selector
+    pub cairo_memory: Option<T>,
 }
 
 #[serde_as]
@@ -60,6 +62,8 @@ struct LookupSelectorsSerdeAs<F: FftField> {
     pub range_check: Option<E<F, D<F>>>,
     #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
     pub ffmul: Option<E<F, D<F>>>,
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub cairo_memory: Option<E<F, D<F>>>,
 }
 
 impl<F: FftField> serde_with::SerializeAs<LookupSelectors<E<F, D<F>>>>
@@ -74,6 +78,7 @@ impl<F: FftField> serde_with::SerializeAs<LookupSelectors<E<F, D<F>>>>
             lookup: val.lookup.clone(),
             range_check: val.range_check.clone(),
             ffmul: val.ffmul.clone(),
+            cairo_memory: val.cairo_memory.clone(),
         };
         repr.serialize(serializer)
     }
@@ -91,12 +96,14 @@ impl<'de, F: FftField> serde_with::DeserializeAs<'de, LookupSelectors<E<F, D<F>>
             lookup,
             range_check,
             ffmul,
+            cairo_memory,
         } = LookupSelectorsSerdeAs::deserialize(deserializer)?;
         Ok(LookupSelectors {
             xor,
             lookup,
             range_check,
             ffmul,
+            cairo_memory,
         })
     }
 }
@@ -110,6 +117,7 @@ impl<T> std::ops::Index<LookupPattern> for LookupSelectors<T> {
             LookupPattern::Lookup => &self.lookup,
             LookupPattern::RangeCheck => &self.range_check,
             LookupPattern::ForeignFieldMul => &self.ffmul,
+            LookupPattern::CairoMemory => &self.cairo_memory,
         }
     }
 }
@@ -121,6 +129,7 @@ impl<T> std::ops::IndexMut<LookupPattern> for LookupSelectors<T> {
             LookupPattern::Lookup => &mut self.lookup,
             LookupPattern::RangeCheck => &mut self.range_check,
             LookupPattern::ForeignFieldMul => &mut self.ffmul,
+            LookupPattern::CairoMemory => &mut self.cairo_memory,
         }
     }
 }
@@ -132,6 +141,7 @@ impl<T> LookupSelectors<T> {
             lookup,
             range_check,
             ffmul,
+            cairo_memory,
         } = self;
         // This closure isn't really redundant -- it shields the parameter from a copy -- but
         // clippy isn't smart enough to figure that out..
@@ -142,6 +152,7 @@ impl<T> LookupSelectors<T> {
             lookup: lookup.map(f),
             range_check: range_check.map(f),
             ffmul: ffmul.map(f),
+            cairo_memory: cairo_memory.map(f),
         }
     }
 
@@ -151,6 +162,7 @@ impl<T> LookupSelectors<T> {
             lookup: self.lookup.as_ref(),
             range_check: self.range_check.as_ref(),
             ffmul: self.ffmul.as_ref(),
+            cairo_memory: self.cairo_memory.as_ref(),
         }
     }
 }
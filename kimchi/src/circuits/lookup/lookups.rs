@@ -4,8 +4,8 @@ use crate::circuits::{
     lookup::{
         index::LookupSelectors,
         tables::{
-            combine_table_entry, get_table, GateLookupTable, LookupTable, RANGE_CHECK_TABLE_ID,
-            XOR_TABLE_ID,
+            combine_table_entry, get_table, GateLookupTable, LookupTable, CAIRO_MEMORY_TABLE_ID,
+            RANGE_CHECK_TABLE_ID, XOR_TABLE_ID,
         },
     },
 };
@@ -52,6 +52,7 @@ pub struct LookupPatterns {
     pub lookup: bool,
     pub range_check: bool,
     pub foreign_field_mul: bool,
+    pub cairo_memory: bool,
 }
 
 impl IntoIterator for LookupPatterns {
@@ -65,6 +66,7 @@ impl IntoIterator for LookupPatterns {
             lookup,
             range_check,
             foreign_field_mul,
+            cairo_memory,
         } = self;
 
         let mut patterns = Vec::with_capacity(5);
@@ -81,6 +83,9 @@ impl IntoIterator for LookupPatterns {
         if foreign_field_mul {
             patterns.push(LookupPattern::ForeignFieldMul)
         }
+        if cairo_memory {
+            patterns.push(LookupPattern::CairoMemory)
+        }
         patterns.into_iter()
     }
 }
@@ -94,6 +99,7 @@ impl std::ops::Index<LookupPattern> for LookupPatterns {
             LookupPattern::Lookup => &self.lookup,
             LookupPattern::RangeCheck => &self.range_check,
             LookupPattern::ForeignFieldMul => &self.foreign_field_mul,
+            LookupPattern::CairoMemory => &self.cairo_memory,
         }
     }
 }
@@ -105,6 +111,7 @@ impl std::ops::IndexMut<LookupPattern> for LookupPatterns {
             LookupPattern::Lookup => &mut self.lookup,
             LookupPattern::RangeCheck => &mut self.range_check,
             LookupPattern::ForeignFieldMul => &mut self.foreign_field_mul,
+            LookupPattern::CairoMemory => &mut self.cairo_memory,
         }
     }
 }
@@ -392,13 +399,17 @@ pub enum LookupPattern {
     Lookup,
     RangeCheck,
     ForeignFieldMul,
+    CairoMemory,
 }
 
 impl LookupPattern {
     /// Returns the maximum number of lookups per row that are used by the pattern.
     pub fn max_lookups_per_row(&self) -> usize {
         match self {
-            LookupPattern::Xor | LookupPattern::RangeCheck | LookupPattern::ForeignFieldMul => 4,
+            LookupPattern::Xor
+            | LookupPattern::RangeCheck
+            | LookupPattern::ForeignFieldMul
+            | LookupPattern::CairoMemory => 4,
             LookupPattern::Lookup => 3,
         }
     }
@@ -407,7 +418,7 @@ impl LookupPattern {
     pub fn max_joint_size(&self) -> u32 {
         match self {
             LookupPattern::Xor => 3,
-            LookupPattern::Lookup => 2,
+            LookupPattern::Lookup | LookupPattern::CairoMemory => 2,
             LookupPattern::ForeignFieldMul | LookupPattern::RangeCheck => 1,
         }
     }
@@ -496,6 +507,29 @@ impl LookupPattern {
                     })
                     .collect()
             }
+            LookupPattern::CairoMemory => {
+                // each row represents a Cairo instruction, accessing the
+                // memory at the addresses of the instruction itself, of the
+                // destination and of the operands
+                //
+                // 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14
+                // a - - - - - - - - - -  -  -  -  v
+                // - - - - - v - - - - -  a  -  -  -
+                // - - - - - - v - - - -  -  a  -  -
+                // - - - - - - - v - - -  -  -  a  -
+                [(0, 14), (11, 5), (12, 6), (13, 7)]
+                    .into_iter()
+                    .map(|(address, value)| {
+                        let l = |loc: LocalPosition| SingleLookup {
+                            value: vec![(F::one(), loc)],
+                        };
+                        JointLookup {
+                            table_id: LookupTableID::Constant(CAIRO_MEMORY_TABLE_ID),
+                            entry: vec![l(curr_row(address)), l(curr_row(value))],
+                        }
+                    })
+                    .collect()
+            }
         }
     }
 
@@ -506,6 +540,8 @@ impl LookupPattern {
             LookupPattern::Lookup => None,
             LookupPattern::RangeCheck => Some(GateLookupTable::RangeCheck),
             LookupPattern::ForeignFieldMul => Some(GateLookupTable::RangeCheck),
+            // the memory is given as a runtime table
+            LookupPattern::CairoMemory => None,
         }
     }
 
@@ -520,6 +556,7 @@ impl LookupPattern {
             }
             (ForeignFieldMul, Curr | Next) => Some(LookupPattern::ForeignFieldMul),
             (Xor16, Curr) => Some(LookupPattern::Xor),
+            (CairoInstruction, Curr) => Some(LookupPattern::CairoMemory),
             _ => None,
         }
     }
@@ -533,6 +570,7 @@ impl GateType {
             LookupPattern::Lookup,
             LookupPattern::RangeCheck,
             LookupPattern::ForeignFieldMul,
+            LookupPattern::CairoMemory,
         ]
     }
 }
@@ -566,12 +604,14 @@ pub mod wasm {
             lookup: bool,
             range_check: bool,
             foreign_field_mul: bool,
+            cairo_memory: bool,
         ) -> LookupPatterns {
             LookupPatterns {
                 xor,
                 lookup,
                 range_check,
                 foreign_field_mul,
+                cairo_memory,
             }
         }
     }
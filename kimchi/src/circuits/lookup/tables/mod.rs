@@ -16,6 +16,10 @@ pub const XOR_TABLE_ID: i32 = 0;
 pub const RANGE_CHECK_TABLE_ID: i32 = 1;
 //~ spec:endcode
 
+/// The ID of the runtime table containing the memory of a Cairo program, see
+/// [crate::circuits::polynomials::turshi].
+pub const CAIRO_MEMORY_TABLE_ID: i32 = 2;
+
 /// Enumerates the different 'fixed' lookup tables used by individual gates
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum GateLookupTable {
@@ -15,3 +15,4 @@ pub mod scalars;
 mod serialization_helper;
 pub mod wires;
 pub mod witness;
+pub mod witness_report;
@@ -19,7 +19,6 @@ use crate::circuits::{
     berkeley_columns::BerkeleyChallengeTerm,
     expr::{constraints::ExprOps, Cache},
     gate::{CircuitGate, GateType},
-    wires::COLUMNS,
 };
 use ark_ff::{Field, PrimeField};
 use std::marker::PhantomData;
@@ -232,11 +231,7 @@ impl<F: PrimeField> CircuitGate<F> {
     /// # Panics
     ///
     /// Will panic if `multiplicative inverse` operation between gate values fails.
-    pub fn verify_complete_add(
-        &self,
-        row: usize,
-        witness: &[Vec<F>; COLUMNS],
-    ) -> Result<(), String> {
+    pub fn verify_complete_add(&self, row: usize, witness: &[Vec<F>]) -> Result<(), String> {
         let x1 = witness[0][row];
         let y1 = witness[1][row];
         let x2 = witness[2][row];
@@ -42,7 +42,7 @@ impl FFOps {
 // - the sign of the operation
 // - the overflow flag
 // - the carry value
-fn compute_ffadd_values<F: PrimeField>(
+pub(crate) fn compute_ffadd_values<F: PrimeField>(
     left_input: &ForeignElement<F, LIMB_BITS, 3>,
     right_input: &ForeignElement<F, LIMB_BITS, 4>,
     opcode: FFOps,
@@ -177,7 +177,7 @@ pub fn create_chain<F: PrimeField>(
     witness
 }
 
-fn init_ffadd_row<F: PrimeField>(
+pub(crate) fn init_ffadd_row<F: PrimeField>(
     witness: &mut [Vec<F>; COLUMNS],
     offset: usize,
     left: [F; 3],
@@ -0,0 +1,771 @@
+//! The layout of the foreign curve gadgets.
+//!
+//! The gates are laid out along with the witness, so that both always agree.
+//! Every intermediate foreign field element has its limbs range-checked, and
+//! its high limb bounded by the one of the modulus.
+
+use super::curve::{inverse, ForeignCurve, ForeignPoint};
+use crate::circuits::{
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::{
+        foreign_field_add::{
+            self,
+            witness::{compute_ffadd_values, init_ffadd_row, FFOps},
+        },
+        foreign_field_common::{BigUintForeignFieldHelpers, KimchiForeignElement, LIMB_BITS},
+        foreign_field_mul,
+        generic::{GENERIC_COEFFS, GENERIC_REGISTERS},
+        range_check,
+    },
+    wires::Wire,
+};
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use o1_utils::foreign_field::{ForeignElement, ForeignFieldHelpers};
+use std::array;
+
+/// A cell of the witness, as `(row, column)`
+type Cell = (usize, usize);
+
+/// A native field element stored in a cell
+#[derive(Clone, Copy, Debug)]
+struct Var<F> {
+    cell: Cell,
+    value: F,
+}
+
+/// A foreign field element whose three limbs are stored in cells
+#[derive(Clone, Debug)]
+struct ForeignVar {
+    limbs: [Cell; 3],
+    value: BigUint,
+}
+
+/// A point of the foreign curve
+#[derive(Clone, Debug)]
+struct PointVar {
+    x: ForeignVar,
+    y: ForeignVar,
+}
+
+impl PointVar {
+    fn new(coordinates: &[ForeignVar]) -> Self {
+        Self {
+            x: coordinates[0].clone(),
+            y: coordinates[1].clone(),
+        }
+    }
+
+    fn value(&self) -> ForeignPoint {
+        ForeignPoint::new(self.x.value.clone(), self.y.value.clone())
+    }
+}
+
+/// The gates and the witness of a gadget under construction
+struct Layout<'a, F: PrimeField> {
+    curve: &'a ForeignCurve,
+    start_row: usize,
+    gates: Vec<CircuitGate<F>>,
+    witness: [Vec<F>; COLUMNS],
+    /// The last generic row, when its second gate is unused
+    free_generic: Option<usize>,
+    /// The bounds of high limbs waiting to be range-checked by three
+    limb_checks: Vec<Var<F>>,
+    /// The constants already stored in the circuit
+    constants: Vec<Var<F>>,
+}
+
+impl<'a, F: PrimeField> Layout<'a, F> {
+    fn new(curve: &'a ForeignCurve, start_row: usize) -> Self {
+        Self {
+            curve,
+            start_row,
+            gates: vec![],
+            witness: array::from_fn(|_| vec![]),
+            free_generic: None,
+            limb_checks: vec![],
+            constants: vec![],
+        }
+    }
+
+    /// Lays out the pending range checks, then copies the outputs to multi-range-checks
+    fn finish(mut self, outputs: &[&ForeignVar]) -> (Vec<CircuitGate<F>>, [Vec<F>; COLUMNS]) {
+        self.flush_limb_checks();
+        for output in outputs {
+            let limbs = output.value.to_field_limbs::<F>();
+            self.multi_range_check(array::from_fn(|i| (Some(output.limbs[i]), limbs[i])));
+        }
+        (self.gates, self.witness)
+    }
+
+    fn next_row(&self) -> usize {
+        self.start_row + self.gates.len()
+    }
+
+    fn append(&mut self, gates: Vec<CircuitGate<F>>, witness: [Vec<F>; COLUMNS]) {
+        assert_eq!(gates.len(), witness[0].len());
+        self.gates.extend(gates);
+        for (col, rows) in self.witness.iter_mut().zip(witness) {
+            col.extend(rows);
+        }
+    }
+
+    /// Adds a copy constraint between two cells
+    fn connect(&mut self, (row1, col1): Cell, (row2, col2): Cell) {
+        let (row1, row2) = (row1 - self.start_row, row2 - self.start_row);
+        let wire = self.gates[row1].wires[col1];
+        self.gates[row1].wires[col1] = self.gates[row2].wires[col2];
+        self.gates[row2].wires[col2] = wire;
+    }
+
+    //
+    // Native field elements
+    //
+
+    /// Adds the generic constraint `c0 * l + c1 * r + c2 * o + c3 * l * r + c4 = 0`
+    /// on the registers `[l, r, o]`, that are copied from the given cells when any.
+    fn generic(
+        &mut self,
+        coeffs: [F; GENERIC_COEFFS],
+        registers: [(Option<Cell>, F); GENERIC_REGISTERS],
+    ) -> [Var<F>; GENERIC_REGISTERS] {
+        let (row, half) = match self.free_generic.take() {
+            Some(row) => (row, 1),
+            None => {
+                let row = self.next_row();
+                let gate = CircuitGate::create_generic(
+                    Wire::for_row(row),
+                    [F::zero(); GENERIC_COEFFS * 2],
+                );
+                self.append(vec![gate], array::from_fn(|_| vec![F::zero()]));
+                self.free_generic = Some(row);
+                (row, 0)
+            }
+        };
+        let local_row = row - self.start_row;
+        for (i, coeff) in coeffs.into_iter().enumerate() {
+            self.gates[local_row].coeffs[half * GENERIC_COEFFS + i] = coeff;
+        }
+        let mut vars = vec![];
+        for (i, (source, value)) in registers.into_iter().enumerate() {
+            let cell = (row, half * GENERIC_REGISTERS + i);
+            self.witness[cell.1][local_row] = value;
+            if let Some(source) = source {
+                self.connect(source, cell);
+            }
+            vars.push(Var { cell, value });
+        }
+        vars.try_into().unwrap()
+    }
+
+    fn constant(&mut self, value: F) -> Var<F> {
+        if let Some(var) = self.constants.iter().find(|var| var.value == value) {
+            return *var;
+        }
+        let [var, _, _] = self.generic(
+            [F::one(), F::zero(), F::zero(), F::zero(), -value],
+            [(None, value), (None, F::zero()), (None, F::zero())],
+        );
+        self.constants.push(var);
+        var
+    }
+
+    /// Returns a new boolean variable
+    fn boolean(&mut self, bit: bool) -> Var<F> {
+        let value = F::from(bit);
+        // l * r - l = 0, with l = r
+        let [left, right, _] = self.generic(
+            [-F::one(), F::zero(), F::zero(), F::one(), F::zero()],
+            [(None, value), (None, value), (None, F::zero())],
+        );
+        self.connect(left.cell, right.cell);
+        left
+    }
+
+    /// Returns `a * b`
+    fn mul(&mut self, a: Var<F>, b: Var<F>) -> Var<F> {
+        let [_, _, output] = self.generic(
+            [F::zero(), F::zero(), -F::one(), F::one(), F::zero()],
+            [
+                (Some(a.cell), a.value),
+                (Some(b.cell), b.value),
+                (None, a.value * b.value),
+            ],
+        );
+        output
+    }
+
+    /// Returns `ca * a + cb * b`
+    fn linear(&mut self, a: Var<F>, ca: F, b: Var<F>, cb: F) -> Var<F> {
+        let [_, _, output] = self.generic(
+            [ca, cb, -F::one(), F::zero(), F::zero()],
+            [
+                (Some(a.cell), a.value),
+                (Some(b.cell), b.value),
+                (None, ca * a.value + cb * b.value),
+            ],
+        );
+        output
+    }
+
+    //
+    // Foreign field elements
+    //
+
+    /// Range-checks three 88-bit limbs, copied from the given cells when any,
+    /// and returns the cells of the limbs in the multi-range-check
+    fn multi_range_check(&mut self, limbs: [(Option<Cell>, F); 3]) -> [Cell; 3] {
+        let row = self.next_row();
+        let (_, gates) = CircuitGate::create_multi_range_check(row);
+        let witness = range_check::witness::create_multi(limbs[0].1, limbs[1].1, limbs[2].1);
+        self.append(gates, witness);
+        array::from_fn(|i| {
+            let cell = (row + i, 0);
+            if let Some(source) = limbs[i].0 {
+                self.connect(source, cell);
+            }
+            cell
+        })
+    }
+
+    fn flush_limb_checks(&mut self) {
+        while !self.limb_checks.is_empty() {
+            let count = self.limb_checks.len().min(3);
+            let limbs: Vec<_> = self.limb_checks.drain(..count).collect();
+            self.multi_range_check(array::from_fn(|i| match limbs.get(i) {
+                Some(limb) => (Some(limb.cell), limb.value),
+                None => (None, F::zero()),
+            }));
+        }
+    }
+
+    /// Constrains the high limb of `x` to be at most the one of the modulus,
+    /// by range-checking `x2 + 2^88 - f2 - 1`
+    fn high_bound(&mut self, x: &ForeignVar, modulus: &BigUint) {
+        let offset =
+            KimchiForeignElement::<F>::two_to_limb() - modulus.to_field_limbs::<F>()[2] - F::one();
+        let high = x.value.to_field_limbs::<F>()[2];
+        let [_, _, bound] = self.generic(
+            [F::one(), F::zero(), -F::one(), F::zero(), offset],
+            [
+                (Some(x.limbs[2]), high),
+                (None, F::zero()),
+                (None, high + offset),
+            ],
+        );
+        self.limb_checks.push(bound);
+        if self.limb_checks.len() == 3 {
+            self.flush_limb_checks();
+        }
+    }
+
+    /// Returns new foreign field elements, whose multi-range-checks are the first rows
+    fn inputs(&mut self, values: &[(&BigUint, &BigUint)]) -> Vec<ForeignVar> {
+        let vars: Vec<_> = values
+            .iter()
+            .map(|(value, _)| {
+                let limbs = value.to_field_limbs::<F>();
+                ForeignVar {
+                    limbs: self.multi_range_check(limbs.map(|limb| (None, limb))),
+                    value: (*value).clone(),
+                }
+            })
+            .collect();
+        for (var, (_, modulus)) in vars.iter().zip(values) {
+            self.high_bound(var, modulus);
+        }
+        vars
+    }
+
+    /// Returns a new foreign field element
+    fn foreign(&mut self, value: BigUint, modulus: &BigUint) -> ForeignVar {
+        self.inputs(&[(&value, modulus)]).remove(0)
+    }
+
+    fn foreign_constant(&mut self, value: &BigUint) -> ForeignVar {
+        let limbs = value.to_field_limbs::<F>();
+        ForeignVar {
+            limbs: limbs.map(|limb| self.constant(limb).cell),
+            value: value.clone(),
+        }
+    }
+
+    fn assert_equal(&mut self, a: &ForeignVar, b: &ForeignVar) {
+        for (a, b) in a.limbs.iter().zip(&b.limbs) {
+            self.connect(*a, *b);
+        }
+    }
+
+    /// Returns `a * b` reduced modulo `modulus`
+    fn foreign_mul(&mut self, a: &ForeignVar, b: &ForeignVar, modulus: &BigUint) -> ForeignVar {
+        let row = self.next_row();
+        let (_, gates) = CircuitGate::create_foreign_field_mul(row, modulus);
+        let (witness, checks) = foreign_field_mul::witness::create(&a.value, &b.value, modulus);
+        self.append(gates, witness);
+        for i in 0..3 {
+            self.connect(a.limbs[i], (row, i));
+            self.connect(b.limbs[i], (row, 3 + i));
+        }
+
+        // the quotient
+        let quotient = checks.multi_ranges[0];
+        self.multi_range_check(array::from_fn(|i| (Some((row + 1, 2 + i)), quotient[i])));
+        // the quotient bound and the intermediate products
+        let [quotient_hi_bound, product1_lo, product1_hi_0] = checks.multi_ranges[1];
+        self.multi_range_check([
+            (Some((row + 1, 5)), quotient_hi_bound),
+            (Some((row, 6)), product1_lo),
+            (Some((row + 1, 6)), product1_hi_0),
+        ]);
+        // the remainder, in compact form
+        let compact_row = self.next_row();
+        let (_, gates) = CircuitGate::create_compact_multi_range_check(compact_row);
+        let [remainder01, remainder2] = checks.compact_multi_ranges[0];
+        let witness = range_check::witness::create_multi_compact(remainder01, remainder2);
+        self.append(gates, witness);
+        self.connect((row + 1, 0), (compact_row + 2, 1));
+        self.connect((row + 1, 1), (compact_row, 0));
+
+        let remainder = ForeignVar {
+            limbs: [(compact_row + 1, 0), (compact_row + 2, 0), (compact_row, 0)],
+            value: (&a.value * &b.value) % modulus,
+        };
+        self.high_bound(&remainder, modulus);
+        remainder
+    }
+
+    /// Returns `a + b` or `a - b` reduced modulo `modulus`
+    fn foreign_add(
+        &mut self,
+        a: &ForeignVar,
+        b: &ForeignVar,
+        operation: FFOps,
+        modulus: &BigUint,
+    ) -> ForeignVar {
+        let row = self.next_row();
+        let (_, gates) = CircuitGate::create_single_ffadd(row, operation, modulus);
+        let left = ForeignElement::<F, LIMB_BITS, 3>::from_biguint(a.value.clone());
+        let right = ForeignElement::<F, LIMB_BITS, 4>::from_biguint(b.value.clone());
+        let (result, _sign, overflow, carry) = compute_ffadd_values(
+            &left,
+            &right,
+            operation,
+            &ForeignElement::from_biguint(modulus.clone()),
+        );
+        let mut witness = array::from_fn(|_| vec![F::zero(); 2]);
+        init_ffadd_row(
+            &mut witness,
+            0,
+            left.limbs,
+            [right[0], right[1], right[2]],
+            overflow,
+            carry,
+        );
+        for (col, limb) in result.limbs.iter().enumerate() {
+            witness[col][1] = *limb;
+        }
+        self.append(gates, witness);
+        for i in 0..3 {
+            self.connect(a.limbs[i], (row, i));
+            self.connect(b.limbs[i], (row, 3 + i));
+        }
+
+        let limbs = self.multi_range_check(array::from_fn(|i| (Some((row + 1, i)), result[i])));
+        let sum = ForeignVar {
+            limbs,
+            value: result.to_biguint(),
+        };
+        self.high_bound(&sum, modulus);
+        sum
+    }
+
+    fn foreign_sub(&mut self, a: &ForeignVar, b: &ForeignVar, modulus: &BigUint) -> ForeignVar {
+        self.foreign_add(a, b, FFOps::Sub, modulus)
+    }
+
+    /// Constrains `x` to be smaller than the modulus, by range-checking `x + 2^264 - f`
+    fn bound_check(&mut self, x: &ForeignVar, modulus: &BigUint) {
+        let row = self.next_row();
+        let (_, gates) = CircuitGate::create_single_ffadd(row, FFOps::Add, modulus);
+        let mut witness = array::from_fn(|_| vec![]);
+        let limbs = x.value.to_field_limbs::<F>();
+        foreign_field_add::witness::extend_witness_bound_addition(
+            &mut witness,
+            &limbs,
+            &modulus.to_field_limbs(),
+        );
+        self.append(gates, witness);
+        for (i, limb) in x.limbs.iter().enumerate() {
+            self.connect(*limb, (row, i));
+        }
+        // the right input is 2^264 and the overflow is 1
+        let zero = self.constant(F::zero());
+        let two_to_limb = self.constant(KimchiForeignElement::<F>::two_to_limb());
+        let one = self.constant(F::one());
+        self.connect(zero.cell, (row, 3));
+        self.connect(zero.cell, (row, 4));
+        self.connect(two_to_limb.cell, (row, 5));
+        self.connect(one.cell, (row, 6));
+
+        let bound: [F; 3] = array::from_fn(|i| self.cell_value((row + 1, i)));
+        self.multi_range_check(array::from_fn(|i| (Some((row + 1, i)), bound[i])));
+    }
+
+    fn cell_value(&self, (row, col): Cell) -> F {
+        self.witness[col][row - self.start_row]
+    }
+
+    //
+    // Points of the foreign curve
+    //
+
+    fn constant_point(&mut self, point: &ForeignPoint) -> PointVar {
+        PointVar {
+            x: self.foreign_constant(&point.x),
+            y: self.foreign_constant(&point.y),
+        }
+    }
+
+    /// Constrains `y^2 = x^3 + b`
+    fn assert_on_curve(&mut self, point: &PointVar) {
+        let base = &self.curve.base_modulus.clone();
+        let b = self.foreign_constant(&self.curve.b.clone());
+        let y2 = self.foreign_mul(&point.y, &point.y, base);
+        let x2 = self.foreign_mul(&point.x, &point.x, base);
+        let x3 = self.foreign_mul(&x2, &point.x, base);
+        let x3_plus_b = self.foreign_add(&x3, &b, FFOps::Add, base);
+        self.assert_equal(&y2, &x3_plus_b);
+    }
+
+    /// Returns the sum of `p` and the point of abscissa `x` on the line of slope `slope` through `p`
+    fn add_with_slope(&mut self, slope: &ForeignVar, p: &PointVar, x: &ForeignVar) -> PointVar {
+        let base = &self.curve.base_modulus.clone();
+        let slope2 = self.foreign_mul(slope, slope, base);
+        let slope2_minus_px = self.foreign_sub(&slope2, &p.x, base);
+        let x3 = self.foreign_sub(&slope2_minus_px, x, base);
+        let px_minus_x3 = self.foreign_sub(&p.x, &x3, base);
+        let product = self.foreign_mul(slope, &px_minus_x3, base);
+        let y3 = self.foreign_sub(&product, &p.y, base);
+        PointVar { x: x3, y: y3 }
+    }
+
+    /// Returns `p + q`, which requires `p != ±q`
+    fn add_points(&mut self, p: &PointVar, q: &PointVar) -> PointVar {
+        let base = &self.curve.base_modulus.clone();
+        let (p_value, q_value) = (p.value(), q.value());
+        let slope_value = (&q_value.y + base - &p_value.y)
+            * self.curve.inverse(&(&q_value.x + base - &p_value.x))
+            % base;
+
+        // slope * (qx - px) = qy - py
+        let slope = self.foreign(slope_value, base);
+        let dx = self.foreign_sub(&q.x, &p.x, base);
+        let dy = self.foreign_sub(&q.y, &p.y, base);
+        let product = self.foreign_mul(&slope, &dx, base);
+        self.assert_equal(&product, &dy);
+
+        self.add_with_slope(&slope, p, &q.x)
+    }
+
+    /// Returns `2p`, which requires `p` not to be of order 2
+    fn double_point(&mut self, p: &PointVar) -> PointVar {
+        let base = &self.curve.base_modulus.clone();
+        let p_value = p.value();
+        let slope_value = BigUint::from(3u32)
+            * &p_value.x
+            * &p_value.x
+            * self.curve.inverse(&(BigUint::from(2u32) * &p_value.y))
+            % base;
+
+        // slope * 2y = 3x^2
+        let slope = self.foreign(slope_value, base);
+        let x2 = self.foreign_mul(&p.x, &p.x, base);
+        let two_x2 = self.foreign_add(&x2, &x2, FFOps::Add, base);
+        let three_x2 = self.foreign_add(&two_x2, &x2, FFOps::Add, base);
+        let two_y = self.foreign_add(&p.y, &p.y, FFOps::Add, base);
+        let product = self.foreign_mul(&slope, &two_y, base);
+        self.assert_equal(&product, &three_x2);
+
+        self.add_with_slope(&slope, p, &p.x)
+    }
+
+    /// Returns `2a + t` computed as `(a + t) + a`, without the ordinate of `a + t`.
+    /// It requires `a != ±t` and `a + t != ±a`.
+    fn double_and_add(&mut self, a: &PointVar, t: &PointVar) -> PointVar {
+        let base = &self.curve.base_modulus.clone();
+        let (a_value, t_value) = (a.value(), t.value());
+        let slope1_value = (&t_value.y + base - &a_value.y)
+            * self.curve.inverse(&(&t_value.x + base - &a_value.x))
+            % base;
+        let x1_value =
+            (&slope1_value * &slope1_value + 2u32 * base - &a_value.x - &t_value.x) % base;
+        let slope2_value = (base - &slope1_value
+            + BigUint::from(2u32)
+                * &a_value.y
+                * self.curve.inverse(&(&a_value.x + base - &x1_value)))
+            % base;
+
+        // slope1 * (tx - ax) = ty - ay
+        let slope1 = self.foreign(slope1_value, base);
+        let dx = self.foreign_sub(&t.x, &a.x, base);
+        let dy = self.foreign_sub(&t.y, &a.y, base);
+        let product = self.foreign_mul(&slope1, &dx, base);
+        self.assert_equal(&product, &dy);
+
+        // x1 = slope1^2 - ax - tx
+        let slope1_2 = self.foreign_mul(&slope1, &slope1, base);
+        let slope1_2_minus_ax = self.foreign_sub(&slope1_2, &a.x, base);
+        let x1 = self.foreign_sub(&slope1_2_minus_ax, &t.x, base);
+
+        // (slope1 + slope2) * (ax - x1) = 2ay
+        let slope2 = self.foreign(slope2_value, base);
+        let slopes = self.foreign_add(&slope1, &slope2, FFOps::Add, base);
+        let dx = self.foreign_sub(&a.x, &x1, base);
+        let product = self.foreign_mul(&slopes, &dx, base);
+        let two_y = self.foreign_add(&a.y, &a.y, FFOps::Add, base);
+        self.assert_equal(&product, &two_y);
+
+        self.add_with_slope(&slope2, a, &x1)
+    }
+
+    /// Returns the little-endian bits of the first `bits` bits of `k`,
+    /// that is constrained to be smaller than `2^bits`
+    fn decompose(&mut self, k: &ForeignVar, bits: usize) -> Vec<Var<F>> {
+        let mut result = vec![];
+        for (j, limb) in k.limbs.iter().enumerate() {
+            let count = bits.saturating_sub(j * LIMB_BITS).min(LIMB_BITS);
+            if count == 0 {
+                let zero = self.constant(F::zero());
+                self.connect(zero.cell, *limb);
+                continue;
+            }
+            let mut acc: Option<Var<F>> = None;
+            for i in 0..count {
+                let bit = self.boolean(k.value.bit((j * LIMB_BITS + i) as u64));
+                acc = Some(match acc {
+                    None => bit,
+                    Some(acc) => {
+                        let power = F::from(BigUint::one() << i);
+                        self.linear(acc, F::one(), bit, power)
+                    }
+                });
+                result.push(bit);
+            }
+            self.connect(acc.unwrap().cell, *limb);
+        }
+        result
+    }
+
+    /// Returns `sum_i k_i * p_i` for scalars `k_i` smaller than `2^bits`.
+    ///
+    /// It adds to an accumulator the sum of an offset point and the points
+    /// selected by the bits of the scalars, one bit at a time, and finally
+    /// subtracts `(2^bits - 1)` times the offset point. The incomplete
+    /// formulas are used, so the result must not be the point at infinity.
+    fn scalar_mul(&mut self, points: &[PointVar], scalars: &[ForeignVar], bits: usize) -> PointVar {
+        assert_eq!(points.len(), scalars.len());
+        let offset = self.curve.offset_point();
+
+        // the sums of the offset point and of the subsets of the points,
+        // indexed by the bitmask of the subset
+        let mut table = vec![self.constant_point(&offset)];
+        for point in points {
+            for i in 0..table.len() {
+                let sum = self.add_points(&table[i].clone(), point);
+                table.push(sum);
+            }
+        }
+
+        // a table entry is a multilinear polynomial of the bits of the mask,
+        // whose coefficients are the limbs of its Möbius transform
+        let mut coefficients: Vec<[Var<F>; 6]> = table
+            .iter()
+            .map(|entry| {
+                let limbs: [[Var<F>; 3]; 2] = [&entry.x, &entry.y].map(|coordinate| {
+                    let values = coordinate.value.to_field_limbs::<F>();
+                    array::from_fn(|i| Var {
+                        cell: coordinate.limbs[i],
+                        value: values[i],
+                    })
+                });
+                array::from_fn(|i| limbs[i / 3][i % 3])
+            })
+            .collect();
+        for j in 0..points.len() {
+            for mask in 0..table.len() {
+                if mask & (1 << j) != 0 {
+                    let (high, low) = (coefficients[mask], coefficients[mask ^ (1 << j)]);
+                    coefficients[mask] =
+                        array::from_fn(|i| self.linear(high[i], F::one(), low[i], -F::one()));
+                }
+            }
+        }
+
+        let bits_of_scalars: Vec<_> = scalars.iter().map(|k| self.decompose(k, bits)).collect();
+        let select = |layout: &mut Self, i: usize| -> PointVar {
+            // the monomials of the bits, indexed by their bitmask
+            let mut monomials: Vec<Option<Var<F>>> = vec![None];
+            for scalar_bits in &bits_of_scalars {
+                for mask in 0..monomials.len() {
+                    let monomial = match monomials[mask] {
+                        None => scalar_bits[i],
+                        Some(monomial) => layout.mul(monomial, scalar_bits[i]),
+                    };
+                    monomials.push(Some(monomial));
+                }
+            }
+            let mask = bits_of_scalars
+                .iter()
+                .enumerate()
+                .filter(|(_, scalar_bits)| scalar_bits[i].value.is_one())
+                .fold(0, |mask, (j, _)| mask | (1 << j));
+
+            let limbs: [Cell; 6] = array::from_fn(|limb| {
+                let mut acc = coefficients[0][limb];
+                for (monomial, coefficient) in monomials.iter().zip(&coefficients).skip(1) {
+                    let term = layout.mul(monomial.unwrap(), coefficient[limb]);
+                    acc = layout.linear(acc, F::one(), term, F::one());
+                }
+                acc.cell
+            });
+            let value = table[mask].value();
+            PointVar {
+                x: ForeignVar {
+                    limbs: [limbs[0], limbs[1], limbs[2]],
+                    value: value.x,
+                },
+                y: ForeignVar {
+                    limbs: [limbs[3], limbs[4], limbs[5]],
+                    value: value.y,
+                },
+            }
+        };
+
+        // the first step doubles the accumulator separately, as it may be
+        // equal to the selected point
+        let mut acc = select(self, bits - 1);
+        if bits > 1 {
+            let t = select(self, bits - 2);
+            let double = self.double_point(&acc);
+            acc = self.add_points(&double, &t);
+        }
+        for i in (0..bits.saturating_sub(2)).rev() {
+            let t = select(self, i);
+            acc = self.double_and_add(&acc, &t);
+        }
+
+        let correction = self
+            .curve
+            .scalar_mul(&offset, &((BigUint::one() << bits) - 1u32))
+            .unwrap();
+        let correction = self.constant_point(&self.curve.negate(&correction));
+        self.add_points(&acc, &correction)
+    }
+
+    /// Verifies the ECDSA signature `(r, s)` of the message hash `hash` with the public key
+    fn ecdsa_verify(
+        &mut self,
+        public_key: &PointVar,
+        hash: &ForeignVar,
+        r: &ForeignVar,
+        s: &ForeignVar,
+    ) {
+        let base = &self.curve.base_modulus.clone();
+        let order = &self.curve.scalar_modulus.clone();
+        self.assert_on_curve(public_key);
+
+        // 0 < r, s < n
+        self.bound_check(r, order);
+        self.bound_check(s, order);
+        let one = self.foreign_constant(&BigUint::one());
+        let r_inverse = self.foreign(inverse(&r.value, order), order);
+        let product = self.foreign_mul(r, &r_inverse, order);
+        self.assert_equal(&product, &one);
+        let s_inverse = self.foreign(inverse(&s.value, order), order);
+        let product = self.foreign_mul(s, &s_inverse, order);
+        self.assert_equal(&product, &one);
+
+        // (x, y) = (hash / s) * G + (r / s) * public_key
+        let u1 = self.foreign_mul(hash, &s_inverse, order);
+        let u2 = self.foreign_mul(r, &s_inverse, order);
+        let generator = self.constant_point(&self.curve.generator.clone());
+        let point = self.scalar_mul(
+            &[generator, public_key.clone()],
+            &[u1, u2],
+            order.bits() as usize,
+        );
+
+        // x = r mod n, where x < p < 2n
+        self.bound_check(&point.x, base);
+        let zero = self.foreign_constant(&BigUint::zero());
+        let x = self.foreign_add(&point.x, &zero, FFOps::Add, order);
+        self.assert_equal(&x, r);
+    }
+}
+
+/// Lays out the addition of two points
+pub(super) fn ec_add<F: PrimeField>(
+    curve: &ForeignCurve,
+    start_row: usize,
+    p: &ForeignPoint,
+    q: &ForeignPoint,
+) -> (Vec<CircuitGate<F>>, [Vec<F>; COLUMNS]) {
+    let mut layout = Layout::new(curve, start_row);
+    let base = &curve.base_modulus;
+    let inputs = layout.inputs(&[(&p.x, base), (&p.y, base), (&q.x, base), (&q.y, base)]);
+    let sum = layout.add_points(&PointVar::new(&inputs[..2]), &PointVar::new(&inputs[2..]));
+    layout.finish(&[&sum.x, &sum.y])
+}
+
+/// Lays out the doubling of a point
+pub(super) fn ec_double<F: PrimeField>(
+    curve: &ForeignCurve,
+    start_row: usize,
+    p: &ForeignPoint,
+) -> (Vec<CircuitGate<F>>, [Vec<F>; COLUMNS]) {
+    let mut layout = Layout::new(curve, start_row);
+    let base = &curve.base_modulus;
+    let inputs = layout.inputs(&[(&p.x, base), (&p.y, base)]);
+    let double = layout.double_point(&PointVar::new(&inputs));
+    layout.finish(&[&double.x, &double.y])
+}
+
+/// Lays out the multiplication of a point by a scalar of `bits` bits
+pub(super) fn ec_scalar_mul<F: PrimeField>(
+    curve: &ForeignCurve,
+    start_row: usize,
+    p: &ForeignPoint,
+    k: &BigUint,
+    bits: usize,
+) -> (Vec<CircuitGate<F>>, [Vec<F>; COLUMNS]) {
+    let mut layout = Layout::new(curve, start_row);
+    let (base, order) = (&curve.base_modulus, &curve.scalar_modulus);
+    let inputs = layout.inputs(&[(&p.x, base), (&p.y, base), (k, order)]);
+    let point = PointVar::new(&inputs[..2]);
+    let product = layout.scalar_mul(&[point], &inputs[2..], bits);
+    layout.finish(&[&product.x, &product.y])
+}
+
+/// Lays out the verification of an ECDSA signature
+pub(super) fn ecdsa_verify<F: PrimeField>(
+    curve: &ForeignCurve,
+    start_row: usize,
+    public_key: &ForeignPoint,
+    hash: &BigUint,
+    (r, s): (&BigUint, &BigUint),
+) -> (Vec<CircuitGate<F>>, [Vec<F>; COLUMNS]) {
+    let mut layout = Layout::new(curve, start_row);
+    let (base, order) = (&curve.base_modulus, &curve.scalar_modulus);
+    let inputs = layout.inputs(&[
+        (&public_key.x, base),
+        (&public_key.y, base),
+        (hash, order),
+        (r, order),
+        (s, order),
+    ]);
+    let public_key = PointVar::new(&inputs[..2]);
+    layout.ecdsa_verify(&public_key, &inputs[2], &inputs[3], &inputs[4]);
+    layout.finish(&[])
+}
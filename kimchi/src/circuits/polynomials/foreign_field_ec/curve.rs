@@ -0,0 +1,139 @@
+//! Parameters and out-of-circuit arithmetic of the foreign curves.
+
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+/// An affine point of a foreign curve, with coordinates in its base field
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ForeignPoint {
+    pub x: BigUint,
+    pub y: BigUint,
+}
+
+impl ForeignPoint {
+    pub fn new(x: BigUint, y: BigUint) -> Self {
+        Self { x, y }
+    }
+}
+
+/// A short Weierstrass curve `y^2 = x^3 + b` over a foreign field
+#[derive(Clone, Debug)]
+pub struct ForeignCurve {
+    /// The modulus of the base field
+    pub base_modulus: BigUint,
+    /// The order of the group of points, that is the modulus of the scalar field
+    pub scalar_modulus: BigUint,
+    /// The constant coefficient of the curve equation
+    pub b: BigUint,
+    /// The generator of the group of points
+    pub generator: ForeignPoint,
+}
+
+impl ForeignCurve {
+    /// The secp256k1 curve, used by Bitcoin and Ethereum signatures
+    pub fn secp256k1() -> Self {
+        let hex = |s: &str| BigUint::parse_bytes(s.as_bytes(), 16).unwrap();
+        Self {
+            base_modulus: hex("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"),
+            scalar_modulus: hex("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"),
+            b: BigUint::from(7u32),
+            generator: ForeignPoint::new(
+                hex("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+                hex("483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"),
+            ),
+        }
+    }
+
+    /// Returns `x^-1` in the base field, or zero if `x` is zero
+    pub fn inverse(&self, x: &BigUint) -> BigUint {
+        inverse(x, &self.base_modulus)
+    }
+
+    /// Checks that the point satisfies the curve equation
+    pub fn is_on_curve(&self, p: &ForeignPoint) -> bool {
+        let modulus = &self.base_modulus;
+        (&p.y * &p.y) % modulus == (&p.x * &p.x * &p.x + &self.b) % modulus
+    }
+
+    /// Returns `-p`
+    pub fn negate(&self, p: &ForeignPoint) -> ForeignPoint {
+        let y = (&self.base_modulus - &p.y) % &self.base_modulus;
+        ForeignPoint::new(p.x.clone(), y)
+    }
+
+    /// Returns `p + q` with the incomplete addition formula, that requires `p != ±q`
+    pub fn add(&self, p: &ForeignPoint, q: &ForeignPoint) -> ForeignPoint {
+        let modulus = &self.base_modulus;
+        let dx = (&q.x + modulus - &p.x) % modulus;
+        let dy = (&q.y + modulus - &p.y) % modulus;
+        let slope = (dy * self.inverse(&dx)) % modulus;
+        self.add_with_slope(&slope, p, &q.x)
+    }
+
+    /// Returns `2p`, which requires `p` not to be of order 2
+    pub fn double(&self, p: &ForeignPoint) -> ForeignPoint {
+        let modulus = &self.base_modulus;
+        let numerator = (BigUint::from(3u32) * &p.x * &p.x) % modulus;
+        let slope = (numerator * self.inverse(&(BigUint::from(2u32) * &p.y))) % modulus;
+        self.add_with_slope(&slope, p, &p.x)
+    }
+
+    /// Returns `k * p`, or `None` for the point at infinity
+    pub fn scalar_mul(&self, p: &ForeignPoint, k: &BigUint) -> Option<ForeignPoint> {
+        let mut acc: Option<ForeignPoint> = None;
+        for i in (0..k.bits()).rev() {
+            acc = acc.and_then(|acc| self.add_complete(&acc, &acc));
+            if k.bit(i) {
+                acc = match acc {
+                    None => Some(p.clone()),
+                    Some(acc) => self.add_complete(&acc, p),
+                };
+            }
+        }
+        acc
+    }
+
+    /// An auxiliary point of the curve, with an unknown discrete logarithm.
+    /// It is the point of smallest abscissa above `2^128` with an even ordinate.
+    pub fn offset_point(&self) -> ForeignPoint {
+        let modulus = &self.base_modulus;
+        // this computes square roots when the modulus is 3 mod 4
+        assert_eq!(modulus % 4u32, BigUint::from(3u32));
+        let sqrt_exponent = (modulus + 1u32) / 4u32;
+        let mut x = BigUint::one() << 128;
+        loop {
+            let y2: BigUint = (&x * &x * &x + &self.b) % modulus;
+            let y = y2.modpow(&sqrt_exponent, modulus);
+            if (&y * &y) % modulus == y2 {
+                let y = if y.is_even() { y } else { modulus - y };
+                return ForeignPoint::new(x, y);
+            }
+            x += 1u32;
+        }
+    }
+
+    // Returns the sum of p and the point of abscissa x on the line of the given slope through p
+    fn add_with_slope(&self, slope: &BigUint, p: &ForeignPoint, x: &BigUint) -> ForeignPoint {
+        let modulus = &self.base_modulus;
+        let x3 = (slope * slope + 2u32 * modulus - &p.x - x) % modulus;
+        let y3 = (slope * (&p.x + modulus - &x3) + modulus - &p.y) % modulus;
+        ForeignPoint::new(x3, y3)
+    }
+
+    // Adds two points, handling the doubling and the point at infinity
+    fn add_complete(&self, p: &ForeignPoint, q: &ForeignPoint) -> Option<ForeignPoint> {
+        if p.x != q.x {
+            Some(self.add(p, q))
+        } else if p.y == q.y && !p.y.is_zero() {
+            Some(self.double(p))
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns `x^-1` modulo the prime `modulus`, or zero if `x` is zero
+pub fn inverse(x: &BigUint, modulus: &BigUint) -> BigUint {
+    x.modpow(&(modulus - 2u32), modulus)
+}
@@ -0,0 +1,112 @@
+//! This module obtains the gates of the foreign curve gadgets.
+
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+
+use crate::circuits::gate::CircuitGate;
+
+use super::{
+    circuit,
+    curve::{ForeignCurve, ForeignPoint},
+};
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Create the gates of the addition of two points `p + q` of a foreign curve,
+    /// which requires `p != ±q`
+    /// - Inputs: `p.x`, `p.y`, `q.x`, `q.y`
+    /// - Outputs tuple (next_row, circuit_gates) where
+    ///   - next_row      - next row after this gate
+    ///   - circuit_gates - vector of circuit gates comprising this gadget
+    pub fn create_foreign_ec_add(start_row: usize, curve: &ForeignCurve) -> (usize, Vec<Self>) {
+        let dummy = ForeignPoint::default();
+        let (gates, _) = circuit::ec_add::<F>(curve, start_row, &dummy, &dummy);
+        (start_row + gates.len(), gates)
+    }
+
+    /// Create the gates of the doubling of a point `2p` of a foreign curve
+    /// - Inputs: `p.x`, `p.y`
+    /// - Outputs tuple (next_row, circuit_gates) where
+    ///   - next_row      - next row after this gate
+    ///   - circuit_gates - vector of circuit gates comprising this gadget
+    pub fn create_foreign_ec_double(start_row: usize, curve: &ForeignCurve) -> (usize, Vec<Self>) {
+        let dummy = ForeignPoint::default();
+        let (gates, _) = circuit::ec_double::<F>(curve, start_row, &dummy);
+        (start_row + gates.len(), gates)
+    }
+
+    /// Create the gates of the multiplication `k * p` of a point of a foreign curve
+    /// by a scalar `k < 2^bits`
+    /// - Inputs: `p.x`, `p.y`, `k`
+    /// - Outputs tuple (next_row, circuit_gates) where
+    ///   - next_row      - next row after this gate
+    ///   - circuit_gates - vector of circuit gates comprising this gadget
+    pub fn create_foreign_ec_scalar_mul(
+        start_row: usize,
+        curve: &ForeignCurve,
+        bits: usize,
+    ) -> (usize, Vec<Self>) {
+        let dummy = ForeignPoint::default();
+        let (gates, _) =
+            circuit::ec_scalar_mul::<F>(curve, start_row, &dummy, &BigUint::default(), bits);
+        (start_row + gates.len(), gates)
+    }
+
+    /// Create the gates of the verification of an ECDSA signature `(r, s)`
+    /// of a message hash with a public key, on a foreign curve
+    /// - Inputs: `public_key.x`, `public_key.y`, `hash`, `r`, `s`
+    /// - Outputs tuple (next_row, circuit_gates) where
+    ///   - next_row      - next row after this gate
+    ///   - circuit_gates - vector of circuit gates comprising this gadget
+    pub fn create_ecdsa_verify(start_row: usize, curve: &ForeignCurve) -> (usize, Vec<Self>) {
+        let dummy = BigUint::default();
+        let (gates, _) = circuit::ecdsa_verify::<F>(
+            curve,
+            start_row,
+            &ForeignPoint::default(),
+            &dummy,
+            (&dummy, &dummy),
+        );
+        (start_row + gates.len(), gates)
+    }
+
+    /// Extend the circuit with the addition of two points of a foreign curve
+    pub fn extend_foreign_ec_add(
+        gates: &mut Vec<Self>,
+        curr_row: &mut usize,
+        curve: &ForeignCurve,
+    ) {
+        let (next_row, circuit_gates) = Self::create_foreign_ec_add(*curr_row, curve);
+        *curr_row = next_row;
+        gates.extend_from_slice(&circuit_gates);
+    }
+
+    /// Extend the circuit with the doubling of a point of a foreign curve
+    pub fn extend_foreign_ec_double(
+        gates: &mut Vec<Self>,
+        curr_row: &mut usize,
+        curve: &ForeignCurve,
+    ) {
+        let (next_row, circuit_gates) = Self::create_foreign_ec_double(*curr_row, curve);
+        *curr_row = next_row;
+        gates.extend_from_slice(&circuit_gates);
+    }
+
+    /// Extend the circuit with the multiplication of a point of a foreign curve by a scalar
+    pub fn extend_foreign_ec_scalar_mul(
+        gates: &mut Vec<Self>,
+        curr_row: &mut usize,
+        curve: &ForeignCurve,
+        bits: usize,
+    ) {
+        let (next_row, circuit_gates) = Self::create_foreign_ec_scalar_mul(*curr_row, curve, bits);
+        *curr_row = next_row;
+        gates.extend_from_slice(&circuit_gates);
+    }
+
+    /// Extend the circuit with the verification of an ECDSA signature
+    pub fn extend_ecdsa_verify(gates: &mut Vec<Self>, curr_row: &mut usize, curve: &ForeignCurve) {
+        let (next_row, circuit_gates) = Self::create_ecdsa_verify(*curr_row, curve);
+        *curr_row = next_row;
+        gates.extend_from_slice(&circuit_gates);
+    }
+}
@@ -0,0 +1,24 @@
+//! Foreign curve module
+//!
+//! Gadgets for the arithmetic of a short Weierstrass curve `y^2 = x^3 + b`
+//! over a foreign field, such as secp256k1, and for the verification of ECDSA
+//! signatures. They are made of foreign field multiplication and addition,
+//! range check and generic gates.
+//!
+//! Each gadget first range-checks its foreign field inputs, in the order of the
+//! arguments of its witness function: the limbs of the `i`-th input are in the
+//! first column of the rows `start_row + 4 * i` to `start_row + 4 * i + 2`.
+//! The coordinates of the resulting point, if any, are range-checked the same
+//! way in the last 8 rows of the gadget.
+//!
+//! The layout of a gadget does not depend on the values of its inputs, and the
+//! witness of a point at infinity is not supported: inputs, intermediate
+//! values and results of the operations must be points of the curve.
+//!
+//! The verification of a secp256k1 signature, as used by Ethereum, takes about
+//! 51000 rows, which fits in a domain of size `2^16`.
+
+mod circuit;
+pub mod curve;
+pub mod gadget;
+pub mod witness;
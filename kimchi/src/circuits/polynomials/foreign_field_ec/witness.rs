@@ -0,0 +1,53 @@
+//! This module computes the witness of the foreign curve gadgets.
+
+use crate::circuits::polynomial::COLUMNS;
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+
+use super::{
+    circuit,
+    curve::{ForeignCurve, ForeignPoint},
+};
+
+/// Create the witness of the addition of two points `p + q`
+pub fn create_add<F: PrimeField>(
+    curve: &ForeignCurve,
+    p: &ForeignPoint,
+    q: &ForeignPoint,
+) -> [Vec<F>; COLUMNS] {
+    circuit::ec_add(curve, 0, p, q).1
+}
+
+/// Create the witness of the doubling of a point `2p`
+pub fn create_double<F: PrimeField>(curve: &ForeignCurve, p: &ForeignPoint) -> [Vec<F>; COLUMNS] {
+    circuit::ec_double(curve, 0, p).1
+}
+
+/// Create the witness of the multiplication of a point by a scalar `k < 2^bits`
+pub fn create_scalar_mul<F: PrimeField>(
+    curve: &ForeignCurve,
+    p: &ForeignPoint,
+    k: &BigUint,
+    bits: usize,
+) -> [Vec<F>; COLUMNS] {
+    circuit::ec_scalar_mul(curve, 0, p, k, bits).1
+}
+
+/// Create the witness of the verification of the ECDSA signature `(r, s)`
+/// of a message hash, which is the integer of its big-endian bytes
+pub fn create_ecdsa_verify<F: PrimeField>(
+    curve: &ForeignCurve,
+    public_key: &ForeignPoint,
+    hash: &BigUint,
+    r: &BigUint,
+    s: &BigUint,
+) -> [Vec<F>; COLUMNS] {
+    circuit::ecdsa_verify(curve, 0, public_key, hash, (r, s)).1
+}
+
+/// Extend an existing witness with the one of a gadget
+pub fn extend<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS], gadget_witness: [Vec<F>; COLUMNS]) {
+    for (col, rows) in witness.iter_mut().zip(gadget_witness) {
+        col.extend(rows);
+    }
+}
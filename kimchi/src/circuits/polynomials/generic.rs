@@ -267,7 +267,7 @@ pub mod testing {
         pub fn verify_generic(
             &self,
             row: usize,
-            witness: &[Vec<F>; COLUMNS],
+            witness: &[Vec<F>],
             public: &[F],
         ) -> Result<(), String> {
             // assignments
@@ -89,7 +89,7 @@ where
     F: PrimeField,
 {
     const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::KeccakRound);
-    const CONSTRAINTS: u32 = 389;
+    const CONSTRAINTS: u32 = 485;
 
     // Constraints for one round of the Keccak permutation function
     fn constraint_checks<T: ExprOps<F, BerkeleyChallengeTerm>>(
@@ -239,9 +239,19 @@ where
             }
         } // END chi
 
-        // STEP iota: 4 constraints
+        // STEP iota: 4 * 5 * 5 = 100 constraints
         for (q, c) in rc.iter().enumerate() {
             constraints.push(state_g(0, 0, q) - (state_f[0][0][q].clone() + c.clone()));
+        }
+        for y in 0..DIM {
+            for x in 0..DIM {
+                if x == 0 && y == 0 {
+                    continue;
+                }
+                for q in 0..QUARTERS {
+                    constraints.push(state_g(y, x, q) - state_f[y][x][q].clone());
+                }
+            }
         } // END iota
 
         constraints
@@ -67,7 +67,7 @@ impl<F: PrimeField> CircuitGate<F> {
         if pad {
             // Check pad 0x01 (0x00 ... 0x00)* 0x80 or 0x81 if only one byte for padding
             for i in 0..pad_bytes {
-                coeffs[140 - i] = F::one(); // flag for padding
+                coeffs[139 - i] = F::one(); // flag for padding
                 if i == 0 {
                     coeffs[SPONGE_COEFFS - 1 - i] += F::from(0x80u8); // pad
                 }
@@ -1,4 +1,18 @@
 //! Keccak hash module
+//!
+//! The gates [circuitgates::KeccakRound] and [circuitgates::KeccakSponge] are
+//! laid out over [constants::KECCAK_COLS] witness columns, instead of the
+//! [crate::circuits::wires::COLUMNS] columns of the other gates, which is
+//! supported by the `W` parameter of the [crate::prover_index::ProverIndex],
+//! the [crate::proof::ProverProof] and the
+//! [crate::verifier_index::VerifierIndex]. Only the first
+//! [crate::circuits::wires::PERMUTS] columns can be wired.
+//!
+//! Their constraints can be checked on a witness, but the prover and the
+//! verifier reject them: the constraints do not range check the
+//! decompositions of the state into shifts and quarters, and do not tie the
+//! bytes of the digest in the squeeze row to the state, so a proof would say
+//! nothing about the hash.
 pub mod circuitgates;
 pub mod constants;
 pub mod gadget;
@@ -4,6 +4,7 @@ pub mod endomul_scalar;
 pub mod endosclmul;
 pub mod foreign_field_add;
 pub mod foreign_field_common;
+pub mod foreign_field_ec;
 pub mod foreign_field_mul;
 pub mod generic;
 pub mod keccak;
@@ -42,7 +42,7 @@ use crate::{
     circuits::{
         constraints::ConstraintSystem,
         polynomial::WitnessOverDomains,
-        wires::{Wire, COLUMNS, PERMUTS},
+        wires::{Wire, PERMUTS},
     },
     curve::KimchiCurve,
     error::ProverError,
@@ -193,8 +193,12 @@ where
     }
 }
 
-impl<F: PrimeField, G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>
-    ProverIndex<G, OpeningProof>
+impl<
+        F: PrimeField,
+        G: KimchiCurve<ScalarField = F>,
+        OpeningProof: OpenProof<G>,
+        const W: usize,
+    > ProverIndex<G, OpeningProof, W>
 {
     /// permutation quotient poly contribution computation
     ///
@@ -208,7 +212,7 @@ impl<F: PrimeField, G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>
     #[allow(clippy::type_complexity)]
     pub fn perm_quot(
         &self,
-        lagrange: &WitnessOverDomains<F>,
+        lagrange: &WitnessOverDomains<F, W>,
         beta: F,
         gamma: F,
         z: &DensePolynomial<F>,
@@ -331,7 +335,7 @@ impl<F: PrimeField, G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>
     /// permutation linearization poly contribution computation
     pub fn perm_lnrz(
         &self,
-        e: &ProofEvaluations<PointEvaluations<F>>,
+        e: &ProofEvaluations<PointEvaluations<F>, W>,
         zeta: F,
         beta: F,
         gamma: F,
@@ -360,8 +364,8 @@ impl<F: PrimeField, G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>
 }
 
 impl<F: PrimeField> ConstraintSystem<F> {
-    pub fn perm_scalars(
-        e: &ProofEvaluations<PointEvaluations<F>>,
+    pub fn perm_scalars<const W: usize>(
+        e: &ProofEvaluations<PointEvaluations<F>, W>,
         beta: F,
         gamma: F,
         mut alphas: impl Iterator<Item = F>,
@@ -401,8 +405,12 @@ impl<F: PrimeField> ConstraintSystem<F> {
     }
 }
 
-impl<F: PrimeField, G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>
-    ProverIndex<G, OpeningProof>
+impl<
+        F: PrimeField,
+        G: KimchiCurve<ScalarField = F>,
+        OpeningProof: OpenProof<G>,
+        const W: usize,
+    > ProverIndex<G, OpeningProof, W>
 {
     /// permutation aggregation polynomial computation
     ///
@@ -415,7 +423,7 @@ impl<F: PrimeField, G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>
     /// Will panic if `first element` is not 1.
     pub fn perm_aggreg(
         &self,
-        witness: &[Vec<F>; COLUMNS],
+        witness: &[Vec<F>; W],
         beta: &F,
         gamma: &F,
         rng: &mut (impl RngCore + CryptoRng),
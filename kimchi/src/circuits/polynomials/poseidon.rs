@@ -142,7 +142,7 @@ impl<F: PrimeField> CircuitGate<F> {
         &self,
         row: usize,
         // TODO(mimoo): we should just pass two rows instead of the whole witness
-        witness: &[Vec<F>; COLUMNS],
+        witness: &[Vec<F>],
     ) -> Result<(), String> {
         ensure_eq!(
             self.typ,
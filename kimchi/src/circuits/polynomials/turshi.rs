@@ -77,6 +77,30 @@
 //!- op0: content of first operand of right part
 //!- op1: content of second operand of right part
 //!- res: result of the operation in the right part
+//!
+//!The memory is proven to be a read-only function of the addresses with a
+//!runtime lookup table containing a single value per address (see
+//![cairo_memory_table_cfg]). Each instruction looks up the pairs of address
+//!and value of the instruction itself, of the destination and of the operands
+//!in the table, and the public memory (the program, the arguments of the entry
+//!point and the output, given in the public input) is looked up in the same
+//!table by the circuit of [CircuitGate::create_cairo_circuit].
+//!
+//!The same circuit constrains the content of the builtin segments (see
+//![CairoBuiltinSegment]). The pointers to each segment given to the entry
+//!point and returned by it bound the segment, whose instances are looked up in
+//!the memory table and checked by other gates of kimchi:
+//!- `range_check`: each value is the sum of two limbs of 64 bits, checked by
+//!  `RangeCheck0` gates;
+//!- `bitwise`: the exclusive or of the inputs is checked by `Xor16` gates,
+//!  which also bound the inputs, and the conjunction and disjunction are
+//!  deduced from it with generic gates;
+//!- `pedersen`: the points of each input are computed by `VarBaseMul` gates and
+//!  added by `CompleteAdd` gates;
+//!- `poseidon`: the permutation is computed by `Poseidon` gates.
+//!
+//!The `output` segment is part of the public memory, so only its bounds are
+//!checked.
 
 use crate::{
     alphas::Alphas,
@@ -85,16 +109,27 @@ use crate::{
         berkeley_columns::{BerkeleyChallengeTerm, BerkeleyChallenges, Column, E},
         constraints::ConstraintSystem,
         expr::{self, constraints::ExprOps, Cache},
-        gate::{CircuitGate, GateType},
+        gate::{CircuitGate, Connect, GateType},
+        lookup::{
+            runtime_tables::{RuntimeTable, RuntimeTableCfg},
+            tables::CAIRO_MEMORY_TABLE_ID,
+        },
+        polynomials::{generic::GenericGateSpec, poseidon::POS_ROWS_PER_HASH, xor::num_xors},
         wires::{GateWires, Wire, COLUMNS},
     },
     curve::KimchiCurve,
     proof::ProofEvaluations,
 };
+use ark_ec::CurveGroup;
 use ark_ff::{FftField, Field, PrimeField};
 use log::error;
+use o1_utils::{field_helpers::i32_to_field, FieldHelpers};
 use std::{array, marker::PhantomData};
 use turshi::{
+    builtins::{
+        pedersen_points, CairoBuiltin, CairoField, BITWISE_BITS, PEDERSEN_BITS, PEDERSEN_LOW_BITS,
+        POSEIDON_WIDTH,
+    },
     runner::{CairoInstruction, CairoProgram, Pointers},
     word::{FlagBits, Offsets},
 };
@@ -102,6 +137,74 @@ use turshi::{
 const NUM_FLAGS: usize = 16;
 pub const CIRCUIT_GATE_COUNT: usize = 4;
 
+/// Number of public inputs of the circuit of [CircuitGate::create_cairo_circuit]
+/// before the public memory: the initial and final program counters and
+/// allocation pointers, and the ID of the memory table
+const PUBLIC_HEADER_LEN: usize = 5;
+
+/// Number of bits of the limbs checked by the `RangeCheck0` gates of the
+/// builtins
+const LIMB_BITS: usize = 64;
+
+/// Number of bits of the high part of the inputs of the `pedersen` builtin
+const PEDERSEN_HIGH_BITS: usize = PEDERSEN_BITS - PEDERSEN_LOW_BITS;
+
+/// A cell of the circuit, as a row and a column
+type Cell = (usize, usize);
+
+/// A builtin segment of the memory of a Cairo program, whose content is
+/// constrained by the circuit of [CircuitGate::create_cairo_circuit]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CairoBuiltinSegment {
+    /// The builtin using the segment
+    pub builtin: CairoBuiltin,
+    /// The index in the public memory of the argument of the entry point
+    /// pointing to the segment
+    pub pointer: usize,
+    /// The number of instances of the builtin in the segment
+    pub instances: usize,
+}
+
+/// Returns the builtin segments of an execution of a Cairo program, in the
+/// order of the arguments of its entry point
+///
+/// # Panics
+///
+/// Will panic if the pointer to a segment is not in the public memory.
+pub fn cairo_builtin_segments<F: Field>(prog: &CairoProgram<F>) -> Vec<CairoBuiltinSegment> {
+    let public_memory = prog.public_memory();
+    let codelen = prog.mem.get_codelen() as u64;
+    prog.mem
+        .builtin_segments()
+        .into_iter()
+        .enumerate()
+        .map(|(i, (builtin, _, size))| {
+            // the pointers are the first arguments, right after the bytecode
+            let address = F::from(codelen + 1 + i as u64);
+            let pointer = public_memory
+                .iter()
+                .position(|(addr, _)| *addr == address)
+                .expect("pointer to a builtin segment out of the public memory");
+            CairoBuiltinSegment {
+                builtin,
+                pointer,
+                instances: (size / builtin.cells_per_instance()) as usize,
+            }
+        })
+        .collect()
+}
+
+/// Returns the configuration of the runtime table containing the `size` cells
+/// of the memory of a Cairo program (after the dummy cell at address 0),
+/// indexed by their address. As each address appears once in the table, the
+/// lookups into the table prove that the memory is a function.
+pub fn cairo_memory_table_cfg<F: Field>(size: usize) -> RuntimeTableCfg<F> {
+    RuntimeTableCfg {
+        id: CAIRO_MEMORY_TABLE_ID,
+        first_column: (1..=size as u64).map(F::from).collect(),
+    }
+}
+
 // GATE-RELATED
 
 impl<F: PrimeField> CircuitGate<F> {
@@ -253,8 +356,697 @@ impl<F: PrimeField> CircuitGate<F> {
     }
 }
 
+impl<F: CairoField> CircuitGate<F> {
+    /// Creates the circuit proving an execution of `num` instructions of a
+    /// Cairo program with `public_memory` entries of public memory and the
+    /// segments `builtins` (see [cairo_builtin_segments]), to be used with the
+    /// memory table of [cairo_memory_table_cfg].
+    /// Returns the gates of the circuit and its number of public inputs (see
+    /// [witness::cairo_public_input]).
+    pub fn create_cairo_circuit(
+        num: usize,
+        public_memory: usize,
+        builtins: &[CairoBuiltinSegment],
+    ) -> (Vec<Self>, usize) {
+        // 0..5: 1 row per public input for the initial and final pointers and the table ID
+        // 5..5+2k: 2 rows per entry of the public memory, for its address and its value
+        // ...: 1 row per 3 entries of the public memory for their lookups
+        // ...: the rows of the Cairo gadget
+        // ...: the rows of the builtin segments
+        let public = PUBLIC_HEADER_LEN + 2 * public_memory;
+        let mut gates: Vec<Self> = (0..public)
+            .map(|row| {
+                CircuitGate::create_generic_gadget(Wire::for_row(row), GenericGateSpec::Pub, None)
+            })
+            .collect();
+        let lookups = push_memory_lookups(&mut gates, public_memory);
+        for (entry, (addr, value)) in lookups.into_iter().enumerate() {
+            let row = PUBLIC_HEADER_LEN + 2 * entry;
+            gates.connect_cell_pair((row, 0), addr);
+            gates.connect_cell_pair((row + 1, 0), value);
+        }
+        let claim_row = gates.len();
+        let (cairo, _) = CircuitGate::create_cairo_gadget(claim_row, num);
+        gates.extend(cairo);
+        // the claimed pointers are public
+        for col in 0..4 {
+            gates.connect_cell_pair((col, 0), (claim_row, col));
+        }
+        if !builtins.is_empty() {
+            Self::extend_cairo_builtins(&mut gates, builtins);
+        }
+        (gates, public)
+    }
+
+    /// Appends the rows constraining the builtin segments to the circuit of
+    /// [CircuitGate::create_cairo_circuit]
+    fn extend_cairo_builtins(gates: &mut Vec<Self>, builtins: &[CairoBuiltinSegment]) {
+        // 0: 1 row for a cell constrained to zero
+        // ...: 2 rows per point of the pedersen builtin for the point and its double
+        // then for each segment:
+        // - 1 row for its bounds and 1 row for the lookup of its final pointer
+        // - the rows of its instances
+        let zero = (
+            push_generic(gates, GenericGateSpec::Const(F::zero()), None),
+            0,
+        );
+        let points: Vec<[[Cell; 2]; 2]> =
+            if builtins.iter().any(|s| s.builtin == CairoBuiltin::Pedersen) {
+                pedersen_points::<F>()
+                    .iter()
+                    .map(|point| {
+                        let double = (*point + point).into_affine();
+                        [point, &double].map(|p| {
+                            let row = push_generic(
+                                gates,
+                                GenericGateSpec::Const(p.x),
+                                Some(GenericGateSpec::Const(p.y)),
+                            );
+                            [(row, 0), (row, 3)]
+                        })
+                    })
+                    .collect()
+            } else {
+                vec![]
+            };
+        // the final allocation pointer is public
+        let fin_ap = (3, 0);
+        let count = builtins.len() as u64;
+        for (i, segment) in builtins.iter().enumerate() {
+            let cells = segment.builtin.cells_per_instance();
+            let size = segment.instances as u64 * cells;
+            // the segment starts at the pointer given to the entry point, and
+            // ends at the pointer it returns among its last values
+            let row = push_generic(
+                gates,
+                GenericGateSpec::Plus(F::from(size)),
+                Some(GenericGateSpec::Plus(F::from(i as u64) - F::from(count))),
+            );
+            let pointer = PUBLIC_HEADER_LEN + 2 * segment.pointer + 1;
+            gates.connect_cell_pair((pointer, 0), (row, 0));
+            gates.connect_cell_pair(fin_ap, (row, 3));
+            let (addr, value) = push_memory_lookups(gates, 1)[0];
+            gates.connect_cell_pair((row, 5), addr);
+            gates.connect_cell_pair((row, 2), value);
+            if segment.builtin == CairoBuiltin::Output {
+                continue;
+            }
+            let base = (row, 0);
+            for instance in 0..segment.instances as u64 {
+                let addresses =
+                    push_addresses(gates, base, instance * cells..(instance + 1) * cells);
+                let lookups = push_memory_lookups(gates, addresses.len());
+                let mut values = vec![];
+                for (address, (addr, value)) in addresses.into_iter().zip(lookups) {
+                    gates.connect_cell_pair(address, addr);
+                    values.push(value);
+                }
+                match segment.builtin {
+                    CairoBuiltin::Output => (),
+                    CairoBuiltin::RangeCheck => push_range_check(gates, values[0], zero),
+                    CairoBuiltin::Bitwise => push_bitwise(gates, &values, zero),
+                    CairoBuiltin::Pedersen => push_pedersen(gates, &values, &points, zero),
+                    CairoBuiltin::Poseidon => push_poseidon(gates, &values),
+                }
+            }
+        }
+    }
+}
+
+/// Appends a row of generic gates to the circuit, and returns its row
+fn push_generic<F: PrimeField>(
+    gates: &mut Vec<CircuitGate<F>>,
+    gate1: GenericGateSpec<F>,
+    gate2: Option<GenericGateSpec<F>>,
+) -> usize {
+    let row = gates.len();
+    gates.push(CircuitGate::create_generic_gadget(
+        Wire::for_row(row),
+        gate1,
+        gate2,
+    ));
+    row
+}
+
+/// Appends the rows of `count` lookups into the memory table, 3 per row, and
+/// returns the cells of the address and of the value of each lookup. The
+/// unused lookups of the last row are left unconstrained.
+fn push_memory_lookups<F: PrimeField>(
+    gates: &mut Vec<CircuitGate<F>>,
+    count: usize,
+) -> Vec<(Cell, Cell)> {
+    let mut lookups = vec![];
+    for i in 0..count {
+        if i % 3 == 0 {
+            let row = gates.len();
+            gates.push(CircuitGate::new(
+                GateType::Lookup,
+                Wire::for_row(row),
+                vec![],
+            ));
+            // all the lookups use the table ID given in the public input
+            gates.connect_cell_pair((PUBLIC_HEADER_LEN - 1, 0), (row, 0));
+        }
+        let (row, j) = (gates.len() - 1, i % 3);
+        lookups.push(((row, 2 * j + 1), (row, 2 * j + 2)));
+    }
+    lookups
+}
+
+/// Appends the rows computing the addresses at `offsets` from the address in
+/// the cell `base`, 2 per row, and returns their cells
+fn push_addresses<F: PrimeField>(
+    gates: &mut Vec<CircuitGate<F>>,
+    base: Cell,
+    offsets: std::ops::Range<u64>,
+) -> Vec<Cell> {
+    let offsets: Vec<u64> = offsets.collect();
+    let mut addresses = vec![];
+    for pair in offsets.chunks(2) {
+        let row = push_generic(
+            gates,
+            GenericGateSpec::Plus(F::from(pair[0])),
+            pair.get(1)
+                .map(|offset| GenericGateSpec::Plus(F::from(*offset))),
+        );
+        for j in 0..pair.len() {
+            gates.connect_cell_pair(base, (row, 3 * j));
+            addresses.push((row, 3 * j + 2));
+        }
+    }
+    addresses
+}
+
+/// Appends a `RangeCheck0` gate checking that the value of the cell `value`
+/// has 64 bits, its two most significant limbs being copied from `zero`
+fn push_range_check_64<F: PrimeField>(gates: &mut Vec<CircuitGate<F>>, value: Cell, zero: Cell) {
+    let (_, range_check) = CircuitGate::create_range_check(gates.len());
+    let row = gates.len();
+    gates.extend(range_check);
+    gates.connect_cell_pair(value, (row, 0));
+    gates.connect_cell_pair(zero, (row, 1));
+    gates.connect_cell_pair(zero, (row, 2));
+}
+
+/// Appends the rows checking an instance of the `range_check` builtin: its
+/// value is the sum of two limbs of 64 bits
+fn push_range_check<F: PrimeField>(gates: &mut Vec<CircuitGate<F>>, value: Cell, zero: Cell) {
+    let row = push_generic(
+        gates,
+        GenericGateSpec::Add {
+            left_coeff: None,
+            right_coeff: Some(F::two_pow(LIMB_BITS as u64)),
+            output_coeff: None,
+        },
+        None,
+    );
+    gates.connect_cell_pair(value, (row, 2));
+    push_range_check_64(gates, (row, 0), zero);
+    push_range_check_64(gates, (row, 1), zero);
+}
+
+/// Returns the factor bringing the most significant chunk of 16 bits of the
+/// inputs of the `bitwise` builtin to the top of a limb of 64 bits, so that
+/// the range check of the product bounds the inputs
+fn bitwise_top_shift<F: PrimeField>() -> F {
+    let top_bits = BITWISE_BITS - 16 * (num_xors(BITWISE_BITS) - 1);
+    F::two_pow((LIMB_BITS - top_bits) as u64)
+}
+
+/// Appends the rows checking an instance `x, y, x & y, x ^ y, x | y` of the
+/// `bitwise` builtin. The exclusive or is checked by the `Xor16` gates, and
+/// the other operations follow from `x + y = (x ^ y) + 2 (x & y)` and
+/// `x + y = 2 (x | y) - (x ^ y)`.
+fn push_bitwise<F: PrimeField>(gates: &mut Vec<CircuitGate<F>>, values: &[Cell], zero: Cell) {
+    let (x, y, and, xor, or) = (values[0], values[1], values[2], values[3], values[4]);
+    let add = GenericGateSpec::Add {
+        left_coeff: None,
+        right_coeff: None,
+        output_coeff: None,
+    };
+    let sum = push_generic(gates, add, None);
+    gates.connect_cell_pair(x, (sum, 0));
+    gates.connect_cell_pair(y, (sum, 1));
+    let ops = push_generic(
+        gates,
+        GenericGateSpec::Add {
+            left_coeff: None,
+            right_coeff: Some(-F::one()),
+            output_coeff: Some(-F::from(2u64)),
+        },
+        Some(GenericGateSpec::Add {
+            left_coeff: None,
+            right_coeff: None,
+            output_coeff: Some(-F::from(2u64)),
+        }),
+    );
+    for col in [0, 3] {
+        gates.connect_cell_pair((sum, 2), (ops, col));
+        gates.connect_cell_pair(xor, (ops, col + 1));
+    }
+    gates.connect_cell_pair(and, (ops, 2));
+    gates.connect_cell_pair(or, (ops, 5));
+
+    let (_, xor_gates) = CircuitGate::create_xor_gadget(gates.len(), BITWISE_BITS);
+    let first = gates.len();
+    gates.extend(xor_gates);
+    let (last, zero_row) = (gates.len() - 2, gates.len() - 1);
+    gates.connect_cell_pair(x, (first, 0));
+    gates.connect_cell_pair(y, (first, 1));
+    gates.connect_cell_pair(xor, (first, 2));
+    gates.connect_cell_pair((zero_row, 0), (zero_row, 1));
+    gates.connect_cell_pair((zero_row, 0), (zero_row, 2));
+
+    // the most significant chunks of the inputs are bounded by range checks
+    let top = GenericGateSpec::Add {
+        left_coeff: Some(bitwise_top_shift()),
+        right_coeff: Some(F::zero()),
+        output_coeff: None,
+    };
+    let shifted = push_generic(gates, top.clone(), Some(top));
+    gates.connect_cell_pair((last, 0), (shifted, 0));
+    gates.connect_cell_pair((last, 1), (shifted, 3));
+    push_range_check_64(gates, (shifted, 2), zero);
+    push_range_check_64(gates, (shifted, 5), zero);
+}
+
+/// Appends the rows checking an instance of the `poseidon` builtin, whose
+/// input state is permuted by `Poseidon` gates into its output state
+fn push_poseidon<F: CairoField>(gates: &mut Vec<CircuitGate<F>>, values: &[Cell]) {
+    let row = gates.len();
+    let last = row + POS_ROWS_PER_HASH;
+    let (poseidon, _) = CircuitGate::create_poseidon_gadget(
+        row,
+        [Wire::for_row(row), Wire::for_row(last)],
+        &F::poseidon_params().round_constants,
+    );
+    gates.extend(poseidon);
+    for i in 0..POSEIDON_WIDTH {
+        gates.connect_cell_pair(values[i], (row, i));
+        gates.connect_cell_pair(values[POSEIDON_WIDTH + i], (last, i));
+    }
+}
+
+/// Appends the rows computing the multiplication of the point `base` by a
+/// scalar of `bits` bits with `VarBaseMul` gates, starting from the double of
+/// the point `double` (see [pedersen_points]). Returns the cells of the scalar
+/// and of the result.
+fn push_scalar_mul<F: PrimeField>(
+    gates: &mut Vec<CircuitGate<F>>,
+    base: [Cell; 2],
+    double: [Cell; 2],
+    bits: usize,
+    zero: Cell,
+) -> (Cell, [Cell; 2]) {
+    let chunks = (bits + 4) / 5;
+    let first = gates.len();
+    for chunk in 0..chunks {
+        let row = gates.len();
+        gates.extend(CircuitGate::create_vbmul(&[
+            Wire::for_row(row),
+            Wire::for_row(row + 1),
+        ]));
+        gates.connect_cell_pair(base[0], (row, 0));
+        gates.connect_cell_pair(base[1], (row, 1));
+        if chunk == 0 {
+            gates.connect_cell_pair(double[0], (row, 2));
+            gates.connect_cell_pair(double[1], (row, 3));
+            gates.connect_cell_pair(zero, (row, 4));
+        } else {
+            // the accumulator and the scalar of the previous chunk
+            gates.connect_cell_pair((row - 1, 0), (row, 2));
+            gates.connect_cell_pair((row - 1, 1), (row, 3));
+            gates.connect_cell_pair((row - 2, 5), (row, 4));
+        }
+    }
+    // the most significant bits of the first chunk are not part of the scalar
+    for col in 2..2 + 5 * chunks - bits {
+        gates.connect_cell_pair(zero, (first + 1, col));
+    }
+    let last = gates.len() - 2;
+    ((last, 5), [(last + 1, 0), (last + 1, 1)])
+}
+
+/// Appends a `CompleteAdd` gate adding the points `left` and `right`, whose
+/// sum is not the point at infinity, and returns the cells of the sum
+fn push_complete_add<F: PrimeField>(
+    gates: &mut Vec<CircuitGate<F>>,
+    left: [Cell; 2],
+    right: [Cell; 2],
+    zero: Cell,
+) -> [Cell; 2] {
+    let row = gates.len();
+    gates.push(CircuitGate::new(
+        GateType::CompleteAdd,
+        Wire::for_row(row),
+        vec![],
+    ));
+    gates.connect_cell_pair(left[0], (row, 0));
+    gates.connect_cell_pair(left[1], (row, 1));
+    gates.connect_cell_pair(right[0], (row, 2));
+    gates.connect_cell_pair(right[1], (row, 3));
+    gates.connect_cell_pair(zero, (row, 6));
+    [(row, 4), (row, 5)]
+}
+
+/// Appends the rows checking an instance `x, y, H(x, y)` of the `pedersen`
+/// builtin, given the cells of the `points` of the hash and of their doubles.
+/// Each input is split into its low and high bits, which are the scalars of
+/// the multiplications of two points, and the hash is the x-coordinate of the
+/// sum of the 4 products.
+fn push_pedersen<F: PrimeField>(
+    gates: &mut Vec<CircuitGate<F>>,
+    values: &[Cell],
+    points: &[[[Cell; 2]; 2]],
+    zero: Cell,
+) {
+    let split = GenericGateSpec::Add {
+        left_coeff: None,
+        right_coeff: Some(F::two_pow(PEDERSEN_LOW_BITS as u64)),
+        output_coeff: None,
+    };
+    let row = push_generic(gates, split.clone(), Some(split));
+    gates.connect_cell_pair(values[0], (row, 2));
+    gates.connect_cell_pair(values[1], (row, 5));
+    let mut sum: Option<[Cell; 2]> = None;
+    for j in 0..2 {
+        let parts = [
+            ((row, 3 * j), PEDERSEN_LOW_BITS),
+            ((row, 3 * j + 1), PEDERSEN_HIGH_BITS),
+        ];
+        for (k, (part, bits)) in parts.into_iter().enumerate() {
+            let [point, double] = points[2 * j + k];
+            let (scalar, product) = push_scalar_mul(gates, point, double, bits, zero);
+            gates.connect_cell_pair(scalar, part);
+            sum = Some(match sum {
+                None => product,
+                Some(sum) => push_complete_add(gates, sum, product, zero),
+            });
+        }
+    }
+    let [hash, _] = sum.expect("the hash sums 4 points");
+    gates.connect_cell_pair(values[2], hash);
+}
+
 pub mod witness {
     use super::*;
+    use crate::circuits::polynomials::{poseidon, range_check, varbasemul, xor};
+    use num_bigint::BigUint;
+    use num_traits::One;
+
+    /// Returns the public input of the circuit of
+    /// [CircuitGate::create_cairo_circuit], given the initial and final
+    /// pointers of an execution and its public memory (see
+    /// [CairoProgram::public_memory])
+    pub fn cairo_public_input<F: Field>(
+        ini: &impl Pointers<F>,
+        fin: &impl Pointers<F>,
+        public_memory: &[(F, F)],
+    ) -> Vec<F> {
+        let mut public = vec![
+            ini.pc(),
+            ini.ap(),
+            fin.pc(),
+            fin.ap(),
+            i32_to_field(CAIRO_MEMORY_TABLE_ID),
+        ];
+        public.extend(
+            public_memory
+                .iter()
+                .flat_map(|(addr, value)| [*addr, *value]),
+        );
+        public
+    }
+
+    /// Returns the witness of the circuit of [CircuitGate::create_cairo_circuit]
+    /// for an execution of a Cairo program
+    pub fn cairo_circuit_witness<F: CairoField>(prog: &CairoProgram<F>) -> [Vec<F>; COLUMNS] {
+        let public_memory = prog.public_memory();
+        let public = cairo_public_input(&prog.ini(), &prog.fin(), &public_memory);
+        let table_id = public[PUBLIC_HEADER_LEN - 1];
+
+        let mut witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![F::zero(); public.len()]);
+        witness[0].clone_from(&public);
+        memory_lookups_witness(&mut witness, table_id, &public_memory);
+        append_rows(&mut witness, cairo_witness(prog));
+        append_rows(&mut witness, builtins_witness(prog, table_id));
+        witness
+    }
+
+    /// Returns the witness of the rows constraining the builtin segments in
+    /// the circuit of [CircuitGate::create_cairo_circuit]
+    fn builtins_witness<F: CairoField>(prog: &CairoProgram<F>, table_id: F) -> [Vec<F>; COLUMNS] {
+        let mut witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![]);
+        let segments = prog.mem.builtin_segments();
+        if segments.is_empty() {
+            return witness;
+        }
+        // the cell constrained to zero
+        push_row(&mut witness, &[]);
+        let points: Vec<[(F, F); 2]> = if segments
+            .iter()
+            .any(|(builtin, _, _)| *builtin == CairoBuiltin::Pedersen)
+        {
+            pedersen_points::<F>()
+                .iter()
+                .map(|point| {
+                    let double = (*point + point).into_affine();
+                    [point, &double].map(|p| {
+                        push_row(&mut witness, &[p.x, F::zero(), F::zero(), p.y]);
+                        (p.x, p.y)
+                    })
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+        let fin_ap = prog.fin().ap();
+        let count = segments.len() as u64;
+        for (i, (builtin, base, size)) in segments.into_iter().enumerate() {
+            let stop = base + F::from(size);
+            let ret = fin_ap + F::from(i as u64) - F::from(count);
+            push_row(
+                &mut witness,
+                &[base, F::zero(), stop, fin_ap, F::zero(), ret],
+            );
+            memory_lookups_witness(&mut witness, table_id, &[(ret, stop)]);
+            if builtin == CairoBuiltin::Output {
+                continue;
+            }
+            let cells = builtin.cells_per_instance();
+            for offset in (0..size).step_by(cells as usize) {
+                let addresses = addresses_witness(&mut witness, base, offset..offset + cells);
+                let entries: Vec<(F, F)> = addresses
+                    .into_iter()
+                    .map(|addr| (addr, prog.mem[addr].map_or(F::zero(), |word| word.word())))
+                    .collect();
+                memory_lookups_witness(&mut witness, table_id, &entries);
+                let values: Vec<F> = entries.into_iter().map(|(_, value)| value).collect();
+                match builtin {
+                    CairoBuiltin::Output => (),
+                    CairoBuiltin::RangeCheck => range_check_witness(&mut witness, values[0]),
+                    CairoBuiltin::Bitwise => bitwise_witness(&mut witness, &values),
+                    CairoBuiltin::Pedersen => pedersen_witness(&mut witness, &values, &points),
+                    CairoBuiltin::Poseidon => poseidon_witness(&mut witness, &values),
+                }
+            }
+        }
+        witness
+    }
+
+    /// Appends a row to the witness, starting with `cells` and completed with
+    /// zeros
+    fn push_row<F: Field>(witness: &mut [Vec<F>; COLUMNS], cells: &[F]) {
+        for (col, column) in witness.iter_mut().enumerate() {
+            column.push(cells.get(col).copied().unwrap_or_else(F::zero));
+        }
+    }
+
+    /// Appends the rows of `rows` to the witness
+    fn append_rows<F: Field>(witness: &mut [Vec<F>; COLUMNS], rows: [Vec<F>; COLUMNS]) {
+        for (column, cells) in witness.iter_mut().zip(rows) {
+            column.extend(cells);
+        }
+    }
+
+    /// Appends the rows of the lookups of `entries` into the memory table
+    fn memory_lookups_witness<F: Field>(
+        witness: &mut [Vec<F>; COLUMNS],
+        table_id: F,
+        entries: &[(F, F)],
+    ) {
+        for chunk in entries.chunks(3) {
+            let mut row = vec![table_id];
+            for j in 0..3 {
+                // the unused lookups of the last row look up the first entry
+                let (addr, value) = chunk.get(j).unwrap_or(&chunk[0]);
+                row.extend([*addr, *value]);
+            }
+            push_row(witness, &row);
+        }
+    }
+
+    /// Appends the rows computing the addresses at `offsets` from `base`, and
+    /// returns the addresses
+    fn addresses_witness<F: Field>(
+        witness: &mut [Vec<F>; COLUMNS],
+        base: F,
+        offsets: std::ops::Range<u64>,
+    ) -> Vec<F> {
+        let addresses: Vec<F> = offsets.map(|offset| base + F::from(offset)).collect();
+        for pair in addresses.chunks(2) {
+            let row: Vec<F> = pair
+                .iter()
+                .flat_map(|addr| [base, F::zero(), *addr])
+                .collect();
+            push_row(witness, &row);
+        }
+        addresses
+    }
+
+    /// Appends the rows of an instance of the `range_check` builtin
+    fn range_check_witness<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS], value: F) {
+        let value: BigUint = value.into();
+        let low = &value & ((BigUint::one() << LIMB_BITS) - 1u32);
+        let (low, high) = (F::from(low), F::from(value >> LIMB_BITS));
+        push_row(
+            witness,
+            &[low, high, low + high * F::two_pow(LIMB_BITS as u64)],
+        );
+        range_check::witness::extend_single(witness, low);
+        range_check::witness::extend_single(witness, high);
+    }
+
+    /// Appends the rows of an instance of the `bitwise` builtin
+    fn bitwise_witness<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS], values: &[F]) {
+        let (x, y, and, xor, or) = (values[0], values[1], values[2], values[3], values[4]);
+        push_row(witness, &[x, y, x + y]);
+        push_row(witness, &[x + y, xor, and, x + y, xor, or]);
+        let xor_rows = xor::create_xor_witness(x, y, BITWISE_BITS);
+        let last = num_xors(BITWISE_BITS) - 1;
+        let shift = bitwise_top_shift::<F>();
+        let (x_top, y_top) = (shift * xor_rows[0][last], shift * xor_rows[1][last]);
+        let (x_last, y_last) = (xor_rows[0][last], xor_rows[1][last]);
+        append_rows(witness, xor_rows);
+        push_row(
+            witness,
+            &[x_last, F::zero(), x_top, y_last, F::zero(), y_top],
+        );
+        range_check::witness::extend_single(witness, x_top);
+        range_check::witness::extend_single(witness, y_top);
+    }
+
+    /// Appends the rows of an instance of the `poseidon` builtin
+    fn poseidon_witness<F: CairoField>(witness: &mut [Vec<F>; COLUMNS], values: &[F]) {
+        let mut rows: [Vec<F>; COLUMNS] =
+            array::from_fn(|_| vec![F::zero(); POS_ROWS_PER_HASH + 1]);
+        let input = array::from_fn(|i| values[i]);
+        poseidon::generate_witness(0, F::poseidon_params(), &mut rows, input);
+        append_rows(witness, rows);
+    }
+
+    /// Appends the rows of the multiplication of `base` by `scalar`, of `bits`
+    /// bits, starting from `double`, and returns the product
+    fn scalar_mul_witness<F: PrimeField>(
+        witness: &mut [Vec<F>; COLUMNS],
+        base: (F, F),
+        double: (F, F),
+        scalar: &BigUint,
+        bits: usize,
+    ) -> (F, F) {
+        let chunks = (bits + 4) / 5;
+        let bits_msb: Vec<bool> = (0..5 * chunks as u64)
+            .rev()
+            .map(|i| scalar.bit(i))
+            .collect();
+        let mut rows: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![F::zero(); 2 * chunks]);
+        let product = varbasemul::witness(&mut rows, 0, base, &bits_msb, double);
+        append_rows(witness, rows);
+        product.acc
+    }
+
+    /// Appends the row of the addition of `left` and `right`, and returns
+    /// their sum
+    fn complete_add_witness<F: Field>(
+        witness: &mut [Vec<F>; COLUMNS],
+        (x1, y1): (F, F),
+        (x2, y2): (F, F),
+    ) -> (F, F) {
+        let same_x = x1 == x2;
+        let s = if same_x {
+            x1.square() * F::from(3u64) / y1.double()
+        } else {
+            (y2 - y1) / (x2 - x1)
+        };
+        let x3 = s.square() - x1 - x2;
+        let y3 = s * (x1 - x3) - y1;
+        let x21_inv = (x2 - x1).inverse().unwrap_or_else(F::zero);
+        push_row(
+            witness,
+            &[
+                x1,
+                y1,
+                x2,
+                y2,
+                x3,
+                y3,
+                F::zero(),
+                F::from(u64::from(same_x)),
+                s,
+                F::zero(),
+                x21_inv,
+            ],
+        );
+        (x3, y3)
+    }
+
+    /// Appends the rows of an instance of the `pedersen` builtin, given the
+    /// `points` of the hash and their doubles
+    fn pedersen_witness<F: PrimeField>(
+        witness: &mut [Vec<F>; COLUMNS],
+        values: &[F],
+        points: &[[(F, F); 2]],
+    ) {
+        let parts: Vec<(BigUint, BigUint)> = values[..2]
+            .iter()
+            .map(|input| {
+                let input: BigUint = (*input).into();
+                let low = &input & ((BigUint::one() << PEDERSEN_LOW_BITS) - 1u32);
+                (low, input >> PEDERSEN_LOW_BITS)
+            })
+            .collect();
+        let row: Vec<F> = parts
+            .iter()
+            .zip(&values[..2])
+            .flat_map(|((low, high), input)| [F::from(low.clone()), F::from(high.clone()), *input])
+            .collect();
+        push_row(witness, &row);
+        let mut sum = None;
+        for (j, (low, high)) in parts.iter().enumerate() {
+            let parts = [(low, PEDERSEN_LOW_BITS), (high, PEDERSEN_HIGH_BITS)];
+            for (k, (scalar, bits)) in parts.into_iter().enumerate() {
+                let [point, double] = points[2 * j + k];
+                let product = scalar_mul_witness(witness, point, double, scalar, bits);
+                sum = Some(match sum {
+                    None => product,
+                    Some(sum) => complete_add_witness(witness, sum, product),
+                });
+            }
+        }
+    }
+
+    /// Returns the content of the memory table of [cairo_memory_table_cfg]
+    /// after the execution of a Cairo program. The cells that were not written
+    /// contain zero.
+    pub fn cairo_memory_table<F: Field>(prog: &CairoProgram<F>) -> RuntimeTable<F> {
+        let data = (1..prog.mem.len())
+            .map(|addr| prog.mem[F::from(addr)].map_or(F::zero(), |word| word.word()))
+            .collect();
+        RuntimeTable {
+            id: CAIRO_MEMORY_TABLE_ID,
+            data,
+        }
+    }
 
     /// Returns the witness of an execution of a Cairo program in `CircuitGate` format
     pub fn cairo_witness<F: Field>(prog: &CairoProgram<F>) -> [Vec<F>; COLUMNS] {
@@ -320,13 +1112,24 @@ pub mod witness {
         ]
     }
 
+    /// Returns the witness of the result of the instruction, which is the
+    /// inverse of the destination (or zero) for conditional jumps, whose result
+    /// is unused by the runner
+    fn res_witness<F: Field>(inst: &CairoInstruction<F>) -> F {
+        if inst.f_pc_jnz().is_one() {
+            inst.dst().inverse().unwrap_or_else(F::zero)
+        } else {
+            inst.res()
+        }
+    }
+
     fn instruction_witness<F: Field>(inst: &CairoInstruction<F>) -> [F; COLUMNS] {
         [
             inst.pc(),
             inst.ap(),
             inst.fp(),
             inst.size(),
-            inst.res(),
+            res_witness(inst),
             inst.dst(),
             inst.op1(),
             inst.op0(),
@@ -369,7 +1172,7 @@ pub mod witness {
             curr.ap(),
             curr.fp(),
             curr.size(),
-            curr.res(),
+            res_witness(curr),
             curr.dst(),
             curr.op1(),
             next.pc(),
@@ -578,7 +1381,7 @@ pub mod testing {
             (one - f_pc_jnz) * res, //               if  pc_up != 4 : res = ..  // no res in conditional jumps
             f_res_mul * op0 * op1                 // if res_log = 2 : op0 * op1
             + f_res_add * (op0 + op1)             // if res_log = 1 : op0 + op1
-            + (one - f_res_add - f_res_mul) * op1, // if res_log = 0 : op1
+            + (one - f_res_add - f_res_mul - f_pc_jnz) * op1, // if res_log = 0 : op1
             "invalid result"
         );
 
@@ -756,6 +1559,7 @@ pub fn circuit_gate_combined_constraints<F: PrimeField>(
     }
 }
 
+#[derive(Default)]
 pub struct Claim<F>(PhantomData<F>);
 
 impl<F> Argument<F> for Claim<F>
@@ -796,6 +1600,7 @@ where
     }
 }
 
+#[derive(Default)]
 pub struct Instruction<F>(PhantomData<F>);
 
 impl<F> Argument<F> for Instruction<F>
@@ -923,10 +1728,10 @@ where
 
         // * Check value of result
         constraints.push(
-            (T::one() - f_pc_jnz) * res.clone()                              // if pc_up != 4 : res = ..        // no res in conditional jumps
+            (T::one() - f_pc_jnz.clone()) * res.clone()                      // if pc_up != 4 : res = ..        // no res in conditional jumps
           - (f_res_mul.clone() * op0.clone() * op1.clone()                     //      if res_log = 2 : op0 * op1
           + f_res_add.clone() * (op0.clone() + op1.clone())                    //      if res_log = 1 : op0 + op1
-          + (T::one() - f_res_add - f_res_mul) * op1), //      if res_log = 0 : op1
+          + (T::one() - f_res_add - f_res_mul - f_pc_jnz) * op1), //      if res_log = 0 : op1
         );
 
         // * Check storage of current fp for a call instruction
@@ -945,6 +1750,7 @@ where
     }
 }
 
+#[derive(Default)]
 pub struct Flags<F>(PhantomData<F>);
 
 impl<F> Argument<F> for Flags<F>
@@ -1015,6 +1821,7 @@ where
     }
 }
 
+#[derive(Default)]
 pub struct Transition<F>(PhantomData<F>);
 
 impl<F> Argument<F> for Transition<F>
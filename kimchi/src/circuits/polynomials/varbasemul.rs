@@ -144,7 +144,7 @@ impl<F: PrimeField> CircuitGate<F> {
     /// # Errors
     ///
     /// TODO
-    pub fn verify_vbmul(&self, _row: usize, _witness: &[Vec<F>; COLUMNS]) -> Result<(), String> {
+    pub fn verify_vbmul(&self, _row: usize, _witness: &[Vec<F>]) -> Result<(), String> {
         // TODO: implement
         Ok(())
     }
@@ -0,0 +1,127 @@
+//! This module contains the report produced by
+//! [ConstraintSystem::check_witness](crate::circuits::constraints::ConstraintSystem::check_witness).
+//! Unlike [ProverIndex::verify](crate::prover_index::ProverIndex::verify),
+//! which stops at the first error, the check evaluates every gate and every
+//! wire of the circuit, and collects all the failures.
+//!
+//! The report can be printed, or serialized (e.g. to JSON) to be inspected
+//! with other tools.
+
+use crate::circuits::{gate::GateType, polynomial::COLUMNS, wires::Wire};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use std::{borrow::Cow, fmt};
+
+/// The kind of a failure found when checking a witness
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FailureKind {
+    /// The constraint with the given index, counted from 1 in the order of
+    /// `constraint_checks` as in
+    /// [CircuitGateError::Constraint](crate::circuits::gate::CircuitGateError::Constraint),
+    /// does not evaluate to zero
+    Constraint(usize),
+    /// The cell `src` is wired to the cell `dst`, which has a different value
+    CopyConstraint { src: Wire, dst: Wire },
+    /// The cell of the given column is wired to a column that is not part of
+    /// the permutation
+    WireColumn(usize),
+    /// The gate of a public input row does not only toggle its left wire
+    IncorrectPublic,
+}
+
+impl fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailureKind::Constraint(i) => write!(f, "constraint {i} is not satisfied"),
+            FailureKind::CopyConstraint { src, dst } => write!(
+                f,
+                "copy constraint {},{} -> {},{} is not satisfied",
+                src.row, src.col, dst.row, dst.col
+            ),
+            FailureKind::WireColumn(col) => {
+                write!(f, "column {col} is wired outside of the permutation")
+            }
+            FailureKind::IncorrectPublic => write!(f, "incorrect public input gate"),
+        }
+    }
+}
+
+/// A failure found when checking a witness
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "F: CanonicalSerialize + CanonicalDeserialize")]
+pub struct WitnessFailure<F> {
+    /// The row of the failing gate
+    pub row: usize,
+    /// The type of the failing gate
+    pub typ: GateType,
+    /// What failed
+    pub kind: FailureKind,
+    /// The witness values of the row
+    #[serde_as(as = "[o1_utils::serialization::SerdeAs; COLUMNS]")]
+    pub curr: [F; COLUMNS],
+    /// The witness values of the next row, which some gates constrain too
+    #[serde_as(as = "[o1_utils::serialization::SerdeAs; COLUMNS]")]
+    pub next: [F; COLUMNS],
+    /// The labels of the snarky constraints that produced the row, if any
+    pub labels: Vec<String>,
+}
+
+impl<F: PrimeField> fmt::Display for WitnessFailure<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "row {} ({:?}): {}", self.row, self.typ, self.kind)?;
+        if !self.labels.is_empty() {
+            write!(f, " [{}]", self.labels.join(", "))?;
+        }
+        let values = |row: &[F; COLUMNS]| {
+            row.iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        writeln!(f)?;
+        writeln!(f, "  curr: [{}]", values(&self.curr))?;
+        write!(f, "  next: [{}]", values(&self.next))
+    }
+}
+
+/// All the failures found when checking a witness, ordered by row
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "F: CanonicalSerialize + CanonicalDeserialize")]
+pub struct WitnessReport<F> {
+    pub failures: Vec<WitnessFailure<F>>,
+}
+
+impl<F: PrimeField> WitnessReport<F> {
+    /// Returns true if the witness satisfies the circuit
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Attaches to the failures the labels of their rows, given for each row
+    /// of the circuit as returned by
+    /// [SnarkyConstraintSystem::row_labels](crate::snarky::constraint_system::SnarkyConstraintSystem::row_labels).
+    pub fn with_labels(mut self, labels: &[Vec<Cow<'static, str>>]) -> Self {
+        for failure in &mut self.failures {
+            if let Some(labels) = labels.get(failure.row) {
+                failure.labels = labels.iter().map(|l| l.to_string()).collect();
+            }
+        }
+        self
+    }
+}
+
+impl<F: PrimeField> fmt::Display for WitnessReport<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_ok() {
+            return write!(f, "the witness satisfies all the constraints");
+        }
+        write!(f, "{} failure(s):", self.failures.len())?;
+        for failure in &self.failures {
+            write!(f, "\n{failure}")?;
+        }
+        Ok(())
+    }
+}
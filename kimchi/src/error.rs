@@ -81,6 +81,9 @@ pub enum VerifyError {
 
     #[error("the commitment for {0:?} is missing")]
     MissingCommitment(crate::circuits::berkeley_columns::Column),
+
+    #[error("the {0} are not supported by the verifier")]
+    UnsupportedGates(&'static str),
 }
 
 /// Errors that can arise when preparing the setup
@@ -16,10 +16,15 @@ use crate::{
             endosclmul::EndosclMul,
             foreign_field_add::circuitgates::ForeignFieldAdd,
             foreign_field_mul::circuitgates::ForeignFieldMul,
-            generic, permutation,
+            generic,
+            keccak::{
+                circuitgates::{KeccakRound, KeccakSponge},
+                constants::KECCAK_COLS,
+            },
+            permutation,
             poseidon::Poseidon,
             range_check::circuitgates::{RangeCheck0, RangeCheck1},
-            rot,
+            rot, turshi,
             varbasemul::VarbaseMul,
             xor,
         },
@@ -50,12 +55,30 @@ pub fn constraints_expr<F: PrimeField>(
     // register powers of alpha so that we don't reuse them across mutually inclusive constraints
     let mut powers_of_alpha = Alphas::<F>::default();
 
+    // The Cairo gates are only supported when the feature flags are known, as
+    // the Cairo instruction gate has more constraints than any other gate and
+    // would shift the powers of alpha used by the other arguments.
+    let cairo = feature_flags.map_or(false, |feature_flags| feature_flags.cairo);
+    // The same goes for the Keccak gates, whose sponge gate has the most
+    // constraints of all.
+    let keccak = feature_flags.map_or(false, |feature_flags| feature_flags.keccak);
+
     // Set up powers of alpha. Only the max number of constraints matters.
     // The gate type argument can just be the zero gate.
-    powers_of_alpha.register(
-        ArgumentType::Gate(GateType::Zero),
-        VarbaseMul::<F>::CONSTRAINTS,
-    );
+    let mut max_constraints = VarbaseMul::<F>::CONSTRAINTS;
+    if cairo {
+        max_constraints = std::cmp::max(max_constraints, turshi::Instruction::<F>::CONSTRAINTS);
+    }
+    if keccak {
+        max_constraints = std::cmp::max(
+            max_constraints,
+            std::cmp::max(
+                KeccakRound::<F>::CONSTRAINTS,
+                KeccakSponge::<F>::CONSTRAINTS,
+            ),
+        );
+    }
+    powers_of_alpha.register(ArgumentType::Gate(GateType::Zero), max_constraints);
 
     let mut cache = expr::Cache::default();
 
@@ -161,6 +184,18 @@ pub fn constraints_expr<F: PrimeField>(
         }
     }
 
+    if cairo {
+        expr += turshi::Claim::combined_constraints(&powers_of_alpha, &mut cache);
+        expr += turshi::Instruction::combined_constraints(&powers_of_alpha, &mut cache);
+        expr += turshi::Flags::combined_constraints(&powers_of_alpha, &mut cache);
+        expr += turshi::Transition::combined_constraints(&powers_of_alpha, &mut cache);
+    }
+
+    if keccak {
+        expr += KeccakRound::combined_constraints(&powers_of_alpha, &mut cache);
+        expr += KeccakSponge::combined_constraints(&powers_of_alpha, &mut cache);
+    }
+
     if generic {
         expr += generic::Generic::combined_constraints(&powers_of_alpha, &mut cache);
     }
@@ -194,6 +229,8 @@ pub fn constraints_expr<F: PrimeField>(
                 lookup: true,
                 range_check: true,
                 foreign_field_mul: true,
+                // not supported by the expression using `IfFeature`
+                cairo_memory: false,
             },
             uses_runtime_tables: true,
             joint_lookup_used: true,
@@ -229,7 +266,10 @@ pub fn constraints_expr<F: PrimeField>(
     // Check that the feature flags correctly turn on or off the constraints generated by the given
     // flags.
     if cfg!(feature = "check_feature_flags") {
-        if let Some(feature_flags) = feature_flags {
+        // The Cairo and Keccak gates are not part of the expression using `IfFeature`
+        if let Some(feature_flags) =
+            feature_flags.filter(|feature_flags| !feature_flags.cairo && !feature_flags.keccak)
+        {
             let (feature_flagged_expr, _) = constraints_expr(None, generic);
             let feature_flagged_expr = feature_flagged_expr.apply_feature_flags(feature_flags);
             assert_eq!(expr, feature_flagged_expr);
@@ -260,12 +300,16 @@ pub fn linearization_columns<F: FftField>(
                 foreign_field_mul: true,
                 xor: true,
                 rot: true,
+                // not supported by the expression using `IfFeature`
+                cairo: false,
+                keccak: false,
                 lookup_features: LookupFeatures {
                     patterns: LookupPatterns {
                         xor: true,
                         lookup: true,
                         range_check: true,
                         foreign_field_mul: true,
+                        cairo_memory: false,
                     },
                     joint_lookup_used: true,
                     uses_runtime_tables: true,
@@ -274,13 +318,20 @@ pub fn linearization_columns<F: FftField>(
         }
     };
 
+    // the Keccak gates use more witness and coefficient columns than the others
+    let columns = if feature_flags.keccak {
+        KECCAK_COLS
+    } else {
+        COLUMNS
+    };
+
     // the witness polynomials
-    for i in 0..COLUMNS {
+    for i in 0..columns {
         h.insert(Witness(i));
     }
 
     // the coefficient polynomials
-    for i in 0..COLUMNS {
+    for i in 0..columns {
         h.insert(Coefficient(i));
     }
 
@@ -325,6 +376,12 @@ pub fn linearization_columns<F: FftField>(
     h.insert(Index(GateType::ForeignFieldMul));
     h.insert(Index(GateType::Xor16));
     h.insert(Index(GateType::Rot64));
+    h.insert(Index(GateType::CairoClaim));
+    h.insert(Index(GateType::CairoInstruction));
+    h.insert(Index(GateType::CairoFlags));
+    h.insert(Index(GateType::CairoTransition));
+    h.insert(Index(GateType::KeccakRound));
+    h.insert(Index(GateType::KeccakSponge));
 
     // lookup selectors
     h.insert(LookupRuntimeSelector);
@@ -332,6 +389,7 @@ pub fn linearization_columns<F: FftField>(
     h.insert(LookupKindIndex(LookupPattern::Lookup));
     h.insert(LookupKindIndex(LookupPattern::RangeCheck));
     h.insert(LookupKindIndex(LookupPattern::ForeignFieldMul));
+    h.insert(LookupKindIndex(LookupPattern::CairoMemory));
 
     h
 }
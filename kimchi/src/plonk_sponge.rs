@@ -30,7 +30,10 @@ pub trait FrSponge<Fr: Field> {
 
     /// Absorbs the given evaluations into the sponge.
     // TODO: IMO this function should be inlined in prover/verifier
-    fn absorb_evaluations(&mut self, e: &ProofEvaluations<PointEvaluations<Vec<Fr>>>);
+    fn absorb_evaluations<const W: usize>(
+        &mut self,
+        e: &ProofEvaluations<PointEvaluations<Vec<Fr>>, W>,
+    );
 }
 
 impl<Fr: PrimeField> FrSponge<Fr> for DefaultFrSponge<Fr, SC> {
@@ -60,7 +63,10 @@ impl<Fr: PrimeField> FrSponge<Fr> for DefaultFrSponge<Fr, SC> {
     }
 
     // We absorb all evaluations of the same polynomial at the same time
-    fn absorb_evaluations(&mut self, e: &ProofEvaluations<PointEvaluations<Vec<Fr>>>) {
+    fn absorb_evaluations<const W: usize>(
+        &mut self,
+        e: &ProofEvaluations<PointEvaluations<Vec<Fr>>, W>,
+    ) {
         self.last_squeezed = vec![];
 
         let ProofEvaluations {
@@ -81,6 +87,12 @@ impl<Fr: PrimeField> FrSponge<Fr> for DefaultFrSponge<Fr, SC> {
             foreign_field_mul_selector,
             xor_selector,
             rot_selector,
+            cairo_claim_selector,
+            cairo_instruction_selector,
+            cairo_flags_selector,
+            cairo_transition_selector,
+            keccak_round_selector,
+            keccak_sponge_selector,
             lookup_aggregation,
             lookup_table,
             lookup_sorted,
@@ -90,6 +102,7 @@ impl<Fr: PrimeField> FrSponge<Fr> for DefaultFrSponge<Fr, SC> {
             lookup_gate_lookup_selector,
             range_check_lookup_selector,
             foreign_field_mul_lookup_selector,
+            cairo_memory_lookup_selector,
         } = e;
 
         let mut points = vec![
@@ -125,6 +138,24 @@ impl<Fr: PrimeField> FrSponge<Fr> for DefaultFrSponge<Fr, SC> {
         if let Some(rot_selector) = rot_selector.as_ref() {
             points.push(rot_selector)
         }
+        if let Some(cairo_claim_selector) = cairo_claim_selector.as_ref() {
+            points.push(cairo_claim_selector)
+        }
+        if let Some(cairo_instruction_selector) = cairo_instruction_selector.as_ref() {
+            points.push(cairo_instruction_selector)
+        }
+        if let Some(cairo_flags_selector) = cairo_flags_selector.as_ref() {
+            points.push(cairo_flags_selector)
+        }
+        if let Some(cairo_transition_selector) = cairo_transition_selector.as_ref() {
+            points.push(cairo_transition_selector)
+        }
+        if let Some(keccak_round_selector) = keccak_round_selector.as_ref() {
+            points.push(keccak_round_selector)
+        }
+        if let Some(keccak_sponge_selector) = keccak_sponge_selector.as_ref() {
+            points.push(keccak_sponge_selector)
+        }
         if let Some(lookup_aggregation) = lookup_aggregation.as_ref() {
             points.push(lookup_aggregation)
         }
@@ -155,6 +186,9 @@ impl<Fr: PrimeField> FrSponge<Fr> for DefaultFrSponge<Fr, SC> {
         {
             points.push(foreign_field_mul_lookup_selector)
         }
+        if let Some(cairo_memory_lookup_selector) = cairo_memory_lookup_selector.as_ref() {
+            points.push(cairo_memory_lookup_selector)
+        }
 
         points.into_iter().for_each(|p| {
             self.sponge.absorb(&p.zeta);
@@ -8,9 +8,14 @@
 //! We generate the SRS within the test in this module.
 //! If you modify the SRS, you will need to regenerate the SRS by passing the
 //! `SRS_OVERWRITE` env var.
+//!
+//! On unix, the SRS can also be memory-mapped, see [get_mmap_srs]. It avoids
+//! keeping all the points of a large SRS in memory.
 
 use crate::curve::KimchiCurve;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+#[cfg(unix)]
+use poly_commitment::mmap_srs::MmapSRS;
 use poly_commitment::{hash_map_cache::HashMapCache, ipa::SRS, PolyComm};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -120,6 +125,62 @@ where
     get_srs_generic(StoredSRSType::Test)
 }
 
+/// Generic memory-mapped SRS getter function.
+///
+/// The SRS stored on disk is converted once in the format of [MmapSRS], in a
+/// file next to it with the extension `mmap`, and the following calls map this
+/// file. It is converted again when the stored SRS is more recent, e.g. after
+/// regenerating it. The Lagrange bases are not converted, they are computed
+/// when needed and cached on disk, see [MmapSRS::get_mapped_lagrange_basis].
+#[cfg(unix)]
+pub fn get_mmap_srs_generic<G>(srs_type: StoredSRSType) -> MmapSRS<G>
+where
+    G: KimchiCurve,
+{
+    let srs_path = get_srs_path::<G>(srs_type);
+    let mmap_path = srs_path.with_extension("mmap");
+    let modified = |path: &PathBuf| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    // A missing file is older than any other
+    if modified(&mmap_path) < modified(&srs_path) {
+        let srs = get_srs_generic::<G>(srs_type);
+        // The file is written to a temporary file first, so that an
+        // interrupted conversion does not leave a truncated file.
+        let tmp_path = mmap_path.with_extension(format!("mmap.{}.tmp", std::process::id()));
+        let file = File::create(&tmp_path)
+            .unwrap_or_else(|err| panic!("could not create the SRS file {tmp_path:?}: {err}"));
+        MmapSRS::write(&srs, file)
+            .unwrap_or_else(|err| panic!("could not write the SRS file {tmp_path:?}: {err}"));
+        // The Lagrange bases cached for the previous SRS are stale
+        let mut lagrange_cache_dir = mmap_path.as_os_str().to_owned();
+        lagrange_cache_dir.push(".lagrange");
+        let _ = std::fs::remove_dir_all(lagrange_cache_dir);
+        std::fs::rename(&tmp_path, &mmap_path)
+            .unwrap_or_else(|err| panic!("could not write the SRS file {mmap_path:?}: {err}"));
+    }
+    MmapSRS::open(&mmap_path)
+        .unwrap_or_else(|err| panic!("could not open the SRS file {mmap_path:?}: {err}"))
+}
+
+/// Obtains a memory-mapped SRS for a specific curve from disk.
+/// Panics if the SRS does not exists.
+#[cfg(unix)]
+pub fn get_mmap_srs<G>() -> MmapSRS<G>
+where
+    G: KimchiCurve,
+{
+    get_mmap_srs_generic(StoredSRSType::Prod)
+}
+
+/// Obtains a memory-mapped Test SRS for a specific curve from disk.
+/// Panics if the SRS does not exists.
+#[cfg(unix)]
+pub fn get_mmap_srs_test<G>() -> MmapSRS<G>
+where
+    G: KimchiCurve,
+{
+    get_mmap_srs_generic(StoredSRSType::Test)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -232,6 +293,42 @@ mod tests {
         get_srs_test::<Vesta>();
     }
 
+    /// Checks that the memory-mapped SRS has the points of the stored one, and
+    /// commits to the same evaluations.
+    #[cfg(unix)]
+    fn check_mmap_srs<G>()
+    where
+        G: KimchiCurve,
+        G::BaseField: PrimeField,
+    {
+        let srs = get_srs_test::<G>();
+        let mmap_srs = get_mmap_srs_test::<G>();
+        assert_eq!(mmap_srs.h(), srs.h);
+        assert_eq!(mmap_srs.g().to_vec(), srs.g);
+
+        let domain = EvaluationDomains::<G::ScalarField>::create(1 << 4).unwrap();
+        let evals = ark_poly::Evaluations::from_vec_and_domain(
+            (0..domain.d1.size).map(G::ScalarField::from).collect(),
+            domain.d1,
+        );
+        assert_eq!(
+            mmap_srs.commit_evaluations_non_hiding(domain.d1, &evals),
+            srs.commit_evaluations_non_hiding(domain.d1, &evals)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    pub fn check_get_mmap_srs_test_pallas() {
+        check_mmap_srs::<Pallas>();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    pub fn check_get_mmap_srs_test_vesta() {
+        check_mmap_srs::<Vesta>();
+    }
+
     /// This test checks that the two serialized SRS on disk are correct.
     #[test]
     pub fn heavy_test_srs_serialization() {
@@ -44,11 +44,12 @@ pub struct PointEvaluations<Evals> {
 /// are single-sized#[serde_as]
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct ProofEvaluations<Evals> {
+pub struct ProofEvaluations<Evals, const W: usize = COLUMNS> {
     /// public input polynomials
     pub public: Option<Evals>,
     /// witness polynomials
-    pub w: [Evals; COLUMNS],
+    #[serde_as(as = "[_; W]")]
+    pub w: [Evals; W],
     /// permutation polynomial
     pub z: Evals,
     /// permutation polynomials
@@ -56,7 +57,8 @@ pub struct ProofEvaluations<Evals> {
     /// commitment form)
     pub s: [Evals; PERMUTS - 1],
     /// coefficient polynomials
-    pub coefficients: [Evals; COLUMNS],
+    #[serde_as(as = "[_; W]")]
+    pub coefficients: [Evals; W],
     /// evaluation of the generic selector polynomial
     pub generic_selector: Evals,
     /// evaluation of the poseidon selector polynomial
@@ -108,6 +110,35 @@ pub struct ProofEvaluations<Evals> {
     /// evaluation of the ForeignFieldMul range check pattern selector
     /// polynomial
     pub foreign_field_mul_lookup_selector: Option<Evals>,
+
+    // Cairo and Keccak evaluations
+    //
+    // They are only serialized when present, so that the proofs of the
+    // circuits without these gates keep their format. As the serialization
+    // is positional, a field can only be present if the fields above it are:
+    // the Cairo memory pattern comes with the Cairo gates, and the Keccak gates
+    // cannot be proven.
+    /// evaluation of the CairoClaim selector polynomial
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cairo_claim_selector: Option<Evals>,
+    /// evaluation of the CairoInstruction selector polynomial
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cairo_instruction_selector: Option<Evals>,
+    /// evaluation of the CairoFlags selector polynomial
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cairo_flags_selector: Option<Evals>,
+    /// evaluation of the CairoTransition selector polynomial
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cairo_transition_selector: Option<Evals>,
+    /// evaluation of the Cairo memory pattern selector polynomial
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cairo_memory_lookup_selector: Option<Evals>,
+    /// evaluation of the KeccakRound selector polynomial
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keccak_round_selector: Option<Evals>,
+    /// evaluation of the KeccakSponge selector polynomial
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keccak_sponge_selector: Option<Evals>,
 }
 
 /// Commitments linked to the lookup feature
@@ -127,9 +158,10 @@ pub struct LookupCommitments<G: AffineRepr> {
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(bound = "G: ark_serialize::CanonicalDeserialize + ark_serialize::CanonicalSerialize")]
-pub struct ProverCommitments<G: AffineRepr> {
+pub struct ProverCommitments<G: AffineRepr, const W: usize = COLUMNS> {
     /// The commitments to the witness (execution trace)
-    pub w_comm: [PolyComm<G>; COLUMNS],
+    #[serde_as(as = "[_; W]")]
+    pub w_comm: [PolyComm<G>; W],
     /// The commitment to the permutation polynomial
     pub z_comm: PolyComm<G>,
     /// The commitment to the quotient polynomial
@@ -143,9 +175,9 @@ pub struct ProverCommitments<G: AffineRepr> {
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(bound = "G: ark_serialize::CanonicalDeserialize + ark_serialize::CanonicalSerialize")]
-pub struct ProverProof<G: AffineRepr, OpeningProof> {
+pub struct ProverProof<G: AffineRepr, OpeningProof, const W: usize = COLUMNS> {
     /// All the polynomial commitments required in the proof
-    pub commitments: ProverCommitments<G>,
+    pub commitments: ProverCommitments<G, W>,
 
     /// batched commitment opening proof
     #[serde(bound(
@@ -155,7 +187,7 @@ pub struct ProverProof<G: AffineRepr, OpeningProof> {
     pub proof: OpeningProof,
 
     /// Two evaluations over a number of committed polynomials
-    pub evals: ProofEvaluations<PointEvaluations<Vec<G::ScalarField>>>,
+    pub evals: ProofEvaluations<PointEvaluations<Vec<G::ScalarField>>, W>,
 
     /// Required evaluation for [Maller's
     /// optimization](https://o1-labs.github.io/proof-systems/kimchi/maller_15.html#the-evaluation-of-l)
@@ -201,8 +233,8 @@ impl<Evals> PointEvaluations<Evals> {
     }
 }
 
-impl<Eval> ProofEvaluations<Eval> {
-    pub fn map<Eval2, FN: Fn(Eval) -> Eval2>(self, f: &FN) -> ProofEvaluations<Eval2> {
+impl<Eval, const W: usize> ProofEvaluations<Eval, W> {
+    pub fn map<Eval2, FN: Fn(Eval) -> Eval2>(self, f: &FN) -> ProofEvaluations<Eval2, W> {
         let ProofEvaluations {
             public,
             w,
@@ -221,6 +253,12 @@ impl<Eval> ProofEvaluations<Eval> {
             foreign_field_mul_selector,
             xor_selector,
             rot_selector,
+            cairo_claim_selector,
+            cairo_instruction_selector,
+            cairo_flags_selector,
+            cairo_transition_selector,
+            keccak_round_selector,
+            keccak_sponge_selector,
             lookup_aggregation,
             lookup_table,
             lookup_sorted,
@@ -230,6 +268,7 @@ impl<Eval> ProofEvaluations<Eval> {
             lookup_gate_lookup_selector,
             range_check_lookup_selector,
             foreign_field_mul_lookup_selector,
+            cairo_memory_lookup_selector,
         } = self;
         ProofEvaluations {
             public: public.map(f),
@@ -249,6 +288,12 @@ impl<Eval> ProofEvaluations<Eval> {
             foreign_field_mul_selector: foreign_field_mul_selector.map(f),
             xor_selector: xor_selector.map(f),
             rot_selector: rot_selector.map(f),
+            cairo_claim_selector: cairo_claim_selector.map(f),
+            cairo_instruction_selector: cairo_instruction_selector.map(f),
+            cairo_flags_selector: cairo_flags_selector.map(f),
+            cairo_transition_selector: cairo_transition_selector.map(f),
+            keccak_round_selector: keccak_round_selector.map(f),
+            keccak_sponge_selector: keccak_sponge_selector.map(f),
             lookup_aggregation: lookup_aggregation.map(f),
             lookup_table: lookup_table.map(f),
             lookup_sorted: lookup_sorted.map(|x| x.map(f)),
@@ -258,16 +303,17 @@ impl<Eval> ProofEvaluations<Eval> {
             lookup_gate_lookup_selector: lookup_gate_lookup_selector.map(f),
             range_check_lookup_selector: range_check_lookup_selector.map(f),
             foreign_field_mul_lookup_selector: foreign_field_mul_lookup_selector.map(f),
+            cairo_memory_lookup_selector: cairo_memory_lookup_selector.map(f),
         }
     }
 
-    pub fn map_ref<Eval2, FN: Fn(&Eval) -> Eval2>(&self, f: &FN) -> ProofEvaluations<Eval2> {
+    pub fn map_ref<Eval2, FN: Fn(&Eval) -> Eval2>(&self, f: &FN) -> ProofEvaluations<Eval2, W> {
         let ProofEvaluations {
             public,
-            w: [w0, w1, w2, w3, w4, w5, w6, w7, w8, w9, w10, w11, w12, w13, w14],
+            w,
             z,
             s: [s0, s1, s2, s3, s4, s5],
-            coefficients: [c0, c1, c2, c3, c4, c5, c6, c7, c8, c9, c10, c11, c12, c13, c14],
+            coefficients,
             generic_selector,
             poseidon_selector,
             complete_add_selector,
@@ -280,6 +326,12 @@ impl<Eval> ProofEvaluations<Eval> {
             foreign_field_mul_selector,
             xor_selector,
             rot_selector,
+            cairo_claim_selector,
+            cairo_instruction_selector,
+            cairo_flags_selector,
+            cairo_transition_selector,
+            keccak_round_selector,
+            keccak_sponge_selector,
             lookup_aggregation,
             lookup_table,
             lookup_sorted,
@@ -289,45 +341,14 @@ impl<Eval> ProofEvaluations<Eval> {
             lookup_gate_lookup_selector,
             range_check_lookup_selector,
             foreign_field_mul_lookup_selector,
+            cairo_memory_lookup_selector,
         } = self;
         ProofEvaluations {
             public: public.as_ref().map(f),
-            w: [
-                f(w0),
-                f(w1),
-                f(w2),
-                f(w3),
-                f(w4),
-                f(w5),
-                f(w6),
-                f(w7),
-                f(w8),
-                f(w9),
-                f(w10),
-                f(w11),
-                f(w12),
-                f(w13),
-                f(w14),
-            ],
+            w: array::from_fn(|i| f(&w[i])),
             z: f(z),
             s: [f(s0), f(s1), f(s2), f(s3), f(s4), f(s5)],
-            coefficients: [
-                f(c0),
-                f(c1),
-                f(c2),
-                f(c3),
-                f(c4),
-                f(c5),
-                f(c6),
-                f(c7),
-                f(c8),
-                f(c9),
-                f(c10),
-                f(c11),
-                f(c12),
-                f(c13),
-                f(c14),
-            ],
+            coefficients: array::from_fn(|i| f(&coefficients[i])),
             generic_selector: f(generic_selector),
             poseidon_selector: f(poseidon_selector),
             complete_add_selector: f(complete_add_selector),
@@ -340,6 +361,12 @@ impl<Eval> ProofEvaluations<Eval> {
             foreign_field_mul_selector: foreign_field_mul_selector.as_ref().map(f),
             xor_selector: xor_selector.as_ref().map(f),
             rot_selector: rot_selector.as_ref().map(f),
+            cairo_claim_selector: cairo_claim_selector.as_ref().map(f),
+            cairo_instruction_selector: cairo_instruction_selector.as_ref().map(f),
+            cairo_flags_selector: cairo_flags_selector.as_ref().map(f),
+            cairo_transition_selector: cairo_transition_selector.as_ref().map(f),
+            keccak_round_selector: keccak_round_selector.as_ref().map(f),
+            keccak_sponge_selector: keccak_sponge_selector.as_ref().map(f),
             lookup_aggregation: lookup_aggregation.as_ref().map(f),
             lookup_table: lookup_table.as_ref().map(f),
             lookup_sorted: array::from_fn(|i| lookup_sorted[i].as_ref().map(f)),
@@ -349,6 +376,7 @@ impl<Eval> ProofEvaluations<Eval> {
             lookup_gate_lookup_selector: lookup_gate_lookup_selector.as_ref().map(f),
             range_check_lookup_selector: range_check_lookup_selector.as_ref().map(f),
             foreign_field_mul_lookup_selector: foreign_field_mul_lookup_selector.as_ref().map(f),
+            cairo_memory_lookup_selector: cairo_memory_lookup_selector.as_ref().map(f),
         }
     }
 }
@@ -400,11 +428,11 @@ impl<G: AffineRepr> RecursionChallenge<G> {
     }
 }
 
-impl<F: Zero + Copy> ProofEvaluations<PointEvaluations<F>> {
+impl<F: Zero + Copy, const W: usize> ProofEvaluations<PointEvaluations<F>, W> {
     pub fn dummy_with_witness_evaluations(
-        curr: [F; COLUMNS],
-        next: [F; COLUMNS],
-    ) -> ProofEvaluations<PointEvaluations<F>> {
+        curr: [F; W],
+        next: [F; W],
+    ) -> ProofEvaluations<PointEvaluations<F>, W> {
         let pt = |curr, next| PointEvaluations {
             zeta: curr,
             zeta_omega: next,
@@ -427,6 +455,12 @@ impl<F: Zero + Copy> ProofEvaluations<PointEvaluations<F>> {
             foreign_field_mul_selector: None,
             xor_selector: None,
             rot_selector: None,
+            cairo_claim_selector: None,
+            cairo_instruction_selector: None,
+            cairo_flags_selector: None,
+            cairo_transition_selector: None,
+            keccak_round_selector: None,
+            keccak_sponge_selector: None,
             lookup_aggregation: None,
             lookup_table: None,
             lookup_sorted: array::from_fn(|_| None),
@@ -436,12 +470,13 @@ impl<F: Zero + Copy> ProofEvaluations<PointEvaluations<F>> {
             lookup_gate_lookup_selector: None,
             range_check_lookup_selector: None,
             foreign_field_mul_lookup_selector: None,
+            cairo_memory_lookup_selector: None,
         }
     }
 }
 
-impl<F: FftField> ProofEvaluations<PointEvaluations<Vec<F>>> {
-    pub fn combine(&self, pt: &PointEvaluations<F>) -> ProofEvaluations<PointEvaluations<F>> {
+impl<F: FftField, const W: usize> ProofEvaluations<PointEvaluations<Vec<F>>, W> {
+    pub fn combine(&self, pt: &PointEvaluations<F>) -> ProofEvaluations<PointEvaluations<F>, W> {
         self.map_ref(&|evals| PointEvaluations {
             zeta: DensePolynomial::eval_polynomial(&evals.zeta, pt.zeta),
             zeta_omega: DensePolynomial::eval_polynomial(&evals.zeta_omega, pt.zeta_omega),
@@ -449,7 +484,7 @@ impl<F: FftField> ProofEvaluations<PointEvaluations<Vec<F>>> {
     }
 }
 
-impl<F> ProofEvaluations<F> {
+impl<F, const W: usize> ProofEvaluations<F, W> {
     pub fn get_column(&self, col: Column) -> Option<&F> {
         match col {
             Column::Witness(i) => Some(&self.w[i]),
@@ -467,6 +502,9 @@ impl<F> ProofEvaluations<F> {
             Column::LookupKindIndex(LookupPattern::ForeignFieldMul) => {
                 self.foreign_field_mul_lookup_selector.as_ref()
             }
+            Column::LookupKindIndex(LookupPattern::CairoMemory) => {
+                self.cairo_memory_lookup_selector.as_ref()
+            }
             Column::LookupRuntimeSelector => self.runtime_lookup_table_selector.as_ref(),
             Column::LookupRuntimeTable => self.runtime_lookup_table.as_ref(),
             Column::Index(GateType::Generic) => Some(&self.generic_selector),
@@ -481,6 +519,12 @@ impl<F> ProofEvaluations<F> {
             Column::Index(GateType::ForeignFieldMul) => self.foreign_field_mul_selector.as_ref(),
             Column::Index(GateType::Xor16) => self.xor_selector.as_ref(),
             Column::Index(GateType::Rot64) => self.rot_selector.as_ref(),
+            Column::Index(GateType::CairoClaim) => self.cairo_claim_selector.as_ref(),
+            Column::Index(GateType::CairoInstruction) => self.cairo_instruction_selector.as_ref(),
+            Column::Index(GateType::CairoFlags) => self.cairo_flags_selector.as_ref(),
+            Column::Index(GateType::CairoTransition) => self.cairo_transition_selector.as_ref(),
+            Column::Index(GateType::KeccakRound) => self.keccak_round_selector.as_ref(),
+            Column::Index(GateType::KeccakSponge) => self.keccak_sponge_selector.as_ref(),
             Column::Index(_) => None,
             Column::Coefficient(i) => Some(&self.coefficients[i]),
             Column::Permutation(i) => Some(&self.s[i]),
@@ -961,6 +1005,13 @@ pub mod caml {
                 rot_selector: cpe
                     .rot_selector
                     .map(|x| x.map(&|x| x.into_iter().map(Into::into).collect())),
+                // The Cairo and Keccak gates are not supported by the OCaml bindings
+                cairo_claim_selector: None,
+                cairo_instruction_selector: None,
+                cairo_flags_selector: None,
+                cairo_transition_selector: None,
+                keccak_round_selector: None,
+                keccak_sponge_selector: None,
                 lookup_aggregation: cpe
                     .lookup_aggregation
                     .map(|x| x.map(&|x| x.into_iter().map(Into::into).collect())),
@@ -993,6 +1044,8 @@ pub mod caml {
                 foreign_field_mul_lookup_selector: cpe
                     .foreign_field_mul_lookup_selector
                     .map(|x| x.map(&|x| x.iter().map(|x| x.clone().into()).collect())),
+                // The Cairo memory pattern is not supported by the OCaml bindings
+                cairo_memory_lookup_selector: None,
             }
         }
     }
@@ -14,14 +14,17 @@ use crate::{
             endosclmul::EndosclMul,
             foreign_field_add::circuitgates::ForeignFieldAdd,
             foreign_field_mul::{self, circuitgates::ForeignFieldMul},
-            generic, permutation,
+            generic,
+            keccak::circuitgates::{KeccakRound, KeccakSponge},
+            permutation,
             poseidon::Poseidon,
             range_check::circuitgates::{RangeCheck0, RangeCheck1},
             rot::Rot64,
+            turshi,
             varbasemul::VarbaseMul,
             xor::Xor16,
         },
-        wires::{COLUMNS, PERMUTS},
+        wires::PERMUTS,
     },
     curve::KimchiCurve,
     error::ProverError,
@@ -123,7 +126,7 @@ where
     runtime_second_col_d8: Option<Evaluations<F, D<F>>>,
 }
 
-impl<G: KimchiCurve, OpeningProof: OpenProof<G>> ProverProof<G, OpeningProof>
+impl<G: KimchiCurve, OpeningProof: OpenProof<G>, const W: usize> ProverProof<G, OpeningProof, W>
 where
     G::BaseField: PrimeField,
 {
@@ -138,13 +141,13 @@ where
         RNG: RngCore + CryptoRng,
     >(
         groupmap: &G::Map,
-        witness: [Vec<G::ScalarField>; COLUMNS],
+        witness: [Vec<G::ScalarField>; W],
         runtime_tables: &[RuntimeTable<G::ScalarField>],
-        index: &ProverIndex<G, OpeningProof>,
+        index: &ProverIndex<G, OpeningProof, W>,
         rng: &mut RNG,
     ) -> Result<Self>
     where
-        VerifierIndex<G, OpeningProof>: Clone,
+        VerifierIndex<G, OpeningProof, W>: Clone,
     {
         Self::create_recursive::<EFqSponge, EFrSponge, RNG>(
             groupmap,
@@ -174,19 +177,29 @@ where
         RNG: RngCore + CryptoRng,
     >(
         group_map: &G::Map,
-        mut witness: [Vec<G::ScalarField>; COLUMNS],
+        mut witness: [Vec<G::ScalarField>; W],
         runtime_tables: &[RuntimeTable<G::ScalarField>],
-        index: &ProverIndex<G, OpeningProof>,
+        index: &ProverIndex<G, OpeningProof, W>,
         prev_challenges: Vec<RecursionChallenge<G>>,
-        blinders: Option<[Option<PolyComm<G::ScalarField>>; COLUMNS]>,
+        blinders: Option<[Option<PolyComm<G::ScalarField>>; W]>,
         rng: &mut RNG,
     ) -> Result<Self>
     where
-        VerifierIndex<G, OpeningProof>: Clone,
+        VerifierIndex<G, OpeningProof, W>: Clone,
     {
         internal_tracing::checkpoint!(internal_traces; create_recursive);
         let d1_size = index.cs.domain.d1.size();
 
+        // The constraints of the Keccak gates do not range check the
+        // decompositions of the state, nor tie the digest to the state, so a
+        // proof of a circuit using them would say nothing about the hash.
+        // See [crate::circuits::polynomials::keccak].
+        if index.cs.feature_flags.keccak {
+            return Err(ProverError::Prover(
+                "the Keccak gates are not sound yet and cannot be proven",
+            ));
+        }
+
         let (_, endo_r) = G::endos();
 
         let num_chunks = if d1_size < index.max_poly_size {
@@ -292,13 +305,14 @@ where
         //~    This is why we need to absorb the commitment to the public polynomial at this point.
         absorb_commitment(&mut fq_sponge, &public_comm);
 
-        //~ 1. Commit to the witness columns by creating `COLUMNS` hidding commitments.
+        //~ 1. Commit to the witness columns by creating `COLUMNS` hidding commitments
+        //~    (or `KECCAK_COLS` of them when the circuit uses the Keccak gates).
         //~
         //~    Note: since the witness is in evaluation form,
         //~    we can use the `commit_evaluation` optimization.
         internal_tracing::checkpoint!(internal_traces; commit_to_witness_columns);
         let mut w_comm = vec![];
-        for col in 0..COLUMNS {
+        for col in 0..W {
             // witness coeff -> witness eval
             let witness_eval =
                 Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
@@ -327,7 +341,7 @@ where
             w_comm.push(com);
         }
 
-        let w_comm: [BlindedCommitment<G>; COLUMNS] = w_comm
+        let w_comm: [BlindedCommitment<G>; W] = w_comm
             .try_into()
             .expect("previous loop is of the correct length");
 
@@ -340,7 +354,7 @@ where
         //~    As mentioned above, we commit using the evaluations form rather than the coefficients
         //~    form so we can take advantage of the sparsity of the evaluations (i.e., there are many
         //~    0 entries and entries that have less-than-full-size field elemnts.)
-        let witness_poly: [DensePolynomial<G::ScalarField>; COLUMNS] = array::from_fn(|i| {
+        let witness_poly: [DensePolynomial<G::ScalarField>; W] = array::from_fn(|i| {
             Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
                 witness[i].clone(),
                 index.cs.domain.d1,
@@ -714,6 +728,34 @@ where
                 index_evals.insert(GateType::Rot64, selector);
             }
 
+            if let Some(selector) = index.column_evaluations.cairo_claim_selector8.as_ref() {
+                index_evals.insert(GateType::CairoClaim, selector);
+            }
+
+            if let Some(selector) = index
+                .column_evaluations
+                .cairo_instruction_selector8
+                .as_ref()
+            {
+                index_evals.insert(GateType::CairoInstruction, selector);
+            }
+
+            if let Some(selector) = index.column_evaluations.cairo_flags_selector8.as_ref() {
+                index_evals.insert(GateType::CairoFlags, selector);
+            }
+
+            if let Some(selector) = index.column_evaluations.cairo_transition_selector8.as_ref() {
+                index_evals.insert(GateType::CairoTransition, selector);
+            }
+
+            if let Some(selector) = index.column_evaluations.keccak_round_selector8.as_ref() {
+                index_evals.insert(GateType::KeccakRound, selector);
+            }
+
+            if let Some(selector) = index.column_evaluations.keccak_sponge_selector8.as_ref() {
+                index_evals.insert(GateType::KeccakSponge, selector);
+            }
+
             let mds = &G::sponge_params().mds;
             Environment {
                 constants: Constants {
@@ -791,6 +833,8 @@ where
                     .is_some();
                 let xor_enabled = index.column_evaluations.xor_selector8.is_some();
                 let rot_enabled = index.column_evaluations.rot_selector8.is_some();
+                let cairo_enabled = index.cs.feature_flags.cairo;
+                let keccak_enabled = index.cs.feature_flags.keccak;
 
                 for gate in [
                     (
@@ -815,6 +859,14 @@ where
                     (&Xor16::default(), xor_enabled),
                     // Rot gate
                     (&Rot64::default(), rot_enabled),
+                    // Cairo gates
+                    (&turshi::Claim::default(), cairo_enabled),
+                    (&turshi::Instruction::default(), cairo_enabled),
+                    (&turshi::Flags::default(), cairo_enabled),
+                    (&turshi::Transition::default(), cairo_enabled),
+                    // Keccak gates
+                    (&KeccakRound::default(), keccak_enabled),
+                    (&KeccakSponge::default(), keccak_enabled),
                 ]
                 .into_iter()
                 .filter_map(|(gate, is_enabled)| if is_enabled { Some(gate) } else { None })
@@ -988,7 +1040,7 @@ where
             };
 
         internal_tracing::checkpoint!(internal_traces; chunk_eval_zeta_omega_poly);
-        let chunked_evals = ProofEvaluations::<PointEvaluations<Vec<G::ScalarField>>> {
+        let chunked_evals = ProofEvaluations::<PointEvaluations<Vec<G::ScalarField>>, W> {
             public: {
                 let chunked = public_poly.to_chunked_polynomial(num_chunks, index.max_poly_size);
                 Some(PointEvaluations {
@@ -1070,6 +1122,36 @@ where
                 .rot_selector8
                 .as_ref()
                 .map(chunked_evals_for_selector),
+            cairo_claim_selector: index
+                .column_evaluations
+                .cairo_claim_selector8
+                .as_ref()
+                .map(chunked_evals_for_selector),
+            cairo_instruction_selector: index
+                .column_evaluations
+                .cairo_instruction_selector8
+                .as_ref()
+                .map(chunked_evals_for_selector),
+            cairo_flags_selector: index
+                .column_evaluations
+                .cairo_flags_selector8
+                .as_ref()
+                .map(chunked_evals_for_selector),
+            cairo_transition_selector: index
+                .column_evaluations
+                .cairo_transition_selector8
+                .as_ref()
+                .map(chunked_evals_for_selector),
+            keccak_round_selector: index
+                .column_evaluations
+                .keccak_round_selector8
+                .as_ref()
+                .map(chunked_evals_for_selector),
+            keccak_sponge_selector: index
+                .column_evaluations
+                .keccak_sponge_selector8
+                .as_ref()
+                .map(chunked_evals_for_selector),
 
             runtime_lookup_table_selector: index.cs.lookup_constraint_system.as_ref().and_then(
                 |lcs| {
@@ -1108,6 +1190,14 @@ where
                         .map(chunked_evals_for_selector)
                 },
             ),
+            cairo_memory_lookup_selector: index.cs.lookup_constraint_system.as_ref().and_then(
+                |lcs| {
+                    lcs.lookup_selectors
+                        .cairo_memory
+                        .as_ref()
+                        .map(chunked_evals_for_selector)
+                },
+            ),
         };
 
         let zeta_to_srs_len = zeta.pow([index.max_poly_size as u64]);
@@ -1116,7 +1206,7 @@ where
 
         //~ 1. Evaluate the same polynomials without chunking them
         //~    (so that each polynomial should correspond to a single value this time).
-        let evals: ProofEvaluations<PointEvaluations<G::ScalarField>> = {
+        let evals: ProofEvaluations<PointEvaluations<G::ScalarField>, W> = {
             let powers_of_eval_points_for_chunks = PointEvaluations {
                 zeta: zeta_to_srs_len,
                 zeta_omega: zeta_omega_to_srs_len,
@@ -1354,6 +1444,54 @@ where
         if let Some(rot_selector8) = index.column_evaluations.rot_selector8.as_ref() {
             polynomials.push((evaluations_form(rot_selector8), non_hiding(num_chunks)));
         }
+        if let Some(cairo_claim_selector8) = index.column_evaluations.cairo_claim_selector8.as_ref()
+        {
+            polynomials.push((
+                evaluations_form(cairo_claim_selector8),
+                non_hiding(num_chunks),
+            ));
+        }
+        if let Some(cairo_instruction_selector8) = index
+            .column_evaluations
+            .cairo_instruction_selector8
+            .as_ref()
+        {
+            polynomials.push((
+                evaluations_form(cairo_instruction_selector8),
+                non_hiding(num_chunks),
+            ));
+        }
+        if let Some(cairo_flags_selector8) = index.column_evaluations.cairo_flags_selector8.as_ref()
+        {
+            polynomials.push((
+                evaluations_form(cairo_flags_selector8),
+                non_hiding(num_chunks),
+            ));
+        }
+        if let Some(cairo_transition_selector8) =
+            index.column_evaluations.cairo_transition_selector8.as_ref()
+        {
+            polynomials.push((
+                evaluations_form(cairo_transition_selector8),
+                non_hiding(num_chunks),
+            ));
+        }
+        if let Some(keccak_round_selector8) =
+            index.column_evaluations.keccak_round_selector8.as_ref()
+        {
+            polynomials.push((
+                evaluations_form(keccak_round_selector8),
+                non_hiding(num_chunks),
+            ));
+        }
+        if let Some(keccak_sponge_selector8) =
+            index.column_evaluations.keccak_sponge_selector8.as_ref()
+        {
+            polynomials.push((
+                evaluations_form(keccak_sponge_selector8),
+                non_hiding(num_chunks),
+            ));
+        }
 
         //~~ * optionally, the runtime table
         //~ 1. if using lookup:
@@ -1449,6 +1587,12 @@ where
                     non_hiding(1),
                 ))
             }
+            if let Some(cairo_memory_lookup_selector) = lcs.lookup_selectors.cairo_memory.as_ref() {
+                polynomials.push((
+                    evaluations_form(cairo_memory_lookup_selector),
+                    non_hiding(1),
+                ))
+            }
         }
 
         //~ 1. Create an aggregated evaluation proof for all of these polynomials at $\zeta$ and $\zeta\omega$ using $u$ and $v$.
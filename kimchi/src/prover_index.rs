@@ -6,6 +6,8 @@ use crate::{
         berkeley_columns::{BerkeleyChallengeTerm, Column},
         constraints::{ColumnEvaluations, ConstraintSystem},
         expr::{Linearization, PolishToken},
+        polynomials::keccak::constants::KECCAK_COLS,
+        wires::COLUMNS,
     },
     curve::KimchiCurve,
     linearization::expr_linearization,
@@ -22,7 +24,7 @@ use std::sync::Arc;
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 //~spec:startcode
-pub struct ProverIndex<G: KimchiCurve, OpeningProof: OpenProof<G>> {
+pub struct ProverIndex<G: KimchiCurve, OpeningProof: OpenProof<G>, const W: usize = COLUMNS> {
     /// constraints system polynomials
     #[serde(bound = "ConstraintSystem<G::ScalarField>: Serialize + DeserializeOwned")]
     pub cs: ConstraintSystem<G::ScalarField>,
@@ -44,12 +46,12 @@ pub struct ProverIndex<G: KimchiCurve, OpeningProof: OpenProof<G>> {
     /// maximal size of polynomial section
     pub max_poly_size: usize,
 
-    #[serde(bound = "ColumnEvaluations<G::ScalarField>: Serialize + DeserializeOwned")]
-    pub column_evaluations: ColumnEvaluations<G::ScalarField>,
+    #[serde(bound = "ColumnEvaluations<G::ScalarField, W>: Serialize + DeserializeOwned")]
+    pub column_evaluations: ColumnEvaluations<G::ScalarField, W>,
 
     /// The verifier index corresponding to this prover index
     #[serde(skip)]
-    pub verifier_index: Option<VerifierIndex<G, OpeningProof>>,
+    pub verifier_index: Option<VerifierIndex<G, OpeningProof, W>>,
 
     /// The verifier index digest corresponding to this prover index
     #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
@@ -57,23 +59,34 @@ pub struct ProverIndex<G: KimchiCurve, OpeningProof: OpenProof<G>> {
 }
 //~spec:endcode
 
-impl<G: KimchiCurve, OpeningProof: OpenProof<G>> ProverIndex<G, OpeningProof>
+impl<G: KimchiCurve, OpeningProof: OpenProof<G>, const W: usize> ProverIndex<G, OpeningProof, W>
 where
     G::BaseField: PrimeField,
 {
     /// this function compiles the index from constraints
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `W` is smaller than [COLUMNS], or smaller than
+    /// [KECCAK_COLS] when the circuit uses the Keccak gates.
     pub fn create(
         mut cs: ConstraintSystem<G::ScalarField>,
         endo_q: G::ScalarField,
         srs: Arc<OpeningProof::SRS>,
     ) -> Self {
+        assert!(W >= COLUMNS, "the index needs at least {COLUMNS} columns");
+        assert!(
+            !cs.feature_flags.keccak || W >= KECCAK_COLS,
+            "the Keccak gates need {KECCAK_COLS} columns"
+        );
+
         let max_poly_size = srs.max_poly_size();
         cs.endo = endo_q;
 
         // pre-compute the linearization
         let (linearization, powers_of_alpha) = expr_linearization(Some(&cs.feature_flags), true);
 
-        let evaluated_column_coefficients = cs.evaluated_column_coefficients();
+        let evaluated_column_coefficients = cs.evaluated_column_coefficients::<W>();
 
         let column_evaluations = cs.column_evaluations(&evaluated_column_coefficients);
 
@@ -97,7 +110,7 @@ where
         &mut self,
     ) -> G::BaseField
     where
-        VerifierIndex<G, OpeningProof>: Clone,
+        VerifierIndex<G, OpeningProof, W>: Clone,
     {
         if let Some(verifier_index_digest) = self.verifier_index_digest {
             return verifier_index_digest;
@@ -117,7 +130,7 @@ where
         &self,
     ) -> G::BaseField
     where
-        VerifierIndex<G, OpeningProof>: Clone,
+        VerifierIndex<G, OpeningProof, W>: Clone,
     {
         if let Some(verifier_index_digest) = self.verifier_index_digest {
             return verifier_index_digest;
@@ -154,6 +167,7 @@ pub mod testing {
         G: KimchiCurve,
         OpeningProof: OpenProof<G>,
         F: FnMut(D<G::ScalarField>, usize) -> OpeningProof::SRS,
+        const W: usize,
     >(
         gates: Vec<CircuitGate<G::ScalarField>>,
         public: usize,
@@ -163,7 +177,7 @@ pub mod testing {
         disable_gates_checks: bool,
         override_srs_size: Option<usize>,
         mut get_srs: F,
-    ) -> ProverIndex<G, OpeningProof>
+    ) -> ProverIndex<G, OpeningProof, W>
     where
         G::BaseField: PrimeField,
         G::ScalarField: PrimeField,
@@ -192,7 +206,7 @@ pub mod testing {
     /// # Panics
     ///
     /// Will panic if `constraint system` is not built with `gates` input.
-    pub fn new_index_for_test_with_lookups<G: KimchiCurve>(
+    pub fn new_index_for_test_with_lookups<G: KimchiCurve, const W: usize>(
         gates: Vec<CircuitGate<G::ScalarField>>,
         public: usize,
         prev_challenges: usize,
@@ -200,7 +214,7 @@ pub mod testing {
         runtime_tables: Option<Vec<RuntimeTableCfg<G::ScalarField>>>,
         disable_gates_checks: bool,
         override_srs_size: Option<usize>,
-    ) -> ProverIndex<G, OpeningProof<G>>
+    ) -> ProverIndex<G, OpeningProof<G>, W>
     where
         G::BaseField: PrimeField,
         G::ScalarField: PrimeField,
@@ -237,6 +251,6 @@ pub mod testing {
         G::BaseField: PrimeField,
         G::ScalarField: PrimeField,
     {
-        new_index_for_test_with_lookups::<G>(gates, public, 0, vec![], None, false, None)
+        new_index_for_test_with_lookups::<G, COLUMNS>(gates, public, 0, vec![], None, false, None)
     }
 }
@@ -5,7 +5,10 @@
 use std::marker::PhantomData;
 
 use crate::{
-    circuits::{constraints::ConstraintSystem, gate::CircuitGate, polynomial::COLUMNS},
+    circuits::{
+        constraints::ConstraintSystem, gate::CircuitGate, polynomial::COLUMNS,
+        witness_report::WitnessReport,
+    },
     curve::KimchiCurve,
     groupmap::GroupMap,
     mina_poseidon::FqSponge,
@@ -47,6 +50,13 @@ type Proof<C> = ProverProof<<C as SnarkyCircuit>::Curve, <C as SnarkyCircuit>::P
 type Output<C> = <<C as SnarkyCircuit>::PublicOutput as SnarkyType<
     ScalarField<<C as SnarkyCircuit>::Curve>,
 >>::OutOfCircuit;
+/// The witness, the public input (including the public output), and the public
+/// output of an execution of a circuit.
+type Execution<C> = (
+    Witness<ScalarField<<C as SnarkyCircuit>::Curve>>,
+    Vec<ScalarField<<C as SnarkyCircuit>::Curve>>,
+    Output<C>,
+);
 
 impl<Circuit> ProverIndexWrapper<Circuit>
 where
@@ -61,21 +71,13 @@ where
         .generate_asm()
     }
 
-    /// Produces a proof for the given public input.
-    pub fn prove<EFqSponge, EFrSponge>(
-        // TODO: this should not be mutable ideally
+    /// Runs the circuit on the given inputs, and returns the witness, the public
+    /// input (including the public output), and the public output.
+    fn generate_witness(
         &mut self,
         public_input: <Circuit::PublicInput as SnarkyType<ScalarField<Circuit::Curve>>>::OutOfCircuit,
         private_input: Circuit::PrivateInput,
-        // TODO: rename to verify_witness?
-        debug: bool,
-    ) -> SnarkyResult<(Proof<Circuit>, Box<Output<Circuit>>)>
-    where
-        <Circuit::Curve as AffineRepr>::BaseField: PrimeField,
-        EFqSponge: Clone
-            + FqSponge<BaseField<Circuit::Curve>, Circuit::Curve, ScalarField<Circuit::Curve>>,
-        EFrSponge: FrSponge<ScalarField<Circuit::Curve>>,
-    {
+    ) -> SnarkyResult<Execution<Circuit>> {
         // create public input
         let public_input_without_output =
             Circuit::PublicInput::value_to_field_elements(&public_input).0;
@@ -133,6 +135,71 @@ where
         let public_output =
             Circuit::PublicOutput::value_of_field_elements(public_output_values, aux);
 
+        Ok((witness, public_input_and_output, public_output))
+    }
+
+    /// Runs the circuit on the given inputs without checking the constraints
+    /// on the fly, and checks the resulting witness against every gate of the
+    /// circuit. The failures are reported with the labels of their rows.
+    pub fn check_witness(
+        &mut self,
+        public_input: <Circuit::PublicInput as SnarkyType<ScalarField<Circuit::Curve>>>::OutOfCircuit,
+        private_input: Circuit::PrivateInput,
+    ) -> SnarkyResult<WitnessReport<ScalarField<Circuit::Curve>>> {
+        self.check_tampered_witness(public_input, private_input, |_, _| ())
+    }
+
+    /// Same as [Self::check_witness], but the witness is modified by `tamper`,
+    /// given the gates of the circuit, before being checked.
+    /// This is used to test that the gadgets reject bad witnesses.
+    pub(crate) fn check_tampered_witness(
+        &mut self,
+        public_input: <Circuit::PublicInput as SnarkyType<ScalarField<Circuit::Curve>>>::OutOfCircuit,
+        private_input: Circuit::PrivateInput,
+        tamper: impl FnOnce(
+            &[CircuitGate<ScalarField<Circuit::Curve>>],
+            &mut [Vec<ScalarField<Circuit::Curve>>; COLUMNS],
+        ),
+    ) -> SnarkyResult<WitnessReport<ScalarField<Circuit::Curve>>> {
+        let eval_constraints = self.compiled_circuit.sys.eval_constraints;
+        self.compiled_circuit.sys.eval_constraints = false;
+        let res = self.generate_witness(public_input, private_input);
+        self.compiled_circuit.sys.eval_constraints = eval_constraints;
+        let (mut witness, public_input_and_output, _) = res?;
+        tamper(&self.index.cs.gates, &mut witness.0);
+
+        // the constraint system is always set after compilation
+        let labels = self
+            .compiled_circuit
+            .sys
+            .system
+            .as_ref()
+            .unwrap()
+            .row_labels();
+        Ok(self
+            .index
+            .check_witness(&witness.0, &public_input_and_output)
+            .with_labels(&labels))
+    }
+
+    /// Produces a proof for the given public input.
+    pub fn prove<EFqSponge, EFrSponge>(
+        // TODO: this should not be mutable ideally
+        &mut self,
+        public_input: <Circuit::PublicInput as SnarkyType<ScalarField<Circuit::Curve>>>::OutOfCircuit,
+        private_input: Circuit::PrivateInput,
+        // TODO: rename to verify_witness?
+        debug: bool,
+    ) -> SnarkyResult<(Proof<Circuit>, Box<Output<Circuit>>)>
+    where
+        <Circuit::Curve as AffineRepr>::BaseField: PrimeField,
+        EFqSponge: Clone
+            + FqSponge<BaseField<Circuit::Curve>, Circuit::Curve, ScalarField<Circuit::Curve>>,
+        EFrSponge: FrSponge<ScalarField<Circuit::Curve>>,
+    {
+        let (witness, public_input_and_output, public_output) =
+            self.generate_witness(public_input, private_input)?;
+
         // verify the witness
         // TODO: return error instead of panicking
         if debug {
@@ -191,7 +258,7 @@ where
         // verify the proof
         let group_map = <Circuit::Curve as CommitmentCurve>::Map::setup();
 
-        verify::<Circuit::Curve, EFqSponge, EFrSponge, Circuit::Proof>(
+        verify::<Circuit::Curve, EFqSponge, EFrSponge, Circuit::Proof, COLUMNS>(
             &group_map,
             &self.index,
             &proof,
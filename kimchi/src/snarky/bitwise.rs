@@ -0,0 +1,160 @@
+//! Bitwise gadgets on words of a fixed length:
+//! XOR with the `Xor16` gate, AND on top of it, NOT as a linear combination,
+//! and the rotation of 64-bit words with the `Rot64` gate.
+
+use super::{
+    constraint_system::{KimchiConstraint, Rot64Input},
+    range_checks::{range_check_64_limbs, range_check_64_row},
+    runner::Constraint,
+};
+use crate::{
+    circuits::{
+        expr::constraints::ExprOps,
+        polynomials::{rot::RotMode, xor::num_xors},
+    },
+    FieldVar, RunState, SnarkyResult,
+};
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use o1_utils::{BigUintFieldHelpers, FieldHelpers};
+use std::borrow::Cow;
+
+/// Extracts the 4-bit nybbles of the 16 least significant bits of `word`
+fn xor_nybbles<F: PrimeField>(word: &BigUint) -> [F; 4] {
+    std::array::from_fn(|i| {
+        ((word >> (4 * i)) & BigUint::from(0xfu8))
+            .to_field()
+            .unwrap()
+    })
+}
+
+/// Constrains `out = in1 XOR in2`, returning `out`.
+/// The inputs are constrained to fit in `bits`, rounded up to a multiple of 16.
+pub fn xor<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    in1: FieldVar<F>,
+    in2: FieldVar<F>,
+    bits: usize,
+) -> SnarkyResult<FieldVar<F>> {
+    let out: FieldVar<F> = runner.compute(loc.clone(), |w| {
+        let in1 = w.read_var(&in1).to_biguint();
+        let in2 = w.read_var(&in2).to_biguint();
+        (in1 ^ in2).to_field().unwrap()
+    })?;
+
+    // each Xor16 row decomposes the 16 least significant bits of the words
+    // and passes the rest of the words to the next row,
+    // and the words are fully decomposed once the final row is zero
+    let num_xors = num_xors(bits);
+    let mut rows = vec![];
+    let mut words = [in1, in2, out.clone()];
+    for i in 0..num_xors {
+        let nybbles: [[FieldVar<F>; 4]; 3] = runner.compute(loc.clone(), |w| {
+            words
+                .clone()
+                .map(|v| xor_nybbles(&w.read_var(&v).to_biguint()))
+        })?;
+        let next: [FieldVar<F>; 3] = if i + 1 < num_xors {
+            runner.compute(loc.clone(), |w| {
+                words
+                    .clone()
+                    .map(|v| (w.read_var(&v).to_biguint() >> 16u32).to_field().unwrap())
+            })?
+        } else {
+            [FieldVar::zero(), FieldVar::zero(), FieldVar::zero()]
+        };
+        let row = words.into_iter().chain(nybbles.into_iter().flatten());
+        rows.push(row.collect());
+        words = next;
+    }
+    rows.push(words.to_vec());
+
+    let constraint = Constraint::KimchiConstraint(KimchiConstraint::Xor16(rows));
+    runner.add_constraint(constraint, Some("Xor".into()), loc)?;
+
+    Ok(out)
+}
+
+/// Constrains `out = in1 AND in2`, returning `out`.
+/// It relies on `in1 + in2 = (in1 XOR in2) + 2 * (in1 AND in2)`,
+/// and the inputs are constrained to fit in `bits` by the XOR.
+pub fn and<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    in1: FieldVar<F>,
+    in2: FieldVar<F>,
+    bits: usize,
+) -> SnarkyResult<FieldVar<F>> {
+    let out: FieldVar<F> = runner.compute(loc.clone(), |w| {
+        let in1 = w.read_var(&in1).to_biguint();
+        let in2 = w.read_var(&in2).to_biguint();
+        (in1 & in2).to_field().unwrap()
+    })?;
+    let xor = xor(runner, loc.clone(), in1.clone(), in2.clone(), bits)?;
+
+    let sum = in1 + in2;
+    let res = xor + out.scale(F::from(2u64));
+    runner.assert_eq(Some("And".into()), loc, sum, res)?;
+
+    Ok(out)
+}
+
+/// Returns `NOT input` on `bits` bits, as the linear combination `2^bits - 1 - input`.
+/// The input must already be known to fit in `bits`.
+pub fn not<F: PrimeField>(input: FieldVar<F>, bits: usize) -> FieldVar<F> {
+    FieldVar::constant(F::two_pow(bits as u64) - F::one()) - input
+}
+
+/// Constrains `rotated` to be the rotation of the 64-bit `word` by `rot` bits
+/// to the left or to the right, returning `rotated`.
+/// The word must already be known to fit in 64 bits.
+pub fn rot64<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    word: FieldVar<F>,
+    rot: u32,
+    side: RotMode,
+) -> SnarkyResult<FieldVar<F>> {
+    assert!(rot <= 64, "Rotation value must be less or equal than 64");
+    let rot = if side == RotMode::Right {
+        64 - rot
+    } else {
+        rot
+    };
+
+    // word * 2^rot = excess * 2^64 + shifted
+    let (rotated, (excess, shifted)): (FieldVar<F>, (FieldVar<F>, FieldVar<F>)) =
+        runner.compute(loc.clone(), |w| {
+            let word = w.read_var(&word).to_biguint();
+            let shifted = (&word << rot) % (BigUint::from(1u8) << 64);
+            let excess = &word >> (64 - rot);
+            let rotated = &shifted + &excess;
+            let [rotated, excess, shifted] =
+                [rotated, excess, shifted].map(|v| v.to_field().unwrap());
+            (rotated, (excess, shifted))
+        })?;
+
+    // the excess is also checked to be less than 2^rot
+    let (bound_limbs, bound_crumbs): ([FieldVar<F>; 4], [FieldVar<F>; 8]) =
+        runner.compute(loc.clone(), |w| {
+            let bound = w.read_var(&excess) + F::two_pow(64) - F::two_pow(rot as u64);
+            range_check_64_limbs(bound)
+        })?;
+
+    let rot_row = [word, rotated.clone(), excess.clone()]
+        .into_iter()
+        .chain(bound_limbs)
+        .chain(bound_crumbs)
+        .collect();
+    let shifted_row = range_check_64_row(runner, loc.clone(), shifted)?;
+    let excess_row = range_check_64_row(runner, loc.clone(), excess)?;
+
+    let constraint = Constraint::KimchiConstraint(KimchiConstraint::Rot64(Rot64Input {
+        rows: vec![rot_row, shifted_row, excess_row],
+        two_to_rot: F::two_pow(rot as u64),
+    }));
+    runner.add_constraint(constraint, Some("Rot64".into()), loc)?;
+
+    Ok(rotated)
+}
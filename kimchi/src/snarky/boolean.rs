@@ -90,6 +90,43 @@ where
         self.0.clone()
     }
 
+    /// Returns the booleans `index == i` for `i` in `0..len`,
+    /// which constrains `index` to be smaller than `len`.
+    pub fn one_hot(
+        index: &FieldVar<F>,
+        len: usize,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<Vec<Self>> {
+        let mut flags: Vec<Self> = vec![];
+        for i in 0..len {
+            let flag = cs.compute(loc.clone(), |w| w.read_var(index) == F::from(i as u64))?;
+            flags.push(flag);
+        }
+
+        // exactly one flag is set, at the index
+        let sum = FieldVar::sum(&flags.iter().map(|flag| &flag.0).collect::<Vec<_>>());
+        cs.assert_eq(
+            Some("one_hot".into()),
+            loc.clone(),
+            sum,
+            FieldVar::constant(F::one()),
+        )?;
+        let terms: Vec<_> = flags
+            .iter()
+            .enumerate()
+            .map(|(i, flag)| (F::from(i as u64), flag.0.clone()))
+            .collect();
+        cs.assert_eq(
+            Some("one_hot".into()),
+            loc,
+            FieldVar::linear_combination(&terms),
+            index.clone(),
+        )?;
+
+        Ok(flags)
+    }
+
     pub fn not(&self) -> Self {
         Self(Self::true_().0 - &self.0)
     }
@@ -233,9 +233,49 @@ pub struct EcEndoscaleInput<Var> {
     pub n_acc: Var,
 }
 
+#[derive(Debug)]
+#[cfg_attr(
+    feature = "ocaml_types",
+    derive(ocaml::IntoValue, ocaml::FromValue, ocaml_gen::Struct)
+)]
+pub struct Rot64Input<Var, Field> {
+    //[[Var; 15]; 3]: the Rot64 row, followed by the RangeCheck0 rows of the shifted and excess words
+    pub rows: Vec<Vec<Var>>,
+    pub two_to_rot: Field,
+}
+
+#[derive(Debug)]
+#[cfg_attr(
+    feature = "ocaml_types",
+    derive(ocaml::IntoValue, ocaml::FromValue, ocaml_gen::Struct)
+)]
+pub struct ForeignFieldAddInput<Var, Field> {
+    pub left: Vec<Var>,
+    pub right: Vec<Var>,
+    pub overflow: Var,
+    pub carry: Var,
+    pub result: Vec<Var>,
+    pub foreign_field_modulus: Vec<Field>,
+    pub sign: Field,
+}
+
+#[derive(Debug)]
+#[cfg_attr(
+    feature = "ocaml_types",
+    derive(ocaml::IntoValue, ocaml::FromValue, ocaml_gen::Struct)
+)]
+pub struct ForeignFieldMulInput<Var, Field> {
+    //[[Var; 15]; 2]: the ForeignFieldMul row, followed by its Zero row
+    pub rows: Vec<Vec<Var>>,
+    pub hi_foreign_field_modulus: Field,
+    pub neg_foreign_field_modulus: Vec<Field>,
+}
+
 /** A PLONK constraint (or gate) can be [`Basic`](KimchiConstraint::Basic), [`Poseidon`](KimchiConstraint::Poseidon),
  * [`EcAddComplete`](KimchiConstraint::EcAddComplete), [`EcScale`](KimchiConstraint::EcScale),
- * [`EcEndoscale`](KimchiConstraint::EcEndoscale), or [`EcEndoscalar`](KimchiConstraint::EcEndoscalar). */
+ * [`EcEndoscale`](KimchiConstraint::EcEndoscale), [`EcEndoscalar`](KimchiConstraint::EcEndoscalar),
+ * [`RangeCheck`](KimchiConstraint::RangeCheck), [`Xor16`](KimchiConstraint::Xor16), [`Rot64`](KimchiConstraint::Rot64),
+ * [`ForeignFieldAdd`](KimchiConstraint::ForeignFieldAdd), or [`ForeignFieldMul`](KimchiConstraint::ForeignFieldMul). */
 #[derive(Debug)]
 #[cfg_attr(
     feature = "ocaml_types",
@@ -251,6 +291,11 @@ pub enum KimchiConstraint<Var, Field> {
     EcEndoscalar(Vec<EndoscaleScalarRound<Var>>),
     //[[Var; 15]; 4]
     RangeCheck(Vec<Vec<Var>>),
+    //[[Var; 15]; N + 1]: N chained Xor16 rows, followed by a zero row
+    Xor16(Vec<Vec<Var>>),
+    Rot64(Rot64Input<Var, Field>),
+    ForeignFieldAdd(ForeignFieldAddInput<Var, Field>),
+    ForeignFieldMul(ForeignFieldMulInput<Var, Field>),
 }
 
 /* TODO: This is a Unique_id in OCaml. */
@@ -299,6 +344,8 @@ where
     internal_vars: HashMap<InternalVar, (Vec<(Field, V)>, Option<Field>)>,
     /** The variables that hold each witness value for each row, in reverse order. */
     rows: Vec<Vec<Option<V>>>,
+    /// The labels of the constraints that produced each row, see [Self::row_labels].
+    labels: Vec<Vec<Cow<'static, str>>>,
     /** A circuit is described by a series of gates.
        A gate is finalized once [finalize_and_get_gates](SnarkyConstraintSystem::finalize_and_get_gates) is called.
        The finalized tag contains the digest of the circuit.
@@ -494,6 +541,7 @@ impl<Field: PrimeField> SnarkyConstraintSystem<Field> {
             internal_vars: HashMap::new(),
             gates: Circuit::Unfinalized(Vec::new()),
             rows: Vec::new(),
+            labels: Vec::new(),
             next_row: 0,
             equivalence_classes: HashMap::new(),
             generic_gate_optimization: true,
@@ -576,6 +624,7 @@ impl<Field: PrimeField> SnarkyConstraintSystem<Field> {
         }
         self.next_row += 1;
         self.rows.push(vars);
+        self.labels.push(labels.to_vec());
     }
 
     /// Returns the number of rows in the constraint system.
@@ -585,6 +634,20 @@ impl<Field: PrimeField> SnarkyConstraintSystem<Field> {
         self.rows.len()
     }
 
+    /// Returns, for each row of the compiled circuit, the labels that were on
+    /// the stack (see [RunState::with_label](crate::snarky::runner::RunState::with_label))
+    /// when the constraint of the row was added. The public input rows have no
+    /// labels.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `public_input_size` is None.
+    pub fn row_labels(&self) -> Vec<Vec<Cow<'static, str>>> {
+        let mut labels = vec![vec![]; self.get_primary_input_size()];
+        labels.extend(self.labels.iter().cloned());
+        labels
+    }
+
     /// Fill the `gate` values(input and output), and finalize the `circuit`.
     ///
     /// # Panics
@@ -968,7 +1031,7 @@ impl<Field: PrimeField> SnarkyConstraintSystem<Field> {
                 if s == Field::one() {
                     x
                 } else {
-                    let sx = self.create_internal(Some(s), vec![(s, x)]);
+                    let sx = self.create_internal(None, vec![(s, x)]);
                     // s * x - sx = 0
                     self.add_generic_constraint(
                         labels,
@@ -990,7 +1053,7 @@ impl<Field: PrimeField> SnarkyConstraintSystem<Field> {
             (s, ConstantOrVar::Constant) => match self.cached_constants.get(&s) {
                 Some(x) => *x,
                 None => {
-                    let x = self.create_internal(None, vec![]);
+                    let x = self.create_internal(Some(s), vec![]);
                     self.add_generic_constraint(
                         labels,
                         loc,
@@ -1683,8 +1746,106 @@ impl<Field: PrimeField> SnarkyConstraintSystem<Field> {
                 self.add_row(labels, loc, r2, GateType::RangeCheck1, vec![]);
                 self.add_row(labels, loc, r3, GateType::Zero, vec![]);
             }
+            KimchiConstraint::Xor16(rows) => {
+                assert!(rows.len() > 1, "a xor needs at least one Xor16 row");
+                let num_xors = rows.len() - 1;
+                let rows = self.reduce_rows(labels, loc, rows);
+                for (i, row) in rows.into_iter().enumerate() {
+                    let kind = if i < num_xors {
+                        GateType::Xor16
+                    } else {
+                        GateType::Zero
+                    };
+                    self.add_row(labels, loc, row, kind, vec![]);
+                }
+            }
+            KimchiConstraint::Rot64(Rot64Input { rows, two_to_rot }) => {
+                assert_eq!(rows.len(), 3, "wrong number of rows");
+                let [rot, shifted, excess]: [_; 3] =
+                    self.reduce_rows(labels, loc, rows).try_into().unwrap();
+                self.add_row(labels, loc, rot, GateType::Rot64, vec![two_to_rot]);
+                self.add_row(
+                    labels,
+                    loc,
+                    shifted,
+                    GateType::RangeCheck0,
+                    vec![Field::zero()],
+                );
+                self.add_row(
+                    labels,
+                    loc,
+                    excess,
+                    GateType::RangeCheck0,
+                    vec![Field::zero()],
+                );
+            }
+            KimchiConstraint::ForeignFieldAdd(ForeignFieldAddInput {
+                left,
+                right,
+                overflow,
+                carry,
+                result,
+                foreign_field_modulus,
+                sign,
+            }) => {
+                assert!(left.len() == 3 && right.len() == 3 && result.len() == 3);
+                assert_eq!(foreign_field_modulus.len(), 3);
+                // 0   1   2   3   4   5   6         7
+                // a0  a1  a2  b0  b1  b2  overflow  carry
+                // r0  r1  r2
+                let curr_row = left
+                    .into_iter()
+                    .chain(right)
+                    .chain([overflow, carry])
+                    .collect();
+                let [curr_row, next_row]: [_; 2] = self
+                    .reduce_rows(labels, loc, vec![curr_row, result])
+                    .try_into()
+                    .unwrap();
+                let mut coeffs = foreign_field_modulus;
+                coeffs.push(sign);
+                self.add_row(labels, loc, curr_row, GateType::ForeignFieldAdd, coeffs);
+                self.add_row(labels, loc, next_row, GateType::Zero, vec![]);
+            }
+            KimchiConstraint::ForeignFieldMul(ForeignFieldMulInput {
+                rows,
+                hi_foreign_field_modulus,
+                neg_foreign_field_modulus,
+            }) => {
+                assert_eq!(rows.len(), 2, "wrong number of rows");
+                assert_eq!(neg_foreign_field_modulus.len(), 3);
+                let [curr_row, next_row]: [_; 2] =
+                    self.reduce_rows(labels, loc, rows).try_into().unwrap();
+                let mut coeffs = vec![hi_foreign_field_modulus];
+                coeffs.extend(neg_foreign_field_modulus);
+                self.add_row(labels, loc, curr_row, GateType::ForeignFieldMul, coeffs);
+                self.add_row(labels, loc, next_row, GateType::Zero, vec![]);
+            }
         }
     }
+
+    /// Reduces the variables of all the given rows to internal variables.
+    /// This must happen before any of the rows is added,
+    /// as the reduction might add generic gates in between chained rows.
+    fn reduce_rows<Cvar>(
+        &mut self,
+        labels: &[Cow<'static, str>],
+        loc: &Cow<'static, str>,
+        rows: Vec<Vec<Cvar>>,
+    ) -> Vec<Vec<Option<V>>>
+    where
+        Cvar: SnarkyCvar<Field = Field>,
+    {
+        rows.into_iter()
+            .map(|row| {
+                assert!(row.len() <= COLUMNS, "size of row is > {COLUMNS}");
+                row.into_iter()
+                    .map(|v| Some(self.reduce_to_var(labels, loc, v)))
+                    .collect()
+            })
+            .collect()
+    }
+
     pub(crate) fn sponge_params(&self) -> mina_poseidon::poseidon::ArithmeticSpongeParams<Field> {
         self.constants.poseidon.clone()
     }
@@ -1809,7 +1970,11 @@ where
             | KimchiConstraint::EcScale { .. }
             | KimchiConstraint::EcEndoscale { .. }
             | KimchiConstraint::EcEndoscalar { .. }
-            | KimchiConstraint::RangeCheck { .. } => (),
+            | KimchiConstraint::RangeCheck { .. }
+            | KimchiConstraint::Xor16 { .. }
+            | KimchiConstraint::Rot64 { .. }
+            | KimchiConstraint::ForeignFieldAdd { .. }
+            | KimchiConstraint::ForeignFieldMul { .. } => (),
         };
         Ok(())
     }
@@ -1,31 +1,98 @@
-use self::instance::{Instance, RelaxedInstance};
-use super::{poseidon::DuplexState, prelude::*};
+//! An in-circuit verifier for one step of Nova-style folding,
+//! to use kimchi circuits as the step function of an IVC chain.
+//!
+//! The circuit is defined over the scalar field of the curve `C`,
+//! and the folded commitments are points of the other curve of the cycle,
+//! whose coordinates are native field elements.
+//! The scalars of the other curve (the challenges and `u` of the relaxed instance)
+//! are foreign field elements, handled with the [foreign_field] gadgets.
+
+use self::instance::{Instance, RelaxedInstance, WitnessCommitments};
+use super::{
+    boolean::Boolean,
+    constraint_system::{EcAddCompleteInput, KimchiConstraint, ScaleRound},
+    foreign_field::{self, ForeignFieldModulus},
+    poseidon::DuplexState,
+    prelude::*,
+    runner::Constraint,
+    snarky_type::SnarkyType,
+};
 use crate::{
+    circuits::{
+        polynomials::{foreign_field_common::LIMB_BITS, varbasemul},
+        wires::COLUMNS,
+    },
     curve::KimchiCurve,
     loc,
     snarky::{api::SnarkyCircuit, cvar::FieldVar, runner::RunState},
 };
 use ark_ec::AffineRepr;
-use ark_ff::{BigInteger, One, PrimeField};
-use mina_curves::pasta::Fp;
-use poly_commitment::{ipa::OpeningProof, OpenProof};
+use ark_ff::{BigInteger, Field, One, PrimeField};
+use num_bigint::BigUint;
+use num_traits::Zero;
+use poly_commitment::OpenProof;
 use std::{marker::PhantomData, ops::Add};
 
-mod instance;
+pub mod instance;
 
 const CHALLENGE_BITS: usize = 127;
 
-struct FoldingCircuit<C: KimchiCurve, P: OpenProof<C>, const N: usize> {
+/// The number of bits processed by the `VarBaseMul` gates of a scalar multiplication,
+/// a multiple of 5 large enough for half a challenge
+const SCALE_BITS: usize = 130;
+
+/// The number of `VarBaseMul` gates of a scalar multiplication
+const SCALE_ROUNDS: usize = SCALE_BITS / 5;
+
+/// The function being folded, run in the circuit on the input of a step
+/// to return the input of the next step
+pub type StepFunction<F, const N: usize> =
+    fn(&mut RunState<F>, Argument<FieldVar<F>, N>) -> SnarkyResult<Argument<FieldVar<F>, N>>;
+
+pub struct FoldingCircuit<C: KimchiCurve, P: OpenProof<C>, const N: usize> {
     _field: PhantomData<C>,
     _proof: PhantomData<P>,
     /// Commitment sets and their sizes
     commitments: Vec<usize>,
     /// Challenges sets and their sizes
     challenges: Vec<usize>,
+    /// The function being folded
+    step: StepFunction<F<C>, N>,
+}
+
+impl<C: KimchiCurve, P: OpenProof<C>, const N: usize> FoldingCircuit<C, P, N> {
+    pub fn new(
+        commitments: Vec<usize>,
+        challenges: Vec<usize>,
+        step: StepFunction<F<C>, N>,
+    ) -> Self {
+        Self {
+            _field: PhantomData,
+            _proof: PhantomData,
+            commitments,
+            challenges,
+            step,
+        }
+    }
+}
+
+/// The modulus of the scalar field of the other curve of the cycle,
+/// which is the base field of `C`
+#[derive(Debug, Clone)]
+pub struct OtherField<C>(PhantomData<C>);
+
+impl<C: KimchiCurve> ForeignFieldModulus for OtherField<C> {
+    fn modulus() -> BigUint {
+        C::BaseField::characteristic()
+            .iter()
+            .rev()
+            .fold(BigUint::zero(), |acc, limb| (acc << 64) + limb)
+    }
 }
 
 /// The value of the input an output of the function being folded
-struct Argument<F, const N: usize>([F; N]);
+#[derive(Debug, Clone)]
+pub struct Argument<F, const N: usize>(pub [F; N]);
 
 type Point<F> = [F; 2];
 
@@ -33,70 +100,257 @@ type Point<F> = [F; 2];
 type Hash<F> = FieldVar<F>;
 
 /// Represents an element of the other curve's field. As the other field can
-/// have a higher order, it could require more than one limbs. We suppose the
-/// other field values can be encoded on two limbs.
+/// have a higher order, it could require more than one limbs. The other field
+/// values are encoded on three limbs of 88 bits, as the [foreign_field] gadgets.
 #[derive(Debug, Clone)]
-pub struct ForeignElement<F>([F; 2]);
+pub struct ForeignElement<F>(pub [F; 3]);
 
 /// A challenge encoded on 127 bits
 #[derive(Debug, Clone)]
 struct SmallChallenge<F: PrimeField>(FieldVar<F>);
 
 #[derive(Debug, Clone)]
-pub struct FullChallenge<F>(ForeignElement<F>);
+pub struct FullChallenge<F>(pub ForeignElement<F>);
+
+/// The public input of a step, `((i, [hash1, hash2]), (z_0, z_i))`, where the
+/// hashes are the ones of the newest instance
+pub type Public<F, const N: usize> = ((F, [F; 2]), ([F; N], [F; N]));
 
 pub struct Private<F, const N: usize> {
-    /// First input
-    z_0: Argument<F, N>,
-    /// Input for this step and output of the previous one
-    z_i: Argument<F, N>,
-    /// All instances accumulated so far
-    u_acc: RelaxedInstance<F>,
-    /// Newest instance to be folded into the accumulated instance
-    u_i: Instance<F>,
+    /// All instances accumulated so far, or the initial accumulator if `i` is 0
+    pub u_acc: RelaxedInstance<F>,
+    /// Witness commitments of the newest instance to be folded into the
+    /// accumulated instance, whose hashes are public
+    pub u_i_commitments: Vec<WitnessCommitments<F>>,
     /// 2 commitments to error terms to be used when folding the error column
-    t: [Point<F>; 2],
-    i: F,
+    pub t: [Point<F>; 2],
 }
 
-/// This should run the inner circuit and provide its output, in our case it may be simpler
-/// to just run the circuit separatly and provide the variables pointing to the output
+/// Runs the function being folded on the input of this step,
+/// returning the input of the next step
 fn apply<F: PrimeField, const N: usize>(
-    _z_i: Argument<FieldVar<F>, N>,
-) -> Argument<FieldVar<F>, N> {
-    // TODO
-    todo!()
+    sys: &mut RunState<F>,
+    step: StepFunction<F, N>,
+    z_i: Argument<FieldVar<F>, N>,
+) -> SnarkyResult<Argument<FieldVar<F>, N>> {
+    step(sys, z_i)
 }
 
 type F<C> = <C as AffineRepr>::ScalarField;
 
-fn challenge_linear_combination<F: PrimeField>(
-    _full: FullChallenge<FieldVar<F>>,
-    _small: SmallChallenge<F>,
-    _combiner: &SmallChallenge<F>,
-) -> FullChallenge<FieldVar<F>> {
-    // TODO
-    todo!()
+/// Splits `v` in two limbs of 88 bits, constraining it to fit in `bits` bits.
+/// `bits` must be between 88 and 176.
+fn split_limbs<F: PrimeField>(
+    sys: &mut RunState<F>,
+    v: &FieldVar<F>,
+    bits: usize,
+) -> SnarkyResult<[FieldVar<F>; 2]> {
+    assert!((LIMB_BITS..=2 * LIMB_BITS).contains(&bits));
+    let (low, high): (FieldVar<F>, FieldVar<F>) = sys.compute(loc!(), |wit| {
+        let val = wit.read_var(v).into_bigint();
+        let mut high = val;
+        high.divn(LIMB_BITS as u32);
+        let mut low = high;
+        low.muln(LIMB_BITS as u32);
+        let mut diff = val;
+        diff.sub_with_borrow(&low);
+        (F::from_bigint(diff).unwrap(), F::from_bigint(high).unwrap())
+    })?;
+
+    // the high limb fits in `bits - 88` bits iff its shift by `176 - bits` fits in 88 bits
+    let shifted = high.scale(F::from(2u64).pow([(2 * LIMB_BITS - bits) as u64]));
+    sys.range_check(loc!(), low.clone(), high.clone(), shifted)?;
+
+    let composition = &low + high.scale(F::from(2u64).pow([LIMB_BITS as u64]));
+    v.assert_equals(sys, loc!(), &composition)?;
+    Ok([low, high])
 }
 
+/// Converts a challenge of the native field to an element of the foreign field
+fn challenge_to_foreign<F: PrimeField, M: ForeignFieldModulus>(
+    sys: &mut RunState<F>,
+    challenge: &SmallChallenge<F>,
+) -> SnarkyResult<foreign_field::ForeignElement<F, M>> {
+    let [low, high] = split_limbs(sys, &challenge.0, CHALLENGE_BITS)?;
+    Ok(foreign_field::ForeignElement::from_limbs_unsafe([
+        low,
+        high,
+        FieldVar::zero(),
+    ]))
+}
+
+/// Returns `full + small * combiner` in the other field
+fn challenge_linear_combination<F: PrimeField, M: ForeignFieldModulus>(
+    sys: &mut RunState<F>,
+    full: FullChallenge<FieldVar<F>>,
+    small: SmallChallenge<F>,
+    combiner: &SmallChallenge<F>,
+) -> SnarkyResult<FullChallenge<FieldVar<F>>> {
+    let full = foreign_field::ForeignElement::<F, M>::from_limbs_unsafe(full.0 .0);
+    full.check(sys, loc!())?;
+    let small = challenge_to_foreign::<F, M>(sys, &small)?;
+    let combiner = challenge_to_foreign::<F, M>(sys, combiner)?;
+
+    let product = small.mul(&combiner, sys, loc!())?;
+    let res = full.add(&product, sys, loc!())?;
+    Ok(FullChallenge(ForeignElement(res.limbs().clone())))
+}
+
+/// Returns `a + combiner * b`
 fn commitment_linear_combination<F: PrimeField>(
-    _a: Point<FieldVar<F>>,
-    _b: Point<FieldVar<F>>,
-    _combiner: &SmallChallenge<F>,
-) -> Point<FieldVar<F>> {
-    // TODO
-    todo!()
+    sys: &mut RunState<F>,
+    a: Point<FieldVar<F>>,
+    b: Point<FieldVar<F>>,
+    combiner: &SmallChallenge<F>,
+) -> SnarkyResult<Point<FieldVar<F>>> {
+    let b = ec_scale(sys, b, combiner)?;
+    ec_add(sys, a, b)
+}
+
+/// Returns `a + b` with a `CompleteAdd` gate.
+/// The points must not be the point at infinity.
+fn ec_add<F: PrimeField>(
+    sys: &mut RunState<F>,
+    a: Point<FieldVar<F>>,
+    b: Point<FieldVar<F>>,
+) -> SnarkyResult<Point<FieldVar<F>>> {
+    let [x3, y3, inf, same_x, slope, inf_z, x21_inv]: [FieldVar<F>; 7] =
+        sys.compute(loc!(), |wit| {
+            let [x1, y1] = a.clone().map(|v| wit.read_var(&v));
+            let [x2, y2] = b.clone().map(|v| wit.read_var(&v));
+            let same_x = x1 == x2;
+            let inf = same_x && y1 != y2;
+            let slope = if same_x {
+                // the curves of the cycle have a = 0
+                (x1.square() * F::from(3u64)) / y1.double()
+            } else {
+                (y2 - y1) / (x2 - x1)
+            };
+            let (x3, y3) = if inf {
+                (F::zero(), F::zero())
+            } else {
+                let x3 = slope.square() - x1 - x2;
+                (x3, slope * (x1 - x3) - y1)
+            };
+            let inf_z = if inf {
+                (y2 - y1).inverse().unwrap()
+            } else {
+                F::zero()
+            };
+            let x21_inv = (x2 - x1).inverse().unwrap_or_else(F::zero);
+            [x3, y3, F::from(inf), F::from(same_x), slope, inf_z, x21_inv]
+        })?;
+
+    let [x1, y1] = a;
+    let [x2, y2] = b;
+    let constraint = KimchiConstraint::EcAddComplete(EcAddCompleteInput {
+        p1: (x1, y1),
+        p2: (x2, y2),
+        p3: (x3.clone(), y3.clone()),
+        inf,
+        same_x,
+        slope,
+        inf_z,
+        x21_inv,
+    });
+    sys.add_constraint(
+        Constraint::KimchiConstraint(constraint),
+        Some("ec_add".into()),
+        loc!(),
+    )?;
+    Ok([x3, y3])
 }
-fn ec_add<F: PrimeField>(_a: Point<FieldVar<F>>, _b: Point<FieldVar<F>>) -> Point<FieldVar<F>> {
-    // TODO
-    todo!()
+
+/// Returns `(2^SCALE_BITS + 1 + 2 * scalar) * base` with `VarBaseMul` gates,
+/// where `acc0` is `2 * base` and `scalar` fits in `SCALE_BITS` bits
+fn var_base_mul<F: PrimeField>(
+    sys: &mut RunState<F>,
+    base: &Point<FieldVar<F>>,
+    acc0: &Point<FieldVar<F>>,
+    scalar: &FieldVar<F>,
+) -> SnarkyResult<Point<FieldVar<F>>> {
+    // the witness of the gates, from the kimchi witness generator
+    let table: [[FieldVar<F>; COLUMNS]; 2 * SCALE_ROUNDS] = sys.compute(loc!(), |wit| {
+        let [xt, yt] = base.clone().map(|v| wit.read_var(&v));
+        let [x0, y0] = acc0.clone().map(|v| wit.read_var(&v));
+        let bits = wit.read_var(scalar).into_bigint().to_bits_le();
+        let bits_msb: Vec<_> = bits.into_iter().take(SCALE_BITS).rev().collect();
+
+        let mut w: [Vec<F>; COLUMNS] = std::array::from_fn(|_| vec![F::zero(); 2 * SCALE_ROUNDS]);
+        varbasemul::witness(&mut w, 0, (xt, yt), &bits_msb, (x0, y0));
+        std::array::from_fn(|row| std::array::from_fn(|col| w[col][row]))
+    })?;
+
+    let (xt, yt) = (base[0].clone(), base[1].clone());
+    let mut acc = (acc0[0].clone(), acc0[1].clone());
+    let mut n = FieldVar::zero();
+    let mut rounds = vec![];
+    for rows in table.chunks(2) {
+        let [curr, next] = [&rows[0], &rows[1]];
+        let point =
+            |row: &[FieldVar<F>; COLUMNS], col: usize| (row[col].clone(), row[col + 1].clone());
+        let accs = vec![
+            acc,
+            point(curr, 7),
+            point(curr, 9),
+            point(curr, 11),
+            point(curr, 13),
+            point(next, 0),
+        ];
+        acc = accs[5].clone();
+        let n_next = curr[5].clone();
+        rounds.push(ScaleRound {
+            accs,
+            bits: next[2..7].to_vec(),
+            ss: next[7..12].to_vec(),
+            base: (xt.clone(), yt.clone()),
+            n_prev: n,
+            n_next: n_next.clone(),
+        });
+        n = n_next;
+    }
+
+    sys.add_constraint(
+        Constraint::KimchiConstraint(KimchiConstraint::EcScale(rounds)),
+        Some("ec_scale".into()),
+        loc!(),
+    )?;
+    scalar.assert_equals(sys, loc!(), &n)?;
+    Ok([acc.0, acc.1])
 }
+
+/// Returns `scalar * point`. The scalar must not be zero.
 fn ec_scale<F: PrimeField>(
-    _point: Point<FieldVar<F>>,
-    _scalar: &SmallChallenge<F>,
-) -> Point<FieldVar<F>> {
-    // TODO
-    todo!()
+    sys: &mut RunState<F>,
+    point: Point<FieldVar<F>>,
+    scalar: &SmallChallenge<F>,
+) -> SnarkyResult<Point<FieldVar<F>>> {
+    // scalar = 2 * half + low_bit
+    let (half, low_bit): (FieldVar<F>, Boolean<F>) = sys.compute(loc!(), |wit| {
+        let scalar = wit.read_var(&scalar.0).into_bigint();
+        let low_bit = scalar.is_odd();
+        let mut half = scalar;
+        half.div2();
+        (F::from_bigint(half).unwrap(), low_bit)
+    })?;
+    let composition = half.scale(F::from(2u64)) + low_bit.to_field_var();
+    scalar.0.assert_equals(sys, loc!(), &composition)?;
+
+    // the gates compute (2^n + 1 + 2 * half) * point, from which (2^n + 1) * point is removed
+    let acc0 = ec_add(sys, point.clone(), point.clone())?;
+    let shifted = var_base_mul(sys, &point, &acc0, &half)?;
+    let [x, y] = var_base_mul(sys, &point, &acc0, &FieldVar::zero())?;
+    let neg_shift = [x, -y];
+
+    // add the low bit: low_bit * point - (2^n + 1) * point
+    let odd_correction = ec_add(sys, point, neg_shift.clone())?;
+    let [x_odd, y_odd] = odd_correction;
+    let [x_even, y_even] = neg_shift;
+    let correction = [
+        sys.if_(loc!(), low_bit.clone(), x_odd, x_even)?,
+        sys.if_(loc!(), low_bit, y_odd, y_even)?,
+    ];
+    ec_add(sys, shifted, correction)
 }
 
 /// Trims to 127 bits
@@ -109,12 +363,16 @@ fn trim<F: PrimeField>(
         let val = wit.read_var(v);
         let mut high = val.into_bigint();
         high.divn(CHALLENGE_BITS as u32);
+        let mut shifted = high;
+        shifted.muln(CHALLENGE_BITS as u32);
         let mut low = val.into_bigint();
-        low.sub_with_borrow(&high);
+        low.sub_with_borrow(&shifted);
         (F::from_bigint(high).unwrap(), F::from_bigint(low).unwrap())
     })?;
     let composition = high.mul(base, None, loc!(), sys)? + &low;
-    // TODO: constraint low to 127 bits
+    // the decomposition is unique as low fits in 127 bits and high in 128 bits
+    split_limbs(sys, &low, CHALLENGE_BITS)?;
+    split_limbs(sys, &high, CHALLENGE_BITS + 1)?;
     v.assert_equals(sys, loc!(), &composition)?;
     Ok(low)
 }
@@ -145,43 +403,54 @@ impl<C: KimchiCurve, P: OpenProof<C>, const N: usize> SnarkyCircuit for FoldingC
     type Proof = P;
 
     type PrivateInput = Private<F<C>, N>;
-    type PublicInput = ();
+    type PublicInput = Public<FieldVar<F<C>>, N>;
     type PublicOutput = [Hash<F<C>>; 2];
 
     /// Implement the IVC circuit, see https://eprint.iacr.org/2021/370.pdf, Fig
-    /// 4, page 18
+    /// 4, page 18.
+    /// The step `i`, the inputs `z_0` and `z_i` and the hashes of the newest
+    /// instance are public. When `i` is 0, there is nothing to fold yet: the
+    /// hash of the newest instance is not checked and the initial accumulator
+    /// is output.
     fn circuit(
         &self,
         sys: &mut RunState<C::ScalarField>,
-        _public: Self::PublicInput,
+        public: Self::PublicInput,
         private: Option<&Self::PrivateInput>,
     ) -> SnarkyResult<Self::PublicOutput> {
+        let ((i, hashes), (z_0, z_i)) = public;
         let one = FieldVar::constant(F::<C>::one());
         //dividing by this should make a number of 127 bits or less zero
         let power = 1u128 << 127;
         let power = F::<C>::from(power);
         let power = FieldVar::Constant(power);
 
-        let u_i: Instance<FieldVar<F<C>>> = Instance::compute(private, sys, &self.commitments)?;
+        let u_i: Instance<FieldVar<F<C>>> =
+            Instance::compute(private, sys, &self.commitments, hashes)?;
         let hash1 = u_i.hash1.clone();
         let hash2 = u_i.hash2.clone();
         let u_acc: RelaxedInstance<FieldVar<F<C>>> =
             RelaxedInstance::compute(private, sys, &self.commitments, &self.challenges)?;
 
-        //check hash of inputs
-        let i: FieldVar<F<C>> = sys.compute(loc!(), |_| private.unwrap().i)?;
-        let z_0: [FieldVar<F<C>>; N] = sys.compute(loc!(), |_| private.unwrap().z_0.0)?;
+        let is_base = i.equal(sys, loc!(), &FieldVar::zero())?;
+
+        // the first step starts from z_0
+        for (z_0, z_i) in z_0.iter().zip(z_i.iter()) {
+            let expected = sys.if_(loc!(), is_base.clone(), z_0.clone(), z_i.clone())?;
+            z_i.assert_equals(sys, loc!(), &expected)?;
+        }
         let z_0 = Argument(z_0);
-        let z_i: [FieldVar<F<C>>; N] = sys.compute(loc!(), |_| private.unwrap().z_i.0)?;
         let z_i = Argument(z_i);
 
+        //check hash of inputs, except for the first step
         let inputs_hash = hash(sys, i.clone(), &z_0, &z_i, &u_acc, &power)?;
-
-        inputs_hash.assert_equals(sys, loc!(), &hash1)?;
+        let expected = sys.if_(loc!(), is_base.clone(), hash1.clone(), inputs_hash)?;
+        hash1.assert_equals(sys, loc!(), &expected)?;
 
         let t = sys.compute(loc!(), |_| private.unwrap().t)?;
-        let u_acc_new = u_acc.fold(sys, u_i, t, &power)?;
-        let z_next = apply(z_i);
+        let u_acc_folded = u_acc.clone().fold::<OtherField<C>>(sys, u_i, t, &power)?;
+        let u_acc_new = RelaxedInstance::if_(sys, &is_base, u_acc, u_acc_folded)?;
+        let z_next = apply(sys, self.step, z_i)?;
         let i = i.add(one);
 
         // here we should output this in some way so that it can be used as the input
@@ -192,26 +461,220 @@ impl<C: KimchiCurve, P: OpenProof<C>, const N: usize> SnarkyCircuit for FoldingC
     }
 }
 
-#[allow(dead_code)]
-fn example<const N: usize>(private: Private<Fp, N>) {
-    use mina_curves::pasta::{Vesta, VestaParameters};
+#[cfg(test)]
+mod tests {
+    use super::{
+        instance::{Challenges, WitnessCommitments},
+        *,
+    };
+    use crate::circuits::polynomials::foreign_field_common::{
+        BigUintForeignFieldHelpers, FieldArrayCompose,
+    };
+    use ark_ec::CurveGroup;
+    use ark_ff::UniformRand;
+    use mina_curves::pasta::{Fp, Fq, Pallas, Vesta, VestaParameters};
     use mina_poseidon::{
         constants::PlonkSpongeConstantsKimchi,
+        permutation::poseidon_block_cipher,
         sponge::{DefaultFqSponge, DefaultFrSponge},
     };
+    use o1_utils::{BigUintFieldHelpers, FieldHelpers};
+    use poly_commitment::ipa::OpeningProof;
+    use rand::Rng;
+
     type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
     type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
 
-    let circuit = FoldingCircuit {
-        _field: PhantomData,
-        _proof: PhantomData::<OpeningProof<Vesta>>,
-        commitments: vec![15],
-        challenges: vec![1, 5],
-    };
-    let (mut prover_index, verifier_index) = circuit.compile_to_indexes().unwrap();
-    let (proof, public_output) = prover_index
-        .prove::<BaseSponge, ScalarSponge>((), private, true)
-        .unwrap();
+    fn random_point(rng: &mut impl Rng) -> Point<Fp> {
+        let point = (Pallas::generator() * Fq::rand(rng)).into_affine();
+        [point.x, point.y]
+    }
+
+    fn random_challenge(rng: &mut impl Rng) -> FullChallenge<Fp> {
+        FullChallenge(ForeignElement(Fq::rand(rng).to_biguint().to_field_limbs()))
+    }
+
+    fn random_small(rng: &mut impl Rng) -> Fp {
+        Fp::from(rng.gen::<u128>() >> 1)
+    }
+
+    /// Checks the linear combinations of commitments and challenges
+    struct CombinationCircuit;
+
+    impl SnarkyCircuit for CombinationCircuit {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Vesta>;
+
+        type PrivateInput = ([Point<Fp>; 2], FullChallenge<Fp>, [Fp; 2]);
+        type PublicInput = ();
+        type PublicOutput = (Point<FieldVar<Fp>>, FullChallenge<FieldVar<Fp>>);
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            _public: Self::PublicInput,
+            private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            let [a, b]: [Point<FieldVar<Fp>>; 2] = sys.compute(loc!(), |_| private.unwrap().0)?;
+            let full = sys.compute(loc!(), |_| private.unwrap().1.clone())?;
+            let [small, r]: [FieldVar<Fp>; 2] = sys.compute(loc!(), |_| private.unwrap().2)?;
+            let r = SmallChallenge(r);
+
+            let point = commitment_linear_combination(sys, a, b, &r)?;
+            let challenge = challenge_linear_combination::<Fp, OtherField<Vesta>>(
+                sys,
+                full,
+                SmallChallenge(small),
+                &r,
+            )?;
+            Ok((point, challenge))
+        }
+    }
+
+    #[test]
+    fn snarky_folding_combinations() {
+        let (mut prover_index, verifier_index) = CombinationCircuit.compile_to_indexes().unwrap();
+
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        for r in [random_small(&mut rng), Fp::one(), Fp::from(2u64)] {
+            let (a, b) = (random_point(&mut rng), random_point(&mut rng));
+            let full = random_challenge(&mut rng);
+            let small = random_small(&mut rng);
+
+            let (proof, public_output) = prover_index
+                .prove::<BaseSponge, ScalarSponge>((), ([a, b], full.clone(), [small, r]), true)
+                .unwrap();
+            let (point, challenge) = *public_output;
+
+            let to_scalar = |x: Fp| Fq::from_biguint(&x.to_biguint()).unwrap();
+            let r_scalar = to_scalar(r);
+            let [a, b] = [a, b].map(|[x, y]| Pallas::new_unchecked(x, y));
+            let expected = (a + b * r_scalar).into_affine();
+            assert_eq!(point, [expected.x, expected.y]);
 
-    verifier_index.verify::<BaseSponge, ScalarSponge>(proof, (), *public_output);
+            let full = Fq::from_biguint(&full.0 .0.compose()).unwrap();
+            let expected = full + to_scalar(small) * r_scalar;
+            assert_eq!(challenge.0 .0.compose(), expected.to_biguint());
+
+            verifier_index.verify::<BaseSponge, ScalarSponge>(proof, (), (point, challenge));
+        }
+    }
+
+    /// The out-of-circuit version of [hash]
+    fn hash_out_of_circuit(elements: &[Fp]) -> Fp {
+        let params = Vesta::sponge_params();
+        let mut state = [Fp::zero(); 2];
+        for chunk in elements.chunks(2) {
+            let mut full_state = vec![
+                state[0] + chunk[0],
+                state[1] + chunk.get(1).copied().unwrap_or_default(),
+                Fp::zero(),
+            ];
+            poseidon_block_cipher::<Fp, PlonkSpongeConstantsKimchi>(params, &mut full_state);
+            state = [full_state[0], full_state[1]];
+        }
+        let hash = state[0].to_biguint() % (BigUint::from(1u8) << CHALLENGE_BITS);
+        hash.to_field().unwrap()
+    }
+
+    fn increment(
+        _sys: &mut RunState<Fp>,
+        z: Argument<FieldVar<Fp>, 1>,
+    ) -> SnarkyResult<Argument<FieldVar<Fp>, 1>> {
+        let one = FieldVar::constant(Fp::one());
+        let [z] = z.0;
+        Ok(Argument([z + one]))
+    }
+
+    fn random_accumulator(rng: &mut impl Rng) -> RelaxedInstance<Fp> {
+        RelaxedInstance {
+            hash1: random_challenge(rng),
+            hash2: random_challenge(rng),
+            witness_commitments: vec![WitnessCommitments(vec![
+                random_point(rng),
+                random_point(rng),
+            ])],
+            u: random_challenge(rng),
+            error_commitment: random_point(rng),
+            challenges: vec![
+                Challenges(vec![random_challenge(rng)]),
+                Challenges(vec![random_challenge(rng), random_challenge(rng)]),
+            ],
+        }
+    }
+
+    /// The out-of-circuit version of the hash of the input of a step
+    fn hash_of_step(i: Fp, z_0: Fp, z_i: Fp, u_acc: &RelaxedInstance<Fp>) -> Fp {
+        let mut elements = vec![i, z_0, z_i];
+        for point in &u_acc.witness_commitments[0].0 {
+            elements.extend(point);
+        }
+        for challenge in [&u_acc.hash1, &u_acc.hash2, &u_acc.u] {
+            elements.extend(challenge.0 .0);
+        }
+        elements.extend(u_acc.error_commitment);
+        for set in &u_acc.challenges {
+            for challenge in &set.0 {
+                elements.extend(challenge.0 .0);
+            }
+        }
+        hash_out_of_circuit(&elements)
+    }
+
+    /// Proves and verifies a step of the [increment] chain, returning the
+    /// public output
+    fn prove_step(
+        rng: &mut impl Rng,
+        public: Public<Fp, 1>,
+        u_acc: RelaxedInstance<Fp>,
+    ) -> [Fp; 2] {
+        let circuit =
+            FoldingCircuit::<Vesta, OpeningProof<Vesta>, 1>::new(vec![2], vec![1, 2], increment);
+        let (mut prover_index, verifier_index) = circuit.compile_to_indexes().unwrap();
+
+        let private = Private {
+            u_acc,
+            u_i_commitments: vec![WitnessCommitments(vec![
+                random_point(rng),
+                random_point(rng),
+            ])],
+            t: [random_point(rng), random_point(rng)],
+        };
+        let (proof, public_output) = prover_index
+            .prove::<BaseSponge, ScalarSponge>(public.clone(), private, true)
+            .unwrap();
+
+        verifier_index.verify::<BaseSponge, ScalarSponge>(proof, public, *public_output);
+        *public_output
+    }
+
+    #[test]
+    fn snarky_folding_step() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let u_acc = random_accumulator(&mut rng);
+        let (i, z_0, z_i) = (Fp::from(3u64), Fp::from(0u64), Fp::from(3u64));
+
+        // the instance of the previous step commits to the hash of its input
+        let hash1 = hash_of_step(i, z_0, z_i, &u_acc);
+        let hash2 = random_small(&mut rng);
+
+        let public_output = prove_step(&mut rng, ((i, [hash1, hash2]), ([z_0], [z_i])), u_acc);
+        assert_eq!(public_output[0], hash2);
+    }
+
+    #[test]
+    fn snarky_folding_base_case() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let u_acc = random_accumulator(&mut rng);
+        let z_0 = Fp::from(5u64);
+
+        // there is no previous step, the hash of the instance is not checked
+        let [hash1, hash2] = [random_small(&mut rng), random_small(&mut rng)];
+
+        // the initial accumulator is output unchanged
+        let expected = hash_of_step(Fp::one(), z_0, z_0 + Fp::one(), &u_acc);
+        let public = ((Fp::zero(), [hash1, hash2]), ([z_0], [z_0]));
+        let public_output = prove_step(&mut rng, public, u_acc);
+        assert_eq!(public_output, [hash2, expected]);
+    }
 }
@@ -1,7 +1,9 @@
 use crate::{
     loc,
     snarky::{
+        boolean::Boolean,
         folding::{ForeignElement, FullChallenge, Point, Private},
+        foreign_field::ForeignFieldModulus,
         poseidon::DuplexState,
         snarky_type::SnarkyType,
     },
@@ -16,7 +18,7 @@ use super::{
 };
 
 #[derive(Debug, Clone)]
-pub struct WitnessCommitments<F>(Vec<Point<F>>);
+pub struct WitnessCommitments<F>(pub Vec<Point<F>>);
 
 #[derive(Debug, Clone)]
 pub struct Instance<F> {
@@ -36,24 +38,23 @@ impl<F: PrimeField> Instance<FieldVar<F>> {
     }
 }
 impl<F> Instance<F> {
+    /// Builds the instance from its hashes, which are public inputs of the
+    /// circuit, and its witness commitments, which are private inputs
     pub fn compute<const N: usize>(
         private_input: Option<&Private<F, N>>,
         sys: &mut RunState<F>,
         commitment_sets: &[usize],
+        [hash1, hash2]: [FieldVar<F>; 2],
     ) -> SnarkyResult<Instance<FieldVar<F>>>
     where
         F: PrimeField,
     {
-        let (hash1, hash2) = sys.compute(loc!(), |_| {
-            let ins = private_input.unwrap().u_i.clone();
-            (ins.hash1, ins.hash2)
-        })?;
         let mut witness_commitments = Vec::with_capacity(commitment_sets.len());
         for (i, set_size) in commitment_sets.iter().enumerate() {
             let mut set = Vec::with_capacity(*set_size);
             for j in 0..*set_size {
                 let commitment = sys.compute(loc!(), |_| {
-                    let a: Point<F> = private_input.unwrap().u_i.witness_commitments[i].0[j];
+                    let a: Point<F> = private_input.unwrap().u_i_commitments[i].0[j];
                     a
                 })?;
                 set.push(commitment);
@@ -68,16 +69,16 @@ impl<F> Instance<F> {
     }
 }
 #[derive(Debug, Clone)]
-struct Challenges<F>(Vec<FullChallenge<F>>);
+pub struct Challenges<F>(pub Vec<FullChallenge<F>>);
 
 #[derive(Debug, Clone)]
 pub struct RelaxedInstance<F> {
     pub hash1: FullChallenge<F>,
     pub hash2: FullChallenge<F>,
     pub witness_commitments: Vec<WitnessCommitments<F>>,
-    u: FullChallenge<F>,
-    error_commitment: Point<F>,
-    challenges: Vec<Challenges<F>>,
+    pub u: FullChallenge<F>,
+    pub error_commitment: Point<F>,
+    pub challenges: Vec<Challenges<F>>,
 }
 
 impl<F: PrimeField> RelaxedInstance<FieldVar<F>> {
@@ -101,40 +102,46 @@ impl<F: PrimeField> RelaxedInstance<FieldVar<F>> {
 
     /// See https://eprint.iacr.org/2021/370.pdf, page 15
     /// Fold the circuit described by `sys` with the other circuit `other`.
-    pub fn fold(
+    /// `M` is the modulus of the scalar field of the committed points.
+    pub fn fold<M: ForeignFieldModulus>(
         self,
         sys: &mut RunState<F>,
         other: Instance<FieldVar<F>>,
         error_terms: [Point<FieldVar<F>>; 2],
         base: &FieldVar<F>,
     ) -> SnarkyResult<Self> {
-        // let mut state = DuplexState::new();
-        let mut challenge_generator = ChallengeGenerator::new(sys, &self, &other, None);
+        // the cross terms are absorbed as well, so that r is sampled after the
+        // prover committed to them
+        let mut challenge_generator =
+            ChallengeGenerator::new(sys, &self, &other, &error_terms, None);
         let r = challenge_generator.squeeze_challenge(sys, base)?;
-        let hash1 = challenge_linear_combination(self.hash1, SmallChallenge(other.hash1), &r);
-        let hash2 = challenge_linear_combination(self.hash2, SmallChallenge(other.hash2), &r);
+        let hash1 =
+            challenge_linear_combination::<F, M>(sys, self.hash1, SmallChallenge(other.hash1), &r)?;
+        let hash2 =
+            challenge_linear_combination::<F, M>(sys, self.hash2, SmallChallenge(other.hash2), &r)?;
         // Combining the witnesses commitments, see W <- W1 + r W2
-        let witness_commitments = self
+        let mut witness_commitments = Vec::with_capacity(self.witness_commitments.len());
+        for (a, b) in self
             .witness_commitments
             .into_iter()
             .zip(other.witness_commitments)
-            .map(|(a, b)| {
-                let set =
-                    a.0.into_iter()
-                        .zip(b.0)
-                        .map(|(a, b)| commitment_linear_combination(a, b, &r));
-                WitnessCommitments(set.collect())
-            })
-            .collect();
+        {
+            let mut set = Vec::with_capacity(a.0.len());
+            for (a, b) in a.0.into_iter().zip(b.0) {
+                set.push(commitment_linear_combination(sys, a, b, &r)?);
+            }
+            witness_commitments.push(WitnessCommitments(set));
+        }
         let one = FieldVar::constant(F::one());
-        let u = challenge_linear_combination(self.u, SmallChallenge(one.clone()), &r);
+        let u = challenge_linear_combination::<F, M>(sys, self.u, SmallChallenge(one.clone()), &r)?;
 
-        let rr = r.0.mul(&r.0, None, loc!(), sys)?;
+        // E <- E + r T1 + r^2 T2
         let [t1, t2] = error_terms;
-        let t1 = ec_scale(t1, &r);
-        let t2 = ec_scale(t2, &SmallChallenge(rr));
-        let error_commitment = ec_add(t1, t2);
-        let error_commitment = ec_add(self.error_commitment, error_commitment);
+        let t1 = ec_scale(sys, t1, &r)?;
+        let t2 = ec_scale(sys, t2, &r)?;
+        let t2 = ec_scale(sys, t2, &r)?;
+        let error_commitment = ec_add(sys, t1, t2)?;
+        let error_commitment = ec_add(sys, self.error_commitment, error_commitment)?;
 
         let mut new_sets = Vec::with_capacity(self.challenges.len());
         for _ in 0..self.challenges.len() {
@@ -159,19 +166,19 @@ impl<F: PrimeField> RelaxedInstance<FieldVar<F>> {
                 std::mem::swap(challenge, &mut trimed);
             }
         }
-        let challenges = self
-            .challenges
-            .into_iter()
-            .zip(new_sets)
-            .map(|(a, b)| {
-                let set =
-                    a.0.into_iter()
-                        .zip(b)
-                        .map(|(a, b)| challenge_linear_combination(a, SmallChallenge(b), &r))
-                        .collect();
-                Challenges(set)
-            })
-            .collect();
+        let mut challenges = Vec::with_capacity(self.challenges.len());
+        for (a, b) in self.challenges.into_iter().zip(new_sets) {
+            let mut set = Vec::with_capacity(a.0.len());
+            for (a, b) in a.0.into_iter().zip(b) {
+                set.push(challenge_linear_combination::<F, M>(
+                    sys,
+                    a,
+                    SmallChallenge(b),
+                    &r,
+                )?);
+            }
+            challenges.push(Challenges(set));
+        }
 
         Ok(RelaxedInstance {
             hash1,
@@ -184,6 +191,69 @@ impl<F: PrimeField> RelaxedInstance<FieldVar<F>> {
     }
 }
 
+impl<F: PrimeField> RelaxedInstance<FieldVar<F>> {
+    /// Returns `then_` if `b` is true, `else_` otherwise
+    pub fn if_(
+        sys: &mut RunState<F>,
+        b: &Boolean<F>,
+        then_: Self,
+        else_: Self,
+    ) -> SnarkyResult<Self> {
+        let mut witness_commitments = Vec::with_capacity(then_.witness_commitments.len());
+        for (t, e) in then_
+            .witness_commitments
+            .into_iter()
+            .zip(else_.witness_commitments)
+        {
+            let mut set = Vec::with_capacity(t.0.len());
+            for (t, e) in t.0.into_iter().zip(e.0) {
+                set.push(select(sys, b, t, e)?);
+            }
+            witness_commitments.push(WitnessCommitments(set));
+        }
+        let mut challenges = Vec::with_capacity(then_.challenges.len());
+        for (t, e) in then_.challenges.into_iter().zip(else_.challenges) {
+            let mut set = Vec::with_capacity(t.0.len());
+            for (t, e) in t.0.into_iter().zip(e.0) {
+                set.push(select_challenge(sys, b, t, e)?);
+            }
+            challenges.push(Challenges(set));
+        }
+        Ok(RelaxedInstance {
+            hash1: select_challenge(sys, b, then_.hash1, else_.hash1)?,
+            hash2: select_challenge(sys, b, then_.hash2, else_.hash2)?,
+            witness_commitments,
+            u: select_challenge(sys, b, then_.u, else_.u)?,
+            error_commitment: select(sys, b, then_.error_commitment, else_.error_commitment)?,
+            challenges,
+        })
+    }
+}
+
+/// Returns `then_` if `b` is true, `else_` otherwise, element-wise
+fn select<F: PrimeField, const K: usize>(
+    sys: &mut RunState<F>,
+    b: &Boolean<F>,
+    then_: [FieldVar<F>; K],
+    else_: [FieldVar<F>; K],
+) -> SnarkyResult<[FieldVar<F>; K]> {
+    let mut res = Vec::with_capacity(K);
+    for (t, e) in then_.into_iter().zip(else_) {
+        res.push(sys.if_(loc!(), b.clone(), t, e)?);
+    }
+    Ok(res.try_into().unwrap())
+}
+
+fn select_challenge<F: PrimeField>(
+    sys: &mut RunState<F>,
+    b: &Boolean<F>,
+    then_: FullChallenge<FieldVar<F>>,
+    else_: FullChallenge<FieldVar<F>>,
+) -> SnarkyResult<FullChallenge<FieldVar<F>>> {
+    let limbs = select(sys, b, then_.0 .0, else_.0 .0)?;
+    Ok(FullChallenge(ForeignElement(limbs)))
+}
+
 impl<F> RelaxedInstance<F> {
     pub fn compute<const N: usize>(
         private_input: Option<&Private<F, N>>,
@@ -200,7 +270,7 @@ impl<F> RelaxedInstance<F> {
             let mut set = Vec::with_capacity(*set_size);
             for j in 0..*set_size {
                 let commitment = sys.compute(loc!(), |_| {
-                    let a: Point<F> = private_input.unwrap().u_i.witness_commitments[i].0[j];
+                    let a: Point<F> = private_input.unwrap().u_acc.witness_commitments[i].0[j];
                     a
                 })?;
                 set.push(commitment);
@@ -243,14 +313,14 @@ impl<F: PrimeField> SnarkyType<F> for FullChallenge<FieldVar<F>> {
 
     type OutOfCircuit = FullChallenge<F>;
 
-    const SIZE_IN_FIELD_ELEMENTS: usize = 2;
+    const SIZE_IN_FIELD_ELEMENTS: usize = 3;
 
     fn to_cvars(&self) -> (Vec<FieldVar<F>>, Self::Auxiliary) {
         (self.0 .0.to_vec(), ())
     }
 
     fn from_cvars_unsafe(cvars: Vec<FieldVar<F>>, _aux: Self::Auxiliary) -> Self {
-        let chall: [FieldVar<F>; 2] = cvars.try_into().unwrap();
+        let chall: [FieldVar<F>; 3] = cvars.try_into().unwrap();
         FullChallenge(ForeignElement(chall))
     }
 
@@ -259,7 +329,8 @@ impl<F: PrimeField> SnarkyType<F> for FullChallenge<FieldVar<F>> {
         _cs: &mut RunState<F>,
         _loc: std::borrow::Cow<'static, str>,
     ) -> SnarkyResult<()> {
-        //TODO: maybe check the size of each limb
+        // the limbs are checked when the challenge is folded,
+        // as it requires the modulus of the foreign field
         Ok(())
     }
 
@@ -270,7 +341,7 @@ impl<F: PrimeField> SnarkyType<F> for FullChallenge<FieldVar<F>> {
     }
 
     fn value_of_field_elements(fields: Vec<F>, _aux: Self::Auxiliary) -> Self::OutOfCircuit {
-        let chall: [F; 2] = fields.try_into().unwrap();
+        let chall: [F; 3] = fields.try_into().unwrap();
         FullChallenge(ForeignElement(chall))
     }
 }
@@ -284,11 +355,15 @@ impl<F: PrimeField> ChallengeGenerator<F> {
         sys: &mut RunState<F>,
         relaxed: &RelaxedInstance<FieldVar<F>>,
         other: &Instance<FieldVar<F>>,
+        error_terms: &[Point<FieldVar<F>>; 2],
         initial_state: Option<DuplexState<F>>,
     ) -> Self {
         let mut state = initial_state.unwrap_or_default();
         relaxed.absorb_into_sponge(&mut state, sys);
         other.absorb_into_sponge(&mut state, sys);
+        for error_term in error_terms {
+            state.absorb(sys, loc!(), error_term);
+        }
         ChallengeGenerator { state }
     }
     fn squeeze_challenge(
@@ -0,0 +1,574 @@
+//! The [ForeignElement] type is a snarky type representing an element of a foreign field,
+//! as three limbs of 88 bits.
+//! Its variables are constrained to represent the canonical form of the element
+//! (that is, to be less than the foreign field modulus),
+//! and its addition, subtraction and multiplication compile to the
+//! `ForeignFieldAdd` and `ForeignFieldMul` gates.
+
+use super::{
+    constraint_system::{ForeignFieldAddInput, ForeignFieldMulInput, KimchiConstraint},
+    errors::SnarkyResult,
+    range_checks::range_check,
+    runner::{Constraint, RunState, WitnessGeneration},
+    snarky_type::SnarkyType,
+};
+use crate::{
+    circuits::{
+        expr::constraints::ExprOps,
+        polynomials::{
+            foreign_field_add::witness::FFOps,
+            foreign_field_common::{
+                BigUintForeignFieldHelpers, FieldArrayBigUintHelpers, FieldArrayCompose, LIMB_BITS,
+                LIMB_COUNT,
+            },
+            foreign_field_mul::{circuitgates, witness},
+        },
+    },
+    FieldVar,
+};
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use num_integer::Integer;
+use o1_utils::{BigUintFieldHelpers, FieldHelpers};
+use std::{borrow::Cow, fmt::Debug, marker::PhantomData};
+
+/// The modulus of a foreign field.
+pub trait ForeignFieldModulus: Debug + Clone {
+    fn modulus() -> BigUint;
+}
+
+/// An element of the foreign field of modulus `M`, with limbs in the native field `F`.
+#[derive(Debug, Clone)]
+pub struct ForeignElement<F: PrimeField, M: ForeignFieldModulus> {
+    limbs: [FieldVar<F>; LIMB_COUNT],
+    modulus: PhantomData<M>,
+}
+
+/// Computes the carry of the foreign field addition
+/// `left + sign * right - overflow * modulus = result`
+fn ffadd_carry<F: PrimeField>(
+    left: &[F; 3],
+    right: &[F; 3],
+    sign: F,
+    overflow: F,
+    modulus: &[F; 3],
+    result: &[F; 3],
+) -> F {
+    let compact = |limbs: &[F; 3]| limbs[0] + limbs[1] * F::two_pow(LIMB_BITS as u64);
+    (compact(left) + sign * compact(right) - overflow * compact(modulus) - compact(result))
+        / F::two_pow(2 * LIMB_BITS as u64)
+}
+
+impl<F, M> SnarkyType<F> for ForeignElement<F, M>
+where
+    F: PrimeField,
+    M: ForeignFieldModulus,
+{
+    type Auxiliary = ();
+
+    type OutOfCircuit = BigUint;
+
+    const SIZE_IN_FIELD_ELEMENTS: usize = LIMB_COUNT;
+
+    fn to_cvars(&self) -> (Vec<FieldVar<F>>, Self::Auxiliary) {
+        (self.limbs.to_vec(), ())
+    }
+
+    fn from_cvars_unsafe(cvars: Vec<FieldVar<F>>, _aux: Self::Auxiliary) -> Self {
+        assert_eq!(cvars.len(), Self::SIZE_IN_FIELD_ELEMENTS);
+        Self::from_limbs_unsafe(cvars.try_into().unwrap())
+    }
+
+    /// Range checks the limbs, and checks that the element is less than the modulus
+    /// by range checking the limbs of `x + 2^264 - modulus`,
+    /// computed with a `ForeignFieldAdd` gate.
+    fn check(&self, cs: &mut RunState<F>, loc: Cow<'static, str>) -> SnarkyResult<()> {
+        let [x0, x1, x2] = self.limbs.clone();
+        range_check(cs, loc.clone(), x0, x1, x2)?;
+
+        let modulus = Self::modulus_limbs();
+        let right = [F::zero(), F::zero(), F::two_pow(LIMB_BITS as u64)];
+        let (bound, carry): ([FieldVar<F>; 3], FieldVar<F>) = cs.compute(loc.clone(), |w| {
+            // the bound only fits in 264 bits if the element is less than the
+            // modulus, otherwise the truncated one does not satisfy the gate
+            let x = self.read_limbs(w);
+            let bound: [F; 3] = ((x.compose() + BigUint::binary_modulus() - M::modulus())
+                % BigUint::binary_modulus())
+            .to_field_limbs();
+            let carry = ffadd_carry(&x, &right, F::one(), F::one(), &modulus, &bound);
+            (bound, carry)
+        })?;
+
+        let constraint = KimchiConstraint::ForeignFieldAdd(ForeignFieldAddInput {
+            left: self.limbs.to_vec(),
+            right: right.map(FieldVar::constant).to_vec(),
+            overflow: FieldVar::constant(F::one()),
+            carry,
+            result: bound.to_vec(),
+            foreign_field_modulus: modulus.to_vec(),
+            sign: F::one(),
+        });
+        cs.add_constraint(
+            Constraint::KimchiConstraint(constraint),
+            Some("foreign field bound check".into()),
+            loc.clone(),
+        )?;
+
+        let [b0, b1, b2] = bound;
+        range_check(cs, loc, b0, b1, b2)
+    }
+
+    fn constraint_system_auxiliary() -> Self::Auxiliary {}
+
+    fn value_to_field_elements(value: &Self::OutOfCircuit) -> (Vec<F>, Self::Auxiliary) {
+        (value.to_field_limbs::<F>().to_vec(), ())
+    }
+
+    fn value_of_field_elements(fields: Vec<F>, _aux: Self::Auxiliary) -> Self::OutOfCircuit {
+        let limbs: [F; 3] = fields.try_into().unwrap();
+        limbs.compose()
+    }
+}
+
+impl<F, M> ForeignElement<F, M>
+where
+    F: PrimeField,
+    M: ForeignFieldModulus,
+{
+    /// Creates an element from its limbs, without constraining them.
+    pub fn from_limbs_unsafe(limbs: [FieldVar<F>; LIMB_COUNT]) -> Self {
+        Self {
+            limbs,
+            modulus: PhantomData,
+        }
+    }
+
+    pub fn constant(value: &BigUint) -> Self {
+        assert!(
+            *value < M::modulus(),
+            "value must be less than the foreign field modulus"
+        );
+        Self::from_limbs_unsafe(value.to_field_limbs::<F>().map(FieldVar::constant))
+    }
+
+    pub fn limbs(&self) -> &[FieldVar<F>; LIMB_COUNT] {
+        &self.limbs
+    }
+
+    fn modulus_limbs() -> [F; 3] {
+        let modulus = M::modulus();
+        assert!(
+            modulus <= BigUint::max_foreign_field_modulus::<F>(),
+            "foreign_field_modulus exceeds maximum: {} > {}",
+            modulus,
+            BigUint::max_foreign_field_modulus::<F>()
+        );
+        modulus.to_field_limbs()
+    }
+
+    fn read_limbs(&self, w: &dyn WitnessGeneration<F>) -> [F; 3] {
+        self.limbs.clone().map(|limb| w.read_var(&limb))
+    }
+
+    fn add_or_sub(
+        &self,
+        other: &Self,
+        op: FFOps,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<Self> {
+        let modulus = Self::modulus_limbs();
+        let sign = op.sign::<F>();
+
+        // left + sign * right = overflow * modulus + result
+        let values = |w: &dyn WitnessGeneration<F>| {
+            let left = self.read_limbs(w);
+            let right = other.read_limbs(w);
+            let (left_big, right_big) = (left.compose(), right.compose());
+            let (result, has_overflow) = match op {
+                FFOps::Add => {
+                    let sum = left_big + right_big;
+                    if sum >= M::modulus() {
+                        (sum - M::modulus(), true)
+                    } else {
+                        (sum, false)
+                    }
+                }
+                FFOps::Sub => {
+                    if left_big < right_big {
+                        (left_big + M::modulus() - right_big, true)
+                    } else {
+                        (left_big - right_big, false)
+                    }
+                }
+            };
+            let overflow = if has_overflow { sign } else { F::zero() };
+            let carry = ffadd_carry(
+                &left,
+                &right,
+                sign,
+                overflow,
+                &modulus,
+                &result.to_field_limbs(),
+            );
+            (result, overflow, carry)
+        };
+
+        let result: Self = cs.compute(loc.clone(), |w| values(w).0)?;
+        let (overflow, carry): (FieldVar<F>, FieldVar<F>) = cs.compute(loc.clone(), |w| {
+            let (_, overflow, carry) = values(w);
+            (overflow, carry)
+        })?;
+
+        let constraint = KimchiConstraint::ForeignFieldAdd(ForeignFieldAddInput {
+            left: self.limbs.to_vec(),
+            right: other.limbs.to_vec(),
+            overflow,
+            carry,
+            result: result.limbs.to_vec(),
+            foreign_field_modulus: modulus.to_vec(),
+            sign,
+        });
+        cs.add_constraint(
+            Constraint::KimchiConstraint(constraint),
+            Some("foreign field addition".into()),
+            loc,
+        )?;
+
+        Ok(result)
+    }
+
+    /// Returns `self + other` in the foreign field.
+    pub fn add(
+        &self,
+        other: &Self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<Self> {
+        self.add_or_sub(other, FFOps::Add, cs, loc)
+    }
+
+    /// Returns `self - other` in the foreign field.
+    pub fn sub(
+        &self,
+        other: &Self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<Self> {
+        self.add_or_sub(other, FFOps::Sub, cs, loc)
+    }
+
+    /// Returns `self * other` in the foreign field.
+    pub fn mul(
+        &self,
+        other: &Self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<Self> {
+        let modulus = M::modulus();
+        let hi_modulus = Self::modulus_limbs()[2];
+        let neg_modulus = modulus.negate();
+
+        // left * right = quotient * modulus + remainder
+        let remainder: Self = cs.compute(loc.clone(), |w| {
+            let product = self.read_limbs(w).compose() * other.read_limbs(w).compose();
+            product % &modulus
+        })?;
+
+        // the quotient, its high bound, the decomposition of the middle
+        // intermediate product, and the carries
+        let witness: ([FieldVar<F>; 8], [FieldVar<F>; 11]) = cs.compute(loc.clone(), |w| {
+            let left = self.read_limbs(w);
+            let right = other.read_limbs(w);
+            let (quotient, remainder) = (left.compose() * right.compose()).div_rem(&modulus);
+
+            let products: [F; 3] = circuitgates::compute_intermediate_products(
+                &left,
+                &right,
+                &quotient.to_field_limbs(),
+                &neg_modulus.to_field_limbs(),
+            );
+            let [product1_lo, product1_hi_0, product1_hi_1, carry0, carry1]: [F; 5] =
+                witness::compute_witness_variables(&products.to_limbs(), &remainder.to_limbs());
+            let quotient_hi_bound: F = witness::compute_high_bound(&quotient, &modulus)
+                .to_field()
+                .unwrap();
+
+            // carry1 is split into 7 limbs of 12 bits, 3 crumbs and 1 bit
+            let carry1 = carry1.to_biguint();
+            let carry1_bits = |start: usize, end: usize| -> F {
+                let mask = (BigUint::from(1u8) << (end - start)) - 1u8;
+                ((&carry1 >> start) & mask).to_field().unwrap()
+            };
+            let carry1_parts = [
+                carry1_bits(0, 12),
+                carry1_bits(12, 24),
+                carry1_bits(24, 36),
+                carry1_bits(36, 48),
+                carry1_bits(48, 60),
+                carry1_bits(60, 72),
+                carry1_bits(72, 84),
+                carry1_bits(84, 86),
+                carry1_bits(86, 88),
+                carry1_bits(88, 90),
+                carry1_bits(90, 91),
+            ];
+
+            let [q0, q1, q2] = quotient.to_field_limbs();
+            let values = [
+                q0,
+                q1,
+                q2,
+                quotient_hi_bound,
+                product1_lo,
+                product1_hi_0,
+                product1_hi_1,
+                carry0,
+            ];
+            (values, carry1_parts)
+        })?;
+        let (values, carry1) = witness;
+        let [q0, q1, q2, quotient_hi_bound, product1_lo, product1_hi_0, product1_hi_1, carry0] =
+            values;
+
+        // 0   1   2   3   4   5   6     7      8       9       10      11      12      13      14
+        // a0  a1  a2  b0  b1  b2  p10   c1_0   c1_12   c1_24   c1_36   c1_84   c1_86   c1_88   c1_90
+        // r01 r2  q0  q1  q2  q'2 p110  p111   c1_48   c1_60   c1_72   c0
+        let [r0, r1, r2] = remainder.limbs.clone();
+        let remainder01 = r0 + r1.scale(F::two_pow(LIMB_BITS as u64));
+        let curr_row = self
+            .limbs
+            .iter()
+            .chain(other.limbs.iter())
+            .cloned()
+            .chain([product1_lo.clone()])
+            .chain(carry1[0..4].iter().cloned())
+            .chain(carry1[7..11].iter().cloned())
+            .collect();
+        let next_row = [
+            remainder01,
+            r2,
+            q0.clone(),
+            q1.clone(),
+            q2.clone(),
+            quotient_hi_bound.clone(),
+            product1_hi_0.clone(),
+            product1_hi_1,
+        ]
+        .into_iter()
+        .chain(carry1[4..7].iter().cloned())
+        .chain([carry0])
+        .collect();
+
+        let neg_modulus: [F; 3] = neg_modulus.to_field_limbs();
+        let constraint = KimchiConstraint::ForeignFieldMul(ForeignFieldMulInput {
+            rows: vec![curr_row, next_row],
+            hi_foreign_field_modulus: hi_modulus,
+            neg_foreign_field_modulus: neg_modulus.to_vec(),
+        });
+        cs.add_constraint(
+            Constraint::KimchiConstraint(constraint),
+            Some("foreign field multiplication".into()),
+            loc.clone(),
+        )?;
+
+        // the limbs of the quotient, of its high bound and of the middle
+        // intermediate product are 88 bits each
+        range_check(cs, loc.clone(), q0, q1, q2)?;
+        range_check(cs, loc, quotient_hi_bound, product1_lo, product1_hi_0)?;
+
+        Ok(remainder)
+    }
+
+    /// Constrains `self` and `other` to represent the same element.
+    pub fn assert_equals(
+        &self,
+        other: &Self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<()> {
+        for (x, y) in self.limbs.iter().zip(other.limbs.iter()) {
+            cs.assert_eq(
+                Some("foreign field equality".into()),
+                loc.clone(),
+                x.clone(),
+                y.clone(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{circuits::gate::GateType, loc, snarky::api::SnarkyCircuit};
+    use ark_ff::One;
+    use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+    use mina_poseidon::{
+        constants::PlonkSpongeConstantsKimchi,
+        sponge::{DefaultFqSponge, DefaultFrSponge},
+    };
+    use num_bigint::RandBigInt;
+    use poly_commitment::ipa::OpeningProof;
+
+    type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+    #[derive(Debug, Clone)]
+    struct Secp256k1Base;
+
+    impl ForeignFieldModulus for Secp256k1Base {
+        fn modulus() -> BigUint {
+            BigUint::from_bytes_be(&secp256k1::constants::FIELD_SIZE)
+        }
+    }
+
+    type Element = ForeignElement<Fp, Secp256k1Base>;
+
+    struct TestCircuit;
+
+    impl SnarkyCircuit for TestCircuit {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = (BigUint, BigUint);
+        type PublicInput = ();
+        type PublicOutput = [Element; 3];
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            _public: Self::PublicInput,
+            private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            let x: Element = sys.compute(loc!(), |_| private.unwrap().0.clone())?;
+            let y: Element = sys.compute(loc!(), |_| private.unwrap().1.clone())?;
+
+            let sum = x.add(&y, sys, loc!())?;
+            let diff = x.sub(&y, sys, loc!())?;
+            let product = x.mul(&y, sys, loc!())?;
+
+            // (x + y) - y = x
+            sum.sub(&y, sys, loc!())?.assert_equals(&x, sys, loc!())?;
+
+            Ok([sum, diff, product])
+        }
+    }
+
+    #[test]
+    fn snarky_foreign_field() {
+        let (mut prover_index, verifier_index) = TestCircuit.compile_to_indexes().unwrap();
+
+        let modulus = Secp256k1Base::modulus();
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let max = &modulus - 1u8;
+        for (x, y) in [
+            (
+                rng.gen_biguint_below(&modulus),
+                rng.gen_biguint_below(&modulus),
+            ),
+            (max.clone(), max),
+            (BigUint::from(0u8), BigUint::from(1u8)),
+        ] {
+            let debug = true;
+            let (proof, public_output) = prover_index
+                .prove::<BaseSponge, ScalarSponge>((), (x.clone(), y.clone()), debug)
+                .unwrap();
+
+            let expected = [
+                (&x + &y) % &modulus,
+                (&x + &modulus - &y) % &modulus,
+                (&x * &y) % &modulus,
+            ];
+            assert_eq!(*public_output, expected);
+
+            verifier_index.verify::<BaseSponge, ScalarSponge>(proof, (), *public_output);
+        }
+    }
+
+    /// Checks an element given by its limbs, that are not constrained beforehand
+    struct CheckCircuit;
+
+    impl SnarkyCircuit for CheckCircuit {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = [Fp; 3];
+        type PublicInput = ();
+        type PublicOutput = ();
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            _public: Self::PublicInput,
+            private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            let limbs: [FieldVar<Fp>; 3] = sys.compute(loc!(), |_| *private.unwrap())?;
+            let x = Element::from_limbs_unsafe(limbs);
+            x.check(sys, loc!())
+        }
+    }
+
+    #[test]
+    fn snarky_foreign_field_rejects_unreduced_elements() {
+        let (mut prover_index, _) = CheckCircuit.compile_to_indexes().unwrap();
+
+        let modulus = Secp256k1Base::modulus();
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let x = rng.gen_biguint_below(&modulus);
+        let limbs: [Fp; 3] = x.to_field_limbs();
+        let report = prover_index.check_witness((), limbs).unwrap();
+        assert!(report.is_ok(), "{report}");
+
+        // the same value, with a first limb of more than 88 bits
+        let [x0, x1, x2] = limbs;
+        let unreduced = [x0 + Fp::two_pow(LIMB_BITS as u64), x1 - Fp::one(), x2];
+        assert_eq!(unreduced.compose(), x);
+        let report = prover_index.check_witness((), unreduced).unwrap();
+        assert!(report
+            .failures
+            .iter()
+            .any(|failure| failure.typ == GateType::RangeCheck0));
+
+        // limbs of 88 bits, but a value that is not less than the modulus
+        for value in [
+            modulus.clone(),
+            &modulus + 1u8,
+            BigUint::binary_modulus() - 1u8,
+        ] {
+            let report = prover_index
+                .check_witness((), value.to_field_limbs())
+                .unwrap();
+            assert!(!report.is_ok());
+        }
+    }
+
+    #[test]
+    fn snarky_foreign_field_rejects_tampered_product() {
+        let (mut prover_index, _) = TestCircuit.compile_to_indexes().unwrap();
+
+        let modulus = Secp256k1Base::modulus();
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let x = rng.gen_biguint_below(&modulus);
+        let y = rng.gen_biguint_below(&modulus);
+
+        // the remainder is in the columns 0 and 1, and the quotient in the
+        // columns 2 to 4, of the row after the ForeignFieldMul gate
+        for col in [0, 1, 2, 3, 4] {
+            let report = prover_index
+                .check_tampered_witness((), (x.clone(), y.clone()), |gates, witness| {
+                    let row = gates
+                        .iter()
+                        .position(|gate| gate.typ == GateType::ForeignFieldMul)
+                        .unwrap();
+                    witness[col][row + 1] += Fp::one();
+                })
+                .unwrap();
+            assert!(report
+                .failures
+                .iter()
+                .any(|failure| failure.typ == GateType::ForeignFieldMul));
+        }
+    }
+}
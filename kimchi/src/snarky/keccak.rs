@@ -0,0 +1,362 @@
+//! The Keccak-256 hash function (as used in Ethereum) over bytes,
+//! with a permutation built from the bitwise gadgets on 64-bit words.
+//!
+//! Note that it does not use the `KeccakRound` and `KeccakSponge` gates:
+//! snarky only wires the first [PERMUTS](crate::circuits::wires::PERMUTS)
+//! columns, so it cannot connect the message and the digest stored in the
+//! wider Keccak rows to the rest of the circuit.
+
+use super::{
+    boolean::Boolean,
+    errors::SnarkyResult,
+    runner::RunState,
+    uint::{UInt64, UInt8},
+};
+use crate::circuits::polynomials::keccak::{
+    constants::{DIM, RATE_IN_BYTES, ROUNDS},
+    Keccak, OFF, RC,
+};
+use crate::FieldVar;
+use ark_ff::PrimeField;
+use std::borrow::Cow;
+
+/// The state of the permutation, indexed by `[x][y]`
+type State<F> = [[UInt64<F>; DIM]; DIM];
+
+/// Applies one round of the Keccak-f[1600] permutation to the state
+fn round<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    state: State<F>,
+    rc: u64,
+) -> SnarkyResult<State<F>> {
+    // theta
+    let mut c = vec![];
+    for lanes in &state {
+        let mut column = lanes[0].clone();
+        for lane in &lanes[1..] {
+            column = column.xor(lane, runner, loc.clone())?;
+        }
+        c.push(column);
+    }
+    let mut d = vec![];
+    for x in 0..DIM {
+        let rotated = c[(x + 1) % DIM].rotate_left(1, runner, loc.clone())?;
+        d.push(c[(x + DIM - 1) % DIM].xor(&rotated, runner, loc.clone())?);
+    }
+    let mut a = state;
+    for (lanes, d) in a.iter_mut().zip(&d) {
+        for lane in lanes.iter_mut() {
+            *lane = lane.xor(d, runner, loc.clone())?;
+        }
+    }
+
+    // rho and pi
+    let mut b = a.clone();
+    for (x, lanes) in a.iter().enumerate() {
+        for (y, lane) in lanes.iter().enumerate() {
+            // the offsets are indexed by [y][x]
+            let rotated = match OFF[y][x] {
+                0 => lane.clone(),
+                off => lane.rotate_left(off as u32, runner, loc.clone())?,
+            };
+            b[y][(2 * x + 3 * y) % DIM] = rotated;
+        }
+    }
+
+    // chi
+    let mut a = b.clone();
+    for (x, lanes) in a.iter_mut().enumerate() {
+        for (y, lane) in lanes.iter_mut().enumerate() {
+            let not = b[(x + 1) % DIM][y].not();
+            let and = not.and(&b[(x + 2) % DIM][y], runner, loc.clone())?;
+            *lane = b[x][y].xor(&and, runner, loc.clone())?;
+        }
+    }
+
+    // iota
+    a[0][0] = a[0][0].xor(&UInt64::constant(rc), runner, loc.clone())?;
+
+    Ok(a)
+}
+
+/// Applies the Keccak-f[1600] permutation to the state
+fn permutation<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    mut state: State<F>,
+) -> SnarkyResult<State<F>> {
+    for rc in RC.iter().take(ROUNDS) {
+        state = round(runner, loc.clone(), state, *rc)?;
+    }
+    Ok(state)
+}
+
+/// Absorbs a block of the padded message, the first block being the initial state
+fn absorb<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    state: Option<State<F>>,
+    block: &[UInt8<F>],
+) -> SnarkyResult<State<F>> {
+    let mut lanes = vec![UInt64::constant(0); DIM * DIM];
+    for (lane, bytes) in lanes.iter_mut().zip(block.chunks(8)) {
+        *lane = UInt64::from_bytes(&bytes.to_vec().try_into().unwrap());
+    }
+    // the lane x + 5 * y is at [x][y]
+    let lane = |x: usize, y: usize| lanes[x + DIM * y].clone();
+    let new_state = match state {
+        None => std::array::from_fn(|x| std::array::from_fn(|y| lane(x, y))),
+        Some(state) => {
+            let mut new_state = state.clone();
+            for (x, lanes) in new_state.iter_mut().enumerate() {
+                for (y, new_lane) in lanes.iter_mut().enumerate() {
+                    // the lanes of the capacity are untouched
+                    if x + DIM * y < RATE_IN_BYTES / 8 {
+                        *new_lane = state[x][y].xor(&lane(x, y), runner, loc.clone())?;
+                    }
+                }
+            }
+            new_state
+        }
+    };
+    permutation(runner, loc, new_state)
+}
+
+/// Squeezes the digest, made of the first 4 lanes
+fn squeeze<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    state: &State<F>,
+) -> SnarkyResult<[UInt8<F>; 32]> {
+    let mut digest = vec![];
+    for lane in &state[..4] {
+        digest.extend(lane[0].to_bytes(runner, loc.clone())?);
+    }
+    Ok(digest.try_into().unwrap())
+}
+
+/// Returns the Keccak-256 hash of `message`.
+pub fn keccak256<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    message: &[UInt8<F>],
+) -> SnarkyResult<[UInt8<F>; 32]> {
+    // pad with the 10*1 rule
+    let padded_len = Keccak::padded_length(message.len());
+    let mut padding = vec![0u8; padded_len - message.len()];
+    padding[0] = 0x01;
+    *padding.last_mut().unwrap() |= 0x80;
+    let padded: Vec<_> = message
+        .iter()
+        .cloned()
+        .chain(padding.into_iter().map(UInt8::constant))
+        .collect();
+
+    let mut state = None;
+    for block in padded.chunks(RATE_IN_BYTES) {
+        state = Some(absorb(runner, loc.clone(), state, block)?);
+    }
+    squeeze(runner, loc, &state.unwrap())
+}
+
+/// Returns the Keccak-256 hash of the first `length` bytes of `message`,
+/// where `length` is a variable constrained to be at most the length of `message`.
+///
+/// The circuit hashes as many blocks as a message of the maximum length,
+/// and the digest is selected after the block holding the end of the padding.
+pub fn keccak256_var<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    message: &[UInt8<F>],
+    length: &FieldVar<F>,
+) -> SnarkyResult<[UInt8<F>; 32]> {
+    let max_len = message.len();
+    let blocks = max_len / RATE_IN_BYTES + 1;
+
+    // the position of the end of the message
+    let end = Boolean::one_hot(length, max_len + 1, runner, loc.clone())?;
+    // the last block, that holds the end of the message
+    let last: Vec<_> = end
+        .chunks(RATE_IN_BYTES)
+        .map(|flags| {
+            let sum = flags
+                .iter()
+                .fold(FieldVar::zero(), |acc, flag| acc + &flag.to_field_var());
+            Boolean::create_unsafe(sum)
+        })
+        .collect();
+
+    // pad with the 10*1 rule: the message, then 0x01 at its end,
+    // and 0x80 at the end of the last block
+    let mut padded = vec![];
+    let mut in_message = FieldVar::constant(F::one());
+    for i in 0..blocks * RATE_IN_BYTES {
+        let mut byte = FieldVar::zero();
+        if i <= max_len {
+            in_message = in_message.seal(runner, loc.clone())? - &end[i].to_field_var();
+            byte = byte + &end[i].to_field_var();
+        }
+        if i < max_len {
+            let value = message[i].to_field_var().mul(
+                &in_message,
+                Some("keccak padding".into()),
+                loc.clone(),
+                runner,
+            )?;
+            byte = byte + &value;
+        }
+        if i % RATE_IN_BYTES == RATE_IN_BYTES - 1 {
+            byte = byte
+                + &last[i / RATE_IN_BYTES]
+                    .to_field_var()
+                    .scale(F::from(0x80u8));
+        }
+        padded.push(UInt8::create_unsafe(byte));
+    }
+
+    let mut state = None;
+    let mut digests = vec![];
+    for block in padded.chunks(RATE_IN_BYTES) {
+        let new_state = absorb(runner, loc.clone(), state, block)?;
+        digests.push(squeeze(runner, loc.clone(), &new_state)?);
+        state = Some(new_state);
+    }
+
+    let mut digest = vec![];
+    for i in 0..32 {
+        let options: Vec<_> = digests.iter().map(|digest| digest[i].clone()).collect();
+        digest.push(UInt8::select(&last, &options, runner, loc.clone())?);
+    }
+    Ok(digest.try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{circuits::gate::GateType, loc, snarky::api::SnarkyCircuit};
+    use ark_ff::One;
+    use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+    use mina_poseidon::{
+        constants::PlonkSpongeConstantsKimchi,
+        sponge::{DefaultFqSponge, DefaultFrSponge},
+    };
+    use poly_commitment::ipa::OpeningProof;
+    use sha3::{Digest, Keccak256};
+
+    type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+    /// Hashes a message of a fixed length
+    struct TestCircuit {
+        len: usize,
+    }
+
+    impl SnarkyCircuit for TestCircuit {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = Vec<u8>;
+        type PublicInput = ();
+        type PublicOutput = [UInt8<Fp>; 32];
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            _public: Self::PublicInput,
+            private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            let mut message = vec![];
+            for i in 0..self.len {
+                let byte: UInt8<Fp> = sys.compute(loc!(), |_| private.unwrap()[i])?;
+                message.push(byte);
+            }
+            sys.keccak256(loc!(), &message)
+        }
+    }
+
+    /// Hashes a message of a variable length, up to a maximum length
+    struct VarLengthCircuit {
+        max_len: usize,
+    }
+
+    impl SnarkyCircuit for VarLengthCircuit {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = Vec<u8>;
+        type PublicInput = ();
+        type PublicOutput = [UInt8<Fp>; 32];
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            _public: Self::PublicInput,
+            private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            let length: FieldVar<Fp> =
+                sys.compute(loc!(), |_| Fp::from(private.unwrap().len() as u64))?;
+            let mut message = vec![];
+            for i in 0..self.max_len {
+                let byte: UInt8<Fp> =
+                    sys.compute(loc!(), |_| private.unwrap().get(i).copied().unwrap_or(0))?;
+                message.push(byte);
+            }
+            sys.keccak256_var(loc!(), &message, &length)
+        }
+    }
+
+    #[test]
+    fn snarky_keccak256() {
+        let message = b"the quick brown fox jumps over the lazy dog".to_vec();
+        let test_circuit = TestCircuit { len: message.len() };
+        let (mut prover_index, verifier_index) = test_circuit.compile_to_indexes().unwrap();
+
+        let debug = true;
+        let (proof, digest) = prover_index
+            .prove::<BaseSponge, ScalarSponge>((), message.clone(), debug)
+            .unwrap();
+        assert_eq!(digest.to_vec(), Keccak256::digest(&message).to_vec());
+
+        verifier_index.verify::<BaseSponge, ScalarSponge>(proof, (), *digest);
+    }
+
+    #[test]
+    fn snarky_keccak256_var() {
+        let test_circuit = VarLengthCircuit { max_len: 140 };
+        let (mut prover_index, verifier_index) = test_circuit.compile_to_indexes().unwrap();
+
+        // the message ends in the first block, or in the second one
+        for len in [0, 135, 140] {
+            let message: Vec<u8> = (0..len as u8).collect();
+            let debug = true;
+            let (proof, digest) = prover_index
+                .prove::<BaseSponge, ScalarSponge>((), message.clone(), debug)
+                .unwrap();
+            assert_eq!(digest.to_vec(), Keccak256::digest(&message).to_vec());
+
+            verifier_index.verify::<BaseSponge, ScalarSponge>(proof, (), *digest);
+        }
+    }
+
+    #[test]
+    fn snarky_keccak256_rejects_tampered_witness() {
+        let message = b"the quick brown fox".to_vec();
+        let test_circuit = TestCircuit { len: message.len() };
+        let (mut prover_index, _) = test_circuit.compile_to_indexes().unwrap();
+
+        let report = prover_index.check_witness((), message.clone()).unwrap();
+        assert!(report.is_ok(), "{report}");
+
+        // the output of the last XOR of the permutation, and the last rotation
+        for (typ, col) in [(GateType::Xor16, 2), (GateType::Rot64, 1)] {
+            let report = prover_index
+                .check_tampered_witness((), message.clone(), |gates, witness| {
+                    let row = gates.iter().rposition(|gate| gate.typ == typ).unwrap();
+                    witness[col][row] += Fp::one();
+                })
+                .unwrap();
+            assert!(report.failures.iter().any(|failure| failure.typ == typ));
+        }
+    }
+}
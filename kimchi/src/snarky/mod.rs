@@ -9,16 +9,21 @@
 
 pub mod api;
 pub mod asm;
+pub mod bitwise;
 pub mod boolean;
 pub mod constants;
 pub mod constraint_system;
 pub mod cvar;
 pub mod errors;
 pub mod folding;
+pub mod foreign_field;
+pub mod keccak;
 pub mod poseidon;
 pub(crate) mod range_checks;
 pub mod runner;
+pub mod sha256;
 pub mod snarky_type;
+pub mod uint;
 pub mod union_find;
 
 #[cfg(test)]
@@ -27,6 +27,10 @@ pub fn poseidon<F: PrimeField>(
     let (constraint, hash) = {
         let params = runner.poseidon_params();
         let mut iter = successors((initial_state, 0_usize).into(), |(prev, i)| {
+            // the iterator eagerly computes the successor of the last state
+            if *i == ROUNDS_PER_HASH {
+                return None;
+            }
             //this case may justify moving to Cow
             let state = round(runner, loc.clone(), prev, *i, &params);
             Some((state, i + 1))
@@ -163,7 +167,10 @@ where
     ) -> (FieldVar<F>, FieldVar<F>) {
         let left = self.state[0].clone();
         let right = self.state[1].clone();
-        sys.poseidon(loc, (left, right))
+        let (left, right) = sys.poseidon(loc, (left, right));
+        self.state[0] = left.clone();
+        self.state[1] = right.clone();
+        (left, right)
     }
 
     /// Squeeze.
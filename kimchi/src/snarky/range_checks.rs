@@ -82,6 +82,30 @@ impl<F: PrimeField> RangeCheckLimbs2<F> {
     }
 }
 
+/// Extracts the 12-bit limbs and 2-bit crumbs of the 64 least significant bits of f,
+/// with the layout of the columns 3 to 14 of a `RangeCheck0` row
+pub(crate) fn range_check_64_limbs<F: PrimeField>(f: F) -> ([F; 4], [F; 8]) {
+    let (limbs, crumbs) = RangeCheckLimbs1::parse(f).into_repr();
+    ([limbs[2], limbs[3], limbs[4], limbs[5]], crumbs)
+}
+
+/// Creates the row of a `RangeCheck0` gate constraining `v` to fit in 64 bits,
+/// its 2 most significant limbs being copied from the zero constant
+pub(crate) fn range_check_64_row<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    v: FieldVar<F>,
+) -> SnarkyResult<Vec<FieldVar<F>>> {
+    let (limbs, crumbs): ([FieldVar<F>; 4], [FieldVar<F>; 8]) =
+        runner.compute(loc, |w| range_check_64_limbs(w.read_var(&v)))?;
+    let row = [v, FieldVar::zero(), FieldVar::zero()]
+        .into_iter()
+        .chain(limbs)
+        .chain(crumbs)
+        .collect();
+    Ok(row)
+}
+
 pub fn range_check<F: PrimeField>(
     runner: &mut RunState<F>,
     loc: Cow<'static, str>,
@@ -8,8 +8,11 @@ use super::{
     errors::{
         RealSnarkyError, SnarkyCompilationError, SnarkyError, SnarkyResult, SnarkyRuntimeResult,
     },
+    keccak::{keccak256, keccak256_var},
     poseidon::poseidon,
     range_checks::range_check,
+    sha256::{sha256, sha256_var},
+    uint::UInt8,
 };
 use crate::{
     circuits::gate::CircuitGate,
@@ -675,4 +678,44 @@ where
     ) -> SnarkyResult<()> {
         range_check(self, loc, v0, v1, v2)
     }
+
+    /// Returns the Keccak-256 hash of the bytes of `message`.
+    pub fn keccak256(
+        &mut self,
+        loc: Cow<'static, str>,
+        message: &[UInt8<F>],
+    ) -> SnarkyResult<[UInt8<F>; 32]> {
+        keccak256(self, loc, message)
+    }
+
+    /// Returns the Keccak-256 hash of the first `length` bytes of `message`,
+    /// where `length` is at most the length of `message`.
+    pub fn keccak256_var(
+        &mut self,
+        loc: Cow<'static, str>,
+        message: &[UInt8<F>],
+        length: &FieldVar<F>,
+    ) -> SnarkyResult<[UInt8<F>; 32]> {
+        keccak256_var(self, loc, message, length)
+    }
+
+    /// Returns the SHA-256 hash of the bytes of `message`.
+    pub fn sha256(
+        &mut self,
+        loc: Cow<'static, str>,
+        message: &[UInt8<F>],
+    ) -> SnarkyResult<[UInt8<F>; 32]> {
+        sha256(self, loc, message)
+    }
+
+    /// Returns the SHA-256 hash of the first `length` bytes of `message`,
+    /// where `length` is at most the length of `message`.
+    pub fn sha256_var(
+        &mut self,
+        loc: Cow<'static, str>,
+        message: &[UInt8<F>],
+        length: &FieldVar<F>,
+    ) -> SnarkyResult<[UInt8<F>; 32]> {
+        sha256_var(self, loc, message, length)
+    }
 }
@@ -0,0 +1,347 @@
+//! The SHA-256 hash function, built from 32-bit XOR, AND, rotation and shift gadgets.
+
+use super::{
+    boolean::Boolean,
+    errors::SnarkyResult,
+    runner::RunState,
+    uint::{UInt32, UInt64, UInt8},
+};
+use crate::FieldVar;
+use ark_ff::PrimeField;
+use std::borrow::Cow;
+
+/// The number of bytes of a block of the padded message
+pub const BLOCK_IN_BYTES: usize = 64;
+
+/// The round constants
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// The initial hash value
+const H0: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+type State<F> = [UInt32<F>; 8];
+
+// Returns the XOR of the rotations of x to the right by the given amounts,
+// the last one being a shift instead of a rotation if `shift` is set
+fn sigma<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    x: &UInt32<F>,
+    amounts: [u32; 3],
+    shift: bool,
+) -> SnarkyResult<UInt32<F>> {
+    let a = x.rotate_right(amounts[0], runner, loc.clone())?;
+    let b = x.rotate_right(amounts[1], runner, loc.clone())?;
+    let c = if shift {
+        x.shift_right(amounts[2], runner, loc.clone())?
+    } else {
+        x.rotate_right(amounts[2], runner, loc.clone())?
+    };
+    a.xor(&b, runner, loc.clone())?.xor(&c, runner, loc)
+}
+
+/// Compresses a block of 64 bytes into the state
+fn compress<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    state: &State<F>,
+    block: &[UInt8<F>],
+) -> SnarkyResult<State<F>> {
+    // the message schedule, made of big-endian words
+    let mut w: Vec<UInt32<F>> = block
+        .chunks(4)
+        .map(|bytes| {
+            let mut bytes = bytes.to_vec();
+            bytes.reverse();
+            UInt32::from_bytes(&bytes.try_into().unwrap())
+        })
+        .collect();
+    for t in 16..64 {
+        let s0 = sigma(runner, loc.clone(), &w[t - 15], [7, 18, 3], true)?;
+        let s1 = sigma(runner, loc.clone(), &w[t - 2], [17, 19, 10], true)?;
+        let word = UInt32::wrapping_sum(
+            &[s1, w[t - 7].clone(), s0, w[t - 16].clone()],
+            runner,
+            loc.clone(),
+        )?;
+        w.push(word);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state.clone();
+    for t in 0..64 {
+        let s1 = sigma(runner, loc.clone(), &e, [6, 11, 25], false)?;
+        // ch = (e & f) ^ (!e & g)
+        let ch = e.and(&f, runner, loc.clone())?.xor(
+            &e.not().and(&g, runner, loc.clone())?,
+            runner,
+            loc.clone(),
+        )?;
+        let s0 = sigma(runner, loc.clone(), &a, [2, 13, 22], false)?;
+        // maj = (a & b) ^ (a & c) ^ (b & c)
+        let maj = a
+            .and(&b, runner, loc.clone())?
+            .xor(&a.and(&c, runner, loc.clone())?, runner, loc.clone())?
+            .xor(&b.and(&c, runner, loc.clone())?, runner, loc.clone())?;
+
+        let k = UInt32::constant(K[t]);
+        let temp1 = [h, s1, ch, k, w[t].clone()];
+        h = g;
+        g = f;
+        f = e;
+        e = UInt32::wrapping_sum(&[&temp1[..], &[d]].concat(), runner, loc.clone())?;
+        d = c;
+        c = b;
+        b = a;
+        a = UInt32::wrapping_sum(&[&temp1[..], &[s0, maj]].concat(), runner, loc.clone())?;
+    }
+
+    let mut new_state = vec![];
+    for (word, new_word) in state.iter().zip([a, b, c, d, e, f, g, h]) {
+        new_state.push(UInt32::wrapping_sum(
+            &[word.clone(), new_word],
+            runner,
+            loc.clone(),
+        )?);
+    }
+    Ok(new_state.try_into().unwrap())
+}
+
+/// Returns the digest of a state, made of its big-endian words
+fn digest<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    state: &State<F>,
+) -> SnarkyResult<[UInt8<F>; 32]> {
+    let mut digest = vec![];
+    for word in state {
+        let mut bytes = word.to_bytes(runner, loc.clone())?;
+        bytes.reverse();
+        digest.extend(bytes);
+    }
+    Ok(digest.try_into().unwrap())
+}
+
+fn initial_state<F: PrimeField>() -> State<F> {
+    H0.map(UInt32::constant)
+}
+
+/// Returns the SHA-256 hash of `message`.
+pub fn sha256<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    message: &[UInt8<F>],
+) -> SnarkyResult<[UInt8<F>; 32]> {
+    // pad with 0x80, zeros, and the big-endian bit length on 8 bytes
+    let blocks = (message.len() + 8) / BLOCK_IN_BYTES + 1;
+    let mut padding = vec![0u8; blocks * BLOCK_IN_BYTES - message.len()];
+    padding[0] = 0x80;
+    let bit_length = (8 * message.len() as u64).to_be_bytes();
+    let len = padding.len();
+    padding[len - 8..].copy_from_slice(&bit_length);
+    let padded: Vec<_> = message
+        .iter()
+        .cloned()
+        .chain(padding.into_iter().map(UInt8::constant))
+        .collect();
+
+    let mut state = initial_state();
+    for block in padded.chunks(BLOCK_IN_BYTES) {
+        state = compress(runner, loc.clone(), &state, block)?;
+    }
+    digest(runner, loc, &state)
+}
+
+/// Returns the SHA-256 hash of the first `length` bytes of `message`,
+/// where `length` is a variable constrained to be at most the length of `message`.
+///
+/// The circuit compresses as many blocks as a message of the maximum length,
+/// and the digest is selected after the block holding the end of the padding.
+pub fn sha256_var<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    message: &[UInt8<F>],
+    length: &FieldVar<F>,
+) -> SnarkyResult<[UInt8<F>; 32]> {
+    let max_len = message.len();
+    let blocks = (max_len + 8) / BLOCK_IN_BYTES + 1;
+
+    // the position of the end of the message
+    let end = Boolean::one_hot(length, max_len + 1, runner, loc.clone())?;
+    // the last block, that holds the bit length after the end of the message
+    let mut last = vec![FieldVar::zero(); blocks];
+    for (i, flag) in end.iter().enumerate() {
+        let k = (i + 8) / BLOCK_IN_BYTES;
+        last[k] = last[k].clone() + &flag.to_field_var();
+    }
+    let last: Vec<_> = last.into_iter().map(Boolean::create_unsafe).collect();
+
+    // the big-endian bit length, at most 2^64 - 1
+    let bit_length = UInt64::create_unsafe(length.scale(F::from(8u8)));
+    let mut bit_length = bit_length.to_bytes(runner, loc.clone())?;
+    bit_length.reverse();
+
+    // pad with the message, then 0x80 at its end,
+    // and the bit length at the end of the last block
+    let mut padded = vec![];
+    let mut in_message = FieldVar::constant(F::one());
+    for i in 0..blocks * BLOCK_IN_BYTES {
+        let mut byte = FieldVar::zero();
+        if i <= max_len {
+            in_message = in_message.seal(runner, loc.clone())? - &end[i].to_field_var();
+            byte = byte + &end[i].to_field_var().scale(F::from(0x80u8));
+        }
+        if i < max_len {
+            let value = message[i].to_field_var().mul(
+                &in_message,
+                Some("sha256 padding".into()),
+                loc.clone(),
+                runner,
+            )?;
+            byte = byte + &value;
+        }
+        let offset = i % BLOCK_IN_BYTES;
+        if offset >= BLOCK_IN_BYTES - 8 {
+            let value = bit_length[offset + 8 - BLOCK_IN_BYTES].to_field_var().mul(
+                &last[i / BLOCK_IN_BYTES].to_field_var(),
+                Some("sha256 padding".into()),
+                loc.clone(),
+                runner,
+            )?;
+            byte = byte + &value;
+        }
+        padded.push(UInt8::create_unsafe(byte));
+    }
+
+    let mut state = initial_state();
+    let mut digests = vec![];
+    for block in padded.chunks(BLOCK_IN_BYTES) {
+        state = compress(runner, loc.clone(), &state, block)?;
+        digests.push(digest(runner, loc.clone(), &state)?);
+    }
+
+    let mut digest = vec![];
+    for i in 0..32 {
+        let options: Vec<_> = digests.iter().map(|digest| digest[i].clone()).collect();
+        digest.push(UInt8::select(&last, &options, runner, loc.clone())?);
+    }
+    Ok(digest.try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{loc, snarky::api::SnarkyCircuit};
+    use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+    use mina_poseidon::{
+        constants::PlonkSpongeConstantsKimchi,
+        sponge::{DefaultFqSponge, DefaultFrSponge},
+    };
+    use poly_commitment::ipa::OpeningProof;
+    use sha2::{Digest, Sha256};
+
+    type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+    /// Hashes a message of a fixed length
+    struct TestCircuit {
+        len: usize,
+    }
+
+    impl SnarkyCircuit for TestCircuit {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = Vec<u8>;
+        type PublicInput = ();
+        type PublicOutput = [UInt8<Fp>; 32];
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            _public: Self::PublicInput,
+            private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            let mut message = vec![];
+            for i in 0..self.len {
+                let byte: UInt8<Fp> = sys.compute(loc!(), |_| private.unwrap()[i])?;
+                message.push(byte);
+            }
+            sys.sha256(loc!(), &message)
+        }
+    }
+
+    /// Hashes a message of a variable length, up to a maximum length
+    struct VarLengthCircuit {
+        max_len: usize,
+    }
+
+    impl SnarkyCircuit for VarLengthCircuit {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = Vec<u8>;
+        type PublicInput = ();
+        type PublicOutput = [UInt8<Fp>; 32];
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            _public: Self::PublicInput,
+            private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            let length: FieldVar<Fp> =
+                sys.compute(loc!(), |_| Fp::from(private.unwrap().len() as u64))?;
+            let mut message = vec![];
+            for i in 0..self.max_len {
+                let byte: UInt8<Fp> =
+                    sys.compute(loc!(), |_| private.unwrap().get(i).copied().unwrap_or(0))?;
+                message.push(byte);
+            }
+            sys.sha256_var(loc!(), &message, &length)
+        }
+    }
+
+    #[test]
+    fn snarky_sha256() {
+        let message = b"the quick brown fox jumps over the lazy dog".to_vec();
+        let test_circuit = TestCircuit { len: message.len() };
+        let (mut prover_index, verifier_index) = test_circuit.compile_to_indexes().unwrap();
+
+        let debug = true;
+        let (proof, digest) = prover_index
+            .prove::<BaseSponge, ScalarSponge>((), message.clone(), debug)
+            .unwrap();
+        assert_eq!(digest.to_vec(), Sha256::digest(&message).to_vec());
+
+        verifier_index.verify::<BaseSponge, ScalarSponge>(proof, (), *digest);
+    }
+
+    #[test]
+    fn snarky_sha256_var() {
+        let test_circuit = VarLengthCircuit { max_len: 70 };
+        let (mut prover_index, verifier_index) = test_circuit.compile_to_indexes().unwrap();
+
+        // the padding fits in the first block, or spills over the second one
+        for len in [10, 60] {
+            let message: Vec<u8> = (0..len as u8).collect();
+            let debug = true;
+            let (proof, digest) = prover_index
+                .prove::<BaseSponge, ScalarSponge>((), message.clone(), debug)
+                .unwrap();
+            assert_eq!(digest.to_vec(), Sha256::digest(&message).to_vec());
+
+            verifier_index.verify::<BaseSponge, ScalarSponge>(proof, (), *digest);
+        }
+    }
+}
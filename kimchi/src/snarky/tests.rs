@@ -1,3 +1,4 @@
+use crate::circuits::gate::GateType;
 use crate::{
     loc,
     snarky::{
@@ -141,3 +142,32 @@ fn test_simple_circuit() {
         }
     }
 }
+
+#[test]
+fn test_check_witness() {
+    let test_circuit = TestCircuit {};
+    let (mut prover_index, _verifier_index) = test_circuit.compile_to_indexes().unwrap();
+
+    // a good execution
+    let private_input = Priv {
+        x: Fp::one(),
+        y: Fp::from(2),
+        z: Fp::from(2),
+    };
+    let report = prover_index.check_witness(true, private_input).unwrap();
+    assert!(report.is_ok(), "{report}");
+
+    // a bad execution is reported with the labels of the failing constraint
+    let private_input = Priv {
+        x: Fp::one(),
+        y: Fp::from(3),
+        z: Fp::from(2),
+    };
+    let report = prover_index.check_witness(true, private_input).unwrap();
+    assert!(!report.is_ok());
+    assert!(report.failures.iter().all(|f| f.typ == GateType::Generic));
+    assert!(report
+        .failures
+        .iter()
+        .any(|f| f.labels.iter().any(|l| l.contains("x * y = z"))));
+}
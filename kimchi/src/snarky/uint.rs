@@ -0,0 +1,481 @@
+//! The [UInt8], [UInt32] and [UInt64] types are snarky types representing
+//! unsigned integers, whose variables are constrained to fit in 8, 32 and 64 bits.
+//! They provide the bitwise operations of the [super::bitwise] gadgets.
+
+use super::{
+    bitwise, boolean::Boolean, errors::SnarkyResult, runner::RunState, snarky_type::SnarkyType,
+};
+use crate::{circuits::polynomials::rot::RotMode, FieldVar};
+use ark_ff::{BigInteger, PrimeField};
+use std::borrow::Cow;
+
+macro_rules! uint {
+    ($name:ident, $ty:ty, $bits:expr) => {
+        #[doc = concat!("An unsigned integer variable of ", stringify!($bits), " bits.")]
+        #[derive(Debug, Clone)]
+        pub struct $name<F: PrimeField>(FieldVar<F>);
+
+        impl<F> SnarkyType<F> for $name<F>
+        where
+            F: PrimeField,
+        {
+            type Auxiliary = ();
+
+            type OutOfCircuit = $ty;
+
+            const SIZE_IN_FIELD_ELEMENTS: usize = 1;
+
+            fn to_cvars(&self) -> (Vec<FieldVar<F>>, Self::Auxiliary) {
+                (vec![self.0.clone()], ())
+            }
+
+            fn from_cvars_unsafe(cvars: Vec<FieldVar<F>>, _aux: Self::Auxiliary) -> Self {
+                assert_eq!(cvars.len(), Self::SIZE_IN_FIELD_ELEMENTS);
+                Self(cvars[0].clone())
+            }
+
+            fn check(&self, cs: &mut RunState<F>, loc: Cow<'static, str>) -> SnarkyResult<()> {
+                Self::range_check(&self.0, cs, loc)
+            }
+
+            fn constraint_system_auxiliary() -> Self::Auxiliary {}
+
+            fn value_to_field_elements(value: &Self::OutOfCircuit) -> (Vec<F>, Self::Auxiliary) {
+                (vec![F::from(*value)], ())
+            }
+
+            fn value_of_field_elements(
+                fields: Vec<F>,
+                _aux: Self::Auxiliary,
+            ) -> Self::OutOfCircuit {
+                assert_eq!(fields.len(), 1);
+                let bytes = fields[0].into_bigint().to_bytes_le();
+                assert!(
+                    bytes[std::mem::size_of::<$ty>()..].iter().all(|b| *b == 0),
+                    "value does not fit in {} bits",
+                    $bits
+                );
+                <$ty>::from_le_bytes(bytes[..std::mem::size_of::<$ty>()].try_into().unwrap())
+            }
+        }
+
+        impl<F> $name<F>
+        where
+            F: PrimeField,
+        {
+            /// The number of bits of the integer.
+            pub const BITS: usize = $bits;
+
+            pub fn constant(value: $ty) -> Self {
+                Self(FieldVar::constant(F::from(value)))
+            }
+
+            /// Creates an integer from a variable, without constraining it to fit in the integer bits.
+            pub fn create_unsafe(x: FieldVar<F>) -> Self {
+                Self(x)
+            }
+
+            pub fn to_field_var(&self) -> FieldVar<F> {
+                self.0.clone()
+            }
+
+            pub fn xor(
+                &self,
+                other: &Self,
+                cs: &mut RunState<F>,
+                loc: Cow<'static, str>,
+            ) -> SnarkyResult<Self> {
+                bitwise::xor(cs, loc, self.0.clone(), other.0.clone(), $bits).map(Self)
+            }
+
+            pub fn and(
+                &self,
+                other: &Self,
+                cs: &mut RunState<F>,
+                loc: Cow<'static, str>,
+            ) -> SnarkyResult<Self> {
+                bitwise::and(cs, loc, self.0.clone(), other.0.clone(), $bits).map(Self)
+            }
+
+            pub fn not(&self) -> Self {
+                Self(bitwise::not(self.0.clone(), $bits))
+            }
+
+            /// Returns the option whose flag is set, given one-hot flags.
+            pub fn select(
+                flags: &[Boolean<F>],
+                options: &[Self],
+                cs: &mut RunState<F>,
+                loc: Cow<'static, str>,
+            ) -> SnarkyResult<Self> {
+                assert_eq!(flags.len(), options.len());
+                let mut terms = vec![];
+                for (flag, option) in flags.iter().zip(options) {
+                    let term = flag.to_field_var().mul(
+                        &option.0,
+                        Some("select".into()),
+                        loc.clone(),
+                        cs,
+                    )?;
+                    terms.push(term);
+                }
+                Ok(Self(FieldVar::sum(&terms.iter().collect::<Vec<_>>())))
+            }
+        }
+    };
+}
+
+uint!(UInt8, u8, 8);
+uint!(UInt32, u32, 32);
+uint!(UInt64, u64, 64);
+
+impl<F: PrimeField> UInt8<F> {
+    /// Constrains `x` to fit in 8 bits: a single Xor16 row checks that both `x` and `2^8 * x` fit in 16 bits.
+    fn range_check(
+        x: &FieldVar<F>,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<()> {
+        let shifted = x.scale(F::from(1u64 << 8));
+        bitwise::xor(cs, loc, x.clone(), shifted, 16)?;
+        Ok(())
+    }
+}
+
+impl<F: PrimeField> UInt32<F> {
+    /// Constrains `x` to fit in 32 bits with the decomposition of a 32-bit XOR.
+    fn range_check(
+        x: &FieldVar<F>,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<()> {
+        bitwise::xor(cs, loc, x.clone(), FieldVar::zero(), 32)?;
+        Ok(())
+    }
+
+    /// Rotates the integer by `bits` to the left.
+    pub fn rotate_left(
+        &self,
+        bits: u32,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<Self> {
+        assert!(bits <= 32, "Rotation value must be less or equal than 32");
+
+        // the rotation of the 64-bit word made of two copies of x
+        // is made of two copies of the rotation of x
+        let two_to_32_plus_one = F::from((1u64 << 32) + 1);
+        let doubled = self.0.scale(two_to_32_plus_one);
+        let rotated = bitwise::rot64(cs, loc.clone(), doubled, bits, RotMode::Left)?;
+
+        let res: FieldVar<F> =
+            cs.compute(loc.clone(), |w| w.read_var(&rotated) / two_to_32_plus_one)?;
+        cs.assert_eq(
+            Some("rotate32".into()),
+            loc,
+            res.scale(two_to_32_plus_one),
+            rotated,
+        )?;
+
+        Ok(Self(res))
+    }
+
+    /// Rotates the integer by `bits` to the right.
+    pub fn rotate_right(
+        &self,
+        bits: u32,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<Self> {
+        assert!(bits <= 32, "Rotation value must be less or equal than 32");
+        self.rotate_left(32 - bits, cs, loc)
+    }
+
+    /// Shifts the integer by `bits` to the right.
+    pub fn shift_right(
+        &self,
+        bits: u32,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<Self> {
+        assert!(bits < 32, "Shift value must be less than 32");
+
+        // x = high * 2^bits + low, where both parts are range-checked
+        // by scaling them to 32 bits
+        let (high, low): (FieldVar<F>, FieldVar<F>) = cs.compute(loc.clone(), |w| {
+            let x = Self::value_of_field_elements(vec![w.read_var(&self.0)], ());
+            (F::from(x >> bits), F::from(x & ((1 << bits) - 1)))
+        })?;
+        Self::range_check(&high.scale(F::from(1u64 << bits)), cs, loc.clone())?;
+        Self::range_check(&low.scale(F::from(1u64 << (32 - bits))), cs, loc.clone())?;
+        cs.assert_eq(
+            Some("shift32".into()),
+            loc,
+            high.scale(F::from(1u64 << bits)) + &low,
+            self.0.clone(),
+        )?;
+
+        Ok(Self(high))
+    }
+
+    /// Returns the sum of at most 256 integers, modulo `2^32`.
+    pub fn wrapping_sum(
+        terms: &[Self],
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<Self> {
+        assert!(terms.len() <= 256, "Too many terms to sum");
+        let sum = FieldVar::sum(&terms.iter().map(|term| &term.0).collect::<Vec<_>>());
+
+        // the carry is range-checked to fit in 8 bits
+        let (res, carry): (Self, UInt8<F>) = cs.compute(loc.clone(), |w| {
+            let sum = w.read_var(&sum).into_bigint().as_ref()[0];
+            (sum as u32, (sum >> 32) as u8)
+        })?;
+        cs.assert_eq(
+            Some("wrapping_sum".into()),
+            loc,
+            res.0.clone() + &carry.0.scale(F::from(1u64 << 32)),
+            sum,
+        )?;
+
+        Ok(res)
+    }
+}
+
+impl<F: PrimeField> UInt64<F> {
+    /// Constrains `x` to fit in 64 bits with the decomposition of a 64-bit XOR.
+    fn range_check(
+        x: &FieldVar<F>,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<()> {
+        bitwise::xor(cs, loc, x.clone(), FieldVar::zero(), 64)?;
+        Ok(())
+    }
+
+    /// Rotates the integer by `bits` to the left.
+    pub fn rotate_left(
+        &self,
+        bits: u32,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<Self> {
+        bitwise::rot64(cs, loc, self.0.clone(), bits, RotMode::Left).map(Self)
+    }
+
+    /// Rotates the integer by `bits` to the right.
+    pub fn rotate_right(
+        &self,
+        bits: u32,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<Self> {
+        bitwise::rot64(cs, loc, self.0.clone(), bits, RotMode::Right).map(Self)
+    }
+}
+
+macro_rules! bytes {
+    ($name:ident, $bytes:expr) => {
+        impl<F: PrimeField> $name<F> {
+            #[doc = concat!("Packs ", stringify!($bytes), " bytes in little-endian order into an integer.")]
+            /// This is a linear combination, and does not add any constraint.
+            pub fn from_bytes(bytes: &[UInt8<F>; $bytes]) -> Self {
+                let terms: Vec<_> = bytes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, byte)| (F::from(1u64 << (8 * i)), byte.0.clone()))
+                    .collect();
+                Self(FieldVar::linear_combination(&terms))
+            }
+
+            #[doc = concat!("Unpacks the integer into ", stringify!($bytes), " bytes in little-endian order.")]
+            pub fn to_bytes(
+                &self,
+                cs: &mut RunState<F>,
+                loc: Cow<'static, str>,
+            ) -> SnarkyResult<[UInt8<F>; $bytes]> {
+                let bytes: [UInt8<F>; $bytes] = cs.compute(loc.clone(), |w| {
+                    let word = Self::value_of_field_elements(vec![w.read_var(&self.0)], ());
+                    word.to_le_bytes()
+                })?;
+                let packed = Self::from_bytes(&bytes);
+                cs.assert_eq(Some("to_bytes".into()), loc, packed.0, self.0.clone())?;
+                Ok(bytes)
+            }
+        }
+    };
+}
+
+bytes!(UInt32, 4);
+bytes!(UInt64, 8);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{circuits::gate::GateType, loc, snarky::api::SnarkyCircuit};
+    use ark_ff::{One, Zero};
+    use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+    use mina_poseidon::{
+        constants::PlonkSpongeConstantsKimchi,
+        sponge::{DefaultFqSponge, DefaultFrSponge},
+    };
+    use poly_commitment::ipa::OpeningProof;
+    use rand::Rng;
+
+    type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+    struct BitwiseCircuit;
+
+    impl SnarkyCircuit for BitwiseCircuit {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = (u64, u64, u32);
+        type PublicInput = ();
+        type PublicOutput = ([UInt64<Fp>; 5], [UInt32<Fp>; 2]);
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            _public: Self::PublicInput,
+            private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            let x: UInt64<Fp> = sys.compute(loc!(), |_| private.unwrap().0)?;
+            let y: UInt64<Fp> = sys.compute(loc!(), |_| private.unwrap().1)?;
+            let z: UInt32<Fp> = sys.compute(loc!(), |_| private.unwrap().2)?;
+
+            let res64 = [
+                x.xor(&y, sys, loc!())?,
+                x.and(&y, sys, loc!())?,
+                x.not(),
+                x.rotate_left(13, sys, loc!())?,
+                y.rotate_right(64, sys, loc!())?,
+            ];
+            let res32 = [
+                z.rotate_left(7, sys, loc!())?,
+                z.rotate_right(1, sys, loc!())?,
+            ];
+
+            Ok((res64, res32))
+        }
+    }
+
+    #[test]
+    fn snarky_bitwise() {
+        let (mut prover_index, verifier_index) = BitwiseCircuit.compile_to_indexes().unwrap();
+
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        for (x, y, z) in [(rng.gen(), rng.gen(), rng.gen()), (u64::MAX, 0, u32::MAX)] {
+            let debug = true;
+            let (proof, public_output) = prover_index
+                .prove::<BaseSponge, ScalarSponge>((), (x, y, z), debug)
+                .unwrap();
+            let (res64, res32) = *public_output;
+            assert_eq!(res64, [x ^ y, x & y, !x, x.rotate_left(13), y]);
+            assert_eq!(res32, [z.rotate_left(7), z.rotate_right(1)]);
+
+            verifier_index.verify::<BaseSponge, ScalarSponge>(proof, (), (res64, res32));
+        }
+    }
+
+    struct BytesCircuit;
+
+    impl SnarkyCircuit for BytesCircuit {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = u64;
+        type PublicInput = ();
+        type PublicOutput = [UInt8<Fp>; 8];
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            _public: Self::PublicInput,
+            private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            let x: UInt64<Fp> = sys.compute(loc!(), |_| *private.unwrap())?;
+            x.to_bytes(sys, loc!())
+        }
+    }
+
+    #[test]
+    fn snarky_to_bytes() {
+        let (mut prover_index, verifier_index) = BytesCircuit.compile_to_indexes().unwrap();
+
+        let x = 0x0123456789abcdef_u64;
+        let (proof, bytes) = prover_index
+            .prove::<BaseSponge, ScalarSponge>((), x, true)
+            .unwrap();
+        assert_eq!(*bytes, x.to_le_bytes());
+
+        verifier_index.verify::<BaseSponge, ScalarSponge>(proof, (), *bytes);
+    }
+
+    #[test]
+    fn snarky_bitwise_rejects_tampered_outputs() {
+        let (mut prover_index, _) = BitwiseCircuit.compile_to_indexes().unwrap();
+
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let input: (u64, u64, u32) = (rng.gen(), rng.gen(), rng.gen());
+        let report = prover_index.check_witness((), input).unwrap();
+        assert!(report.is_ok(), "{report}");
+
+        // the output of a XOR is in the column 2 of its first row,
+        // and the rotated word in the column 1 of a Rot64 row
+        for (typ, col) in [(GateType::Xor16, 2), (GateType::Rot64, 1)] {
+            let report = prover_index
+                .check_tampered_witness((), input, |gates, witness| {
+                    let row = gates.iter().position(|gate| gate.typ == typ).unwrap();
+                    witness[col][row] += Fp::one();
+                })
+                .unwrap();
+            assert!(report.failures.iter().any(|failure| failure.typ == typ));
+        }
+    }
+
+    /// Range checks integers given as field elements
+    struct RangeCheckCircuit;
+
+    impl SnarkyCircuit for RangeCheckCircuit {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = (Fp, Fp);
+        type PublicInput = ();
+        type PublicOutput = ();
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            _public: Self::PublicInput,
+            private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            let x: FieldVar<Fp> = sys.compute(loc!(), |_| private.unwrap().0)?;
+            let y: FieldVar<Fp> = sys.compute(loc!(), |_| private.unwrap().1)?;
+            UInt8::create_unsafe(x).check(sys, loc!())?;
+            UInt64::create_unsafe(y).check(sys, loc!())
+        }
+    }
+
+    #[test]
+    fn snarky_uint_rejects_out_of_range_values() {
+        let (mut prover_index, _) = RangeCheckCircuit.compile_to_indexes().unwrap();
+
+        let max = (Fp::from(u8::MAX), Fp::from(u64::MAX));
+        let report = prover_index.check_witness((), max).unwrap();
+        assert!(report.is_ok(), "{report}");
+
+        for input in [
+            (Fp::from(256u64), Fp::zero()),
+            (-Fp::one(), Fp::zero()),
+            (Fp::zero(), Fp::from(u64::MAX) + Fp::one()),
+            (Fp::zero(), -Fp::one()),
+        ] {
+            let report = prover_index.check_witness((), input).unwrap();
+            assert!(!report.is_ok());
+        }
+    }
+}
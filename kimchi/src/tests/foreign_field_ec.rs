@@ -0,0 +1,199 @@
+use crate::{
+    circuits::{
+        constraints::ConstraintSystem,
+        gate::{CircuitGate, CircuitGateError},
+        polynomial::COLUMNS,
+        polynomials::{
+            foreign_field_common::FieldArrayCompose,
+            foreign_field_ec::{
+                curve::{ForeignCurve, ForeignPoint},
+                witness,
+            },
+        },
+    },
+    tests::framework::TestFramework,
+};
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::One;
+use o1_utils::tests::make_test_rng;
+use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+use sha3::{Digest, Keccak256};
+use std::array;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type VestaBaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type VestaScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+// Returns the point whose coordinates are range-checked in the last rows of the witness
+fn output_point(witness: &[Vec<Fp>; COLUMNS]) -> ForeignPoint {
+    let rows = witness[0].len();
+    let coordinate = |start: usize| {
+        let limbs: [Fp; 3] = array::from_fn(|i| witness[0][start + i]);
+        limbs.compose()
+    };
+    ForeignPoint::new(coordinate(rows - 8), coordinate(rows - 4))
+}
+
+// Returns a random point of the curve
+fn random_point(curve: &ForeignCurve, rng: &mut impl RandBigInt) -> ForeignPoint {
+    let k = rng.gen_biguint_below(&curve.scalar_modulus);
+    curve.scalar_mul(&curve.generator, &k).unwrap()
+}
+
+#[test]
+fn test_foreign_ec_add_and_double() {
+    let curve = ForeignCurve::secp256k1();
+    let rng = &mut make_test_rng(None);
+    let p = random_point(&curve, rng);
+    let q = random_point(&curve, rng);
+
+    let mut gates = vec![];
+    let mut next_row = 0;
+    CircuitGate::<Fp>::extend_foreign_ec_add(&mut gates, &mut next_row, &curve);
+    CircuitGate::<Fp>::extend_foreign_ec_double(&mut gates, &mut next_row, &curve);
+
+    let mut witness = witness::create_add(&curve, &p, &q);
+    assert_eq!(output_point(&witness), curve.add(&p, &q));
+    let double_witness = witness::create_double(&curve, &p);
+    assert_eq!(output_point(&double_witness), curve.double(&p));
+    witness::extend(&mut witness, double_witness);
+    assert_eq!(witness[0].len(), next_row);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<VestaBaseSponge, VestaScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+fn test_foreign_ec_scalar_mul() {
+    let curve = ForeignCurve::secp256k1();
+    let rng = &mut make_test_rng(None);
+    let bits = 8;
+    let p = random_point(&curve, rng);
+    let k = BigUint::from(0b1011_0101u32);
+
+    let (_, gates) = CircuitGate::<Fp>::create_foreign_ec_scalar_mul(0, &curve, bits);
+    let witness = witness::create_scalar_mul(&curve, &p, &k, bits);
+    assert_eq!(output_point(&witness), curve.scalar_mul(&p, &k).unwrap());
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<VestaBaseSponge, VestaScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+fn test_foreign_ec_scalar_mul_invalid() {
+    let curve = ForeignCurve::secp256k1();
+    let rng = &mut make_test_rng(None);
+    let bits = 8;
+    let p = random_point(&curve, rng);
+
+    let (_, gates) = CircuitGate::<Fp>::create_foreign_ec_scalar_mul(0, &curve, bits);
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    // the scalar does not fit in the number of bits
+    let witness = witness::create_scalar_mul(&curve, &p, &BigUint::from(256u32), bits);
+    let result = gates
+        .iter()
+        .enumerate()
+        .try_for_each(|(row, gate)| gate.verify_witness::<Vesta>(row, &witness, &cs, &[]));
+    assert!(matches!(
+        result,
+        Err(CircuitGateError::CopyConstraint { .. })
+    ));
+}
+
+// Returns the inputs of the verification of an Ethereum signature, with a random key,
+// of the Keccak hash of a message: the public key, the hash, and the signature (r, s)
+fn ecdsa_signature(rng: &mut impl RandBigInt) -> (ForeignPoint, BigUint, BigUint, BigUint) {
+    let curve = ForeignCurve::secp256k1();
+    let secp = Secp256k1::new();
+
+    let secret = rng.gen_biguint_range(&BigUint::one(), &curve.scalar_modulus);
+    let mut bytes = [0u8; 32];
+    let secret = secret.to_bytes_be();
+    bytes[32 - secret.len()..].copy_from_slice(&secret);
+    let secret_key = SecretKey::from_slice(&bytes).unwrap();
+    let public_key = PublicKey::from_secret_key(&secp, &secret_key).serialize_uncompressed();
+    let public_key = ForeignPoint::new(
+        BigUint::from_bytes_be(&public_key[1..33]),
+        BigUint::from_bytes_be(&public_key[33..]),
+    );
+    let hash = Keccak256::digest(b"the quick brown fox jumps over the lazy dog");
+    let message = Message::from_digest_slice(&hash).unwrap();
+    let signature = secp.sign_ecdsa(&message, &secret_key).serialize_compact();
+    (
+        public_key,
+        BigUint::from_bytes_be(&hash),
+        BigUint::from_bytes_be(&signature[..32]),
+        BigUint::from_bytes_be(&signature[32..]),
+    )
+}
+
+#[test]
+fn test_foreign_ec_scalar_mul_full_scalars() {
+    let curve = ForeignCurve::secp256k1();
+    let rng = &mut make_test_rng(None);
+    let bits = curve.scalar_modulus.bits() as usize;
+    let p = random_point(&curve, rng);
+
+    // all the combinations of the two most significant bits
+    for top in 0..4u32 {
+        let k = rng.gen_biguint(bits as u64 - 2) | (BigUint::from(top) << (bits - 2));
+        let k = k % &curve.scalar_modulus;
+        let witness = witness::create_scalar_mul(&curve, &p, &k, bits);
+        assert_eq!(output_point(&witness), curve.scalar_mul(&p, &k).unwrap());
+    }
+}
+
+#[test]
+fn test_ecdsa_verify_secp256k1() {
+    let curve = ForeignCurve::secp256k1();
+    let rng = &mut make_test_rng(None);
+    let (public_key, hash, r, s) = ecdsa_signature(rng);
+
+    let (next_row, gates) = CircuitGate::<Fp>::create_ecdsa_verify(0, &curve);
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    let verify = |hash: &BigUint| {
+        let witness = witness::create_ecdsa_verify(&curve, &public_key, hash, &r, &s);
+        assert_eq!(witness[0].len(), next_row);
+        gates
+            .iter()
+            .enumerate()
+            .try_for_each(|(row, gate)| gate.verify_witness::<Vesta>(row, &witness, &cs, &[]))
+    };
+
+    assert_eq!(verify(&hash), Ok(()));
+    // a signature of another message
+    assert!(matches!(
+        verify(&(hash + 1u32)),
+        Err(CircuitGateError::CopyConstraint { .. })
+    ));
+}
+
+#[test]
+fn heavy_test_ecdsa_verify_secp256k1_prove_and_verify() {
+    let curve = ForeignCurve::secp256k1();
+    let rng = &mut make_test_rng(None);
+    let (public_key, hash, r, s) = ecdsa_signature(rng);
+
+    let (_, gates) = CircuitGate::<Fp>::create_ecdsa_verify(0, &curve);
+    let witness = witness::create_ecdsa_verify(&curve, &public_key, &hash, &r, &s);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<VestaBaseSponge, VestaScalarSponge>()
+        .unwrap();
+}
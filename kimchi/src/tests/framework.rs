@@ -35,14 +35,17 @@ use std::{fmt::Write, time::Instant};
 // aliases
 
 #[derive(Default, Clone)]
-pub(crate) struct TestFramework<G: KimchiCurve, OpeningProof: OpenProof<G> = DlogOpeningProof<G>>
-where
+pub(crate) struct TestFramework<
+    G: KimchiCurve,
+    OpeningProof: OpenProof<G> = DlogOpeningProof<G>,
+    const W: usize = COLUMNS,
+> where
     G::BaseField: PrimeField,
     OpeningProof::SRS: Clone,
-    VerifierIndex<G, OpeningProof>: Clone,
+    VerifierIndex<G, OpeningProof, W>: Clone,
 {
     gates: Option<Vec<CircuitGate<G::ScalarField>>>,
-    witness: Option<[Vec<G::ScalarField>; COLUMNS]>,
+    witness: Option<[Vec<G::ScalarField>; W]>,
     public_inputs: Vec<G::ScalarField>,
     lookup_tables: Vec<LookupTable<G::ScalarField>>,
     runtime_tables_setup: Option<Vec<RuntimeTableCfg<G::ScalarField>>>,
@@ -52,24 +55,26 @@ where
     disable_gates_checks: bool,
     override_srs_size: Option<usize>,
 
-    prover_index: Option<ProverIndex<G, OpeningProof>>,
-    verifier_index: Option<VerifierIndex<G, OpeningProof>>,
+    prover_index: Option<ProverIndex<G, OpeningProof, W>>,
+    verifier_index: Option<VerifierIndex<G, OpeningProof, W>>,
 }
 
 #[derive(Clone)]
-pub(crate) struct TestRunner<G: KimchiCurve, OpeningProof: OpenProof<G> = DlogOpeningProof<G>>(
-    TestFramework<G, OpeningProof>,
-)
+pub(crate) struct TestRunner<
+    G: KimchiCurve,
+    OpeningProof: OpenProof<G> = DlogOpeningProof<G>,
+    const W: usize = COLUMNS,
+>(TestFramework<G, OpeningProof, W>)
 where
     G::BaseField: PrimeField,
     OpeningProof::SRS: Clone,
-    VerifierIndex<G, OpeningProof>: Clone;
+    VerifierIndex<G, OpeningProof, W>: Clone;
 
-impl<G: KimchiCurve, OpeningProof: OpenProof<G>> TestFramework<G, OpeningProof>
+impl<G: KimchiCurve, OpeningProof: OpenProof<G>, const W: usize> TestFramework<G, OpeningProof, W>
 where
     G::BaseField: PrimeField,
     OpeningProof::SRS: Clone,
-    VerifierIndex<G, OpeningProof>: Clone,
+    VerifierIndex<G, OpeningProof, W>: Clone,
 {
     #[must_use]
     pub(crate) fn gates(mut self, gates: Vec<CircuitGate<G::ScalarField>>) -> Self {
@@ -78,7 +83,7 @@ where
     }
 
     #[must_use]
-    pub(crate) fn witness(mut self, witness: [Vec<G::ScalarField>; COLUMNS]) -> Self {
+    pub(crate) fn witness(mut self, witness: [Vec<G::ScalarField>; W]) -> Self {
         self.witness = Some(witness);
         self
     }
@@ -129,7 +134,7 @@ where
     pub(crate) fn setup_with_custom_srs<F: FnMut(D<G::ScalarField>, usize) -> OpeningProof::SRS>(
         mut self,
         get_srs: F,
-    ) -> TestRunner<G, OpeningProof> {
+    ) -> TestRunner<G, OpeningProof, W> {
         let start = Instant::now();
 
         let lookup_tables = std::mem::take(&mut self.lookup_tables);
@@ -157,19 +162,20 @@ where
     }
 }
 
-impl<G: KimchiCurve> TestFramework<G>
+impl<G: KimchiCurve, const W: usize> TestFramework<G, DlogOpeningProof<G>, W>
 where
     G::BaseField: PrimeField,
+    VerifierIndex<G, DlogOpeningProof<G>, W>: Clone,
 {
     /// creates the indexes
     #[must_use]
-    pub(crate) fn setup(mut self) -> TestRunner<G> {
+    pub(crate) fn setup(mut self) -> TestRunner<G, DlogOpeningProof<G>, W> {
         let start = Instant::now();
 
         let lookup_tables = std::mem::take(&mut self.lookup_tables);
         let runtime_tables_setup = self.runtime_tables_setup.take();
 
-        let index = new_index_for_test_with_lookups::<G>(
+        let index = new_index_for_test_with_lookups::<G, W>(
             self.gates.take().unwrap(),
             self.public_inputs.len(),
             self.num_prev_challenges,
@@ -190,12 +196,12 @@ where
     }
 }
 
-impl<G: KimchiCurve, OpeningProof: OpenProof<G>> TestRunner<G, OpeningProof>
+impl<G: KimchiCurve, OpeningProof: OpenProof<G>, const W: usize> TestRunner<G, OpeningProof, W>
 where
     G::ScalarField: PrimeField + Clone,
     G::BaseField: PrimeField + Clone,
     OpeningProof::SRS: Clone,
-    VerifierIndex<G, OpeningProof>: Clone,
+    VerifierIndex<G, OpeningProof, W>: Clone,
 {
     #[must_use]
     pub(crate) fn runtime_tables(
@@ -213,12 +219,12 @@ where
     }
 
     #[must_use]
-    pub(crate) fn witness(mut self, witness: [Vec<G::ScalarField>; COLUMNS]) -> Self {
+    pub(crate) fn witness(mut self, witness: [Vec<G::ScalarField>; W]) -> Self {
         self.0.witness = Some(witness);
         self
     }
 
-    pub(crate) fn prover_index(&self) -> &ProverIndex<G, OpeningProof> {
+    pub(crate) fn prover_index(&self) -> &ProverIndex<G, OpeningProof, W> {
         self.0.prover_index.as_ref().unwrap()
     }
 
@@ -291,7 +297,7 @@ where
 
         // verify the proof (propagate any errors)
         let start = Instant::now();
-        verify::<G, EFqSponge, EFrSponge, OpeningProof>(
+        verify::<G, EFqSponge, EFrSponge, OpeningProof, W>(
             &group_map,
             &self.0.verifier_index.unwrap(),
             &proof,
@@ -304,7 +310,7 @@ where
     }
 }
 
-impl<G: KimchiCurve, OpeningProof> TestRunner<G, OpeningProof>
+impl<G: KimchiCurve, OpeningProof, const W: usize> TestRunner<G, OpeningProof, W>
 where
     G::ScalarField: PrimeField + Clone,
     G::BaseField: PrimeField + Clone,
@@ -315,7 +321,7 @@ where
         + serde::Serialize
         + for<'a> serde::Deserialize<'a>,
     OpeningProof::SRS: Clone,
-    VerifierIndex<G, OpeningProof>: Clone,
+    VerifierIndex<G, OpeningProof, W>: Clone,
 {
     /// Regression test: Create a proof and check that is is equal to
     /// the given serialized implementation (and that deserializes
@@ -121,3 +121,53 @@ fn test_generic_gate_kzg() {
     .prove_and_verify::<BaseSponge, ScalarSponge>()
     .unwrap();
 }
+
+#[cfg(feature = "bn254")]
+fn test_generic_gate_shplonk_with_srs_override(override_srs_size: Option<usize>) {
+    type Fp = ark_bn254::Fr;
+    type SpongeParams = PlonkSpongeConstantsKimchi;
+    type BaseSponge = DefaultFqSponge<ark_bn254::g1::Config, SpongeParams>;
+    type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+
+    // create witness
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    // create and verify proof based on the witness
+    let framework = <TestFramework<
+        _,
+        poly_commitment::kzg::ShplonkProof<ark_ec::bn::Bn<ark_bn254::Config>>,
+    > as Default>::default()
+    .gates(gates)
+    .witness(witness)
+    .public_inputs(public);
+    let framework = if let Some(srs_size) = override_srs_size {
+        framework.override_srs_size(srs_size)
+    } else {
+        framework
+    };
+    framework
+        .setup_with_custom_srs(|d1, srs_size| {
+            let srs = poly_commitment::kzg::PairingSRS::create(srs_size);
+            srs.full_srs.get_lagrange_basis(d1);
+            srs
+        })
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[cfg(feature = "bn254")]
+#[test]
+fn test_generic_gate_shplonk() {
+    test_generic_gate_shplonk_with_srs_override(None)
+}
+
+#[cfg(feature = "bn254")]
+#[test]
+fn test_generic_gate_shplonk_chunked() {
+    // The polynomials of the domain of size 32 are committed in two chunks.
+    test_generic_gate_shplonk_with_srs_override(Some(16))
+}
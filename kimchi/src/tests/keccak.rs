@@ -1,18 +1,31 @@
 use std::array;
 
+use super::framework::TestFramework;
 use crate::{
     circuits::{
         constraints::ConstraintSystem,
         gate::{CircuitGate, GateType},
-        polynomials::keccak::{constants::KECCAK_COLS, witness::extend_keccak_witness, Keccak},
+        polynomials::keccak::{
+            constants::{KECCAK_COLS, RATE_IN_BYTES},
+            witness::extend_keccak_witness,
+            Keccak,
+        },
         wires::Wire,
     },
     curve::KimchiCurve,
 };
 use ark_ff::{Field, PrimeField, Zero};
-use mina_curves::pasta::Pallas;
+use mina_curves::pasta::{Fp, Pallas, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
 use num_bigint::BigUint;
 use o1_utils::{BigUintHelpers, FieldHelpers};
+use poly_commitment::ipa::OpeningProof as DlogOpeningProof;
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
 
 fn create_test_constraint_system<G: KimchiCurve>(
     bytelength: usize,
@@ -90,12 +103,7 @@ where
     G::BaseField: PrimeField,
 {
     let bytelength = message.to_bytes_be().len();
-    let padded_len = {
-        let mut sized = message.to_bytes_be();
-        sized.resize(bytelength - sized.len(), 0);
-        Keccak::pad(&sized).len()
-    };
-    let _index = create_test_constraint_system::<G>(padded_len);
+    let _index = create_test_constraint_system::<G>(bytelength);
     let witness = create_keccak_witness::<G>(message);
 
     for r in 1..=24 {
@@ -145,3 +153,45 @@ fn test_blocks() {
         BigUint::from_hex("7e369e1a4362148fca24c67c76f14dbe24b75c73e9b0efdb8c46056c8514287e");
     assert_eq!(expected_3blocks, hash_3blocks);
 }
+
+// Creates the gates and the witness of the Keccak hash of a one block message
+fn keccak_circuit(message: BigUint) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; KECCAK_COLS]) {
+    let mut gates = vec![];
+    let next_row = CircuitGate::<Fp>::extend_keccak(&mut gates, message.to_bytes_be().len());
+    gates.push(CircuitGate {
+        typ: GateType::Zero,
+        wires: Wire::for_row(next_row),
+        coeffs: vec![],
+    });
+    let witness = create_keccak_witness::<Vesta>(message);
+    assert_eq!(gates.len(), witness[0].len());
+    (gates, witness)
+}
+
+#[test]
+// The Keccak constraints hold on an honest witness, but the prover refuses to
+// prove them as they do not constrain the digest yet
+fn test_prover_rejects_keccak_gates() {
+    let (gates, witness) = keccak_circuit(BigUint::from_bytes_be(&[0x2a; RATE_IN_BYTES - 1]));
+
+    let runner = TestFramework::<Vesta, DlogOpeningProof<Vesta>, KECCAK_COLS>::default()
+        .gates(gates)
+        .witness(witness.clone())
+        .setup();
+    runner.prover_index().verify(&witness, &[]).unwrap();
+    assert!(runner.prove::<BaseSponge, ScalarSponge>().is_err());
+}
+
+#[test]
+// A witness with a wrong state in a round row does not satisfy the constraints
+fn test_keccak_tampered_witness() {
+    let (gates, mut witness) = keccak_circuit(BigUint::from_bytes_be(&[0x2a; RATE_IN_BYTES - 1]));
+    // first quarter of the state of the first round
+    witness[0][1] += Fp::from(1u32);
+
+    let runner = TestFramework::<Vesta, DlogOpeningProof<Vesta>, KECCAK_COLS>::default()
+        .gates(gates)
+        .witness(witness.clone())
+        .setup();
+    assert!(runner.prover_index().verify(&witness, &[]).is_err());
+}
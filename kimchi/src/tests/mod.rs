@@ -5,6 +5,7 @@ mod ec;
 mod endomul;
 mod endomul_scalar;
 mod foreign_field_add;
+mod foreign_field_ec;
 mod foreign_field_mul;
 mod framework;
 mod generic;
@@ -16,5 +17,7 @@ mod range_check;
 mod recursion;
 mod rot;
 mod serde;
+mod turshi;
 mod varbasemul;
+mod witness_report;
 mod xor;
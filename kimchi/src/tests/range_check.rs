@@ -1207,7 +1207,7 @@ fn verify_range_check_valid_proof1() {
     let verifier_index = prover_index.verifier_index();
 
     // Verify proof
-    let res = verify::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+    let res = verify::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>, COLUMNS>(
         &group_map,
         &verifier_index,
         &proof,
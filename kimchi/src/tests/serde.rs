@@ -93,7 +93,7 @@ mod tests {
 
         // verify the proof
         let start = Instant::now();
-        verify::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+        verify::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>, COLUMNS>(
             &group_map,
             &verifier_index_deserialize,
             &proof,
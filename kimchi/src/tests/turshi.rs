@@ -0,0 +1,167 @@
+use super::framework::TestFramework;
+use crate::circuits::{
+    gate::CircuitGate,
+    lookup::runtime_tables::RuntimeTable,
+    polynomials::turshi::{
+        cairo_builtin_segments, cairo_memory_table_cfg,
+        witness::{cairo_circuit_witness, cairo_memory_table, cairo_public_input},
+    },
+    wires::COLUMNS,
+};
+use ark_ff::{Field, One};
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::{ipa::SRS, SRS as _};
+use turshi::{BuiltinHintProcessor, CairoCompiledProgram, CairoProgram};
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+const DOUBLE_PROGRAM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../turshi/tests/programs/double.json"
+);
+
+const BUILTINS_PROGRAM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../turshi/tests/programs/builtins.json"
+);
+
+const HASHES_PROGRAM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../turshi/tests/programs/hashes.json"
+);
+
+const HINTS_PROGRAM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../turshi/tests/programs/hints.json"
+);
+
+/// The data given to the prover and to the verifier of a Cairo program
+struct CairoProof {
+    witness: [Vec<Fp>; COLUMNS],
+    public: Vec<Fp>,
+    memory: RuntimeTable<Fp>,
+}
+
+fn prove_and_verify_cairo(
+    program: &CairoCompiledProgram<Fp>,
+    tamper: impl FnOnce(&mut CairoProof),
+) -> Result<(), String> {
+    let mut mem = program.memory().unwrap();
+    let prog = CairoProgram::new_with_hints(
+        &mut mem,
+        program.initial_pc(),
+        program.hints(),
+        &mut BuiltinHintProcessor::default(),
+    );
+
+    let public_memory = prog.public_memory();
+    let (gates, public) = CircuitGate::<Fp>::create_cairo_circuit(
+        prog.trace().len(),
+        public_memory.len(),
+        &cairo_builtin_segments(&prog),
+    );
+    let mut proof = CairoProof {
+        witness: cairo_circuit_witness(&prog),
+        public: cairo_public_input(&prog.ini(), &prog.fin(), &public_memory),
+        memory: cairo_memory_table(&prog),
+    };
+    assert_eq!(proof.public.len(), public);
+    tamper(&mut proof);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(proof.witness)
+        .public_inputs(proof.public)
+        .runtime_tables_setup(vec![cairo_memory_table_cfg(prog.mem.len() as usize - 1)])
+        .setup_with_custom_srs(|d1, srs_size| {
+            let srs = SRS::<Vesta>::create(srs_size);
+            srs.get_lagrange_basis(d1);
+            srs
+        })
+        .runtime_tables(vec![proof.memory])
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+}
+
+#[test]
+fn test_prove_and_verify_compiled_cairo_program() {
+    let program = CairoCompiledProgram::from_file(DOUBLE_PROGRAM).unwrap();
+    prove_and_verify_cairo(&program, |_| ()).unwrap();
+}
+
+#[test]
+fn test_prove_and_verify_cairo_program_with_builtins() {
+    let program = CairoCompiledProgram::from_file(BUILTINS_PROGRAM).unwrap();
+    prove_and_verify_cairo(&program, |_| ()).unwrap();
+}
+
+#[test]
+fn test_prove_and_verify_cairo_program_with_hash_builtins() {
+    let program = CairoCompiledProgram::from_file(HASHES_PROGRAM).unwrap();
+    prove_and_verify_cairo(&program, |_| ()).unwrap();
+}
+
+#[test]
+fn test_prove_and_verify_cairo_program_with_hints() {
+    let program = CairoCompiledProgram::from_file(HINTS_PROGRAM).unwrap();
+    prove_and_verify_cairo(&program, |_| ()).unwrap();
+}
+
+#[test]
+fn test_cairo_program_with_wrong_result() {
+    let program = CairoCompiledProgram::from_file(DOUBLE_PROGRAM).unwrap();
+    // The 5th instruction computes y * y, change its result. The public
+    // memory has 10 entries, looked up in 4 rows, before the Cairo gadget.
+    let first_row = 5 + 2 * 10 + 4;
+    let res = prove_and_verify_cairo(&program, |proof| {
+        proof.witness[4][first_row + 4 * 4 + 1] += Fp::one()
+    });
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_cairo_program_with_inconsistent_memory() {
+    let program = CairoCompiledProgram::from_file(DOUBLE_PROGRAM).unwrap();
+    // The cell 14 contains y * y, written by the 5th instruction
+    let res = prove_and_verify_cairo(&program, |proof| {
+        proof.memory.data[14 - 1] += Fp::one();
+    });
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_cairo_program_with_wrong_output() {
+    let program = CairoCompiledProgram::from_file(BUILTINS_PROGRAM).unwrap();
+    // The last entry of the public memory is the last output, claim another
+    // value for it in the public input and in its lookup
+    let res = prove_and_verify_cairo(&program, |proof| {
+        let last_public = proof.public.len() - 1;
+        proof.public[last_public] += Fp::one();
+        proof.witness[0][last_public] += Fp::one();
+        // 30 entries of public memory are looked up in 10 rows
+        proof.witness[6][last_public + 10] += Fp::one();
+    });
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_cairo_program_with_range_check_out_of_range() {
+    let program = CairoCompiledProgram::from_file(BUILTINS_PROGRAM).unwrap();
+    // Split the range checked value 1000 into limbs that do not have 64 bits.
+    // Its 3 rows and 2 range checks are followed by the 2 rows of the bitwise
+    // segment and the 27 rows of its instance.
+    let res = prove_and_verify_cairo(&program, |proof| {
+        let row = proof.witness[0].len() - 27 - 2 - 2 - 1;
+        let two_to_64 = Fp::from(2u32).pow([64]);
+        let limbs = [Fp::from(1000u32) - two_to_64 * two_to_64, two_to_64];
+        for (i, limb) in limbs.into_iter().enumerate() {
+            proof.witness[i][row] = limb;
+            proof.witness[0][row + 1 + i] = limb;
+        }
+    });
+    assert!(res.is_err());
+}
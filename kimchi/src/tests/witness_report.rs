@@ -0,0 +1,135 @@
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::{CircuitGate, CircuitGateError, GateType},
+    polynomial::COLUMNS,
+    polynomials::range_check,
+    wires::Wire,
+    witness_report::{FailureKind, WitnessReport},
+};
+use ark_ec::AffineRepr;
+use ark_ff::One;
+use mina_curves::pasta::{Fp, Pallas, Vesta};
+use o1_utils::FieldHelpers;
+use std::array;
+
+type PallasField = <Pallas as AffineRepr>::BaseField;
+
+fn create_test_constraint_system() -> ConstraintSystem<Fp> {
+    let (_next_row, gates) = CircuitGate::<Fp>::create_multi_range_check(0);
+    ConstraintSystem::create(gates).build().unwrap()
+}
+
+fn create_valid_witness() -> [Vec<PallasField>; COLUMNS] {
+    range_check::witness::create_multi::<PallasField>(
+        PallasField::from_hex("22f6b4e7ecb4488433ade7000000000000000000000000000000000000000000")
+            .unwrap(),
+        PallasField::from_hex("e20e9d80333f2fba463ffd000000000000000000000000000000000000000000")
+            .unwrap(),
+        PallasField::from_hex("25d28bfd6cdff91ca9bc00000000000000000000000000000000000000000000")
+            .unwrap(),
+    )
+}
+
+#[test]
+fn test_witness_report_valid_witness() {
+    let cs = create_test_constraint_system();
+    let witness = create_valid_witness();
+
+    for (row, gate) in cs.gates.iter().enumerate().take(4) {
+        assert_eq!(
+            gate.verify_witness::<Vesta>(row, &witness, &cs, &[]),
+            Ok(())
+        );
+    }
+    let report = cs.check_witness::<Vesta>(&witness, &[]);
+    assert!(report.is_ok(), "{report}");
+    assert_eq!(
+        report.to_string(),
+        "the witness satisfies all the constraints"
+    );
+}
+
+#[test]
+fn test_witness_report_all_constraints() {
+    let cs = create_test_constraint_system();
+    let witness: [Vec<PallasField>; COLUMNS] = array::from_fn(|_| vec![PallasField::from(7); 4]);
+
+    let report = cs.check_witness::<Vesta>(&witness, &[]);
+    assert!(!report.is_ok());
+
+    // The first failing constraint of each gate is the one of verify_witness
+    for (row, gate) in cs.gates.iter().enumerate().take(4) {
+        let first = report
+            .failures
+            .iter()
+            .find(|f| f.row == row && matches!(f.kind, FailureKind::Constraint(_)));
+        match gate.verify_witness::<Vesta>(row, &witness, &cs, &[]) {
+            Err(CircuitGateError::Constraint(typ, i)) => {
+                let first = first.unwrap();
+                assert_eq!(first.typ, typ);
+                assert_eq!(first.kind, FailureKind::Constraint(i));
+            }
+            Ok(()) => assert!(first.is_none()),
+            Err(err) => panic!("unexpected error: {err}"),
+        }
+    }
+
+    // All the failing constraints are reported, not only the first one
+    let row0: Vec<_> = report.failures.iter().filter(|f| f.row == 0).collect();
+    assert!(row0.len() > 1);
+    for failure in row0 {
+        assert_eq!(failure.typ, GateType::RangeCheck0);
+        assert_eq!(failure.curr, [PallasField::from(7); COLUMNS]);
+        assert_eq!(failure.next, [PallasField::from(7); COLUMNS]);
+    }
+}
+
+#[test]
+fn test_witness_report_wiring_and_gates() {
+    let cs = create_test_constraint_system();
+    let mut witness = create_valid_witness();
+
+    // Invalidate a copy constraint (row 0 col 1 <-> row 3 col 3), which also
+    // breaks the decomposition checked by the gate at row 0
+    witness[1][0] += PallasField::one();
+    // and another one (row 1 col 2 <-> row 3 col 6)
+    witness[2][1] += PallasField::one();
+
+    let report = cs.check_witness::<Vesta>(&witness, &[]);
+    let has = |row, kind| {
+        report
+            .failures
+            .iter()
+            .any(|f| f.row == row && f.kind == kind)
+    };
+
+    // both ends of the broken copy constraints are reported
+    for (src, dst) in [
+        (Wire { row: 0, col: 1 }, Wire { row: 3, col: 3 }),
+        (Wire { row: 1, col: 2 }, Wire { row: 3, col: 6 }),
+    ] {
+        assert!(has(src.row, FailureKind::CopyConstraint { src, dst }));
+        assert!(report.failures.iter().any(|f| f.row == dst.row
+            && matches!(f.kind, FailureKind::CopyConstraint { src: s, .. } if s == dst)));
+    }
+    // as well as the gates
+    assert!(report
+        .failures
+        .iter()
+        .any(|f| f.row == 0 && matches!(f.kind, FailureKind::Constraint(_))));
+    assert!(report
+        .failures
+        .iter()
+        .any(|f| f.row == 1 && matches!(f.kind, FailureKind::Constraint(_))));
+
+    // the failures are ordered by row
+    assert!(report.failures.windows(2).all(|w| w[0].row <= w[1].row));
+
+    // the report can be exported
+    let json = serde_json::to_string(&report).unwrap();
+    let deserialized: WitnessReport<PallasField> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, report);
+    assert!(report
+        .to_string()
+        .contains("row 0 (RangeCheck0): copy constraint 0,1 -> 3,3 is not satisfied"));
+}
@@ -36,18 +36,20 @@ use rand::thread_rng;
 pub type Result<T> = std::result::Result<T, VerifyError>;
 
 #[derive(Debug)]
-pub struct Context<'a, G: KimchiCurve, OpeningProof: OpenProof<G>> {
+pub struct Context<'a, G: KimchiCurve, OpeningProof: OpenProof<G>, const W: usize = COLUMNS> {
     /// The [VerifierIndex] associated to the proof
-    pub verifier_index: &'a VerifierIndex<G, OpeningProof>,
+    pub verifier_index: &'a VerifierIndex<G, OpeningProof, W>,
 
     /// The proof to verify
-    pub proof: &'a ProverProof<G, OpeningProof>,
+    pub proof: &'a ProverProof<G, OpeningProof, W>,
 
     /// The public input used in the creation of the proof
     pub public_input: &'a [G::ScalarField],
 }
 
-impl<'a, G: KimchiCurve, OpeningProof: OpenProof<G>> Context<'a, G, OpeningProof> {
+impl<'a, G: KimchiCurve, OpeningProof: OpenProof<G>, const W: usize>
+    Context<'a, G, OpeningProof, W>
+{
     pub fn get_column(&self, col: Column) -> Option<&'a PolyComm<G>> {
         use Column::*;
         match col {
@@ -80,22 +82,25 @@ impl<'a, G: KimchiCurve, OpeningProof: OpenProof<G>> Context<'a, G, OpeningProof
                     EndoMul => Some(&self.verifier_index.emul_comm),
                     EndoMulScalar => Some(&self.verifier_index.endomul_scalar_comm),
                     Poseidon => Some(&self.verifier_index.psm_comm),
-                    CairoClaim | CairoInstruction | CairoFlags | CairoTransition => None,
+                    CairoClaim => Some(self.verifier_index.cairo_claim_comm.as_ref()?),
+                    CairoInstruction => Some(self.verifier_index.cairo_instruction_comm.as_ref()?),
+                    CairoFlags => Some(self.verifier_index.cairo_flags_comm.as_ref()?),
+                    CairoTransition => Some(self.verifier_index.cairo_transition_comm.as_ref()?),
                     RangeCheck0 => Some(self.verifier_index.range_check0_comm.as_ref()?),
                     RangeCheck1 => Some(self.verifier_index.range_check1_comm.as_ref()?),
                     ForeignFieldAdd => Some(self.verifier_index.foreign_field_add_comm.as_ref()?),
                     ForeignFieldMul => Some(self.verifier_index.foreign_field_mul_comm.as_ref()?),
                     Xor16 => Some(self.verifier_index.xor_comm.as_ref()?),
                     Rot64 => Some(self.verifier_index.rot_comm.as_ref()?),
-                    KeccakRound => todo!(),
-                    KeccakSponge => todo!(),
+                    KeccakRound => Some(self.verifier_index.keccak_round_comm.as_ref()?),
+                    KeccakSponge => Some(self.verifier_index.keccak_sponge_comm.as_ref()?),
                 }
             }
         }
     }
 }
 
-impl<G: KimchiCurve, OpeningProof: OpenProof<G>> ProverProof<G, OpeningProof>
+impl<G: KimchiCurve, OpeningProof: OpenProof<G>, const W: usize> ProverProof<G, OpeningProof, W>
 where
     G::BaseField: PrimeField,
 {
@@ -113,7 +118,7 @@ where
         EFrSponge: FrSponge<G::ScalarField>,
     >(
         &self,
-        index: &VerifierIndex<G, OpeningProof>,
+        index: &VerifierIndex<G, OpeningProof, W>,
         public_comm: &PolyComm<G>,
         public_input: Option<&[G::ScalarField]>,
     ) -> Result<OraclesResult<G, EFqSponge>> {
@@ -492,8 +497,8 @@ where
                     Column::Index(GateType::EndoMulScalar),
                 ]
                 .into_iter()
-                .chain((0..COLUMNS).map(Column::Witness))
-                .chain((0..COLUMNS).map(Column::Coefficient))
+                .chain((0..W).map(Column::Witness))
+                .chain((0..W).map(Column::Coefficient))
                 .chain((0..PERMUTS - 1).map(Column::Permutation))
                 .chain(
                     index
@@ -531,6 +536,42 @@ where
                         .as_ref()
                         .map(|_| Column::Index(GateType::Rot64)),
                 )
+                .chain(
+                    index
+                        .cairo_claim_comm
+                        .as_ref()
+                        .map(|_| Column::Index(GateType::CairoClaim)),
+                )
+                .chain(
+                    index
+                        .cairo_instruction_comm
+                        .as_ref()
+                        .map(|_| Column::Index(GateType::CairoInstruction)),
+                )
+                .chain(
+                    index
+                        .cairo_flags_comm
+                        .as_ref()
+                        .map(|_| Column::Index(GateType::CairoFlags)),
+                )
+                .chain(
+                    index
+                        .cairo_transition_comm
+                        .as_ref()
+                        .map(|_| Column::Index(GateType::CairoTransition)),
+                )
+                .chain(
+                    index
+                        .keccak_round_comm
+                        .as_ref()
+                        .map(|_| Column::Index(GateType::KeccakRound)),
+                )
+                .chain(
+                    index
+                        .keccak_sponge_comm
+                        .as_ref()
+                        .map(|_| Column::Index(GateType::KeccakSponge)),
+                )
                 .chain(
                     index
                         .lookup_index
@@ -572,6 +613,11 @@ where
                                 .chain(self.evals.foreign_field_mul_lookup_selector.as_ref().map(
                                     |_| Column::LookupKindIndex(LookupPattern::ForeignFieldMul),
                                 ))
+                                .chain(
+                                    self.evals.cairo_memory_lookup_selector.as_ref().map(|_| {
+                                        Column::LookupKindIndex(LookupPattern::CairoMemory)
+                                    }),
+                                )
                         })
                         .into_iter()
                         .flatten(),
@@ -620,8 +666,8 @@ where
 /// Enforce the length of evaluations inside [`Proof`].
 /// Atm, the length of evaluations(both `zeta` and `zeta_omega`) SHOULD be 1.
 /// The length value is prone to future change.
-fn check_proof_evals_len<G, OpeningProof>(
-    proof: &ProverProof<G, OpeningProof>,
+fn check_proof_evals_len<G, OpeningProof, const W: usize>(
+    proof: &ProverProof<G, OpeningProof, W>,
     expected_size: usize,
 ) -> Result<()>
 where
@@ -646,6 +692,12 @@ where
         foreign_field_mul_selector,
         xor_selector,
         rot_selector,
+        cairo_claim_selector,
+        cairo_instruction_selector,
+        cairo_flags_selector,
+        cairo_transition_selector,
+        keccak_round_selector,
+        keccak_sponge_selector,
         lookup_aggregation,
         lookup_table,
         lookup_sorted,
@@ -655,6 +707,7 @@ where
         lookup_gate_lookup_selector,
         range_check_lookup_selector,
         foreign_field_mul_lookup_selector,
+        cairo_memory_lookup_selector,
     } = &proof.evals;
 
     let check_eval_len = |eval: &PointEvaluations<Vec<_>>, str: &'static str| -> Result<()> {
@@ -732,6 +785,24 @@ where
     if let Some(rot_selector) = rot_selector {
         check_eval_len(rot_selector, "rot selector")?
     }
+    if let Some(cairo_claim_selector) = cairo_claim_selector {
+        check_eval_len(cairo_claim_selector, "cairo claim selector")?
+    }
+    if let Some(cairo_instruction_selector) = cairo_instruction_selector {
+        check_eval_len(cairo_instruction_selector, "cairo instruction selector")?
+    }
+    if let Some(cairo_flags_selector) = cairo_flags_selector {
+        check_eval_len(cairo_flags_selector, "cairo flags selector")?
+    }
+    if let Some(cairo_transition_selector) = cairo_transition_selector {
+        check_eval_len(cairo_transition_selector, "cairo transition selector")?
+    }
+    if let Some(keccak_round_selector) = keccak_round_selector {
+        check_eval_len(keccak_round_selector, "keccak round selector")?
+    }
+    if let Some(keccak_sponge_selector) = keccak_sponge_selector {
+        check_eval_len(keccak_sponge_selector, "keccak sponge selector")?
+    }
 
     // Lookup selectors
 
@@ -756,13 +827,16 @@ where
             "foreign field mul lookup selector",
         )?
     }
+    if let Some(cairo_memory_lookup_selector) = cairo_memory_lookup_selector {
+        check_eval_len(cairo_memory_lookup_selector, "cairo memory lookup selector")?
+    }
 
     Ok(())
 }
 
-fn to_batch<'a, G, EFqSponge, EFrSponge, OpeningProof: OpenProof<G>>(
-    verifier_index: &VerifierIndex<G, OpeningProof>,
-    proof: &'a ProverProof<G, OpeningProof>,
+fn to_batch<'a, G, EFqSponge, EFrSponge, OpeningProof: OpenProof<G>, const W: usize>(
+    verifier_index: &VerifierIndex<G, OpeningProof, W>,
+    proof: &'a ProverProof<G, OpeningProof, W>,
     public_input: &'a [<G as AffineRepr>::ScalarField],
 ) -> Result<BatchEvaluationProof<'a, G, EFqSponge, OpeningProof>>
 where
@@ -781,6 +855,12 @@ where
 
     let zk_rows = verifier_index.zk_rows;
 
+    // The prover refuses to prove the Keccak gates, see
+    // [crate::circuits::polynomials::keccak]
+    if verifier_index.keccak_round_comm.is_some() || verifier_index.keccak_sponge_comm.is_some() {
+        return Err(VerifyError::UnsupportedGates("Keccak gates"));
+    }
+
     if proof.prev_challenges.len() != verifier_index.prev_challenges {
         return Err(VerifyError::IncorrectPrevChallengesLength(
             verifier_index.prev_challenges,
@@ -969,9 +1049,9 @@ where
     ]
     .into_iter()
     //~~ * witness commitments
-    .chain((0..COLUMNS).map(Column::Witness))
+    .chain((0..W).map(Column::Witness))
     //~~ * coefficient commitments
-    .chain((0..COLUMNS).map(Column::Coefficient))
+    .chain((0..W).map(Column::Coefficient))
     //~~ * sigma commitments
     .chain((0..PERMUTS - 1).map(Column::Permutation))
     //~~ * optional gate commitments
@@ -1011,6 +1091,42 @@ where
             .as_ref()
             .map(|_| Column::Index(GateType::Rot64)),
     )
+    .chain(
+        verifier_index
+            .cairo_claim_comm
+            .as_ref()
+            .map(|_| Column::Index(GateType::CairoClaim)),
+    )
+    .chain(
+        verifier_index
+            .cairo_instruction_comm
+            .as_ref()
+            .map(|_| Column::Index(GateType::CairoInstruction)),
+    )
+    .chain(
+        verifier_index
+            .cairo_flags_comm
+            .as_ref()
+            .map(|_| Column::Index(GateType::CairoFlags)),
+    )
+    .chain(
+        verifier_index
+            .cairo_transition_comm
+            .as_ref()
+            .map(|_| Column::Index(GateType::CairoTransition)),
+    )
+    .chain(
+        verifier_index
+            .keccak_round_comm
+            .as_ref()
+            .map(|_| Column::Index(GateType::KeccakRound)),
+    )
+    .chain(
+        verifier_index
+            .keccak_sponge_comm
+            .as_ref()
+            .map(|_| Column::Index(GateType::KeccakSponge)),
+    )
     //~~ * lookup commitments
     //~
     .chain(
@@ -1133,6 +1249,12 @@ where
                     .as_ref()
                     .map(|_| Column::LookupKindIndex(LookupPattern::ForeignFieldMul)),
             )
+            .chain(
+                li.lookup_selectors
+                    .cairo_memory
+                    .as_ref()
+                    .map(|_| Column::LookupKindIndex(LookupPattern::CairoMemory)),
+            )
         })
         .into_iter()
         .flatten()
@@ -1168,10 +1290,10 @@ where
 /// # Errors
 ///
 /// Will give error if `proof(s)` are not verified as valid.
-pub fn verify<G, EFqSponge, EFrSponge, OpeningProof: OpenProof<G>>(
+pub fn verify<G, EFqSponge, EFrSponge, OpeningProof: OpenProof<G>, const W: usize>(
     group_map: &G::Map,
-    verifier_index: &VerifierIndex<G, OpeningProof>,
-    proof: &ProverProof<G, OpeningProof>,
+    verifier_index: &VerifierIndex<G, OpeningProof, W>,
+    proof: &ProverProof<G, OpeningProof, W>,
     public_input: &[G::ScalarField],
 ) -> Result<()>
 where
@@ -1185,7 +1307,7 @@ where
         proof,
         public_input,
     }];
-    batch_verify::<G, EFqSponge, EFrSponge, OpeningProof>(group_map, &proofs)
+    batch_verify::<G, EFqSponge, EFrSponge, OpeningProof, W>(group_map, &proofs)
 }
 
 /// This function verifies the batch of zk-proofs
@@ -1195,9 +1317,9 @@ where
 /// # Errors
 ///
 /// Will give error if `srs` of `proof` is invalid or `verify` process fails.
-pub fn batch_verify<G, EFqSponge, EFrSponge, OpeningProof: OpenProof<G>>(
+pub fn batch_verify<G, EFqSponge, EFrSponge, OpeningProof: OpenProof<G>, const W: usize>(
     group_map: &G::Map,
-    proofs: &[Context<G, OpeningProof>],
+    proofs: &[Context<G, OpeningProof, W>],
 ) -> Result<()>
 where
     G: KimchiCurve,
@@ -1234,7 +1356,7 @@ where
         public_input,
     } in proofs
     {
-        batch.push(to_batch::<G, EFqSponge, EFrSponge, OpeningProof>(
+        batch.push(to_batch::<G, EFqSponge, EFrSponge, OpeningProof, W>(
             verifier_index,
             proof,
             public_input,
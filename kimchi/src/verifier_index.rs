@@ -56,7 +56,7 @@ pub struct LookupVerifierIndex<G: CommitmentCurve> {
 
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct VerifierIndex<G: KimchiCurve, OpeningProof: OpenProof<G>> {
+pub struct VerifierIndex<G: KimchiCurve, OpeningProof: OpenProof<G>, const W: usize = COLUMNS> {
     /// evaluation domain
     #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub domain: D<G::ScalarField>,
@@ -79,7 +79,8 @@ pub struct VerifierIndex<G: KimchiCurve, OpeningProof: OpenProof<G>> {
     pub sigma_comm: [PolyComm<G>; PERMUTS],
     /// coefficient commitment array
     #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
-    pub coefficients_comm: [PolyComm<G>; COLUMNS],
+    #[serde_as(as = "[_; W]")]
+    pub coefficients_comm: [PolyComm<G>; W],
     /// coefficient commitment array
     #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
     pub generic_comm: PolyComm<G>,
@@ -127,6 +128,30 @@ pub struct VerifierIndex<G: KimchiCurve, OpeningProof: OpenProof<G>> {
     #[serde(bound = "Option<PolyComm<G>>: Serialize + DeserializeOwned")]
     pub rot_comm: Option<PolyComm<G>>,
 
+    /// Cairo claim gate commitments
+    #[serde(bound = "Option<PolyComm<G>>: Serialize + DeserializeOwned")]
+    pub cairo_claim_comm: Option<PolyComm<G>>,
+
+    /// Cairo instruction gate commitments
+    #[serde(bound = "Option<PolyComm<G>>: Serialize + DeserializeOwned")]
+    pub cairo_instruction_comm: Option<PolyComm<G>>,
+
+    /// Cairo flags gate commitments
+    #[serde(bound = "Option<PolyComm<G>>: Serialize + DeserializeOwned")]
+    pub cairo_flags_comm: Option<PolyComm<G>>,
+
+    /// Cairo transition gate commitments
+    #[serde(bound = "Option<PolyComm<G>>: Serialize + DeserializeOwned")]
+    pub cairo_transition_comm: Option<PolyComm<G>>,
+
+    /// Keccak round gate commitments
+    #[serde(bound = "Option<PolyComm<G>>: Serialize + DeserializeOwned")]
+    pub keccak_round_comm: Option<PolyComm<G>>,
+
+    /// Keccak sponge gate commitments
+    #[serde(bound = "Option<PolyComm<G>>: Serialize + DeserializeOwned")]
+    pub keccak_sponge_comm: Option<PolyComm<G>>,
+
     /// wire coordinate shifts
     #[serde_as(as = "[o1_utils::serialization::SerdeAs; PERMUTS]")]
     pub shift: [G::ScalarField; PERMUTS],
@@ -153,7 +178,7 @@ pub struct VerifierIndex<G: KimchiCurve, OpeningProof: OpenProof<G>> {
 }
 //~spec:endcode
 
-impl<G: KimchiCurve, OpeningProof: OpenProof<G>> ProverIndex<G, OpeningProof>
+impl<G: KimchiCurve, OpeningProof: OpenProof<G>, const W: usize> ProverIndex<G, OpeningProof, W>
 where
     G::BaseField: PrimeField,
 {
@@ -162,9 +187,9 @@ where
     /// # Panics
     ///
     /// Will panic if `srs` cannot be in `cell`.
-    pub fn verifier_index(&self) -> VerifierIndex<G, OpeningProof>
+    pub fn verifier_index(&self) -> VerifierIndex<G, OpeningProof, W>
     where
-        VerifierIndex<G, OpeningProof>: Clone,
+        VerifierIndex<G, OpeningProof, W>: Clone,
     {
         if let Some(verifier_index) = &self.verifier_index {
             return verifier_index.clone();
@@ -291,6 +316,36 @@ where
                 .rot_selector8
                 .as_ref()
                 .map(|eval8| self.srs.commit_evaluations_non_hiding(domain, eval8)),
+            cairo_claim_comm: self
+                .column_evaluations
+                .cairo_claim_selector8
+                .as_ref()
+                .map(|eval8| self.srs.commit_evaluations_non_hiding(domain, eval8)),
+            cairo_instruction_comm: self
+                .column_evaluations
+                .cairo_instruction_selector8
+                .as_ref()
+                .map(|eval8| self.srs.commit_evaluations_non_hiding(domain, eval8)),
+            cairo_flags_comm: self
+                .column_evaluations
+                .cairo_flags_selector8
+                .as_ref()
+                .map(|eval8| self.srs.commit_evaluations_non_hiding(domain, eval8)),
+            cairo_transition_comm: self
+                .column_evaluations
+                .cairo_transition_selector8
+                .as_ref()
+                .map(|eval8| self.srs.commit_evaluations_non_hiding(domain, eval8)),
+            keccak_round_comm: self
+                .column_evaluations
+                .keccak_round_selector8
+                .as_ref()
+                .map(|eval8| self.srs.commit_evaluations_non_hiding(domain, eval8)),
+            keccak_sponge_comm: self
+                .column_evaluations
+                .keccak_sponge_selector8
+                .as_ref()
+                .map(|eval8| self.srs.commit_evaluations_non_hiding(domain, eval8)),
 
             shift: self.cs.shift,
             permutation_vanishing_polynomial_m: {
@@ -316,7 +371,7 @@ where
     }
 }
 
-impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof> {
+impl<G: KimchiCurve, OpeningProof: OpenProof<G>, const W: usize> VerifierIndex<G, OpeningProof, W> {
     /// Gets srs from [`VerifierIndex`] lazily
     pub fn srs(&self) -> &Arc<OpeningProof::SRS>
     where
@@ -425,6 +480,12 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
             foreign_field_mul_comm,
             xor_comm,
             rot_comm,
+            cairo_claim_comm,
+            cairo_instruction_comm,
+            cairo_flags_comm,
+            cairo_transition_comm,
+            keccak_round_comm,
+            keccak_sponge_comm,
 
             // Lookup index; optional
             lookup_index,
@@ -479,6 +540,30 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
             absorb_commitment(&mut fq_sponge, rot_comm);
         }
 
+        if let Some(cairo_claim_comm) = cairo_claim_comm {
+            absorb_commitment(&mut fq_sponge, cairo_claim_comm);
+        }
+
+        if let Some(cairo_instruction_comm) = cairo_instruction_comm {
+            absorb_commitment(&mut fq_sponge, cairo_instruction_comm);
+        }
+
+        if let Some(cairo_flags_comm) = cairo_flags_comm {
+            absorb_commitment(&mut fq_sponge, cairo_flags_comm);
+        }
+
+        if let Some(cairo_transition_comm) = cairo_transition_comm {
+            absorb_commitment(&mut fq_sponge, cairo_transition_comm);
+        }
+
+        if let Some(keccak_round_comm) = keccak_round_comm {
+            absorb_commitment(&mut fq_sponge, keccak_round_comm);
+        }
+
+        if let Some(keccak_sponge_comm) = keccak_sponge_comm {
+            absorb_commitment(&mut fq_sponge, keccak_sponge_comm);
+        }
+
         // Lookup index; optional
 
         if let Some(LookupVerifierIndex {
@@ -494,6 +579,7 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
                     lookup,
                     range_check,
                     ffmul,
+                    cairo_memory,
                 },
         }) = lookup_index
         {
@@ -519,6 +605,9 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
             if let Some(ffmul) = ffmul {
                 absorb_commitment(&mut fq_sponge, ffmul);
             }
+            if let Some(cairo_memory) = cairo_memory {
+                absorb_commitment(&mut fq_sponge, cairo_memory);
+            }
         }
         fq_sponge.digest_fq()
     }
@@ -50,6 +50,13 @@ pub struct WitnessBuilderEnv<
     /// - `runtime_tables[table_id][write_i][row_i]` is a value-vector that's looked up at `row_i`
     pub runtime_lookup_writes: BTreeMap<LT, Vec<Vec<Vec<F>>>>,
 
+    /// Entries of the runtime tables that have their own columns (see
+    /// `LookupTableID::runtime_create_column`), set once per execution
+    /// with `set_runtime_table`.
+    ///
+    /// - `runtime_tables[table_id][row_i]` is the value-vector of the entry at `row_i`
+    pub runtime_tables: BTreeMap<LT, Vec<Vec<F>>>,
+
     /// Fixed values for selector columns. `fixed_selectors[i][j]` is the
     /// value for row #j of the selector #i.
     pub fixed_selectors: Vec<Vec<F>>,
@@ -315,7 +322,7 @@ impl<
                 supposed to write only in witness columns"
                 );
             }
-            Column::LookupFixedTable(_) => {
+            Column::LookupFixedTable(_) | Column::LookupRuntimeTable(_) => {
                 panic!(
                     "This is a lookup related column. The environment is
                 supposed to write only in witness columns"
@@ -372,15 +379,16 @@ impl<
             let mut resolver: BTreeMap<Vec<F>, (usize, usize)> = BTreeMap::new();
 
             {
-                // Populate resolver map either from "reads" or from "writes"
+                // Populate resolver map either from the table or from "writes"
                 if table_id.runtime_create_column() {
-                    let columns = &self.lookup_reads.get(&table_id).unwrap();
+                    let column = self
+                        .runtime_tables
+                        .get(&table_id)
+                        .unwrap_or_else(|| panic!("Runtime table {table_id:?} has not been set"));
                     assert!(
-                        columns.len() == 1,
-                        "We only allow 1 read for runtime tables yet"
+                        column.len() <= domain_size,
+                        "Runtime table {table_id:?} is bigger than the domain size"
                     );
-                    let column = &columns[0];
-                    assert!(column.len() <= domain_size,);
                     for (row_i, value) in column.iter().enumerate() {
                         if resolver.get_mut(value).is_none() {
                             resolver.insert(value.clone(), (0, row_i));
@@ -452,6 +460,7 @@ impl<
             lookup_multiplicities,
             lookup_reads,
             runtime_lookup_writes,
+            runtime_tables: BTreeMap::new(),
             fixed_selectors,
             phantom_cix: PhantomData,
             assert_mapper: Box::new(|x| x),
@@ -468,6 +477,19 @@ impl<
         }
     }
 
+    /// Sets the entries of a runtime table that has its own columns, at
+    /// most one per row. The table is padded with its first entry, and the
+    /// multiplicities of the reads are computed when building the proof
+    /// inputs.
+    pub fn set_runtime_table(&mut self, table_id: LT, entries: Vec<Vec<F>>) {
+        assert!(
+            !table_id.is_fixed() && table_id.runtime_create_column(),
+            "set_runtime_table must be called on runtime tables with their own columns only"
+        );
+        assert!(!entries.is_empty(), "Runtime tables cannot be empty");
+        self.runtime_tables.insert(table_id, entries);
+    }
+
     /// Sets all fixed selectors directly. Each item in `selectors` is
     /// a vector of `domain_size` length.
     pub fn set_fixed_selectors(&mut self, selectors: Vec<Vec<F>>) {
@@ -522,13 +544,23 @@ impl<
             .filter(|table_id| !table_id.is_fixed())
         {
             if table_id.runtime_create_column() {
-                // For runtime tables with no explicit writes, we
-                // store only read requests, so we assemble read
-                // requests into a column.
-                runtime_tables.insert(table_id, self.lookup_reads.get(&table_id).unwrap().clone());
+                // For runtime tables with no explicit writes, the
+                // entries have been set for the whole execution.
+                match self.runtime_tables.get(&table_id) {
+                    Some(entries) => {
+                        runtime_tables.insert(table_id, vec![entries.clone()]);
+                    }
+                    None => {
+                        assert!(
+                            self.lookup_reads[&table_id].is_empty(),
+                            "Runtime table {table_id:?} is read from but has not been set"
+                        );
+                        continue;
+                    }
+                }
             } else {
                 // For runtime tables /with/ explicit writes, these
-                // writes are stored in self.runtime_tables.
+                // writes are stored in self.runtime_lookup_writes.
                 runtime_tables.insert(
                     table_id,
                     self.runtime_lookup_writes.get(&table_id).unwrap().clone(),
@@ -592,6 +624,14 @@ impl<
 
         // Counting multiplicities & adding fixed column into the last column of every table.
         for (table_id, table) in lookup_tables.iter_mut() {
+            // Unused runtime tables have no data, and are not part of the proof.
+            if !table_id.is_fixed() && !lookup_tables_data.contains_key(table_id) {
+                continue;
+            }
+            // Neither are the fixed tables that are never read.
+            if table_id.is_fixed() && self.lookup_reads[table_id].is_empty() {
+                continue;
+            }
             let lookup_m: Vec<Vec<F>> = self.get_lookup_multiplicities(domain_size, *table_id);
             lookup_multiplicities.insert(*table_id, lookup_m.clone());
 
@@ -111,6 +111,13 @@ impl<
                     panic!("No lookup provided")
                 }
             }
+            Self::Column::LookupRuntimeTable((table_id, i)) => {
+                if let Some(ref lookup) = self.lookup {
+                    Some(&lookup.runtime_tables_evals_d8[&ID::from_u32(table_id)][i])
+                } else {
+                    panic!("No lookup provided")
+                }
+            }
         }
     }
 
@@ -148,6 +155,7 @@ impl<
             }
             Self::Column::LookupAggregation
             | Self::Column::LookupFixedTable(_)
+            | Self::Column::LookupRuntimeTable(_)
             | Self::Column::LookupMultiplicity(_)
             | Self::Column::LookupPartialSum(_) => {
                 // When there is a lookup, we do suppose the domain is always D8
@@ -23,6 +23,9 @@ pub enum Column {
     LookupAggregation,
     /// The fixed tables. The parameter is considered to the indexed table.
     LookupFixedTable(u32),
+    /// The runtime tables, whose values are committed by the prover. First
+    /// indexed by table ID, then by the position in the value-vector.
+    LookupRuntimeTable((u32, usize)),
 }
 
 impl Column {
@@ -44,6 +47,7 @@ impl FormattedOutput for Column {
             Column::LookupPartialSum((table_id, i)) => format!("h_{{{table_id}, {i}}}"),
             Column::LookupMultiplicity((table_id, i)) => format!("m_{{{table_id}, {i}}}"),
             Column::LookupFixedTable(i) => format!("t_{{{i}}}"),
+            Column::LookupRuntimeTable((table_id, i)) => format!("rt_{{{table_id}, {i}}}"),
             Column::LookupAggregation => String::from("φ"),
         }
     }
@@ -56,6 +60,7 @@ impl FormattedOutput for Column {
             Column::LookupPartialSum((table_id, i)) => format!("h[{table_id}, {i}]"),
             Column::LookupMultiplicity((table_id, i)) => format!("m[{table_id}, {i}]"),
             Column::LookupFixedTable(i) => format!("t[{i}]"),
+            Column::LookupRuntimeTable((table_id, i)) => format!("rt[{table_id}, {i}]"),
             Column::LookupAggregation => String::from("φ"),
         }
     }
@@ -93,6 +98,7 @@ impl FoldingColumnTrait for Column {
             Column::LookupPartialSum(_) => true,
             Column::LookupMultiplicity(_) => true,
             Column::LookupAggregation => true,
+            Column::LookupRuntimeTable(_) => true,
             // Not witness/public values
             Column::FixedSelector(_) => false,
             Column::LookupFixedTable(_) => false,
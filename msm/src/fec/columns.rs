@@ -1,4 +1,5 @@
 use crate::{
+    circuit_design::composition::MPrism,
     columns::{Column, ColumnIndexer},
     serialization::interpreter::{N_LIMBS_LARGE, N_LIMBS_SMALL},
 };
@@ -158,3 +159,351 @@ impl ColumnIndexer for FECColumn {
         }
     }
 }
+
+/// Number of columns in the FEC doubling circuit.
+pub const FEC_DOUBLE_N_COLUMNS: usize =
+    FECDoubleColumnInput::N_COL + FECColumnOutput::N_COL + FECDoubleColumnInter::N_COL;
+
+/// FEC DOUBLE inputs: one point = two coordinates, and each in 4
+/// "large format" limbs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FECDoubleColumnInput {
+    XP(usize), // 4
+    YP(usize), // 4
+}
+
+/// FEC DOUBLE intermediate (work) columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FECDoubleColumnInter {
+    X2(usize),     // 17
+    S(usize),      // 17
+    Q0(usize),     // 17
+    Q1(usize),     // 17
+    Q2(usize),     // 17
+    Q3(usize),     // 17
+    Q1Sign,        // 1
+    Q2Sign,        // 1
+    Q3Sign,        // 1
+    Q1L(usize),    // 4
+    Q2L(usize),    // 4
+    Q3L(usize),    // 4
+    Carry0(usize), // 36
+    Carry1(usize), // 36
+    Carry2(usize), // 36
+    Carry3(usize), // 36
+}
+
+/// Columns used by the FEC Doubling subcircuit. The output has the
+/// same format as the one of the FEC Addition subcircuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FECDoubleColumn {
+    Input(FECDoubleColumnInput),
+    Output(FECColumnOutput),
+    Inter(FECDoubleColumnInter),
+}
+
+impl ColumnIndexer for FECDoubleColumnInput {
+    const N_COL: usize = 2 * N_LIMBS_LARGE;
+    fn to_column(self) -> Column {
+        match self {
+            FECDoubleColumnInput::XP(i) => {
+                assert!(i < N_LIMBS_LARGE);
+                Column::Relation(i)
+            }
+            FECDoubleColumnInput::YP(i) => {
+                assert!(i < N_LIMBS_LARGE);
+                Column::Relation(N_LIMBS_LARGE + i)
+            }
+        }
+    }
+}
+
+impl ColumnIndexer for FECDoubleColumnInter {
+    const N_COL: usize = 3 * N_LIMBS_LARGE + 14 * N_LIMBS_SMALL + 11;
+    fn to_column(self) -> Column {
+        let small = |offset: usize, i: usize| {
+            assert!(i < N_LIMBS_SMALL);
+            Column::Relation(offset * N_LIMBS_SMALL + i)
+        };
+        let large = |offset: usize, i: usize| {
+            assert!(i < N_LIMBS_LARGE);
+            Column::Relation(6 * N_LIMBS_SMALL + 3 + offset * N_LIMBS_LARGE + i)
+        };
+        let carry = |offset: usize, i: usize| {
+            assert!(i < 2 * N_LIMBS_SMALL + 2);
+            Column::Relation(
+                3 * N_LIMBS_LARGE + 6 * N_LIMBS_SMALL + 3 + offset * (2 * N_LIMBS_SMALL + 2) + i,
+            )
+        };
+        match self {
+            FECDoubleColumnInter::X2(i) => small(0, i),
+            FECDoubleColumnInter::S(i) => small(1, i),
+            FECDoubleColumnInter::Q0(i) => small(2, i),
+            FECDoubleColumnInter::Q1(i) => small(3, i),
+            FECDoubleColumnInter::Q2(i) => small(4, i),
+            FECDoubleColumnInter::Q3(i) => small(5, i),
+            FECDoubleColumnInter::Q1Sign => Column::Relation(6 * N_LIMBS_SMALL),
+            FECDoubleColumnInter::Q2Sign => Column::Relation(6 * N_LIMBS_SMALL + 1),
+            FECDoubleColumnInter::Q3Sign => Column::Relation(6 * N_LIMBS_SMALL + 2),
+            FECDoubleColumnInter::Q1L(i) => large(0, i),
+            FECDoubleColumnInter::Q2L(i) => large(1, i),
+            FECDoubleColumnInter::Q3L(i) => large(2, i),
+            FECDoubleColumnInter::Carry0(i) => carry(0, i),
+            FECDoubleColumnInter::Carry1(i) => carry(1, i),
+            FECDoubleColumnInter::Carry2(i) => carry(2, i),
+            FECDoubleColumnInter::Carry3(i) => carry(3, i),
+        }
+    }
+}
+
+impl ColumnIndexer for FECDoubleColumn {
+    const N_COL: usize = FEC_DOUBLE_N_COLUMNS;
+    fn to_column(self) -> Column {
+        match self {
+            FECDoubleColumn::Input(input) => input.to_column(),
+            FECDoubleColumn::Inter(inter) => inter
+                .to_column()
+                .add_rel_offset(FECDoubleColumnInput::N_COL),
+            FECDoubleColumn::Output(output) => output
+                .to_column()
+                .add_rel_offset(FECDoubleColumnInput::N_COL + FECDoubleColumnInter::N_COL),
+        }
+    }
+}
+
+/// Number of bits of the scalar processed by each row of the FEC
+/// scalar multiplication circuit.
+pub const FEC_SCALAR_MUL_WINDOW_SIZE: usize = 2;
+
+/// Number of points in the table of multiples of the FEC scalar
+/// multiplication circuit, that is `[P, 2P, 3P, 4P]`.
+pub const FEC_SCALAR_MUL_TABLE_SIZE: usize = 1 << FEC_SCALAR_MUL_WINDOW_SIZE;
+
+/// Number of relation columns of one doubling block of the FEC scalar
+/// multiplication circuit: the doubling gadget without its input, and
+/// the output repacked into large limbs.
+const FEC_SCALAR_MUL_DOUBLE_BLOCK: usize =
+    FECDoubleColumnInter::N_COL + FECColumnOutput::N_COL + 2 * N_LIMBS_LARGE;
+
+/// Offset of the first doubling block of the FEC scalar multiplication circuit.
+const FEC_SCALAR_MUL_DOUBLE_OFFSET: usize =
+    (FEC_SCALAR_MUL_TABLE_SIZE + 3) * 2 * N_LIMBS_LARGE + FEC_SCALAR_MUL_WINDOW_SIZE;
+
+/// Offset of the addition block of the FEC scalar multiplication circuit.
+const FEC_SCALAR_MUL_ADD_OFFSET: usize =
+    FEC_SCALAR_MUL_DOUBLE_OFFSET + FEC_SCALAR_MUL_WINDOW_SIZE * FEC_SCALAR_MUL_DOUBLE_BLOCK;
+
+/// Offset of the bus columns of the FEC scalar multiplication circuit.
+const FEC_SCALAR_MUL_BUS_OFFSET: usize =
+    FEC_SCALAR_MUL_ADD_OFFSET + FECColumnInter::N_COL + FECColumnOutput::N_COL + 2 * N_LIMBS_LARGE;
+
+/// Number of values of an entry of the bus of the FEC scalar
+/// multiplication circuit: the row, the accumulator and the table.
+pub const FEC_SCALAR_MUL_BUS_ENTRY_SIZE: usize =
+    1 + (FEC_SCALAR_MUL_TABLE_SIZE + 1) * 2 * N_LIMBS_LARGE;
+
+/// Number of fixed selectors of the FEC scalar multiplication circuit.
+pub const FEC_SCALAR_MUL_N_FSEL: usize = 2;
+
+/// Number of relation columns of the FEC scalar multiplication circuit.
+pub const FEC_SCALAR_MUL_N_REL: usize =
+    FEC_SCALAR_MUL_BUS_OFFSET + FEC_SCALAR_MUL_BUS_ENTRY_SIZE + 2 * N_LIMBS_LARGE;
+
+/// Total number of columns of the FEC scalar multiplication circuit,
+/// including fixed selectors.
+pub const FEC_SCALAR_MUL_N_COLUMNS: usize = FEC_SCALAR_MUL_N_REL + FEC_SCALAR_MUL_N_FSEL;
+
+/// Columns used by the FEC scalar multiplication circuit. Every row
+/// computes `4 Acc + T[d]` with two doublings and one addition, where
+/// `T = [P, 2P, 3P, 4P]` and `d` is a window digit. All the points in
+/// large limbs are stored as x limbs followed by y limbs.
+///
+/// The doubling and addition gadgets are embedded with
+/// [`FECScalarMulDoubleLens`] and [`FECScalarMulAddLens`], which map
+/// their inputs to the columns of the previous blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FECScalarMulColumn {
+    /// A fixed selector column that gives one the current row, starting with 1.
+    CurrentRow,
+    /// A fixed selector that is 1 on the setup row of each scalar
+    /// multiplication, and 0 on the window rows.
+    SetupSel,
+    /// Accumulator read from the previous row, in large limbs.
+    Acc(usize), // 2*4
+    /// Table of multiples `[P, 2P, 3P, 4P]` in large limbs.
+    Table(usize, usize), // 4 * 2*4
+    /// Bits of the window digit `d - 1`.
+    DigitBit(usize), // 2
+    /// The point `T[d]` selected from the table, in large limbs.
+    Addend(usize), // 2*4
+    /// The first input of the addition, in large limbs.
+    AddInput(usize), // 2*4
+    /// Work columns of the j-th doubling.
+    DoubleInter(usize, FECDoubleColumnInter),
+    /// Output of the j-th doubling.
+    DoubleOutput(usize, FECColumnOutput),
+    /// Output of the j-th doubling, repacked into large limbs.
+    DoubleRepacked(usize, usize), // 2*4
+    /// Work columns of the addition.
+    AddInter(FECColumnInter),
+    /// Output of the addition.
+    AddOutput(FECColumnOutput),
+    /// Output of the addition, repacked into large limbs.
+    AddRepacked(usize), // 2*4
+    /// Entry read from the bus, which is zero on the setup row. The
+    /// lookups only support values of degree one, so the entry is
+    /// stored instead of being masked by the setup selector.
+    BusInput(usize), // 1 + 5 * 2*4
+    /// Accumulator written to the bus, in large limbs.
+    BusOutput(usize), // 2*4
+}
+
+impl ColumnIndexer for FECScalarMulColumn {
+    const N_COL: usize = FEC_SCALAR_MUL_N_COLUMNS;
+    fn to_column(self) -> Column {
+        let point = |offset: usize, i: usize| {
+            assert!(i < 2 * N_LIMBS_LARGE);
+            Column::Relation(offset + i)
+        };
+        let double_offset = |j: usize| {
+            assert!(j < FEC_SCALAR_MUL_WINDOW_SIZE);
+            FEC_SCALAR_MUL_DOUBLE_OFFSET + j * FEC_SCALAR_MUL_DOUBLE_BLOCK
+        };
+        match self {
+            Self::CurrentRow => Column::FixedSelector(0),
+            Self::SetupSel => Column::FixedSelector(1),
+            Self::Acc(i) => point(0, i),
+            Self::Table(j, i) => {
+                assert!(j < FEC_SCALAR_MUL_TABLE_SIZE);
+                point((j + 1) * 2 * N_LIMBS_LARGE, i)
+            }
+            Self::DigitBit(i) => {
+                assert!(i < FEC_SCALAR_MUL_WINDOW_SIZE);
+                Column::Relation((FEC_SCALAR_MUL_TABLE_SIZE + 1) * 2 * N_LIMBS_LARGE + i)
+            }
+            Self::Addend(i) => point(
+                (FEC_SCALAR_MUL_TABLE_SIZE + 1) * 2 * N_LIMBS_LARGE + FEC_SCALAR_MUL_WINDOW_SIZE,
+                i,
+            ),
+            Self::AddInput(i) => point(
+                (FEC_SCALAR_MUL_TABLE_SIZE + 2) * 2 * N_LIMBS_LARGE + FEC_SCALAR_MUL_WINDOW_SIZE,
+                i,
+            ),
+            Self::DoubleInter(j, inter) => inter.to_column().add_rel_offset(double_offset(j)),
+            Self::DoubleOutput(j, output) => output
+                .to_column()
+                .add_rel_offset(double_offset(j) + FECDoubleColumnInter::N_COL),
+            Self::DoubleRepacked(j, i) => point(
+                double_offset(j) + FECDoubleColumnInter::N_COL + FECColumnOutput::N_COL,
+                i,
+            ),
+            Self::AddInter(inter) => inter.to_column().add_rel_offset(FEC_SCALAR_MUL_ADD_OFFSET),
+            Self::AddOutput(output) => output
+                .to_column()
+                .add_rel_offset(FEC_SCALAR_MUL_ADD_OFFSET + FECColumnInter::N_COL),
+            Self::AddRepacked(i) => point(
+                FEC_SCALAR_MUL_ADD_OFFSET + FECColumnInter::N_COL + FECColumnOutput::N_COL,
+                i,
+            ),
+            Self::BusInput(i) => {
+                assert!(i < FEC_SCALAR_MUL_BUS_ENTRY_SIZE);
+                Column::Relation(FEC_SCALAR_MUL_BUS_OFFSET + i)
+            }
+            Self::BusOutput(i) => {
+                point(FEC_SCALAR_MUL_BUS_OFFSET + FEC_SCALAR_MUL_BUS_ENTRY_SIZE, i)
+            }
+        }
+    }
+}
+
+/// Lens used to embed the j-th doubling of the FEC scalar
+/// multiplication circuit. The input of the first doubling is the
+/// accumulator, and the input of the next ones is the repacked output
+/// of the previous doubling.
+pub struct FECScalarMulDoubleLens(pub usize);
+
+impl MPrism for FECScalarMulDoubleLens {
+    type Source = FECScalarMulColumn;
+    type Target = FECDoubleColumn;
+
+    fn traverse(&self, source: Self::Source) -> Option<Self::Target> {
+        let input = |i: usize| {
+            if i < N_LIMBS_LARGE {
+                Some(FECDoubleColumn::Input(FECDoubleColumnInput::XP(i)))
+            } else {
+                Some(FECDoubleColumn::Input(FECDoubleColumnInput::YP(
+                    i - N_LIMBS_LARGE,
+                )))
+            }
+        };
+        match source {
+            FECScalarMulColumn::Acc(i) if self.0 == 0 => input(i),
+            FECScalarMulColumn::DoubleRepacked(j, i) if j + 1 == self.0 => input(i),
+            FECScalarMulColumn::DoubleInter(j, inter) if j == self.0 => {
+                Some(FECDoubleColumn::Inter(inter))
+            }
+            FECScalarMulColumn::DoubleOutput(j, output) if j == self.0 => {
+                Some(FECDoubleColumn::Output(output))
+            }
+            _ => None,
+        }
+    }
+
+    fn re_get(&self, target: Self::Target) -> Self::Source {
+        let input = |i: usize| {
+            if self.0 == 0 {
+                FECScalarMulColumn::Acc(i)
+            } else {
+                FECScalarMulColumn::DoubleRepacked(self.0 - 1, i)
+            }
+        };
+        match target {
+            FECDoubleColumn::Input(FECDoubleColumnInput::XP(i)) => input(i),
+            FECDoubleColumn::Input(FECDoubleColumnInput::YP(i)) => input(N_LIMBS_LARGE + i),
+            FECDoubleColumn::Inter(inter) => FECScalarMulColumn::DoubleInter(self.0, inter),
+            FECDoubleColumn::Output(output) => FECScalarMulColumn::DoubleOutput(self.0, output),
+        }
+    }
+}
+
+/// Lens used to embed the addition of the FEC scalar multiplication
+/// circuit, which adds the addend `T[d]` to the addition input.
+pub struct FECScalarMulAddLens {}
+
+impl MPrism for FECScalarMulAddLens {
+    type Source = FECScalarMulColumn;
+    type Target = FECColumn;
+
+    fn traverse(&self, source: Self::Source) -> Option<Self::Target> {
+        match source {
+            FECScalarMulColumn::AddInput(i) => Some(FECColumn::Input(if i < N_LIMBS_LARGE {
+                FECColumnInput::XP(i)
+            } else {
+                FECColumnInput::YP(i - N_LIMBS_LARGE)
+            })),
+            FECScalarMulColumn::Addend(i) => Some(FECColumn::Input(if i < N_LIMBS_LARGE {
+                FECColumnInput::XQ(i)
+            } else {
+                FECColumnInput::YQ(i - N_LIMBS_LARGE)
+            })),
+            FECScalarMulColumn::AddInter(inter) => Some(FECColumn::Inter(inter)),
+            FECScalarMulColumn::AddOutput(output) => Some(FECColumn::Output(output)),
+            _ => None,
+        }
+    }
+
+    fn re_get(&self, target: Self::Target) -> Self::Source {
+        match target {
+            FECColumn::Input(FECColumnInput::XP(i)) => FECScalarMulColumn::AddInput(i),
+            FECColumn::Input(FECColumnInput::YP(i)) => {
+                FECScalarMulColumn::AddInput(N_LIMBS_LARGE + i)
+            }
+            FECColumn::Input(FECColumnInput::XQ(i)) => FECScalarMulColumn::Addend(i),
+            FECColumn::Input(FECColumnInput::YQ(i)) => {
+                FECScalarMulColumn::Addend(N_LIMBS_LARGE + i)
+            }
+            FECColumn::Inter(inter) => FECScalarMulColumn::AddInter(inter),
+            FECColumn::Output(output) => FECScalarMulColumn::AddOutput(output),
+        }
+    }
+}
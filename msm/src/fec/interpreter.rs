@@ -1,10 +1,16 @@
 use crate::{
     circuit_design::{
         capabilities::{read_column_array, write_column_array_const, write_column_const},
-        ColAccessCap, ColWriteCap, LookupCap,
+        composition::SubEnvColumn,
+        ColAccessCap, ColWriteCap, LookupCap, MultiRowReadCap,
     },
     fec::{
-        columns::{FECColumn, FECColumnInput, FECColumnInter, FECColumnOutput},
+        columns::{
+            FECColumn, FECColumnInput, FECColumnInter, FECColumnOutput, FECDoubleColumn,
+            FECDoubleColumnInput, FECDoubleColumnInter, FECScalarMulAddLens, FECScalarMulColumn,
+            FECScalarMulDoubleLens, FEC_SCALAR_MUL_BUS_ENTRY_SIZE, FEC_SCALAR_MUL_N_FSEL,
+            FEC_SCALAR_MUL_TABLE_SIZE, FEC_SCALAR_MUL_WINDOW_SIZE,
+        },
         lookups::LookupTable,
     },
     serialization::interpreter::{
@@ -28,6 +34,57 @@ pub fn limbs_to_bigints<F: PrimeField, const N: usize>(input: [F; N]) -> Vec<Big
         .collect::<Vec<_>>()
 }
 
+/// Computes the carries of a limb equation given the values `res_i`
+/// of each of its `2L-1` limb equations before carries are added,
+/// that is the carries `c_i` such that
+///
+///   res_i - (c_i * 2^B if i < 2L-2 else 0) + (c_{i-1} if i > 0 else 0) = 0
+///
+/// Returns the carries decomposed into the `2*S+2` signed small limbs
+/// of the carry columns, as described in `constrain_ec_addition`.
+pub fn compute_carry_limbs_small<F: PrimeField>(
+    res: [F; 2 * N_LIMBS_LARGE - 1],
+) -> [F; 2 * N_LIMBS_SMALL + 2] {
+    // Native field modulus (prime)
+    let n_bui: BigUint = TryFrom::try_from(F::MODULUS).unwrap();
+    let n_bi: BigInt = n_bui.to_bigint().unwrap();
+    let n_half_bi = &n_bi / BigInt::from(2u64);
+    let large_limb_size_bi: BigInt = BigInt::from(1u128 << LIMB_BITSIZE_LARGE);
+
+    let mut carry_limbs_small = [F::zero(); 2 * N_LIMBS_SMALL + 2];
+    let mut carry: F = F::zero();
+    for (i, res_i) in res.into_iter().enumerate() {
+        let mut res_bi = (res_i + carry).to_bigint_positive();
+        if res_bi > n_half_bi {
+            res_bi -= &n_bi;
+        }
+        let (div, rem) = res_bi.div_rem(&large_limb_size_bi);
+        assert!(
+            rem.is_zero(),
+            "Cannot compute carry for step {i:?}: div {div:?}, rem {rem:?}"
+        );
+        if i < 2 * N_LIMBS_LARGE - 2 {
+            // Large carries are at most 79 bits, and are split into
+            // signed 14-bit limbs, the last chunk being signed 9 bits.
+            let (carry_abs_bi, carry_sign): (BigInt, F) = if div.is_negative() {
+                (-div.clone(), -F::one())
+            } else {
+                (div.clone(), F::one())
+            };
+            let carry_limbs: [F; 6] = limb_decompose_biguint::<F, { LIMB_BITSIZE_SMALL - 1 }, 6>(
+                carry_abs_bi.to_biguint().unwrap(),
+            );
+            for (j, limb) in carry_limbs.iter().enumerate() {
+                carry_limbs_small[6 * i + j] = carry_sign * limb;
+            }
+            carry = F::from_biguint(&bigint_to_biguint_f(div, &n_bi)).unwrap();
+        } else {
+            assert!(div.is_zero(), "Last carry is non-zero");
+        }
+    }
+    carry_limbs_small
+}
+
 /// When P = (xP,yP) and Q = (xQ,yQ) are not negative of each other, thus function ensures
 ///
 /// P + Q = R where
@@ -697,3 +754,846 @@ pub fn ec_add_circuit<
 
     (xr, yr)
 }
+
+/// When P = (xP,yP) is a point of a curve y^2 = x^3 + b (that is,
+/// with a = 0) with yP != 0, this function ensures
+///
+/// 2P = R where
+///
+/// s = 3 xP^2 / (2 yP)
+///
+/// xR = s^2 - 2 xP and yR = -yP + s(xP - xR)
+///
+///
+/// Equations that we check:
+///   0. xP^2 - x2 - q_0 f = 0
+///   1. 2 yP s - 3 x2 - q_1 f = 0
+///   2. xR - s^2 + 2 xP - q_2 f = 0
+///   3. yR + yP - s (xP - xR) - q_3 f = 0
+///
+/// The limb equations, formats and carries are the same as for
+/// `constrain_ec_addition`, to which we refer for the details, with
+/// two differences.
+///
+/// First, the slope equation is split into two, with the auxiliary
+/// x2 = xP^2 mod f. Checking 2 yP s - 3 xP^2 - q_1 f = 0 directly
+/// would require |q_1| < 3f, which does not always fit into 17 small
+/// limbs, and larger carries. With x2, we have 0 <= q_0 < f (so q_0
+/// does not need a sign) and -3 < q_1 < 2f. The carries of Eq1 are at
+/// most 1.5 times the ones of the addition, that is 79 bits, so they
+/// still fit into the 2*S+2 carry format.
+///
+/// Second, the foreign field modulus is a constant and not a column.
+///
+/// As for the addition, the input is trusted and not range checked,
+/// while x2, s and the output are range checked to be in the field.
+#[allow(clippy::needless_range_loop)]
+pub fn constrain_ec_doubling<
+    F: PrimeField,
+    Ff: PrimeField,
+    Env: ColAccessCap<F, FECDoubleColumn> + LookupCap<F, FECDoubleColumn, LookupTable<Ff>>,
+>(
+    env: &mut Env,
+) {
+    use FECDoubleColumnInter as Inter;
+
+    let xp_limbs_large: [_; N_LIMBS_LARGE] =
+        read_column_array(env, |i| FECDoubleColumn::Input(FECDoubleColumnInput::XP(i)));
+    let yp_limbs_large: [_; N_LIMBS_LARGE] =
+        read_column_array(env, |i| FECDoubleColumn::Input(FECDoubleColumnInput::YP(i)));
+    let xr_limbs_small: [_; N_LIMBS_SMALL] =
+        read_column_array(env, |i| FECDoubleColumn::Output(FECColumnOutput::XR(i)));
+    let yr_limbs_small: [_; N_LIMBS_SMALL] =
+        read_column_array(env, |i| FECDoubleColumn::Output(FECColumnOutput::YR(i)));
+    let x2_limbs_small: [_; N_LIMBS_SMALL] =
+        read_column_array(env, |i| FECDoubleColumn::Inter(Inter::X2(i)));
+    let s_limbs_small: [_; N_LIMBS_SMALL] =
+        read_column_array(env, |i| FECDoubleColumn::Inter(Inter::S(i)));
+
+    let q0_limbs_small: [_; N_LIMBS_SMALL] =
+        read_column_array(env, |i| FECDoubleColumn::Inter(Inter::Q0(i)));
+    let q1_limbs_small: [_; N_LIMBS_SMALL] =
+        read_column_array(env, |i| FECDoubleColumn::Inter(Inter::Q1(i)));
+    let q2_limbs_small: [_; N_LIMBS_SMALL] =
+        read_column_array(env, |i| FECDoubleColumn::Inter(Inter::Q2(i)));
+    let q3_limbs_small: [_; N_LIMBS_SMALL] =
+        read_column_array(env, |i| FECDoubleColumn::Inter(Inter::Q3(i)));
+    let q1_limbs_large: [_; N_LIMBS_LARGE] =
+        read_column_array(env, |i| FECDoubleColumn::Inter(Inter::Q1L(i)));
+    let q2_limbs_large: [_; N_LIMBS_LARGE] =
+        read_column_array(env, |i| FECDoubleColumn::Inter(Inter::Q2L(i)));
+    let q3_limbs_large: [_; N_LIMBS_LARGE] =
+        read_column_array(env, |i| FECDoubleColumn::Inter(Inter::Q3L(i)));
+
+    let q1_sign = env.read_column(FECDoubleColumn::Inter(Inter::Q1Sign));
+    let q2_sign = env.read_column(FECDoubleColumn::Inter(Inter::Q2Sign));
+    let q3_sign = env.read_column(FECDoubleColumn::Inter(Inter::Q3Sign));
+
+    let carry0_limbs_small: [_; 2 * N_LIMBS_SMALL + 2] =
+        read_column_array(env, |i| FECDoubleColumn::Inter(Inter::Carry0(i)));
+    let carry1_limbs_small: [_; 2 * N_LIMBS_SMALL + 2] =
+        read_column_array(env, |i| FECDoubleColumn::Inter(Inter::Carry1(i)));
+    let carry2_limbs_small: [_; 2 * N_LIMBS_SMALL + 2] =
+        read_column_array(env, |i| FECDoubleColumn::Inter(Inter::Carry2(i)));
+    let carry3_limbs_small: [_; 2 * N_LIMBS_SMALL + 2] =
+        read_column_array(env, |i| FECDoubleColumn::Inter(Inter::Carry3(i)));
+
+    // u128 covers our limb sizes shifts which is good
+    let constant_u128 = |x: u128| -> Env::Variable { Env::constant(From::from(x)) };
+
+    // Auxiliary square, slope and result variables must be in the field.
+    for (i, x) in x2_limbs_small
+        .iter()
+        .chain(s_limbs_small.iter())
+        .chain(xr_limbs_small.iter())
+        .chain(yr_limbs_small.iter())
+        .enumerate()
+    {
+        if i % N_LIMBS_SMALL == N_LIMBS_SMALL - 1 {
+            env.lookup(
+                LookupTable::RangeCheckFfHighest(PhantomData),
+                vec![x.clone()],
+            );
+        } else {
+            env.lookup(LookupTable::RangeCheck15, vec![x.clone()]);
+        }
+    }
+
+    // Quotient limbs must fit into 15 bits, but we don't care if they're in the field.
+    for x in q0_limbs_small
+        .iter()
+        .chain(q1_limbs_small.iter())
+        .chain(q2_limbs_small.iter())
+        .chain(q3_limbs_small.iter())
+    {
+        env.lookup(LookupTable::RangeCheck15, vec![x.clone()]);
+    }
+
+    // Signs must be -1 or 1.
+    for x in [&q1_sign, &q2_sign, &q3_sign] {
+        env.assert_zero(x.clone() * x.clone() - Env::constant(F::one()));
+    }
+
+    // Carry limbs need to be in particular ranges.
+    for (i, x) in carry0_limbs_small
+        .iter()
+        .chain(carry1_limbs_small.iter())
+        .chain(carry2_limbs_small.iter())
+        .chain(carry3_limbs_small.iter())
+        .enumerate()
+    {
+        if i % 6 == 5 {
+            env.lookup(LookupTable::RangeCheck9Abs, vec![x.clone()]);
+        } else {
+            env.lookup(LookupTable::RangeCheck14Abs, vec![x.clone()]);
+        }
+    }
+
+    // Make sure qi_limbs_large are properly constructed from qi_limbs_small and qi_sign
+    for (q_limbs_small, q_limbs_large, q_sign) in [
+        (&q1_limbs_small, &q1_limbs_large, &q1_sign),
+        (&q2_limbs_small, &q2_limbs_large, &q2_sign),
+        (&q3_limbs_small, &q3_limbs_large, &q3_sign),
+    ] {
+        let q_limbs_large_abs_expected = combine_small_to_large::<_, _, Env>(q_limbs_small.clone());
+        for j in 0..N_LIMBS_LARGE {
+            env.assert_zero(
+                q_limbs_large[j].clone() - q_sign.clone() * q_limbs_large_abs_expected[j].clone(),
+            );
+        }
+    }
+
+    let f_bui: BigUint = TryFrom::try_from(Ff::MODULUS).unwrap();
+    let f_limbs_large: [Env::Variable; N_LIMBS_LARGE] =
+        limb_decompose_biguint::<F, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(f_bui)
+            .map(|f_i| Env::constant(f_i));
+
+    let xr_limbs_large = combine_small_to_large::<_, _, Env>(xr_limbs_small);
+    let yr_limbs_large = combine_small_to_large::<_, _, Env>(yr_limbs_small);
+    let x2_limbs_large = combine_small_to_large::<_, _, Env>(x2_limbs_small);
+    let s_limbs_large = combine_small_to_large::<_, _, Env>(s_limbs_small);
+    let q0_limbs_large = combine_small_to_large::<_, _, Env>(q0_limbs_small);
+
+    let carry0_limbs_large: [_; 2 * N_LIMBS_LARGE - 2] =
+        combine_carry::<F, _, Env>(carry0_limbs_small);
+    let carry1_limbs_large: [_; 2 * N_LIMBS_LARGE - 2] =
+        combine_carry::<F, _, Env>(carry1_limbs_small);
+    let carry2_limbs_large: [_; 2 * N_LIMBS_LARGE - 2] =
+        combine_carry::<F, _, Env>(carry2_limbs_small);
+    let carry3_limbs_large: [_; 2 * N_LIMBS_LARGE - 2] =
+        combine_carry::<F, _, Env>(carry3_limbs_small);
+
+    let limb_size_large = constant_u128(1u128 << LIMB_BITSIZE_LARGE);
+    let add_extra_carries =
+        |i: usize, carry_limbs_large: &[Env::Variable; 2 * N_LIMBS_LARGE - 2]| -> Env::Variable {
+            if i == 0 {
+                -(carry_limbs_large[0].clone() * limb_size_large.clone())
+            } else if i < 2 * N_LIMBS_LARGE - 2 {
+                carry_limbs_large[i - 1].clone()
+                    - carry_limbs_large[i].clone() * limb_size_large.clone()
+            } else if i == 2 * N_LIMBS_LARGE - 2 {
+                carry_limbs_large[i - 1].clone()
+            } else {
+                panic!("add_extra_carries: the index {i:?} is too high")
+            }
+        };
+
+    // Equation 0
+    // General form: \sum xP_j xP_k - x2_i - \sum q_0_j f_k - c_i * 2^B + c_{i-1} = 0
+    for i in 0..2 * N_LIMBS_LARGE - 1 {
+        let mut constraint0 = fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+            xp_limbs_large[j].clone() * xp_limbs_large[k].clone()
+        });
+        if i < N_LIMBS_LARGE {
+            constraint0 = constraint0 - x2_limbs_large[i].clone();
+        }
+        constraint0 = constraint0
+            - fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+                q0_limbs_large[j].clone() * f_limbs_large[k].clone()
+            });
+        constraint0 = constraint0 + add_extra_carries(i, &carry0_limbs_large);
+        env.assert_zero(constraint0);
+    }
+
+    // Equation 1
+    // General form: 2 \sum yP_j s_k - 3 x2_i - \sum q_1_j f_k - c_i * 2^B + c_{i-1} = 0
+    for i in 0..2 * N_LIMBS_LARGE - 1 {
+        let mut constraint1 = fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+            yp_limbs_large[j].clone() * s_limbs_large[k].clone()
+        }) * constant_u128(2);
+        if i < N_LIMBS_LARGE {
+            constraint1 = constraint1 - x2_limbs_large[i].clone() * constant_u128(3);
+        }
+        constraint1 = constraint1
+            - fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+                q1_limbs_large[j].clone() * f_limbs_large[k].clone()
+            });
+        constraint1 = constraint1 + add_extra_carries(i, &carry1_limbs_large);
+        env.assert_zero(constraint1);
+    }
+
+    // Equation 2
+    // General form: xR_i - \sum s_j s_k + 2 xP_i - \sum q_2_j f_k - c_i * 2^B + c_{i-1} = 0
+    for i in 0..2 * N_LIMBS_LARGE - 1 {
+        let mut constraint2 = -fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+            s_limbs_large[j].clone() * s_limbs_large[k].clone()
+        });
+        if i < N_LIMBS_LARGE {
+            constraint2 = constraint2
+                + xr_limbs_large[i].clone()
+                + xp_limbs_large[i].clone() * constant_u128(2);
+        }
+        constraint2 = constraint2
+            - fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+                q2_limbs_large[j].clone() * f_limbs_large[k].clone()
+            });
+        constraint2 = constraint2 + add_extra_carries(i, &carry2_limbs_large);
+        env.assert_zero(constraint2);
+    }
+
+    // Equation 3
+    // General form: yR_i + yP_i - \sum s_j (xP_k - xR_k) - \sum q_3_j f_k - c_i * 2^B + c_{i-1} = 0
+    for i in 0..2 * N_LIMBS_LARGE - 1 {
+        let mut constraint3 = -fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+            s_limbs_large[j].clone() * (xp_limbs_large[k].clone() - xr_limbs_large[k].clone())
+        });
+        if i < N_LIMBS_LARGE {
+            constraint3 = constraint3 + yr_limbs_large[i].clone() + yp_limbs_large[i].clone();
+        }
+        constraint3 = constraint3
+            - fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+                q3_limbs_large[j].clone() * f_limbs_large[k].clone()
+            });
+        constraint3 = constraint3 + add_extra_carries(i, &carry3_limbs_large);
+        env.assert_zero(constraint3)
+    }
+}
+
+/// Creates a witness for doubling a point p, represented as a pair of
+/// foreign field elements. Returns a point.
+///
+/// This function is witness-generation counterpart (called by the prover) of
+/// `constrain_ec_doubling` -- see the documentation of the latter.
+pub fn ec_double_circuit<
+    F: PrimeField,
+    Ff: PrimeField,
+    Env: ColWriteCap<F, FECDoubleColumn> + LookupCap<F, FECDoubleColumn, LookupTable<Ff>>,
+>(
+    env: &mut Env,
+    xp: Ff,
+    yp: Ff,
+) -> (Ff, Ff) {
+    use FECDoubleColumnInter as Inter;
+
+    let x2: Ff = xp * xp;
+    let slope: Ff = (x2 + x2 + x2) / (yp + yp);
+    let xr: Ff = slope * slope - xp - xp;
+    let yr: Ff = slope * (xp - xr) - yp;
+
+    // Foreign field modulus
+    let f_bui: BigUint = TryFrom::try_from(Ff::MODULUS).unwrap();
+    let f_bi: BigInt = f_bui.to_bigint().unwrap();
+
+    let xp_bi: BigInt = FieldHelpers::to_bigint_positive(&xp);
+    let yp_bi: BigInt = FieldHelpers::to_bigint_positive(&yp);
+    let x2_bi: BigInt = FieldHelpers::to_bigint_positive(&x2);
+    let slope_bi: BigInt = FieldHelpers::to_bigint_positive(&slope);
+    let xr_bi: BigInt = FieldHelpers::to_bigint_positive(&xr);
+    let yr_bi: BigInt = FieldHelpers::to_bigint_positive(&yr);
+
+    // Computes the quotient of an equation, splitting it into its
+    // absolute value and its sign, since storing negative numbers is
+    // a mess.
+    let quotient = |value: BigInt| -> (BigUint, F) {
+        let (q_bi, r_bi) = value.div_rem(&f_bi);
+        assert!(r_bi.is_zero());
+        if q_bi.is_negative() {
+            ((-q_bi).to_biguint().unwrap(), -F::one())
+        } else {
+            (q_bi.to_biguint().unwrap(), F::one())
+        }
+    };
+
+    // Equation 0: xP^2 - x2 - q_0 f = 0
+    let (q0_bui, q0_sign) = quotient(&xp_bi * &xp_bi - &x2_bi);
+    assert!(q0_sign == F::one());
+    // Equation 1: 2 yP s - 3 x2 - q_1 f = 0
+    let (q1_bui, q1_sign) = quotient(BigInt::from(2u64) * &yp_bi * &slope_bi - 3u64 * &x2_bi);
+    // Equation 2: xR - s^2 + 2 xP - q_2 f = 0
+    let (q2_bui, q2_sign) = quotient(&xr_bi - &slope_bi * &slope_bi + 2u64 * &xp_bi);
+    // Equation 3: yR + yP - s (xP - xR) - q_3 f = 0
+    let (q3_bui, q3_sign) = quotient(&yr_bi + &yp_bi - &slope_bi * (&xp_bi - &xr_bi));
+
+    let xp_limbs_large: [F; N_LIMBS_LARGE] =
+        limb_decompose_ff::<F, Ff, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(&xp);
+    let yp_limbs_large: [F; N_LIMBS_LARGE] =
+        limb_decompose_ff::<F, Ff, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(&yp);
+    let f_limbs_large: [F; N_LIMBS_LARGE] =
+        limb_decompose_biguint::<F, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(f_bui);
+    let x2_limbs_large: [F; N_LIMBS_LARGE] =
+        limb_decompose_ff::<F, Ff, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(&x2);
+    let slope_limbs_large: [F; N_LIMBS_LARGE] =
+        limb_decompose_ff::<F, Ff, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(&slope);
+    let xr_limbs_large: [F; N_LIMBS_LARGE] =
+        limb_decompose_ff::<F, Ff, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(&xr);
+    let yr_limbs_large: [F; N_LIMBS_LARGE] =
+        limb_decompose_ff::<F, Ff, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(&yr);
+
+    // Big limbs /have/ sign in them.
+    let signed_limbs_large = |q_bui: &BigUint, q_sign: F| -> [F; N_LIMBS_LARGE] {
+        limb_decompose_biguint::<F, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(q_bui.clone())
+            .map(|v| v * q_sign)
+    };
+    let q0_limbs_large = signed_limbs_large(&q0_bui, q0_sign);
+    let q1_limbs_large = signed_limbs_large(&q1_bui, q1_sign);
+    let q2_limbs_large = signed_limbs_large(&q2_bui, q2_sign);
+    let q3_limbs_large = signed_limbs_large(&q3_bui, q3_sign);
+
+    write_column_array_const(env, &xp_limbs_large, |i| {
+        FECDoubleColumn::Input(FECDoubleColumnInput::XP(i))
+    });
+    write_column_array_const(env, &yp_limbs_large, |i| {
+        FECDoubleColumn::Input(FECDoubleColumnInput::YP(i))
+    });
+
+    for (value, column) in [
+        (&x2, Inter::X2 as fn(usize) -> Inter),
+        (&slope, Inter::S as fn(usize) -> Inter),
+    ] {
+        let limbs_small: [F; N_LIMBS_SMALL] =
+            limb_decompose_ff::<F, Ff, LIMB_BITSIZE_SMALL, N_LIMBS_SMALL>(value);
+        write_column_array_const(env, &limbs_small, |i| FECDoubleColumn::Inter(column(i)));
+    }
+    for (value, column) in [
+        (&xr, FECColumnOutput::XR as fn(usize) -> FECColumnOutput),
+        (&yr, FECColumnOutput::YR as fn(usize) -> FECColumnOutput),
+    ] {
+        let limbs_small: [F; N_LIMBS_SMALL] =
+            limb_decompose_ff::<F, Ff, LIMB_BITSIZE_SMALL, N_LIMBS_SMALL>(value);
+        write_column_array_const(env, &limbs_small, |i| FECDoubleColumn::Output(column(i)));
+    }
+
+    // Small limbs are signless 15-bit
+    for (q_bui, column) in [
+        (&q0_bui, Inter::Q0 as fn(usize) -> Inter),
+        (&q1_bui, Inter::Q1 as fn(usize) -> Inter),
+        (&q2_bui, Inter::Q2 as fn(usize) -> Inter),
+        (&q3_bui, Inter::Q3 as fn(usize) -> Inter),
+    ] {
+        let limbs_small: [F; N_LIMBS_SMALL] =
+            limb_decompose_biguint::<F, LIMB_BITSIZE_SMALL, N_LIMBS_SMALL>(q_bui.clone());
+        write_column_array_const(env, &limbs_small, |i| FECDoubleColumn::Inter(column(i)));
+    }
+
+    write_column_const(env, FECDoubleColumn::Inter(Inter::Q1Sign), &q1_sign);
+    write_column_const(env, FECDoubleColumn::Inter(Inter::Q2Sign), &q2_sign);
+    write_column_const(env, FECDoubleColumn::Inter(Inter::Q3Sign), &q3_sign);
+
+    write_column_array_const(env, &q1_limbs_large, |i| {
+        FECDoubleColumn::Inter(Inter::Q1L(i))
+    });
+    write_column_array_const(env, &q2_limbs_large, |i| {
+        FECDoubleColumn::Inter(Inter::Q2L(i))
+    });
+    write_column_array_const(env, &q3_limbs_large, |i| {
+        FECDoubleColumn::Inter(Inter::Q3L(i))
+    });
+
+    let two: F = From::from(2u64);
+    let three: F = From::from(3u64);
+
+    // Equation 0: xP^2 - x2 - q_0 f = 0
+    let carry0_limbs_small = compute_carry_limbs_small::<F>(core::array::from_fn(|i| {
+        let mut res = fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+            xp_limbs_large[j] * xp_limbs_large[k]
+        });
+        if i < N_LIMBS_LARGE {
+            res -= x2_limbs_large[i];
+        }
+        res - fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+            q0_limbs_large[j] * f_limbs_large[k]
+        })
+    }));
+
+    // Equation 1: 2 yP s - 3 x2 - q_1 f = 0
+    let carry1_limbs_small = compute_carry_limbs_small::<F>(core::array::from_fn(|i| {
+        let mut res = two
+            * fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+                yp_limbs_large[j] * slope_limbs_large[k]
+            });
+        if i < N_LIMBS_LARGE {
+            res -= three * x2_limbs_large[i];
+        }
+        res - fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+            q1_limbs_large[j] * f_limbs_large[k]
+        })
+    }));
+
+    // Equation 2: xR - s^2 + 2 xP - q_2 f = 0
+    let carry2_limbs_small = compute_carry_limbs_small::<F>(core::array::from_fn(|i| {
+        let mut res = -fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+            slope_limbs_large[j] * slope_limbs_large[k]
+        });
+        if i < N_LIMBS_LARGE {
+            res += xr_limbs_large[i] + two * xp_limbs_large[i];
+        }
+        res - fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+            q2_limbs_large[j] * f_limbs_large[k]
+        })
+    }));
+
+    // Equation 3: yR + yP - s (xP - xR) - q_3 f = 0
+    let carry3_limbs_small = compute_carry_limbs_small::<F>(core::array::from_fn(|i| {
+        let mut res = -fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+            slope_limbs_large[j] * (xp_limbs_large[k] - xr_limbs_large[k])
+        });
+        if i < N_LIMBS_LARGE {
+            res += yr_limbs_large[i] + yp_limbs_large[i];
+        }
+        res - fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+            q3_limbs_large[j] * f_limbs_large[k]
+        })
+    }));
+
+    write_column_array_const(env, &carry0_limbs_small, |i| {
+        FECDoubleColumn::Inter(Inter::Carry0(i))
+    });
+    write_column_array_const(env, &carry1_limbs_small, |i| {
+        FECDoubleColumn::Inter(Inter::Carry1(i))
+    });
+    write_column_array_const(env, &carry2_limbs_small, |i| {
+        FECDoubleColumn::Inter(Inter::Carry2(i))
+    });
+    write_column_array_const(env, &carry3_limbs_small, |i| {
+        FECDoubleColumn::Inter(Inter::Carry3(i))
+    });
+
+    constrain_ec_doubling::<F, Ff, Env>(env);
+
+    (xr, yr)
+}
+
+/// Builds the fixed selectors of the FEC scalar multiplication
+/// circuit, for a sequence of scalar multiplications of `n_windows`
+/// windows each. The last one is truncated if it does not fit into
+/// the domain.
+pub fn build_scalar_mul_selectors<F: PrimeField>(
+    domain_size: usize,
+    n_windows: usize,
+) -> [Vec<F>; FEC_SCALAR_MUL_N_FSEL] {
+    let current_row = (0..domain_size).map(|i| F::from((i + 1) as u64)).collect();
+    let setup_sel = (0..domain_size)
+        .map(|i| {
+            if i % (n_windows + 1) == 0 {
+                F::one()
+            } else {
+                F::zero()
+            }
+        })
+        .collect();
+    [current_row, setup_sel]
+}
+
+/// Number of windows of the FEC scalar multiplication circuit needed
+/// to multiply by any element of the scalar field `Fr`.
+pub fn scalar_mul_n_windows<Fr: PrimeField>() -> usize {
+    (Fr::MODULUS_BIT_SIZE as usize + FEC_SCALAR_MUL_WINDOW_SIZE - 1) / FEC_SCALAR_MUL_WINDOW_SIZE
+}
+
+/// Decomposes the scalar `k` into the window digits `d_i ∈ [1,4]` of
+/// the FEC scalar multiplication circuit, most significant first, such
+/// that `2 * 4^n + \sum_i d_i 4^{n-1-i} = k mod r`, where `n` is
+/// given by `scalar_mul_n_windows` and `r` is the modulus of `Fr`.
+///
+/// Since `4^n >= r`, the `4^n` values of the sum are enough to cover
+/// every residue modulo `r`: the digits are the bijective base-4
+/// representation of the smallest such value.
+pub fn scalar_mul_digits<Fr: PrimeField>(k: Fr) -> Vec<usize> {
+    let n_windows = scalar_mul_n_windows::<Fr>();
+    let r_bui: BigUint = TryFrom::try_from(Fr::MODULUS).unwrap();
+    let pow_bui: BigUint = BigUint::from(1u64) << (FEC_SCALAR_MUL_WINDOW_SIZE * n_windows);
+    // The smallest value with n digits, 1 + 4 + ... + 4^{n-1}.
+    let lowest_bui: BigUint = (&pow_bui - 1u64) / 3u64;
+    let base_bui: BigUint = 2u64 * &pow_bui + &lowest_bui;
+
+    let mut value_bui: BigUint =
+        lowest_bui + (k.to_biguint() + &r_bui - (base_bui % &r_bui)) % &r_bui;
+    let mut digits: Vec<usize> = (0..n_windows)
+        .map(|_| {
+            let digit_bui = (&value_bui - 1u64) % 4u64 + 1u64;
+            value_bui = (&value_bui - &digit_bui) / 4u64;
+            TryFrom::try_from(digit_bui).unwrap()
+        })
+        .collect();
+    assert!(value_bui.is_zero());
+    digits.reverse();
+    digits
+}
+
+/// Constraints of the FEC scalar multiplication circuit, which
+/// computes `[k]P` for a point P of a curve y^2 = x^3 + b (that is,
+/// with a = 0) over the foreign field, with windows of 2 bits.
+///
+/// Each scalar multiplication uses `n + 1` consecutive rows, where `n`
+/// is the number of windows:
+///
+/// - The first one, the setup row, builds the table of multiples
+///   `T = [P, 2P, 3P, 4P]` with the same gadgets as the other rows: the
+///   doublings compute 2P and 4P from the accumulator `Acc = P`, and
+///   the addition computes 3P = 2P + P. Its output accumulator is 2P.
+/// - Each of the next ones, the window rows, computes
+///   `4 Acc + T[d]` with two doublings and one addition, where
+///   `d ∈ [1,4]` is the window digit given by its two bits.
+///
+/// The result is thus `[2 * 4^n + \sum_i d_i 4^{n-1-i}]P`, see
+/// `scalar_mul_digits`. Starting with 2P ensures that the addition of
+/// the first window is not a doubling.
+///
+/// Rows are chained with a communication bus keyed by the row
+/// index: each row writes `(row, Acc', T)` and each window row reads
+/// `(row - 1, Acc, T)`, so the table of the setup row is carried along
+/// with the accumulator. The setup row reads a dummy zero entry
+/// instead. The point P is the input of the circuit, and the result
+/// can be read from the bus, at the row of the last window.
+///
+/// As for `constrain_ec_addition`, the formulas are incomplete: the
+/// witness generation fails if `4 Acc = ±T[d]`, which only happens with
+/// negligible probability for a random scalar.
+pub fn constrain_ec_scalar_mul<
+    F: PrimeField,
+    Ff: PrimeField,
+    Env: ColAccessCap<F, FECScalarMulColumn> + LookupCap<F, FECScalarMulColumn, LookupTable<Ff>>,
+>(
+    env: &mut Env,
+) {
+    for j in 0..FEC_SCALAR_MUL_WINDOW_SIZE {
+        constrain_ec_doubling::<F, Ff, _>(&mut SubEnvColumn::new(env, FECScalarMulDoubleLens(j)));
+    }
+    constrain_ec_addition::<F, Ff, _>(&mut SubEnvColumn::new(env, FECScalarMulAddLens {}));
+    constrain_ec_scalar_mul_wiring::<F, Ff, Env>(env);
+}
+
+/// Constraints of the FEC scalar multiplication circuit that connect
+/// the gadgets together and to the bus, see `constrain_ec_scalar_mul`.
+fn constrain_ec_scalar_mul_wiring<
+    F: PrimeField,
+    Ff: PrimeField,
+    Env: ColAccessCap<F, FECScalarMulColumn> + LookupCap<F, FECScalarMulColumn, LookupTable<Ff>>,
+>(
+    env: &mut Env,
+) {
+    use FECScalarMulColumn as Col;
+
+    let current_row = env.read_column(Col::CurrentRow);
+    let setup = env.read_column(Col::SetupSel);
+    let not_setup = Env::constant(F::one()) - setup.clone();
+
+    let acc: [_; 2 * N_LIMBS_LARGE] = read_column_array(env, Col::Acc);
+    let table: [[_; 2 * N_LIMBS_LARGE]; FEC_SCALAR_MUL_TABLE_SIZE] =
+        core::array::from_fn(|j| read_column_array(env, |i| Col::Table(j, i)));
+    let bits: [_; FEC_SCALAR_MUL_WINDOW_SIZE] = read_column_array(env, Col::DigitBit);
+    let addend: [_; 2 * N_LIMBS_LARGE] = read_column_array(env, Col::Addend);
+    let add_input: [_; 2 * N_LIMBS_LARGE] = read_column_array(env, Col::AddInput);
+    let double_repacked: [[_; 2 * N_LIMBS_LARGE]; FEC_SCALAR_MUL_WINDOW_SIZE] =
+        core::array::from_fn(|j| read_column_array(env, |i| Col::DoubleRepacked(j, i)));
+    let add_repacked: [_; 2 * N_LIMBS_LARGE] = read_column_array(env, Col::AddRepacked);
+    let add_f_limbs_large: [_; N_LIMBS_LARGE] =
+        read_column_array(env, |i| Col::AddInter(FECColumnInter::F(i)));
+
+    // The addition gadget takes the foreign field modulus as a column.
+    let f_bui: BigUint = TryFrom::try_from(Ff::MODULUS).unwrap();
+    let f_limbs_large: [F; N_LIMBS_LARGE] =
+        limb_decompose_biguint::<F, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(f_bui);
+    for (f_i, f_limb) in add_f_limbs_large.iter().zip(f_limbs_large.iter()) {
+        env.assert_zero(f_i.clone() - Env::constant(*f_limb));
+    }
+
+    // Repacking the outputs to 75 bits.
+    let repack = |env: &mut Env,
+                  repacked: &[Env::Variable; 2 * N_LIMBS_LARGE],
+                  output: &dyn Fn(FECColumnOutput) -> Col| {
+        let xr_limbs_small: [_; N_LIMBS_SMALL] =
+            read_column_array(env, |i| output(FECColumnOutput::XR(i)));
+        let yr_limbs_small: [_; N_LIMBS_SMALL] =
+            read_column_array(env, |i| output(FECColumnOutput::YR(i)));
+        let xr_limbs_large = combine_small_to_large::<_, _, Env>(xr_limbs_small);
+        let yr_limbs_large = combine_small_to_large::<_, _, Env>(yr_limbs_small);
+        for (i, x) in xr_limbs_large.into_iter().chain(yr_limbs_large).enumerate() {
+            env.assert_zero(repacked[i].clone() - x);
+        }
+    };
+    for (j, repacked) in double_repacked.iter().enumerate() {
+        repack(env, repacked, &|output| Col::DoubleOutput(j, output));
+    }
+    repack(env, &add_repacked, &Col::AddOutput);
+
+    // Digit bits are boolean, and select the addend from the table.
+    for bit in bits.iter() {
+        env.assert_zero(bit.clone() * bit.clone() - bit.clone());
+    }
+    let selectors: [Env::Variable; FEC_SCALAR_MUL_TABLE_SIZE] = core::array::from_fn(|m| {
+        bits.iter()
+            .enumerate()
+            .fold(Env::constant(F::one()), |acc, (b, bit)| {
+                if (m >> b) & 1 == 1 {
+                    acc * bit.clone()
+                } else {
+                    acc * (Env::constant(F::one()) - bit.clone())
+                }
+            })
+    });
+    for i in 0..2 * N_LIMBS_LARGE {
+        let selected = selectors
+            .iter()
+            .zip(table.iter())
+            .fold(Env::constant(F::zero()), |acc, (sel, point)| {
+                acc + sel.clone() * point[i].clone()
+            });
+        env.assert_zero(addend[i].clone() - selected);
+    }
+
+    // The addition adds the addend to 2 Acc on the setup row, and to
+    // 4 Acc on the window rows.
+    for (i, add_input_i) in add_input.iter().enumerate() {
+        env.assert_zero(
+            add_input_i.clone()
+                - setup.clone() * double_repacked[0][i].clone()
+                - not_setup.clone() * double_repacked[FEC_SCALAR_MUL_WINDOW_SIZE - 1][i].clone(),
+        );
+    }
+
+    // On the setup row, the accumulator is P = T[1], the addend is T[1],
+    // and the table is [P, 2P, 3P, 4P].
+    for bit in bits.iter() {
+        env.assert_zero(setup.clone() * bit.clone());
+    }
+    for i in 0..2 * N_LIMBS_LARGE {
+        env.assert_zero(setup.clone() * (acc[i].clone() - table[0][i].clone()));
+        env.assert_zero(setup.clone() * (table[1][i].clone() - double_repacked[0][i].clone()));
+        env.assert_zero(setup.clone() * (table[2][i].clone() - add_repacked[i].clone()));
+        env.assert_zero(
+            setup.clone()
+                * (table[3][i].clone()
+                    - double_repacked[FEC_SCALAR_MUL_WINDOW_SIZE - 1][i].clone()),
+        );
+    }
+
+    // The lookups only support values of degree one, so the masked
+    // entries of the bus are stored in columns.
+    let bus_input: [_; FEC_SCALAR_MUL_BUS_ENTRY_SIZE] = read_column_array(env, Col::BusInput);
+    let bus_output: [_; 2 * N_LIMBS_LARGE] = read_column_array(env, Col::BusOutput);
+    {
+        // The output is Acc' = 2P on the setup row.
+        for (i, bus_output_i) in bus_output.iter().enumerate() {
+            env.assert_zero(
+                bus_output_i.clone()
+                    - setup.clone() * table[1][i].clone()
+                    - not_setup.clone() * add_repacked[i].clone(),
+            );
+        }
+
+        // The input is (row - 1, [Acc, T]), unless on the setup row.
+        let mut vec_input: Vec<_> = acc.to_vec();
+        vec_input.extend(table.iter().flatten().cloned());
+        vec_input.insert(0, current_row.clone() - Env::constant(F::one()));
+        for (bus_input_i, input_i) in bus_input.iter().zip(vec_input) {
+            env.assert_zero(bus_input_i.clone() - not_setup.clone() * input_i);
+        }
+    }
+
+    {
+        // Writing the output
+        // (row, [Acc', T])
+        let mut vec_output: Vec<_> = bus_output.to_vec();
+        vec_output.extend(table.iter().flatten().cloned());
+        vec_output.insert(0, current_row);
+        env.lookup_runtime_write(LookupTable::ScalarMulBus, vec_output);
+
+        // Writing the dummy entry read by the setup rows
+        // (0, [VEC representing 0])
+        env.lookup_runtime_write(
+            LookupTable::ScalarMulBus,
+            vec![Env::constant(F::zero()); FEC_SCALAR_MUL_BUS_ENTRY_SIZE],
+        );
+
+        // Reading the input, which is the dummy entry on the setup row
+        env.lookup(LookupTable::ScalarMulBus, bus_input.to_vec());
+    }
+}
+
+/// Writes a point into columns in large limbs, x limbs followed by y limbs.
+fn write_point_large<F, Ff, Env, ColMap>(env: &mut Env, point: (Ff, Ff), column_map: ColMap)
+where
+    F: PrimeField,
+    Ff: PrimeField,
+    Env: ColWriteCap<F, FECScalarMulColumn>,
+    ColMap: Fn(usize) -> FECScalarMulColumn,
+{
+    let x_limbs_large: [F; N_LIMBS_LARGE] =
+        limb_decompose_ff::<F, Ff, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(&point.0);
+    let y_limbs_large: [F; N_LIMBS_LARGE] =
+        limb_decompose_ff::<F, Ff, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(&point.1);
+    write_column_array_const(env, &x_limbs_large, &column_map);
+    write_column_array_const(env, &y_limbs_large, |i| column_map(N_LIMBS_LARGE + i));
+}
+
+/// Creates the witness of the row of index `row` of the FEC scalar
+/// multiplication circuit. The setup row is given no table and builds
+/// it. Returns the accumulator and the table written to the bus.
+fn ec_scalar_mul_row_circuit<F, Ff, Env>(
+    env: &mut Env,
+    row: usize,
+    acc: (Ff, Ff),
+    table: Option<[(Ff, Ff); FEC_SCALAR_MUL_TABLE_SIZE]>,
+    digit: usize,
+) -> ((Ff, Ff), [(Ff, Ff); FEC_SCALAR_MUL_TABLE_SIZE])
+where
+    F: PrimeField,
+    Ff: PrimeField,
+    Env: ColWriteCap<F, FECScalarMulColumn> + LookupCap<F, FECScalarMulColumn, LookupTable<Ff>>,
+{
+    use FECScalarMulColumn as Col;
+
+    assert!((1..=FEC_SCALAR_MUL_TABLE_SIZE).contains(&digit));
+    let is_setup = table.is_none();
+    let addend = table.map_or(acc, |table| table[digit - 1]);
+    let input_acc = acc;
+
+    write_point_large(env, acc, Col::Acc);
+    for b in 0..FEC_SCALAR_MUL_WINDOW_SIZE {
+        let bit: F = From::from((((digit - 1) >> b) & 1) as u64);
+        write_column_const(env, Col::DigitBit(b), &bit);
+    }
+    write_point_large(env, addend, Col::Addend);
+
+    let mut doubles = vec![];
+    let mut input = acc;
+    for j in 0..FEC_SCALAR_MUL_WINDOW_SIZE {
+        input = ec_double_circuit(
+            &mut SubEnvColumn::new(env, FECScalarMulDoubleLens(j)),
+            input.0,
+            input.1,
+        );
+        write_point_large(env, input, |i| Col::DoubleRepacked(j, i));
+        doubles.push(input);
+    }
+
+    let add_input = if is_setup {
+        doubles[0]
+    } else {
+        doubles[FEC_SCALAR_MUL_WINDOW_SIZE - 1]
+    };
+    write_point_large(env, add_input, Col::AddInput);
+    let add_output = ec_add_circuit(
+        &mut SubEnvColumn::new(env, FECScalarMulAddLens {}),
+        add_input.0,
+        add_input.1,
+        addend.0,
+        addend.1,
+    );
+    write_point_large(env, add_output, Col::AddRepacked);
+
+    let (acc, table) = match table {
+        Some(table) => (add_output, table),
+        None => (
+            doubles[0],
+            [
+                acc,
+                doubles[0],
+                add_output,
+                doubles[FEC_SCALAR_MUL_WINDOW_SIZE - 1],
+            ],
+        ),
+    };
+    for (j, point) in table.iter().enumerate() {
+        write_point_large(env, *point, |i| Col::Table(j, i));
+    }
+    write_point_large(env, acc, Col::BusOutput);
+    if !is_setup {
+        // The previous output is keyed by its row starting with 1, that
+        // is by the index of the current row
+        write_column_const(env, Col::BusInput(0), &F::from(row as u64));
+        for (j, point) in [input_acc].iter().chain(table.iter()).enumerate() {
+            write_point_large(env, *point, |i| {
+                Col::BusInput(1 + j * 2 * N_LIMBS_LARGE + i)
+            });
+        }
+    }
+
+    constrain_ec_scalar_mul_wiring::<F, Ff, Env>(env);
+
+    (acc, table)
+}
+
+/// Creates the witness of the scalar multiplication of the point
+/// p = (xp, yp) by the scalar given by its window digits, see
+/// `scalar_mul_digits`. Writes one setup row and one row per digit,
+/// starting at the current row, and moves to the row after. Returns a
+/// point.
+///
+/// This function is witness-generation counterpart (called by the prover) of
+/// `constrain_ec_scalar_mul` -- see the documentation of the latter.
+/// The rows must match the fixed selectors, see `build_scalar_mul_selectors`.
+pub fn ec_scalar_mul_circuit<F, Ff, Env>(
+    env: &mut Env,
+    xp: Ff,
+    yp: Ff,
+    digits: &[usize],
+) -> (Ff, Ff)
+where
+    F: PrimeField,
+    Ff: PrimeField,
+    Env: ColWriteCap<F, FECScalarMulColumn>
+        + LookupCap<F, FECScalarMulColumn, LookupTable<Ff>>
+        + MultiRowReadCap<F, FECScalarMulColumn>,
+{
+    let row = env.curr_row();
+    let (mut acc, mut table) = ec_scalar_mul_row_circuit(env, row, (xp, yp), None, 1);
+    env.next_row();
+    for digit in digits.iter() {
+        let row = env.curr_row();
+        (acc, table) = ec_scalar_mul_row_circuit(env, row, acc, Some(table), *digit);
+        env.next_row();
+    }
+    acc
+}
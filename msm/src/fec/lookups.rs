@@ -17,6 +17,8 @@ pub enum LookupTable<Ff> {
     /// x ∈ [0, ff_highest] where ff_highest is the highest 15-bit
     /// limb of the modulus of the foreign field `Ff`.
     RangeCheckFfHighest(PhantomData<Ff>),
+    /// Communication bus for the scalar multiplication circuit.
+    ScalarMulBus,
 }
 
 impl<Ff: PrimeField> LookupTableID for LookupTable<Ff> {
@@ -26,6 +28,7 @@ impl<Ff: PrimeField> LookupTableID for LookupTable<Ff> {
             Self::RangeCheck14Abs => 2,
             Self::RangeCheck9Abs => 3,
             Self::RangeCheckFfHighest(_) => 4,
+            Self::ScalarMulBus => 5,
         }
     }
 
@@ -35,17 +38,20 @@ impl<Ff: PrimeField> LookupTableID for LookupTable<Ff> {
             2 => Self::RangeCheck14Abs,
             3 => Self::RangeCheck9Abs,
             4 => Self::RangeCheckFfHighest(PhantomData),
+            5 => Self::ScalarMulBus,
             _ => panic!("Invalid lookup table id"),
         }
     }
 
-    /// All tables are fixed tables.
     fn is_fixed(&self) -> bool {
-        true
+        !matches!(self, Self::ScalarMulBus)
     }
 
     fn runtime_create_column(&self) -> bool {
-        panic!("No runtime tables specified");
+        match self {
+            Self::ScalarMulBus => false,
+            _ => panic!("runtime_create_column was called on a non-runtime table"),
+        }
     }
 
     fn length(&self) -> usize {
@@ -57,11 +63,15 @@ impl<Ff: PrimeField> LookupTableID for LookupTable<Ff> {
                 crate::serialization::interpreter::ff_modulus_highest_limb::<Ff>(),
             )
             .unwrap(),
+            Self::ScalarMulBus => 1 << 15,
         }
     }
 
     /// Converts a value to its index in the fixed table.
     fn ix_by_value<F: PrimeField>(&self, value: &[F]) -> Option<usize> {
+        if !self.is_fixed() {
+            return None;
+        }
         let value = value[0];
         assert!(self.is_member(value));
         Some(match self {
@@ -81,6 +91,7 @@ impl<Ff: PrimeField> LookupTableID for LookupTable<Ff> {
                 }
             }
             Self::RangeCheckFfHighest(_) => TryFrom::try_from(value.to_biguint()).unwrap(),
+            Self::ScalarMulBus => unreachable!(),
         })
     }
 
@@ -90,6 +101,7 @@ impl<Ff: PrimeField> LookupTableID for LookupTable<Ff> {
             Self::RangeCheck14Abs,
             Self::RangeCheck9Abs,
             Self::RangeCheckFfHighest(PhantomData),
+            Self::ScalarMulBus,
         ]
     }
 }
@@ -140,6 +152,7 @@ impl<Ff: PrimeField> LookupTable<Ff> {
                 })
                 .collect(),
             Self::RangeCheckFfHighest(_) => Self::entries_ff_highest::<F>(domain_d1_size),
+            Self::ScalarMulBus => panic!("The scalar multiplication bus has no fixed entries"),
         }
     }
 
@@ -159,6 +172,7 @@ impl<Ff: PrimeField> LookupTable<Ff> {
                     F::from_biguint(&(f_bui >> ((N_LIMBS - 1) * LIMB_BITSIZE))).unwrap();
                 value < top_modulus_f
             }
+            Self::ScalarMulBus => panic!("The scalar multiplication bus has no fixed entries"),
         }
     }
 }
@@ -9,18 +9,28 @@ mod tests {
         circuit_design::{ConstraintBuilderEnv, WitnessBuilderEnv},
         columns::ColumnIndexer,
         fec::{
-            columns::{FECColumn, FEC_N_COLUMNS},
-            interpreter::{constrain_ec_addition, ec_add_circuit},
+            columns::{
+                FECColumn, FECDoubleColumn, FECScalarMulColumn, FEC_DOUBLE_N_COLUMNS,
+                FEC_N_COLUMNS, FEC_SCALAR_MUL_N_COLUMNS, FEC_SCALAR_MUL_N_FSEL,
+            },
+            interpreter::{
+                build_scalar_mul_selectors, constrain_ec_addition, constrain_ec_doubling,
+                constrain_ec_scalar_mul, ec_add_circuit, ec_double_circuit, ec_scalar_mul_circuit,
+                scalar_mul_digits, scalar_mul_n_windows,
+            },
             lookups::LookupTable,
         },
         logup::LookupTableID,
         Ff1, Fp,
     };
-    use ark_ec::AffineRepr;
-    use ark_ff::UniformRand;
+    use ark_ec::{
+        short_weierstrass::{Affine, SWCurveConfig},
+        AffineRepr, CurveGroup,
+    };
+    use ark_ff::{PrimeField, UniformRand};
     use rand::{CryptoRng, RngCore};
     use std::{
-        collections::{BTreeMap, HashMap},
+        collections::{BTreeMap, BTreeSet, HashMap},
         ops::Mul,
     };
 
@@ -138,6 +148,252 @@ mod tests {
         }
     }
 
+    type FECDoubleWitnessBuilderEnv<Ff> = WitnessBuilderEnv<
+        Fp,
+        FECDoubleColumn,
+        { <FECDoubleColumn as ColumnIndexer>::N_COL },
+        { <FECDoubleColumn as ColumnIndexer>::N_COL },
+        0,
+        0,
+        LookupTable<Ff>,
+    >;
+
+    type FECScalarMulWitnessBuilderEnv<Ff> = WitnessBuilderEnv<
+        Fp,
+        FECScalarMulColumn,
+        { <FECScalarMulColumn as ColumnIndexer>::N_COL - FEC_SCALAR_MUL_N_FSEL },
+        { <FECScalarMulColumn as ColumnIndexer>::N_COL - FEC_SCALAR_MUL_N_FSEL },
+        0,
+        FEC_SCALAR_MUL_N_FSEL,
+        LookupTable<Ff>,
+    >;
+
+    fn build_fec_doubling_circuit<C: SWCurveConfig, RNG: RngCore + CryptoRng>(
+        rng: &mut RNG,
+        domain_size: usize,
+    ) -> FECDoubleWitnessBuilderEnv<C::BaseField>
+    where
+        C::BaseField: PrimeField,
+    {
+        let mut witness_env = FECDoubleWitnessBuilderEnv::<C::BaseField>::create();
+
+        for row_i in 0..domain_size {
+            let k: C::ScalarField = UniformRand::rand(rng);
+            let p: Affine<C> = Affine::<C>::generator().mul(k).into();
+
+            let (rx, ry) = ec_double_circuit(&mut witness_env, p.x, p.y);
+
+            let r: Affine<C> = Affine::new_unchecked(rx, ry);
+            assert!(
+                r == (p + p).into_affine(),
+                "fec doubling circuit does not compute actual 2p"
+            );
+
+            if row_i < domain_size - 1 {
+                witness_env.next_row();
+            }
+        }
+
+        witness_env
+    }
+
+    /// Builds the scalar multiplication circuit for `n_scalars` random
+    /// scalars, checks the results, and returns the witness
+    /// environment. The last scalar multiplication is truncated if it
+    /// does not fit into the domain, as the fixed selectors.
+    fn build_fec_scalar_mul_circuit<C: SWCurveConfig, RNG: RngCore + CryptoRng>(
+        rng: &mut RNG,
+        domain_size: usize,
+        n_scalars: usize,
+    ) -> FECScalarMulWitnessBuilderEnv<C::BaseField>
+    where
+        C::BaseField: PrimeField,
+    {
+        let n_windows = scalar_mul_n_windows::<C::ScalarField>();
+        assert!((n_scalars - 1) * (n_windows + 1) < domain_size);
+
+        let mut witness_env = FECScalarMulWitnessBuilderEnv::<C::BaseField>::create();
+        witness_env
+            .set_fixed_selectors(build_scalar_mul_selectors::<Fp>(domain_size, n_windows).to_vec());
+
+        for scalar_i in 0..n_scalars {
+            let kp: C::ScalarField = UniformRand::rand(rng);
+            let p: Affine<C> = Affine::<C>::generator().mul(kp).into();
+            let k: C::ScalarField = UniformRand::rand(rng);
+
+            let digits = scalar_mul_digits(k);
+            assert_eq!(digits.len(), n_windows);
+            let rows_left = domain_size - scalar_i * (n_windows + 1);
+            if rows_left < n_windows + 1 {
+                ec_scalar_mul_circuit(&mut witness_env, p.x, p.y, &digits[..rows_left - 1]);
+                continue;
+            }
+            let (rx, ry) = ec_scalar_mul_circuit(&mut witness_env, p.x, p.y, &digits);
+
+            let r: Affine<C> = Affine::new_unchecked(rx, ry);
+            assert!(
+                r == p.mul(k).into_affine(),
+                "fec scalar multiplication circuit does not compute actual [k]p"
+            );
+        }
+
+        witness_env
+    }
+
+    #[test]
+    pub fn test_fec_doubling_circuit() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        build_fec_doubling_circuit::<mina_curves::pasta::PallasParameters, _>(&mut rng, 1 << 4);
+        build_fec_doubling_circuit::<ark_bn254::g1::Config, _>(&mut rng, 1 << 4);
+    }
+
+    #[test]
+    pub fn test_regression_relation_constraints_fec_doubling() {
+        let mut constraint_env = ConstraintBuilderEnv::<Fp, LookupTable<Ff1>>::create();
+        constrain_ec_doubling::<Fp, Ff1, _>(&mut constraint_env);
+        let constraints = constraint_env.get_relation_constraints();
+
+        assert_eq!(constraints.len(), 43);
+
+        assert!(constraints.iter().map(|c| c.degree(1, 0)).max() <= Some(3));
+    }
+
+    #[test]
+    /// Builds the scalar multiplication circuit with random points and
+    /// scalars, and checks that every bus read corresponds to a bus
+    /// write, which the witness environment does not enforce.
+    pub fn test_fec_scalar_mul_circuit() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+
+        let witness_env = build_fec_scalar_mul_circuit::<mina_curves::pasta::PallasParameters, _>(
+            &mut rng,
+            1 << 8,
+            1,
+        );
+        let writes: BTreeSet<_> = witness_env.runtime_lookup_writes[&LookupTable::ScalarMulBus]
+            .iter()
+            .flatten()
+            .cloned()
+            .collect();
+        for read in witness_env.lookup_reads[&LookupTable::ScalarMulBus]
+            .iter()
+            .flatten()
+        {
+            assert!(writes.contains(read), "bus read has no matching write");
+        }
+
+        build_fec_scalar_mul_circuit::<ark_bn254::g1::Config, _>(&mut rng, 1 << 8, 1);
+    }
+
+    #[test]
+    pub fn test_regression_relation_constraints_fec_scalar_mul() {
+        let mut constraint_env = ConstraintBuilderEnv::<Fp, LookupTable<Ff1>>::create();
+        constrain_ec_scalar_mul::<Fp, Ff1, _>(&mut constraint_env);
+        let constraints = constraint_env.get_relation_constraints();
+
+        assert_eq!(constraints.len(), 251);
+
+        assert!(constraints.iter().map(|c| c.degree(1, 0)).max() <= Some(3));
+    }
+
+    #[test]
+    pub fn heavy_test_fec_doubling_completeness() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let domain_size = 1 << 15; // Otherwise we can't do 15-bit lookups.
+
+        let mut constraint_env = ConstraintBuilderEnv::<Fp, LookupTable<Ff1>>::create();
+        constrain_ec_doubling::<Fp, Ff1, _>(&mut constraint_env);
+        let constraints = constraint_env.get_constraints();
+
+        let witness_env = build_fec_doubling_circuit::<mina_curves::pasta::PallasParameters, _>(
+            &mut rng,
+            domain_size,
+        );
+
+        let mut lookup_tables_data = BTreeMap::new();
+        for table_id in LookupTable::<Ff1>::all_variants()
+            .into_iter()
+            .filter(|table_id| table_id.is_fixed())
+        {
+            lookup_tables_data.insert(
+                table_id,
+                vec![table_id
+                    .entries(domain_size as u64)
+                    .into_iter()
+                    .map(|x| vec![x])
+                    .collect()],
+            );
+        }
+        let proof_inputs = witness_env.get_proof_inputs(domain_size, lookup_tables_data);
+
+        crate::test::test_completeness_generic::<
+            FEC_DOUBLE_N_COLUMNS,
+            FEC_DOUBLE_N_COLUMNS,
+            0,
+            0,
+            LookupTable<Ff1>,
+            _,
+        >(
+            constraints,
+            Box::new([]),
+            proof_inputs,
+            domain_size,
+            &mut rng,
+        );
+    }
+
+    #[test]
+    pub fn heavy_test_fec_scalar_mul_completeness() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let domain_size = 1 << 15; // Otherwise we can't do 15-bit lookups.
+
+        let mut constraint_env = ConstraintBuilderEnv::<Fp, LookupTable<Ff1>>::create();
+        constrain_ec_scalar_mul::<Fp, Ff1, _>(&mut constraint_env);
+        let constraints = constraint_env.get_constraints();
+
+        // Every row of the domain is used, so that the constraints hold
+        // on the whole domain.
+        let n_windows = scalar_mul_n_windows::<mina_curves::pasta::Fq>();
+        let n_scalars = (domain_size + n_windows) / (n_windows + 1);
+        let witness_env = build_fec_scalar_mul_circuit::<mina_curves::pasta::PallasParameters, _>(
+            &mut rng,
+            domain_size,
+            n_scalars,
+        );
+
+        // The bus is a runtime table, given by the writes of the witness.
+        let mut lookup_tables_data = witness_env.get_runtime_tables(domain_size);
+        for table_id in LookupTable::<Ff1>::all_variants()
+            .into_iter()
+            .filter(|table_id| table_id.is_fixed())
+        {
+            lookup_tables_data.insert(
+                table_id,
+                vec![table_id
+                    .entries(domain_size as u64)
+                    .into_iter()
+                    .map(|x| vec![x])
+                    .collect()],
+            );
+        }
+        let proof_inputs = witness_env.get_proof_inputs(domain_size, lookup_tables_data);
+
+        crate::test::test_completeness_generic::<
+            { FEC_SCALAR_MUL_N_COLUMNS - FEC_SCALAR_MUL_N_FSEL },
+            { FEC_SCALAR_MUL_N_COLUMNS - FEC_SCALAR_MUL_N_FSEL },
+            0,
+            FEC_SCALAR_MUL_N_FSEL,
+            LookupTable<Ff1>,
+            _,
+        >(
+            constraints,
+            Box::new(build_scalar_mul_selectors::<Fp>(domain_size, n_windows)),
+            proof_inputs,
+            domain_size,
+            &mut rng,
+        );
+    }
+
     #[test]
     pub fn heavy_test_fec_completeness() {
         let mut rng = o1_utils::tests::make_test_rng(None);
@@ -151,7 +407,10 @@ mod tests {
 
         // Fixed tables can be generated inside lookup_tables_data. Runtime should be generated here.
         let mut lookup_tables_data = BTreeMap::new();
-        for table_id in LookupTable::<Ff1>::all_variants().into_iter() {
+        for table_id in LookupTable::<Ff1>::all_variants()
+            .into_iter()
+            .filter(|table_id| table_id.is_fixed())
+        {
             lookup_tables_data.insert(
                 table_id,
                 vec![table_id
@@ -1,42 +1,57 @@
 use crate::columns::{Column, ColumnIndexer};
 
-use crate::N_LIMBS;
+/// Number of limbs representing one foreign field element in the FFA
+/// circuit. With limbs of [`crate::LIMB_BITSIZE`] bits it covers 270 bits,
+/// which is enough for any foreign modulus of at most 256 bits.
+pub const FFA_N_LIMBS: usize = 18;
+
+/// Maximum bitsize of the foreign modulus supported by the FFA circuit.
+pub const FFA_MAX_MODULUS_BITSIZE: usize = 256;
 
 /// Number of columns in the FFA circuits.
-pub const FFA_N_COLUMNS: usize = 5 * N_LIMBS;
-pub const FFA_NPUB_COLUMNS: usize = N_LIMBS;
+pub const FFA_N_COLUMNS: usize = 6 * FFA_N_LIMBS - 1;
+pub const FFA_NPUB_COLUMNS: usize = FFA_N_LIMBS;
 
-/// Column indexer for MSM columns.
+/// Column indexer for FFA columns.
 ///
-/// They represent the equation
-///   `InputA(i) + InputB(i) = ModulusF(i) * Quotient + Carry(i) * 2^LIMB_SIZE - Carry(i-1)`
+/// They represent the equations
+///   `InputA(i) + InputB(i) = f(i) * Quotient + Remainder(i) + Carry(i) * 2^LIMB_SIZE - Carry(i-1)`
+///   `Remainder(i) - f(i) = RemainderBound(i) + BoundCarry(i) * 2^LIMB_SIZE - BoundCarry(i-1)`
+/// where `f(i)` are the (constant) limbs of the foreign modulus. The
+/// second one ensures that `Remainder < f`, since `RemainderBound` is
+/// `Remainder - f + 2^(LIMB_SIZE * FFA_N_LIMBS)`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum FFAColumn {
     InputA(usize),
     InputB(usize),
-    ModulusF(usize),
     Remainder(usize),
+    RemainderBound(usize),
     Carry(usize),
     Quotient,
+    BoundCarry(usize),
 }
 
 impl ColumnIndexer for FFAColumn {
     const N_COL: usize = FFA_N_COLUMNS;
     fn to_column(self) -> Column {
         let to_column_inner = |offset, i| {
-            assert!(i < N_LIMBS);
-            Column::Relation(N_LIMBS * offset + i)
+            assert!(i < FFA_N_LIMBS);
+            Column::Relation(FFA_N_LIMBS * offset + i)
         };
         match self {
             FFAColumn::InputA(i) => to_column_inner(0, i),
             FFAColumn::InputB(i) => to_column_inner(1, i),
-            FFAColumn::ModulusF(i) => to_column_inner(2, i),
-            FFAColumn::Remainder(i) => to_column_inner(3, i),
+            FFAColumn::Remainder(i) => to_column_inner(2, i),
+            FFAColumn::RemainderBound(i) => to_column_inner(3, i),
             FFAColumn::Carry(i) => {
-                assert!(i < N_LIMBS - 1);
+                assert!(i < FFA_N_LIMBS - 1);
                 to_column_inner(4, i)
             }
-            FFAColumn::Quotient => to_column_inner(4, N_LIMBS - 1),
+            FFAColumn::Quotient => to_column_inner(4, FFA_N_LIMBS - 1),
+            FFAColumn::BoundCarry(i) => {
+                assert!(i < FFA_N_LIMBS - 1);
+                to_column_inner(5, i)
+            }
         }
     }
 }
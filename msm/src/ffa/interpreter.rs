@@ -1,71 +1,124 @@
 use crate::{
     circuit_design::{ColAccessCap, ColWriteCap, LookupCap},
-    ffa::{columns::FFAColumn, lookups::LookupTable},
+    ffa::{
+        columns::{FFAColumn, FFA_MAX_MODULUS_BITSIZE, FFA_N_LIMBS},
+        lookups::LookupTable,
+    },
     serialization::interpreter::{limb_decompose_biguint, limb_decompose_ff},
-    LIMB_BITSIZE, N_LIMBS,
+    LIMB_BITSIZE,
 };
 use ark_ff::PrimeField;
 use num_bigint::BigUint;
 use num_integer::Integer;
 use o1_utils::field_helpers::FieldHelpers;
 
-// For now this function does not /compute/ anything, although it could.
-/// Constraint for one row of FF addition:
+/// Returns the limbs of the modulus of the foreign field `Ff`.
+pub fn ff_modulus_limbs<F: PrimeField, Ff: PrimeField>() -> [F; FFA_N_LIMBS] {
+    let f_bigint: BigUint = TryFrom::try_from(Ff::MODULUS).unwrap();
+    assert!(
+        f_bigint.bits() as usize <= FFA_MAX_MODULUS_BITSIZE,
+        "The FFA circuit supports foreign moduli of at most {FFA_MAX_MODULUS_BITSIZE} bits"
+    );
+    limb_decompose_biguint::<F, LIMB_BITSIZE, FFA_N_LIMBS>(f_bigint)
+}
+
+/// Constraint for one row of FF addition of `a` and `b` modulo `f`:
 ///
 /// - First:        a_0 + b_0 - q * f_0 - r_0 - c_0 * 2^{15} = 0
 /// - Intermediate: a_i + b_i - q * f_i - r_i - c_i * 2^{15} + c_{i-1} = 0
-/// - Last (n=16):  a_n + b_n - q * f_n - r_n                + c_{n-1} = 0
+/// - Last (n=17):  a_n + b_n - q * f_n - r_n                + c_{n-1} = 0
+///
+/// The remainder is then proven to be in the field, that is `r < f`,
+/// by decomposing `r' = r - f + 2^{15 * 18}` into 15-bit limbs, which
+/// is only possible if `r - f` is negative:
 ///
-/// q, c_i ∈ {-1,0,1}
-/// a_i, b_i, f_i, r_i ∈ [0,2^15)
-pub fn constrain_ff_addition_row<
+/// - First:        r_0 - f_0 - r'_0 - d_0 * 2^{15} = 0
+/// - Intermediate: r_i - f_i - r'_i - d_i * 2^{15} + d_{i-1} = 0
+/// - Last (n=17):  r_n - f_n - r'_n + 2^{15}       + d_{n-1} = 0
+///
+/// q, c_i, d_i ∈ {-1,0,1}
+/// a_i, b_i, r_i, r'_i ∈ [0,2^15)
+///
+/// The limbs of f are constants, so any foreign field `Ff` of at most
+/// 256 bits is supported. The inputs `a` and `b` are not required to
+/// be reduced, but there is no valid quotient if `a + b >= 2 * f`.
+pub fn constrain_ff_addition<
     F: PrimeField,
+    Ff: PrimeField,
     Env: ColAccessCap<F, FFAColumn> + LookupCap<F, FFAColumn, LookupTable>,
 >(
     env: &mut Env,
-    limb_num: usize,
 ) {
-    let a: Env::Variable = Env::read_column(env, FFAColumn::InputA(limb_num));
-    let b: Env::Variable = Env::read_column(env, FFAColumn::InputB(limb_num));
-    let f: Env::Variable = Env::read_column(env, FFAColumn::ModulusF(limb_num));
-    let r: Env::Variable = Env::read_column(env, FFAColumn::Remainder(limb_num));
+    let f_limbs: [F; FFA_N_LIMBS] = ff_modulus_limbs::<F, Ff>();
+    let limb_size = Env::constant(From::from((1 << LIMB_BITSIZE) as u64));
+
     let q: Env::Variable = Env::read_column(env, FFAColumn::Quotient);
-    env.lookup(LookupTable::RangeCheck15, vec![a.clone()]);
-    env.lookup(LookupTable::RangeCheck15, vec![b.clone()]);
-    env.lookup(LookupTable::RangeCheck15, vec![f.clone()]);
-    env.lookup(LookupTable::RangeCheck15, vec![r.clone()]);
     env.lookup(LookupTable::RangeCheck1BitSigned, vec![q.clone()]);
-    let constraint = if limb_num == 0 {
-        let limb_size = Env::constant(From::from((1 << LIMB_BITSIZE) as u64));
-        let c0: Env::Variable = Env::read_column(env, FFAColumn::Carry(limb_num));
-        env.lookup(LookupTable::RangeCheck1BitSigned, vec![c0.clone()]);
-        a + b - q * f - r - c0 * limb_size
-    } else if limb_num < N_LIMBS - 1 {
-        let limb_size = Env::constant(From::from((1 << LIMB_BITSIZE) as u64));
-        let c_prev: Env::Variable = Env::read_column(env, FFAColumn::Carry(limb_num - 1));
-        let c_cur: Env::Variable = Env::read_column(env, FFAColumn::Carry(limb_num));
-        env.lookup(LookupTable::RangeCheck1BitSigned, vec![c_prev.clone()]);
-        env.lookup(LookupTable::RangeCheck1BitSigned, vec![c_cur.clone()]);
-        a + b - q * f - r - c_cur * limb_size + c_prev
-    } else {
-        let c_prev: Env::Variable = Env::read_column(env, FFAColumn::Carry(limb_num - 1));
-        env.lookup(LookupTable::RangeCheck1BitSigned, vec![c_prev.clone()]);
-        a + b - q * f - r + c_prev
-    };
-    env.assert_zero(constraint);
+
+    for (limb_i, f_limb) in f_limbs.iter().enumerate() {
+        let a: Env::Variable = Env::read_column(env, FFAColumn::InputA(limb_i));
+        let b: Env::Variable = Env::read_column(env, FFAColumn::InputB(limb_i));
+        let r: Env::Variable = Env::read_column(env, FFAColumn::Remainder(limb_i));
+        let r_bound: Env::Variable = Env::read_column(env, FFAColumn::RemainderBound(limb_i));
+        let f = Env::constant(*f_limb);
+        env.lookup(LookupTable::RangeCheck15, vec![a.clone()]);
+        env.lookup(LookupTable::RangeCheck15, vec![b.clone()]);
+        env.lookup(LookupTable::RangeCheck15, vec![r.clone()]);
+        env.lookup(LookupTable::RangeCheck15, vec![r_bound.clone()]);
+
+        let mut constraint = a + b - q.clone() * f.clone() - r.clone();
+        let mut bound_constraint = r - f - r_bound;
+        if limb_i > 0 {
+            let c_prev: Env::Variable = Env::read_column(env, FFAColumn::Carry(limb_i - 1));
+            let d_prev: Env::Variable = Env::read_column(env, FFAColumn::BoundCarry(limb_i - 1));
+            constraint = constraint + c_prev;
+            bound_constraint = bound_constraint + d_prev;
+        }
+        if limb_i < FFA_N_LIMBS - 1 {
+            let c_cur: Env::Variable = Env::read_column(env, FFAColumn::Carry(limb_i));
+            let d_cur: Env::Variable = Env::read_column(env, FFAColumn::BoundCarry(limb_i));
+            env.lookup(LookupTable::RangeCheck1BitSigned, vec![c_cur.clone()]);
+            env.lookup(LookupTable::RangeCheck1BitSigned, vec![d_cur.clone()]);
+            constraint = constraint - c_cur * limb_size.clone();
+            bound_constraint = bound_constraint - d_cur * limb_size.clone();
+        } else {
+            // The last bound carry is always -1, it is the
+            // 2^{15 * 18} term of r'.
+            bound_constraint = bound_constraint + limb_size.clone();
+        }
+        env.assert_zero(constraint);
+        env.assert_zero(bound_constraint);
+    }
 }
 
-pub fn constrain_ff_addition<
-    F: PrimeField,
-    Env: ColAccessCap<F, FFAColumn> + LookupCap<F, FFAColumn, LookupTable>,
->(
-    env: &mut Env,
-) {
-    for limb_i in 0..N_LIMBS {
-        constrain_ff_addition_row(env, limb_i);
+/// Computes the carries of the limb-wise sum `Σ_i terms[i]`, where
+/// every partial sum plus the previous carry must be a multiple of the
+/// limb size, i.e. -1, 0 or 1 times the limb size.
+fn compute_carries<F: PrimeField>(terms: [F; FFA_N_LIMBS]) -> [F; FFA_N_LIMBS] {
+    let limb_size: F = From::from((1 << LIMB_BITSIZE) as u64);
+    let mut carries = [F::zero(); FFA_N_LIMBS];
+    let mut carry: F = F::zero();
+    for (limb_i, term) in terms.into_iter().enumerate() {
+        let res = term + carry;
+        carry = if res == limb_size {
+            // Overflow
+            F::one()
+        } else if res == -limb_size {
+            // Underflow
+            F::zero() - F::one()
+        } else if res.is_zero() {
+            // Neither overflow nor overflow, the transcendent way of being
+            F::zero()
+        } else {
+            panic!("Computed carry is not -1,0,1, impossible: limb number {limb_i:?}")
+        };
+        carries[limb_i] = carry;
     }
+    carries
 }
 
+/// Computes `r = a + b mod f` where `f` is the modulus of `Ff`, writes
+/// the witness of the FF addition and checks the constraints.
 pub fn ff_addition_circuit<
     F: PrimeField,
     Ff: PrimeField,
@@ -74,63 +127,59 @@ pub fn ff_addition_circuit<
     env: &mut Env,
     a: Ff,
     b: Ff,
-) {
+) -> Ff {
     let f_bigint: BigUint = TryFrom::try_from(Ff::MODULUS).unwrap();
+    let f_limbs: [F; FFA_N_LIMBS] = ff_modulus_limbs::<F, Ff>();
 
-    let a_limbs: [F; N_LIMBS] = limb_decompose_ff::<F, Ff, LIMB_BITSIZE, N_LIMBS>(&a);
-    let b_limbs: [F; N_LIMBS] = limb_decompose_ff::<F, Ff, LIMB_BITSIZE, N_LIMBS>(&b);
-    let f_limbs: [F; N_LIMBS] =
-        limb_decompose_biguint::<F, LIMB_BITSIZE, N_LIMBS>(f_bigint.clone());
+    let a_limbs: [F; FFA_N_LIMBS] = limb_decompose_ff::<F, Ff, LIMB_BITSIZE, FFA_N_LIMBS>(&a);
+    let b_limbs: [F; FFA_N_LIMBS] = limb_decompose_ff::<F, Ff, LIMB_BITSIZE, FFA_N_LIMBS>(&b);
     a_limbs.iter().enumerate().for_each(|(i, var)| {
         env.write_column(FFAColumn::InputA(i), &Env::constant(*var));
     });
     b_limbs.iter().enumerate().for_each(|(i, var)| {
         env.write_column(FFAColumn::InputB(i), &Env::constant(*var));
     });
-    f_limbs.iter().enumerate().for_each(|(i, var)| {
-        env.write_column(FFAColumn::ModulusF(i), &Env::constant(*var));
-    });
 
     let a_bigint = FieldHelpers::to_biguint(&a);
     let b_bigint = FieldHelpers::to_biguint(&b);
 
-    // TODO FIXME this computation must be done over BigInts, not BigUInts
-    // q can be -1! But only in subtraction, so for now we don't care.
-    // for now with addition only q ∈ {0,1}
+    // Inputs are field elements, so a + b < 2 * f and q ∈ {0,1}.
     let (q_bigint, r_bigint) = (a_bigint + b_bigint).div_rem(&f_bigint);
-    let r_limbs: [F; N_LIMBS] = limb_decompose_biguint::<F, LIMB_BITSIZE, N_LIMBS>(r_bigint);
-    // We expect just one limb.
-    let q: F = limb_decompose_biguint::<F, LIMB_BITSIZE, N_LIMBS>(q_bigint)[0];
+    let q: F = F::from_biguint(&q_bigint).unwrap();
+    let r_limbs: [F; FFA_N_LIMBS] =
+        limb_decompose_biguint::<F, LIMB_BITSIZE, FFA_N_LIMBS>(r_bigint.clone());
+    let r_bound_bigint: BigUint =
+        r_bigint.clone() + (BigUint::from(1u64) << (LIMB_BITSIZE * FFA_N_LIMBS)) - f_bigint;
+    let r_bound_limbs: [F; FFA_N_LIMBS] =
+        limb_decompose_biguint::<F, LIMB_BITSIZE, FFA_N_LIMBS>(r_bound_bigint);
 
     env.write_column(FFAColumn::Quotient, &Env::constant(q));
     r_limbs.iter().enumerate().for_each(|(i, var)| {
         env.write_column(FFAColumn::Remainder(i), &Env::constant(*var));
     });
+    r_bound_limbs.iter().enumerate().for_each(|(i, var)| {
+        env.write_column(FFAColumn::RemainderBound(i), &Env::constant(*var));
+    });
 
-    let limb_size: F = From::from((1 << LIMB_BITSIZE) as u64);
-    let mut carry: F = From::from(0u64);
-    for limb_i in 0..N_LIMBS {
-        let res = a_limbs[limb_i] + b_limbs[limb_i] - q * f_limbs[limb_i] - r_limbs[limb_i] + carry;
-        let newcarry: F = if res == limb_size {
-            // Overflow
-            F::one()
-        } else if res == -limb_size {
-            // Underflow
-            F::zero() - F::one()
-        } else if res.is_zero() {
-            // Neither overflow nor overflow, the transcendent way of being
-            F::zero()
-        } else {
-            panic!("Computed carry is not -1,0,1, impossible: limb number {limb_i:?}")
-        };
-        // Last carry should be zero, otherwise we record it
-        if limb_i < N_LIMBS - 1 {
-            env.write_column(FFAColumn::Carry(limb_i), &Env::constant(newcarry));
-            carry = newcarry;
-        } else {
-            // should this be in circiut?
-            assert!(newcarry.is_zero());
-        }
-        constrain_ff_addition_row(env, limb_i);
+    let carries = compute_carries(std::array::from_fn(|i| {
+        a_limbs[i] + b_limbs[i] - q * f_limbs[i] - r_limbs[i]
+    }));
+    // The last carry must be zero, since a + b - q * f - r = 0.
+    assert!(carries[FFA_N_LIMBS - 1].is_zero());
+    let bound_carries = compute_carries(std::array::from_fn(|i| {
+        r_limbs[i] - f_limbs[i] - r_bound_limbs[i]
+    }));
+    // The last bound carry must be -1, since r - f - r' = -2^{15 * 18}.
+    assert!(bound_carries[FFA_N_LIMBS - 1] == F::zero() - F::one());
+    for limb_i in 0..FFA_N_LIMBS - 1 {
+        env.write_column(FFAColumn::Carry(limb_i), &Env::constant(carries[limb_i]));
+        env.write_column(
+            FFAColumn::BoundCarry(limb_i),
+            &Env::constant(bound_carries[limb_i]),
+        );
     }
+
+    constrain_ff_addition::<F, Ff, Env>(env);
+
+    Ff::from_biguint(&r_bigint).unwrap()
 }
@@ -6,19 +6,32 @@ pub mod lookups;
 mod tests {
 
     use crate::{
-        circuit_design::{ConstraintBuilderEnv, WitnessBuilderEnv},
+        circuit_design::{ColWriteCap, ConstraintBuilderEnv, WitnessBuilderEnv},
         columns::ColumnIndexer,
         ffa::{
-            columns::FFAColumn,
+            columns::{FFAColumn, FFA_N_LIMBS},
             interpreter::{self as ffa_interpreter},
             lookups::LookupTable,
         },
         logup::LookupTableID,
         Ff1, Fp,
     };
-    use ark_ff::UniformRand;
+    use ark_ff::{
+        fields::{MontBackend, MontConfig},
+        Fp256, One, PrimeField, UniformRand, Zero,
+    };
     use rand::{CryptoRng, RngCore};
-    use std::collections::BTreeMap;
+    use std::collections::{BTreeMap, HashMap};
+
+    /// The base field of secp256k1, which is a 256 bits modulus.
+    #[derive(MontConfig)]
+    #[modulus = "115792089237316195423570985008687907853269984665640564039457584007908834671663"]
+    #[generator = "3"]
+    pub struct Secp256k1BaseConfig;
+    pub type Secp256k1Base = Fp256<MontBackend<Secp256k1BaseConfig, 4>>;
+
+    /// The base field of BN254, which is larger than [`Fp`].
+    type BN254Base = ark_bn254::Fq;
 
     type FFAWitnessBuilderEnv = WitnessBuilderEnv<
         Fp,
@@ -33,43 +46,37 @@ mod tests {
     /// Builds the FF addition circuit with random values. The witness
     /// environment enforces the constraints internally, so it is
     /// enough to just build the circuit to ensure it is satisfied.
-    fn build_ffa_circuit<RNG: RngCore + CryptoRng>(
+    fn build_ffa_circuit<Ff: PrimeField, RNG: RngCore + CryptoRng>(
         rng: &mut RNG,
         domain_size: usize,
     ) -> FFAWitnessBuilderEnv {
         let mut witness_env = FFAWitnessBuilderEnv::create();
 
-        for _row_i in 0..domain_size {
-            let a: Ff1 = <Ff1 as UniformRand>::rand(rng);
-            let b: Ff1 = <Ff1 as UniformRand>::rand(rng);
-
-            //use rand::Rng;
-            //let a: Ff1 = From::from(rng.gen_range(0..(1 << 50)));
-            //let b: Ff1 = From::from(rng.gen_range(0..(1 << 50)));
-            ffa_interpreter::ff_addition_circuit(&mut witness_env, a, b);
+        for row_i in 0..domain_size {
+            // Also cover the edge cases of the reduction.
+            let (a, b): (Ff, Ff) = match row_i {
+                0 => (Ff::zero(), Ff::zero()),
+                1 => (-Ff::one(), Ff::one()),
+                2 => (-Ff::one(), -Ff::one()),
+                _ => (UniformRand::rand(rng), UniformRand::rand(rng)),
+            };
+            let r = ffa_interpreter::ff_addition_circuit(&mut witness_env, a, b);
+            assert_eq!(r, a + b);
             witness_env.next_row();
         }
 
         witness_env
     }
 
-    #[test]
-    /// Tests if FFA circuit is valid.
-    pub fn test_ffa_circuit() {
-        let mut rng = o1_utils::tests::make_test_rng(None);
-        build_ffa_circuit(&mut rng, 1 << 4);
-    }
-
-    #[test]
-    pub fn heavy_test_ffa_completeness() {
+    fn test_ffa_completeness<Ff: PrimeField>() {
         let mut rng = o1_utils::tests::make_test_rng(None);
         let domain_size = 1 << 15; // Otherwise we can't do 15-bit lookups.
 
         let mut constraint_env = ConstraintBuilderEnv::<Fp, LookupTable>::create();
-        ffa_interpreter::constrain_ff_addition(&mut constraint_env);
+        ffa_interpreter::constrain_ff_addition::<Fp, Ff, _>(&mut constraint_env);
         let constraints = constraint_env.get_constraints();
 
-        let witness_env = build_ffa_circuit(&mut rng, domain_size);
+        let witness_env = build_ffa_circuit::<Ff, _>(&mut rng, domain_size);
 
         // Fixed tables can be generated inside lookup_tables_data. Runtime should be generated here.
         let mut lookup_tables_data = BTreeMap::new();
@@ -100,4 +107,67 @@ mod tests {
             &mut rng,
         );
     }
+
+    #[test]
+    /// Tests if FFA circuit is valid.
+    pub fn test_ffa_circuit() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        build_ffa_circuit::<Ff1, _>(&mut rng, 1 << 4);
+        build_ffa_circuit::<Secp256k1Base, _>(&mut rng, 1 << 4);
+        build_ffa_circuit::<BN254Base, _>(&mut rng, 1 << 4);
+    }
+
+    #[test]
+    pub fn test_regression_relation_constraints_ffa() {
+        let mut constraint_env = ConstraintBuilderEnv::<Fp, LookupTable>::create();
+        ffa_interpreter::constrain_ff_addition::<Fp, Secp256k1Base, _>(&mut constraint_env);
+        let constraints = constraint_env.get_relation_constraints();
+
+        let mut constraints_degrees = HashMap::new();
+
+        assert_eq!(constraints.len(), 36);
+
+        constraints.iter().for_each(|c| {
+            let degree = c.degree(1, 0);
+            *constraints_degrees.entry(degree).or_insert(0) += 1;
+        });
+
+        // The modulus is a constant, so all the constraints are linear.
+        assert_eq!(constraints_degrees.get(&1), Some(&36));
+    }
+
+    #[test]
+    #[should_panic]
+    /// The remainder must be reduced modulo the foreign field.
+    pub fn test_ffa_unreduced_remainder() {
+        let mut witness_env = FFAWitnessBuilderEnv::create();
+        ffa_interpreter::ff_addition_circuit(&mut witness_env, -Ff1::one(), Ff1::one());
+
+        // Instead of q = 1 and r = 0, claim q = 0 and r = f, which
+        // satisfies a + b = q * f + r without any carry.
+        let f_limbs = ffa_interpreter::ff_modulus_limbs::<Fp, Ff1>();
+        witness_env.write_column(FFAColumn::Quotient, &Fp::zero());
+        for (i, f_i) in f_limbs.iter().enumerate() {
+            witness_env.write_column(FFAColumn::Remainder(i), f_i);
+        }
+        for i in 0..FFA_N_LIMBS - 1 {
+            witness_env.write_column(FFAColumn::Carry(i), &Fp::zero());
+        }
+        ffa_interpreter::constrain_ff_addition::<Fp, Ff1, _>(&mut witness_env);
+    }
+
+    #[test]
+    pub fn heavy_test_ffa_completeness() {
+        test_ffa_completeness::<Ff1>();
+    }
+
+    #[test]
+    pub fn heavy_test_ffa_completeness_secp256k1() {
+        test_ffa_completeness::<Secp256k1Base>();
+    }
+
+    #[test]
+    pub fn heavy_test_ffa_completeness_bn254() {
+        test_ffa_completeness::<BN254Base>();
+    }
 }
@@ -0,0 +1,53 @@
+use crate::{
+    columns::{Column, ColumnIndexer},
+    serialization::interpreter::N_LIMBS_SMALL,
+};
+
+/// Number of columns in the FFM circuit.
+pub const FFM_N_COLUMNS: usize = 5 * N_LIMBS_SMALL + 2 * N_LIMBS_SMALL + 2 + (N_LIMBS_SMALL - 1);
+
+/// Column indexer for the FF multiplication circuit.
+///
+/// They represent the equations
+///   `InputA * InputB = Quotient * f + Remainder`
+///   `Remainder(i) - f(i) = RemainderBound(i) + BoundCarry(i) * 2^LIMB_SIZE - BoundCarry(i-1)`
+/// over the integers, where `f` is the (constant) foreign modulus. As in
+/// the FFA circuit, the second one ensures that `Remainder < f`. All
+/// the values are in 17 limbs of 15 bits, except for the carries which
+/// are stored in the signed format of the FEC circuits, that is six
+/// small limbs per large carry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FFMColumn {
+    InputA(usize),         // 17
+    InputB(usize),         // 17
+    Remainder(usize),      // 17
+    Quotient(usize),       // 17
+    RemainderBound(usize), // 17
+    Carry(usize),          // 36
+    BoundCarry(usize),     // 16
+}
+
+impl ColumnIndexer for FFMColumn {
+    const N_COL: usize = FFM_N_COLUMNS;
+    fn to_column(self) -> Column {
+        let to_column_inner = |offset, i| {
+            assert!(i < N_LIMBS_SMALL);
+            Column::Relation(N_LIMBS_SMALL * offset + i)
+        };
+        match self {
+            FFMColumn::InputA(i) => to_column_inner(0, i),
+            FFMColumn::InputB(i) => to_column_inner(1, i),
+            FFMColumn::Remainder(i) => to_column_inner(2, i),
+            FFMColumn::Quotient(i) => to_column_inner(3, i),
+            FFMColumn::RemainderBound(i) => to_column_inner(4, i),
+            FFMColumn::Carry(i) => {
+                assert!(i < 2 * N_LIMBS_SMALL + 2);
+                Column::Relation(5 * N_LIMBS_SMALL + i)
+            }
+            FFMColumn::BoundCarry(i) => {
+                assert!(i < N_LIMBS_SMALL - 1);
+                Column::Relation(7 * N_LIMBS_SMALL + 2 + i)
+            }
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{
+    circuit_design::{
+        capabilities::{read_column_array, write_column_array_const},
+        ColAccessCap, ColWriteCap, LookupCap,
+    },
+    fec::{interpreter::compute_carry_limbs_small, lookups::LookupTable},
+    ffm::columns::FFMColumn,
+    serialization::interpreter::{
+        combine_carry, combine_small_to_large, fold_choice2, limb_decompose_biguint,
+        limb_decompose_ff, LIMB_BITSIZE_LARGE, LIMB_BITSIZE_SMALL, N_LIMBS_LARGE, N_LIMBS_SMALL,
+    },
+};
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use num_integer::Integer;
+use o1_utils::field_helpers::FieldHelpers;
+
+/// Constraints for one row of FF multiplication of `a` and `b` modulo
+/// `f`, that is `a * b - q * f - r = 0` over the integers.
+///
+/// As in `constrain_ec_addition`, the equation is checked on large
+/// 75-bit limbs, which are recombined from the range-checked 15-bit
+/// limbs of the columns:
+///
+/// for i ∈ [0..2L-2]:
+///    \sum_{j,k < L | k+j = i} a_j b_k
+///       - (r_i if i < L else 0)
+///       - \sum_{j,k < L | k+j = i} q_j f_k
+///       - (c_i * 2^B if i < 2L-2 else 0)
+///       + (c_{i-1} if i > 0 else 0) = 0
+///
+/// The inputs `a` and `b` are not required to be reduced, but there is
+/// no valid quotient if `a * b >= 2^{15 * 17} * f`. For a, b < f, the
+/// quotient satisfies 0 <= q < f, so unlike the FEC equations it does
+/// not need a sign. The limbs of f are
+/// constants, and the carries are at most 78 bits, so they fit into
+/// the signed carry format of the FEC circuits.
+///
+/// As in `constrain_ff_addition`, the remainder is then proven to be in
+/// the field, that is `r < f`, by decomposing `r' = r - f + 2^{15 * 17}`
+/// into 15-bit limbs, which is only possible if `r - f` is negative:
+///
+/// - First:        r_0 - f_0 - r'_0 - d_0 * 2^{15} = 0
+/// - Intermediate: r_i - f_i - r'_i - d_i * 2^{15} + d_{i-1} = 0
+/// - Last (n=16):  r_n - f_n - r'_n + 2^{15}       + d_{n-1} = 0
+///
+/// with d_i ∈ {-1,0,1}, and r_i, r'_i ∈ [0,2^15).
+pub fn constrain_ff_multiplication<
+    F: PrimeField,
+    Ff: PrimeField,
+    Env: ColAccessCap<F, FFMColumn> + LookupCap<F, FFMColumn, LookupTable<Ff>>,
+>(
+    env: &mut Env,
+) {
+    let a_limbs_small: [_; N_LIMBS_SMALL] = read_column_array(env, FFMColumn::InputA);
+    let b_limbs_small: [_; N_LIMBS_SMALL] = read_column_array(env, FFMColumn::InputB);
+    let r_limbs_small: [_; N_LIMBS_SMALL] = read_column_array(env, FFMColumn::Remainder);
+    let q_limbs_small: [_; N_LIMBS_SMALL] = read_column_array(env, FFMColumn::Quotient);
+    let r_bound_limbs_small: [_; N_LIMBS_SMALL] = read_column_array(env, FFMColumn::RemainderBound);
+    let carry_limbs_small: [_; 2 * N_LIMBS_SMALL + 2] = read_column_array(env, FFMColumn::Carry);
+    let bound_carry_limbs_small: [_; N_LIMBS_SMALL - 1] =
+        read_column_array(env, FFMColumn::BoundCarry);
+
+    // Limbs of the inputs, of the quotient, of the remainder and of its
+    // bound must fit into 15 bits.
+    for x in a_limbs_small
+        .iter()
+        .chain(b_limbs_small.iter())
+        .chain(q_limbs_small.iter())
+        .chain(r_limbs_small.iter())
+        .chain(r_bound_limbs_small.iter())
+    {
+        env.lookup(LookupTable::RangeCheck15, vec![x.clone()]);
+    }
+
+    for x in bound_carry_limbs_small.iter() {
+        env.lookup(LookupTable::RangeCheck14Abs, vec![x.clone()]);
+    }
+
+    for (i, x) in carry_limbs_small.iter().enumerate() {
+        if i % 6 == 5 {
+            env.lookup(LookupTable::RangeCheck9Abs, vec![x.clone()]);
+        } else {
+            env.lookup(LookupTable::RangeCheck14Abs, vec![x.clone()]);
+        }
+    }
+
+    let f_bui: BigUint = TryFrom::try_from(Ff::MODULUS).unwrap();
+    let f_limbs_small: [F; N_LIMBS_SMALL] =
+        limb_decompose_biguint::<F, LIMB_BITSIZE_SMALL, N_LIMBS_SMALL>(f_bui.clone());
+    let f_limbs_large: [Env::Variable; N_LIMBS_LARGE] =
+        limb_decompose_biguint::<F, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(f_bui)
+            .map(|f_i| Env::constant(f_i));
+
+    let limb_size_small: Env::Variable = Env::constant(From::from(1u64 << LIMB_BITSIZE_SMALL));
+    for i in 0..N_LIMBS_SMALL {
+        let mut bound_constraint = r_limbs_small[i].clone()
+            - Env::constant(f_limbs_small[i])
+            - r_bound_limbs_small[i].clone();
+        if i > 0 {
+            bound_constraint = bound_constraint + bound_carry_limbs_small[i - 1].clone();
+        }
+        if i < N_LIMBS_SMALL - 1 {
+            bound_constraint =
+                bound_constraint - bound_carry_limbs_small[i].clone() * limb_size_small.clone();
+        } else {
+            // The last bound carry is always -1, it is the
+            // 2^{15 * 17} term of r'.
+            bound_constraint = bound_constraint + limb_size_small.clone();
+        }
+        env.assert_zero(bound_constraint);
+    }
+
+    let a_limbs_large = combine_small_to_large::<_, _, Env>(a_limbs_small);
+    let b_limbs_large = combine_small_to_large::<_, _, Env>(b_limbs_small);
+    let r_limbs_large = combine_small_to_large::<_, _, Env>(r_limbs_small);
+    let q_limbs_large = combine_small_to_large::<_, _, Env>(q_limbs_small);
+    let carry_limbs_large: [_; 2 * N_LIMBS_LARGE - 2] =
+        combine_carry::<F, _, Env>(carry_limbs_small);
+
+    let limb_size_large: Env::Variable = Env::constant(From::from(1u128 << LIMB_BITSIZE_LARGE));
+
+    for i in 0..2 * N_LIMBS_LARGE - 1 {
+        let mut constraint = fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+            a_limbs_large[j].clone() * b_limbs_large[k].clone()
+        });
+        if i < N_LIMBS_LARGE {
+            constraint = constraint - r_limbs_large[i].clone();
+        }
+        constraint = constraint
+            - fold_choice2(N_LIMBS_LARGE, i, |j, k| {
+                q_limbs_large[j].clone() * f_limbs_large[k].clone()
+            });
+        if i > 0 {
+            constraint = constraint + carry_limbs_large[i - 1].clone();
+        }
+        if i < 2 * N_LIMBS_LARGE - 2 {
+            constraint = constraint - carry_limbs_large[i].clone() * limb_size_large.clone();
+        }
+        env.assert_zero(constraint);
+    }
+}
+
+/// Computes `r = a * b mod f` where `f` is the modulus of `Ff`, writes
+/// the witness of the FF multiplication and checks the constraints.
+///
+/// This function is witness-generation counterpart (called by the prover) of
+/// `constrain_ff_multiplication` -- see the documentation of the latter.
+pub fn ff_multiplication_circuit<
+    F: PrimeField,
+    Ff: PrimeField,
+    Env: ColAccessCap<F, FFMColumn>
+        + ColWriteCap<F, FFMColumn>
+        + LookupCap<F, FFMColumn, LookupTable<Ff>>,
+>(
+    env: &mut Env,
+    a: Ff,
+    b: Ff,
+) -> Ff {
+    let f_bui: BigUint = TryFrom::try_from(Ff::MODULUS).unwrap();
+
+    let (q_bui, r_bui) = (a.to_biguint() * b.to_biguint()).div_rem(&f_bui);
+
+    let a_limbs_small: [F; N_LIMBS_SMALL] =
+        limb_decompose_ff::<F, Ff, LIMB_BITSIZE_SMALL, N_LIMBS_SMALL>(&a);
+    let b_limbs_small: [F; N_LIMBS_SMALL] =
+        limb_decompose_ff::<F, Ff, LIMB_BITSIZE_SMALL, N_LIMBS_SMALL>(&b);
+    let r_limbs_small: [F; N_LIMBS_SMALL] =
+        limb_decompose_biguint::<F, LIMB_BITSIZE_SMALL, N_LIMBS_SMALL>(r_bui.clone());
+    let q_limbs_small: [F; N_LIMBS_SMALL] =
+        limb_decompose_biguint::<F, LIMB_BITSIZE_SMALL, N_LIMBS_SMALL>(q_bui.clone());
+
+    write_column_array_const(env, &a_limbs_small, FFMColumn::InputA);
+    write_column_array_const(env, &b_limbs_small, FFMColumn::InputB);
+    write_column_array_const(env, &r_limbs_small, FFMColumn::Remainder);
+    write_column_array_const(env, &q_limbs_small, FFMColumn::Quotient);
+
+    let f_limbs_small: [F; N_LIMBS_SMALL] =
+        limb_decompose_biguint::<F, LIMB_BITSIZE_SMALL, N_LIMBS_SMALL>(f_bui.clone());
+    let r_bound_bui: BigUint =
+        r_bui.clone() + (BigUint::from(1u64) << (LIMB_BITSIZE_SMALL * N_LIMBS_SMALL)) - &f_bui;
+    let r_bound_limbs_small: [F; N_LIMBS_SMALL] =
+        limb_decompose_biguint::<F, LIMB_BITSIZE_SMALL, N_LIMBS_SMALL>(r_bound_bui);
+    write_column_array_const(env, &r_bound_limbs_small, FFMColumn::RemainderBound);
+
+    // Each partial difference plus the previous carry is -1, 0 or 1
+    // times the limb size, and the last carry is -1.
+    let limb_size_small: F = From::from(1u64 << LIMB_BITSIZE_SMALL);
+    let mut bound_carry_limbs_small = [F::zero(); N_LIMBS_SMALL - 1];
+    let mut bound_carry = F::zero();
+    for i in 0..N_LIMBS_SMALL - 1 {
+        let res = r_limbs_small[i] - f_limbs_small[i] - r_bound_limbs_small[i] + bound_carry;
+        bound_carry = res / limb_size_small;
+        bound_carry_limbs_small[i] = bound_carry;
+    }
+    write_column_array_const(env, &bound_carry_limbs_small, FFMColumn::BoundCarry);
+
+    let a_limbs_large: [F; N_LIMBS_LARGE] =
+        limb_decompose_ff::<F, Ff, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(&a);
+    let b_limbs_large: [F; N_LIMBS_LARGE] =
+        limb_decompose_ff::<F, Ff, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(&b);
+    let r_limbs_large: [F; N_LIMBS_LARGE] =
+        limb_decompose_biguint::<F, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(r_bui.clone());
+    let q_limbs_large: [F; N_LIMBS_LARGE] =
+        limb_decompose_biguint::<F, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(q_bui);
+    let f_limbs_large: [F; N_LIMBS_LARGE] =
+        limb_decompose_biguint::<F, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(f_bui);
+
+    let carry_limbs_small = compute_carry_limbs_small::<F>(core::array::from_fn(|i| {
+        let mut res = fold_choice2(N_LIMBS_LARGE, i, |j, k| a_limbs_large[j] * b_limbs_large[k]);
+        if i < N_LIMBS_LARGE {
+            res -= r_limbs_large[i];
+        }
+        res - fold_choice2(N_LIMBS_LARGE, i, |j, k| q_limbs_large[j] * f_limbs_large[k])
+    }));
+    write_column_array_const(env, &carry_limbs_small, FFMColumn::Carry);
+
+    constrain_ff_multiplication::<F, Ff, Env>(env);
+
+    Ff::from_biguint(&r_bui).unwrap()
+}
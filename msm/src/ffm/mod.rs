@@ -0,0 +1,203 @@
+pub mod columns;
+pub mod interpreter;
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        circuit_design::{
+            capabilities::write_column_array_const, ConstraintBuilderEnv, WitnessBuilderEnv,
+        },
+        columns::ColumnIndexer,
+        fec::{interpreter::compute_carry_limbs_small, lookups::LookupTable},
+        ffm::{
+            columns::{FFMColumn, FFM_N_COLUMNS},
+            interpreter::{constrain_ff_multiplication, ff_multiplication_circuit},
+        },
+        logup::LookupTableID,
+        serialization::interpreter::{
+            fold_choice2, limb_decompose_biguint, limb_decompose_ff, LIMB_BITSIZE_LARGE,
+            LIMB_BITSIZE_SMALL, N_LIMBS_LARGE, N_LIMBS_SMALL,
+        },
+        Ff1, Fp,
+    };
+    use ark_ff::{Field, PrimeField, UniformRand, Zero};
+    use num_bigint::BigUint;
+    use rand::{CryptoRng, RngCore};
+    use std::collections::{BTreeMap, HashMap};
+
+    /// The base field of BN254, which is larger than [`Fp`].
+    type BN254Base = ark_bn254::Fq;
+
+    type FFMWitnessBuilderEnv<Ff> = WitnessBuilderEnv<
+        Fp,
+        FFMColumn,
+        { <FFMColumn as ColumnIndexer>::N_COL },
+        { <FFMColumn as ColumnIndexer>::N_COL },
+        0,
+        0,
+        LookupTable<Ff>,
+    >;
+
+    /// Builds the FF multiplication circuit with random values. The
+    /// witness environment enforces the constraints internally, so it
+    /// is enough to just build the circuit to ensure it is satisfied.
+    fn build_ffm_circuit<Ff: PrimeField, RNG: RngCore + CryptoRng>(
+        rng: &mut RNG,
+        domain_size: usize,
+    ) -> FFMWitnessBuilderEnv<Ff> {
+        let mut witness_env = FFMWitnessBuilderEnv::<Ff>::create();
+
+        for row_i in 0..domain_size {
+            // Also cover the edge cases of the reduction.
+            let (a, b): (Ff, Ff) = match row_i {
+                0 => (Ff::zero(), UniformRand::rand(rng)),
+                1 => (-Ff::one(), -Ff::one()),
+                _ => (UniformRand::rand(rng), UniformRand::rand(rng)),
+            };
+            let r = ff_multiplication_circuit(&mut witness_env, a, b);
+            assert_eq!(r, a * b);
+
+            if row_i < domain_size - 1 {
+                witness_env.next_row();
+            }
+        }
+
+        witness_env
+    }
+
+    fn test_ffm_completeness<Ff: PrimeField>() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let domain_size = 1 << 15; // Otherwise we can't do 15-bit lookups.
+
+        let mut constraint_env = ConstraintBuilderEnv::<Fp, LookupTable<Ff>>::create();
+        constrain_ff_multiplication::<Fp, Ff, _>(&mut constraint_env);
+        let constraints = constraint_env.get_constraints();
+
+        let witness_env = build_ffm_circuit::<Ff, _>(&mut rng, domain_size);
+
+        let mut lookup_tables_data = BTreeMap::new();
+        for table_id in LookupTable::<Ff>::all_variants()
+            .into_iter()
+            .filter(|table_id| table_id.is_fixed())
+        {
+            lookup_tables_data.insert(
+                table_id,
+                vec![table_id
+                    .entries(domain_size as u64)
+                    .into_iter()
+                    .map(|x| vec![x])
+                    .collect()],
+            );
+        }
+        let proof_inputs = witness_env.get_proof_inputs(domain_size, lookup_tables_data);
+
+        crate::test::test_completeness_generic::<
+            FFM_N_COLUMNS,
+            FFM_N_COLUMNS,
+            0,
+            0,
+            LookupTable<Ff>,
+            _,
+        >(
+            constraints,
+            Box::new([]),
+            proof_inputs,
+            domain_size,
+            &mut rng,
+        );
+    }
+
+    #[test]
+    pub fn test_ffm_circuit() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        build_ffm_circuit::<Ff1, _>(&mut rng, 1 << 4);
+        build_ffm_circuit::<BN254Base, _>(&mut rng, 1 << 4);
+    }
+
+    #[test]
+    pub fn test_regression_relation_constraints_ffm() {
+        let mut constraint_env = ConstraintBuilderEnv::<Fp, LookupTable<Ff1>>::create();
+        constrain_ff_multiplication::<Fp, Ff1, _>(&mut constraint_env);
+        let constraints = constraint_env.get_relation_constraints();
+
+        let mut constraints_degrees = HashMap::new();
+
+        assert_eq!(constraints.len(), 24);
+
+        constraints.iter().for_each(|c| {
+            let degree = c.degree(1, 0);
+            *constraints_degrees.entry(degree).or_insert(0) += 1;
+        });
+
+        // The modulus is a constant, so only a * b is quadratic, and the
+        // bound of the remainder is linear.
+        assert_eq!(constraints_degrees.get(&1), Some(&17));
+        assert_eq!(constraints_degrees.get(&2), Some(&7));
+    }
+
+    #[test]
+    #[should_panic]
+    /// The remainder must be reduced modulo the foreign field.
+    pub fn test_ffm_unreduced_remainder() {
+        let mut witness_env = FFMWitnessBuilderEnv::<Ff1>::create();
+        // As integers, a * b = f + 1, so q = 1 and r = 1.
+        let a = Ff1::from(2u64);
+        let b = a.inverse().unwrap();
+        ff_multiplication_circuit(&mut witness_env, a, b);
+
+        // Instead, claim q = 0 and r = f + 1, which satisfies
+        // a * b = q * f + r with the right carries.
+        let f_bui: BigUint = TryFrom::try_from(Ff1::MODULUS).unwrap();
+        let r_bui = &f_bui + 1u64;
+        let r_limbs_small: [Fp; N_LIMBS_SMALL] =
+            limb_decompose_biguint::<Fp, LIMB_BITSIZE_SMALL, N_LIMBS_SMALL>(r_bui.clone());
+        write_column_array_const(&mut witness_env, &r_limbs_small, FFMColumn::Remainder);
+        write_column_array_const(
+            &mut witness_env,
+            &[Fp::zero(); N_LIMBS_SMALL],
+            FFMColumn::Quotient,
+        );
+        let a_limbs_large: [Fp; N_LIMBS_LARGE] =
+            limb_decompose_ff::<Fp, Ff1, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(&a);
+        let b_limbs_large: [Fp; N_LIMBS_LARGE] =
+            limb_decompose_ff::<Fp, Ff1, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(&b);
+        let r_limbs_large: [Fp; N_LIMBS_LARGE] =
+            limb_decompose_biguint::<Fp, LIMB_BITSIZE_LARGE, N_LIMBS_LARGE>(r_bui.clone());
+        let carry_limbs_small = compute_carry_limbs_small::<Fp>(core::array::from_fn(|i| {
+            let res = fold_choice2(N_LIMBS_LARGE, i, |j, k| a_limbs_large[j] * b_limbs_large[k]);
+            if i < N_LIMBS_LARGE {
+                res - r_limbs_large[i]
+            } else {
+                res
+            }
+        }));
+        write_column_array_const(&mut witness_env, &carry_limbs_small, FFMColumn::Carry);
+
+        // The bound r - f + 2^{15 * 17} does not fit into the limbs,
+        // and its truncation makes the last bound carry 0 instead of -1.
+        let r_bound_limbs_small: [Fp; N_LIMBS_SMALL] =
+            limb_decompose_biguint::<Fp, LIMB_BITSIZE_SMALL, N_LIMBS_SMALL>(r_bui - f_bui);
+        write_column_array_const(
+            &mut witness_env,
+            &r_bound_limbs_small,
+            FFMColumn::RemainderBound,
+        );
+        write_column_array_const(
+            &mut witness_env,
+            &[Fp::zero(); N_LIMBS_SMALL - 1],
+            FFMColumn::BoundCarry,
+        );
+        constrain_ff_multiplication::<Fp, Ff1, _>(&mut witness_env);
+    }
+
+    #[test]
+    pub fn heavy_test_ffm_completeness() {
+        test_ffm_completeness::<Ff1>();
+    }
+
+    #[test]
+    pub fn heavy_test_ffm_completeness_bn254() {
+        test_ffm_completeness::<BN254Base>();
+    }
+}
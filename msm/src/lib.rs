@@ -25,6 +25,7 @@ pub mod witness;
 
 pub mod fec;
 pub mod ffa;
+pub mod ffm;
 pub mod serialization;
 pub mod test;
 
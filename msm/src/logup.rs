@@ -128,6 +128,8 @@
 //!
 //! To summarize, the prover will:
 //! - commit to the multiplicities m.
+//! - commit to the columns of the runtime tables, whose values are chosen by
+//! the prover.
 //! - commit to individual looked-up values f (which include the table t) which
 //! should be already included in the PlonK protocol as columns.
 //! - coin an evaluation point β.
@@ -282,6 +284,9 @@ pub struct LookupProof<T, ID> {
     pub(crate) sum: T,
     /// All fixed lookup tables values, indexed by their ID
     pub(crate) fixed_tables: BTreeMap<ID, T>,
+    /// The columns of the runtime tables, one per position in the
+    /// value-vector, indexed by their ID
+    pub(crate) runtime_tables: BTreeMap<ID, Vec<T>>,
 }
 
 /// Iterator implementation to abstract the content of the structure.
@@ -301,6 +306,10 @@ impl<'lt, G, ID: LookupTableID> IntoIterator for &'lt LookupProof<G, ID> {
         self.fixed_tables
             .values()
             .for_each(|t| iter_contents.push(t));
+        // Runtime tables
+        self.runtime_tables
+            .values()
+            .for_each(|t| iter_contents.extend(t));
         iter_contents.into_iter()
     }
 }
@@ -437,13 +446,29 @@ pub fn constraint_lookups<F: PrimeField, ID: LookupTableID>(
             })
             .collect();
 
-        if table_id.is_fixed() || table_id.runtime_create_column() {
+        if table_id.is_fixed() {
             let table_lookup = Logup {
                 table_id: *table_id,
                 numerator: -curr_cell(Column::LookupMultiplicity((table_id_u32, 0))),
                 value: vec![curr_cell(Column::LookupFixedTable(table_id_u32))],
             };
             lookups.push(table_lookup);
+        } else if table_id.runtime_create_column() {
+            // The entries of the table have the width of the values read
+            // from it, each position being a committed column.
+            let width = reads[0].len();
+            assert!(
+                reads.iter().all(|value| value.len() == width),
+                "All the values read from the runtime table {table_id:?} must have the same length"
+            );
+            let table_lookup = Logup {
+                table_id: *table_id,
+                numerator: -curr_cell(Column::LookupMultiplicity((table_id_u32, 0))),
+                value: (0..width)
+                    .map(|i| curr_cell(Column::LookupRuntimeTable((table_id_u32, i))))
+                    .collect(),
+            };
+            lookups.push(table_lookup);
         } else {
             lookup_writes
                 .get(table_id)
@@ -509,6 +534,9 @@ pub mod prover {
         pub lookup_counters_evals_d8: &'a BTreeMap<ID, Vec<Evaluations<F, D<F>>>>,
         /// The evaluations of the fixed tables, over d8, indexed by the table ID.
         pub fixed_tables_evals_d8: &'a BTreeMap<ID, Evaluations<F, D<F>>>,
+        /// The evaluations of the columns of the runtime tables, over d8,
+        /// indexed by the table ID.
+        pub runtime_tables_evals_d8: &'a BTreeMap<ID, Vec<Evaluations<F, D<F>>>>,
     }
 
     /// Represents the environment for the logup argument.
@@ -542,6 +570,14 @@ pub mod prover {
         pub fixed_lookup_tables_evals_d8:
             BTreeMap<ID, Evaluations<G::ScalarField, D<G::ScalarField>>>,
 
+        /// The polynomials of the columns of the runtime tables, indexed by the table ID.
+        pub runtime_lookup_tables_poly_d1: BTreeMap<ID, Vec<DensePolynomial<G::ScalarField>>>,
+        /// The commitments to the columns of the runtime tables, indexed by the table ID.
+        pub runtime_lookup_tables_comms_d1: BTreeMap<ID, Vec<PolyComm<G>>>,
+        #[allow(clippy::type_complexity)]
+        pub runtime_lookup_tables_evals_d8:
+            BTreeMap<ID, Vec<Evaluations<G::ScalarField, D<G::ScalarField>>>>,
+
         /// The combiner used for vector lookups
         pub joint_combiner: G::ScalarField,
 
@@ -636,6 +672,82 @@ pub mod prover {
             });
             // -- end of m(X)
 
+            // -- start of the runtime tables
+            // The values of the runtime tables are chosen by the prover, and
+            // must therefore be committed before coining the challenges. Each
+            // position of the value-vector of the entries is a column.
+            let runtime_lookup_tables_evals_d1: BTreeMap<
+                ID,
+                Vec<Evaluations<G::ScalarField, D<G::ScalarField>>>,
+            > = lookups
+                .iter()
+                .filter(|(table_id, _)| !table_id.is_fixed() && table_id.runtime_create_column())
+                .map(|(table_id, logup_witness)| {
+                    // By convention, the table is the last looked-up column
+                    let table = logup_witness.f.last().unwrap();
+                    let width = table[0].value.len();
+                    assert!(
+                        table.iter().all(|entry| entry.value.len() == width),
+                        "All the entries of the runtime table {table_id:?} must have the same length"
+                    );
+                    let columns = (0..width)
+                        .map(|i| {
+                            Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
+                                table.iter().map(|entry| entry.value[i]).collect(),
+                                domain.d1,
+                            )
+                        })
+                        .collect();
+                    (*table_id, columns)
+                })
+                .collect();
+
+            let runtime_lookup_tables_poly_d1: BTreeMap<ID, Vec<DensePolynomial<G::ScalarField>>> =
+                (&runtime_lookup_tables_evals_d1)
+                    .into_par_iter()
+                    .map(|(id, evals)| {
+                        (*id, evals.iter().map(|e| e.interpolate_by_ref()).collect())
+                    })
+                    .collect();
+
+            #[allow(clippy::type_complexity)]
+            let runtime_lookup_tables_evals_d8: BTreeMap<
+                ID,
+                Vec<Evaluations<G::ScalarField, D<G::ScalarField>>>,
+            > = (&runtime_lookup_tables_poly_d1)
+                .into_par_iter()
+                .map(|(id, polys)| {
+                    (
+                        *id,
+                        polys
+                            .iter()
+                            .map(|poly| poly.evaluate_over_domain_by_ref(domain.d8))
+                            .collect(),
+                    )
+                })
+                .collect();
+
+            let runtime_lookup_tables_comms_d1: BTreeMap<ID, Vec<PolyComm<G>>> =
+                (&runtime_lookup_tables_evals_d1)
+                    .into_par_iter()
+                    .map(|(id, evals)| {
+                        (
+                            *id,
+                            evals
+                                .iter()
+                                .map(|evals| srs.commit_evaluations_non_hiding(domain.d1, evals))
+                                .collect(),
+                        )
+                    })
+                    .collect();
+
+            runtime_lookup_tables_comms_d1.values().for_each(|comms| {
+                comms
+                    .iter()
+                    .for_each(|comm| absorb_commitment(fq_sponge, comm))
+            });
+            // -- end of the runtime tables
+
             // -- start computing the row sums h(X)
             // It will be used to compute the running sum in lookup_aggregation
             // Coin a combiner to perform vector lookup.
@@ -706,11 +818,11 @@ pub mod prover {
                         // If last element and fixed lookup tables, we keep
                         // the *combined* value of the table.
                         //
-                        // Otherwise we're processing a runtime table
-                        // with explicit writes, so we don't need to
-                        // create any extra columns.
-                        if (table_id.is_fixed() || table_id.runtime_create_column()) && i == (n - 1)
-                        {
+                        // Otherwise we're processing a runtime table,
+                        // whose columns have already been committed, or
+                        // whose values are explicit writes, so we don't
+                        // need to create any extra columns.
+                        if table_id.is_fixed() && i == (n - 1) {
                             fixed_lookup_tables
                                 .entry(*table_id)
                                 .or_insert_with(Vec::new)
@@ -886,11 +998,10 @@ pub mod prover {
             // - φ(ω^n) = 0
             let lookup_aggregation_evals_d1 = {
                 {
-                    for table_id in ID::all_variants().into_iter() {
+                    for (table_id, lookup_terms) in lookup_terms_evals_d1.iter() {
                         let mut acc = G::ScalarField::zero();
                         for i in 0..domain.d1.size as usize {
                             // φ(1) = 0
-                            let lookup_terms = lookup_terms_evals_d1.get(&table_id).unwrap();
                             acc = lookup_terms.iter().fold(acc, |acc, lte| acc + lte[i]);
                         }
                         // Sanity check to verify that the accumulator ends up being zero.
@@ -950,6 +1061,10 @@ pub mod prover {
                 fixed_lookup_tables_comms_d1,
                 fixed_lookup_tables_evals_d8,
 
+                runtime_lookup_tables_poly_d1,
+                runtime_lookup_tables_comms_d1,
+                runtime_lookup_tables_evals_d8,
+
                 joint_combiner: vector_lookup_combiner,
                 beta,
             }
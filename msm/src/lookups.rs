@@ -1,8 +1,12 @@
 //! Instantiate the Logup protocol for the MSM project.
 
-use crate::logup::{Logup, LogupWitness, LookupTableID};
+use crate::{
+    logup::{Logup, LogupWitness, LookupTableID},
+    DOMAIN_SIZE,
+};
 use ark_ff::{FftField, PrimeField};
 use kimchi::circuits::domains::EvaluationDomains;
+use o1_utils::FieldHelpers;
 use rand::{seq::SliceRandom, thread_rng, Rng};
 use std::{cmp::Ord, iter};
 
@@ -87,21 +91,32 @@ impl LookupTableID for LookupTableIDs {
     fn length(&self) -> usize {
         match self {
             LookupTableIDs::RangeCheck16 => 1 << 16,
-            LookupTableIDs::Custom(_) => todo!(),
+            // Custom tables have at most one entry per row.
+            LookupTableIDs::Custom(_) => DOMAIN_SIZE,
         }
     }
 
-    /// All tables are fixed tables.
+    /// Custom tables are runtime tables, the range check table is fixed.
     fn is_fixed(&self) -> bool {
-        true
+        match self {
+            LookupTableIDs::RangeCheck16 => true,
+            LookupTableIDs::Custom(_) => false,
+        }
     }
 
+    /// The values of custom tables are committed in their own columns.
     fn runtime_create_column(&self) -> bool {
-        panic!("No runtime tables specified");
+        match self {
+            LookupTableIDs::RangeCheck16 => panic!("RangeCheck16 is not a runtime table"),
+            LookupTableIDs::Custom(_) => true,
+        }
     }
 
-    fn ix_by_value<F: PrimeField>(&self, _value: &[F]) -> Option<usize> {
-        todo!()
+    fn ix_by_value<F: PrimeField>(&self, value: &[F]) -> Option<usize> {
+        match self {
+            LookupTableIDs::RangeCheck16 => Some(TryFrom::try_from(value[0].to_biguint()).unwrap()),
+            LookupTableIDs::Custom(_) => None,
+        }
     }
 
     fn all_variants() -> Vec<Self> {
@@ -9,6 +9,7 @@ use kimchi::{
     circuits::{
         domains::EvaluationDomains,
         expr::{ColumnEvaluations, ExprError},
+        gate::CurrOrNext,
     },
     curve::KimchiCurve,
     proof::PointEvaluations,
@@ -141,6 +142,20 @@ impl<
                     panic!("No lookup provided")
                 }
             }
+            Self::Column::LookupRuntimeTable((table_id, idx)) => {
+                if let Some(ref lookup) = self.logup_evals {
+                    // The runtime tables are chosen by the prover, so a
+                    // malformed proof may not contain the column.
+                    lookup
+                        .runtime_tables
+                        .get(&ID::from_u32(table_id))
+                        .and_then(|columns| columns.get(idx))
+                        .ok_or(ExprError::MissingEvaluation(col, CurrOrNext::Curr))?
+                        .clone()
+                } else {
+                    panic!("No lookup provided")
+                }
+            }
         };
         Ok(res)
     }
@@ -173,3 +188,23 @@ pub struct Proof<
     pub(crate) proof_evals: ProofEvaluations<N_WIT, N_REL, N_DSEL, N_FSEL, G::ScalarField, ID>,
     pub(crate) opening_proof: OpeningProof,
 }
+
+impl<
+        const N_WIT: usize,
+        const N_REL: usize,
+        const N_DSEL: usize,
+        const N_FSEL: usize,
+        G: KimchiCurve,
+        OpeningProof: OpenProof<G>,
+        ID: LookupTableID,
+    > Proof<N_WIT, N_REL, N_DSEL, N_FSEL, G, OpeningProof, ID>
+{
+    /// Returns the commitments to the columns of the runtime table
+    /// `table_id`, one per position in the value-vector of its entries.
+    /// They can be compared to the commitments of the tables the proof is
+    /// expected to read from, for instance a memory snapshot.
+    pub fn runtime_table_comms(&self, table_id: &ID) -> Option<&[PolyComm<G>]> {
+        let logup_comms = self.proof_comms.logup_comms.as_ref()?;
+        logup_comms.runtime_tables.get(table_id).map(Vec::as_slice)
+    }
+}
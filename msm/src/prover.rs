@@ -190,6 +190,7 @@ where
         h: lookup_env.lookup_terms_comms_d1.clone(),
         sum: lookup_env.lookup_aggregation_comm_d1.clone(),
         fixed_tables: lookup_env.fixed_lookup_tables_comms_d1.clone(),
+        runtime_tables: lookup_env.runtime_lookup_tables_comms_d1.clone(),
     });
 
     // -- end computing the running sum in lookup_aggregation
@@ -255,6 +256,7 @@ where
                     lookup_aggregation_evals_d8: &lookup_env.lookup_aggregation_evals_d8,
                     lookup_counters_evals_d8: &lookup_env.lookup_counters_evals_d8,
                     fixed_tables_evals_d8: &lookup_env.fixed_lookup_tables_evals_d8,
+                    runtime_tables_evals_d8: &lookup_env.runtime_lookup_tables_evals_d8,
                 }
             }),
             domain,
@@ -425,6 +427,20 @@ where
                 })
                 .collect()
         },
+        runtime_tables: lookup_env
+            .runtime_lookup_tables_poly_d1
+            .iter()
+            .map(|(id, polys)| {
+                let polys_evals: Vec<_> = polys
+                    .iter()
+                    .map(|poly| PointEvaluations {
+                        zeta: poly.evaluate(&zeta),
+                        zeta_omega: poly.evaluate(&zeta_omega),
+                    })
+                    .collect();
+                (*id, polys_evals)
+            })
+            .collect(),
     });
 
     ////////////////////////////////////////////////////////////////////////////
@@ -552,6 +568,19 @@ where
                 .map(|poly| (coefficients_form(poly), non_hiding(1)))
                 .collect::<Vec<_>>(),
         );
+        // -- Adding runtime lookup tables
+        polynomials.extend(
+            lookup_env
+                .runtime_lookup_tables_poly_d1
+                .values()
+                .flat_map(|polys| {
+                    polys
+                        .iter()
+                        .map(|poly| (coefficients_form(poly), non_hiding(1)))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>(),
+        );
     }
     polynomials.push((coefficients_form(&ft), non_hiding(1)));
 
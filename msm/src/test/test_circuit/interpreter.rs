@@ -300,8 +300,15 @@ pub fn constrain_lookups<
         ],
     );
 
-    // For now we only allow one read per runtime table with runtime_create_column = true.
-    //env.lookup(LookupTable::RuntimeTable1, vec![a0.clone(), a1.clone()]);
+    env.lookup(
+        LookupTable::RuntimeTable1,
+        vec![
+            a0.clone(),
+            a0.clone() - Env::constant(F::one()),
+            a0 + Env::constant(F::one()),
+            Env::constant(F::from(4u64)),
+        ],
+    );
 
     env.lookup_runtime_write(
         LookupTable::RuntimeTable2,
@@ -349,6 +356,15 @@ pub fn lookups_circuit<
     }
 }
 
+/// Entries of the runtime table `RuntimeTable1` for the test circuit, that
+/// are the rows of the fixed selectors along with the constant 4.
+pub fn build_runtime_table<F: Field>(domain_size: usize) -> Vec<Vec<F>> {
+    let [sel1, sel2, sel3] = *build_fixed_selectors::<F>(domain_size);
+    (0..domain_size)
+        .map(|i| vec![sel1[i], sel2[i], sel3[i], F::from(4u64)])
+        .collect()
+}
+
 /// Fixed selectors for the test circuit.
 pub fn build_fixed_selectors<F: Field>(domain_size: usize) -> Box<[Vec<F>; N_FSEL_TEST]> {
     // 0 1 2 3 4 ...
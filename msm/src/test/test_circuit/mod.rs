@@ -8,14 +8,21 @@ mod tests {
         circuit_design::{ConstraintBuilderEnv, WitnessBuilderEnv},
         logup::LookupTableID,
         lookups::DummyLookupTable,
+        proof::ProofInputs,
+        prover::prove,
         test::test_circuit::{
             columns::{TestColumn, N_COL_TEST, N_FSEL_TEST},
             interpreter as test_interpreter,
             lookups::LookupTable as TestLookupTable,
         },
-        Ff1, Fp,
+        verifier::verify,
+        witness::Witness,
+        BaseSponge, Ff1, Fp, OpeningProof, ScalarSponge, BN254,
     };
     use ark_ff::UniformRand;
+    use ark_poly::Evaluations;
+    use kimchi::circuits::domains::EvaluationDomains;
+    use poly_commitment::{kzg::PairingSRS, SRS as _};
     use rand::{CryptoRng, Rng, RngCore};
     use std::collections::BTreeMap;
 
@@ -427,22 +434,15 @@ mod tests {
         build_test_mul_circuit::<_, DummyLookupTable>(&mut rng, 1 << 4);
     }
 
-    #[test]
-    fn test_completeness_lookups() {
-        let mut rng = o1_utils::tests::make_test_rng(None);
-
-        // Include tests for completeness for Logup as the random witness
-        // includes all arguments
-        let domain_size = 1 << 15;
-
+    fn build_lookups_proof_inputs(
+        domain_size: usize,
+        runtime_table: Vec<Vec<Fp>>,
+    ) -> ProofInputs<{ N_COL_TEST - N_FSEL_TEST }, Fp, TestLookupTable> {
         let fixed_selectors = test_interpreter::build_fixed_selectors(domain_size);
 
-        let mut constraint_env = ConstraintBuilderEnv::<Fp, TestLookupTable>::create();
-        test_interpreter::constrain_lookups::<Fp, _>(&mut constraint_env);
-        let constraints = constraint_env.get_relation_constraints();
-
         let mut witness_env: TestWitnessBuilderEnv<TestLookupTable> = WitnessBuilderEnv::create();
         witness_env.set_fixed_selectors(fixed_selectors.to_vec());
+        witness_env.set_runtime_table(TestLookupTable::RuntimeTable1, runtime_table);
         test_interpreter::lookups_circuit(&mut witness_env, domain_size);
         let runtime_tables: BTreeMap<_, Vec<Vec<Vec<_>>>> =
             witness_env.get_runtime_tables(domain_size);
@@ -465,7 +465,25 @@ mod tests {
             lookup_tables_data.insert(table_id, runtime_table);
         }
 
-        let proof_inputs = witness_env.get_proof_inputs(domain_size, lookup_tables_data);
+        witness_env.get_proof_inputs(domain_size, lookup_tables_data)
+    }
+
+    #[test]
+    fn test_completeness_lookups() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+
+        // Include tests for completeness for Logup as the random witness
+        // includes all arguments
+        let domain_size = 1 << 15;
+
+        let fixed_selectors = test_interpreter::build_fixed_selectors(domain_size);
+
+        let mut constraint_env = ConstraintBuilderEnv::<Fp, TestLookupTable>::create();
+        test_interpreter::constrain_lookups::<Fp, _>(&mut constraint_env);
+        let constraints = constraint_env.get_constraints();
+
+        let runtime_table = test_interpreter::build_runtime_table(domain_size);
+        let proof_inputs = build_lookups_proof_inputs(domain_size, runtime_table);
 
         crate::test::test_completeness_generic::<
             { N_COL_TEST - N_FSEL_TEST },
@@ -483,6 +501,157 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_runtime_table_commitments() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let domain_size = 1 << 15;
+        let domain = EvaluationDomains::<Fp>::create(domain_size).unwrap();
+        let srs: PairingSRS<BN254> = crate::precomputed_srs::get_bn254_srs(domain);
+
+        let fixed_selectors = test_interpreter::build_fixed_selectors(domain_size);
+
+        let mut constraint_env = ConstraintBuilderEnv::<Fp, TestLookupTable>::create();
+        test_interpreter::constrain_lookups::<Fp, _>(&mut constraint_env);
+        let constraints = constraint_env.get_constraints();
+
+        let mut prove_with_table = |runtime_table| {
+            let proof_inputs = build_lookups_proof_inputs(domain_size, runtime_table);
+            prove::<
+                _,
+                OpeningProof,
+                BaseSponge,
+                ScalarSponge,
+                _,
+                { N_COL_TEST - N_FSEL_TEST },
+                { N_COL_TEST - N_FSEL_TEST },
+                0,
+                N_FSEL_TEST,
+                TestLookupTable,
+            >(
+                domain,
+                &srs,
+                &constraints,
+                fixed_selectors.clone(),
+                proof_inputs,
+                &mut rng,
+            )
+            .unwrap()
+        };
+        let verify_proof = |proof| {
+            verify::<
+                _,
+                OpeningProof,
+                BaseSponge,
+                ScalarSponge,
+                { N_COL_TEST - N_FSEL_TEST },
+                { N_COL_TEST - N_FSEL_TEST },
+                0,
+                N_FSEL_TEST,
+                0,
+                TestLookupTable,
+            >(
+                domain,
+                &srs,
+                &constraints,
+                fixed_selectors.clone(),
+                proof,
+                Witness::zero_vec(domain_size),
+            )
+        };
+
+        let runtime_table = test_interpreter::build_runtime_table(domain_size);
+        // The same entries, in another order
+        let mut runtime_table_prime = runtime_table.clone();
+        runtime_table_prime.reverse();
+
+        let proof = prove_with_table(runtime_table.clone());
+        let proof_prime = prove_with_table(runtime_table_prime);
+        assert!(verify_proof(&proof));
+        assert!(verify_proof(&proof_prime));
+
+        // The proof exposes the commitments to the columns of the runtime table
+        let comms = proof
+            .runtime_table_comms(&TestLookupTable::RuntimeTable1)
+            .unwrap();
+        assert_eq!(comms.len(), 4);
+        for (i, comm) in comms.iter().enumerate() {
+            let column = Evaluations::from_vec_and_domain(
+                runtime_table.iter().map(|entry| entry[i]).collect(),
+                domain.d1,
+            );
+            assert_eq!(*comm, srs.commit_evaluations_non_hiding(domain.d1, &column));
+        }
+
+        // The proof does not verify with the commitments of another table
+        let mut proof_clone = proof.clone();
+        proof_clone
+            .proof_comms
+            .logup_comms
+            .as_mut()
+            .unwrap()
+            .runtime_tables = proof_prime
+            .proof_comms
+            .logup_comms
+            .as_ref()
+            .unwrap()
+            .runtime_tables
+            .clone();
+        assert!(!verify_proof(&proof_clone));
+
+        // Malformed proofs, whose runtime tables are not the ones read by the
+        // constraints, are rejected
+        let malformed_proofs: Vec<_> = [false, true]
+            .into_iter()
+            .map(|extra_column| {
+                let mut proof = proof.clone();
+                let comms = proof
+                    .proof_comms
+                    .logup_comms
+                    .as_mut()
+                    .unwrap()
+                    .runtime_tables
+                    .get_mut(&TestLookupTable::RuntimeTable1)
+                    .unwrap();
+                let evals = proof
+                    .proof_evals
+                    .logup_evals
+                    .as_mut()
+                    .unwrap()
+                    .runtime_tables
+                    .get_mut(&TestLookupTable::RuntimeTable1)
+                    .unwrap();
+                if extra_column {
+                    comms.push(comms[0].clone());
+                    evals.push(evals[0]);
+                } else {
+                    comms.pop();
+                    evals.pop();
+                }
+                proof
+            })
+            .collect();
+        for proof in &malformed_proofs {
+            assert!(!verify_proof(proof));
+        }
+        // a missing table
+        let mut proof_no_table = proof.clone();
+        proof_no_table
+            .proof_comms
+            .logup_comms
+            .as_mut()
+            .unwrap()
+            .runtime_tables
+            .clear();
+        proof_no_table
+            .proof_evals
+            .logup_evals
+            .as_mut()
+            .unwrap()
+            .runtime_tables
+            .clear();
+        assert!(!verify_proof(&proof_no_table));
+    }
+
     #[test]
     fn test_completeness() {
         let mut rng = o1_utils::tests::make_test_rng(None);
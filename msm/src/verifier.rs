@@ -2,19 +2,20 @@
 #![allow(clippy::boxed_local)]
 
 use crate::logup::LookupTableID;
-use ark_ff::{Field, Zero};
+use ark_ff::{FftField, Field, Zero};
 use ark_poly::{
     univariate::DensePolynomial, EvaluationDomain, Evaluations, Polynomial,
     Radix2EvaluationDomain as R2D,
 };
 use rand::thread_rng;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::collections::BTreeMap;
 
 use kimchi::{
     circuits::{
         berkeley_columns::BerkeleyChallenges,
         domains::EvaluationDomains,
-        expr::{Constants, Expr, PolishToken},
+        expr::{Constants, Expr, PolishToken, Variable},
     },
     curve::KimchiCurve,
     groupmap::GroupMap,
@@ -29,7 +30,7 @@ use poly_commitment::{
     OpenProof, SRS,
 };
 
-use crate::{expr::E, proof::Proof, witness::Witness};
+use crate::{columns::Column, expr::E, proof::Proof, witness::Witness};
 
 pub fn verify<
     G: KimchiCurve,
@@ -59,6 +60,45 @@ where
         opening_proof,
     } = proof;
 
+    ////////////////////////////////////////////////////////////////////////////
+    // Checking the shape of the lookup proof
+    ////////////////////////////////////////////////////////////////////////////
+
+    // The commitments and the evaluations of the lookup argument are zipped
+    // below, so there must be exactly one evaluation per commitment.
+    match (&proof_comms.logup_comms, &proof_evals.logup_evals) {
+        (None, None) => (),
+        (Some(comms), Some(evals)) => {
+            fn same_shape<T, U, ID: LookupTableID>(
+                comms: &BTreeMap<ID, Vec<T>>,
+                evals: &BTreeMap<ID, Vec<U>>,
+            ) -> bool {
+                comms.len() == evals.len()
+                    && comms
+                        .iter()
+                        .zip(evals)
+                        .all(|((id1, c), (id2, e))| id1 == id2 && c.len() == e.len())
+            }
+            let same_tables = comms.fixed_tables.keys().eq(evals.fixed_tables.keys());
+            // The runtime tables must be the ones read by the constraints,
+            // with one column per position of their entries.
+            let expected_runtime_tables = runtime_table_widths(constraints);
+            let same_runtime_tables = comms.runtime_tables.len() == expected_runtime_tables.len()
+                && comms.runtime_tables.iter().all(|(id, columns)| {
+                    expected_runtime_tables.get(&id.to_u32()) == Some(&columns.len())
+                });
+            if !(same_shape(&comms.m, &evals.m)
+                && same_shape(&comms.h, &evals.h)
+                && same_shape(&comms.runtime_tables, &evals.runtime_tables)
+                && same_tables
+                && same_runtime_tables)
+            {
+                return false;
+            }
+        }
+        _ => return false,
+    }
+
     ////////////////////////////////////////////////////////////////////////////
     // Re-evaluating public inputs
     ////////////////////////////////////////////////////////////////////////////
@@ -153,6 +193,13 @@ where
                     .for_each(|comm| absorb_commitment(&mut fq_sponge, comm))
             });
 
+            // Then the columns of the runtime tables, chosen by the prover
+            logup_comms.runtime_tables.values().for_each(|comms| {
+                comms
+                    .iter()
+                    .for_each(|comm| absorb_commitment(&mut fq_sponge, comm))
+            });
+
             // FIXME @volhovm it seems that the verifier does not
             // actually check that the fixed tables used in the proof
             // are the fixed tables defined in the code. In other
@@ -288,15 +335,17 @@ where
     let combined_expr =
         Expr::combine_constraints(0..(constraints.len() as u32), constraints.clone());
     // Note the minus! ft polynomial at zeta (ft_eval0) is minus evaluation of the expression.
-    let ft_eval0 = -PolishToken::evaluate(
+    let ft_eval0 = match PolishToken::evaluate(
         combined_expr.to_polish().as_slice(),
         domain.d1,
         zeta,
         proof_evals,
         &constants,
         &challenges,
-    )
-    .unwrap();
+    ) {
+        Ok(eval) => -eval,
+        Err(_) => return false,
+    };
 
     coms_and_evaluations.push(Evaluation {
         commitment: ft_comm,
@@ -333,3 +382,22 @@ where
     let group_map = G::Map::setup();
     OpeningProof::verify(srs, &group_map, &mut [batch], &mut thread_rng())
 }
+
+/// Returns the runtime tables read by the constraints, indexed by their ID,
+/// with the number of columns of their entries.
+fn runtime_table_widths<F: FftField>(constraints: &[E<F>]) -> BTreeMap<u32, usize> {
+    let mut widths = BTreeMap::new();
+    for constraint in constraints {
+        for token in constraint.to_polish() {
+            if let PolishToken::Cell(Variable {
+                col: Column::LookupRuntimeTable((table_id, idx)),
+                ..
+            }) = token
+            {
+                let width = widths.entry(table_id).or_insert(0);
+                *width = (*width).max(idx + 1);
+            }
+        }
+    }
+    widths
+}
@@ -34,7 +34,7 @@ cargo run --bin ${BINARY_FLAVOR} \
     --pprof.cpu \
     --info-at "${INFO_AT:-%10000000}" \
     --snapshot-state-at "${SNAPSHOT_STATE_AT:-%10000000}" \
-    --proof-at never \
+    --proof-at "${PROOF_AT:-never}" \
     --stop-at "${STOP_AT:-never}" \
     --input "${ZKVM_STATE_FILENAME:-./state.json}" \
     -- \
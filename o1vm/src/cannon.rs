@@ -120,6 +120,72 @@ impl FromStr for PreimageKey {
     }
 }
 
+/// The type of a preimage key, given by its first byte. See the [preimage
+/// oracle specification](https://specs.optimism.io/fault-proof/index.html#pre-image-key-types).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreimageKeyType {
+    /// Data local to the program being executed, e.g. the L1 head
+    Local = 1,
+    /// Data identified by its keccak256 hash
+    Keccak256 = 2,
+    GlobalGeneric = 3,
+    /// Data identified by its sha256 hash
+    Sha256 = 4,
+    Blob = 5,
+    Precompile = 6,
+}
+
+impl TryFrom<u8> for PreimageKeyType {
+    type Error = ParsePreimageKeyError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(PreimageKeyType::Local),
+            2 => Ok(PreimageKeyType::Keccak256),
+            3 => Ok(PreimageKeyType::GlobalGeneric),
+            4 => Ok(PreimageKeyType::Sha256),
+            5 => Ok(PreimageKeyType::Blob),
+            6 => Ok(PreimageKeyType::Precompile),
+            _ => Err(ParsePreimageKeyError(format!(
+                "Unknown preimage key type {value}"
+            ))),
+        }
+    }
+}
+
+impl PreimageKey {
+    /// The key of a hash `hash` of type `key_type`, i.e. the hash whose first
+    /// byte is replaced by the type.
+    fn from_hash(key_type: PreimageKeyType, mut hash: [u8; 32]) -> Self {
+        hash[0] = key_type as u8;
+        PreimageKey(hash)
+    }
+
+    /// The key of the data local to the program with identifier `ident`.
+    pub fn local(ident: u64) -> Self {
+        let mut key = [0; 32];
+        key[0] = PreimageKeyType::Local as u8;
+        key[24..].copy_from_slice(&ident.to_be_bytes());
+        PreimageKey(key)
+    }
+
+    /// The key of `data` when it is requested by its keccak256 hash.
+    pub fn keccak256(data: &[u8]) -> Self {
+        use sha3::{Digest, Keccak256};
+        Self::from_hash(PreimageKeyType::Keccak256, Keccak256::digest(data).into())
+    }
+
+    /// The key of `data` when it is requested by its sha256 hash.
+    pub fn sha256(data: &[u8]) -> Self {
+        use sha2::{Digest, Sha256};
+        Self::from_hash(PreimageKeyType::Sha256, Sha256::digest(data).into())
+    }
+
+    pub fn key_type(&self) -> Result<PreimageKeyType, ParsePreimageKeyError> {
+        PreimageKeyType::try_from(self.0[0])
+    }
+}
+
 fn deserialize_preimage_key<'de, D>(deserializer: D) -> Result<[u8; 32], D::Error>
 where
     D: Deserializer<'de>,
@@ -147,6 +213,21 @@ pub enum StepFrequency {
     Range(u64, Option<u64>),
 }
 
+impl StepFrequency {
+    /// Returns true if the frequency matches the step `step`.
+    pub fn matches(&self, step: u64) -> bool {
+        match self {
+            StepFrequency::Never => false,
+            StepFrequency::Always => true,
+            StepFrequency::Exactly(n) => *n == step,
+            StepFrequency::Every(n) => step % *n == 0,
+            StepFrequency::Range(lo, hi_opt) => {
+                step >= *lo && (hi_opt.is_none() || step < hi_opt.unwrap())
+            }
+        }
+    }
+}
+
 // Simple parser for Cannon's "frequency format"
 // A frequency input is either
 // - never/always
@@ -215,6 +296,13 @@ pub struct VmConfiguration {
     pub snapshot_fmt: String,
     pub pprof_cpu: bool,
     pub host: Option<HostProgram>,
+    /// The preimages to use instead of running the host program, see
+    /// [crate::preimage_oracle::NativePreImageOracle]
+    pub preimage_store: Option<std::path::PathBuf>,
+    /// The file the trace of the execution is written to, see [crate::tracer]
+    pub trace: Option<String>,
+    pub trace_format: crate::tracer::TraceFormat,
+    pub breakpoints: Vec<crate::tracer::Breakpoint>,
 }
 
 #[derive(Debug, Clone)]
@@ -456,6 +544,38 @@ mod tests {
         );
         assert!(PreimageKey::from_str("0x01").is_err());
     }
+
+    #[test]
+    fn test_preimagekey_types() {
+        assert_eq!(
+            PreimageKey::local(1),
+            PreimageKey::from_str(
+                "0x0100000000000000000000000000000000000000000000000000000000000001"
+            )
+            .unwrap()
+        );
+        // keccak256("") = c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470
+        assert_eq!(
+            PreimageKey::keccak256(&[]),
+            PreimageKey::from_str(
+                "0x02d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+            )
+            .unwrap()
+        );
+        // sha256("") = e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855
+        assert_eq!(
+            PreimageKey::sha256(&[]),
+            PreimageKey::from_str(
+                "0x04b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            PreimageKey::sha256(&[]).key_type(),
+            Ok(PreimageKeyType::Sha256)
+        );
+        assert!(PreimageKey([0; 32]).key_type().is_err());
+    }
 }
 
 pub const HINT_CLIENT_READ_FD: i32 = 3;
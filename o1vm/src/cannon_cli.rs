@@ -1,5 +1,9 @@
-use crate::cannon::*;
+use crate::{
+    cannon::*,
+    tracer::{Breakpoint, TraceFormat},
+};
 use clap::{arg, value_parser, Arg, ArgAction};
+use std::{path::PathBuf, str::FromStr};
 
 pub fn main_cli() -> clap::Command {
     let app_name = "o1vm";
@@ -9,7 +13,7 @@ pub fn main_cli() -> clap::Command {
         .arg(arg!(--input <FILE> "initial state file").default_value("state.json"))
         .arg(arg!(--output <FILE> "output state file").default_value("out.json"))
         .arg(arg!(--meta <FILE> "metadata file").default_value("meta.json"))
-        // The CLI arguments below this line are ignored at this point
+        // The proofs are only generated by the pickles flavor, see o1vm::pickles
         .arg(
             Arg::new("proof-at")
                 .short('p')
@@ -49,6 +53,23 @@ pub fn main_cli() -> clap::Command {
                 .long("pprof.cpu")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            arg!(--"preimage-store" <PATH> "JSON file or directory containing the preimages, used instead of a host program")
+                .value_parser(value_parser!(PathBuf)),
+        )
+        .arg(
+            arg!(--trace <FILE> "write the trace of the executed instructions to FILE (- for the standard output)"),
+        )
+        .arg(
+            arg!(--"trace-format" <FORMAT> "format of the trace: text or jsonl")
+                .default_value("text")
+                .value_parser(TraceFormat::from_str),
+        )
+        .arg(
+            arg!(--break <BREAKPOINT> "interrupt the execution in the debugger before the given step, or on the entry of the given symbol")
+                .action(ArgAction::Append)
+                .value_parser(Breakpoint::from_str),
+        )
         .arg(
             arg!(host: [HOST] "host program specification <host program> [host program arguments]")
                 .num_args(1..)
@@ -83,6 +104,15 @@ pub fn read_configuration(cli: &clap::ArgMatches) -> VmConfiguration {
         .map(|vals| vals.collect::<Vec<_>>())
         .unwrap_or_default();
 
+    let preimage_store = cli.get_one::<PathBuf>("preimage-store").cloned();
+
+    let trace = cli.get_one::<String>("trace").cloned();
+    let trace_format = *cli.get_one::<TraceFormat>("trace-format").unwrap();
+    let breakpoints = cli
+        .get_many::<Breakpoint>("break")
+        .map(|vals| vals.cloned().collect())
+        .unwrap_or_default();
+
     let host = if host_spec.is_empty() {
         None
     } else {
@@ -108,5 +138,9 @@ pub fn read_configuration(cli: &clap::ArgMatches) -> VmConfiguration {
         snapshot_fmt: snapshot_fmt.to_string(),
         pprof_cpu: *pprof_cpu,
         host,
+        preimage_store,
+        trace,
+        trace_format,
+        breakpoints,
     }
 }
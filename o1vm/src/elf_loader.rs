@@ -1,5 +1,5 @@
-use crate::cannon::{Page, State, PAGE_SIZE};
-use elf::{endian::LittleEndian, section::SectionHeader, ElfBytes};
+use crate::cannon::{Meta, Page, State, Symbol, PAGE_SIZE};
+use elf::{abi::STT_FUNC, endian::LittleEndian, section::SectionHeader, ElfBytes};
 use log::debug;
 use std::{collections::HashMap, path::Path};
 
@@ -143,3 +143,32 @@ pub fn parse_riscv32(path: &Path) -> Result<State, String> {
 
     Ok(state)
 }
+
+/// Parse the symbol table of an ELF file, and return the functions it defines
+/// in the format of the metadata given by Cannon. It is used to name the
+/// instruction pointers when tracing an execution, see [crate::tracer].
+/// Returns no symbol if the binary has been stripped.
+pub fn parse_riscv32_symbols(path: &Path) -> Result<Meta, String> {
+    let file_data = std::fs::read(path).map_err(|err| err.to_string())?;
+    let file = ElfBytes::<LittleEndian>::minimal_parse(file_data.as_slice())
+        .map_err(|err| err.to_string())?;
+    let Some((symtab, strtab)) = file.symbol_table().map_err(|err| err.to_string())? else {
+        return Ok(Meta { symbols: vec![] });
+    };
+    let mut symbols = symtab
+        .iter()
+        .filter(|symbol| symbol.st_symtype() == STT_FUNC && symbol.st_size != 0)
+        .map(|symbol| {
+            let name = strtab
+                .get(symbol.st_name as usize)
+                .map_err(|err| err.to_string())?;
+            Ok(Symbol {
+                name: name.to_string(),
+                start: symbol.st_value as u32,
+                size: symbol.st_size as usize,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    symbols.sort_by_key(|symbol| symbol.start);
+    Ok(Meta { symbols })
+}
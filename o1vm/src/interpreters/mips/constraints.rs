@@ -32,6 +32,16 @@ pub struct Env<Fp> {
     lookups: Vec<Lookup<E<Fp>>>,
     /// Selector (as expression) for the constraints of the environment.
     selector: Option<E<Fp>>,
+    /// Number of times the instruction counter has been increased since the
+    /// beginning of the instruction. The instruction counter column contains
+    /// the value at the beginning of the instruction, and the witness
+    /// increases it after each access to a register or to the memory.
+    instruction_counter_offset: u32,
+    /// The values range checked by the instruction, i.e. the time elapsed
+    /// since the previous access to each register or memory cell accessed.
+    /// They are checked by the memory checking argument of the pickles
+    /// flavor, see [crate::pickles::ram].
+    range_checks: Vec<E<Fp>>,
 }
 
 impl<Fp: Field> Default for Env<Fp> {
@@ -42,6 +52,8 @@ impl<Fp: Field> Default for Env<Fp> {
             constraints: Vec::new(),
             lookups: Vec::new(),
             selector: None,
+            instruction_counter_offset: 0,
+            range_checks: Vec::new(),
         }
     }
 }
@@ -108,10 +120,15 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
 
     fn instruction_counter(&self) -> Self::Variable {
         self.variable(MIPSColumn::InstructionCounter)
+            + Self::constant(self.instruction_counter_offset)
     }
 
     fn increase_instruction_counter(&mut self) {
-        // No-op, witness only
+        self.instruction_counter_offset += 1;
+    }
+
+    fn range_check64(&mut self, value: &Self::Variable) {
+        self.range_checks.push(value.clone());
     }
 
     unsafe fn fetch_register(
@@ -635,6 +652,8 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         self.constraints.clear();
         self.lookups.clear();
         self.selector = None;
+        self.instruction_counter_offset = 0;
+        self.range_checks.clear();
     }
 }
 
@@ -672,4 +691,8 @@ impl<Fp: Field> Env<Fp> {
     pub fn get_lookups(&self) -> Vec<Lookup<E<Fp>>> {
         self.lookups.clone()
     }
+
+    pub fn get_range_checks(&self) -> Vec<E<Fp>> {
+        self.range_checks.clone()
+    }
 }
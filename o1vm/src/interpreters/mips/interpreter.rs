@@ -556,9 +556,11 @@ pub trait InterpreterEnv {
         self.lookup_2bits(value);
     }
 
-    fn range_check64(&mut self, _value: &Self::Variable) {
-        // TODO
-    }
+    /// Range check that `value` is in `[0, 2^64)`. The check is not made by
+    /// the interpreter: the constraint environment records the value, which
+    /// is decomposed in bytes by the memory checking argument of the pickles
+    /// flavor, see [crate::pickles::ram].
+    fn range_check64(&mut self, _value: &Self::Variable) {}
 
     fn set_instruction_pointer(&mut self, ip: Self::Variable) {
         let idx = Self::constant(REGISTER_CURRENT_IP as u32);
@@ -581,10 +583,16 @@ pub trait InterpreterEnv {
             let value_location = self.alloc_scratch();
             unsafe { self.fetch_register(&idx, value_location) }
         };
+        let last_accessed = {
+            let last_accessed_location = self.alloc_scratch();
+            unsafe { self.fetch_register_access(&idx, last_accessed_location) }
+        };
+        let elapsed_time = self.instruction_counter() - last_accessed.clone();
         self.add_lookup(Lookup::write_one(
             LookupTableIDs::RegisterLookup,
-            vec![idx, self.instruction_counter(), ip.clone()],
+            vec![idx, last_accessed, ip.clone()],
         ));
+        self.range_check64(&elapsed_time);
         ip
     }
 
@@ -609,10 +617,16 @@ pub trait InterpreterEnv {
             let value_location = self.alloc_scratch();
             unsafe { self.fetch_register(&idx, value_location) }
         };
+        let last_accessed = {
+            let last_accessed_location = self.alloc_scratch();
+            unsafe { self.fetch_register_access(&idx, last_accessed_location) }
+        };
+        let elapsed_time = self.instruction_counter() - last_accessed.clone();
         self.add_lookup(Lookup::write_one(
             LookupTableIDs::RegisterLookup,
-            vec![idx, self.instruction_counter(), ip.clone()],
+            vec![idx, last_accessed, ip.clone()],
         ));
+        self.range_check64(&elapsed_time);
         ip
     }
 
@@ -1142,6 +1156,11 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RTypeInstructi
             let exit_code = env.read_register(&Env::constant(4));
             env.report_exit(&exit_code);
             env.set_halted(Env::constant(1));
+            // The VM stays on this instruction. The instruction pointers are
+            // written back so that the accesses to them are balanced, as for
+            // any other instruction.
+            env.set_instruction_pointer(instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer.clone());
         }
         RTypeInstruction::SyscallReadHint => {
             // We don't really write here, since the value is unused, per the cannon
@@ -6,6 +6,7 @@ use crate::{
         witness::{Env as WEnv, SyscallEnv},
     },
     preimage_oracle::PreImageOracleT,
+    ramlookup::RAMMultiset,
 };
 use rand::{CryptoRng, Rng, RngCore};
 use std::{fs, path::PathBuf};
@@ -102,6 +103,8 @@ where
         preimage_key: None,
         keccak_env: None,
         hash_counter: 0,
+        ram_lookups: RAMMultiset::default(),
+        tracer: None,
     };
     // Initialize general purpose registers with random values
     for reg in env.registers.general_purpose.iter_mut() {
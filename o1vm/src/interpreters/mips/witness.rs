@@ -20,11 +20,14 @@ use crate::{
             registers::Registers,
         },
     },
-    lookups::Lookup,
+    lookups::{Lookup, LookupTableIDs},
+    pickles::proof::{ProgramState, VmState},
     preimage_oracle::PreImageOracleT,
+    ramlookup::RAMMultiset,
+    tracer::{self, DebuggerAction, Inspect, Tracer},
     utils::memory_size,
 };
-use ark_ff::Field;
+use ark_ff::{Field, PrimeField};
 use core::panic;
 use kimchi::o1_utils::Two;
 use log::{debug, info};
@@ -90,6 +93,12 @@ pub struct Env<Fp, PreImageOracle: PreImageOracleT> {
     pub preimage_key: Option<[u8; 32]>,
     pub keccak_env: Option<KeccakEnv<Fp>>,
     pub hash_counter: u64,
+    /// The accesses to the registers and to the memory since the beginning of
+    /// the current chunk, see [crate::pickles::ram].
+    pub ram_lookups: RAMMultiset<LookupTableIDs>,
+    /// Traces the executed instructions and interrupts the execution on
+    /// breakpoints, if set
+    pub tracer: Option<Tracer>,
 }
 
 fn fresh_scratch_state<Fp: Field, const N: usize>() -> [Fp; N] {
@@ -142,9 +151,14 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp, PreI
         }
     }
 
-    fn add_lookup(&mut self, _lookup: Lookup<Self::Variable>) {
-        // No-op, constraints only
+    fn add_lookup(&mut self, lookup: Lookup<Self::Variable>) {
         // TODO: keep track of multiplicities of fixed tables here as in Keccak?
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer.record_lookup(&lookup);
+        }
+        if lookup.table_id.is_ram() {
+            self.ram_lookups.add(&lookup);
+        }
     }
 
     fn instruction_counter(&self) -> Self::Variable {
@@ -599,11 +613,15 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp, PreI
     }
 
     fn report_exit(&mut self, exit_code: &Self::Variable) {
-        println!(
-            "Exited with code {} at step {}",
-            *exit_code,
-            self.normalized_instruction_counter()
-        );
+        // The exit is executed again to pad the execution trace, see
+        // [Env::step_exited].
+        if !self.halt {
+            println!(
+                "Exited with code {} at step {}",
+                *exit_code,
+                self.normalized_instruction_counter()
+            );
+        }
     }
 
     fn request_preimage_write(
@@ -868,7 +886,8 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
         };
 
         Env {
-            instruction_counter: state.step,
+            // The instruction counter increases by MAX_ACC after each step
+            instruction_counter: state.step * MAX_ACC,
             memory: initial_memory.clone(),
             last_memory_accesses: [0usize; 3],
             memory_write_index: memory_offsets
@@ -891,6 +910,8 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
             preimage_key: None,
             keccak_env: None,
             hash_counter: 0,
+            ram_lookups: RAMMultiset::default(),
+            tracer: None,
         }
     }
 
@@ -1148,7 +1169,7 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
     ) -> Instruction {
         self.reset_scratch_state();
         self.reset_scratch_state_inverse();
-        let (opcode, _instruction) = self.decode_instruction();
+        let (opcode, instruction) = self.decode_instruction();
 
         self.pp_info(&config.info_at, metadata, start);
         self.snapshot_state_at(&config.snapshot_state_at);
@@ -1164,8 +1185,24 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
             return opcode;
         }
 
+        if let Some(mut tracer) = self.tracer.take() {
+            let step = self.normalized_instruction_counter();
+            let pc = self.registers.current_instruction_pointer;
+            let action = tracer.start_step(self, step, pc, instruction, &opcode);
+            self.tracer = Some(tracer);
+            if action == DebuggerAction::Quit {
+                self.halt = true;
+                info!("Halted by the debugger at step={step} instruction={opcode:?}");
+                return opcode;
+            }
+        }
+
         interpreter::interpret_instruction(self, opcode);
 
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer.end_step(&self.registers.iter().copied().collect::<Vec<_>>());
+        }
+
         self.instruction_counter = self.next_instruction_counter();
 
         // Integer division by MAX_ACC to obtain the actual instruction count
@@ -1179,17 +1216,26 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
         opcode
     }
 
-    fn should_trigger_at(&self, at: &StepFrequency) -> bool {
-        let m: u64 = self.normalized_instruction_counter();
-        match at {
-            StepFrequency::Never => false,
-            StepFrequency::Always => true,
-            StepFrequency::Exactly(n) => *n == m,
-            StepFrequency::Every(n) => m % *n == 0,
-            StepFrequency::Range(lo, hi_opt) => {
-                m >= *lo && (hi_opt.is_none() || m < hi_opt.unwrap())
-            }
+    /// Execute again the instruction that made the program exit. It leaves
+    /// the state of the VM unchanged, except for the instruction counter, and
+    /// is used to pad the execution trace of the last chunk.
+    /// Returns `false` if the VM is not on an exit, e.g. if it has been
+    /// stopped using `--stop-at`.
+    pub fn step_exited(&mut self) -> bool {
+        let (opcode, _instruction) = self.decode_instruction();
+        if opcode != Instruction::RType(RTypeInstruction::SyscallExitGroup) {
+            return false;
         }
+        self.reset_scratch_state();
+        self.reset_scratch_state_inverse();
+        interpreter::interpret_instruction(self, opcode);
+        self.instruction_counter = self.next_instruction_counter();
+        true
+    }
+
+    /// Returns true if the frequency `at` matches the current step.
+    pub fn should_trigger_at(&self, at: &StepFrequency) -> bool {
+        at.matches(self.normalized_instruction_counter())
     }
 
     // Compute memory usage
@@ -1296,3 +1342,35 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
         }
     }
 }
+
+impl<Fp, PreImageOracle: PreImageOracleT> Inspect for Env<Fp, PreImageOracle> {
+    fn registers(&self) -> Vec<u32> {
+        self.registers.iter().copied().collect()
+    }
+
+    fn read_memory(&self, address: u32) -> Option<u8> {
+        tracer::read_paged_memory(&self.memory, PAGE_SIZE, address)
+    }
+}
+
+impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
+    /// The current state of the VM, as committed to by the proofs of the
+    /// chunks of the execution trace.
+    pub fn vm_state(&self) -> VmState<Fp> {
+        VmState {
+            instruction_counter: Fp::from(self.instruction_counter),
+            registers: self.registers.iter().map(|r| Fp::from(*r)).collect(),
+        }
+    }
+
+    /// The current state of the VM and the content of its memory. Taken
+    /// before the execution, it is the state to which the proofs of the
+    /// chunks of the execution are bound, see
+    /// [crate::pickles::verifier::verify_chain].
+    pub fn program_state(&self) -> ProgramState<Fp> {
+        ProgramState {
+            vm_state: self.vm_state(),
+            memory: self.memory.clone(),
+        }
+    }
+}
@@ -4,15 +4,12 @@ use super::{
         Instruction::{self, IType, MType, RType, SBType, SType, SyscallType, UJType, UType},
         RInstruction, SBInstruction, SInstruction, SyscallInstruction, UInstruction, UJInstruction,
     },
-    INSTRUCTION_SET_SIZE, SCRATCH_SIZE,
-};
-use kimchi::circuits::{
-    berkeley_columns::BerkeleyChallengeTerm,
-    expr::{ConstantExpr, Expr},
+    INSTRUCTION_SET_SIZE, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE,
 };
+use kimchi_msm::columns::{Column as MSMColumn, ColumnIndexer};
 use strum::EnumCount;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Column {
     ScratchState(usize),
     InstructionCounter,
@@ -100,5 +97,34 @@ impl From<Instruction> for usize {
     }
 }
 
-// FIXME: use other challenges, not Berkeley.
-pub type E<F> = Expr<ConstantExpr<F, BerkeleyChallengeTerm>, Column>;
+/// The number of columns used for relation witness in the RISC-V circuit, i.e.
+/// the scratch state, the instruction counter and the error term.
+pub const N_RISCV_REL_COLS: usize = SCRATCH_SIZE + SCRATCH_SIZE_INVERSE + 2;
+
+impl ColumnIndexer for Column {
+    const N_COL: usize = N_RISCV_REL_COLS + INSTRUCTION_SET_SIZE;
+
+    fn to_column(self) -> MSMColumn {
+        match self {
+            Self::ScratchState(ss) => {
+                assert!(
+                    ss < SCRATCH_SIZE,
+                    "The maximum index is {}, got {}",
+                    SCRATCH_SIZE,
+                    ss
+                );
+                MSMColumn::Relation(ss)
+            }
+            Self::InstructionCounter => MSMColumn::Relation(SCRATCH_SIZE + SCRATCH_SIZE_INVERSE),
+            Self::Selector(s) => {
+                assert!(
+                    s < INSTRUCTION_SET_SIZE,
+                    "The maximum index is {}, got {}",
+                    INSTRUCTION_SET_SIZE,
+                    s
+                );
+                MSMColumn::DynamicSelector(s)
+            }
+        }
+    }
+}
@@ -1,23 +1,35 @@
 use super::{
-    column::{Column, E},
+    column::Column,
     interpreter::{Instruction, InterpreterEnv},
     INSTRUCTION_SET_SIZE,
 };
 use crate::{
     interpreters::riscv32im::{constraints::ConstantTerm::Literal, SCRATCH_SIZE},
     lookups::Lookup,
+    E,
 };
 use ark_ff::{Field, One};
 use kimchi::circuits::{
     expr::{ConstantTerm, Expr, ExprInner, Operations, Variable},
     gate::CurrOrNext,
 };
+use kimchi_msm::columns::ColumnIndexer as _;
 
 pub struct Env<F: Field> {
     pub scratch_state_idx: usize,
     pub lookups: Vec<Lookup<E<F>>>,
     pub constraints: Vec<E<F>>,
     pub selector: Option<E<F>>,
+    /// Number of times the instruction counter has been increased since the
+    /// beginning of the instruction. The instruction counter column contains
+    /// the value at the beginning of the instruction, and the witness
+    /// increases it after each access to a register or to the memory.
+    pub instruction_counter_offset: u32,
+    /// The values range checked by the instruction, i.e. the time elapsed
+    /// since the previous access to each register or memory cell accessed.
+    /// They are checked by the memory checking argument of the pickles
+    /// flavor, see [crate::pickles::ram].
+    pub range_checks: Vec<E<F>>,
 }
 
 impl<Fp: Field> Default for Env<Fp> {
@@ -27,6 +39,8 @@ impl<Fp: Field> Default for Env<Fp> {
             constraints: Vec::new(),
             lookups: Vec::new(),
             selector: None,
+            instruction_counter_offset: 0,
+            range_checks: Vec::new(),
         }
     }
 }
@@ -53,7 +67,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
 
     fn variable(&self, column: Self::Position) -> Self::Variable {
         Expr::Atom(ExprInner::Cell(Variable {
-            col: column,
+            col: column.to_column(),
             row: CurrOrNext::Curr,
         }))
     }
@@ -86,11 +100,15 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
     }
 
     fn instruction_counter(&self) -> Self::Variable {
-        self.variable(Column::InstructionCounter)
+        self.variable(Column::InstructionCounter) + Self::constant(self.instruction_counter_offset)
     }
 
     fn increase_instruction_counter(&mut self) {
-        // No-op, witness only
+        self.instruction_counter_offset += 1;
+    }
+
+    fn range_check64(&mut self, value: &Self::Variable) {
+        self.range_checks.push(value.clone());
     }
 
     unsafe fn fetch_register(
@@ -431,6 +449,8 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         self.constraints.clear();
         self.lookups.clear();
         self.selector = None;
+        self.instruction_counter_offset = 0;
+        self.range_checks.clear();
     }
 }
 
@@ -468,4 +488,8 @@ impl<Fp: Field> Env<Fp> {
     pub fn get_lookups(&self) -> Vec<Lookup<E<Fp>>> {
         self.lookups.clone()
     }
+
+    pub fn get_range_checks(&self) -> Vec<E<Fp>> {
+        self.range_checks.clone()
+    }
 }
@@ -938,9 +938,11 @@ pub trait InterpreterEnv {
         ));
     }
 
-    fn range_check64(&mut self, _value: &Self::Variable) {
-        // TODO
-    }
+    /// Range check that `value` is in `[0, 2^64)`. The check is not made by
+    /// the interpreter: the constraint environment records the value, which
+    /// is decomposed in bytes by the memory checking argument of the pickles
+    /// flavor, see [crate::pickles::ram].
+    fn range_check64(&mut self, _value: &Self::Variable) {}
 
     fn set_instruction_pointer(&mut self, ip: Self::Variable) {
         let idx = Self::constant(REGISTER_CURRENT_IP as u32);
@@ -963,10 +965,16 @@ pub trait InterpreterEnv {
             let value_location = self.alloc_scratch();
             unsafe { self.fetch_register(&idx, value_location) }
         };
+        let last_accessed = {
+            let last_accessed_location = self.alloc_scratch();
+            unsafe { self.fetch_register_access(&idx, last_accessed_location) }
+        };
+        let elapsed_time = self.instruction_counter() - last_accessed.clone();
         self.add_lookup(Lookup::write_one(
             LookupTableIDs::RegisterLookup,
-            vec![idx, self.instruction_counter(), ip.clone()],
+            vec![idx, last_accessed, ip.clone()],
         ));
+        self.range_check64(&elapsed_time);
         ip
     }
 
@@ -991,10 +999,16 @@ pub trait InterpreterEnv {
             let value_location = self.alloc_scratch();
             unsafe { self.fetch_register(&idx, value_location) }
         };
+        let last_accessed = {
+            let last_accessed_location = self.alloc_scratch();
+            unsafe { self.fetch_register_access(&idx, last_accessed_location) }
+        };
+        let elapsed_time = self.instruction_counter() - last_accessed.clone();
         self.add_lookup(Lookup::write_one(
             LookupTableIDs::RegisterLookup,
-            vec![idx, self.instruction_counter(), ip.clone()],
+            vec![idx, last_accessed, ip.clone()],
         ));
+        self.range_check64(&elapsed_time);
         ip
     }
 
@@ -1631,10 +1645,18 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RInstruction)
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
         }
         RInstruction::Fence => {
-            unimplemented!("Fence")
+            // fence: there is a single hart and no device I/O, therefore all
+            // the memory accesses are already ordered and the instruction is
+            // a no-op.
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
         }
         RInstruction::FenceI => {
-            unimplemented!("FenceI")
+            // fence.i: the instructions are fetched from the same memory the
+            // program writes into, therefore the instruction stream is always
+            // synchronized and the instruction is a no-op.
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
         }
     };
 }
@@ -1753,7 +1775,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             // Add a range check here for address
             let v0 = env.read_memory(&address);
             let v1 = env.read_memory(&(address.clone() + Env::constant(1)));
-            let value = (v0 * Env::constant(1 << 8)) + v1;
+            let value = (v1 * Env::constant(1 << 8)) + v0;
             let value = env.sign_extend(&value, 16);
             env.write_register(&rd, value);
             env.set_instruction_pointer(next_instruction_pointer.clone());
@@ -1775,11 +1797,10 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             let v1 = env.read_memory(&(address.clone() + Env::constant(1)));
             let v2 = env.read_memory(&(address.clone() + Env::constant(2)));
             let v3 = env.read_memory(&(address.clone() + Env::constant(3)));
-            let value = (v0 * Env::constant(1 << 24))
-                + (v1 * Env::constant(1 << 16))
-                + (v2 * Env::constant(1 << 8))
-                + v3;
-            let value = env.sign_extend(&value, 32);
+            let value = (v3 * Env::constant(1 << 24))
+                + (v2 * Env::constant(1 << 16))
+                + (v1 * Env::constant(1 << 8))
+                + v0;
             env.write_register(&rd, value);
             env.set_instruction_pointer(next_instruction_pointer.clone());
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
@@ -1817,7 +1838,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             // Add a range check here for address
             let v0 = env.read_memory(&address);
             let v1 = env.read_memory(&(address.clone() + Env::constant(1)));
-            let value = (v0 * Env::constant(1 << 8)) + v1;
+            let value = (v1 * Env::constant(1 << 8)) + v0;
             env.write_register(&rd, value);
             env.set_instruction_pointer(next_instruction_pointer.clone());
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
@@ -1859,10 +1880,28 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
         }
         IInstruction::SetLessThanImmediate => {
-            unimplemented!("SetLessThanImmediate")
+            // slti: x[rd] = (x[rs1] < sext(immediate)) ? 1 : 0
+            let local_rs1 = env.read_register(&rs1);
+            let local_imm = env.sign_extend(&imm, 12);
+            let local_rd = unsafe {
+                let pos = env.alloc_scratch();
+                env.test_less_than_signed(&local_rs1, &local_imm, pos)
+            };
+            env.write_register(&rd, local_rd);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
         }
         IInstruction::SetLessThanImmediateUnsigned => {
-            unimplemented!("SetLessThanImmediateUnsigned")
+            // sltiu: x[rd] = (x[rs1] < (u)sext(immediate)) ? 1 : 0
+            let local_rs1 = env.read_register(&rs1);
+            let local_imm = env.sign_extend(&imm, 12);
+            let local_rd = unsafe {
+                let pos = env.alloc_scratch();
+                env.test_less_than(&local_rs1, &local_imm, pos)
+            };
+            env.write_register(&rd, local_rd);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
         }
         IInstruction::AddImmediate => {
             // addi: x[rd] = x[rs1] + sext(immediate)
@@ -1941,7 +1980,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
 /// [here](https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf)
 pub fn interpret_stype<Env: InterpreterEnv>(env: &mut Env, instr: SInstruction) {
     let instruction_pointer = env.get_instruction_pointer();
-    let _next_instruction_pointer = env.get_next_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
 
     let instruction = {
         let v0 = env.read_memory(&instruction_pointer);
@@ -1988,19 +2027,76 @@ pub fn interpret_stype<Env: InterpreterEnv>(env: &mut Env, instr: SInstruction)
         let pos = env.alloc_scratch();
         unsafe { env.bitmask(&instruction, 32, 25, pos) }
     };
-    env.range_check16(&imm2, 12);
+    env.range_check8(&imm2, 7);
+
+    // check correctness of decomposition
+    env.add_constraint(
+        instruction
+            - (opcode.clone() * Env::constant(1 << 0))    // opcode at bits 0-6
+            - (imm1.clone() * Env::constant(1 << 7))      // imm1 at bits 7-11
+            - (funct3.clone() * Env::constant(1 << 12))   // funct3 at bits 12-14
+            - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
+            - (rs2.clone() * Env::constant(1 << 20))      // rs2 at bits 20-24
+            - (imm2.clone() * Env::constant(1 << 25)), // imm2 at bits 25-31
+    );
+
+    let local_rs1 = env.read_register(&rs1);
+    let local_imm = {
+        let imm = imm2 * Env::constant(1 << 5) + imm1;
+        env.sign_extend(&imm, 12)
+    };
+    let address = {
+        let address_scratch = env.alloc_scratch();
+        let overflow_scratch = env.alloc_scratch();
+        let (address, _overflow) =
+            unsafe { env.add_witness(&local_rs1, &local_imm, address_scratch, overflow_scratch) };
+        address
+    };
+    // Add a range check here for address
+    let value = env.read_register(&rs2);
 
+    // The stored bytes are taken from the decomposition of the whole value
+    // into bytes, so that they are constrained for all the store instructions.
+    let [v0, v1, v2, v3] = [0, 1, 2, 3].map(|i| {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&value, 8 * (i + 1), 8 * i, pos) }
+    });
+    env.lookup_8bits(&v0);
+    env.lookup_8bits(&v1);
+    env.lookup_8bits(&v2);
+    env.lookup_8bits(&v3);
+    // check the decomposition of the value
+    env.add_constraint(
+        value
+            - (v3.clone() * Env::constant(1 << 24))
+            - (v2.clone() * Env::constant(1 << 16))
+            - (v1.clone() * Env::constant(1 << 8))
+            - v0.clone(),
+    );
+
+    // The memory is little-endian: the least significant byte is stored at
+    // the lowest address.
     match instr {
         SInstruction::StoreByte => {
-            unimplemented!("StoreByte")
+            // sb: M[x[rs1] + sext(offset)] = x[rs2][7:0]
+            env.write_memory(&address, v0);
         }
         SInstruction::StoreHalf => {
-            unimplemented!("StoreHalf")
+            // sh: M[x[rs1] + sext(offset)] = x[rs2][15:0]
+            env.write_memory(&address, v0);
+            env.write_memory(&(address.clone() + Env::constant(1)), v1);
         }
         SInstruction::StoreWord => {
-            unimplemented!("StoreWord")
+            // sw: M[x[rs1] + sext(offset)] = x[rs2][31:0]
+            env.write_memory(&address, v0);
+            env.write_memory(&(address.clone() + Env::constant(1)), v1);
+            env.write_memory(&(address.clone() + Env::constant(2)), v2);
+            env.write_memory(&(address.clone() + Env::constant(3)), v3);
         }
     };
+
+    env.set_instruction_pointer(next_instruction_pointer.clone());
+    env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
 }
 
 /// Interpret an SB-type instruction.
@@ -2013,7 +2109,7 @@ pub fn interpret_stype<Env: InterpreterEnv>(env: &mut Env, instr: SInstruction)
 /// [here](https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf)
 pub fn interpret_sbtype<Env: InterpreterEnv>(env: &mut Env, instr: SBInstruction) {
     let instruction_pointer = env.get_instruction_pointer();
-    let _next_instruction_pointer = env.get_next_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
 
     let instruction = {
         let v0 = env.read_memory(&instruction_pointer);
@@ -2032,7 +2128,7 @@ pub fn interpret_sbtype<Env: InterpreterEnv>(env: &mut Env, instr: SBInstruction
     };
     env.range_check8(&opcode, 7);
 
-    // FIXME: trickier
+    // imm1 encodes imm[4:1|11]
     let imm1 = {
         let pos = env.alloc_scratch();
         unsafe { env.bitmask(&instruction, 12, 7, pos) }
@@ -2057,35 +2153,120 @@ pub fn interpret_sbtype<Env: InterpreterEnv>(env: &mut Env, instr: SBInstruction
     };
     env.range_check8(&rs2, 5);
 
-    // FIXME: trickier
+    // imm2 encodes imm[12|10:5]
     let imm2 = {
         let pos = env.alloc_scratch();
         unsafe { env.bitmask(&instruction, 32, 25, pos) }
     };
-    env.range_check16(&imm2, 12);
+    env.range_check8(&imm2, 7);
+
+    // check correctness of decomposition
+    env.add_constraint(
+        instruction
+            - (opcode.clone() * Env::constant(1 << 0))    // opcode at bits 0-6
+            - (imm1.clone() * Env::constant(1 << 7))      // imm1 at bits 7-11
+            - (funct3.clone() * Env::constant(1 << 12))   // funct3 at bits 12-14
+            - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
+            - (rs2.clone() * Env::constant(1 << 20))      // rs2 at bits 20-24
+            - (imm2.clone() * Env::constant(1 << 25)), // imm2 at bits 25-31
+    );
+
+    // Reassemble the offset imm[12:1], the lowest bit is always 0.
+    let offset = {
+        let imm11 = {
+            let pos = env.alloc_scratch();
+            unsafe { env.bitmask(&imm1, 1, 0, pos) }
+        };
+        env.assert_boolean(imm11.clone());
+        let imm4_1 = {
+            let pos = env.alloc_scratch();
+            unsafe { env.bitmask(&imm1, 5, 1, pos) }
+        };
+        env.range_check8(&imm4_1, 4);
+        env.add_constraint(imm1 - imm4_1.clone() * Env::constant(1 << 1) - imm11.clone());
+
+        let imm10_5 = {
+            let pos = env.alloc_scratch();
+            unsafe { env.bitmask(&imm2, 6, 0, pos) }
+        };
+        env.range_check8(&imm10_5, 6);
+        let imm12 = {
+            let pos = env.alloc_scratch();
+            unsafe { env.bitmask(&imm2, 7, 6, pos) }
+        };
+        env.assert_boolean(imm12.clone());
+        env.add_constraint(imm2 - imm12.clone() * Env::constant(1 << 6) - imm10_5.clone());
+
+        let offset = imm12 * Env::constant(1 << 12)
+            + imm11 * Env::constant(1 << 11)
+            + imm10_5 * Env::constant(1 << 5)
+            + imm4_1 * Env::constant(1 << 1);
+        env.sign_extend(&offset, 13)
+    };
 
-    // FIXME: check correctness of decomposition
+    let local_rs1 = env.read_register(&rs1);
+    let local_rs2 = env.read_register(&rs2);
 
-    match instr {
+    // 1 if the branch is taken, 0 otherwise
+    let branch_taken = match instr {
         SBInstruction::BranchEq => {
-            unimplemented!("BranchEq")
+            // beq: if (x[rs1] == x[rs2]) pc += sext(offset)
+            env.equal(&local_rs1, &local_rs2)
         }
         SBInstruction::BranchNeq => {
-            unimplemented!("BranchNeq")
+            // bne: if (x[rs1] != x[rs2]) pc += sext(offset)
+            let equal = env.equal(&local_rs1, &local_rs2);
+            Env::constant(1) - equal
         }
         SBInstruction::BranchLessThan => {
-            unimplemented!("BranchLessThan")
+            // blt: if (x[rs1] < x[rs2]) pc += sext(offset)
+            let pos = env.alloc_scratch();
+            unsafe { env.test_less_than_signed(&local_rs1, &local_rs2, pos) }
         }
         SBInstruction::BranchGreaterThanEqual => {
-            unimplemented!("BranchGreaterThanEqual")
+            // bge: if (x[rs1] >= x[rs2]) pc += sext(offset)
+            let less_than = {
+                let pos = env.alloc_scratch();
+                unsafe { env.test_less_than_signed(&local_rs1, &local_rs2, pos) }
+            };
+            Env::constant(1) - less_than
         }
         SBInstruction::BranchLessThanUnsigned => {
-            unimplemented!("BranchLessThanUnsigned")
+            // bltu: if (x[rs1] < (u)x[rs2]) pc += sext(offset)
+            let pos = env.alloc_scratch();
+            unsafe { env.test_less_than(&local_rs1, &local_rs2, pos) }
         }
         SBInstruction::BranchGreaterThanEqualUnsigned => {
-            unimplemented!("BranchGreaterThanEqualUnsigned")
+            // bgeu: if (x[rs1] >= (u)x[rs2]) pc += sext(offset)
+            let less_than = {
+                let pos = env.alloc_scratch();
+                unsafe { env.test_less_than(&local_rs1, &local_rs2, pos) }
+            };
+            Env::constant(1) - less_than
         }
     };
+
+    let target = {
+        let target_scratch = env.alloc_scratch();
+        let overflow_scratch = env.alloc_scratch();
+        let (target, _overflow) = unsafe {
+            env.add_witness(
+                &instruction_pointer,
+                &offset,
+                target_scratch,
+                overflow_scratch,
+            )
+        };
+        target
+    };
+    let new_instruction_pointer = {
+        let pos = env.alloc_scratch();
+        let res = branch_taken.clone() * target
+            + (Env::constant(1) - branch_taken) * next_instruction_pointer;
+        env.copy(&res, pos)
+    };
+    env.set_instruction_pointer(new_instruction_pointer.clone());
+    env.set_next_instruction_pointer(new_instruction_pointer + Env::constant(4u32));
 }
 
 /// Interpret an U-type instruction.
@@ -2098,7 +2279,7 @@ pub fn interpret_sbtype<Env: InterpreterEnv>(env: &mut Env, instr: SBInstruction
 /// [here](https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf)
 pub fn interpret_utype<Env: InterpreterEnv>(env: &mut Env, instr: UInstruction) {
     let instruction_pointer = env.get_instruction_pointer();
-    let _next_instruction_pointer = env.get_next_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
 
     let instruction = {
         let v0 = env.read_memory(&instruction_pointer);
@@ -2139,12 +2320,27 @@ pub fn interpret_utype<Env: InterpreterEnv>(env: &mut Env, instr: UInstruction)
 
     match instr {
         UInstruction::LoadUpperImmediate => {
-            unimplemented!("LoadUpperImmediate")
+            // lui: x[rd] = sext(immediate[31:12] << 12)
+            let local_rd = imm * Env::constant(1 << 12);
+            env.write_register(&rd, local_rd);
         }
         UInstruction::AddUpperImmediate => {
-            unimplemented!("AddUpperImmediate")
+            // auipc: x[rd] = pc + sext(immediate[31:12] << 12)
+            let offset = imm * Env::constant(1 << 12);
+            let local_rd = {
+                let rd_scratch = env.alloc_scratch();
+                let overflow_scratch = env.alloc_scratch();
+                let (local_rd, _overflow) = unsafe {
+                    env.add_witness(&instruction_pointer, &offset, rd_scratch, overflow_scratch)
+                };
+                local_rd
+            };
+            env.write_register(&rd, local_rd);
         }
     };
+
+    env.set_instruction_pointer(next_instruction_pointer.clone());
+    env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
 }
 
 /// Interpret an UJ-type instruction.
@@ -2157,7 +2353,7 @@ pub fn interpret_utype<Env: InterpreterEnv>(env: &mut Env, instr: UInstruction)
 /// [here](https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf)
 pub fn interpret_ujtype<Env: InterpreterEnv>(env: &mut Env, instr: UJInstruction) {
     let instruction_pointer = env.get_instruction_pointer();
-    let _next_instruction_pointer = env.get_next_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
 
     let instruction = {
         let v0 = env.read_memory(&instruction_pointer);
@@ -2182,16 +2378,65 @@ pub fn interpret_ujtype<Env: InterpreterEnv>(env: &mut Env, instr: UJInstruction
     };
     env.range_check8(&rd, 5);
 
-    // FIXME: trickier
-    let _imm = {
+    // The immediate encodes imm[20|10:1|11|19:12]
+    let imm19_12 = {
         let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 32, 12, pos) }
+        unsafe { env.bitmask(&instruction, 20, 12, pos) }
+    };
+    env.range_check8(&imm19_12, 8);
+
+    let imm11 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 21, 20, pos) }
+    };
+    env.assert_boolean(imm11.clone());
+
+    let imm10_1 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 31, 21, pos) }
+    };
+    env.range_check16(&imm10_1, 10);
+
+    let imm20 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 32, 31, pos) }
+    };
+    env.assert_boolean(imm20.clone());
+
+    // check correctness of decomposition
+    env.add_constraint(
+        instruction
+            - (opcode.clone() * Env::constant(1 << 0))    // opcode at bits 0-6
+            - (rd.clone() * Env::constant(1 << 7))        // rd at bits 7-11
+            - (imm19_12.clone() * Env::constant(1 << 12)) // imm[19:12] at bits 12-19
+            - (imm11.clone() * Env::constant(1 << 20))    // imm[11] at bit 20
+            - (imm10_1.clone() * Env::constant(1 << 21))  // imm[10:1] at bits 21-30
+            - (imm20.clone() * Env::constant(1 << 31)), // imm[20] at bit 31
+    );
+
+    // Reassemble the offset imm[20:1], the lowest bit is always 0.
+    let offset = {
+        let offset = imm20 * Env::constant(1 << 20)
+            + imm19_12 * Env::constant(1 << 12)
+            + imm11 * Env::constant(1 << 11)
+            + imm10_1 * Env::constant(1 << 1);
+        env.sign_extend(&offset, 21)
     };
 
-    // FIXME: check correctness of decomposition
     match instr {
         UJInstruction::JumpAndLink => {
-            unimplemented!("JumpAndLink")
+            // jal: x[rd] = pc+4; pc += sext(offset)
+            let new_instruction_pointer = {
+                let res_scratch = env.alloc_scratch();
+                let overflow_scratch = env.alloc_scratch();
+                let (res, _overflow) = unsafe {
+                    env.add_witness(&instruction_pointer, &offset, res_scratch, overflow_scratch)
+                };
+                res
+            };
+            env.write_register(&rd, next_instruction_pointer);
+            env.set_instruction_pointer(new_instruction_pointer.clone());
+            env.set_next_instruction_pointer(new_instruction_pointer + Env::constant(4u32));
         }
     };
 }
@@ -3,6 +3,10 @@
 // implemented. Using a small value for now.
 pub const SCRATCH_SIZE: usize = 80;
 
+/// Number of columns used to keep values to be inverted. The RISC-V
+/// interpreter does not batch inversions for now.
+pub const SCRATCH_SIZE_INVERSE: usize = 0;
+
 /// Number of instructions in the ISA
 pub const INSTRUCTION_SET_SIZE: usize = 48;
 
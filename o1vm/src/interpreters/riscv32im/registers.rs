@@ -3,10 +3,10 @@ use std::ops::{Index, IndexMut};
 use serde::{Deserialize, Serialize};
 
 pub const N_GP_REGISTERS: usize = 32;
-// FIXME:
-pub const REGISTER_CURRENT_IP: usize = N_GP_REGISTERS + 1;
-pub const REGISTER_NEXT_IP: usize = N_GP_REGISTERS + 2;
-pub const REGISTER_HEAP_POINTER: usize = N_GP_REGISTERS + 3;
+// The indices follow the order of [Registers::iter].
+pub const REGISTER_CURRENT_IP: usize = N_GP_REGISTERS;
+pub const REGISTER_NEXT_IP: usize = N_GP_REGISTERS + 1;
+pub const REGISTER_HEAP_POINTER: usize = N_GP_REGISTERS + 2;
 
 /// This represents the internal state of the virtual machine.
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
@@ -31,6 +31,16 @@ pub struct Registers<T> {
     pub heap_pointer: T,
 }
 
+impl<T> Registers<T> {
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.general_purpose.iter().chain([
+            &self.current_instruction_pointer,
+            &self.next_instruction_pointer,
+            &self.heap_pointer,
+        ])
+    }
+}
+
 impl<T: Clone> Index<usize> for Registers<T> {
     type Output = T;
 
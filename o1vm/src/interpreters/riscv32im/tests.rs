@@ -1,15 +1,19 @@
 use super::{registers::Registers, witness::Env, INSTRUCTION_SET_SIZE, PAGE_SIZE, SCRATCH_SIZE};
-use crate::interpreters::riscv32im::{
-    constraints,
-    interpreter::{
-        IInstruction, Instruction, InterpreterEnv, MInstruction, RInstruction, SBInstruction,
-        SInstruction, SyscallInstruction, UInstruction, UJInstruction,
+use crate::{
+    interpreters::riscv32im::{
+        constraints,
+        interpreter::{
+            interpret_instruction, IInstruction, Instruction, InterpreterEnv, MInstruction,
+            RInstruction, SBInstruction, SInstruction, SyscallInstruction, UInstruction,
+            UJInstruction,
+        },
     },
+    ramlookup::RAMMultiset,
 };
 use ark_ff::Zero;
 use mina_curves::pasta::Fp;
 use rand::{CryptoRng, Rng, RngCore};
-use strum::EnumCount;
+use strum::{EnumCount, IntoEnumIterator};
 
 // Sanity check that we have as many selector as we have instructions
 #[test]
@@ -38,6 +42,31 @@ fn test_regression_selectors_for_instructions() {
         .for_each(|c| assert!(c.degree(1, 0) == 2 || c.degree(1, 0) == 1));
 }
 
+// Check that the constraints can be built for all the instructions, and that
+// they fit in the scratch state and have at most degree 2.
+#[test]
+fn test_constraints_can_be_built_for_all_instructions() {
+    let mut constraints_env = constraints::Env::<Fp>::default();
+    Instruction::iter()
+        .flat_map(|x| x.into_iter())
+        .for_each(|instr| {
+            interpret_instruction(&mut constraints_env, instr);
+            assert!(
+                constraints_env.scratch_state_idx <= SCRATCH_SIZE,
+                "Instruction {:?} uses too many columns",
+                instr
+            );
+            constraints_env.get_constraints().iter().for_each(|c| {
+                assert!(
+                    c.degree(1, 0) <= 2,
+                    "Instruction {:?} has a constraint of degree greater than 2",
+                    instr
+                )
+            });
+            constraints_env.reset();
+        });
+}
+
 pub fn dummy_env() -> Env<Fp> {
     Env {
         instruction_counter: 0,
@@ -51,6 +80,8 @@ pub fn dummy_env() -> Env<Fp> {
         scratch_state: [Fp::zero(); SCRATCH_SIZE],
         halt: false,
         selector: INSTRUCTION_SET_SIZE,
+        ram_lookups: RAMMultiset::default(),
+        tracer: None,
     }
 }
 
@@ -447,3 +478,47 @@ pub fn test_witness_bitmask_bounds() {
         assert_eq!(output, exp_output);
     }
 }
+
+#[test]
+pub fn test_witness_store_and_load_are_little_endian() {
+    let mut env: Env<Fp> = dummy_env();
+    // sw x5, 4(x6); lw x7, 4(x6); lbu x28, 5(x6)
+    let program: [u32; 3] = [0x00532223, 0x00432383, 0x00534e03];
+    program.iter().enumerate().for_each(|(i, instruction)| {
+        env.memory[0].1[4 * i..4 * (i + 1)].copy_from_slice(&instruction.to_le_bytes())
+    });
+    env.registers.next_instruction_pointer = 4;
+    env.registers[5] = 0x12345678;
+    env.registers[6] = 0x100;
+
+    assert_eq!(env.step(), Instruction::SType(SInstruction::StoreWord));
+    assert_eq!(env.memory[0].1[0x104..0x108], [0x78, 0x56, 0x34, 0x12]);
+
+    assert_eq!(env.step(), Instruction::IType(IInstruction::LoadWord));
+    assert_eq!(env.registers[7], 0x12345678);
+
+    assert_eq!(
+        env.step(),
+        Instruction::IType(IInstruction::LoadByteUnsigned)
+    );
+    assert_eq!(env.registers[28], 0x56);
+}
+
+#[test]
+pub fn test_witness_store_byte_and_half() {
+    let mut env: Env<Fp> = dummy_env();
+    // sb x5, 8(x6); sh x5, 12(x6)
+    let program: [u32; 2] = [0x00530423, 0x00531623];
+    program.iter().enumerate().for_each(|(i, instruction)| {
+        env.memory[0].1[4 * i..4 * (i + 1)].copy_from_slice(&instruction.to_le_bytes())
+    });
+    env.registers.next_instruction_pointer = 4;
+    env.registers[5] = 0x12345678;
+    env.registers[6] = 0x100;
+
+    assert_eq!(env.step(), Instruction::SType(SInstruction::StoreByte));
+    assert_eq!(env.memory[0].1[0x108..0x10c], [0x78, 0, 0, 0]);
+
+    assert_eq!(env.step(), Instruction::SType(SInstruction::StoreHalf));
+    assert_eq!(env.memory[0].1[0x10c..0x110], [0x78, 0x56, 0, 0]);
+}
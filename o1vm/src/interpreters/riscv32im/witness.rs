@@ -3,17 +3,21 @@
 use super::{
     column::Column,
     interpreter::{
-        self, IInstruction, Instruction, InterpreterEnv, RInstruction, SBInstruction, SInstruction,
-        SyscallInstruction, UInstruction, UJInstruction,
+        self, IInstruction, Instruction, InterpreterEnv, MInstruction, RInstruction, SBInstruction,
+        SInstruction, SyscallInstruction, UInstruction, UJInstruction,
     },
     registers::Registers,
     INSTRUCTION_SET_SIZE, SCRATCH_SIZE,
 };
 use crate::{
     cannon::{State, PAGE_ADDRESS_MASK, PAGE_ADDRESS_SIZE, PAGE_SIZE},
-    lookups::Lookup,
+    lookups::{Lookup, LookupTableIDs},
+    pickles::proof::{ProgramState, VmState},
+    ramlookup::RAMMultiset,
+    tracer::{self, DebuggerAction, Inspect, Tracer},
 };
-use ark_ff::Field;
+use ark_ff::{Field, PrimeField};
+use log::info;
 use std::array;
 
 /// Maximum number of register accesses per instruction (based on demo)
@@ -48,6 +52,12 @@ pub struct Env<Fp> {
     pub scratch_state: [Fp; SCRATCH_SIZE],
     pub halt: bool,
     pub selector: usize,
+    /// The accesses to the registers and to the memory since the beginning of
+    /// the current chunk, see [crate::pickles::ram].
+    pub ram_lookups: RAMMultiset<LookupTableIDs>,
+    /// Traces the executed instructions and interrupts the execution on
+    /// breakpoints, if set
+    pub tracer: Option<Tracer>,
 }
 
 fn fresh_scratch_state<Fp: Field, const N: usize>() -> [Fp; N] {
@@ -94,9 +104,14 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         }
     }
 
-    fn add_lookup(&mut self, _lookup: Lookup<Self::Variable>) {
-        // No-op, constraints only
+    fn add_lookup(&mut self, lookup: Lookup<Self::Variable>) {
         // TODO: keep track of multiplicities of fixed tables here as in Keccak?
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer.record_lookup(&lookup);
+        }
+        if lookup.table_id.is_ram() {
+            self.ram_lookups.add(&lookup);
+        }
     }
 
     fn instruction_counter(&self) -> Self::Variable {
@@ -301,12 +316,19 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
     }
 
     fn equal(&mut self, x: &Self::Variable, y: &Self::Variable) -> Self::Variable {
-        // To avoid subtraction overflow in the witness interpreter for u32
-        if x > y {
-            self.is_zero(&(*x - *y))
-        } else {
-            self.is_zero(&(*y - *x))
-        }
+        // We do not use `is_zero` as `x - y` might underflow. The difference is
+        // computed over the field instead, as it is done in the constraints.
+        // write the result
+        let pos = self.alloc_scratch();
+        let res = if *x == *y { 1 } else { 0 };
+        self.write_column(pos, res);
+        // write the non deterministic advice inv_or_zero
+        let pos = self.alloc_scratch();
+        let diff = Fp::from(*x) - Fp::from(*y);
+        let inv_or_zero = diff.inverse().unwrap_or(Fp::zero());
+        self.write_field_column(pos, inv_or_zero);
+        // return the result
+        res
     }
 
     unsafe fn test_less_than(
@@ -664,7 +686,8 @@ impl<Fp: Field> Env<Fp> {
         // set the stack pointer to the top of the stack
 
         Env {
-            instruction_counter: state.step,
+            // The instruction counter increases by MAX_ACC after each step
+            instruction_counter: state.step * MAX_ACC,
             memory: initial_memory.clone(),
             last_memory_accesses: [0usize; 3],
             memory_write_index: memory_offsets
@@ -678,6 +701,8 @@ impl<Fp: Field> Env<Fp> {
             scratch_state: fresh_scratch_state(),
             halt: state.exited,
             selector,
+            ram_lookups: RAMMultiset::default(),
+            tracer: None,
         }
     }
 
@@ -750,6 +775,19 @@ impl<Fp: Field> Env<Fp> {
                     },
                     _ => panic!("Unknown IType instruction with full inst {}", instruction),
                 },
+                0b0110011 if (instruction >> 25) & 0x7F == 0b0000001 => // funct7 for the M extension
+                match (instruction >> 12) & 0x7 // bits 12-14 for func3
+                {
+                    0b000 => Instruction::MType(MInstruction::Mul),
+                    0b001 => Instruction::MType(MInstruction::Mulh),
+                    0b010 => Instruction::MType(MInstruction::Mulhsu),
+                    0b011 => Instruction::MType(MInstruction::Mulhu),
+                    0b100 => Instruction::MType(MInstruction::Div),
+                    0b101 => Instruction::MType(MInstruction::Divu),
+                    0b110 => Instruction::MType(MInstruction::Rem),
+                    0b111 => Instruction::MType(MInstruction::Remu),
+                    _ => panic!("Unknown MType 0110011 instruction with full inst {}", instruction),
+                },
                 0b0110011 =>
                 match (instruction >> 12) & 0x7 // bits 12-14 for func3
                 {
@@ -795,10 +833,26 @@ impl<Fp: Field> Env<Fp> {
     /// Execute a single step in the RISCV32i program
     pub fn step(&mut self) -> Instruction {
         self.reset_scratch_state();
-        let (opcode, _instruction) = self.decode_instruction();
+        let (opcode, instruction) = self.decode_instruction();
+
+        if let Some(mut tracer) = self.tracer.take() {
+            let step = self.normalized_instruction_counter();
+            let pc = self.registers.current_instruction_pointer;
+            let action = tracer.start_step(self, step, pc, instruction, &opcode);
+            self.tracer = Some(tracer);
+            if action == DebuggerAction::Quit {
+                self.halt = true;
+                info!("Halted by the debugger at step={step} instruction={opcode:?}");
+                return opcode;
+            }
+        }
 
         interpreter::interpret_instruction(self, opcode);
 
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer.end_step(&self.registers.iter().copied().collect::<Vec<_>>());
+        }
+
         self.instruction_counter = self.next_instruction_counter();
 
         // Integer division by MAX_ACC to obtain the actual instruction count
@@ -812,6 +866,23 @@ impl<Fp: Field> Env<Fp> {
         opcode
     }
 
+    /// Execute again the instruction that halted the VM. As it does not access
+    /// the registers nor the memory, it leaves the state of the VM unchanged,
+    /// except for the instruction counter, and is used to pad the execution
+    /// trace of the last chunk.
+    /// Returns `false` if the VM is not on a halting instruction, e.g. if it
+    /// has been stopped by the debugger.
+    pub fn step_halted(&mut self) -> bool {
+        let (opcode, _instruction) = self.decode_instruction();
+        if !matches!(opcode, Instruction::SyscallType(_)) {
+            return false;
+        }
+        self.reset_scratch_state();
+        interpreter::interpret_instruction(self, opcode);
+        self.instruction_counter = self.next_instruction_counter();
+        true
+    }
+
     pub fn reset_scratch_state(&mut self) {
         self.scratch_state_idx = 0;
         self.scratch_state = fresh_scratch_state();
@@ -837,7 +908,7 @@ impl<Fp: Field> Env<Fp> {
 
     pub fn get_memory_page_index(&mut self, page: u32) -> usize {
         for &i in self.last_memory_accesses.iter() {
-            if self.memory_write_index[i].0 == page {
+            if self.memory[i].0 == page {
                 return i;
             }
         }
@@ -899,3 +970,35 @@ impl<Fp: Field> Env<Fp> {
         self.instruction_counter / MAX_ACC
     }
 }
+
+impl<Fp> Inspect for Env<Fp> {
+    fn registers(&self) -> Vec<u32> {
+        self.registers.iter().copied().collect()
+    }
+
+    fn read_memory(&self, address: u32) -> Option<u8> {
+        tracer::read_paged_memory(&self.memory, PAGE_SIZE, address)
+    }
+}
+
+impl<Fp: PrimeField> Env<Fp> {
+    /// The current state of the VM, as committed to by the proofs of the
+    /// chunks of the execution trace.
+    pub fn vm_state(&self) -> VmState<Fp> {
+        VmState {
+            instruction_counter: Fp::from(self.instruction_counter),
+            registers: self.registers.iter().map(|r| Fp::from(*r)).collect(),
+        }
+    }
+
+    /// The current state of the VM and the content of its memory. Taken
+    /// before the execution, it is the state to which the proofs of the
+    /// chunks of the execution are bound, see
+    /// [crate::pickles::verifier::verify_chain].
+    pub fn program_state(&self) -> ProgramState<Fp> {
+        ProgramState {
+            vm_state: self.vm_state(),
+            memory: self.memory.clone(),
+        }
+    }
+}
@@ -40,7 +40,7 @@ impl From<BerkeleyChallengeTerm> for Challenge {
 
 /// Folding instance containing the commitment to a witness of N columns,
 /// challenges for the proof, and the alphas
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FoldingInstance<const N: usize, G: CommitmentCurve> {
     /// Commitments to the witness columns, including the dynamic selectors
     pub commitments: [G; N],
@@ -394,7 +394,11 @@ pub mod mips {
     use std::ops::Index;
 
     use ark_poly::{Evaluations, Radix2EvaluationDomain};
-    use folding::{expressions::FoldingColumnTrait, FoldingConfig};
+    use folding::{
+        checker::{Checker, ExtendedProvider, Provider},
+        expressions::FoldingColumnTrait,
+        FoldingConfig,
+    };
     use kimchi_msm::columns::Column;
     use poly_commitment::kzg::PairingSRS;
 
@@ -408,17 +412,34 @@ pub mod mips {
 
     use super::{Challenge, DecomposedFoldingEnvironment, FoldingInstance, FoldingWitness};
 
+    /// The maximum number of lookups performed by a single MIPS instruction.
+    /// Each lookup gets its own column when folding, containing the lookup
+    /// term `numerator / (β + table_id + j * v_0 + j^2 * v_1 + ...)`.
+    // Manually change the number of lookup columns if the lookups are modified
+    // in the interpreter
+    pub const N_MIPS_LOOKUP_COLS: usize = 54;
+
+    /// The number of columns of a folded MIPS witness: the columns of the
+    /// MIPS circuit (including the selectors), followed by the lookup terms
+    /// and by the multiplicity of the lookups of each row.
+    pub const N_MIPS_FOLDING_COLS: usize = N_MIPS_COLS + N_MIPS_LOOKUP_COLS + 1;
+
+    /// The column containing the number of times the lookups of each row are
+    /// counted, i.e. one on the rows of the execution and zero on the rows
+    /// padding a chunk. It is the last column of a folded MIPS witness.
+    pub const MIPS_LOOKUP_MULTIPLICITY: Column = Column::LookupMultiplicity((0, 0));
+
     // Decomposable folding compatibility
     pub type DecomposableMIPSFoldingEnvironment = DecomposedFoldingEnvironment<
-        N_MIPS_COLS,
+        N_MIPS_FOLDING_COLS,
         N_MIPS_REL_COLS,
         N_MIPS_SEL_COLS,
         DecomposableMIPSFoldingConfig,
         (),
     >;
 
-    pub type MIPSFoldingWitness = FoldingWitness<N_MIPS_COLS, Fp>;
-    pub type MIPSFoldingInstance = FoldingInstance<N_MIPS_COLS, Curve>;
+    pub type MIPSFoldingWitness = FoldingWitness<N_MIPS_FOLDING_COLS, Fp>;
+    pub type MIPSFoldingInstance = FoldingInstance<N_MIPS_FOLDING_COLS, Curve>;
 
     // -- Start indexer implementations
     // Implement indexers over columns and selectors to implement an abstract
@@ -460,11 +481,20 @@ pub mod mips {
 
         /// Map a column alias to the corresponding witness column.
         fn index(&self, index: Column) -> &Self::Output {
-            match index {
-                Column::Relation(ix) => &self.witness.cols[ix],
-                Column::DynamicSelector(ix) => &self.witness.cols[N_MIPS_REL_COLS + ix],
-                _ => panic!("Invalid column type"),
-            }
+            &self.witness.cols[column_index(index).expect("Invalid column type")]
+        }
+    }
+
+    /// Returns the position of the column in a folded MIPS witness, if any.
+    pub fn column_index(col: Column) -> Option<usize> {
+        match col {
+            Column::Relation(ix) if ix < N_MIPS_COLS => Some(ix),
+            Column::DynamicSelector(ix) if ix < N_MIPS_SEL_COLS => Some(N_MIPS_REL_COLS + ix),
+            // The lookup terms are indexed by their position in the list of
+            // lookups of the instruction, the table ID is only informative.
+            Column::LookupPartialSum((_, ix)) if ix < N_MIPS_LOOKUP_COLS => Some(N_MIPS_COLS + ix),
+            MIPS_LOOKUP_MULTIPLICITY => Some(N_MIPS_FOLDING_COLS - 1),
+            _ => None,
         }
     }
     // -- End of indexer implementations
@@ -490,6 +520,11 @@ pub mod mips {
         type Structure = ();
         type Env = DecomposableMIPSFoldingEnvironment;
     }
+
+    // IMPLEMENT CHECKER TRAITS
+
+    impl Checker<DecomposableMIPSFoldingConfig> for ExtendedProvider<DecomposableMIPSFoldingConfig> {}
+    impl Checker<DecomposableMIPSFoldingConfig> for Provider<DecomposableMIPSFoldingConfig> {}
 }
 
 #[cfg(test)]
@@ -1,8 +1,8 @@
-use ark_ff::UniformRand;
+use ark_ff::{Field, Zero};
 use folding::decomposable_folding::DecomposableFoldingScheme;
-use kimchi::o1_utils;
+use kimchi::{circuits::domains::EvaluationDomains, o1_utils};
 use kimchi_msm::{proof::ProofInputs, prover::prove, verifier::verify, witness::Witness};
-use log::debug;
+use log::{debug, error};
 use o1vm::{
     cannon::{self, Meta, Start, State},
     cannon_cli,
@@ -12,7 +12,7 @@ use o1vm::{
             environment::KeccakEnv,
         },
         mips::{
-            column::{N_MIPS_COLS, N_MIPS_REL_COLS, N_MIPS_SEL_COLS, SCRATCH_SIZE},
+            column::{N_MIPS_REL_COLS, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE},
             constraints as mips_constraints,
             interpreter::Instruction,
             witness::{self as mips_witness},
@@ -20,18 +20,19 @@ use o1vm::{
     },
     legacy::{
         folding::mips::DecomposableMIPSFoldingConfig,
-        proof,
+        proof::{self, MIPSAccumulator},
         trace::{
-            keccak::DecomposedKeccakTrace, mips::DecomposedMIPSTrace, DecomposableTracer, Foldable,
-            Tracer,
+            keccak::DecomposedKeccakTrace, mips::DecomposedMIPSTrace, DecomposableTracer, Tracer,
         },
-        BaseSponge, Fp, OpeningProof, ScalarSponge,
+        BaseSponge, Fp, OpeningProof, Pairing, ScalarSponge,
     },
     lookups::LookupTableIDs,
-    preimage_oracle::PreImageOracle,
+    preimage_oracle::{self, PreImageOracleT},
+    tracer,
 };
-use poly_commitment::SRS as _;
-use std::{cmp::Ordering, collections::HashMap, fs::File, io::BufReader, process::ExitCode};
+use poly_commitment::{kzg::PairingSRS, SRS as _};
+use rand::{CryptoRng, RngCore};
+use std::{fs::File, io::BufReader, process::ExitCode};
 use strum::IntoEnumIterator;
 
 /// Domain size shared by the Keccak evaluations, MIPS evaluation and main
@@ -64,15 +65,14 @@ pub fn main() -> ExitCode {
         )
     });
 
-    let mut po = PreImageOracle::create(&configuration.host);
-    let _child = po.start();
+    let (po, _child) = preimage_oracle::create_oracle(&configuration);
 
     // Initialize some data used for statistical computations
     let start = Start::create(state.step as usize);
 
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
-    let domain = kimchi::circuits::domains::EvaluationDomains::<Fp>::create(DOMAIN_SIZE).unwrap();
+    let domain = EvaluationDomains::<Fp>::create(DOMAIN_SIZE).unwrap();
 
     let mut rng = o1_utils::tests::make_test_rng(None);
 
@@ -85,8 +85,17 @@ pub fn main() -> ExitCode {
 
     // Initialize the environments
     // The Keccak environment is extracted inside the loop
-    let mut mips_wit_env =
-        mips_witness::Env::<Fp, PreImageOracle>::create(cannon::PAGE_SIZE as usize, state, po);
+    let mut mips_wit_env = mips_witness::Env::<Fp, Box<dyn PreImageOracleT>>::create(
+        cannon::PAGE_SIZE as usize,
+        state,
+        po,
+    );
+    mips_wit_env.tracer = tracer::Tracer::from_options(
+        configuration.trace.as_deref(),
+        configuration.trace_format,
+        configuration.breakpoints.clone(),
+        Some(meta.clone()),
+    );
     let mut mips_con_env = mips_constraints::Env::<Fp>::default();
     // The keccak environment is extracted inside the loop
 
@@ -94,36 +103,24 @@ pub fn main() -> ExitCode {
     let mut mips_trace = DecomposedMIPSTrace::new(DOMAIN_SIZE, &mut mips_con_env);
     let mut keccak_trace = DecomposedKeccakTrace::new(DOMAIN_SIZE, &mut KeccakEnv::<Fp>::default());
 
-    let _mips_folding = {
+    // The chunks of the MIPS circuit, including the lookups, are folded into a
+    // single accumulator using one selector per instruction.
+    let (mips_folding, mips_final_constraint) =
         DecomposableFoldingScheme::<DecomposableMIPSFoldingConfig>::new(
-            <DecomposedMIPSTrace as Foldable<
-                N_MIPS_COLS,
-                DecomposableMIPSFoldingConfig,
-                BaseSponge,
-            >>::folding_constraints(&mips_trace),
+            mips_trace.folding_constraints_with_lookups(),
             vec![],
             &srs,
             domain.d1,
             &(),
-        )
-    };
-
-    // Initialize folded instances of the sub circuits
-    let mut mips_folded_instance = HashMap::new();
-    for instr in Instruction::iter().flat_map(|x| x.into_iter()) {
-        mips_folded_instance.insert(
-            instr,
-            ProofInputs::<N_MIPS_COLS, Fp, LookupTableIDs>::default(),
         );
-    }
+    let mut mips_accumulator =
+        MIPSAccumulator::new(&mips_folding, &mips_final_constraint, domain.d1, &srs);
 
-    let mut keccak_folded_instance = HashMap::new();
-    for step in Steps::iter().flat_map(|x| x.into_iter()) {
-        keccak_folded_instance.insert(
-            step,
-            ProofInputs::<N_ZKVM_KECCAK_COLS, Fp, LookupTableIDs>::default(),
-        );
-    }
+    // The chunks of the Keccak circuits are proven separately
+    let mut keccak_verifies = true;
+
+    // The state of the program, to which the proof of the execution is bound
+    let program = mips_wit_env.program_state();
 
     while !mips_wit_env.halt {
         let instr = mips_wit_env.step(&configuration, &meta, &start);
@@ -138,16 +135,12 @@ pub fn main() -> ExitCode {
                 // Add the witness row to the Keccak circuit for this step
                 keccak_trace.push_row(step, &keccak_env.witness_env.witness.cols);
 
-                // If the witness is full, fold it and reset the pre-folding witness
+                // If the witness is full, prove it and reset the witness
                 if keccak_trace.number_of_rows(step) == DOMAIN_SIZE {
                     // Set to zero all selectors except for the one corresponding to the current instruction
                     keccak_trace.set_selector_column::<N_ZKVM_KECCAK_REL_COLS>(step, DOMAIN_SIZE);
-                    proof::fold::<N_ZKVM_KECCAK_COLS, _, OpeningProof, BaseSponge, ScalarSponge>(
-                        domain,
-                        &srs,
-                        keccak_folded_instance.get_mut(&step).unwrap(),
-                        &keccak_trace[step].witness,
-                    );
+                    keccak_verifies &=
+                        prove_keccak_chunk(domain, &srs, &keccak_trace, step, &mut rng);
                     keccak_trace.reset(step);
                 }
             }
@@ -156,176 +149,150 @@ pub fn main() -> ExitCode {
         }
 
         // TODO: unify witness of MIPS to include scratch state, instruction counter, and error
-        for i in 0..N_MIPS_REL_COLS {
-            match i.cmp(&SCRATCH_SIZE) {
-                Ordering::Less => mips_trace.trace.get_mut(&instr).unwrap().witness.cols[i]
-                    .push(mips_wit_env.scratch_state[i]),
-                Ordering::Equal => mips_trace.trace.get_mut(&instr).unwrap().witness.cols[i]
-                    .push(Fp::from(mips_wit_env.instruction_counter)),
-                Ordering::Greater => {
-                    // TODO: error
-                    mips_trace.trace.get_mut(&instr).unwrap().witness.cols[i]
-                        .push(Fp::rand(&mut rand::rngs::OsRng))
-                }
+        let row: [Fp; N_MIPS_REL_COLS] = std::array::from_fn(|i| {
+            if i < SCRATCH_SIZE {
+                mips_wit_env.scratch_state[i]
+            } else if i < SCRATCH_SIZE + SCRATCH_SIZE_INVERSE {
+                mips_wit_env.scratch_state_inverse[i - SCRATCH_SIZE]
+                    .inverse()
+                    .unwrap_or_else(Fp::zero)
+            } else if i == SCRATCH_SIZE + SCRATCH_SIZE_INVERSE {
+                Fp::from(mips_wit_env.instruction_counter)
+            } else {
+                // The rows are not relaxed, so their error is zero. The error
+                // of the accumulator is derived from the cross terms when
+                // folding the chunks, see [proof::MIPSAccumulator::finalize].
+                Fp::zero()
             }
-        }
+        });
+        mips_trace.push_row(instr, &row);
 
         if mips_trace.number_of_rows(instr) == DOMAIN_SIZE {
             // Set to zero all selectors except for the one corresponding to the current instruction
             mips_trace.set_selector_column::<N_MIPS_REL_COLS>(instr, DOMAIN_SIZE);
-            proof::fold::<N_MIPS_COLS, _, OpeningProof, BaseSponge, ScalarSponge>(
-                domain,
-                &srs,
-                mips_folded_instance.get_mut(&instr).unwrap(),
-                &mips_trace[instr].witness,
-            );
+            mips_accumulator.add_chunk(&mips_trace, instr, DOMAIN_SIZE);
             mips_trace.reset(instr);
         }
     }
 
     // Pad any possible remaining rows if the execution was not a multiple of the domain size
     for instr in Instruction::iter().flat_map(|x| x.into_iter()) {
-        // Start by padding with the first row
-        let needs_folding = mips_trace.pad_dummy(instr) != 0;
-        if needs_folding {
+        let n_rows = mips_trace.number_of_rows(instr);
+        if n_rows != 0 {
+            // Start by padding with the first row, whose lookups are not
+            // counted again
+            mips_trace.pad_dummy(instr);
             // Then set the selector columns (all of them, none has selectors set)
             mips_trace.set_selector_column::<N_MIPS_REL_COLS>(instr, DOMAIN_SIZE);
 
-            // Finally fold instance
-            proof::fold::<N_MIPS_COLS, _, OpeningProof, BaseSponge, ScalarSponge>(
-                domain,
-                &srs,
-                mips_folded_instance.get_mut(&instr).unwrap(),
-                &mips_trace[instr].witness,
-            );
+            // Finally add the chunk
+            mips_accumulator.add_chunk(&mips_trace, instr, n_rows);
         }
     }
     for step in Steps::iter().flat_map(|x| x.into_iter()) {
-        let needs_folding = keccak_trace.pad_dummy(step) != 0;
-        if needs_folding {
+        let needs_proving = keccak_trace.pad_dummy(step) != 0;
+        if needs_proving {
             keccak_trace.set_selector_column::<N_ZKVM_KECCAK_REL_COLS>(step, DOMAIN_SIZE);
-
-            proof::fold::<N_ZKVM_KECCAK_COLS, _, OpeningProof, BaseSponge, ScalarSponge>(
-                domain,
-                &srs,
-                keccak_folded_instance.get_mut(&step).unwrap(),
-                &keccak_trace[step].witness,
-            );
+            keccak_verifies &= prove_keccak_chunk(domain, &srs, &keccak_trace, step, &mut rng);
         }
     }
 
     {
         // MIPS
-        for instr in Instruction::iter().flat_map(|x| x.into_iter()) {
-            // Prove only if the instruction was executed
-            // and if the number of constraints is nonzero (otherwise quotient polynomial cannot be created)
-            if mips_trace.in_circuit(instr) && !mips_trace[instr].constraints.is_empty() {
-                debug!("Checking MIPS circuit {:?}", instr);
-                let mips_result = prove::<
-                    _,
-                    OpeningProof,
-                    BaseSponge,
-                    ScalarSponge,
-                    _,
-                    N_MIPS_COLS,
-                    N_MIPS_REL_COLS,
-                    N_MIPS_SEL_COLS,
-                    0,
-                    LookupTableIDs,
-                >(
-                    domain,
-                    &srs,
-                    &mips_trace[instr].constraints,
-                    Box::new([]),
-                    mips_folded_instance[&instr].clone(),
-                    &mut rng,
-                );
-                let mips_proof = mips_result.unwrap();
-                debug!("Generated a MIPS {:?} proof:", instr);
-                let mips_verifies = verify::<
-                    _,
-                    OpeningProof,
-                    BaseSponge,
-                    ScalarSponge,
-                    N_MIPS_COLS,
-                    N_MIPS_REL_COLS,
-                    N_MIPS_SEL_COLS,
-                    0,
-                    0,
-                    LookupTableIDs,
-                >(
-                    domain,
-                    &srs,
-                    &mips_trace[instr].constraints,
-                    Box::new([]),
-                    &mips_proof,
-                    Witness::zero_vec(DOMAIN_SIZE),
-                );
-                if mips_verifies {
-                    debug!("The MIPS {:?} proof verifies\n", instr)
-                } else {
-                    debug!("The MIPS {:?} proof doesn't verify\n", instr)
-                }
-            }
+        debug!("Merging the accumulators of the MIPS instructions");
+        let mips_proof = mips_accumulator.finalize(
+            &mips_trace,
+            program.vm_state.clone(),
+            mips_wit_env.vm_state(),
+            &mut rng,
+        );
+        let mips_verifies = proof::verify_accumulation(
+            &mips_folding,
+            &mips_final_constraint,
+            domain.d1,
+            &srs,
+            &mips_proof,
+            Some(&program),
+        );
+        if mips_verifies {
+            debug!("The MIPS accumulation proof verifies\n")
+        } else {
+            error!("The MIPS accumulation proof doesn't verify");
+            return ExitCode::FAILURE;
         }
     }
 
-    {
-        // KECCAK
-        // FIXME: when folding is applied, the error term will be created to satisfy the folded witness
-        for step in Steps::iter().flat_map(|x| x.into_iter()) {
-            // Prove only if the instruction was executed
-            if keccak_trace.in_circuit(step) {
-                debug!("Checking Keccak circuit {:?}", step);
-                let keccak_result = prove::<
-                    _,
-                    OpeningProof,
-                    BaseSponge,
-                    ScalarSponge,
-                    _,
-                    N_ZKVM_KECCAK_COLS,
-                    N_ZKVM_KECCAK_REL_COLS,
-                    N_ZKVM_KECCAK_SEL_COLS,
-                    0,
-                    LookupTableIDs,
-                >(
-                    domain,
-                    &srs,
-                    &keccak_trace[step].constraints,
-                    Box::new([]),
-                    keccak_folded_instance[&step].clone(),
-                    &mut rng,
-                );
-                let keccak_proof = keccak_result.unwrap();
-                debug!("Generated a Keccak {:?} proof:", step);
-                let keccak_verifies = verify::<
-                    _,
-                    OpeningProof,
-                    BaseSponge,
-                    ScalarSponge,
-                    N_ZKVM_KECCAK_COLS,
-                    N_ZKVM_KECCAK_REL_COLS,
-                    N_ZKVM_KECCAK_SEL_COLS,
-                    0,
-                    0,
-                    LookupTableIDs,
-                >(
-                    domain,
-                    &srs,
-                    &keccak_trace[step].constraints,
-                    Box::new([]),
-                    &keccak_proof,
-                    Witness::zero_vec(DOMAIN_SIZE),
-                );
-                if keccak_verifies {
-                    debug!("The Keccak {:?} proof verifies\n", step)
-                } else {
-                    debug!("The Keccak {:?} proof doesn't verify\n", step)
-                }
-            }
-        }
+    if !keccak_verifies {
+        error!("The proofs of the Keccak circuits don't verify");
+        return ExitCode::FAILURE;
     }
 
-    // TODO: Logic
     ExitCode::SUCCESS
 }
+
+/// Proves the chunk of the Keccak circuit of `step`, whose witness fills the
+/// domain, and verifies the proof.
+fn prove_keccak_chunk<RNG: RngCore + CryptoRng>(
+    domain: EvaluationDomains<Fp>,
+    srs: &PairingSRS<Pairing>,
+    keccak_trace: &DecomposedKeccakTrace,
+    step: Steps,
+    rng: &mut RNG,
+) -> bool {
+    debug!("Proving a chunk of the Keccak circuit {:?}", step);
+    let inputs = ProofInputs::<N_ZKVM_KECCAK_COLS, Fp, LookupTableIDs> {
+        evaluations: keccak_trace[step].witness.clone(),
+        logups: Default::default(),
+    };
+    let keccak_proof = match prove::<
+        _,
+        OpeningProof,
+        BaseSponge,
+        ScalarSponge,
+        _,
+        N_ZKVM_KECCAK_COLS,
+        N_ZKVM_KECCAK_REL_COLS,
+        N_ZKVM_KECCAK_SEL_COLS,
+        0,
+        LookupTableIDs,
+    >(
+        domain,
+        srs,
+        &keccak_trace[step].constraints,
+        Box::new([]),
+        inputs,
+        rng,
+    ) {
+        Ok(proof) => proof,
+        Err(err) => {
+            error!("Could not prove the Keccak {:?} chunk: {:?}", step, err);
+            return false;
+        }
+    };
+    debug!("Generated a Keccak {:?} proof:", step);
+    let keccak_verifies = verify::<
+        _,
+        OpeningProof,
+        BaseSponge,
+        ScalarSponge,
+        N_ZKVM_KECCAK_COLS,
+        N_ZKVM_KECCAK_REL_COLS,
+        N_ZKVM_KECCAK_SEL_COLS,
+        0,
+        0,
+        LookupTableIDs,
+    >(
+        domain,
+        srs,
+        &keccak_trace[step].constraints,
+        Box::new([]),
+        &keccak_proof,
+        Witness::zero_vec(DOMAIN_SIZE),
+    );
+    if keccak_verifies {
+        debug!("The Keccak {:?} proof verifies\n", step)
+    } else {
+        error!("The Keccak {:?} proof doesn't verify", step)
+    }
+    keccak_verifies
+}
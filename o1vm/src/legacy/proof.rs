@@ -1,108 +1,1153 @@
-use crate::lookups::LookupTableIDs;
-use ark_poly::{Evaluations, Radix2EvaluationDomain as D};
-use kimchi::{circuits::domains::EvaluationDomains, curve::KimchiCurve, plonk_sponge::FrSponge};
-use kimchi_msm::{proof::ProofInputs, witness::Witness};
+use crate::{
+    interpreters::mips::{
+        column::{N_MIPS_COLS, N_MIPS_REL_COLS},
+        Instruction,
+    },
+    legacy::{
+        folding::{
+            mips::{
+                column_index, DecomposableMIPSFoldingConfig, MIPSFoldingInstance,
+                MIPSFoldingWitness, N_MIPS_FOLDING_COLS,
+            },
+            FoldingInstance, FoldingWitness,
+        },
+        trace::mips::{add_lookup_entry, DecomposedMIPSTrace, LookupEntries},
+        BaseSponge, Curve, Fp, OpeningProof, Pairing, ScalarSponge,
+    },
+    lookups::LookupTableIDs,
+    pickles::{
+        proof::{ProgramState, PublicInputs, RamEntry, VmState},
+        ram::{boundary_sum, check_boundary, check_chained_boundaries, to_u64},
+    },
+};
+use ark_ec::AffineRepr;
+use ark_ff::{Field, One, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain as _, Evaluations, Polynomial,
+    Radix2EvaluationDomain as D,
+};
+use folding::{
+    checker::{Checker, ExtendedProvider},
+    decomposable_folding::DecomposableFoldingScheme,
+    expressions::FoldingCompatibleExprInner,
+    instance_witness::{ExtendedWitness, RelaxableInstance, RelaxableWitness},
+    Alphas, ExpExtension, FoldingCompatibleExpr, FoldingOutput, RelaxedInstance, RelaxedWitness,
+};
+use kimchi::{
+    circuits::{expr::Variable, gate::CurrOrNext},
+    curve::KimchiCurve,
+    groupmap::GroupMap,
+    plonk_sponge::FrSponge,
+};
+use kimchi_msm::{witness::Witness, LookupTableID};
 use mina_poseidon::{sponge::ScalarChallenge, FqSponge};
-use poly_commitment::{commitment::absorb_commitment, OpenProof, SRS as _};
-use rayon::iter::{
-    IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
+use poly_commitment::{
+    commitment::{
+        absorb_commitment, combined_inner_product, BatchEvaluationProof, CommitmentCurve,
+        Evaluation,
+    },
+    kzg::PairingSRS,
+    utils::DensePolynomialOrEvaluations,
+    OpenProof, PolyComm, SRS as _,
 };
+use rand::{thread_rng, CryptoRng, RngCore};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use std::collections::BTreeMap;
 
-/// FIXME: DUMMY FOLD FUNCTION THAT ONLY KEEPS THE LAST INSTANCE
-pub fn fold<
-    const N: usize,
-    G: KimchiCurve,
-    OpeningProof: OpenProof<G>,
-    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
-    EFrSponge: FrSponge<G::ScalarField>,
->(
-    domain: EvaluationDomains<G::ScalarField>,
-    srs: &OpeningProof::SRS,
-    accumulator: &mut ProofInputs<N, G::ScalarField, LookupTableIDs>,
-    inputs: &Witness<N, Vec<G::ScalarField>>,
-) where
-    <OpeningProof as poly_commitment::OpenProof<G>>::SRS: std::marker::Sync,
-{
-    let commitments = {
-        inputs
-            .par_iter()
-            .map(|evals: &Vec<G::ScalarField>| {
-                let evals = Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
-                    evals.clone(),
-                    domain.d1,
-                );
-                srs.commit_evaluations_non_hiding(domain.d1, &evals)
+pub type MIPSRelaxedInstance = RelaxedInstance<Curve, MIPSFoldingInstance>;
+pub type MIPSRelaxedWitness = RelaxedWitness<Curve, MIPSFoldingWitness>;
+
+/// A chunk of the execution, folded into the accumulator of its instruction.
+#[derive(Clone)]
+pub struct Chunk {
+    /// The instruction executed on the rows of the chunk
+    pub instruction: Instruction,
+    /// The number of rows executing the instruction, the other rows padding
+    /// the chunk
+    pub n_rows: usize,
+    /// The instance of the chunk, including the lookup terms
+    pub instance: MIPSFoldingInstance,
+    /// The sum of the lookup terms of the chunk over all its rows
+    pub lookup_sum: Fp,
+}
+
+/// A relaxed instance and witness pair in which chunks have been accumulated.
+#[derive(Clone)]
+struct Accumulator {
+    instance: MIPSRelaxedInstance,
+    witness: MIPSRelaxedWitness,
+    /// The blinder of the commitments to the columns added by
+    /// quadraticization. The columns of a chunk are committed with a blinder
+    /// of one the first time it is folded.
+    extended_blinder: Fp,
+    /// The blinder of the commitment to the error column. The cross terms are
+    /// committed with a blinder of one, so that folding with the challenge `r`
+    /// gives `b_left - r - r^2 + r^3 b_right`.
+    error_blinder: Fp,
+    /// The sum of the lookup terms of the accumulated witness over all the
+    /// rows
+    lookup_sum: Fp,
+}
+
+/// A folding step, as replayed by the verifier.
+#[derive(Clone)]
+pub struct FoldingStep {
+    /// The instruction of the chunk folded into the accumulator of the
+    /// instruction, or `None` when merging the accumulators of two
+    /// instructions.
+    pub selector: Option<Instruction>,
+    /// The left relaxed instance, extended with the columns added by
+    /// quadraticization
+    pub left: MIPSRelaxedInstance,
+    /// The right relaxed instance, extended with the columns added by
+    /// quadraticization
+    pub right: MIPSRelaxedInstance,
+    /// The commitments to the cross terms
+    pub cross_terms: [PolyComm<Curve>; 2],
+}
+
+/// The evaluations at `ζ` and at zero of the polynomials opened by the
+/// decider.
+#[derive(Clone)]
+pub struct DeciderEvaluations {
+    /// The columns of the accumulated witness
+    pub columns: Vec<[Fp; 2]>,
+    /// The columns added by quadraticization
+    pub extended: Vec<[Fp; 2]>,
+    pub error: [Fp; 2],
+    pub quotient: [Fp; 2],
+}
+
+impl DeciderEvaluations {
+    /// The evaluations, in the order the polynomials are opened.
+    fn iter(&self) -> impl Iterator<Item = &[Fp; 2]> {
+        self.columns
+            .iter()
+            .chain(self.extended.iter())
+            .chain([&self.error, &self.quotient])
+    }
+}
+
+/// Proof that the accumulated witness satisfies the relaxed relation.
+/// The final constraint evaluated on the accumulated witness must vanish on
+/// the domain, i.e. be divisible by its vanishing polynomial. The prover
+/// commits to the quotient, and all the polynomials are opened at a random
+/// point `ζ`. The lookup terms are also opened at zero, as the sum over the
+/// domain of a polynomial of degree less than its size is the size times the
+/// constant term of the polynomial.
+#[derive(Clone)]
+pub struct DeciderProof {
+    pub quotient_commitment: PolyComm<Curve>,
+    pub evaluations: DeciderEvaluations,
+    pub opening: OpeningProof,
+}
+
+/// Proof of a whole MIPS execution, obtained by folding all its chunks into a
+/// single accumulator.
+/// The chunks of each instruction are first folded together, using the
+/// instruction as selector. The accumulators of the different instructions
+/// are then merged into the final accumulator, which is given to the decider.
+///
+/// The lookup challenges are shared by all the chunks, so that the lookup
+/// terms of the whole execution sum to the terms of the entries left at its
+/// boundary: the registers and memory cells before the first access and after
+/// the last one, and the entries of the other tables.
+#[derive(Clone)]
+pub struct AccumulationProof {
+    /// The states of the VM before and after the execution, and the entries
+    /// of the registers and memory at its boundary
+    pub public_inputs: PublicInputs<Fp>,
+    /// The entries looked up in the tables other than the registers and the
+    /// memory, with the number of times they are written minus the number of
+    /// times they are read
+    pub table_entries: LookupEntries,
+    /// The chunks, in the order of the execution
+    pub chunks: Vec<Chunk>,
+    /// The folding steps, in the order they have been performed
+    pub steps: Vec<FoldingStep>,
+    /// The proof that the final accumulated instance is satisfied
+    pub decider: DeciderProof,
+}
+
+/// Folds the chunks of a MIPS execution, including the lookup terms, into a
+/// single accumulator.
+pub struct MIPSAccumulator<'a> {
+    scheme: &'a DecomposableFoldingScheme<'a, DecomposableMIPSFoldingConfig>,
+    final_constraint: &'a FoldingCompatibleExpr<DecomposableMIPSFoldingConfig>,
+    domain: D<Fp>,
+    srs: &'a PairingSRS<Pairing>,
+    n_alphas: usize,
+    /// The chunks added so far with their witness, whose lookup terms are
+    /// computed once the challenges are known
+    chunks: Vec<(Chunk, MIPSFoldingWitness)>,
+    /// The entries looked up by the chunks added so far
+    lookup_entries: LookupEntries,
+}
+
+impl<'a> MIPSAccumulator<'a> {
+    /// Creates an empty accumulator for the given folding scheme, which must
+    /// have been created from
+    /// [DecomposedMIPSTrace::folding_constraints_with_lookups]. The final
+    /// constraint is the one returned when creating the scheme.
+    pub fn new(
+        scheme: &'a DecomposableFoldingScheme<'a, DecomposableMIPSFoldingConfig>,
+        final_constraint: &'a FoldingCompatibleExpr<DecomposableMIPSFoldingConfig>,
+        domain: D<Fp>,
+        srs: &'a PairingSRS<Pairing>,
+    ) -> Self {
+        MIPSAccumulator {
+            scheme,
+            final_constraint,
+            domain,
+            srs,
+            n_alphas: final_constraint.number_of_alphas(),
+            chunks: vec![],
+            lookup_entries: LookupEntries::new(),
+        }
+    }
+
+    /// Adds the chunk of the instruction `instr` contained in the trace, whose
+    /// first `n_rows` rows execute the instruction, the other rows padding the
+    /// chunk.
+    /// The witness of the instruction must have been padded and its selector
+    /// columns set.
+    ///
+    /// The challenges of the lookups are coined once all the chunks are
+    /// known, so the witnesses are kept until [Self::finalize].
+    pub fn add_chunk(&mut self, trace: &DecomposedMIPSTrace, instr: Instruction, n_rows: usize) {
+        let witness = trace.to_folding_witness(instr, n_rows, self.domain);
+        let (witness, entries) = trace.lookup_entries(instr, witness, self.domain);
+        for (entry, multiplicity) in entries {
+            add_lookup_entry(&mut self.lookup_entries, entry, multiplicity);
+        }
+        // The lookup terms are committed in [Self::finalize]
+        let mut commitments = [Curve::zero(); N_MIPS_FOLDING_COLS];
+        for range in [0..N_MIPS_COLS, N_MIPS_FOLDING_COLS - 1..N_MIPS_FOLDING_COLS] {
+            let columns =
+                commit_columns(self.srs, self.domain, &witness.witness.cols[range.clone()]);
+            commitments[range].copy_from_slice(&columns);
+        }
+        let chunk = Chunk {
+            instruction: instr,
+            n_rows,
+            instance: FoldingInstance {
+                commitments,
+                challenges: [Fp::zero(); 3],
+                alphas: Alphas::new(Fp::zero()),
+                blinder: Fp::one(),
+            },
+            lookup_sum: Fp::zero(),
+        };
+        self.chunks.push((chunk, witness));
+    }
+
+    /// Folds all the chunks, and returns the proof of the whole execution,
+    /// going from `initial_state` to `final_state`.
+    ///
+    /// Panics if the accesses to the registers and to the memory are not
+    /// consistent, or if the accumulated witness does not satisfy the relation.
+    pub fn finalize<RNG>(
+        self,
+        trace: &DecomposedMIPSTrace,
+        initial_state: VmState<Fp>,
+        final_state: VmState<Fp>,
+        rng: &mut RNG,
+    ) -> AccumulationProof
+    where
+        RNG: RngCore + CryptoRng,
+    {
+        let MIPSAccumulator {
+            scheme,
+            final_constraint,
+            domain,
+            srs,
+            n_alphas,
+            chunks,
+            lookup_entries,
+        } = self;
+        let (public_inputs, table_entries) =
+            split_lookup_entries(initial_state, final_state, lookup_entries);
+
+        let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+        absorb_boundary(&mut fq_sponge, &public_inputs, &table_entries);
+        for (chunk, _) in chunks.iter() {
+            absorb_chunk_columns(&mut fq_sponge, chunk);
+        }
+        let challenges = lookup_challenges(&mut fq_sponge);
+        let chunks: Vec<(Chunk, MIPSFoldingWitness)> = chunks
+            .into_iter()
+            .map(|(mut chunk, witness)| {
+                let (witness, lookup_sum) =
+                    trace.set_lookup_terms(chunk.instruction, witness, challenges, domain);
+                let lookup_terms = N_MIPS_COLS..N_MIPS_FOLDING_COLS - 1;
+                let lookup_commitments =
+                    commit_columns(srs, domain, &witness.witness.cols[lookup_terms.clone()]);
+                chunk.instance.commitments[lookup_terms].copy_from_slice(&lookup_commitments);
+                chunk.instance.challenges = challenges;
+                chunk.lookup_sum = lookup_sum;
+                absorb_chunk_lookups(&mut fq_sponge, &chunk);
+                (chunk, witness)
             })
-            .collect::<Witness<N, _>>()
+            .collect();
+        let alphas = Alphas::new_sized(fq_sponge.challenge(), n_alphas);
+
+        let zero_vec = Evaluations::from_vec_and_domain(vec![Fp::zero(); domain.size()], domain);
+        let mut steps = vec![];
+        let mut accumulators: BTreeMap<Instruction, Accumulator> = BTreeMap::new();
+        let mut proof_chunks = Vec::with_capacity(chunks.len());
+        for (mut chunk, witness) in chunks {
+            chunk.instance.alphas = alphas.clone();
+            let right = Accumulator {
+                instance: chunk.instance.clone().relax(),
+                witness: witness.relax(&zero_vec),
+                extended_blinder: Fp::one(),
+                error_blinder: Fp::zero(),
+                lookup_sum: chunk.lookup_sum,
+            };
+            let accumulator = match accumulators.remove(&chunk.instruction) {
+                None => right,
+                Some(left) => fold(
+                    scheme,
+                    &mut fq_sponge,
+                    &mut steps,
+                    Some(chunk.instruction),
+                    left,
+                    right,
+                ),
+            };
+            accumulators.insert(chunk.instruction, accumulator);
+            proof_chunks.push(chunk);
+        }
+
+        let mut accumulator: Option<Accumulator> = None;
+        for right in accumulators.into_values() {
+            accumulator = Some(match accumulator {
+                None => right,
+                Some(left) => fold(scheme, &mut fq_sponge, &mut steps, None, left, right),
+            });
+        }
+        let mut accumulator = accumulator.expect("No chunk has been added");
+        // The columns added by quadraticization are only computed when
+        // folding. If the execution consists of a single chunk, it is folded
+        // with itself.
+        if accumulator.instance.extended_instance.extended.is_empty() {
+            accumulator = fold(
+                scheme,
+                &mut fq_sponge,
+                &mut steps,
+                None,
+                accumulator.clone(),
+                accumulator,
+            );
+        }
+
+        let decider = prove_decider(final_constraint, domain, srs, fq_sponge, &accumulator, rng);
+        AccumulationProof {
+            public_inputs,
+            table_entries,
+            chunks: proof_chunks,
+            steps,
+            decider,
+        }
+    }
+}
+
+/// Returns the commitments to the columns.
+fn commit_columns(
+    srs: &PairingSRS<Pairing>,
+    domain: D<Fp>,
+    columns: &[Evaluations<Fp, D<Fp>>],
+) -> Vec<Curve> {
+    columns
+        .par_iter()
+        .map(|column| {
+            srs.commit_evaluations_non_hiding(domain, column)
+                .get_first_chunk()
+        })
+        .collect()
+}
+
+/// Returns the commitment to the column containing one on its `n` first rows
+/// and zero on the other rows.
+fn indicator_commitment(srs: &PairingSRS<Pairing>, domain: D<Fp>, n: usize) -> Curve {
+    let column = (0..domain.size())
+        .map(|i| if i < n { Fp::one() } else { Fp::zero() })
+        .collect();
+    srs.commit_evaluations_non_hiding(domain, &Evaluations::from_vec_and_domain(column, domain))
+        .get_first_chunk()
+}
+
+/// Splits the entries looked up by the whole execution into the public inputs,
+/// which contain the entries of the registers and of the memory, and the
+/// entries of the other tables.
+///
+/// Panics if an entry of the registers or of the memory has a multiplicity
+/// other than 1 or -1, which means that the accesses are not consistent, see
+/// [crate::ramlookup::RAMMultiset::into_boundary].
+fn split_lookup_entries(
+    initial_state: VmState<Fp>,
+    final_state: VmState<Fp>,
+    entries: LookupEntries,
+) -> (PublicInputs<Fp>, LookupEntries) {
+    let mut initial_ram = vec![];
+    let mut final_ram = vec![];
+    let mut table_entries = LookupEntries::new();
+    for ((table_id, value), multiplicity) in entries {
+        if !table_id.is_ram() {
+            table_entries.insert((table_id, value), multiplicity);
+            continue;
+        }
+        let [address, timestamp, value] = value[..] else {
+            panic!("The entries of the RAM tables are made of an address, a timestamp and a value, got {:?}", value)
+        };
+        let entry = RamEntry {
+            table_id: table_id.to_u32(),
+            address,
+            timestamp,
+            value,
+        };
+        if multiplicity == Fp::one() {
+            initial_ram.push(entry);
+        } else if multiplicity == -Fp::one() {
+            final_ram.push(entry);
+        } else {
+            panic!(
+                "The entry {:?} has multiplicity {}, the accesses to the RAM are not consistent",
+                entry, multiplicity
+            )
+        }
+    }
+    let public_inputs = PublicInputs {
+        initial_state,
+        final_state,
+        initial_ram,
+        final_ram,
     };
-    let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
+    (public_inputs, table_entries)
+}
+
+/// Absorbs the public inputs of the execution and the entries of the other
+/// tables, which must be fixed before the lookup challenges are coined.
+fn absorb_boundary(
+    fq_sponge: &mut BaseSponge,
+    public_inputs: &PublicInputs<Fp>,
+    table_entries: &LookupEntries,
+) {
+    fq_sponge.absorb_fr(&public_inputs.to_field_elements());
+    fq_sponge.absorb_fr(&[Fp::from(table_entries.len() as u64)]);
+    for ((table_id, value), multiplicity) in table_entries {
+        fq_sponge.absorb_fr(&[Fp::from(table_id.to_u32()), Fp::from(value.len() as u64)]);
+        fq_sponge.absorb_fr(value);
+        fq_sponge.absorb_fr(&[*multiplicity]);
+    }
+}
 
-    commitments.into_iter().for_each(|comm| {
-        absorb_commitment(&mut fq_sponge, &comm);
+/// Absorbs the instruction and the number of rows of the chunk, and the
+/// commitments to all its columns but the lookup terms.
+fn absorb_chunk_columns(fq_sponge: &mut BaseSponge, chunk: &Chunk) {
+    fq_sponge.absorb_fr(&[
+        Fp::from(usize::from(chunk.instruction) as u64),
+        Fp::from(chunk.n_rows as u64),
+    ]);
+    fq_sponge.absorb_g(&chunk.instance.commitments[..N_MIPS_COLS]);
+    fq_sponge.absorb_g(&chunk.instance.commitments[N_MIPS_FOLDING_COLS - 1..]);
+}
+
+/// Absorbs the commitments to the lookup terms of the chunk and their sum.
+fn absorb_chunk_lookups(fq_sponge: &mut BaseSponge, chunk: &Chunk) {
+    fq_sponge.absorb_g(&chunk.instance.commitments[N_MIPS_COLS..N_MIPS_FOLDING_COLS - 1]);
+    fq_sponge.absorb_fr(&[chunk.lookup_sum]);
+}
+
+/// Coins the challenges β, γ and the joint combiner of the lookups.
+fn lookup_challenges(fq_sponge: &mut BaseSponge) -> [Fp; 3] {
+    let beta = fq_sponge.challenge();
+    let gamma = fq_sponge.challenge();
+    let joint_combiner = fq_sponge.challenge();
+    [beta, gamma, joint_combiner]
+}
+
+/// Folds the accumulators, recording the step for the verifier.
+fn fold(
+    scheme: &DecomposableFoldingScheme<DecomposableMIPSFoldingConfig>,
+    fq_sponge: &mut BaseSponge,
+    steps: &mut Vec<FoldingStep>,
+    selector: Option<Instruction>,
+    left: Accumulator,
+    right: Accumulator,
+) -> Accumulator {
+    let FoldingOutput {
+        folded_instance,
+        folded_witness,
+        t_0,
+        t_1,
+        relaxed_extended_left_instance,
+        relaxed_extended_right_instance,
+        to_absorb: _,
+    } = scheme.fold_instance_witness_pair(
+        (left.instance, left.witness),
+        (right.instance, right.witness),
+        selector,
+        fq_sponge,
+    );
+    // The folding challenge r is such that u = u_left + r * u_right
+    let r =
+        (folded_instance.u - relaxed_extended_left_instance.u) / relaxed_extended_right_instance.u;
+    steps.push(FoldingStep {
+        selector,
+        left: relaxed_extended_left_instance,
+        right: relaxed_extended_right_instance,
+        cross_terms: [t_0, t_1],
     });
+    let r_square = r.square();
+    Accumulator {
+        instance: folded_instance,
+        witness: folded_witness,
+        extended_blinder: left.extended_blinder + r * right.extended_blinder,
+        error_blinder: left.error_blinder - r - r_square + r_square * r * right.error_blinder,
+        lookup_sum: left.lookup_sum + r * right.lookup_sum,
+    }
+}
 
-    // TODO: fold mvlookups as well
-    accumulator
-        .evaluations
-        .par_iter_mut()
-        .zip(inputs.par_iter())
-        .for_each(|(accumulator, inputs)| {
-            accumulator
-                .par_iter_mut()
-                .zip(inputs.par_iter())
-                .for_each(|(accumulator, input)| *accumulator = *input);
-        });
+/// Returns the challenges `v` and `u` used to batch the openings of the
+/// decider, coined from the evaluations.
+fn opening_challenges(fq_sponge: &BaseSponge, evaluations: &DeciderEvaluations) -> (Fp, Fp) {
+    let (_, endo_r) = Curve::endos();
+    let mut fr_sponge = ScalarSponge::new(Curve::sponge_params());
+    fr_sponge.absorb(&fq_sponge.clone().digest());
+    for evaluation in evaluations.iter() {
+        fr_sponge.absorb_multiple(evaluation);
+    }
+    let v = fr_sponge.challenge().to_field(endo_r);
+    let u = fr_sponge.challenge().to_field(endo_r);
+    (v, u)
 }
 
-#[allow(dead_code)]
-/// This function folds the witness of the current circuit with the accumulated Keccak instance
-/// with a random combination using a scaling challenge
-// FIXME: This will have to be adapted when the folding library is available
-fn old_fold<
-    const N: usize,
-    G: KimchiCurve,
-    OpeningProof: OpenProof<G>,
-    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
->(
-    domain: EvaluationDomains<G::ScalarField>,
-    srs: &OpeningProof::SRS,
-    accumulator: &mut ProofInputs<N, G::ScalarField, LookupTableIDs>,
-    inputs: &Witness<N, Vec<G::ScalarField>>,
-) where
-    <OpeningProof as poly_commitment::OpenProof<G>>::SRS: std::marker::Sync,
+/// Proves that the accumulated witness satisfies the relaxed relation
+/// described by the final constraint, see [DeciderProof].
+fn prove_decider<RNG>(
+    final_constraint: &FoldingCompatibleExpr<DecomposableMIPSFoldingConfig>,
+    domain: D<Fp>,
+    srs: &PairingSRS<Pairing>,
+    mut fq_sponge: BaseSponge,
+    accumulator: &Accumulator,
+    rng: &mut RNG,
+) -> DeciderProof
+where
+    RNG: RngCore + CryptoRng,
 {
-    let commitments = {
-        inputs
-            .par_iter()
-            .map(|evals: &Vec<G::ScalarField>| {
-                let evals = Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
-                    evals.clone(),
-                    domain.d1,
-                );
-                srs.commit_evaluations_non_hiding(domain.d1, &evals)
-            })
-            .collect::<Witness<N, _>>()
+    let witness = &accumulator.witness;
+    let columns: Vec<DensePolynomial<Fp>> = witness
+        .extended_witness
+        .witness
+        .witness
+        .cols
+        .par_iter()
+        .map(Evaluations::interpolate_by_ref)
+        .collect();
+    let extended: Vec<DensePolynomial<Fp>> = witness
+        .extended_witness
+        .extended
+        .values()
+        .map(Evaluations::interpolate_by_ref)
+        .collect();
+    let error = witness.error_vec.interpolate_by_ref();
+
+    // The final constraint is of degree two in the columns, it is evaluated
+    // on a domain twice as large.
+    let d2 = D::<Fp>::new(2 * domain.size()).unwrap();
+    let on_d2 = |poly: &DensePolynomial<Fp>| poly.evaluate_over_domain_by_ref(d2);
+    let witness_on_d2 = RelaxedWitness {
+        extended_witness: ExtendedWitness {
+            witness: FoldingWitness {
+                witness: Witness::try_from(columns.iter().map(on_d2).collect::<Vec<_>>()).unwrap(),
+            },
+            extended: witness
+                .extended_witness
+                .extended
+                .keys()
+                .copied()
+                .zip(extended.iter().map(on_d2))
+                .collect(),
+        },
+        error_vec: on_d2(&error),
+    };
+    let constraint = ExtendedProvider::new(accumulator.instance.clone(), witness_on_d2)
+        .check_rec(final_constraint.clone(), d2);
+    let (quotient, remainder) = Evaluations::from_vec_and_domain(constraint, d2)
+        .interpolate()
+        .divide_by_vanishing_poly(domain)
+        .unwrap();
+    assert!(
+        remainder.is_zero(),
+        "The accumulated witness does not satisfy the relaxed relation"
+    );
+
+    let quotient_commitment = srs.commit_non_hiding(&quotient, 1);
+    absorb_commitment(&mut fq_sponge, &quotient_commitment);
+    let (_, endo_r) = Curve::endos();
+    let zeta = ScalarChallenge(fq_sponge.challenge()).to_field(endo_r);
+
+    let evaluate = |poly: &DensePolynomial<Fp>| [poly.evaluate(&zeta), poly.evaluate(&Fp::zero())];
+    let evaluations = DeciderEvaluations {
+        columns: columns.iter().map(evaluate).collect(),
+        extended: extended.iter().map(evaluate).collect(),
+        error: evaluate(&error),
+        quotient: evaluate(&quotient),
+    };
+    let (v, u) = opening_challenges(&fq_sponge, &evaluations);
+
+    let with_blinder = |poly, blinder| {
+        (
+            DensePolynomialOrEvaluations::DensePolynomial(poly),
+            PolyComm::new(vec![blinder]),
+        )
     };
-    let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
+    let polynomials: Vec<(DensePolynomialOrEvaluations<Fp, D<Fp>>, PolyComm<Fp>)> = columns
+        .iter()
+        .map(|poly| with_blinder(poly, Fp::zero()))
+        .chain(
+            extended
+                .iter()
+                .map(|poly| with_blinder(poly, accumulator.extended_blinder)),
+        )
+        .chain([
+            with_blinder(&error, accumulator.error_blinder),
+            with_blinder(&quotient, Fp::zero()),
+        ])
+        .collect();
+    let group_map = <Curve as CommitmentCurve>::Map::setup();
+    let opening = OpenProof::open::<_, _, D<Fp>>(
+        srs,
+        &group_map,
+        polynomials.as_slice(),
+        &[zeta, Fp::zero()],
+        v,
+        u,
+        fq_sponge,
+        rng,
+    );
+    DeciderProof {
+        quotient_commitment,
+        evaluations,
+        opening,
+    }
+}
+
+/// Evaluates the expression at the point at which the decider opened the
+/// polynomials of the accumulated witness, given their evaluations at this
+/// point. Returns `None` if the expression refers to a column that is not part
+/// of the witness.
+fn evaluate_at_point(
+    expr: &FoldingCompatibleExpr<DecomposableMIPSFoldingConfig>,
+    instance: &MIPSRelaxedInstance,
+    evaluations: &DeciderEvaluations,
+) -> Option<Fp> {
+    let evaluate = |expr| evaluate_at_point(expr, instance, evaluations);
+    match expr {
+        FoldingCompatibleExpr::Atom(atom) => match atom {
+            FoldingCompatibleExprInner::Constant(c) => Some(*c),
+            FoldingCompatibleExprInner::Challenge(chal) => {
+                Some(instance.extended_instance.instance[*chal])
+            }
+            FoldingCompatibleExprInner::Cell(Variable {
+                col,
+                row: CurrOrNext::Curr,
+            }) => Some(evaluations.columns[column_index(*col)?][0]),
+            FoldingCompatibleExprInner::Cell(_) => None,
+            FoldingCompatibleExprInner::Extensions(ext) => match ext {
+                ExpExtension::U => Some(instance.u),
+                ExpExtension::Error => Some(evaluations.error[0]),
+                ExpExtension::ExtendedWitness(i) => evaluations.extended.get(*i).map(|e| e[0]),
+                ExpExtension::Alpha(i) => instance.extended_instance.instance.alphas.get(*i),
+                ExpExtension::Selector(s) => Some(evaluations.columns[usize::from(*s)][0]),
+            },
+        },
+        FoldingCompatibleExpr::Double(e) => Some(evaluate(e)?.double()),
+        FoldingCompatibleExpr::Square(e) => Some(evaluate(e)?.square()),
+        FoldingCompatibleExpr::Add(e1, e2) => Some(evaluate(e1)? + evaluate(e2)?),
+        FoldingCompatibleExpr::Sub(e1, e2) => Some(evaluate(e1)? - evaluate(e2)?),
+        FoldingCompatibleExpr::Mul(e1, e2) => Some(evaluate(e1)? * evaluate(e2)?),
+        FoldingCompatibleExpr::Pow(e, k) => Some(evaluate(e)?.pow([*k])),
+    }
+}
+
+/// The decider of the accumulation scheme. It checks the proof that the
+/// accumulated instance is satisfied, and that the lookup terms of the
+/// accumulated witness sum to `lookup_sum`.
+fn verify_decider(
+    final_constraint: &FoldingCompatibleExpr<DecomposableMIPSFoldingConfig>,
+    domain: D<Fp>,
+    srs: &PairingSRS<Pairing>,
+    mut fq_sponge: BaseSponge,
+    instance: &MIPSRelaxedInstance,
+    lookup_sum: Fp,
+    proof: &DeciderProof,
+) -> bool {
+    let evaluations = &proof.evaluations;
+    let extended = &instance.extended_instance.extended;
+    if evaluations.columns.len() != N_MIPS_FOLDING_COLS
+        || evaluations.extended.len() != extended.len()
+    {
+        return false;
+    }
 
-    commitments.into_iter().for_each(|comm| {
-        absorb_commitment(&mut fq_sponge, &comm);
+    absorb_commitment(&mut fq_sponge, &proof.quotient_commitment);
+    let (_, endo_r) = Curve::endos();
+    let zeta = ScalarChallenge(fq_sponge.challenge()).to_field(endo_r);
+
+    let Some(constraint) = evaluate_at_point(final_constraint, instance, evaluations) else {
+        return false;
+    };
+    if constraint != evaluations.quotient[0] * domain.evaluate_vanishing_polynomial(zeta) {
+        return false;
+    }
+    let lookup_terms_at_zero: Fp = evaluations.columns[N_MIPS_COLS..N_MIPS_FOLDING_COLS - 1]
+        .iter()
+        .map(|evaluation| evaluation[1])
+        .sum();
+    if Fp::from(domain.size) * lookup_terms_at_zero != lookup_sum {
+        return false;
+    }
+
+    let (v, u) = opening_challenges(&fq_sponge, evaluations);
+    let commitments = instance
+        .extended_instance
+        .instance
+        .commitments
+        .iter()
+        .map(|commitment| PolyComm::new(vec![*commitment]))
+        .chain(extended.iter().cloned())
+        .chain([
+            instance.error_commitment.clone(),
+            proof.quotient_commitment.clone(),
+        ]);
+    let coms_and_evaluations: Vec<Evaluation<Curve>> = commitments
+        .zip(evaluations.iter())
+        .map(|(commitment, [at_zeta, at_zero])| Evaluation {
+            commitment,
+            evaluations: vec![vec![*at_zeta], vec![*at_zero]],
+        })
+        .collect();
+    let combined_inner_product = {
+        let es: Vec<_> = coms_and_evaluations
+            .iter()
+            .map(|Evaluation { evaluations, .. }| evaluations.clone())
+            .collect();
+        combined_inner_product(&v, &u, es.as_slice())
+    };
+    let batch = BatchEvaluationProof {
+        sponge: fq_sponge,
+        evaluations: coms_and_evaluations,
+        evaluation_points: vec![zeta, Fp::zero()],
+        polyscale: v,
+        evalscale: u,
+        opening: &proof.opening,
+        combined_inner_product,
+    };
+    let group_map = <Curve as CommitmentCurve>::Map::setup();
+    OpeningProof::verify(srs, &group_map, &mut [batch], &mut thread_rng())
+}
+
+/// Checks that the selector columns of the chunk select its instruction, and
+/// that the multiplicity column counts its `n_rows` first rows.
+fn check_chunk_columns(
+    srs: &PairingSRS<Pairing>,
+    domain: D<Fp>,
+    chunk: &Chunk,
+    [zero, one]: [Curve; 2],
+) -> bool {
+    let commitments = &chunk.instance.commitments;
+    let selectors_are_set = (N_MIPS_REL_COLS..N_MIPS_COLS).all(|i| {
+        let expected = if i == usize::from(chunk.instruction) {
+            one
+        } else {
+            zero
+        };
+        commitments[i] == expected
     });
+    (1..=domain.size()).contains(&chunk.n_rows)
+        && selectors_are_set
+        && commitments[N_MIPS_FOLDING_COLS - 1] == indicator_commitment(srs, domain, chunk.n_rows)
+}
+
+/// Whether the entry can be looked up in a table other than the registers and
+/// the memory. The entries of the syscalls are accepted as they are, as the
+/// Keccak circuit, at the other end of the channel, is not bound to the MIPS
+/// execution yet.
+fn is_table_entry(table_id: LookupTableIDs, value: &[Fp]) -> bool {
+    match table_id {
+        LookupTableIDs::AtMost4Lookup
+        | LookupTableIDs::ByteLookup
+        | LookupTableIDs::RangeCheck16Lookup => match value {
+            [v] => to_u64(*v).map_or(false, |v| v < table_id.length() as u64),
+            _ => false,
+        },
+        LookupTableIDs::SyscallLookup => true,
+        _ => false,
+    }
+}
+
+/// Returns the sum of the lookup terms of the entries at the boundary of the
+/// execution, which must be the sum of all the lookup terms of the execution,
+/// or `None` if an entry is not accepted.
+fn boundary_lookup_sum(
+    public_inputs: &PublicInputs<Fp>,
+    table_entries: &LookupEntries,
+    [beta, _, joint_combiner]: [Fp; 3],
+) -> Option<Fp> {
+    let mut sum = boundary_sum(public_inputs, beta, joint_combiner)?;
+    for ((table_id, value), multiplicity) in table_entries {
+        if !is_table_entry(*table_id, value) {
+            return None;
+        }
+        let combined_value = value
+            .iter()
+            .rev()
+            .fold(Fp::zero(), |acc, v| acc * joint_combiner + v)
+            * joint_combiner;
+        sum += *multiplicity * (beta + Fp::from(table_id.to_u32()) + combined_value).inverse()?;
+    }
+    Some(sum)
+}
 
-    let scaling_challenge = ScalarChallenge(fq_sponge.challenge());
-    let (_, endo_r) = G::endos();
-    let scaling_challenge = scaling_challenge.to_field(endo_r);
-    // TODO: fold mvlookups as well
-    accumulator
-        .evaluations
-        .par_iter_mut()
-        .zip(inputs.par_iter())
-        .for_each(|(accumulator, inputs)| {
-            accumulator
-                .par_iter_mut()
-                .zip(inputs.par_iter())
-                .for_each(|(accumulator, input)| {
-                    *accumulator = *input + scaling_challenge * *accumulator
-                });
+/// Checks that `extended` is `instance`, possibly extended with the
+/// commitments to the columns added by quadraticization if `instance` did not
+/// have them yet.
+fn is_extension_of(extended: &MIPSRelaxedInstance, instance: &MIPSRelaxedInstance) -> bool {
+    extended.extended_instance.instance == instance.extended_instance.instance
+        && extended.u == instance.u
+        && extended.error_commitment == instance.error_commitment
+        && extended.blinder == instance.blinder
+        && (instance.extended_instance.extended.is_empty()
+            || extended.extended_instance.extended == instance.extended_instance.extended)
+}
+
+/// Replays the folding steps of the proof, and returns the final accumulated
+/// instance with the sum of its lookup terms. Returns `None` if the steps do
+/// not match the chunks.
+fn replay_folding(
+    scheme: &DecomposableFoldingScheme<DecomposableMIPSFoldingConfig>,
+    fq_sponge: &mut BaseSponge,
+    proof: &AccumulationProof,
+) -> Option<(MIPSRelaxedInstance, Fp)> {
+    let mut steps = proof.steps.iter();
+    let mut fold = |selector: Option<Instruction>,
+                    (left, left_sum): &(MIPSRelaxedInstance, Fp),
+                    (right, right_sum): &(MIPSRelaxedInstance, Fp)|
+     -> Option<(MIPSRelaxedInstance, Fp)> {
+        let step = steps.next()?;
+        if step.selector != selector
+            || !is_extension_of(&step.left, left)
+            || !is_extension_of(&step.right, right)
+        {
+            return None;
+        }
+        let folded = scheme.fold_instance_pair(
+            step.left.clone(),
+            step.right.clone(),
+            step.cross_terms.clone(),
+            fq_sponge,
+        );
+        let r = (folded.u - step.left.u) * step.right.u.inverse()?;
+        Some((folded, *left_sum + r * right_sum))
+    };
+
+    let mut accumulators: BTreeMap<Instruction, (MIPSRelaxedInstance, Fp)> = BTreeMap::new();
+    for chunk in proof.chunks.iter() {
+        let right = (chunk.instance.clone().relax(), chunk.lookup_sum);
+        let accumulator = match accumulators.remove(&chunk.instruction) {
+            None => right,
+            Some(left) => fold(Some(chunk.instruction), &left, &right)?,
+        };
+        accumulators.insert(chunk.instruction, accumulator);
+    }
+
+    let mut accumulator: Option<(MIPSRelaxedInstance, Fp)> = None;
+    for right in accumulators.into_values() {
+        accumulator = Some(match accumulator {
+            None => right,
+            Some(left) => fold(None, &left, &right)?,
         });
+    }
+    let mut accumulator = accumulator?;
+    if accumulator.0.extended_instance.extended.is_empty() {
+        accumulator = fold(None, &accumulator, &accumulator)?;
+    }
+
+    // All the steps must have been used
+    if steps.next().is_some() {
+        return None;
+    }
+    Some(accumulator)
+}
+
+/// Verifies the proof of a whole MIPS execution: the columns and challenges
+/// of each chunk, the balance of the lookups between the chunks and the
+/// boundary of the execution, the folding steps leading to the accumulated
+/// instance, and finally the accumulated instance using the decider.
+///
+/// If the execution starts from the beginning of `program`, the registers and
+/// memory cells must not have been accessed before, and the memory must
+/// contain the program.
+pub fn verify_accumulation(
+    scheme: &DecomposableFoldingScheme<DecomposableMIPSFoldingConfig>,
+    final_constraint: &FoldingCompatibleExpr<DecomposableMIPSFoldingConfig>,
+    domain: D<Fp>,
+    srs: &PairingSRS<Pairing>,
+    proof: &AccumulationProof,
+    program: Option<&ProgramState<Fp>>,
+) -> bool {
+    let public_inputs = &proof.public_inputs;
+    if proof.chunks.is_empty()
+        || !check_boundary(public_inputs)
+        || !check_chained_boundaries([public_inputs], program)
+    {
+        return false;
+    }
+    let selector_commitments = [
+        indicator_commitment(srs, domain, 0),
+        indicator_commitment(srs, domain, domain.size()),
+    ];
+    let columns_are_valid = proof
+        .chunks
+        .iter()
+        .all(|chunk| check_chunk_columns(srs, domain, chunk, selector_commitments));
+    if !columns_are_valid {
+        return false;
+    }
+
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    absorb_boundary(&mut fq_sponge, public_inputs, &proof.table_entries);
+    for chunk in proof.chunks.iter() {
+        absorb_chunk_columns(&mut fq_sponge, chunk);
+    }
+    let challenges = lookup_challenges(&mut fq_sponge);
+    for chunk in proof.chunks.iter() {
+        absorb_chunk_lookups(&mut fq_sponge, chunk);
+    }
+    let alphas = Alphas::new_sized(fq_sponge.challenge(), final_constraint.number_of_alphas());
+    let challenges_are_shared = proof.chunks.iter().all(|chunk| {
+        chunk.instance.challenges == challenges
+            && chunk.instance.alphas == alphas
+            && chunk.instance.blinder == Fp::one()
+    });
+    let lookup_sum: Fp = proof.chunks.iter().map(|chunk| chunk.lookup_sum).sum();
+    let lookups_are_balanced =
+        boundary_lookup_sum(public_inputs, &proof.table_entries, challenges) == Some(lookup_sum);
+    if !(challenges_are_shared && lookups_are_balanced) {
+        return false;
+    }
+
+    let Some((instance, lookup_sum)) = replay_folding(scheme, &mut fq_sponge, proof) else {
+        return false;
+    };
+    verify_decider(
+        final_constraint,
+        domain,
+        srs,
+        fq_sponge,
+        &instance,
+        lookup_sum,
+        &proof.decider,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cannon::{Page, State, PAGE_SIZE},
+        interpreters::mips::{
+            column::{SCRATCH_SIZE, SCRATCH_SIZE_INVERSE},
+            constraints::Env as CEnv,
+            interpreter::interpret_instruction,
+            tests_helpers::OnDiskPreImageOracle,
+            witness::Env as WEnv,
+        },
+        legacy::{
+            folding::mips::N_MIPS_LOOKUP_COLS,
+            trace::{DecomposableTracer, Tracer},
+        },
+    };
+    use kimchi::{circuits::domains::EvaluationDomains, o1_utils};
+    use poly_commitment::SRS;
+    use strum::IntoEnumIterator;
+
+    const DOMAIN_SIZE: usize = 1 << 3;
+
+    /// The encoding of `addiu $1, $1, 1`
+    const ADDIU: u32 = (0b001001 << 26) | (1 << 21) | (1 << 16) | 1;
+    /// The encoding of `ori $2, $2, 3`
+    const ORI: u32 = (0b001101 << 26) | (2 << 21) | (2 << 16) | 3;
+
+    /// A VM running the given instructions from the address zero.
+    fn program_env(instructions: &[u32]) -> WEnv<Fp, OnDiskPreImageOracle> {
+        let mut data = vec![0; PAGE_SIZE as usize];
+        for (i, instruction) in instructions.iter().enumerate() {
+            data[4 * i..4 * i + 4].copy_from_slice(&instruction.to_be_bytes());
+        }
+        let state = State {
+            memory: vec![Page { index: 0, data }],
+            preimage_key: [0; 32],
+            preimage_offset: 0,
+            pc: 0,
+            next_pc: 4,
+            lo: 0,
+            hi: 0,
+            heap: 0,
+            exit: 0,
+            exited: false,
+            step: 0,
+            registers: [0; 32],
+            last_hint: None,
+            preimage: None,
+        };
+        WEnv::create(PAGE_SIZE as usize, state, OnDiskPreImageOracle)
+    }
+
+    /// Executes the next instruction of the VM, and adds its row to the
+    /// trace. Returns the instruction.
+    fn step(
+        env: &mut WEnv<Fp, OnDiskPreImageOracle>,
+        trace: &mut DecomposedMIPSTrace,
+    ) -> Instruction {
+        env.reset_scratch_state();
+        env.reset_scratch_state_inverse();
+        let (instr, _) = env.decode_instruction();
+        let instruction_counter = env.instruction_counter;
+        interpret_instruction(env, instr);
+        env.instruction_counter = env.next_instruction_counter();
+        let row: [Fp; N_MIPS_REL_COLS] = std::array::from_fn(|i| {
+            if i < SCRATCH_SIZE {
+                env.scratch_state[i]
+            } else if i < SCRATCH_SIZE + SCRATCH_SIZE_INVERSE {
+                env.scratch_state_inverse[i - SCRATCH_SIZE]
+                    .inverse()
+                    .unwrap_or_else(Fp::zero)
+            } else if i == SCRATCH_SIZE + SCRATCH_SIZE_INVERSE {
+                Fp::from(instruction_counter)
+            } else {
+                Fp::zero()
+            }
+        });
+        trace.push_row(instr, &row);
+        instr
+    }
+
+    #[test]
+    fn test_mips_number_of_lookup_columns() {
+        let mut constraints_env = CEnv::<Fp>::default();
+        let trace = DecomposedMIPSTrace::new(DOMAIN_SIZE, &mut constraints_env);
+        let max_lookups = trace
+            .trace
+            .values()
+            .map(|instr| instr.lookups.len())
+            .max()
+            .unwrap();
+        assert_eq!(max_lookups, N_MIPS_LOOKUP_COLS);
+    }
+
+    #[test]
+    fn test_mips_accumulation_of_chunks() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let domain = EvaluationDomains::<Fp>::create(DOMAIN_SIZE).unwrap().d1;
+        let srs = PairingSRS::<Pairing>::create(DOMAIN_SIZE);
+
+        let mut constraints_env = CEnv::<Fp>::default();
+        let mut trace = DecomposedMIPSTrace::new(DOMAIN_SIZE, &mut constraints_env);
+        let (scheme, final_constraint) =
+            DecomposableFoldingScheme::<DecomposableMIPSFoldingConfig>::new(
+                trace.folding_constraints_with_lookups(),
+                vec![],
+                &srs,
+                domain,
+                &(),
+            );
+
+        // A full chunk of addiu, a full chunk of ori, and a chunk of addiu
+        // padded after 5 rows. The two chunks of addiu are folded together,
+        // and merged afterwards with the chunk of ori.
+        let instructions: Vec<u32> = [(ADDIU, DOMAIN_SIZE), (ORI, DOMAIN_SIZE), (ADDIU, 5)]
+            .into_iter()
+            .flat_map(|(instruction, n)| std::iter::repeat(instruction).take(n))
+            .collect();
+        let mut env = program_env(&instructions);
+        let program = env.program_state();
+        let mut accumulator = MIPSAccumulator::new(&scheme, &final_constraint, domain, &srs);
+        for _ in 0..instructions.len() {
+            let instr = step(&mut env, &mut trace);
+            if trace.number_of_rows(instr) == DOMAIN_SIZE {
+                trace.set_selector_column::<N_MIPS_REL_COLS>(instr, DOMAIN_SIZE);
+                accumulator.add_chunk(&trace, instr, DOMAIN_SIZE);
+                trace.reset(instr);
+            }
+        }
+        for instr in Instruction::iter().flat_map(|instr| instr.into_iter()) {
+            let n_rows = trace.number_of_rows(instr);
+            if n_rows != 0 {
+                trace.pad_dummy(instr);
+                trace.set_selector_column::<N_MIPS_REL_COLS>(instr, DOMAIN_SIZE);
+                accumulator.add_chunk(&trace, instr, n_rows);
+            }
+        }
+        let proof =
+            accumulator.finalize(&trace, program.vm_state.clone(), env.vm_state(), &mut rng);
+        assert_eq!(proof.chunks.len(), 3);
+        assert_eq!(proof.steps.len(), 2);
+        let verify = |proof: &AccumulationProof| {
+            verify_accumulation(
+                &scheme,
+                &final_constraint,
+                domain,
+                &srs,
+                proof,
+                Some(&program),
+            )
+        };
+        assert!(verify(&proof));
+
+        // The accumulated witness must satisfy the relation
+        {
+            let mut proof = proof.clone();
+            proof.decider.evaluations.columns[0][0] += Fp::one();
+            assert!(!verify(&proof));
+        }
+
+        // The lookup terms of the chunks must sum to the terms of the
+        // boundary
+        {
+            let mut proof = proof.clone();
+            proof.chunks[1].lookup_sum += Fp::one();
+            assert!(!verify(&proof));
+        }
+
+        // The boundary of the execution must be the one of the program
+        {
+            let mut proof = proof.clone();
+            proof.public_inputs.final_ram[0].value += Fp::one();
+            assert!(!verify(&proof));
+        }
+
+        // The padding rows of a chunk are not counted in the lookups
+        {
+            let mut proof = proof.clone();
+            proof.chunks[2].n_rows = DOMAIN_SIZE;
+            assert!(!verify(&proof));
+        }
+
+        // The challenges must be shared by all the chunks
+        {
+            let mut proof = proof.clone();
+            proof.chunks[1].instance.challenges[0] += Fp::one();
+            assert!(!verify(&proof));
+        }
+
+        // The chunks must be folded in the order they were given
+        {
+            let mut proof = proof;
+            proof.chunks.swap(0, 2);
+            assert!(!verify(&proof));
+        }
+    }
 }
@@ -449,12 +449,33 @@ pub mod mips {
             interpreter::{interpret_instruction, Instruction, InterpreterEnv},
         },
         legacy::{
-            folding::{mips::DecomposableMIPSFoldingConfig, ScalarField},
+            folding::{
+                mips::{
+                    DecomposableMIPSFoldingConfig, MIPSFoldingWitness, MIPS_LOOKUP_MULTIPLICITY,
+                    N_MIPS_FOLDING_COLS, N_MIPS_LOOKUP_COLS,
+                },
+                FoldingInstance, FoldingWitness, ScalarField,
+            },
             trace::{DecomposableTracer, DecomposedTrace, Trace, Tracer},
+            Curve, Fp,
         },
+        lookups::{Lookup, LookupTableIDs},
+        E,
     };
-    use ark_ff::Zero;
-    use kimchi_msm::witness::Witness;
+    use ark_ec::AffineRepr;
+    use ark_ff::{batch_inversion, One, Zero};
+    use ark_poly::{EvaluationDomain as _, Evaluations, Radix2EvaluationDomain as D};
+    use folding::{
+        checker::{Checker, Provider},
+        expressions::FoldingCompatibleExpr,
+        Alphas,
+    };
+    use kimchi::circuits::{
+        berkeley_columns::BerkeleyChallengeTerm,
+        expr::{ConstantExpr, Expr, ExprInner, Variable},
+        gate::CurrOrNext,
+    };
+    use kimchi_msm::{columns::Column, witness::Witness, LookupTableID};
     use std::{array, collections::BTreeMap};
     use strum::IntoEnumIterator;
 
@@ -463,6 +484,224 @@ pub mod mips {
     /// The MIPS circuit trace
     pub type DecomposedMIPSTrace = DecomposedTrace<N_MIPS_COLS, DecomposableMIPSFoldingConfig>;
 
+    /// Returns the numerator and the denominator of the term added by the
+    /// lookup to the logup sum, i.e. `numerator` and
+    /// `β + table_id + j * v_0 + j^2 * v_1 + ...`, using the same combination
+    /// as [kimchi_msm::logup].
+    fn lookup_term(lookup: &Lookup<E<Fp>>) -> (E<Fp>, E<Fp>) {
+        let challenge = |chal: BerkeleyChallengeTerm| {
+            E::<Fp>::Atom(ExprInner::Constant(ConstantExpr::from(chal)))
+        };
+        let joint_combiner = challenge(BerkeleyChallengeTerm::JointCombiner);
+        let combined_value = lookup
+            .value
+            .iter()
+            .rev()
+            .fold(E::zero(), |acc, v| acc * joint_combiner.clone() + v.clone())
+            * joint_combiner;
+        let denominator = challenge(BerkeleyChallengeTerm::Beta)
+            + combined_value
+            + lookup.table_id.to_constraint();
+        (lookup.numerator(), denominator)
+    }
+
+    /// Returns the column containing the `i`-th lookup term of an instruction.
+    fn lookup_column(lookup: &Lookup<E<Fp>>, i: usize) -> E<Fp> {
+        Expr::Atom(ExprInner::Cell(Variable {
+            col: Column::LookupPartialSum((lookup.table_id.to_u32(), i)),
+            row: CurrOrNext::Curr,
+        }))
+    }
+
+    /// Returns the number of times the lookups of the current row are counted,
+    /// see [MIPS_LOOKUP_MULTIPLICITY].
+    fn lookup_multiplicity() -> E<Fp> {
+        Expr::Atom(ExprInner::Cell(Variable {
+            col: MIPS_LOOKUP_MULTIPLICITY,
+            row: CurrOrNext::Curr,
+        }))
+    }
+
+    /// Returns the constraints enforcing the lookup terms of an instruction to
+    /// be well formed. The `i`-th lookup term `h_i` must satisfy
+    /// `h_i * (β + table_id + j * v_0 + j^2 * v_1 + ...) = m * numerator_i`,
+    /// where `m` is the multiplicity of the lookups of the row.
+    pub fn lookup_constraints(lookups: &[Lookup<E<Fp>>]) -> Vec<E<Fp>> {
+        lookups
+            .iter()
+            .enumerate()
+            .map(|(i, lookup)| {
+                let (numerator, denominator) = lookup_term(lookup);
+                lookup_column(lookup, i) * denominator - lookup_multiplicity() * numerator
+            })
+            .collect()
+    }
+
+    /// The entries looked up by an execution, indexed by their table and their
+    /// value, with the number of times they are written minus the number of
+    /// times they are read. The entries with a zero multiplicity are removed.
+    pub type LookupEntries = BTreeMap<(LookupTableIDs, Vec<Fp>), Fp>;
+
+    /// Adds `multiplicity` to the multiplicity of the entry.
+    pub fn add_lookup_entry(
+        entries: &mut LookupEntries,
+        entry: (LookupTableIDs, Vec<Fp>),
+        multiplicity: Fp,
+    ) {
+        let total = *entries.entry(entry.clone()).or_insert_with(Fp::zero) + multiplicity;
+        if total.is_zero() {
+            entries.remove(&entry);
+        } else {
+            entries.insert(entry, total);
+        }
+    }
+
+    /// Evaluates the expressions on all the rows of the witness, the challenges
+    /// β, γ and the joint combiner being given by `challenges`. The witness is
+    /// given back with the evaluations.
+    fn evaluate_rows(
+        witness: MIPSFoldingWitness,
+        challenges: [Fp; 3],
+        exprs: Vec<E<Fp>>,
+        domain: D<Fp>,
+    ) -> (MIPSFoldingWitness, Vec<Vec<Fp>>) {
+        // The alphas are not used in the expressions
+        let provider = Provider::<DecomposableMIPSFoldingConfig>::new(
+            FoldingInstance {
+                commitments: [Curve::zero(); N_MIPS_FOLDING_COLS],
+                challenges,
+                alphas: Alphas::new(Fp::zero()),
+                blinder: Fp::one(),
+            },
+            witness,
+        );
+        let evaluations = exprs
+            .into_iter()
+            .map(|expr| provider.check_rec(expr.into(), domain))
+            .collect();
+        (provider.witness, evaluations)
+    }
+
+    impl DecomposedMIPSTrace {
+        /// Returns a map of constraints that are compatible with folding for
+        /// each instruction, including the constraints on the lookup terms.
+        pub fn folding_constraints_with_lookups(
+            &self,
+        ) -> BTreeMap<Instruction, Vec<FoldingCompatibleExpr<DecomposableMIPSFoldingConfig>>>
+        {
+            self.trace
+                .iter()
+                .map(|(instr, trace)| {
+                    let constraints = trace
+                        .constraints
+                        .iter()
+                        .cloned()
+                        .chain(lookup_constraints(&trace.lookups))
+                        .map(FoldingCompatibleExpr::from)
+                        .collect();
+                    (*instr, constraints)
+                })
+                .collect()
+        }
+
+        /// Returns the witness for the given instruction as a folding witness,
+        /// in which the lookups of the first `n_rows` rows are counted, the
+        /// other rows padding the chunk. The lookup terms are left to zero
+        /// until the challenges are known, see [Self::set_lookup_terms].
+        /// The witness must have been padded and its selector columns set.
+        pub fn to_folding_witness(
+            &self,
+            instr: Instruction,
+            n_rows: usize,
+            domain: D<Fp>,
+        ) -> MIPSFoldingWitness {
+            let zero_column =
+                Evaluations::from_vec_and_domain(vec![Fp::zero(); domain.size()], domain);
+            let multiplicities = (0..domain.size())
+                .map(|i| if i < n_rows { Fp::one() } else { Fp::zero() })
+                .collect();
+            let columns: Vec<_> = self[instr]
+                .witness
+                .cols
+                .iter()
+                .map(|w| Evaluations::from_vec_and_domain(w.to_vec(), domain))
+                .chain(std::iter::repeat(zero_column).take(N_MIPS_LOOKUP_COLS))
+                .chain([Evaluations::from_vec_and_domain(multiplicities, domain)])
+                .collect();
+            FoldingWitness {
+                witness: Witness::try_from(columns).unwrap(),
+            }
+        }
+
+        /// Returns the entries looked up by the instruction in the folding
+        /// witness, counted with the multiplicity of their row. The values and
+        /// numerators of the lookups do not depend on the challenges.
+        pub fn lookup_entries(
+            &self,
+            instr: Instruction,
+            witness: MIPSFoldingWitness,
+            domain: D<Fp>,
+        ) -> (MIPSFoldingWitness, LookupEntries) {
+            let lookups = &self[instr].lookups;
+            let exprs = lookups
+                .iter()
+                .flat_map(|lookup| {
+                    std::iter::once(lookup_multiplicity() * lookup.numerator())
+                        .chain(lookup.value.iter().cloned())
+                })
+                .collect();
+            let (witness, evaluations) = evaluate_rows(witness, [Fp::zero(); 3], exprs, domain);
+            let mut entries = LookupEntries::new();
+            let mut evaluations = evaluations.into_iter();
+            for lookup in lookups {
+                let numerators = evaluations.next().unwrap();
+                let values: Vec<Vec<Fp>> = evaluations.by_ref().take(lookup.value.len()).collect();
+                for (row, numerator) in numerators.into_iter().enumerate() {
+                    if !numerator.is_zero() {
+                        let value = values.iter().map(|v| v[row]).collect();
+                        add_lookup_entry(&mut entries, (lookup.table_id, value), numerator);
+                    }
+                }
+            }
+            (witness, entries)
+        }
+
+        /// Sets the lookup terms of the folding witness of the instruction for
+        /// the challenges β, γ and the joint combiner, and returns their sum
+        /// over all the rows.
+        pub fn set_lookup_terms(
+            &self,
+            instr: Instruction,
+            witness: MIPSFoldingWitness,
+            challenges: [Fp; 3],
+            domain: D<Fp>,
+        ) -> (MIPSFoldingWitness, Fp) {
+            let exprs = self[instr]
+                .lookups
+                .iter()
+                .flat_map(|lookup| {
+                    let (numerator, denominator) = lookup_term(lookup);
+                    [lookup_multiplicity() * numerator, denominator]
+                })
+                .collect();
+            let (mut witness, evaluations) = evaluate_rows(witness, challenges, exprs, domain);
+            let mut sum = Fp::zero();
+            for (i, term) in evaluations.chunks(2).enumerate() {
+                let mut denominators = term[1].clone();
+                batch_inversion(&mut denominators);
+                let term: Vec<Fp> = term[0]
+                    .iter()
+                    .zip(denominators)
+                    .map(|(n, d)| *n * d)
+                    .collect();
+                sum += term.iter().sum::<Fp>();
+                witness.witness.cols[N_MIPS_COLS + i] =
+                    Evaluations::from_vec_and_domain(term, domain);
+            }
+            (witness, sum)
+        }
+    }
+
     impl DecomposableTracer<Env<ScalarField<DecomposableMIPSFoldingConfig>>> for DecomposedMIPSTrace {
         fn new(
             domain_size: usize,
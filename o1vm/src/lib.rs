@@ -26,6 +26,9 @@ pub mod preimage_oracle;
 /// The RAM lookup argument.
 pub mod ramlookup;
 
+/// Tracing and debugging of the executions of the witness environments.
+pub mod tracer;
+
 pub mod utils;
 
 use kimchi::circuits::{
@@ -100,8 +100,18 @@ impl LookupTableID for LookupTableIDs {
         panic!("No runtime tables specified");
     }
 
-    fn ix_by_value<F: PrimeField>(&self, _value: &[F]) -> Option<usize> {
-        todo!()
+    fn ix_by_value<F: PrimeField>(&self, value: &[F]) -> Option<usize> {
+        let table = match self {
+            PadLookup => LookupTable::table_pad(),
+            RoundConstantsLookup => LookupTable::table_round_constants(),
+            AtMost4Lookup => LookupTable::table_at_most_4(),
+            ByteLookup => LookupTable::table_byte(),
+            RangeCheck16Lookup => LookupTable::table_range_check_16(),
+            SparseLookup => LookupTable::table_sparse(),
+            ResetLookup => LookupTable::table_reset(),
+            MemoryLookup | RegisterLookup | SyscallLookup | KeccakStepLookup => return None,
+        };
+        LookupTable::is_in_table(&table, value.to_vec())
     }
 
     fn all_variants() -> Vec<Self> {
@@ -121,6 +131,15 @@ impl LookupTableID for LookupTableIDs {
     }
 }
 
+impl LookupTableIDs {
+    /// Whether the table contains the registers or the memory of the VM. The
+    /// consistency of the accesses to these tables is checked by the offline
+    /// memory checking argument, see [crate::pickles::ram].
+    pub fn is_ram(&self) -> bool {
+        matches!(self, MemoryLookup | RegisterLookup)
+    }
+}
+
 /// Trait that creates all the fixed lookup tables used in the VM
 pub(crate) trait FixedLookupTables<F> {
     /// Checks whether a value is in a table and returns the position if it is or None otherwise.
@@ -2,9 +2,12 @@ use ark_ff::FftField;
 use ark_poly::{Evaluations, Radix2EvaluationDomain};
 use kimchi_msm::columns::Column;
 
-use crate::{
-    interpreters::mips::column::{N_MIPS_SEL_COLS, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE},
-    pickles::proof::WitnessColumns,
+use crate::pickles::{
+    proof::{RamColumns, RangeCheckColumns, WitnessColumns},
+    ram::{
+        byte_multiplicity_column, lookup_term_column, range_check_byte_column, AGGREGATION_COLUMN,
+        RANGE_CHECK_BYTES,
+    },
 };
 use kimchi::circuits::{
     berkeley_columns::{BerkeleyChallengeTerm, BerkeleyChallenges},
@@ -18,10 +21,26 @@ type Evals<F> = Evaluations<F, Radix2EvaluationDomain<F>>;
 /// required to evaluate an expression as a polynomial.
 ///
 /// All are evaluations.
-pub struct ColumnEnvironment<'a, F: FftField> {
+pub struct ColumnEnvironment<
+    'a,
+    F: FftField,
+    const N_SCRATCH: usize,
+    const N_SCRATCH_INVERSE: usize,
+    const N_SELECTORS: usize,
+> {
     /// The witness column polynomials. Includes relation columns and dynamic
     /// selector columns.
-    pub witness: &'a WitnessColumns<Evals<F>, [Evals<F>; N_MIPS_SEL_COLS]>,
+    pub witness:
+        &'a WitnessColumns<Evals<F>, [Evals<F>; N_SELECTORS], N_SCRATCH, N_SCRATCH_INVERSE>,
+    /// The columns of the range checks of the timestamps, if the instructions
+    /// make range checks.
+    pub range_check: Option<&'a RangeCheckColumns<Evals<F>>>,
+    /// The table of bytes, in which the bytes of the range checks are looked
+    /// up, if the instructions make range checks.
+    pub byte_table: Option<&'a Evals<F>>,
+    /// The columns of the memory checking argument, if they have already been
+    /// computed.
+    pub ram: Option<&'a RamColumns<Evals<F>>>,
     /// The value `prod_{j != 1} (1 - ω^j)`, used for efficiently
     /// computing the evaluations of the unnormalized Lagrange basis
     /// polynomials.
@@ -35,43 +54,90 @@ pub struct ColumnEnvironment<'a, F: FftField> {
     pub domain: EvaluationDomains<F>,
 }
 
-pub fn get_all_columns() -> Vec<Column> {
-    let mut cols =
-        Vec::<Column>::with_capacity(SCRATCH_SIZE + SCRATCH_SIZE_INVERSE + 2 + N_MIPS_SEL_COLS);
-    for i in 0..SCRATCH_SIZE + SCRATCH_SIZE_INVERSE + 2 {
+/// Return all the columns of a circuit using `N_SCRATCH` columns for the
+/// scratch state, `N_SCRATCH_INVERSE` for the values to be inverted and
+/// `N_SELECTORS` selectors.
+pub fn get_all_columns<
+    const N_SCRATCH: usize,
+    const N_SCRATCH_INVERSE: usize,
+    const N_SELECTORS: usize,
+>() -> Vec<Column> {
+    let mut cols = Vec::<Column>::with_capacity(N_SCRATCH + N_SCRATCH_INVERSE + 2 + N_SELECTORS);
+    for i in 0..N_SCRATCH + N_SCRATCH_INVERSE + 2 {
         cols.push(Column::Relation(i));
     }
-    for i in 0..N_MIPS_SEL_COLS {
+    for i in 0..N_SELECTORS {
         cols.push(Column::DynamicSelector(i));
     }
     cols
 }
 
-impl<G> WitnessColumns<G, [G; N_MIPS_SEL_COLS]> {
+/// Return the columns of the memory checking argument when the instructions
+/// make at most `n_lookup_terms` lookups into the RAM tables.
+pub fn get_ram_columns(n_lookup_terms: usize) -> Vec<Column> {
+    (0..n_lookup_terms)
+        .map(lookup_term_column)
+        .chain([AGGREGATION_COLUMN])
+        .collect()
+}
+
+/// Return the columns of the range checks when the instructions make at most
+/// `n_range_checks` range checks.
+pub fn get_range_check_columns(n_range_checks: usize) -> Vec<Column> {
+    let n_bytes = RANGE_CHECK_BYTES * n_range_checks;
+    (0..n_bytes)
+        .map(range_check_byte_column)
+        .chain((n_bytes > 0).then(byte_multiplicity_column))
+        .collect()
+}
+
+impl<G> RangeCheckColumns<G> {
+    pub fn get_column(&self, col: &Column) -> Option<&G> {
+        match *col {
+            Column::LookupRuntimeTable((_, i)) => self.bytes.get(i),
+            Column::LookupMultiplicity(_) => self.multiplicities.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+impl<G> RamColumns<G> {
+    pub fn get_column(&self, col: &Column) -> Option<&G> {
+        match *col {
+            Column::LookupPartialSum((_, i)) => self.lookup_terms.get(i),
+            Column::LookupAggregation => Some(&self.aggregation),
+            _ => None,
+        }
+    }
+}
+
+impl<G, const N_SCRATCH: usize, const N_SCRATCH_INVERSE: usize, const N_SELECTORS: usize>
+    WitnessColumns<G, [G; N_SELECTORS], N_SCRATCH, N_SCRATCH_INVERSE>
+{
     pub fn get_column(&self, col: &Column) -> Option<&G> {
         match *col {
             Column::Relation(i) => {
-                if i < SCRATCH_SIZE {
+                if i < N_SCRATCH {
                     let res = &self.scratch[i];
                     Some(res)
-                } else if i < SCRATCH_SIZE + SCRATCH_SIZE_INVERSE {
-                    let res = &self.scratch_inverse[i - SCRATCH_SIZE];
+                } else if i < N_SCRATCH + N_SCRATCH_INVERSE {
+                    let res = &self.scratch_inverse[i - N_SCRATCH];
                     Some(res)
-                } else if i == SCRATCH_SIZE + SCRATCH_SIZE_INVERSE {
+                } else if i == N_SCRATCH + N_SCRATCH_INVERSE {
                     let res = &self.instruction_counter;
                     Some(res)
-                } else if i == SCRATCH_SIZE + SCRATCH_SIZE_INVERSE + 1 {
+                } else if i == N_SCRATCH + N_SCRATCH_INVERSE + 1 {
                     let res = &self.error;
                     Some(res)
                 } else {
-                    panic!("We should not have that many relation columns. We have {} columns and index {} was given", SCRATCH_SIZE + SCRATCH_SIZE_INVERSE + 2, i);
+                    panic!("We should not have that many relation columns. We have {} columns and index {} was given", N_SCRATCH + N_SCRATCH_INVERSE + 2, i);
                 }
             }
             Column::DynamicSelector(i) => {
                 assert!(
-                    i < N_MIPS_SEL_COLS,
+                    i < N_SELECTORS,
                     "We do not have that many dynamic selector columns. We have {} columns and index {} was given",
-                    N_MIPS_SEL_COLS,
+                    N_SELECTORS,
                     i
                 );
                 let res = &self.selector[i];
@@ -87,15 +153,30 @@ impl<G> WitnessColumns<G, [G; N_MIPS_SEL_COLS]> {
     }
 }
 
-impl<'a, F: FftField> TColumnEnvironment<'a, F, BerkeleyChallengeTerm, BerkeleyChallenges<F>>
-    for ColumnEnvironment<'a, F>
+impl<
+        'a,
+        F: FftField,
+        const N_SCRATCH: usize,
+        const N_SCRATCH_INVERSE: usize,
+        const N_SELECTORS: usize,
+    > TColumnEnvironment<'a, F, BerkeleyChallengeTerm, BerkeleyChallenges<F>>
+    for ColumnEnvironment<'a, F, N_SCRATCH, N_SCRATCH_INVERSE, N_SELECTORS>
 {
     // FIXME: do we change to the MIPS column type?
     // We do not want to keep kimchi_msm/generic prover
     type Column = Column;
 
     fn get_column(&self, col: &Self::Column) -> Option<&'a Evals<F>> {
-        self.witness.get_column(col)
+        match col {
+            Column::LookupRuntimeTable(_) | Column::LookupMultiplicity(_) => self
+                .range_check
+                .and_then(|range_check| range_check.get_column(col)),
+            Column::LookupFixedTable(_) => self.byte_table,
+            Column::LookupPartialSum(_) | Column::LookupAggregation => {
+                self.ram.and_then(|ram| ram.get_column(col))
+            }
+            _ => self.witness.get_column(col),
+        }
     }
 
     fn get_domain(&self, d: Domain) -> Radix2EvaluationDomain<F> {
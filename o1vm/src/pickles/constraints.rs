@@ -0,0 +1,179 @@
+//! The constraints of the interpreters proven by the pickles flavor.
+//!
+//! The constraints of every instruction are multiplied by the selector of the
+//! instruction, so that only the ones of the instruction executed on a row are
+//! enforced. The selectors are themselves constrained to be booleans, exactly
+//! one of them being activated on each row.
+
+use ark_ff::PrimeField;
+use kimchi_msm::{
+    columns::{Column, ColumnIndexer},
+    expr::E,
+};
+use strum::IntoEnumIterator;
+
+use super::ram::RamLookups;
+use crate::{
+    interpreters::{
+        mips::{
+            column::ColumnAlias as MIPSColumn, constraints as mips_constraints,
+            interpreter as mips_interpreter, witness as mips_witness,
+        },
+        riscv32im::{
+            column::Column as RISCVColumn, constraints as riscv_constraints,
+            interpreter as riscv_interpreter, witness as riscv_witness,
+        },
+    },
+    lookups::LookupTableIDs,
+    ramlookup::RAMLookup,
+};
+
+/// The environment building the constraints of an interpreter, instruction by
+/// instruction.
+pub trait SelectorConstraintEnv<F: PrimeField>: Default {
+    type Instruction;
+
+    /// The maximum number of accesses to the registers and to the memory made
+    /// by an instruction. The instruction counter increases by this amount
+    /// after each instruction.
+    const MAX_ACC: u64;
+
+    /// All the instructions of the interpreter
+    fn instructions() -> Vec<Self::Instruction>;
+
+    /// Build the constraints and the lookups of `instruction`
+    fn interpret(&mut self, instruction: Self::Instruction);
+
+    /// The selector of the last interpreted instruction
+    fn selector(&self) -> E<F>;
+
+    /// The column of the instruction counter, containing its value before the
+    /// execution of the instruction of each row
+    fn instruction_counter_column() -> Column;
+
+    /// The constraints of the last interpreted instruction, without the
+    /// selector
+    fn constraints(&self) -> Vec<E<F>>;
+
+    /// The lookups of the last interpreted instruction, without the selector
+    fn lookups(&self) -> Vec<RAMLookup<E<F>, LookupTableIDs>>;
+
+    /// The values range checked by the last interpreted instruction, without
+    /// the selector
+    fn range_checks(&self) -> Vec<E<F>>;
+
+    /// The constraints enforcing the selectors to be well-formed
+    fn selector_constraints(&self) -> Vec<E<F>>;
+
+    /// Forget the last interpreted instruction
+    fn reset(&mut self);
+}
+
+impl<F: PrimeField> SelectorConstraintEnv<F> for mips_constraints::Env<F> {
+    type Instruction = crate::interpreters::mips::Instruction;
+
+    const MAX_ACC: u64 = mips_witness::MAX_ACC;
+
+    fn instructions() -> Vec<Self::Instruction> {
+        Self::Instruction::iter()
+            .flat_map(|instr_typ| instr_typ.into_iter())
+            .collect()
+    }
+
+    fn interpret(&mut self, instruction: Self::Instruction) {
+        mips_interpreter::interpret_instruction(self, instruction)
+    }
+
+    fn selector(&self) -> E<F> {
+        self.get_selector()
+    }
+
+    fn instruction_counter_column() -> Column {
+        MIPSColumn::InstructionCounter.to_column()
+    }
+
+    fn constraints(&self) -> Vec<E<F>> {
+        self.get_constraints()
+    }
+
+    fn lookups(&self) -> Vec<RAMLookup<E<F>, LookupTableIDs>> {
+        self.get_lookups()
+    }
+
+    fn range_checks(&self) -> Vec<E<F>> {
+        self.get_range_checks()
+    }
+
+    fn selector_constraints(&self) -> Vec<E<F>> {
+        self.get_selector_constraints()
+    }
+
+    fn reset(&mut self) {
+        mips_interpreter::InterpreterEnv::reset(self)
+    }
+}
+
+impl<F: PrimeField> SelectorConstraintEnv<F> for riscv_constraints::Env<F> {
+    type Instruction = riscv_interpreter::Instruction;
+
+    const MAX_ACC: u64 = riscv_witness::MAX_ACC;
+
+    fn instructions() -> Vec<Self::Instruction> {
+        Self::Instruction::iter()
+            .flat_map(|instr_typ| instr_typ.into_iter())
+            .collect()
+    }
+
+    fn interpret(&mut self, instruction: Self::Instruction) {
+        riscv_interpreter::interpret_instruction(self, instruction)
+    }
+
+    fn selector(&self) -> E<F> {
+        self.get_selector()
+    }
+
+    fn instruction_counter_column() -> Column {
+        RISCVColumn::InstructionCounter.to_column()
+    }
+
+    fn constraints(&self) -> Vec<E<F>> {
+        self.get_constraints()
+    }
+
+    fn lookups(&self) -> Vec<RAMLookup<E<F>, LookupTableIDs>> {
+        self.get_lookups()
+    }
+
+    fn range_checks(&self) -> Vec<E<F>> {
+        self.get_range_checks()
+    }
+
+    fn selector_constraints(&self) -> Vec<E<F>> {
+        self.get_selector_constraints()
+    }
+
+    fn reset(&mut self) {
+        riscv_interpreter::InterpreterEnv::reset(self)
+    }
+}
+
+/// Build the constraints of all the instructions of an interpreter, each of
+/// them multiplied by the selector of its instruction, followed by the
+/// constraints of the selectors. The lookups of the instructions into the
+/// registers and the memory, and the range checks of their timestamps, are
+/// combined in the returned [RamLookups].
+pub fn build_constraints<F: PrimeField, Env: SelectorConstraintEnv<F>>(
+) -> (Vec<E<F>>, RamLookups<F>) {
+    let mut env = Env::default();
+    let mut ram_lookups = RamLookups::new(Env::instruction_counter_column(), Env::MAX_ACC);
+    let mut constraints = vec![];
+    for instruction in Env::instructions() {
+        env.interpret(instruction);
+        let selector = env.selector();
+        ram_lookups.add_instruction(&selector, &env.lookups(), &env.range_checks());
+        constraints.extend(env.constraints().into_iter().map(|c| selector.clone() * c));
+        env.reset();
+    }
+    constraints.extend(env.selector_constraints());
+    (constraints, ram_lookups)
+}
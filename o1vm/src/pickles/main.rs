@@ -1,7 +1,7 @@
 use ark_ff::UniformRand;
 use kimchi::circuits::domains::EvaluationDomains;
 use kimchi_msm::expr::E;
-use log::debug;
+use log::{debug, error, info, warn};
 use mina_curves::pasta::VestaParameters;
 use mina_poseidon::{
     constants::PlonkSpongeConstantsKimchi,
@@ -10,30 +10,232 @@ use mina_poseidon::{
 use o1vm::{
     cannon::{self, Meta, Start, State},
     cannon_cli,
-    interpreters::mips::{
-        column::N_MIPS_REL_COLS,
-        constraints as mips_constraints,
-        interpreter::{self, InterpreterEnv},
-        witness::{self as mips_witness},
-        Instruction,
+    interpreters::{
+        mips::{
+            column::{N_MIPS_REL_COLS, N_MIPS_SEL_COLS, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE},
+            constraints as mips_constraints,
+            witness::{self as mips_witness},
+            Instruction,
+        },
+        riscv32im::{
+            constraints as riscv_constraints, INSTRUCTION_SET_SIZE,
+            SCRATCH_SIZE as RISCV_SCRATCH_SIZE, SCRATCH_SIZE_INVERSE as RISCV_SCRATCH_SIZE_INVERSE,
+        },
     },
-    pickles::{proof::ProofInputs, prover, verifier},
-    preimage_oracle::PreImageOracle,
+    pickles::{
+        constraints::{self, SelectorConstraintEnv},
+        proof::{Proof, ProofInputs, PublicInputs},
+        proof_file::{self, Interpreter},
+        prover,
+        ram::RamLookups,
+        verifier, DOMAIN_SIZE,
+    },
+    preimage_oracle::{self, PreImageOracleT},
+    tracer::Tracer,
 };
 use poly_commitment::{ipa::SRS, SRS as _};
 use std::{fs::File, io::BufReader, process::ExitCode, time::Instant};
-use strum::IntoEnumIterator;
 
 use mina_curves::pasta::{Fp, Vesta};
 
-pub const DOMAIN_SIZE: usize = 1 << 15;
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+type MIPSProof = Proof<Vesta, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE, N_MIPS_SEL_COLS>;
 
 pub fn main() -> ExitCode {
-    let cli = cannon_cli::main_cli();
+    let cli = cannon_cli::main_cli().subcommand(
+        clap::Command::new("verify")
+            .about("Verify proofs previously generated by the o1vm")
+            .arg(
+                clap::arg!(--proof <FILE> "Files containing the proofs of consecutive chunks")
+                    .required(true)
+                    .num_args(1..),
+            ),
+    );
+    let matches = cli.get_matches();
+
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    match matches.subcommand() {
+        Some(("verify", matches)) => verify(matches),
+        _ => run(&matches),
+    }
+}
+
+/// Verify proofs previously written by the o1vm when using `--proof-at`, by
+/// this binary or by the RISC-V one.
+/// When several proofs are given, they must be the proofs of consecutive
+/// chunks of the same execution, in the order of the execution.
+fn verify(matches: &clap::ArgMatches) -> ExitCode {
+    let mut interpreter = None;
+    let mut proofs: Vec<(&String, serde_json::Value)> = vec![];
+    for proof_file in matches.get_many::<String>("proof").unwrap() {
+        let proof_file_content = match proof_file::read_proof_file(proof_file) {
+            Ok(content) => content,
+            Err(err) => {
+                error!("{err}");
+                return ExitCode::FAILURE;
+            }
+        };
+        if proof_file_content.domain_size != DOMAIN_SIZE {
+            error!(
+                "The proof in {proof_file} is for a domain of size {}, but the size {DOMAIN_SIZE} is expected",
+                proof_file_content.domain_size
+            );
+            return ExitCode::FAILURE;
+        }
+        if *interpreter.get_or_insert(proof_file_content.interpreter)
+            != proof_file_content.interpreter
+        {
+            error!(
+                "The proof in {proof_file} is not for the same interpreter as the previous ones"
+            );
+            return ExitCode::FAILURE;
+        }
+        proofs.push((proof_file, proof_file_content.proof));
+    }
+
+    match interpreter {
+        Some(Interpreter::Mips) => verify_proofs::<
+            SCRATCH_SIZE,
+            SCRATCH_SIZE_INVERSE,
+            N_MIPS_SEL_COLS,
+            mips_constraints::Env<Fp>,
+        >(proofs),
+        Some(Interpreter::Riscv32im) => verify_proofs::<
+            RISCV_SCRATCH_SIZE,
+            RISCV_SCRATCH_SIZE_INVERSE,
+            INSTRUCTION_SET_SIZE,
+            riscv_constraints::Env<Fp>,
+        >(proofs),
+        None => unreachable!("clap should ensure at least one proof is given"),
+    }
+}
+
+/// Decode the proofs of consecutive chunks of an execution of the interpreter
+/// whose constraints are built by `Env`, and verify them.
+fn verify_proofs<
+    const N_SCRATCH: usize,
+    const N_SCRATCH_INVERSE: usize,
+    const N_SELECTORS: usize,
+    Env: SelectorConstraintEnv<Fp>,
+>(
+    proofs: Vec<(&String, serde_json::Value)>,
+) -> ExitCode {
+    let mut decoded_proofs: Vec<Proof<Vesta, N_SCRATCH, N_SCRATCH_INVERSE, N_SELECTORS>> =
+        Vec::with_capacity(proofs.len());
+    for (proof_file, proof) in proofs {
+        match serde_json::from_value(proof) {
+            Ok(proof) => decoded_proofs.push(proof),
+            Err(err) => {
+                error!("Error deserializing the proof in {proof_file}: {err}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let domain_fp = EvaluationDomains::<Fp>::create(DOMAIN_SIZE).unwrap();
+    let srs: SRS<Vesta> = SRS::create(DOMAIN_SIZE);
+    let (constraints, ram_lookups) = constraints::build_constraints::<Fp, Env>();
+
+    let start = Instant::now();
+    let verif = verifier::verify_chain::<
+        Vesta,
+        BaseSponge,
+        ScalarSponge,
+        N_SCRATCH,
+        N_SCRATCH_INVERSE,
+        N_SELECTORS,
+    >(
+        domain_fp,
+        &srs,
+        &constraints,
+        &ram_lookups,
+        &decoded_proofs,
+        // The proofs may start at any step of the execution, the entries read
+        // for the first time are therefore not checked against the program
+        None,
+    );
+    debug!(
+        "Verification done in {elapsed} μs",
+        elapsed = start.elapsed().as_micros()
+    );
+    if verif {
+        info!("The proofs are valid");
+        ExitCode::SUCCESS
+    } else {
+        error!("The proofs are invalid");
+        ExitCode::FAILURE
+    }
+}
 
+/// Add the row of the instruction just executed by the VM to the execution
+/// trace. `instruction_counter` is the instruction counter before its
+/// execution, from which the timestamps of its accesses are computed.
+fn push_row(
+    proof_inputs: &mut ProofInputs<Vesta, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE>,
+    mips_wit_env: &mips_witness::Env<Fp, Box<dyn PreImageOracleT>>,
+    instruction_counter: u64,
+    rng: &mut rand::rngs::ThreadRng,
+) {
+    proof_inputs.push_row(
+        &mips_wit_env.scratch_state,
+        &mips_wit_env.scratch_state_inverse,
+        Fp::from(instruction_counter),
+        // FIXME: Might be another value
+        Fp::rand(rng),
+        Fp::from((mips_wit_env.selector - N_MIPS_REL_COLS) as u64),
+    );
+}
+
+/// Prove the execution trace contained in `proof_inputs`, and verify the
+/// resulting proof (for testing purposes).
+fn prove_and_verify_chunk(
+    domain: EvaluationDomains<Fp>,
+    srs: &SRS<Vesta>,
+    constraints: &[E<Fp>],
+    ram_lookups: &RamLookups<Fp>,
+    proof_inputs: ProofInputs<Vesta, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE>,
+    rng: &mut rand::rngs::ThreadRng,
+) -> MIPSProof {
+    let start_iteration = Instant::now();
+    let proof = prover::prove::<
+        Vesta,
+        BaseSponge,
+        ScalarSponge,
+        _,
+        SCRATCH_SIZE,
+        SCRATCH_SIZE_INVERSE,
+        N_MIPS_SEL_COLS,
+    >(domain, srs, proof_inputs, constraints, ram_lookups, rng)
+    .unwrap();
+    debug!(
+        "Proof generated in {elapsed} μs",
+        elapsed = start_iteration.elapsed().as_micros()
+    );
+
+    let start_iteration = Instant::now();
+    let verif = verifier::verify::<
+        Vesta,
+        BaseSponge,
+        ScalarSponge,
+        SCRATCH_SIZE,
+        SCRATCH_SIZE_INVERSE,
+        N_MIPS_SEL_COLS,
+    >(domain, srs, constraints, ram_lookups, &proof);
+    debug!(
+        "Verification done in {elapsed} μs",
+        elapsed = start_iteration.elapsed().as_micros()
+    );
+    assert!(verif);
+    proof
+}
+
+fn run(matches: &clap::ArgMatches) -> ExitCode {
     let mut rng = rand::thread_rng();
 
-    let configuration = cannon_cli::read_configuration(&cli.get_matches());
+    let configuration = cannon_cli::read_configuration(matches);
 
     let file =
         File::open(&configuration.input_state_file).expect("Error opening input state file ");
@@ -56,14 +258,11 @@ pub fn main() -> ExitCode {
         )
     });
 
-    let mut po = PreImageOracle::create(&configuration.host);
-    let _child = po.start();
+    let (po, _child) = preimage_oracle::create_oracle(&configuration);
 
     // Initialize some data used for statistical computations
     let start = Start::create(state.step as usize);
 
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
-
     let domain_fp = EvaluationDomains::<Fp>::create(DOMAIN_SIZE).unwrap();
     let srs: SRS<Vesta> = {
         let srs = SRS::create(DOMAIN_SIZE);
@@ -72,92 +271,122 @@ pub fn main() -> ExitCode {
     };
 
     // Initialize the environments
-    let mut mips_wit_env =
-        mips_witness::Env::<Fp, PreImageOracle>::create(cannon::PAGE_SIZE as usize, state, po);
-
-    let constraints = {
-        let mut mips_con_env = mips_constraints::Env::<Fp>::default();
-        let mut constraints = Instruction::iter()
-            .flat_map(|instr_typ| instr_typ.into_iter())
-            .fold(vec![], |mut acc, instr| {
-                interpreter::interpret_instruction(&mut mips_con_env, instr);
-                let selector = mips_con_env.get_selector();
-                let constraints_with_selector: Vec<E<Fp>> = mips_con_env
-                    .get_constraints()
-                    .into_iter()
-                    .map(|c| selector.clone() * c)
-                    .collect();
-                acc.extend(constraints_with_selector);
-                mips_con_env.reset();
-                acc
-            });
-        constraints.extend(mips_con_env.get_selector_constraints());
-        constraints
-    };
+    let mut mips_wit_env = mips_witness::Env::<Fp, Box<dyn PreImageOracleT>>::create(
+        cannon::PAGE_SIZE as usize,
+        state,
+        po,
+    );
+    mips_wit_env.tracer = Tracer::from_options(
+        configuration.trace.as_deref(),
+        configuration.trace_format,
+        configuration.breakpoints.clone(),
+        Some(meta.clone()),
+    );
+
+    let (constraints, ram_lookups) =
+        constraints::build_constraints::<Fp, mips_constraints::Env<Fp>>();
 
-    let mut curr_proof_inputs: ProofInputs<Vesta> = ProofInputs::new(DOMAIN_SIZE);
+    let mut curr_proof_inputs: ProofInputs<Vesta, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE> =
+        ProofInputs::new(DOMAIN_SIZE);
+    // First step of the current chunk matching `--proof-at`, if any. The proof
+    // of the chunk is written to disk when it is set.
+    let mut proof_step: Option<u64> = None;
+    // State of the VM before the first instruction of the current chunk
+    let mut chunk_initial_state = mips_wit_env.vm_state();
     while !mips_wit_env.halt {
-        let _instr: Instruction = mips_wit_env.step(&configuration, &meta, &start);
-        for (scratch, scratch_chunk) in mips_wit_env
-            .scratch_state
-            .iter()
-            .zip(curr_proof_inputs.evaluations.scratch.iter_mut())
-        {
-            scratch_chunk.push(*scratch);
+        if proof_step.is_none() && mips_wit_env.should_trigger_at(&configuration.proof_at) {
+            proof_step = Some(mips_wit_env.normalized_instruction_counter());
         }
-        for (scratch, scratch_chunk) in mips_wit_env
-            .scratch_state_inverse
-            .iter()
-            .zip(curr_proof_inputs.evaluations.scratch_inverse.iter_mut())
-        {
-            scratch_chunk.push(*scratch);
+        // The timestamps of the accesses of the instruction are computed from
+        // the instruction counter before its execution.
+        let instruction_counter = mips_wit_env.instruction_counter;
+        let _instr: Instruction = mips_wit_env.step(&configuration, &meta, &start);
+        // The VM may be stopped before executing the instruction, e.g. with
+        // `--stop-at`.
+        if mips_wit_env.instruction_counter == instruction_counter {
+            break;
         }
-        curr_proof_inputs
-            .evaluations
-            .instruction_counter
-            .push(Fp::from(mips_wit_env.instruction_counter));
-        // FIXME: Might be another value
-        curr_proof_inputs.evaluations.error.push(Fp::rand(&mut rng));
-
-        curr_proof_inputs
-            .evaluations
-            .selector
-            .push(Fp::from((mips_wit_env.selector - N_MIPS_REL_COLS) as u64));
+        push_row(
+            &mut curr_proof_inputs,
+            &mips_wit_env,
+            instruction_counter,
+            &mut rng,
+        );
 
         if curr_proof_inputs.evaluations.instruction_counter.len() == DOMAIN_SIZE {
-            // FIXME
-            let start_iteration = Instant::now();
             debug!("Limit of {DOMAIN_SIZE} reached. We make a proof, verify it (for testing) and start with a new chunk");
-            let proof = prover::prove::<
-                Vesta,
-                DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>,
-                DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>,
-                _,
-            >(domain_fp, &srs, curr_proof_inputs, &constraints, &mut rng)
-            .unwrap();
-            // FIXME: check that the proof is correct. This is for testing purposes.
-            // Leaving like this for now.
-            debug!(
-                "Proof generated in {elapsed} μs",
-                elapsed = start_iteration.elapsed().as_micros()
+            // The final state of this chunk is the initial state of the next one
+            let final_state = mips_wit_env.vm_state();
+            curr_proof_inputs.public_inputs = PublicInputs::new(
+                std::mem::replace(&mut chunk_initial_state, final_state.clone()),
+                final_state,
+                std::mem::take(&mut mips_wit_env.ram_lookups),
+            );
+            let proof = prove_and_verify_chunk(
+                domain_fp,
+                &srs,
+                &constraints,
+                &ram_lookups,
+                curr_proof_inputs,
+                &mut rng,
             );
-            {
-                let start_iteration = Instant::now();
-                let verif = verifier::verify::<
-                    Vesta,
-                    DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>,
-                    DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>,
-                >(domain_fp, &srs, &constraints, &proof);
-                debug!(
-                    "Verification done in {elapsed} μs",
-                    elapsed = start_iteration.elapsed().as_micros()
+            if let Some(step) = proof_step.take() {
+                proof_file::write_proof(
+                    &configuration.proof_fmt,
+                    step,
+                    Interpreter::Mips,
+                    DOMAIN_SIZE,
+                    &proof,
                 );
-                assert!(verif);
             }
 
             curr_proof_inputs = ProofInputs::new(DOMAIN_SIZE);
         }
     }
-    // TODO: Logic
+
+    // The last chunk is padded by executing again the instruction that made
+    // the program exit, which leaves the state of the VM unchanged.
+    if !curr_proof_inputs.evaluations.instruction_counter.is_empty() {
+        while curr_proof_inputs.evaluations.instruction_counter.len() < DOMAIN_SIZE {
+            let instruction_counter = mips_wit_env.instruction_counter;
+            if !mips_wit_env.step_exited() {
+                warn!(
+                    "The VM stopped before the program exited. The last {} instructions are not proven",
+                    curr_proof_inputs.evaluations.instruction_counter.len()
+                );
+                return ExitCode::SUCCESS;
+            }
+            push_row(
+                &mut curr_proof_inputs,
+                &mips_wit_env,
+                instruction_counter,
+                &mut rng,
+            );
+        }
+        debug!("Program halted. We make a proof for the remaining instructions and verify it");
+        curr_proof_inputs.public_inputs = PublicInputs::new(
+            chunk_initial_state,
+            mips_wit_env.vm_state(),
+            std::mem::take(&mut mips_wit_env.ram_lookups),
+        );
+        let proof = prove_and_verify_chunk(
+            domain_fp,
+            &srs,
+            &constraints,
+            &ram_lookups,
+            curr_proof_inputs,
+            &mut rng,
+        );
+        if let Some(step) = proof_step {
+            proof_file::write_proof(
+                &configuration.proof_fmt,
+                step,
+                Interpreter::Mips,
+                DOMAIN_SIZE,
+                &proof,
+            );
+        }
+    }
+
     ExitCode::SUCCESS
 }
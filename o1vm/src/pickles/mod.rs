@@ -11,12 +11,26 @@
 //! ```bash
 //! O1VM_FLAVOR=pickles bash run-code.sh
 //! ```
+//!
+//! RISC-V 32IM programs can be proven directly from an ELF binary, without
+//! going through Cannon, by using:
+//!
+//! ```bash
+//! cargo run --release --bin pickles_riscv32im_o1vm -- --elf <FILE>
+//! ```
 
 pub mod column_env;
+pub mod constraints;
 pub mod proof;
+pub mod proof_file;
 pub mod prover;
+pub mod ram;
 pub mod verifier;
 
+/// Size of the chunks of the execution traces proven by the binaries of the
+/// pickles flavor.
+pub const DOMAIN_SIZE: usize = 1 << 15;
+
 /// Maximum degree of the constraints.
 /// It does include the additional degree induced by the multiplication of the
 /// selectors.
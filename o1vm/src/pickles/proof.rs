@@ -1,21 +1,295 @@
+use crate::{lookups::LookupTableIDs, ramlookup::RAMMultiset};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use kimchi::{curve::KimchiCurve, proof::PointEvaluations};
+use kimchi_msm::LookupTableID;
 use poly_commitment::{ipa::OpeningProof, PolyComm};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 
-use crate::interpreters::mips::column::{N_MIPS_SEL_COLS, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE};
-
-pub struct WitnessColumns<G, S> {
-    pub scratch: [G; SCRATCH_SIZE],
-    pub scratch_inverse: [G; SCRATCH_SIZE_INVERSE],
+/// The columns of the execution trace of a zkVM.
+///
+/// The number of columns depends on the interpreter: `N_SCRATCH` columns are
+/// used for the scratch state and `N_SCRATCH_INVERSE` for the values that are
+/// batch-inverted when building the witness. The selectors are described by
+/// `S`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WitnessColumns<G, S, const N_SCRATCH: usize, const N_SCRATCH_INVERSE: usize> {
+    pub scratch: [G; N_SCRATCH],
+    pub scratch_inverse: [G; N_SCRATCH_INVERSE],
     pub instruction_counter: G,
     pub error: G,
     pub selector: S,
 }
 
-pub struct ProofInputs<G: KimchiCurve> {
-    pub evaluations: WitnessColumns<Vec<G::ScalarField>, Vec<G::ScalarField>>,
+impl<T, const N_SCRATCH: usize, const N_SCRATCH_INVERSE: usize, const N_SELECTORS: usize>
+    WitnessColumns<T, [T; N_SELECTORS], N_SCRATCH, N_SCRATCH_INVERSE>
+{
+    /// Flatten the columns, in the order scratch, scratch inverse, instruction
+    /// counter, error and selectors.
+    pub fn into_vec(self) -> Vec<T> {
+        let WitnessColumns {
+            scratch,
+            scratch_inverse,
+            instruction_counter,
+            error,
+            selector,
+        } = self;
+        scratch
+            .into_iter()
+            .chain(scratch_inverse)
+            .chain([instruction_counter, error])
+            .chain(selector)
+            .collect()
+    }
+
+    /// Build the columns from a list given in the order used by
+    /// [WitnessColumns::into_vec].
+    /// Returns `None` if the list does not have the expected length.
+    pub fn from_vec(columns: Vec<T>) -> Option<Self> {
+        if columns.len() != N_SCRATCH + N_SCRATCH_INVERSE + 2 + N_SELECTORS {
+            return None;
+        }
+        let mut columns = columns.into_iter();
+        let mut next = || columns.next().unwrap();
+        Some(WitnessColumns {
+            scratch: std::array::from_fn(|_| next()),
+            scratch_inverse: std::array::from_fn(|_| next()),
+            instruction_counter: next(),
+            error: next(),
+            selector: std::array::from_fn(|_| next()),
+        })
+    }
+}
+
+/// The columns of the memory checking argument, see [crate::pickles::ram].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RamColumns<G> {
+    /// The terms of the lookups into the RAM tables, one column for the k-th
+    /// lookup of every instruction
+    pub lookup_terms: Vec<G>,
+    /// The running sum of the lookup terms
+    pub aggregation: G,
+}
+
+impl<G> RamColumns<G> {
+    /// Flatten the columns, in the order lookup terms and aggregation.
+    pub fn into_vec(self) -> Vec<G> {
+        let RamColumns {
+            mut lookup_terms,
+            aggregation,
+        } = self;
+        lookup_terms.push(aggregation);
+        lookup_terms
+    }
+
+    /// Build the columns from a list given in the order used by
+    /// [RamColumns::into_vec].
+    /// Returns `None` if the list is empty.
+    pub fn from_vec(mut columns: Vec<G>) -> Option<Self> {
+        let aggregation = columns.pop()?;
+        Some(RamColumns {
+            lookup_terms: columns,
+            aggregation,
+        })
+    }
+}
+
+/// The columns of the range checks of the timestamps, committed to before the
+/// columns of the memory checking argument, see [crate::pickles::ram].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeCheckColumns<G> {
+    /// The bytes of the range checked values, one column for each byte of the
+    /// k-th range check of every instruction
+    pub bytes: Vec<G>,
+    /// The number of lookups of each row of the table of bytes, if the
+    /// instructions make range checks
+    pub multiplicities: Option<G>,
 }
 
-impl<G: KimchiCurve> ProofInputs<G> {
+impl<G> RangeCheckColumns<G> {
+    /// Flatten the columns, in the order bytes and multiplicities.
+    pub fn into_vec(self) -> Vec<G> {
+        let RangeCheckColumns {
+            mut bytes,
+            multiplicities,
+        } = self;
+        bytes.extend(multiplicities);
+        bytes
+    }
+
+    /// Build the columns from a list given in the order used by
+    /// [RangeCheckColumns::into_vec].
+    pub fn from_vec(mut columns: Vec<G>) -> Self {
+        let multiplicities = columns.pop();
+        RangeCheckColumns {
+            bytes: columns,
+            multiplicities,
+        }
+    }
+}
+
+/// The state of the VM at a given step, committed to by a chunk proof.
+///
+/// Both fields are bound to the execution trace, see [crate::pickles::ram]:
+/// the instruction counter by constraints on the first and last rows, and the
+/// registers, including the instruction pointer, by the entries of the
+/// registers table. The content of the memory is given by the entries of the
+/// memory table accessed by the chunk.
+#[serde_as]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "F: CanonicalDeserialize + CanonicalSerialize")]
+pub struct VmState<F> {
+    /// The instruction counter before the next instruction, i.e. the timestamp
+    /// of its first access
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub instruction_counter: F,
+    /// The registers of the VM, in the order of their index
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub registers: Vec<F>,
+}
+
+impl<F: Clone> VmState<F> {
+    /// The values of the state, in the order they are absorbed in the sponge.
+    pub fn to_field_elements(&self) -> Vec<F> {
+        [self.instruction_counter.clone()]
+            .into_iter()
+            .chain(self.registers.iter().cloned())
+            .collect()
+    }
+}
+
+/// The state of a program before its execution, to which the first chunk of
+/// the execution is bound, see [crate::pickles::verifier::verify_chain].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProgramState<F> {
+    pub vm_state: VmState<F>,
+    /// The pages of the memory, given by their index and their content. The
+    /// bytes outside of the pages are zero.
+    pub memory: Vec<(u32, Vec<u8>)>,
+}
+
+impl<F> ProgramState<F> {
+    /// The byte at `address` in the memory of the program.
+    pub fn memory_byte(&self, address: u32) -> u8 {
+        self.memory
+            .iter()
+            .filter(|(_, data)| !data.is_empty())
+            .find_map(|(page, data)| {
+                let page_size = data.len() as u32;
+                (address / page_size == *page).then(|| data[(address % page_size) as usize])
+            })
+            .unwrap_or(0)
+    }
+}
+
+/// The content of a register or of a memory cell at the boundary of a chunk,
+/// as seen by the memory checking argument, see [crate::pickles::ram].
+#[serde_as]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "F: CanonicalDeserialize + CanonicalSerialize")]
+pub struct RamEntry<F> {
+    /// The table containing the entry, i.e. [LookupTableIDs::RegisterLookup]
+    /// or [LookupTableIDs::MemoryLookup]
+    pub table_id: u32,
+    /// The index of the register or the address of the memory cell
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub address: F,
+    /// The timestamp of the last access, i.e. the value of the instruction
+    /// counter at that time
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub timestamp: F,
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub value: F,
+}
+
+impl<F: Clone + From<u64>> RamEntry<F> {
+    /// The values of the entry, in the order they are absorbed in the sponge.
+    pub fn to_field_elements(&self) -> [F; 4] {
+        [
+            F::from(self.table_id as u64),
+            self.address.clone(),
+            self.timestamp.clone(),
+            self.value.clone(),
+        ]
+    }
+}
+
+/// The public inputs of a chunk proof: the state of the VM before the first
+/// instruction of the chunk and after the last one, and the content of the
+/// registers and memory cells accessed by the chunk before and after the
+/// accesses.
+///
+/// The proofs of two consecutive chunks are chained when the final state of the
+/// first one is the initial state of the second one, see
+/// [crate::pickles::verifier::verify_chain].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "F: CanonicalDeserialize + CanonicalSerialize")]
+pub struct PublicInputs<F> {
+    pub initial_state: VmState<F>,
+    pub final_state: VmState<F>,
+    /// The entries before the first access of the chunk to each address
+    pub initial_ram: Vec<RamEntry<F>>,
+    /// The entries after the last access of the chunk to each address
+    pub final_ram: Vec<RamEntry<F>>,
+}
+
+impl<F: PrimeField> PublicInputs<F> {
+    /// Build the public inputs of a chunk from the states of the VM at its
+    /// boundaries and the accesses to the registers and to the memory made by
+    /// its instructions.
+    ///
+    /// Panics if the accesses are not consistent, see
+    /// [RAMMultiset::into_boundary].
+    pub fn new(
+        initial_state: VmState<F>,
+        final_state: VmState<F>,
+        ram_lookups: RAMMultiset<LookupTableIDs>,
+    ) -> Self {
+        let to_ram_entry = |(table_id, value): (LookupTableIDs, Vec<u64>)| {
+            let [address, timestamp, value] = value[..] else {
+                panic!("The entries of the RAM tables are made of an address, a timestamp and a value, got {:?}", value)
+            };
+            RamEntry {
+                table_id: table_id.to_u32(),
+                address: F::from(address),
+                timestamp: F::from(timestamp),
+                value: F::from(value),
+            }
+        };
+        let (initial_ram, final_ram) = ram_lookups.into_boundary();
+        PublicInputs {
+            initial_state,
+            final_state,
+            initial_ram: initial_ram.into_iter().map(to_ram_entry).collect(),
+            final_ram: final_ram.into_iter().map(to_ram_entry).collect(),
+        }
+    }
+}
+
+impl<F: Clone + From<u64>> PublicInputs<F> {
+    /// The values of the public inputs, in the order they are absorbed in the
+    /// sponge. The lists of entries are prefixed by their length.
+    pub fn to_field_elements(&self) -> Vec<F> {
+        let mut elements = self.initial_state.to_field_elements();
+        elements.extend(self.final_state.to_field_elements());
+        for entries in [&self.initial_ram, &self.final_ram] {
+            elements.push(F::from(entries.len() as u64));
+            elements.extend(entries.iter().flat_map(RamEntry::to_field_elements));
+        }
+        elements
+    }
+}
+
+pub struct ProofInputs<G: KimchiCurve, const N_SCRATCH: usize, const N_SCRATCH_INVERSE: usize> {
+    pub evaluations:
+        WitnessColumns<Vec<G::ScalarField>, Vec<G::ScalarField>, N_SCRATCH, N_SCRATCH_INVERSE>,
+    pub public_inputs: PublicInputs<G::ScalarField>,
+}
+
+impl<G: KimchiCurve, const N_SCRATCH: usize, const N_SCRATCH_INVERSE: usize>
+    ProofInputs<G, N_SCRATCH, N_SCRATCH_INVERSE>
+{
     pub fn new(domain_size: usize) -> Self {
         ProofInputs {
             evaluations: WitnessColumns {
@@ -25,17 +299,171 @@ impl<G: KimchiCurve> ProofInputs<G> {
                 error: Vec::with_capacity(domain_size),
                 selector: Vec::with_capacity(domain_size),
             },
+            public_inputs: PublicInputs::default(),
+        }
+    }
+
+    /// Add a row to the execution trace. The selector is given as the index of
+    /// the instruction executed at this row.
+    pub fn push_row(
+        &mut self,
+        scratch: &[G::ScalarField; N_SCRATCH],
+        scratch_inverse: &[G::ScalarField; N_SCRATCH_INVERSE],
+        instruction_counter: G::ScalarField,
+        error: G::ScalarField,
+        selector: G::ScalarField,
+    ) {
+        let evaluations = &mut self.evaluations;
+        for (value, column) in scratch.iter().zip(evaluations.scratch.iter_mut()) {
+            column.push(*value);
         }
+        for (value, column) in scratch_inverse
+            .iter()
+            .zip(evaluations.scratch_inverse.iter_mut())
+        {
+            column.push(*value);
+        }
+        evaluations.instruction_counter.push(instruction_counter);
+        evaluations.error.push(error);
+        evaluations.selector.push(selector);
     }
 }
 
 // FIXME: should we blind the commitment?
-pub struct Proof<G: KimchiCurve> {
-    pub commitments: WitnessColumns<PolyComm<G>, [PolyComm<G>; N_MIPS_SEL_COLS]>,
-    pub zeta_evaluations: WitnessColumns<G::ScalarField, [G::ScalarField; N_MIPS_SEL_COLS]>,
-    pub zeta_omega_evaluations: WitnessColumns<G::ScalarField, [G::ScalarField; N_MIPS_SEL_COLS]>,
+/// A proof that a chunk of the execution trace satisfies the constraints of
+/// the interpreter.
+///
+/// The proof can be serialized using [serde]. The columns are flattened
+/// following [WitnessColumns::into_vec], and the field elements and the curve
+/// points are encoded using their canonical compressed representation, which
+/// keeps the format independent of the number of columns of the interpreter.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(
+    bound = "G: CanonicalDeserialize + CanonicalSerialize",
+    into = "SerializableProof<G>",
+    try_from = "SerializableProof<G>"
+)]
+pub struct Proof<
+    G: KimchiCurve,
+    const N_SCRATCH: usize,
+    const N_SCRATCH_INVERSE: usize,
+    const N_SELECTORS: usize,
+> {
+    /// The states of the VM the proof commits to
+    pub public_inputs: PublicInputs<G::ScalarField>,
+    pub commitments:
+        WitnessColumns<PolyComm<G>, [PolyComm<G>; N_SELECTORS], N_SCRATCH, N_SCRATCH_INVERSE>,
+    pub zeta_evaluations:
+        WitnessColumns<G::ScalarField, [G::ScalarField; N_SELECTORS], N_SCRATCH, N_SCRATCH_INVERSE>,
+    pub zeta_omega_evaluations:
+        WitnessColumns<G::ScalarField, [G::ScalarField; N_SELECTORS], N_SCRATCH, N_SCRATCH_INVERSE>,
+    pub range_check_commitments: RangeCheckColumns<PolyComm<G>>,
+    pub range_check_zeta_evaluations: RangeCheckColumns<G::ScalarField>,
+    pub range_check_zeta_omega_evaluations: RangeCheckColumns<G::ScalarField>,
+    pub ram_commitments: RamColumns<PolyComm<G>>,
+    pub ram_zeta_evaluations: RamColumns<G::ScalarField>,
+    pub ram_zeta_omega_evaluations: RamColumns<G::ScalarField>,
     pub quotient_commitment: PolyComm<G>,
     pub quotient_evaluations: PointEvaluations<Vec<G::ScalarField>>,
     /// IPA opening proof
     pub opening_proof: OpeningProof<G>,
 }
+
+/// Serialized representation of [Proof], where the columns are given as
+/// lists.
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "G: CanonicalDeserialize + CanonicalSerialize")]
+struct SerializableProof<G: KimchiCurve> {
+    public_inputs: PublicInputs<G::ScalarField>,
+    commitments: Vec<PolyComm<G>>,
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    zeta_evaluations: Vec<G::ScalarField>,
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    zeta_omega_evaluations: Vec<G::ScalarField>,
+    range_check_commitments: Vec<PolyComm<G>>,
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    range_check_zeta_evaluations: Vec<G::ScalarField>,
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    range_check_zeta_omega_evaluations: Vec<G::ScalarField>,
+    ram_commitments: Vec<PolyComm<G>>,
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    ram_zeta_evaluations: Vec<G::ScalarField>,
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    ram_zeta_omega_evaluations: Vec<G::ScalarField>,
+    quotient_commitment: PolyComm<G>,
+    quotient_evaluations: PointEvaluations<Vec<G::ScalarField>>,
+    opening_proof: OpeningProof<G>,
+}
+
+impl<
+        G: KimchiCurve,
+        const N_SCRATCH: usize,
+        const N_SCRATCH_INVERSE: usize,
+        const N_SELECTORS: usize,
+    > From<Proof<G, N_SCRATCH, N_SCRATCH_INVERSE, N_SELECTORS>> for SerializableProof<G>
+{
+    fn from(proof: Proof<G, N_SCRATCH, N_SCRATCH_INVERSE, N_SELECTORS>) -> Self {
+        SerializableProof {
+            public_inputs: proof.public_inputs,
+            commitments: proof.commitments.into_vec(),
+            zeta_evaluations: proof.zeta_evaluations.into_vec(),
+            zeta_omega_evaluations: proof.zeta_omega_evaluations.into_vec(),
+            range_check_commitments: proof.range_check_commitments.into_vec(),
+            range_check_zeta_evaluations: proof.range_check_zeta_evaluations.into_vec(),
+            range_check_zeta_omega_evaluations: proof.range_check_zeta_omega_evaluations.into_vec(),
+            ram_commitments: proof.ram_commitments.into_vec(),
+            ram_zeta_evaluations: proof.ram_zeta_evaluations.into_vec(),
+            ram_zeta_omega_evaluations: proof.ram_zeta_omega_evaluations.into_vec(),
+            quotient_commitment: proof.quotient_commitment,
+            quotient_evaluations: proof.quotient_evaluations,
+            opening_proof: proof.opening_proof,
+        }
+    }
+}
+
+impl<
+        G: KimchiCurve,
+        const N_SCRATCH: usize,
+        const N_SCRATCH_INVERSE: usize,
+        const N_SELECTORS: usize,
+    > TryFrom<SerializableProof<G>> for Proof<G, N_SCRATCH, N_SCRATCH_INVERSE, N_SELECTORS>
+{
+    type Error = String;
+
+    fn try_from(proof: SerializableProof<G>) -> Result<Self, Self::Error> {
+        let n_columns = N_SCRATCH + N_SCRATCH_INVERSE + 2 + N_SELECTORS;
+        let wrong_length =
+            |name: &str, len: usize| format!("Expected {n_columns} {name}, got {len}");
+        let n_commitments = proof.commitments.len();
+        let n_zeta_evaluations = proof.zeta_evaluations.len();
+        let n_zeta_omega_evaluations = proof.zeta_omega_evaluations.len();
+        Ok(Proof {
+            public_inputs: proof.public_inputs,
+            commitments: WitnessColumns::from_vec(proof.commitments)
+                .ok_or_else(|| wrong_length("commitments", n_commitments))?,
+            zeta_evaluations: WitnessColumns::from_vec(proof.zeta_evaluations)
+                .ok_or_else(|| wrong_length("evaluations at zeta", n_zeta_evaluations))?,
+            zeta_omega_evaluations: WitnessColumns::from_vec(proof.zeta_omega_evaluations)
+                .ok_or_else(|| {
+                    wrong_length("evaluations at zeta * omega", n_zeta_omega_evaluations)
+                })?,
+            range_check_commitments: RangeCheckColumns::from_vec(proof.range_check_commitments),
+            range_check_zeta_evaluations: RangeCheckColumns::from_vec(
+                proof.range_check_zeta_evaluations,
+            ),
+            range_check_zeta_omega_evaluations: RangeCheckColumns::from_vec(
+                proof.range_check_zeta_omega_evaluations,
+            ),
+            ram_commitments: RamColumns::from_vec(proof.ram_commitments)
+                .ok_or("Missing the commitments of the RAM columns")?,
+            ram_zeta_evaluations: RamColumns::from_vec(proof.ram_zeta_evaluations)
+                .ok_or("Missing the evaluations at zeta of the RAM columns")?,
+            ram_zeta_omega_evaluations: RamColumns::from_vec(proof.ram_zeta_omega_evaluations)
+                .ok_or("Missing the evaluations at zeta * omega of the RAM columns")?,
+            quotient_commitment: proof.quotient_commitment,
+            quotient_evaluations: proof.quotient_evaluations,
+            opening_proof: proof.opening_proof,
+        })
+    }
+}
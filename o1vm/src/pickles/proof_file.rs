@@ -0,0 +1,75 @@
+//! The files the proofs of the chunks are written to when using `--proof-at`,
+//! shared by the binaries of the pickles flavor.
+//!
+//! A file contains a single proof, preceded by the version of the format, the
+//! interpreter whose execution is proven and the size of the domain, which are
+//! checked before verifying it.
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+};
+
+/// The version of the format of the proof files, to be increased when the
+/// proofs or the constraints change.
+pub const PROOF_FILE_VERSION: u32 = 4;
+
+/// The interpreter whose execution trace is proven.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Interpreter {
+    Mips,
+    Riscv32im,
+}
+
+/// The content of a proof file.
+#[derive(Serialize, Deserialize)]
+pub struct ProofFile<P> {
+    pub version: u32,
+    pub interpreter: Interpreter,
+    pub domain_size: usize,
+    pub proof: P,
+}
+
+/// Write the proof of the chunk containing the step `step` to the file
+/// described by `proof_fmt`, where `%d` is replaced by `step`.
+pub fn write_proof<P: Serialize>(
+    proof_fmt: &str,
+    step: u64,
+    interpreter: Interpreter,
+    domain_size: usize,
+    proof: &P,
+) {
+    let filename = proof_fmt.replace("%d", &step.to_string());
+    let file = File::create(&filename).unwrap_or_else(|_| panic!("Could not create {filename}"));
+    let proof_file = ProofFile {
+        version: PROOF_FILE_VERSION,
+        interpreter,
+        domain_size,
+        proof,
+    };
+    serde_json::to_writer(BufWriter::new(file), &proof_file)
+        .unwrap_or_else(|err| panic!("Error writing the proof to {filename}: {err}"));
+    info!("Proof for the chunk containing step {step} written to {filename}");
+}
+
+/// Read the proof file `filename`, leaving the proof undecoded as its type
+/// depends on the interpreter given in the file.
+///
+/// Returns an error if the file cannot be read, is malformed or does not have
+/// the version [PROOF_FILE_VERSION].
+pub fn read_proof_file(filename: &str) -> Result<ProofFile<serde_json::Value>, String> {
+    let file = File::open(filename).map_err(|err| format!("Could not open {filename}: {err}"))?;
+    let content: serde_json::Value = serde_json::from_reader(BufReader::new(file))
+        .map_err(|err| format!("Error deserializing the proof in {filename}: {err}"))?;
+    // The version is checked first, as the other fields depend on it
+    let version = content.get("version").and_then(serde_json::Value::as_u64);
+    if version != Some(PROOF_FILE_VERSION as u64) {
+        return Err(format!(
+            "The proof in {filename} has the version {version:?}, but the version {PROOF_FILE_VERSION} is expected"
+        ));
+    }
+    serde_json::from_value(content)
+        .map_err(|err| format!("Error deserializing the proof in {filename}: {err}"))
+}
@@ -27,10 +27,11 @@ use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterato
 
 use super::{
     column_env::ColumnEnvironment,
-    proof::{Proof, ProofInputs, WitnessColumns},
+    proof::{Proof, ProofInputs, RamColumns, RangeCheckColumns, WitnessColumns},
+    ram::{self, RamLookups},
     DEGREE_QUOTIENT_POLYNOMIAL,
 };
-use crate::{interpreters::mips::column::N_MIPS_SEL_COLS, E};
+use crate::E;
 use thiserror::Error;
 
 /// Errors that can arise when creating a proof
@@ -38,6 +39,12 @@ use thiserror::Error;
 pub enum ProverError {
     #[error("the provided constraint has degree {0} > allowed {1}; expr: {2}")]
     ConstraintDegreeTooHigh(u64, u64, String),
+    #[error(
+        "the accesses to the registers and the memory are not consistent with the public inputs"
+    )]
+    InconsistentRamAccesses,
+    #[error("the domain must have at least {0} rows to contain the table of the range checks")]
+    DomainTooSmallForRangeChecks(usize),
 }
 
 /// Make a PlonKish proof for the given circuit. As inputs, we get the execution
@@ -45,25 +52,40 @@ pub enum ProverError {
 /// `domain`.
 ///
 /// The proof is made of the following steps:
-/// 1. For each column, we create a commitment and absorb it in the sponge.
-/// 2. We compute the quotient polynomial.
-/// 3. We evaluate each polynomial (columns + quotient) to two challenges ζ and ζω.
-/// 4. We make a batch opening proof using the IPA PCS.
+/// 1. We absorb the public inputs, i.e. the initial and final states of the VM
+///    and the entries of the RAM tables, and for each column, we create a
+///    commitment and absorb it in the sponge.
+/// 2. We decompose in bytes the values range checked by the instructions, and
+///    absorb the commitments to the bytes and to their multiplicities.
+/// 3. We compute the columns of the memory checking argument for the lookups
+///    `ram_lookups`, see [super::ram], and absorb their commitments.
+/// 4. We compute the quotient polynomial.
+/// 5. We evaluate each polynomial (columns + quotient) to two challenges ζ and ζω.
+/// 6. We make a batch opening proof using the IPA PCS.
 ///
 /// The final proof consists of the opening proof, the commitments and the
 /// evaluations at ζ and ζω.
+///
+/// The shape of the circuit is given by the number of columns used for the
+/// scratch state (`N_SCRATCH`), for the values to be inverted
+/// (`N_SCRATCH_INVERSE`) and by the number of selectors (`N_SELECTORS`), which
+/// depend on the interpreter used to build the execution trace.
 pub fn prove<
     G: KimchiCurve,
     EFqSponge: FqSponge<G::BaseField, G, G::ScalarField> + Clone,
     EFrSponge: FrSponge<G::ScalarField>,
     RNG,
+    const N_SCRATCH: usize,
+    const N_SCRATCH_INVERSE: usize,
+    const N_SELECTORS: usize,
 >(
     domain: EvaluationDomains<G::ScalarField>,
     srs: &SRS<G>,
-    inputs: ProofInputs<G>,
+    inputs: ProofInputs<G, N_SCRATCH, N_SCRATCH_INVERSE>,
     constraints: &[E<G::ScalarField>],
+    ram_lookups: &RamLookups<G::ScalarField>,
     rng: &mut RNG,
-) -> Result<Proof<G>, ProverError>
+) -> Result<Proof<G, N_SCRATCH, N_SCRATCH_INVERSE, N_SELECTORS>, ProverError>
 where
     G::BaseField: PrimeField,
     RNG: RngCore + CryptoRng,
@@ -73,15 +95,26 @@ where
 
     let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
 
+    ////////////////////////////////////////////////////////////////////////////
+    // Round 0: Absorbing the public inputs
+    ////////////////////////////////////////////////////////////////////////////
+
+    let ProofInputs {
+        evaluations,
+        public_inputs,
+    } = inputs;
+    fq_sponge.absorb_fr(&public_inputs.to_field_elements());
+
     ////////////////////////////////////////////////////////////////////////////
     // Round 1: Creating and absorbing column commitments
     ////////////////////////////////////////////////////////////////////////////
 
     debug!("Prover: interpolating all columns, including the selectors");
-    let ProofInputs { evaluations } = inputs;
     let polys: WitnessColumns<
         DensePolynomial<G::ScalarField>,
-        [DensePolynomial<G::ScalarField>; N_MIPS_SEL_COLS],
+        [DensePolynomial<G::ScalarField>; N_SELECTORS],
+        N_SCRATCH,
+        N_SCRATCH_INVERSE,
     > = {
         let WitnessColumns {
             scratch,
@@ -94,7 +127,7 @@ where
         let domain_size = domain.d1.size as usize;
 
         // Build the selectors
-        let selector: [Vec<G::ScalarField>; N_MIPS_SEL_COLS] = array::from_fn(|i| {
+        let selector: [Vec<G::ScalarField>; N_SELECTORS] = array::from_fn(|i| {
             let mut s_i = Vec::with_capacity(domain_size);
             for s in &selector {
                 s_i.push(if G::ScalarField::from(i as u64) == *s {
@@ -130,7 +163,12 @@ where
     };
 
     debug!("Prover: committing to all columns, including the selectors");
-    let commitments: WitnessColumns<PolyComm<G>, [PolyComm<G>; N_MIPS_SEL_COLS]> = {
+    let commitments: WitnessColumns<
+        PolyComm<G>,
+        [PolyComm<G>; N_SELECTORS],
+        N_SCRATCH,
+        N_SCRATCH_INVERSE,
+    > = {
         let WitnessColumns {
             scratch,
             scratch_inverse,
@@ -207,33 +245,268 @@ where
     }
 
     ////////////////////////////////////////////////////////////////////////////
-    // Round 2: Creating and committing to the quotient polynomial
+    // Round 2: Creating and committing to the columns of the range checks
     ////////////////////////////////////////////////////////////////////////////
 
     let (_, endo_r) = G::endos();
+    let zk_rows = 0;
+    let constants = Constants {
+        endo_coefficient: *endo_r,
+        mds: &G::sponge_params().mds,
+        zk_rows,
+    };
+
+    let n_range_checks = ram_lookups.n_range_checks();
+    if n_range_checks > 0 && domain.d1.size < ram::BYTE_TABLE_SIZE as u64 {
+        return Err(ProverError::DomainTooSmallForRangeChecks(
+            ram::BYTE_TABLE_SIZE,
+        ));
+    }
+
+    debug!("Prover: decomposing the range checked values in bytes");
+    let range_check_polys: RangeCheckColumns<DensePolynomial<G::ScalarField>> = {
+        let column_env: ColumnEnvironment<
+            '_,
+            G::ScalarField,
+            N_SCRATCH,
+            N_SCRATCH_INVERSE,
+            N_SELECTORS,
+        > = ColumnEnvironment {
+            constants: constants.clone(),
+            // The range checked values do not depend on any challenge
+            challenges: BerkeleyChallenges {
+                alpha: G::ScalarField::zero(),
+                beta: G::ScalarField::zero(),
+                gamma: G::ScalarField::zero(),
+                joint_combiner: G::ScalarField::zero(),
+            },
+            witness: &evaluations_d8,
+            range_check: None,
+            byte_table: None,
+            ram: None,
+            l0_1: l0_1(domain.d1),
+            domain,
+        };
+        let domain_size = domain.d1.size as usize;
+        let eval_rows = |expr: &E<G::ScalarField>| {
+            let evals = expr.evaluations(&column_env).evals;
+            let step = evals.len() / domain_size;
+            evals.into_iter().step_by(step).collect::<Vec<_>>()
+        };
+        // The number of lookups of each byte, stored on the row of the byte in
+        // the table
+        let mut multiplicities = vec![G::ScalarField::zero(); domain_size];
+        let mut bytes: Vec<Vec<G::ScalarField>> =
+            Vec::with_capacity(ram_lookups.n_range_check_bytes());
+        for k in 0..n_range_checks {
+            let mut value_bytes: [Vec<G::ScalarField>; ram::RANGE_CHECK_BYTES] =
+                array::from_fn(|_| Vec::with_capacity(domain_size));
+            for value in eval_rows(ram_lookups.range_check(k)) {
+                // A value out of range is an access reading an entry before
+                // it is written
+                let value = ram::to_u64(value).ok_or(ProverError::InconsistentRamAccesses)?;
+                for (i, column) in value_bytes.iter_mut().enumerate() {
+                    let byte = (value >> (8 * i)) & 0xff;
+                    multiplicities[byte as usize] += G::ScalarField::one();
+                    column.push(G::ScalarField::from(byte));
+                }
+            }
+            bytes.extend(value_bytes);
+        }
+
+        let eval_col = |evals: Vec<G::ScalarField>| {
+            Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(evals, domain.d1)
+                .interpolate()
+        };
+        RangeCheckColumns {
+            bytes: bytes.into_par_iter().map(eval_col).collect(),
+            multiplicities: (n_range_checks > 0).then(|| eval_col(multiplicities)),
+        }
+    };
+
+    let range_check_commitments: RangeCheckColumns<PolyComm<G>> = {
+        let comm = |poly: &DensePolynomial<G::ScalarField>| {
+            srs.commit_custom(
+                poly,
+                num_chunks,
+                &PolyComm::new(vec![G::ScalarField::one()]),
+            )
+            .unwrap()
+            .commitment
+        };
+        RangeCheckColumns {
+            bytes: range_check_polys.bytes.par_iter().map(comm).collect(),
+            multiplicities: range_check_polys.multiplicities.as_ref().map(comm),
+        }
+    };
+
+    let range_check_evaluations_d8: RangeCheckColumns<
+        Evaluations<G::ScalarField, D<G::ScalarField>>,
+    > = {
+        let eval_d8 =
+            |poly: &DensePolynomial<G::ScalarField>| poly.evaluate_over_domain_by_ref(domain.d8);
+        RangeCheckColumns {
+            bytes: range_check_polys.bytes.par_iter().map(eval_d8).collect(),
+            multiplicities: range_check_polys.multiplicities.as_ref().map(eval_d8),
+        }
+    };
+
+    // The table of bytes is fixed, and only used by the range checks
+    let byte_table_d8 = (n_range_checks > 0).then(|| {
+        Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
+            ram::byte_table(domain.d1.size as usize),
+            domain.d1,
+        )
+        .interpolate()
+        .evaluate_over_domain(domain.d8)
+    });
+
+    for comm in range_check_commitments
+        .bytes
+        .iter()
+        .chain(range_check_commitments.multiplicities.iter())
+    {
+        absorb_commitment(&mut fq_sponge, comm)
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Round 3: Creating and committing to the columns of the memory checking
+    // argument
+    ////////////////////////////////////////////////////////////////////////////
+
+    // Lookup challenges
+    let beta: G::ScalarField = fq_sponge.challenge();
+    let joint_combiner: G::ScalarField = fq_sponge.challenge();
+
+    let boundary_sum = ram::boundary_sum(&public_inputs, beta, joint_combiner)
+        .ok_or(ProverError::InconsistentRamAccesses)?;
+
+    debug!("Prover: computing the columns of the memory checking argument");
+    let ram_polys: RamColumns<DensePolynomial<G::ScalarField>> = {
+        let column_env: ColumnEnvironment<
+            '_,
+            G::ScalarField,
+            N_SCRATCH,
+            N_SCRATCH_INVERSE,
+            N_SELECTORS,
+        > = ColumnEnvironment {
+            constants: constants.clone(),
+            challenges: BerkeleyChallenges {
+                alpha: G::ScalarField::zero(),
+                beta,
+                gamma: G::ScalarField::zero(),
+                joint_combiner,
+            },
+            witness: &evaluations_d8,
+            range_check: Some(&range_check_evaluations_d8),
+            byte_table: byte_table_d8.as_ref(),
+            ram: None,
+            l0_1: l0_1(domain.d1),
+            domain,
+        };
+        let domain_size = domain.d1.size as usize;
+        // Evaluate an expression on the rows of the execution trace, using the
+        // evaluations on a larger domain.
+        let eval_rows = |expr: &E<G::ScalarField>| {
+            let evals = expr.evaluations(&column_env).evals;
+            let step = evals.len() / domain_size;
+            evals.into_iter().step_by(step).collect::<Vec<_>>()
+        };
+        let lookup_terms: Vec<Vec<G::ScalarField>> = (0..ram_lookups.len())
+            .into_par_iter()
+            .map(|k| {
+                let mut denominators = eval_rows(&ram_lookups.denominator(k));
+                ark_ff::batch_inversion(&mut denominators);
+                eval_rows(&ram_lookups.numerator(k))
+                    .into_iter()
+                    .zip(denominators)
+                    .map(|(numerator, inv_denominator)| numerator * inv_denominator)
+                    .collect()
+            })
+            .collect();
+
+        // The running sum of the lookup terms, starting from zero. It must be
+        // back to zero after the last row.
+        let boundary_term = boundary_sum / G::ScalarField::from(domain_size as u64);
+        let mut aggregation = Vec::with_capacity(domain_size);
+        let mut acc = G::ScalarField::zero();
+        for i in 0..domain_size {
+            aggregation.push(acc);
+            acc += lookup_terms.iter().map(|h| h[i]).sum::<G::ScalarField>() - boundary_term;
+        }
+        if !acc.is_zero() {
+            return Err(ProverError::InconsistentRamAccesses);
+        }
+
+        let eval_col = |evals: Vec<G::ScalarField>| {
+            Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(evals, domain.d1)
+                .interpolate()
+        };
+        RamColumns {
+            lookup_terms: lookup_terms.into_par_iter().map(eval_col).collect(),
+            aggregation: eval_col(aggregation),
+        }
+    };
+
+    let ram_commitments: RamColumns<PolyComm<G>> = {
+        let comm = |poly: &DensePolynomial<G::ScalarField>| {
+            srs.commit_custom(
+                poly,
+                num_chunks,
+                &PolyComm::new(vec![G::ScalarField::one()]),
+            )
+            .unwrap()
+            .commitment
+        };
+        RamColumns {
+            lookup_terms: ram_polys.lookup_terms.par_iter().map(comm).collect(),
+            aggregation: comm(&ram_polys.aggregation),
+        }
+    };
+
+    let ram_evaluations_d8: RamColumns<Evaluations<G::ScalarField, D<G::ScalarField>>> = {
+        let eval_d8 =
+            |poly: &DensePolynomial<G::ScalarField>| poly.evaluate_over_domain_by_ref(domain.d8);
+        RamColumns {
+            lookup_terms: ram_polys.lookup_terms.par_iter().map(eval_d8).collect(),
+            aggregation: eval_d8(&ram_polys.aggregation),
+        }
+    };
+
+    for comm in ram_commitments.lookup_terms.iter() {
+        absorb_commitment(&mut fq_sponge, comm)
+    }
+    absorb_commitment(&mut fq_sponge, &ram_commitments.aggregation);
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Round 4: Creating and committing to the quotient polynomial
+    ////////////////////////////////////////////////////////////////////////////
 
     // Constraints combiner
     let alpha: G::ScalarField = fq_sponge.challenge();
 
-    let zk_rows = 0;
-    let column_env: ColumnEnvironment<'_, G::ScalarField> = {
+    let column_env: ColumnEnvironment<
+        '_,
+        G::ScalarField,
+        N_SCRATCH,
+        N_SCRATCH_INVERSE,
+        N_SELECTORS,
+    > = {
         // FIXME: use a proper Challenge structure
         let challenges = BerkeleyChallenges {
             alpha,
+            beta,
             // No permutation argument for the moment
-            beta: G::ScalarField::zero(),
             gamma: G::ScalarField::zero(),
-            // No lookup for the moment
-            joint_combiner: G::ScalarField::zero(),
+            joint_combiner,
         };
         ColumnEnvironment {
-            constants: Constants {
-                endo_coefficient: *endo_r,
-                mds: &G::sponge_params().mds,
-                zk_rows,
-            },
+            constants,
             challenges,
             witness: &evaluations_d8,
+            range_check: Some(&range_check_evaluations_d8),
+            byte_table: byte_table_d8.as_ref(),
+            ram: Some(&ram_evaluations_d8),
             l0_1: l0_1(domain.d1),
             domain,
         }
@@ -249,8 +522,13 @@ where
     // ```
     let quotient_poly: DensePolynomial<G::ScalarField> = {
         // Compute ∑ α^i constraint_i as an expression
+        let constraints: Vec<E<G::ScalarField>> = constraints
+            .iter()
+            .cloned()
+            .chain(ram_lookups.constraints(&public_inputs, boundary_sum, domain.d1))
+            .collect();
         let combined_expr =
-            E::combine_constraints(0..(constraints.len() as u32), (constraints).to_vec());
+            E::combine_constraints(0..(constraints.len() as u32), constraints.to_vec());
 
         // We want to compute the quotient polynomial, i.e.
         // t(X) = (∑ α^i constraint_i(X)) / Z_H(X).
@@ -301,7 +579,7 @@ where
     absorb_commitment(&mut fq_sponge, &quotient_commitment.commitment);
 
     ////////////////////////////////////////////////////////////////////////////
-    // Round 3: Evaluations at ζ and ζω
+    // Round 5: Evaluations at ζ and ζω
     ////////////////////////////////////////////////////////////////////////////
 
     debug!("Prover: evaluating all columns, including the selectors, at ζ and ζω");
@@ -331,12 +609,41 @@ where
         }
     };
     // All evaluations at ζ
-    let zeta_evaluations: WitnessColumns<G::ScalarField, [G::ScalarField; N_MIPS_SEL_COLS]> =
-        evals(&zeta);
+    let zeta_evaluations: WitnessColumns<
+        G::ScalarField,
+        [G::ScalarField; N_SELECTORS],
+        N_SCRATCH,
+        N_SCRATCH_INVERSE,
+    > = evals(&zeta);
 
     // All evaluations at ζω
-    let zeta_omega_evaluations: WitnessColumns<G::ScalarField, [G::ScalarField; N_MIPS_SEL_COLS]> =
-        evals(&zeta_omega);
+    let zeta_omega_evaluations: WitnessColumns<
+        G::ScalarField,
+        [G::ScalarField; N_SELECTORS],
+        N_SCRATCH,
+        N_SCRATCH_INVERSE,
+    > = evals(&zeta_omega);
+
+    let range_check_evals = |point| {
+        let eval = |poly: &DensePolynomial<G::ScalarField>| poly.evaluate(point);
+        RangeCheckColumns {
+            bytes: range_check_polys.bytes.par_iter().map(eval).collect(),
+            multiplicities: range_check_polys.multiplicities.as_ref().map(eval),
+        }
+    };
+    let range_check_zeta_evaluations: RangeCheckColumns<G::ScalarField> = range_check_evals(&zeta);
+    let range_check_zeta_omega_evaluations: RangeCheckColumns<G::ScalarField> =
+        range_check_evals(&zeta_omega);
+
+    let ram_evals = |point| {
+        let eval = |poly: &DensePolynomial<G::ScalarField>| poly.evaluate(point);
+        RamColumns {
+            lookup_terms: ram_polys.lookup_terms.par_iter().map(eval).collect(),
+            aggregation: eval(&ram_polys.aggregation),
+        }
+    };
+    let ram_zeta_evaluations: RamColumns<G::ScalarField> = ram_evals(&zeta);
+    let ram_zeta_omega_evaluations: RamColumns<G::ScalarField> = ram_evals(&zeta_omega);
 
     let chunked_quotient = quotient_poly
         .to_chunked_polynomial(DEGREE_QUOTIENT_POLYNOMIAL as usize, domain.d1.size as usize);
@@ -387,6 +694,30 @@ where
         fr_sponge.absorb(zeta_eval);
         fr_sponge.absorb(zeta_omega_eval);
     }
+    for (zeta_eval, zeta_omega_eval) in range_check_zeta_evaluations
+        .bytes
+        .iter()
+        .chain(range_check_zeta_evaluations.multiplicities.iter())
+        .zip(
+            range_check_zeta_omega_evaluations
+                .bytes
+                .iter()
+                .chain(range_check_zeta_omega_evaluations.multiplicities.iter()),
+        )
+    {
+        fr_sponge.absorb(zeta_eval);
+        fr_sponge.absorb(zeta_omega_eval);
+    }
+    for (zeta_eval, zeta_omega_eval) in ram_zeta_evaluations
+        .lookup_terms
+        .iter()
+        .zip(ram_zeta_omega_evaluations.lookup_terms.iter())
+    {
+        fr_sponge.absorb(zeta_eval);
+        fr_sponge.absorb(zeta_omega_eval);
+    }
+    fr_sponge.absorb(&ram_zeta_evaluations.aggregation);
+    fr_sponge.absorb(&ram_zeta_omega_evaluations.aggregation);
     for (quotient_zeta_eval, quotient_zeta_omega_eval) in quotient_evaluations
         .zeta
         .iter()
@@ -396,7 +727,7 @@ where
         fr_sponge.absorb(quotient_zeta_omega_eval);
     }
     ////////////////////////////////////////////////////////////////////////////
-    // Round 4: Opening proof w/o linearization polynomial
+    // Round 6: Opening proof w/o linearization polynomial
     ////////////////////////////////////////////////////////////////////////////
 
     let mut polynomials: Vec<_> = polys.scratch.into_iter().collect();
@@ -404,6 +735,8 @@ where
     polynomials.push(polys.instruction_counter);
     polynomials.push(polys.error);
     polynomials.extend(polys.selector);
+    polynomials.extend(range_check_polys.into_vec());
+    polynomials.extend(ram_polys.into_vec());
 
     // Preparing the polynomials for the opening proof
     let mut polynomials: Vec<_> = polynomials
@@ -446,9 +779,16 @@ where
     );
 
     Ok(Proof {
+        public_inputs,
         commitments,
         zeta_evaluations,
         zeta_omega_evaluations,
+        range_check_commitments,
+        range_check_zeta_evaluations,
+        range_check_zeta_omega_evaluations,
+        ram_commitments,
+        ram_zeta_evaluations,
+        ram_zeta_omega_evaluations,
         quotient_commitment: quotient_commitment.commitment,
         quotient_evaluations,
         opening_proof,
@@ -0,0 +1,518 @@
+//! Offline memory checking for the registers and the memory of the VM.
+//!
+//! Each access of an instruction to an address `a` writes the entry `(a, t, v)`
+//! in a RAM table, where `v` is the content of the address before the access
+//! and `t` the timestamp of the previous access, and reads the entry
+//! `(a, t', v')`, where `v'` is the content after the access and `t'` the
+//! current timestamp (see `access_register_if` and `access_memory` in the
+//! interpreters). Over a chunk, the entry read by an access is the one written
+//! by the next access to the same address. The multiset of the entries written
+//! minus the multiset of the entries read is therefore made of the entries
+//! before the first access to each address, the initial entries, and of the
+//! entries after the last one, the final entries. Both are part of the public
+//! inputs, see [PublicInputs].
+//!
+//! The equality of the multisets is checked using the logarithmic derivative
+//! method (logup), as in [kimchi_msm::logup]. For random `β` and `j`, we check
+//! ```text
+//! ∑_{written e} 1 / (β + f(e)) - ∑_{read e} 1 / (β + f(e))
+//!   = ∑_{initial e} 1 / (β + f(e)) - ∑_{final e} 1 / (β + f(e))
+//! ```
+//! where `f(e) = table_id + j a + j^2 t + j^3 v` for `e = (a, t, v)`.
+//! The k-th lookup of each row is kept in a column `h_k`, constrained by
+//! ```text
+//! h_k(X) (β + f_k(X)) = m_k(X)
+//! ```
+//! where `m_k` is `1` for a write, `-1` for a read, and `0` if the instruction
+//! has less than `k` lookups. The running sum `φ` of the lookup terms is
+//! constrained by
+//! ```text
+//! φ(ωX) - φ(X) - ∑_k h_k(X) + S / N = 0
+//! ```
+//! where `S` is the right-hand side above and `N` the size of the domain. As
+//! the domain is cyclic, the constraint holds on all rows only if the sum of
+//! all the lookup terms is `S`.
+//!
+//! The verifier checks that the initial and final entries of the registers
+//! match the states of the VM given in the public inputs, and
+//! [crate::pickles::verifier::verify_chain] checks that the final entries of
+//! a chunk are the initial entries of the next chunk accessing the same
+//! addresses. When the chunks start from the beginning of the execution of a
+//! program, it also checks that the first entry of each address is the one of
+//! the program, with a timestamp of zero.
+//!
+//! An entry must not be read before it is written, i.e. the time `t' - t`
+//! elapsed since the previous access to an address must be nonnegative. The
+//! interpreters give it to `range_check64`, which is checked by decomposing it
+//! in bytes `b_i`, stored in dedicated columns committed to before the lookup
+//! challenges are sampled:
+//! ```text
+//! t' - t - ∑_i 2^{8i} b_i = 0
+//! ```
+//! The bytes are looked up in a table of bytes, in the same running sum as
+//! the RAM tables: each byte adds the term `-1 / (β + f(b_i))`, and the table
+//! adds the term `m(X) / (β + f(T(X)))`, where `T` contains the bytes on its
+//! first 256 rows and zero on the other ones, and `m` is the number of lookups
+//! of each byte. The table is fixed, and evaluated by the verifier itself.
+//!
+//! The instruction counter `c` is bound to the execution trace and to the
+//! states of the VM by the constraints
+//! ```text
+//! L_0(X) (c(X) - c_initial) = 0
+//! L_{N-1}(X) (c(X) + MAX_ACC - c_final) = 0
+//! (c(ωX) - c(X) - MAX_ACC) (L_{N-1}(X) - N ω) = 0
+//! ```
+//! where `L_i` vanishes on all the rows but the i-th one, on which it is equal
+//! to `N ω^{-i}`, and `MAX_ACC` is the increase of the counter after each
+//! instruction. The counter therefore increases by `MAX_ACC` from a row to the
+//! next one, and the timestamps of the accesses of different rows cannot
+//! overlap.
+
+use std::collections::BTreeMap;
+
+use ark_ff::{Field, One, PrimeField, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+use kimchi::circuits::{
+    berkeley_columns::BerkeleyChallengeTerm,
+    expr::{ConstantExpr, ConstantTerm::Literal, Expr, ExprInner, Operations, RowOffset},
+    gate::CurrOrNext,
+};
+use kimchi_msm::{columns::Column, LookupTableID};
+
+use super::proof::{ProgramState, PublicInputs, RamEntry};
+use crate::{lookups::LookupTableIDs, ramlookup::RAMLookup, E};
+
+/// The number of bytes of the values given to `range_check64`
+pub const RANGE_CHECK_BYTES: usize = 8;
+
+/// The number of rows of the table of bytes
+pub const BYTE_TABLE_SIZE: usize = 256;
+
+/// The column containing the terms of the k-th lookup of every row. The RAM
+/// lookups come first, followed by the lookups of the bytes of the range
+/// checks and by the table of bytes. The first index of the column, usually
+/// the table ID, is not relevant as a column gathers lookups into several
+/// tables.
+pub fn lookup_term_column(k: usize) -> Column {
+    Column::LookupPartialSum((0, k))
+}
+
+/// The column containing the running sum of the lookup terms.
+pub const AGGREGATION_COLUMN: Column = Column::LookupAggregation;
+
+/// The column containing the i-th byte of the range checked values, the
+/// bytes of the k-th range check of every instruction being the ones
+/// `8k..8k + 8`, in little-endian order.
+pub fn range_check_byte_column(i: usize) -> Column {
+    Column::LookupRuntimeTable((LookupTableIDs::ByteLookup.to_u32(), i))
+}
+
+/// The column containing the number of lookups of each row of the table of
+/// bytes.
+pub fn byte_multiplicity_column() -> Column {
+    Column::LookupMultiplicity((LookupTableIDs::ByteLookup.to_u32(), 0))
+}
+
+/// The fixed column containing the table of bytes. It is not committed to, see
+/// [byte_table_evaluation].
+pub fn byte_table_column() -> Column {
+    Column::LookupFixedTable(LookupTableIDs::ByteLookup.to_u32())
+}
+
+/// The k-th lookup of every instruction, where the expressions of the
+/// instructions are multiplied by their selector and summed. As exactly one
+/// selector is activated on each row, the expressions are equal to the ones of
+/// the instruction executed on the row.
+#[derive(Clone, Debug)]
+struct CombinedLookup<F: Field> {
+    numerator: E<F>,
+    table_id: E<F>,
+    value: Vec<E<F>>,
+}
+
+/// The lookups of the instructions into the RAM tables and their range
+/// checks, combined using the selectors of the instructions.
+#[derive(Clone, Debug)]
+pub struct RamLookups<F: Field> {
+    lookups: Vec<CombinedLookup<F>>,
+    /// The k-th value range checked by every instruction, combined as the
+    /// lookups
+    range_checks: Vec<E<F>>,
+    /// The column of the instruction counter, from which the timestamps of the
+    /// accesses are computed
+    instruction_counter: Column,
+    /// The increase of the instruction counter after each instruction, i.e.
+    /// the maximum number of accesses made by an instruction
+    max_accesses: u64,
+}
+
+impl<F: PrimeField> RamLookups<F> {
+    /// The lookups of an interpreter whose instruction counter is stored in
+    /// the column `instruction_counter` and increases by `max_accesses` after
+    /// each instruction. No instruction is added yet.
+    pub fn new(instruction_counter: Column, max_accesses: u64) -> Self {
+        RamLookups {
+            lookups: vec![],
+            range_checks: vec![],
+            instruction_counter,
+            max_accesses,
+        }
+    }
+
+    /// Add the lookups and the range checks of the instruction activated by
+    /// `selector`. The lookups into tables other than the RAM tables are
+    /// ignored.
+    pub fn add_instruction(
+        &mut self,
+        selector: &E<F>,
+        lookups: &[RAMLookup<E<F>, LookupTableIDs>],
+        range_checks: &[E<F>],
+    ) {
+        let ram_lookups = lookups.iter().filter(|lookup| lookup.table_id.is_ram());
+        for (k, lookup) in ram_lookups.enumerate() {
+            if k == self.lookups.len() {
+                self.lookups.push(CombinedLookup {
+                    numerator: E::zero(),
+                    table_id: E::zero(),
+                    value: vec![],
+                });
+            }
+            let combined = &mut self.lookups[k];
+            combined.numerator += selector.clone() * lookup.numerator();
+            combined.table_id += selector.clone() * constant(F::from(lookup.table_id.to_u32()));
+            if combined.value.len() < lookup.value.len() {
+                combined.value.resize(lookup.value.len(), E::zero());
+            }
+            for (combined_value, value) in combined.value.iter_mut().zip(lookup.value.iter()) {
+                *combined_value += selector.clone() * value.clone();
+            }
+        }
+        for (k, value) in range_checks.iter().enumerate() {
+            if k == self.range_checks.len() {
+                self.range_checks.push(E::zero());
+            }
+            self.range_checks[k] += selector.clone() * value.clone();
+        }
+    }
+
+    /// The number of lookup terms per row, i.e. the maximum number of lookups
+    /// into the RAM tables made by an instruction, plus the lookups of the
+    /// bytes of its range checks and the table of bytes.
+    pub fn len(&self) -> usize {
+        let n_bytes = self.n_range_check_bytes();
+        self.lookups.len() + n_bytes + usize::from(n_bytes > 0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The maximum number of values range checked by an instruction.
+    pub fn n_range_checks(&self) -> usize {
+        self.range_checks.len()
+    }
+
+    /// The number of columns containing the bytes of the range checked values.
+    pub fn n_range_check_bytes(&self) -> usize {
+        RANGE_CHECK_BYTES * self.n_range_checks()
+    }
+
+    /// The k-th value range checked on each row.
+    pub(crate) fn range_check(&self, k: usize) -> &E<F> {
+        &self.range_checks[k]
+    }
+
+    /// The numerator of the k-th lookup term, i.e. its multiplicity.
+    pub(crate) fn numerator(&self, k: usize) -> E<F> {
+        let n_ram_lookups = self.lookups.len();
+        if k < n_ram_lookups {
+            self.lookups[k].numerator.clone()
+        } else if k < n_ram_lookups + self.n_range_check_bytes() {
+            // The bytes are read from the table
+            constant(-F::one())
+        } else {
+            Expr::cell(byte_multiplicity_column(), CurrOrNext::Curr)
+        }
+    }
+
+    /// The denominator of the k-th lookup term, i.e.
+    /// `β + table_id + j value_0 + j^2 value_1 + ...`.
+    pub(crate) fn denominator(&self, k: usize) -> E<F> {
+        let beta = challenge(BerkeleyChallengeTerm::Beta);
+        let joint_combiner = challenge(BerkeleyChallengeTerm::JointCombiner);
+        let n_ram_lookups = self.lookups.len();
+        let (table_id, value) = if k < n_ram_lookups {
+            let CombinedLookup {
+                table_id, value, ..
+            } = &self.lookups[k];
+            (table_id.clone(), value.clone())
+        } else {
+            let byte_table_id = constant(F::from(LookupTableIDs::ByteLookup.to_u32()));
+            let column = if k < n_ram_lookups + self.n_range_check_bytes() {
+                range_check_byte_column(k - n_ram_lookups)
+            } else {
+                byte_table_column()
+            };
+            (byte_table_id, vec![Expr::cell(column, CurrOrNext::Curr)])
+        };
+        let combined_value = value
+            .iter()
+            .rev()
+            .fold(E::zero(), |acc, v| acc * joint_combiner.clone() + v.clone())
+            * joint_combiner;
+        beta + table_id + combined_value
+    }
+
+    /// The constraints of the argument for the domain `domain`, given the
+    /// value `boundary_sum` computed by [boundary_sum] from `public_inputs`.
+    ///
+    /// On top of the lookups, the range checked values are constrained to be
+    /// made of their bytes, and the instruction counter is constrained to go
+    /// from the one of the initial state to the one of the final state,
+    /// increasing by `max_accesses` on each row.
+    pub(crate) fn constraints(
+        &self,
+        public_inputs: &PublicInputs<F>,
+        boundary_sum: F,
+        domain: D<F>,
+    ) -> Vec<E<F>> {
+        let curr = |col| Expr::cell(col, CurrOrNext::Curr);
+        let next = |col| Expr::cell(col, CurrOrNext::Next);
+        let mut constraints: Vec<E<F>> = (0..self.len())
+            .map(|k| curr(lookup_term_column(k)) * self.denominator(k) - self.numerator(k))
+            .collect();
+        let lookup_terms_sum = (0..self.len())
+            .map(|k| curr(lookup_term_column(k)))
+            .fold(E::zero(), |acc, h| acc + h);
+        let boundary_term = boundary_sum / F::from(domain.size);
+        constraints.push(
+            next(AGGREGATION_COLUMN) - curr(AGGREGATION_COLUMN) - lookup_terms_sum
+                + constant(boundary_term),
+        );
+        for (k, value) in self.range_checks.iter().enumerate() {
+            let bytes = (0..RANGE_CHECK_BYTES).rev().fold(E::zero(), |acc, i| {
+                acc * constant(F::from(1u64 << 8))
+                    + curr(range_check_byte_column(RANGE_CHECK_BYTES * k + i))
+            });
+            constraints.push(value.clone() - bytes);
+        }
+        let instruction_counter = curr(self.instruction_counter);
+        let max_accesses = constant(F::from(self.max_accesses));
+        constraints.push(
+            row_selector(0)
+                * (instruction_counter.clone()
+                    - constant(public_inputs.initial_state.instruction_counter)),
+        );
+        constraints.push(
+            row_selector(-1)
+                * (instruction_counter.clone() + max_accesses.clone()
+                    - constant(public_inputs.final_state.instruction_counter)),
+        );
+        // The value of L_{N-1} on the last row, where the instruction counter
+        // of the next row is the one of the first row
+        let last_row_value = F::from(domain.size) * domain.group_gen;
+        constraints.push(
+            (next(self.instruction_counter) - instruction_counter - max_accesses)
+                * (row_selector(-1) - constant(last_row_value)),
+        );
+        constraints
+    }
+}
+
+fn constant<F: Field>(x: F) -> E<F> {
+    E::constant(Operations::from(Literal(x)))
+}
+
+/// The polynomial vanishing on all the rows but the row `offset`, counted
+/// from the end of the domain when negative.
+fn row_selector<F: Field>(offset: i32) -> E<F> {
+    E::Atom(ExprInner::UnnormalizedLagrangeBasis(RowOffset {
+        zk_rows: false,
+        offset,
+    }))
+}
+
+fn challenge<F: Field>(challenge: BerkeleyChallengeTerm) -> E<F> {
+    E::Atom(ExprInner::Constant(ConstantExpr::from(challenge)))
+}
+
+/// The value of `x` as an integer, or `None` if it does not fit in 64 bits.
+pub(crate) fn to_u64<F: PrimeField>(x: F) -> Option<u64> {
+    let bigint = x.into_bigint();
+    let (low, high) = bigint.as_ref().split_first()?;
+    high.iter().all(|limb| *limb == 0).then_some(*low)
+}
+
+/// The table of bytes on the rows of a domain of size `domain_size`, i.e. `i`
+/// on the row `i < 256` and zero on the other rows.
+pub(crate) fn byte_table<F: PrimeField>(domain_size: usize) -> Vec<F> {
+    (0..domain_size)
+        .map(|i| {
+            if i < BYTE_TABLE_SIZE {
+                F::from(i as u64)
+            } else {
+                F::zero()
+            }
+        })
+        .collect()
+}
+
+/// The evaluation at `x` of the polynomial interpolating the table of bytes on
+/// `domain`, i.e. `∑_{i < 256} i L_i(x)` for the Lagrange basis `L_i` of
+/// `domain`.
+pub(crate) fn byte_table_evaluation<F: PrimeField>(domain: D<F>, x: F) -> F {
+    domain
+        .evaluate_all_lagrange_coefficients(x)
+        .into_iter()
+        .take(BYTE_TABLE_SIZE)
+        .enumerate()
+        .map(|(i, lagrange)| F::from(i as u64) * lagrange)
+        .sum()
+}
+
+/// Returns `∑_{initial e} 1 / (β + f(e)) - ∑_{final e} 1 / (β + f(e))`, i.e. the
+/// expected sum of all the lookup terms of the chunk, or `None` if one of the
+/// denominators is zero.
+pub(crate) fn boundary_sum<F: PrimeField>(
+    public_inputs: &PublicInputs<F>,
+    beta: F,
+    joint_combiner: F,
+) -> Option<F> {
+    let term = |entry: &RamEntry<F>| {
+        let combined_value = [entry.value, entry.timestamp, entry.address]
+            .iter()
+            .fold(F::zero(), |acc, v| acc * joint_combiner + v)
+            * joint_combiner;
+        (beta + F::from(entry.table_id) + combined_value).inverse()
+    };
+    let initial_sum = public_inputs
+        .initial_ram
+        .iter()
+        .map(term)
+        .sum::<Option<F>>()?;
+    let final_sum = public_inputs
+        .final_ram
+        .iter()
+        .map(term)
+        .sum::<Option<F>>()?;
+    Some(initial_sum - final_sum)
+}
+
+/// Returns the entries of `entries` belonging to `table_id`, indexed by their
+/// address, or `None` if an address is given twice or if an entry does not
+/// belong to a RAM table.
+fn entries_by_address<F: PrimeField>(
+    entries: &[RamEntry<F>],
+    table_id: LookupTableIDs,
+) -> Option<BTreeMap<F, &RamEntry<F>>> {
+    let mut by_address = BTreeMap::new();
+    for entry in entries {
+        if !(entry.table_id == LookupTableIDs::MemoryLookup.to_u32()
+            || entry.table_id == LookupTableIDs::RegisterLookup.to_u32())
+        {
+            return None;
+        }
+        if entry.table_id == table_id.to_u32() && by_address.insert(entry.address, entry).is_some()
+        {
+            return None;
+        }
+    }
+    Some(by_address)
+}
+
+/// Check that the initial and final entries of the public inputs are
+/// well-formed and consistent with the states of the VM:
+/// - each address has at most one initial and one final entry, and an address
+///   has an initial entry if and only if it has a final one;
+/// - the entries of the registers contain the values of the registers in the
+///   initial and final states;
+/// - the registers that are not accessed have the same value in both states.
+pub(crate) fn check_boundary<F: PrimeField>(public_inputs: &PublicInputs<F>) -> bool {
+    let PublicInputs {
+        initial_state,
+        final_state,
+        initial_ram,
+        final_ram,
+    } = public_inputs;
+    for table_id in [LookupTableIDs::MemoryLookup, LookupTableIDs::RegisterLookup] {
+        let (Some(initial), Some(final_)) = (
+            entries_by_address(initial_ram, table_id),
+            entries_by_address(final_ram, table_id),
+        ) else {
+            return false;
+        };
+        if !initial.keys().eq(final_.keys()) {
+            return false;
+        }
+        if table_id == LookupTableIDs::RegisterLookup {
+            let n_registers = initial_state.registers.len();
+            let is_register = |idx: &F| (0..n_registers).any(|i| F::from(i as u64) == *idx);
+            if final_state.registers.len() != n_registers || !initial.keys().all(is_register) {
+                return false;
+            }
+            let registers_are_consistent = (0..n_registers).all(|i| {
+                match (
+                    initial.get(&F::from(i as u64)),
+                    final_.get(&F::from(i as u64)),
+                ) {
+                    (Some(initial_entry), Some(final_entry)) => {
+                        initial_entry.value == initial_state.registers[i]
+                            && final_entry.value == final_state.registers[i]
+                    }
+                    _ => initial_state.registers[i] == final_state.registers[i],
+                }
+            });
+            if !registers_are_consistent {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Check that the final entries of each chunk are the initial entries of the
+/// next chunk accessing the same addresses. The public inputs must be given in
+/// the order of the execution.
+///
+/// If the chunks start from the beginning of the execution of `program`, the
+/// addresses that are not accessed by a previous chunk must also have never
+/// been accessed, i.e. their initial entry must have a zero timestamp, and the
+/// memory cells must contain the bytes of the program. The values of the
+/// registers are already bound to the initial state by [check_boundary].
+pub(crate) fn check_chained_boundaries<'a, F: PrimeField + 'a>(
+    public_inputs: impl IntoIterator<Item = &'a PublicInputs<F>>,
+    program: Option<&ProgramState<F>>,
+) -> bool {
+    // The last entry of each address accessed so far
+    let mut last_entries: BTreeMap<(u32, F), &RamEntry<F>> = BTreeMap::new();
+    let is_initial_entry = |entry: &RamEntry<F>| {
+        let Some(program) = program else {
+            return true;
+        };
+        if !entry.timestamp.is_zero() {
+            return false;
+        }
+        if entry.table_id != LookupTableIDs::MemoryLookup.to_u32() {
+            return true;
+        }
+        match to_u64(entry.address).and_then(|address| u32::try_from(address).ok()) {
+            Some(address) => entry.value == F::from(program.memory_byte(address)),
+            None => false,
+        }
+    };
+    for public_inputs in public_inputs {
+        let chained = public_inputs.initial_ram.iter().all(|entry| {
+            match last_entries.get(&(entry.table_id, entry.address)) {
+                Some(last_entry) => *last_entry == entry,
+                None => is_initial_entry(entry),
+            }
+        });
+        if !chained {
+            return false;
+        }
+        for entry in public_inputs.final_ram.iter() {
+            last_entries.insert((entry.table_id, entry.address), entry);
+        }
+    }
+    true
+}
@@ -0,0 +1,255 @@
+use clap::arg;
+use kimchi::circuits::domains::EvaluationDomains;
+use kimchi_msm::expr::E;
+use log::{debug, warn};
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use o1vm::{
+    cannon::{step_frequency_parser, StepFrequency},
+    elf_loader,
+    interpreters::riscv32im::{
+        constraints as riscv_constraints, interpreter::Instruction, witness as riscv_witness,
+        INSTRUCTION_SET_SIZE, PAGE_SIZE, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE,
+    },
+    pickles::{
+        constraints::build_constraints,
+        proof::{Proof, ProofInputs, PublicInputs},
+        proof_file::{self, Interpreter},
+        prover,
+        ram::RamLookups,
+        verifier, DOMAIN_SIZE,
+    },
+    tracer::{Breakpoint, TraceFormat, Tracer},
+};
+use poly_commitment::{ipa::SRS, SRS as _};
+use std::{path::PathBuf, process::ExitCode, str::FromStr, time::Instant};
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+fn main_cli() -> clap::Command {
+    clap::Command::new("o1vm-riscv32im")
+        .version("0.1")
+        .about("o1vm - proving the execution of RISC-V 32IM programs")
+        .arg(
+            arg!(--elf <FILE> "RISC-V 32IM ELF binary to execute")
+                .required(true)
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+        .arg(arg!(--trace <FILE> "write the trace of the executed instructions to FILE (- for the standard output)"))
+        .arg(
+            arg!(--"trace-format" <FORMAT> "format of the trace: text or jsonl")
+                .default_value("text")
+                .value_parser(TraceFormat::from_str),
+        )
+        .arg(
+            arg!(--break <BREAKPOINT> "interrupt the execution in the debugger before the given step, or on the entry of the given symbol")
+                .action(clap::ArgAction::Append)
+                .value_parser(Breakpoint::from_str),
+        )
+        .arg(
+            arg!(--"proof-at" <FREQ> "write the proof of the chunks containing the matching steps")
+                .default_value("never")
+                .value_parser(step_frequency_parser),
+        )
+        .arg(
+            arg!(--"proof-fmt" <FORMAT> "format of the proof files, %d being replaced by the step")
+                .default_value("proof-%d.json"),
+        )
+}
+
+/// Add the row of the instruction just executed by the VM to the execution
+/// trace. `instruction_counter` is the instruction counter before its
+/// execution, from which the timestamps of its accesses are computed.
+fn push_row(
+    proof_inputs: &mut ProofInputs<Vesta, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE>,
+    riscv_wit_env: &riscv_witness::Env<Fp>,
+    instruction_counter: u64,
+) {
+    // The selectors are stored after the scratch state and the instruction
+    // counter, see [riscv32im::column::Column].
+    proof_inputs.push_row(
+        &riscv_wit_env.scratch_state,
+        &[],
+        Fp::from(instruction_counter),
+        // FIXME: Might be another value
+        Fp::from(0u64),
+        Fp::from((riscv_wit_env.selector - SCRATCH_SIZE - 1) as u64),
+    );
+}
+
+/// Prove the execution trace contained in `proof_inputs`, and verify the
+/// resulting proof (for testing purposes).
+fn prove_and_verify_chunk(
+    domain: EvaluationDomains<Fp>,
+    srs: &SRS<Vesta>,
+    constraints: &[E<Fp>],
+    ram_lookups: &RamLookups<Fp>,
+    proof_inputs: ProofInputs<Vesta, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE>,
+    rng: &mut rand::rngs::ThreadRng,
+) -> Proof<Vesta, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE, INSTRUCTION_SET_SIZE> {
+    let start_iteration = Instant::now();
+    let proof = prover::prove::<
+        Vesta,
+        BaseSponge,
+        ScalarSponge,
+        _,
+        SCRATCH_SIZE,
+        SCRATCH_SIZE_INVERSE,
+        INSTRUCTION_SET_SIZE,
+    >(domain, srs, proof_inputs, constraints, ram_lookups, rng)
+    .unwrap();
+    debug!(
+        "Proof generated in {elapsed} μs",
+        elapsed = start_iteration.elapsed().as_micros()
+    );
+
+    let start_iteration = Instant::now();
+    let verif = verifier::verify::<
+        Vesta,
+        BaseSponge,
+        ScalarSponge,
+        SCRATCH_SIZE,
+        SCRATCH_SIZE_INVERSE,
+        INSTRUCTION_SET_SIZE,
+    >(domain, srs, constraints, ram_lookups, &proof);
+    debug!(
+        "Verification done in {elapsed} μs",
+        elapsed = start_iteration.elapsed().as_micros()
+    );
+    assert!(verif);
+    proof
+}
+
+pub fn main() -> ExitCode {
+    let matches = main_cli().get_matches();
+    let elf_path = matches
+        .get_one::<PathBuf>("elf")
+        .expect("The path to the ELF binary must be given with --elf");
+
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let state = elf_loader::parse_riscv32(elf_path)
+        .unwrap_or_else(|err| panic!("Error parsing ELF file {}: {err}", elf_path.display()));
+
+    let mut rng = rand::thread_rng();
+
+    let domain_fp = EvaluationDomains::<Fp>::create(DOMAIN_SIZE).unwrap();
+    let srs: SRS<Vesta> = {
+        let srs = SRS::create(DOMAIN_SIZE);
+        srs.get_lagrange_basis(domain_fp.d1);
+        srs
+    };
+
+    // Initialize the environments
+    let mut riscv_wit_env = riscv_witness::Env::<Fp>::create(PAGE_SIZE as usize, state);
+    riscv_wit_env.tracer = Tracer::from_options(
+        matches.get_one::<String>("trace").map(String::as_str),
+        *matches.get_one::<TraceFormat>("trace-format").unwrap(),
+        matches
+            .get_many::<Breakpoint>("break")
+            .map(|vals| vals.cloned().collect())
+            .unwrap_or_default(),
+        Some(
+            elf_loader::parse_riscv32_symbols(elf_path)
+                .unwrap_or_else(|err| panic!("Error parsing the symbols of the ELF file: {err}")),
+        ),
+    );
+
+    let proof_at = matches.get_one::<StepFrequency>("proof-at").unwrap();
+    let proof_fmt = matches.get_one::<String>("proof-fmt").unwrap();
+
+    let (constraints, ram_lookups) = build_constraints::<Fp, riscv_constraints::Env<Fp>>();
+
+    let mut curr_proof_inputs: ProofInputs<Vesta, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE> =
+        ProofInputs::new(DOMAIN_SIZE);
+    // First step of the current chunk matching `--proof-at`, if any. The proof
+    // of the chunk is written to disk when it is set.
+    let mut proof_step: Option<u64> = None;
+    // State of the VM before the first instruction of the current chunk
+    let mut chunk_initial_state = riscv_wit_env.vm_state();
+    while !riscv_wit_env.halt {
+        let step = riscv_wit_env.normalized_instruction_counter();
+        if proof_step.is_none() && proof_at.matches(step) {
+            proof_step = Some(step);
+        }
+        // The timestamps of the accesses of the instruction are computed from
+        // the instruction counter before its execution.
+        let instruction_counter = riscv_wit_env.instruction_counter;
+        let _instr: Instruction = riscv_wit_env.step();
+        // The VM may be stopped before executing the instruction, e.g. by the
+        // debugger.
+        if riscv_wit_env.instruction_counter == instruction_counter {
+            break;
+        }
+        push_row(&mut curr_proof_inputs, &riscv_wit_env, instruction_counter);
+
+        if curr_proof_inputs.evaluations.instruction_counter.len() == DOMAIN_SIZE {
+            debug!("Limit of {DOMAIN_SIZE} reached. We make a proof, verify it (for testing) and start with a new chunk");
+            // The final state of this chunk is the initial state of the next one
+            let final_state = riscv_wit_env.vm_state();
+            curr_proof_inputs.public_inputs = PublicInputs::new(
+                std::mem::replace(&mut chunk_initial_state, final_state.clone()),
+                final_state,
+                std::mem::take(&mut riscv_wit_env.ram_lookups),
+            );
+            let proof = prove_and_verify_chunk(
+                domain_fp,
+                &srs,
+                &constraints,
+                &ram_lookups,
+                curr_proof_inputs,
+                &mut rng,
+            );
+            if let Some(step) = proof_step.take() {
+                proof_file::write_proof(
+                    proof_fmt,
+                    step,
+                    Interpreter::Riscv32im,
+                    DOMAIN_SIZE,
+                    &proof,
+                );
+            }
+
+            curr_proof_inputs = ProofInputs::new(DOMAIN_SIZE);
+        }
+    }
+
+    // The last chunk is padded by executing again the instruction that halted
+    // the VM, which leaves the state of the VM unchanged.
+    if !curr_proof_inputs.evaluations.instruction_counter.is_empty() {
+        while curr_proof_inputs.evaluations.instruction_counter.len() < DOMAIN_SIZE {
+            let instruction_counter = riscv_wit_env.instruction_counter;
+            if !riscv_wit_env.step_halted() {
+                warn!(
+                    "The VM stopped before the program halted. The last {} instructions are not proven",
+                    curr_proof_inputs.evaluations.instruction_counter.len()
+                );
+                return ExitCode::SUCCESS;
+            }
+            push_row(&mut curr_proof_inputs, &riscv_wit_env, instruction_counter);
+        }
+        debug!("Program halted. We make a proof for the remaining instructions and verify it");
+        curr_proof_inputs.public_inputs = PublicInputs::new(
+            chunk_initial_state,
+            riscv_wit_env.vm_state(),
+            std::mem::take(&mut riscv_wit_env.ram_lookups),
+        );
+        let proof = prove_and_verify_chunk(
+            domain_fp,
+            &srs,
+            &constraints,
+            &ram_lookups,
+            curr_proof_inputs,
+            &mut rng,
+        );
+        if let Some(step) = proof_step {
+            proof_file::write_proof(proof_fmt, step, Interpreter::Riscv32im, DOMAIN_SIZE, &proof);
+        }
+    }
+
+    ExitCode::SUCCESS
+}
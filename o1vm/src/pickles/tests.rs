@@ -2,15 +2,15 @@ use std::time::Instant;
 
 use super::{
     super::interpreters::mips::column::SCRATCH_SIZE,
-    proof::{ProofInputs, WitnessColumns},
+    constraints::build_constraints,
+    proof::{ProgramState, Proof, ProofInputs, PublicInputs, VmState, WitnessColumns},
     prover::prove,
+    ram::{self, RamLookups},
 };
 use crate::{
     interpreters::mips::{
-        column::SCRATCH_SIZE_INVERSE,
+        column::{N_MIPS_SEL_COLS, SCRATCH_SIZE_INVERSE},
         constraints as mips_constraints,
-        interpreter::{self, InterpreterEnv},
-        Instruction,
     },
     pickles::{verifier::verify, MAXIMUM_DEGREE_CONSTRAINTS, TOTAL_NUMBER_OF_CONSTRAINTS},
 };
@@ -25,29 +25,10 @@ use mina_poseidon::{
 };
 use o1_utils::tests::make_test_rng;
 use poly_commitment::SRS;
-use strum::IntoEnumIterator;
 
 #[test]
 fn test_regression_constraints_with_selectors() {
-    let constraints = {
-        let mut mips_con_env = mips_constraints::Env::<Fp>::default();
-        let mut constraints = Instruction::iter()
-            .flat_map(|instr_typ| instr_typ.into_iter())
-            .fold(vec![], |mut acc, instr| {
-                interpreter::interpret_instruction(&mut mips_con_env, instr);
-                let selector = mips_con_env.get_selector();
-                let constraints_with_selector: Vec<E<Fp>> = mips_con_env
-                    .get_constraints()
-                    .into_iter()
-                    .map(|c| selector.clone() * c)
-                    .collect();
-                acc.extend(constraints_with_selector);
-                mips_con_env.reset();
-                acc
-            });
-        constraints.extend(mips_con_env.get_selector_constraints());
-        constraints
-    };
+    let (constraints, _) = build_constraints::<Fp, mips_constraints::Env<Fp>>();
 
     assert_eq!(constraints.len(), TOTAL_NUMBER_OF_CONSTRAINTS);
 
@@ -59,11 +40,33 @@ fn zero_to_n_minus_one(n: usize) -> Vec<Fq> {
     (0..n).map(|i| Fq::from((i) as u64)).collect()
 }
 
+/// The lookups of a circuit without accesses to the registers and the memory,
+/// whose instruction counter increases by one on each row.
+fn no_ram_lookups() -> RamLookups<Fq> {
+    RamLookups::new(Column::Relation(SCRATCH_SIZE + SCRATCH_SIZE_INVERSE), 1)
+}
+
+/// The public inputs of a circuit without accesses to the registers and the
+/// memory, whose instruction counter goes from `initial` to `final_`.
+fn counter_public_inputs(initial: u64, final_: u64) -> PublicInputs<Fq> {
+    PublicInputs {
+        initial_state: VmState {
+            instruction_counter: Fq::from(initial),
+            registers: vec![],
+        },
+        final_state: VmState {
+            instruction_counter: Fq::from(final_),
+            registers: vec![],
+        },
+        ..PublicInputs::default()
+    }
+}
+
 #[test]
 fn test_small_circuit() {
     let domain = EvaluationDomains::<Fq>::create(8).unwrap();
     let srs = SRS::create(8);
-    let proof_input = ProofInputs::<Pallas> {
+    let proof_input = ProofInputs::<Pallas, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE> {
         evaluations: WitnessColumns {
             scratch: std::array::from_fn(|_| zero_to_n_minus_one(8)),
             scratch_inverse: std::array::from_fn(|_| (0..8).map(|_| Fq::zero()).collect()),
@@ -76,6 +79,7 @@ fn test_small_circuit() {
                 .collect(),
             selector: zero_to_n_minus_one(8),
         },
+        public_inputs: counter_public_inputs(1, 9),
     };
     let mut expr = Expr::zero();
     for i in 0..SCRATCH_SIZE + SCRATCH_SIZE_INVERSE + 2 {
@@ -86,17 +90,33 @@ fn test_small_circuit() {
     type BaseSponge = DefaultFqSponge<PallasParameters, PlonkSpongeConstantsKimchi>;
     type ScalarSponge = DefaultFrSponge<Fq, PlonkSpongeConstantsKimchi>;
 
-    let proof = prove::<Pallas, BaseSponge, ScalarSponge, _>(
+    let proof = prove::<
+        Pallas,
+        BaseSponge,
+        ScalarSponge,
+        _,
+        SCRATCH_SIZE,
+        SCRATCH_SIZE_INVERSE,
+        N_MIPS_SEL_COLS,
+    >(
         domain,
         &srs,
         proof_input,
         &[expr.clone()],
+        &no_ram_lookups(),
         &mut rng,
     )
     .unwrap();
 
     let instant_before_verification = Instant::now();
-    let verif = verify::<Pallas, BaseSponge, ScalarSponge>(domain, &srs, &[expr.clone()], &proof);
+    let verif = verify::<
+        Pallas,
+        BaseSponge,
+        ScalarSponge,
+        SCRATCH_SIZE,
+        SCRATCH_SIZE_INVERSE,
+        N_MIPS_SEL_COLS,
+    >(domain, &srs, &[expr.clone()], &no_ram_lookups(), &proof);
     let instant_after_verification = Instant::now();
     debug!(
         "Verification took: {} ms",
@@ -105,6 +125,87 @@ fn test_small_circuit() {
     assert!(verif, "Verification fails");
 }
 
+#[test]
+fn test_proof_serialization_roundtrip() {
+    let domain = EvaluationDomains::<Fq>::create(8).unwrap();
+    let srs = SRS::create(8);
+    let proof_input = ProofInputs::<Pallas, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE> {
+        evaluations: WitnessColumns {
+            scratch: std::array::from_fn(|_| zero_to_n_minus_one(8)),
+            scratch_inverse: std::array::from_fn(|_| (0..8).map(|_| Fq::zero()).collect()),
+            instruction_counter: zero_to_n_minus_one(8),
+            error: zero_to_n_minus_one(8),
+            selector: zero_to_n_minus_one(8),
+        },
+        public_inputs: counter_public_inputs(0, 8),
+    };
+    let constraint = Expr::cell(Column::Relation(0), CurrOrNext::Curr)
+        - Expr::cell(Column::Relation(1), CurrOrNext::Curr);
+    let constraints = [constraint];
+    let mut rng = make_test_rng(None);
+
+    type BaseSponge = DefaultFqSponge<PallasParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fq, PlonkSpongeConstantsKimchi>;
+    type MIPSProof = Proof<Pallas, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE, N_MIPS_SEL_COLS>;
+
+    let proof: MIPSProof = prove::<
+        Pallas,
+        BaseSponge,
+        ScalarSponge,
+        _,
+        SCRATCH_SIZE,
+        SCRATCH_SIZE_INVERSE,
+        N_MIPS_SEL_COLS,
+    >(
+        domain,
+        &srs,
+        proof_input,
+        &constraints,
+        &no_ram_lookups(),
+        &mut rng,
+    )
+    .unwrap();
+
+    let serialized = serde_json::to_string(&proof).unwrap();
+    let deserialized: MIPSProof = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(proof.commitments, deserialized.commitments);
+    assert_eq!(proof.zeta_evaluations, deserialized.zeta_evaluations);
+    assert_eq!(
+        proof.zeta_omega_evaluations,
+        deserialized.zeta_omega_evaluations
+    );
+
+    let verif = verify::<
+        Pallas,
+        BaseSponge,
+        ScalarSponge,
+        SCRATCH_SIZE,
+        SCRATCH_SIZE_INVERSE,
+        N_MIPS_SEL_COLS,
+    >(domain, &srs, &constraints, &no_ram_lookups(), &deserialized);
+    assert!(verif, "Verification of the deserialized proof fails");
+
+    // A malformed proof is rejected instead of making the verifier panic
+    let mut malformed = deserialized.clone();
+    malformed.quotient_evaluations.zeta.pop();
+    malformed.opening_proof.lr.pop();
+    let verif = verify::<
+        Pallas,
+        BaseSponge,
+        ScalarSponge,
+        SCRATCH_SIZE,
+        SCRATCH_SIZE_INVERSE,
+        N_MIPS_SEL_COLS,
+    >(domain, &srs, &constraints, &no_ram_lookups(), &malformed);
+    assert!(!verif, "A malformed proof must be rejected");
+
+    // A proof for an interpreter with a different number of columns must be
+    // rejected.
+    let res: Result<Proof<Pallas, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE, 1>, _> =
+        serde_json::from_str(&serialized);
+    assert!(res.is_err());
+}
+
 #[test]
 fn test_arkworks_batch_inversion_with_only_zeroes() {
     let input = vec![Fq::zero(); 8];
@@ -132,3 +233,396 @@ fn test_arkworks_batch_inversion_with_zeroes_and_random() {
     ark_ff::batch_inversion::<Fq>(&mut output);
     assert_eq!(output, exp_output);
 }
+
+mod riscv32im {
+    use super::*;
+    use crate::{
+        elf_loader,
+        interpreters::riscv32im::{
+            constraints as riscv_constraints,
+            interpreter::{Instruction, SyscallInstruction},
+            witness as riscv_witness, INSTRUCTION_SET_SIZE, PAGE_SIZE,
+            SCRATCH_SIZE as RISCV_SCRATCH_SIZE, SCRATCH_SIZE_INVERSE as RISCV_SCRATCH_SIZE_INVERSE,
+        },
+        lookups::LookupTableIDs,
+        pickles::verifier::verify_chain,
+    };
+    use kimchi_msm::LookupTableID;
+    use mina_curves::pasta::{Vesta, VestaParameters};
+
+    type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+    type RiscvProofInputs = ProofInputs<Vesta, RISCV_SCRATCH_SIZE, RISCV_SCRATCH_SIZE_INVERSE>;
+    type RiscvProof =
+        Proof<Vesta, RISCV_SCRATCH_SIZE, RISCV_SCRATCH_SIZE_INVERSE, INSTRUCTION_SET_SIZE>;
+
+    fn constraints() -> (Vec<E<Fp>>, RamLookups<Fp>) {
+        build_constraints::<Fp, riscv_constraints::Env<Fp>>()
+    }
+
+    /// Execute the program `program`, splitting the execution trace in chunks
+    /// of `domain_size` rows. The last chunk is padded. The state of the
+    /// program before its execution is returned with the chunks.
+    fn program_chunks(
+        program: &str,
+        domain_size: usize,
+    ) -> (ProgramState<Fp>, Vec<RiscvProofInputs>) {
+        let curr_dir = std::env::current_dir().unwrap();
+        let path = curr_dir
+            .join("resources/programs/riscv32im/bin")
+            .join(program);
+        let state = elf_loader::parse_riscv32(&path).unwrap();
+        let mut riscv_wit_env = riscv_witness::Env::<Fp>::create(PAGE_SIZE as usize, state);
+        let program_state = riscv_wit_env.program_state();
+
+        let mut chunks = vec![];
+        let mut proof_inputs = RiscvProofInputs::new(domain_size);
+        let mut chunk_initial_state = riscv_wit_env.vm_state();
+        while !riscv_wit_env.halt {
+            let instruction_counter = riscv_wit_env.instruction_counter;
+            riscv_wit_env.step();
+            let push_row = |proof_inputs: &mut RiscvProofInputs,
+                            riscv_wit_env: &riscv_witness::Env<Fp>,
+                            instruction_counter| {
+                proof_inputs.push_row(
+                    &riscv_wit_env.scratch_state,
+                    &[],
+                    Fp::from(instruction_counter),
+                    Fp::zero(),
+                    Fp::from((riscv_wit_env.selector - RISCV_SCRATCH_SIZE - 1) as u64),
+                )
+            };
+            push_row(&mut proof_inputs, &riscv_wit_env, instruction_counter);
+            if proof_inputs.evaluations.instruction_counter.len() == domain_size
+                || riscv_wit_env.halt
+            {
+                // The last chunk is padded by executing again the instruction
+                // halting the VM
+                while proof_inputs.evaluations.instruction_counter.len() < domain_size {
+                    let instruction_counter = riscv_wit_env.instruction_counter;
+                    assert!(riscv_wit_env.step_halted());
+                    push_row(&mut proof_inputs, &riscv_wit_env, instruction_counter);
+                }
+                let final_state = riscv_wit_env.vm_state();
+                proof_inputs.public_inputs = PublicInputs::new(
+                    std::mem::replace(&mut chunk_initial_state, final_state.clone()),
+                    final_state,
+                    std::mem::take(&mut riscv_wit_env.ram_lookups),
+                );
+                chunks.push(std::mem::replace(
+                    &mut proof_inputs,
+                    RiscvProofInputs::new(domain_size),
+                ));
+            }
+        }
+        (program_state, chunks)
+    }
+
+    #[test]
+    fn test_prove_and_verify_riscv32im_fibonacci() {
+        const DOMAIN_SIZE: usize = 1 << 11;
+
+        let (constraints, ram_lookups) = constraints();
+        let (_, mut chunks) = program_chunks("fibonacci-7", DOMAIN_SIZE);
+        assert_eq!(chunks.len(), 1);
+
+        let domain = EvaluationDomains::<Fp>::create(DOMAIN_SIZE).unwrap();
+        let srs = SRS::create(DOMAIN_SIZE);
+        let mut rng = make_test_rng(None);
+
+        let proof: RiscvProof = prove::<
+            Vesta,
+            BaseSponge,
+            ScalarSponge,
+            _,
+            RISCV_SCRATCH_SIZE,
+            RISCV_SCRATCH_SIZE_INVERSE,
+            INSTRUCTION_SET_SIZE,
+        >(
+            domain,
+            &srs,
+            chunks.remove(0),
+            &constraints,
+            &ram_lookups,
+            &mut rng,
+        )
+        .unwrap();
+        let verify_proof = |proof: &RiscvProof| {
+            verify::<
+                Vesta,
+                BaseSponge,
+                ScalarSponge,
+                RISCV_SCRATCH_SIZE,
+                RISCV_SCRATCH_SIZE_INVERSE,
+                INSTRUCTION_SET_SIZE,
+            >(domain, &srs, &constraints, &ram_lookups, proof)
+        };
+        assert!(verify_proof(&proof), "Verification fails");
+
+        // The final content of a memory cell is not the one left by the
+        // execution
+        let mut tampered = proof.clone();
+        let entry = tampered
+            .public_inputs
+            .final_ram
+            .iter_mut()
+            .find(|entry| entry.table_id == LookupTableIDs::MemoryLookup.to_u32())
+            .unwrap();
+        entry.value += Fp::one();
+        assert!(!verify_proof(&tampered));
+
+        // The instruction counters of the states are not the ones of the
+        // execution trace
+        let mut tampered = proof.clone();
+        tampered.public_inputs.initial_state.instruction_counter += Fp::one();
+        assert!(!verify_proof(&tampered));
+        let mut tampered = proof.clone();
+        tampered.public_inputs.final_state.instruction_counter += Fp::one();
+        assert!(!verify_proof(&tampered));
+
+        // An access to the memory is hidden from the verifier
+        let mut tampered = proof.clone();
+        let idx = tampered
+            .public_inputs
+            .initial_ram
+            .iter()
+            .position(|entry| entry.table_id == LookupTableIDs::MemoryLookup.to_u32())
+            .unwrap();
+        let entry = tampered.public_inputs.initial_ram.remove(idx);
+        tampered.public_inputs.final_ram.retain(|final_entry| {
+            (final_entry.table_id, final_entry.address) != (entry.table_id, entry.address)
+        });
+        assert!(!verify_proof(&tampered));
+    }
+
+    #[test]
+    fn test_prove_and_verify_riscv32im_chunk_padded_with_halt() {
+        const DOMAIN_SIZE: usize = 1 << 9;
+
+        let (constraints, ram_lookups) = constraints();
+        let (_, mut chunks) = program_chunks("no-action", DOMAIN_SIZE);
+        assert_eq!(chunks.len(), 1);
+        let chunk = chunks.remove(0);
+
+        // The trace ends with the instruction halting the VM, repeated to fill
+        // the domain
+        let halt_selector = Fp::from(
+            (usize::from(Instruction::SyscallType(SyscallInstruction::SyscallSuccess))
+                - RISCV_SCRATCH_SIZE
+                - 1) as u64,
+        );
+        let selector = &chunk.evaluations.selector;
+        assert_eq!(selector.len(), DOMAIN_SIZE);
+        assert_eq!(selector[DOMAIN_SIZE - 2], halt_selector);
+        assert_eq!(selector[DOMAIN_SIZE - 1], halt_selector);
+
+        let domain = EvaluationDomains::<Fp>::create(DOMAIN_SIZE).unwrap();
+        let srs = SRS::create(DOMAIN_SIZE);
+        let mut rng = make_test_rng(None);
+        let proof: RiscvProof = prove::<
+            Vesta,
+            BaseSponge,
+            ScalarSponge,
+            _,
+            RISCV_SCRATCH_SIZE,
+            RISCV_SCRATCH_SIZE_INVERSE,
+            INSTRUCTION_SET_SIZE,
+        >(domain, &srs, chunk, &constraints, &ram_lookups, &mut rng)
+        .unwrap();
+        assert!(verify::<
+            Vesta,
+            BaseSponge,
+            ScalarSponge,
+            RISCV_SCRATCH_SIZE,
+            RISCV_SCRATCH_SIZE_INVERSE,
+            INSTRUCTION_SET_SIZE,
+        >(domain, &srs, &constraints, &ram_lookups, &proof));
+    }
+
+    #[test]
+    fn test_riscv32im_chunk_proofs_are_chained() {
+        const DOMAIN_SIZE: usize = 1 << 9;
+
+        let (constraints, ram_lookups) = constraints();
+        let (program, chunks) = program_chunks("fibonacci-7", DOMAIN_SIZE);
+        assert!(chunks.len() > 1);
+
+        let domain = EvaluationDomains::<Fp>::create(DOMAIN_SIZE).unwrap();
+        let srs = SRS::create(DOMAIN_SIZE);
+        let mut rng = make_test_rng(None);
+
+        let mut proofs: Vec<RiscvProof> = chunks
+            .into_iter()
+            .map(|chunk| {
+                prove::<
+                    Vesta,
+                    BaseSponge,
+                    ScalarSponge,
+                    _,
+                    RISCV_SCRATCH_SIZE,
+                    RISCV_SCRATCH_SIZE_INVERSE,
+                    INSTRUCTION_SET_SIZE,
+                >(domain, &srs, chunk, &constraints, &ram_lookups, &mut rng)
+                .unwrap()
+            })
+            .collect();
+
+        let verify_proofs_of = |proofs: &[RiscvProof], program: Option<&ProgramState<Fp>>| {
+            verify_chain::<
+                Vesta,
+                BaseSponge,
+                ScalarSponge,
+                RISCV_SCRATCH_SIZE,
+                RISCV_SCRATCH_SIZE_INVERSE,
+                INSTRUCTION_SET_SIZE,
+            >(domain, &srs, &constraints, &ram_lookups, proofs, program)
+        };
+        let verify_proofs = |proofs: &[RiscvProof]| verify_proofs_of(proofs, Some(&program));
+        assert!(verify_proofs(&proofs), "Verification of the chain fails");
+        assert!(verify_proofs_of(&proofs, None));
+
+        // The chain does not start from the state of the program
+        assert!(!verify_proofs(&proofs[1..]));
+        assert!(verify_proofs_of(&proofs[1..], None));
+
+        // The memory of the program is not the one read by the chain
+        let entry = proofs[0]
+            .public_inputs
+            .initial_ram
+            .iter()
+            .find(|entry| entry.table_id == LookupTableIDs::MemoryLookup.to_u32())
+            .unwrap();
+        let address = ram::to_u64(entry.address).unwrap() as u32;
+        let page = address / PAGE_SIZE;
+        let mut data: Vec<u8> = (0..PAGE_SIZE)
+            .map(|i| program.memory_byte(page * PAGE_SIZE + i))
+            .collect();
+        data[(address % PAGE_SIZE) as usize] ^= 1;
+        let mut other_program = program.clone();
+        other_program.memory.insert(0, (page, data));
+        assert!(!verify_proofs_of(&proofs, Some(&other_program)));
+
+        // The chain does not start from the registers of the program
+        let mut other_program = program.clone();
+        other_program.vm_state.registers[1] += Fp::one();
+        assert!(!verify_proofs_of(&proofs, Some(&other_program)));
+
+        // Each proof is valid, but they are not given in the execution order
+        proofs.swap(0, 1);
+        assert!(!verify_proofs(&proofs));
+        proofs.swap(0, 1);
+
+        // The public inputs are bound to the proof
+        let mut proof = proofs[0].clone();
+        proof.public_inputs.final_state.instruction_counter += Fp::one();
+        assert!(!verify_proofs(&[proof]));
+
+        // A chunk does not read the content of the memory left by the previous
+        // chunks
+        let mut proofs = proofs;
+        let last = proofs.len() - 1;
+        let entry = &mut proofs[last].public_inputs.initial_ram[0];
+        entry.value += Fp::one();
+        assert!(!verify_proofs(&proofs));
+    }
+}
+
+mod mips {
+    use super::*;
+    use crate::{
+        cannon::{Page, State, PAGE_SIZE},
+        interpreters::mips::{
+            column::N_MIPS_REL_COLS, interpreter::SYSCALL_EXIT_GROUP,
+            tests_helpers::OnDiskPreImageOracle, witness as mips_witness,
+        },
+    };
+    use mina_curves::pasta::{Vesta, VestaParameters};
+
+    type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+    /// A VM whose first instruction makes the program exit.
+    fn exiting_env() -> mips_witness::Env<Fp, OnDiskPreImageOracle> {
+        let mut data = vec![0; PAGE_SIZE as usize];
+        // syscall
+        data[3] = 0x0c;
+        let mut registers = [0; 32];
+        registers[2] = SYSCALL_EXIT_GROUP;
+        let state = State {
+            memory: vec![Page { index: 0, data }],
+            preimage_key: [0; 32],
+            preimage_offset: 0,
+            pc: 0,
+            next_pc: 4,
+            lo: 0,
+            hi: 0,
+            heap: 0,
+            exit: 0,
+            exited: false,
+            step: 0,
+            registers,
+            last_hint: None,
+            preimage: None,
+        };
+        mips_witness::Env::create(PAGE_SIZE as usize, state, OnDiskPreImageOracle)
+    }
+
+    #[test]
+    fn test_prove_and_verify_mips_chunk_padded_with_exit() {
+        // The domain must contain the table of the range checks
+        const DOMAIN_SIZE: usize = 1 << 8;
+
+        let (constraints, ram_lookups) = build_constraints::<Fp, mips_constraints::Env<Fp>>();
+        let mut mips_wit_env = exiting_env();
+        let initial_state = mips_wit_env.vm_state();
+
+        let mut proof_inputs =
+            ProofInputs::<Vesta, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE>::new(DOMAIN_SIZE);
+        for _ in 0..DOMAIN_SIZE {
+            let instruction_counter = mips_wit_env.instruction_counter;
+            assert!(mips_wit_env.step_exited());
+            proof_inputs.push_row(
+                &mips_wit_env.scratch_state,
+                &mips_wit_env.scratch_state_inverse,
+                Fp::from(instruction_counter),
+                Fp::zero(),
+                Fp::from((mips_wit_env.selector - N_MIPS_REL_COLS) as u64),
+            );
+        }
+        assert!(mips_wit_env.halt);
+        proof_inputs.public_inputs = PublicInputs::new(
+            initial_state,
+            mips_wit_env.vm_state(),
+            std::mem::take(&mut mips_wit_env.ram_lookups),
+        );
+
+        let domain = EvaluationDomains::<Fp>::create(DOMAIN_SIZE).unwrap();
+        let srs = SRS::create(DOMAIN_SIZE);
+        let mut rng = make_test_rng(None);
+        let proof = prove::<
+            Vesta,
+            BaseSponge,
+            ScalarSponge,
+            _,
+            SCRATCH_SIZE,
+            SCRATCH_SIZE_INVERSE,
+            N_MIPS_SEL_COLS,
+        >(
+            domain,
+            &srs,
+            proof_inputs,
+            &constraints,
+            &ram_lookups,
+            &mut rng,
+        )
+        .unwrap();
+        assert!(verify::<
+            Vesta,
+            BaseSponge,
+            ScalarSponge,
+            SCRATCH_SIZE,
+            SCRATCH_SIZE_INVERSE,
+            N_MIPS_SEL_COLS,
+        >(domain, &srs, &constraints, &ram_lookups, &proof));
+    }
+}
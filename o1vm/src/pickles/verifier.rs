@@ -14,44 +14,117 @@ use kimchi::{
     plonk_sponge::FrSponge,
     proof::PointEvaluations,
 };
+use log::debug;
 use mina_poseidon::{sponge::ScalarChallenge, FqSponge};
+use o1_utils::math;
 use poly_commitment::{
     commitment::{
         absorb_commitment, combined_inner_product, BatchEvaluationProof, Evaluation, PolyComm,
     },
     ipa::OpeningProof,
-    OpenProof,
+    OpenProof, SRS as _,
 };
 
 use super::{
-    column_env::get_all_columns,
-    proof::{Proof, WitnessColumns},
+    column_env::{get_all_columns, get_ram_columns, get_range_check_columns},
+    proof::{ProgramState, Proof, RamColumns, RangeCheckColumns, WitnessColumns},
+    ram::{self, RamLookups},
+    DEGREE_QUOTIENT_POLYNOMIAL,
 };
-use crate::{interpreters::mips::column::N_MIPS_SEL_COLS, E};
+use crate::E;
 use kimchi_msm::columns::Column;
 
-type CommitmentColumns<G> = WitnessColumns<PolyComm<G>, [PolyComm<G>; N_MIPS_SEL_COLS]>;
-type EvaluationColumns<F> = WitnessColumns<F, [F; N_MIPS_SEL_COLS]>;
+type CommitmentColumns<
+    G,
+    const N_SCRATCH: usize,
+    const N_SCRATCH_INVERSE: usize,
+    const N_SELECTORS: usize,
+> = WitnessColumns<PolyComm<G>, [PolyComm<G>; N_SELECTORS], N_SCRATCH, N_SCRATCH_INVERSE>;
+type EvaluationColumns<
+    F,
+    const N_SCRATCH: usize,
+    const N_SCRATCH_INVERSE: usize,
+    const N_SELECTORS: usize,
+> = WitnessColumns<F, [F; N_SELECTORS], N_SCRATCH, N_SCRATCH_INVERSE>;
 
-struct ColumnEval<'a, G: AffineRepr> {
-    commitment: &'a CommitmentColumns<G>,
-    zeta_eval: &'a EvaluationColumns<G::ScalarField>,
-    zeta_omega_eval: &'a EvaluationColumns<G::ScalarField>,
+struct ColumnEval<
+    'a,
+    G: AffineRepr,
+    const N_SCRATCH: usize,
+    const N_SCRATCH_INVERSE: usize,
+    const N_SELECTORS: usize,
+> {
+    commitment: &'a CommitmentColumns<G, N_SCRATCH, N_SCRATCH_INVERSE, N_SELECTORS>,
+    zeta_eval: &'a EvaluationColumns<G::ScalarField, N_SCRATCH, N_SCRATCH_INVERSE, N_SELECTORS>,
+    zeta_omega_eval:
+        &'a EvaluationColumns<G::ScalarField, N_SCRATCH, N_SCRATCH_INVERSE, N_SELECTORS>,
+    range_check_commitment: &'a RangeCheckColumns<PolyComm<G>>,
+    range_check_zeta_eval: &'a RangeCheckColumns<G::ScalarField>,
+    range_check_zeta_omega_eval: &'a RangeCheckColumns<G::ScalarField>,
+    /// The evaluations of the fixed table of bytes, computed by the verifier
+    byte_table_eval: PointEvaluations<G::ScalarField>,
+    ram_commitment: &'a RamColumns<PolyComm<G>>,
+    ram_zeta_eval: &'a RamColumns<G::ScalarField>,
+    ram_zeta_omega_eval: &'a RamColumns<G::ScalarField>,
 }
 
-impl<G: AffineRepr> ColumnEvaluations<G::ScalarField> for ColumnEval<'_, G> {
+impl<
+        G: AffineRepr,
+        const N_SCRATCH: usize,
+        const N_SCRATCH_INVERSE: usize,
+        const N_SELECTORS: usize,
+    > ColumnEval<'_, G, N_SCRATCH, N_SCRATCH_INVERSE, N_SELECTORS>
+{
+    fn get_commitment(&self, col: &Column) -> Option<&PolyComm<G>> {
+        match col {
+            Column::LookupRuntimeTable(_) | Column::LookupMultiplicity(_) => {
+                self.range_check_commitment.get_column(col)
+            }
+            Column::LookupPartialSum(_) | Column::LookupAggregation => {
+                self.ram_commitment.get_column(col)
+            }
+            _ => self.commitment.get_column(col),
+        }
+    }
+}
+
+impl<
+        G: AffineRepr,
+        const N_SCRATCH: usize,
+        const N_SCRATCH_INVERSE: usize,
+        const N_SELECTORS: usize,
+    > ColumnEvaluations<G::ScalarField>
+    for ColumnEval<'_, G, N_SCRATCH, N_SCRATCH_INVERSE, N_SELECTORS>
+{
     type Column = Column;
     fn evaluate(
         &self,
         col: Self::Column,
     ) -> Result<PointEvaluations<G::ScalarField>, ExprError<Self::Column>> {
         let ColumnEval {
-            commitment: _,
             zeta_eval,
             zeta_omega_eval,
+            range_check_zeta_eval,
+            range_check_zeta_omega_eval,
+            byte_table_eval,
+            ram_zeta_eval,
+            ram_zeta_omega_eval,
+            ..
         } = self;
-        if let Some(&zeta) = zeta_eval.get_column(&col) {
-            if let Some(&zeta_omega) = zeta_omega_eval.get_column(&col) {
+        let (zeta_eval, zeta_omega_eval) = match col {
+            Column::LookupFixedTable(_) => return Ok(byte_table_eval.clone()),
+            Column::LookupRuntimeTable(_) | Column::LookupMultiplicity(_) => (
+                range_check_zeta_eval.get_column(&col),
+                range_check_zeta_omega_eval.get_column(&col),
+            ),
+            Column::LookupPartialSum(_) | Column::LookupAggregation => (
+                ram_zeta_eval.get_column(&col),
+                ram_zeta_omega_eval.get_column(&col),
+            ),
+            _ => (zeta_eval.get_column(&col), zeta_omega_eval.get_column(&col)),
+        };
+        if let Some(&zeta) = zeta_eval {
+            if let Some(&zeta_omega) = zeta_omega_eval {
                 Ok(PointEvaluations { zeta, zeta_omega })
             } else {
                 Err(ExprError::MissingEvaluation(col, CurrOrNext::Next))
@@ -62,37 +135,101 @@ impl<G: AffineRepr> ColumnEvaluations<G::ScalarField> for ColumnEval<'_, G> {
     }
 }
 
+/// Verify the proof of a chunk of an execution trace, for the constraints
+/// `constraints` and the lookups into the RAM tables `ram_lookups` of the
+/// interpreter.
+///
+/// On top of the constraints, it checks that the accesses to the registers and
+/// to the memory are consistent with the public inputs, see [super::ram].
 pub fn verify<
     G: KimchiCurve,
     EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
     EFrSponge: FrSponge<G::ScalarField>,
+    const N_SCRATCH: usize,
+    const N_SCRATCH_INVERSE: usize,
+    const N_SELECTORS: usize,
 >(
     domain: EvaluationDomains<G::ScalarField>,
     srs: &<OpeningProof<G> as OpenProof<G>>::SRS,
     constraints: &[E<G::ScalarField>],
-    proof: &Proof<G>,
+    ram_lookups: &RamLookups<G::ScalarField>,
+    proof: &Proof<G, N_SCRATCH, N_SCRATCH_INVERSE, N_SELECTORS>,
 ) -> bool
 where
     <G as AffineRepr>::BaseField: PrimeField,
 {
     let Proof {
+        public_inputs,
         commitments,
         zeta_evaluations,
         zeta_omega_evaluations,
+        range_check_commitments,
+        range_check_zeta_evaluations,
+        range_check_zeta_omega_evaluations,
+        ram_commitments,
+        ram_zeta_evaluations,
+        ram_zeta_omega_evaluations,
         quotient_commitment,
         quotient_evaluations,
         opening_proof,
     } = proof;
 
+    if !ram::check_boundary(public_inputs) {
+        debug!("Verifier: the public inputs are not consistent with the RAM entries");
+        return false;
+    }
+
+    // Checking the shape of the proof before using it, as it may come from an
+    // untrusted source
+    {
+        let n_lookup_terms = ram_lookups.len();
+        let n_range_check_bytes = ram_lookups.n_range_check_bytes();
+        fn range_checks_have_shape<T>(columns: &RangeCheckColumns<T>, n_bytes: usize) -> bool {
+            columns.bytes.len() == n_bytes && columns.multiplicities.is_some() == (n_bytes > 0)
+        }
+        let n_quotient_chunks = DEGREE_QUOTIENT_POLYNOMIAL as usize;
+        let columns_have_one_chunk = commitments
+            .scratch
+            .iter()
+            .chain(commitments.scratch_inverse.iter())
+            .chain([&commitments.instruction_counter, &commitments.error])
+            .chain(commitments.selector.iter())
+            .chain(range_check_commitments.bytes.iter())
+            .chain(range_check_commitments.multiplicities.iter())
+            .chain(ram_commitments.lookup_terms.iter())
+            .chain([&ram_commitments.aggregation])
+            .all(|comm| comm.len() == 1);
+        // The table of bytes must fit in the domain
+        let table_fits = n_range_check_bytes == 0 || domain.d1.size >= ram::BYTE_TABLE_SIZE as u64;
+        if !table_fits
+            || !range_checks_have_shape(range_check_commitments, n_range_check_bytes)
+            || !range_checks_have_shape(range_check_zeta_evaluations, n_range_check_bytes)
+            || !range_checks_have_shape(range_check_zeta_omega_evaluations, n_range_check_bytes)
+            || ram_commitments.lookup_terms.len() != n_lookup_terms
+            || ram_zeta_evaluations.lookup_terms.len() != n_lookup_terms
+            || ram_zeta_omega_evaluations.lookup_terms.len() != n_lookup_terms
+            || !columns_have_one_chunk
+            || quotient_commitment.len() != n_quotient_chunks
+            || quotient_evaluations.zeta.len() != n_quotient_chunks
+            || quotient_evaluations.zeta_omega.len() != n_quotient_chunks
+            || opening_proof.lr.len() != math::ceil_log2(srs.size())
+        {
+            debug!("Verifier: the proof does not have the expected shape");
+            return false;
+        }
+    }
+
     ////////////////////////////////////////////////////////////////////////////
-    // TODO :  public inputs
+    // Absorbing the public inputs
     ////////////////////////////////////////////////////////////////////////////
 
+    let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
+    fq_sponge.absorb_fr(&public_inputs.to_field_elements());
+
     ////////////////////////////////////////////////////////////////////////////
     // Absorbing all the commitments to the columns
     ////////////////////////////////////////////////////////////////////////////
 
-    let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
     for comm in commitments.scratch.iter() {
         absorb_commitment(&mut fq_sponge, comm)
     }
@@ -104,6 +241,31 @@ where
     for comm in commitments.selector.iter() {
         absorb_commitment(&mut fq_sponge, comm)
     }
+    for comm in range_check_commitments
+        .bytes
+        .iter()
+        .chain(range_check_commitments.multiplicities.iter())
+    {
+        absorb_commitment(&mut fq_sponge, comm)
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Memory checking argument
+    ////////////////////////////////////////////////////////////////////////////
+
+    // Sample the lookup challenges with the Fq-Sponge.
+    let beta = fq_sponge.challenge();
+    let joint_combiner = fq_sponge.challenge();
+
+    let Some(boundary_sum) = ram::boundary_sum(public_inputs, beta, joint_combiner) else {
+        debug!("Verifier: a RAM entry of the public inputs cancels the lookup challenges");
+        return false;
+    };
+
+    for comm in ram_commitments.lookup_terms.iter() {
+        absorb_commitment(&mut fq_sponge, comm)
+    }
+    absorb_commitment(&mut fq_sponge, &ram_commitments.aggregation);
 
     // Sample α with the Fq-Sponge.
     let alpha = fq_sponge.challenge();
@@ -125,6 +287,16 @@ where
         commitment: commitments,
         zeta_eval: zeta_evaluations,
         zeta_omega_eval: zeta_omega_evaluations,
+        range_check_commitment: range_check_commitments,
+        range_check_zeta_eval: range_check_zeta_evaluations,
+        range_check_zeta_omega_eval: range_check_zeta_omega_evaluations,
+        byte_table_eval: PointEvaluations {
+            zeta: ram::byte_table_evaluation(domain.d1, zeta),
+            zeta_omega: ram::byte_table_evaluation(domain.d1, zeta_omega),
+        },
+        ram_commitment: ram_commitments,
+        ram_zeta_eval: ram_zeta_evaluations,
+        ram_zeta_omega_eval: ram_zeta_omega_evaluations,
     };
 
     // -- Absorb all commitments_and_evaluations
@@ -160,6 +332,30 @@ where
         fr_sponge.absorb(zeta_eval);
         fr_sponge.absorb(zeta_omega_eval);
     }
+    for (zeta_eval, zeta_omega_eval) in range_check_zeta_evaluations
+        .bytes
+        .iter()
+        .chain(range_check_zeta_evaluations.multiplicities.iter())
+        .zip(
+            range_check_zeta_omega_evaluations
+                .bytes
+                .iter()
+                .chain(range_check_zeta_omega_evaluations.multiplicities.iter()),
+        )
+    {
+        fr_sponge.absorb(zeta_eval);
+        fr_sponge.absorb(zeta_omega_eval);
+    }
+    for (zeta_eval, zeta_omega_eval) in ram_zeta_evaluations
+        .lookup_terms
+        .iter()
+        .zip(ram_zeta_omega_evaluations.lookup_terms.iter())
+    {
+        fr_sponge.absorb(zeta_eval);
+        fr_sponge.absorb(zeta_omega_eval);
+    }
+    fr_sponge.absorb(&ram_zeta_evaluations.aggregation);
+    fr_sponge.absorb(&ram_zeta_omega_evaluations.aggregation);
     for (quotient_zeta_eval, quotient_zeta_omega_eval) in quotient_evaluations
         .zeta
         .iter()
@@ -172,11 +368,10 @@ where
     // FIXME: use a proper Challenge structure
     let challenges = BerkeleyChallenges {
         alpha,
+        beta,
         // No permutation argument for the moment
-        beta: G::ScalarField::zero(),
         gamma: G::ScalarField::zero(),
-        // No lookup for the moment
-        joint_combiner: G::ScalarField::zero(),
+        joint_combiner,
     };
     let (_, endo_r) = G::endos();
 
@@ -186,43 +381,52 @@ where
         zk_rows: 0,
     };
 
+    let constraints: Vec<E<G::ScalarField>> = constraints
+        .iter()
+        .cloned()
+        .chain(ram_lookups.constraints(public_inputs, boundary_sum, domain.d1))
+        .collect();
     let combined_expr =
         Expr::combine_constraints(0..(constraints.len() as u32), constraints.to_vec());
 
-    let numerator_zeta = PolishToken::evaluate(
+    let numerator_zeta = match PolishToken::evaluate(
         combined_expr.to_polish().as_slice(),
         domain.d1,
         zeta,
         &column_eval,
         &constants,
         &challenges,
-    )
-    .unwrap_or_else(|_| panic!("Could not evaluate quotient polynomial at zeta"));
+    ) {
+        Ok(res) => res,
+        Err(err) => {
+            debug!("Verifier: could not evaluate the constraints at ζ: {err}");
+            return false;
+        }
+    };
 
     let v_chal = fr_sponge.challenge();
     let v = v_chal.to_field(endo_r);
     let u_chal = fr_sponge.challenge();
     let u = u_chal.to_field(endo_r);
 
-    let mut evaluations: Vec<_> = get_all_columns()
-        .into_iter()
-        .map(|column| {
-            let commitment = column_eval
-                .commitment
-                .get_column(&column)
-                .unwrap_or_else(|| panic!("Could not get `commitment` for `Evaluation`"))
-                .clone();
-
-            let evaluations = column_eval
-                .evaluate(column)
-                .unwrap_or_else(|_| panic!("Could not get `evaluations` for `Evaluation`"));
-
-            Evaluation {
-                commitment,
-                evaluations: vec![vec![evaluations.zeta], vec![evaluations.zeta_omega]],
-            }
-        })
-        .collect();
+    let evaluations: Option<Vec<_>> =
+        get_all_columns::<N_SCRATCH, N_SCRATCH_INVERSE, N_SELECTORS>()
+            .into_iter()
+            .chain(get_range_check_columns(ram_lookups.n_range_checks()))
+            .chain(get_ram_columns(ram_lookups.len()))
+            .map(|column| {
+                let commitment = column_eval.get_commitment(&column)?.clone();
+                let evaluations = column_eval.evaluate(column).ok()?;
+                Some(Evaluation {
+                    commitment,
+                    evaluations: vec![vec![evaluations.zeta], vec![evaluations.zeta_omega]],
+                })
+            })
+            .collect();
+    let Some(mut evaluations) = evaluations else {
+        debug!("Verifier: missing the commitment or the evaluations of a column");
+        return false;
+    };
 
     evaluations.push(Evaluation {
         commitment: proof.quotient_commitment.clone(),
@@ -265,3 +469,54 @@ where
     (quotient_zeta == numerator_zeta / (zeta.pow([domain.d1.size]) - G::ScalarField::one()))
         && OpeningProof::verify(srs, &group_map, &mut [batch], &mut thread_rng())
 }
+
+/// Verify the proofs of consecutive chunks of an execution trace.
+///
+/// On top of verifying each proof individually, it checks that the proofs
+/// chain correctly, i.e. that the final state of the VM committed to by a proof
+/// is the initial state committed to by the next one, and that the registers
+/// and memory cells accessed by a chunk have the content left by the last
+/// chunk accessing them.
+///
+/// If the proofs start from the beginning of the execution of a program,
+/// `program` gives its initial state, to which the first chunk and the content
+/// of the memory cells accessed for the first time are bound. Otherwise, the
+/// entries read for the first time by the chain are not checked.
+pub fn verify_chain<
+    G: KimchiCurve,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+    const N_SCRATCH: usize,
+    const N_SCRATCH_INVERSE: usize,
+    const N_SELECTORS: usize,
+>(
+    domain: EvaluationDomains<G::ScalarField>,
+    srs: &<OpeningProof<G> as OpenProof<G>>::SRS,
+    constraints: &[E<G::ScalarField>],
+    ram_lookups: &RamLookups<G::ScalarField>,
+    proofs: &[Proof<G, N_SCRATCH, N_SCRATCH_INVERSE, N_SELECTORS>],
+    program: Option<&ProgramState<G::ScalarField>>,
+) -> bool
+where
+    <G as AffineRepr>::BaseField: PrimeField,
+{
+    let starts_with_program = match (program, proofs.first()) {
+        (Some(program), Some(proof)) => proof.public_inputs.initial_state == program.vm_state,
+        _ => true,
+    };
+    let chained = proofs
+        .windows(2)
+        .all(|proofs| proofs[0].public_inputs.final_state == proofs[1].public_inputs.initial_state);
+    starts_with_program
+        && chained
+        && ram::check_chained_boundaries(proofs.iter().map(|proof| &proof.public_inputs), program)
+        && proofs.iter().all(|proof| {
+            verify::<G, EFqSponge, EFrSponge, N_SCRATCH, N_SCRATCH_INVERSE, N_SELECTORS>(
+                domain,
+                srs,
+                constraints,
+                ram_lookups,
+                proof,
+            )
+        })
+}
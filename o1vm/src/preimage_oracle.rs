@@ -1,15 +1,20 @@
 use crate::cannon::{
-    Hint, HostProgram, Preimage, HINT_CLIENT_READ_FD, HINT_CLIENT_WRITE_FD,
-    PREIMAGE_CLIENT_READ_FD, PREIMAGE_CLIENT_WRITE_FD,
+    Hint, HostProgram, Preimage, PreimageKey, PreimageKeyType, VmConfiguration,
+    HINT_CLIENT_READ_FD, HINT_CLIENT_WRITE_FD, PREIMAGE_CLIENT_READ_FD, PREIMAGE_CLIENT_WRITE_FD,
 };
 use command_fds::{CommandFdExt, FdMapping};
 use log::debug;
 use os_pipe::{PipeReader, PipeWriter};
 use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
     io::{Read, Write},
     os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    path::{Path, PathBuf},
     process::{Child, Command},
+    str::FromStr,
 };
+use thiserror::Error;
 
 pub struct PreImageOracle {
     pub cmd: Command,
@@ -224,6 +229,192 @@ impl PreImageOracleT for PreImageOracle {
     }
 }
 
+impl<T: PreImageOracleT + ?Sized> PreImageOracleT for Box<T> {
+    fn get_preimage(&mut self, key: [u8; 32]) -> Preimage {
+        (**self).get_preimage(key)
+    }
+
+    fn hint(&mut self, hint: Hint) {
+        (**self).hint(hint)
+    }
+}
+
+/// Errors that can arise when loading the preimages of a
+/// [NativePreImageOracle]
+#[derive(Error, Debug)]
+pub enum PreimageStoreError {
+    #[error("could not read {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("could not parse the preimages in {0}: {1}")]
+    Json(PathBuf, serde_json::Error),
+    #[error("invalid preimage key {0}")]
+    InvalidKey(String),
+    #[error("the preimage of the key {0} is not hexadecimal")]
+    InvalidPreimage(String),
+    #[error("the preimage given for the key {0} does not hash to the key")]
+    HashMismatch(String),
+}
+
+/// A preimage oracle answering the requests of the program from preimages
+/// stored in memory, i.e. without running a host program. It is useful to run
+/// executions reading preimages hermetically, e.g. in tests.
+///
+/// The preimages can be loaded from a JSON file mapping the keys to the
+/// preimages, both hex-encoded:
+/// ```json
+/// { "0x02...": "0x68656c6c6f" }
+/// ```
+/// or from a directory containing one file `0x<key>.txt` per key, whose
+/// content is the hex-encoded preimage.
+#[derive(Debug, Clone, Default)]
+pub struct NativePreImageOracle {
+    preimages: HashMap<[u8; 32], Vec<u8>>,
+}
+
+impl NativePreImageOracle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store `data` as the preimage of `key`. The keys of the hash types
+    /// must be the hash of `data`.
+    pub fn insert(&mut self, key: PreimageKey, data: Vec<u8>) -> Result<(), PreimageStoreError> {
+        let expected_key = match key.key_type() {
+            Ok(PreimageKeyType::Keccak256) => Some(PreimageKey::keccak256(&data)),
+            Ok(PreimageKeyType::Sha256) => Some(PreimageKey::sha256(&data)),
+            Ok(_) => None,
+            Err(_) => return Err(PreimageStoreError::InvalidKey(hex::encode(key.0))),
+        };
+        if expected_key.map_or(false, |expected_key| expected_key != key) {
+            return Err(PreimageStoreError::HashMismatch(hex::encode(key.0)));
+        }
+        self.preimages.insert(key.0, data);
+        Ok(())
+    }
+
+    /// Store `data` as the preimage of its keccak256 key, and return the key.
+    pub fn insert_keccak256(&mut self, data: Vec<u8>) -> PreimageKey {
+        let key = PreimageKey::keccak256(&data);
+        self.preimages.insert(key.0, data);
+        key
+    }
+
+    /// Store `data` as the preimage of its sha256 key, and return the key.
+    pub fn insert_sha256(&mut self, data: Vec<u8>) -> PreimageKey {
+        let key = PreimageKey::sha256(&data);
+        self.preimages.insert(key.0, data);
+        key
+    }
+
+    /// Store `data` as the local data of the program with identifier `ident`,
+    /// and return the key.
+    pub fn insert_local(&mut self, ident: u64, data: Vec<u8>) -> PreimageKey {
+        let key = PreimageKey::local(ident);
+        self.preimages.insert(key.0, data);
+        key
+    }
+
+    pub fn len(&self) -> usize {
+        self.preimages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.preimages.is_empty()
+    }
+
+    fn insert_hex(&mut self, key: &str, data: &str) -> Result<(), PreimageStoreError> {
+        let key = PreimageKey::from_str(key.trim())
+            .map_err(|_| PreimageStoreError::InvalidKey(key.to_string()))?;
+        let data = data.trim();
+        let data = hex::decode(data.strip_prefix("0x").unwrap_or(data))
+            .map_err(|_| PreimageStoreError::InvalidPreimage(hex::encode(key.0)))?;
+        self.insert(key, data)
+    }
+
+    /// Load the preimages from a JSON file, see [NativePreImageOracle].
+    pub fn from_json_file(path: &Path) -> Result<Self, PreimageStoreError> {
+        let contents =
+            fs::read_to_string(path).map_err(|err| PreimageStoreError::Io(path.into(), err))?;
+        let entries: BTreeMap<String, String> = serde_json::from_str(&contents)
+            .map_err(|err| PreimageStoreError::Json(path.into(), err))?;
+        let mut oracle = Self::new();
+        for (key, data) in entries.iter() {
+            oracle.insert_hex(key, data)?;
+        }
+        Ok(oracle)
+    }
+
+    /// Load the preimages from the files `0x<key>.txt` of a directory, see
+    /// [NativePreImageOracle]. The other files are ignored.
+    pub fn from_directory(path: &Path) -> Result<Self, PreimageStoreError> {
+        let io_error = |err| PreimageStoreError::Io(path.into(), err);
+        let mut oracle = Self::new();
+        for entry in fs::read_dir(path).map_err(io_error)? {
+            let file_path = entry.map_err(io_error)?.path();
+            let Some(key) = file_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".txt"))
+            else {
+                continue;
+            };
+            let data = fs::read_to_string(&file_path)
+                .map_err(|err| PreimageStoreError::Io(file_path.clone(), err))?;
+            oracle.insert_hex(key, &data)?;
+        }
+        Ok(oracle)
+    }
+
+    /// Load the preimages from `path`, which is either a directory or a JSON
+    /// file.
+    pub fn load(path: &Path) -> Result<Self, PreimageStoreError> {
+        if path.is_dir() {
+            Self::from_directory(path)
+        } else {
+            Self::from_json_file(path)
+        }
+    }
+}
+
+impl PreImageOracleT for NativePreImageOracle {
+    fn get_preimage(&mut self, key: [u8; 32]) -> Preimage {
+        let preimage = self
+            .preimages
+            .get(&key)
+            .unwrap_or_else(|| panic!("No preimage for the key 0x{}", hex::encode(key)));
+        debug!(
+            "Got preimage of length {}\n {}",
+            preimage.len(),
+            hex::encode(preimage)
+        );
+        Preimage::create(preimage.clone())
+    }
+
+    // The hints tell the host program which preimages to fetch next. They
+    // are useless as all the preimages are already available.
+    fn hint(&mut self, _hint: Hint) {}
+}
+
+/// Create the preimage oracle described by the configuration: a
+/// [NativePreImageOracle] if a preimage store is given with
+/// `--preimage-store`, or a [PreImageOracle] talking to the host program
+/// otherwise. The host program is started, and its process is returned as it
+/// must outlive the execution.
+pub fn create_oracle(configuration: &VmConfiguration) -> (Box<dyn PreImageOracleT>, Option<Child>) {
+    match &configuration.preimage_store {
+        Some(path) => {
+            let oracle = NativePreImageOracle::load(path)
+                .unwrap_or_else(|err| panic!("Could not load the preimage store: {err}"));
+            (Box::new(oracle), None)
+        }
+        None => {
+            let mut po = PreImageOracle::create(&configuration.host);
+            let child = po.start();
+            (Box::new(po), Some(child))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -299,4 +490,75 @@ mod tests {
         assert_eq!(n, len);
         assert_eq!(data, msg2);
     }
+
+    fn tests_directory() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("resources/tests")
+    }
+
+    #[test]
+    fn test_native_oracle_returns_the_stored_preimages() {
+        let mut oracle = NativePreImageOracle::new();
+        let keccak_key = oracle.insert_keccak256(b"hello".to_vec());
+        let sha256_key = oracle.insert_sha256(b"world".to_vec());
+        let local_key = oracle.insert_local(1, vec![42; 32]);
+        assert_eq!(oracle.len(), 3);
+
+        assert_eq!(oracle.get_preimage(keccak_key.0).get(), b"hello");
+        assert_eq!(oracle.get_preimage(sha256_key.0).get(), b"world");
+        assert_eq!(oracle.get_preimage(local_key.0).get(), vec![42; 32]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_native_oracle_panics_on_unknown_key() {
+        let mut oracle = NativePreImageOracle::new();
+        oracle.get_preimage(PreimageKey::keccak256(b"hello").0);
+    }
+
+    #[test]
+    fn test_native_oracle_rejects_wrong_preimage() {
+        let mut oracle = NativePreImageOracle::new();
+        let res = oracle.insert(PreimageKey::keccak256(b"hello"), b"world".to_vec());
+        assert!(matches!(res, Err(PreimageStoreError::HashMismatch(_))));
+        let res = oracle.insert(PreimageKey::sha256(b"hello"), b"hello".to_vec());
+        assert!(res.is_ok());
+        let res = oracle.insert(PreimageKey([0; 32]), vec![]);
+        assert!(matches!(res, Err(PreimageStoreError::InvalidKey(_))));
+    }
+
+    #[test]
+    fn test_native_oracle_from_directory() {
+        let mut oracle = NativePreImageOracle::load(&tests_directory()).unwrap();
+        assert!(!oracle.is_empty());
+
+        // The preimages are the ones read by the MIPS tests
+        let key = "022107307879258577230c5aa2f90567bda40877a7e85dceb6ff1f37480fef3d";
+        let contents = fs::read_to_string(tests_directory().join(format!("0x{key}.txt"))).unwrap();
+        let key = PreimageKey::from_str(key).unwrap();
+        assert_eq!(
+            oracle.get_preimage(key.0).get(),
+            hex::decode(contents.trim()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_native_oracle_from_json_file() {
+        let path =
+            std::env::temp_dir().join(format!("o1vm-test-preimages-{}.json", std::process::id()));
+        let key = PreimageKey::keccak256(b"hello");
+        let json = format!(
+            "{{\"0x{}\": \"0x{}\", \"{}\": \"2a\"}}",
+            hex::encode(key.0),
+            hex::encode(b"hello"),
+            hex::encode(PreimageKey::local(2).0),
+        );
+        fs::write(&path, json).unwrap();
+        let res = NativePreImageOracle::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        let mut oracle = res.unwrap();
+        assert_eq!(oracle.len(), 2);
+        assert_eq!(oracle.get_preimage(key.0).get(), b"hello");
+        assert_eq!(oracle.get_preimage(PreimageKey::local(2).0).get(), vec![42]);
+    }
 }
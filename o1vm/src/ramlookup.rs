@@ -1,5 +1,6 @@
 use ark_ff::{Field, One, Zero};
 use kimchi_msm::{Logup, LookupTableID};
+use std::collections::BTreeMap;
 
 /// Enum representing the two different modes of a RAMLookup
 #[derive(Copy, Clone, Debug)]
@@ -115,3 +116,69 @@ impl<F: std::fmt::Display + Field, ID: LookupTableID> std::fmt::Display for RAML
         Ok(())
     }
 }
+
+/// An entry of a RAM table, i.e. the table it belongs to and its values.
+pub type RAMEntry<ID> = (ID, Vec<u64>);
+
+/// The multiset of the entries written to and read from the RAM tables by a
+/// sequence of [RAMLookup], counted with their multiplicities.
+///
+/// Each access to an address writes the previous entry, with the timestamp of
+/// the last access, and reads the new entry, with the current timestamp. Along
+/// a sequence of accesses, the entries therefore cancel out, except for the
+/// first entry written and the last entry read for each address.
+#[derive(Clone, Debug)]
+pub struct RAMMultiset<ID: LookupTableID> {
+    multiplicities: BTreeMap<RAMEntry<ID>, i64>,
+}
+
+impl<ID: LookupTableID> Default for RAMMultiset<ID> {
+    fn default() -> Self {
+        Self {
+            multiplicities: BTreeMap::new(),
+        }
+    }
+}
+
+impl<ID: LookupTableID> RAMMultiset<ID> {
+    /// Add the entry of `lookup` to the multiset, positively if it is written
+    /// and negatively if it is read.
+    pub fn add(&mut self, lookup: &RAMLookup<u64, ID>) {
+        let magnitude = lookup.magnitude as i64;
+        if magnitude == 0 {
+            return;
+        }
+        let key = (lookup.table_id, lookup.value.clone());
+        let multiplicity = self.multiplicities.entry(key.clone()).or_insert(0);
+        match lookup.mode {
+            LookupMode::Read => *multiplicity -= magnitude,
+            LookupMode::Write => *multiplicity += magnitude,
+        }
+        if *multiplicity == 0 {
+            self.multiplicities.remove(&key);
+        }
+    }
+
+    /// Returns the entries that have been written but not read, i.e. the
+    /// values of the addresses before the accesses, and the entries that have
+    /// been read but not written, i.e. the values of the addresses after the
+    /// accesses.
+    ///
+    /// Panics if an entry has a multiplicity other than 1 or -1, which means
+    /// that the accesses are not consistent.
+    pub fn into_boundary(self) -> (Vec<RAMEntry<ID>>, Vec<RAMEntry<ID>>) {
+        let mut initial = vec![];
+        let mut final_ = vec![];
+        for (entry, multiplicity) in self.multiplicities {
+            match multiplicity {
+                1 => initial.push(entry),
+                -1 => final_.push(entry),
+                _ => panic!(
+                    "The entry {:?} has multiplicity {}, the accesses to the RAM are not consistent",
+                    entry, multiplicity
+                ),
+            }
+        }
+        (initial, final_)
+    }
+}
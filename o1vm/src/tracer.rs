@@ -0,0 +1,617 @@
+//! Instruction-level tracing of the executions of the witness environments,
+//! and a simple step debugger.
+//!
+//! A [Tracer] attached to a witness environment records, for each executed
+//! instruction, the instruction pointer, the decoded instruction, the symbol it
+//! belongs to, the registers it modified and the memory cells it accessed. The
+//! trace can be printed in a human-readable format, or dumped as one JSON
+//! object per line to be compared with the traces of other implementations,
+//! e.g. the Go implementation of Cannon.
+//!
+//! The execution can also be interrupted on [Breakpoint]s. The debugger then
+//! reads commands from its input (the standard input by default), see
+//! [DEBUGGER_HELP].
+
+use crate::{
+    cannon::{Meta, Symbol},
+    lookups::LookupTableIDs,
+    ramlookup::{LookupMode, RAMLookup},
+};
+use serde::Serialize;
+use std::{
+    collections::HashSet,
+    fmt::{self, Debug, Display},
+    io::{self, BufRead, Write},
+    str::FromStr,
+};
+
+/// The commands accepted by the debugger.
+pub const DEBUGGER_HELP: &str = "\
+Commands:
+  s, step               execute the current instruction and stop before the next one
+  c, continue           run until the next breakpoint
+  r, regs               print the registers
+  m, mem <addr> [len]   print `len` bytes (default 4) of the memory at the hexadecimal address `addr`
+  b, break <step|sym>   add a breakpoint on a step or on the entry of a symbol
+  q, quit               stop the execution
+  h, help               print this message";
+
+/// A register modified by an instruction. The index follows the order of the
+/// iterator over the registers of the interpreter.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct RegisterDiff {
+    pub index: usize,
+    pub before: u32,
+    pub after: u32,
+}
+
+/// A byte of the memory accessed by an instruction.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct MemoryAccess {
+    pub address: u32,
+    pub before: u8,
+    pub after: u8,
+}
+
+/// The trace of the execution of a single instruction.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct StepTrace {
+    pub step: u64,
+    pub pc: u32,
+    /// The encoding of the instruction
+    pub insn: u32,
+    pub instruction: String,
+    pub symbol: Option<String>,
+    pub registers: Vec<RegisterDiff>,
+    pub memory: Vec<MemoryAccess>,
+}
+
+impl Display for StepTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "step={} pc={:#010x} insn={:#010x} instruction={} name={}",
+            self.step,
+            self.pc,
+            self.insn,
+            self.instruction,
+            self.symbol.as_deref().unwrap_or("n/a")
+        )?;
+        for RegisterDiff {
+            index,
+            before,
+            after,
+        } in self.registers.iter()
+        {
+            write!(f, " r{index}={before:#x}->{after:#x}")?;
+        }
+        for MemoryAccess {
+            address,
+            before,
+            after,
+        } in self.memory.iter()
+        {
+            if before == after {
+                write!(f, " mem[{address:#x}]={before:#04x}")?;
+            } else {
+                write!(f, " mem[{address:#x}]={before:#04x}->{after:#04x}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The format of the traces written by a [Tracer].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// One line per instruction, using [Display]
+    Text,
+    /// One JSON object per instruction and per line
+    Jsonl,
+}
+
+impl FromStr for TraceFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(TraceFormat::Text),
+            "jsonl" => Ok(TraceFormat::Jsonl),
+            _ => Err(format!("Unknown trace format {s}, expected text or jsonl")),
+        }
+    }
+}
+
+/// A condition to interrupt the execution before an instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Before the given step, i.e. the normalized instruction counter
+    Step(u64),
+    /// Before the first instruction of the given symbol
+    Symbol(String),
+}
+
+impl FromStr for Breakpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err("Empty breakpoint".to_string());
+        }
+        Ok(s.parse::<u64>()
+            .map_or_else(|_| Breakpoint::Symbol(s.to_string()), Breakpoint::Step))
+    }
+}
+
+/// What the witness environment should do after the debugger returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebuggerAction {
+    /// Execute the instruction
+    Continue,
+    /// Stop the execution, without executing the instruction
+    Quit,
+}
+
+/// The state of a witness environment that can be inspected by the debugger.
+pub trait Inspect {
+    /// The registers, in the order of the iterator over the registers of the
+    /// interpreter.
+    fn registers(&self) -> Vec<u32>;
+
+    /// The byte at `address`, or `None` if the memory page is not allocated.
+    /// It must not allocate any page, as it would modify the state of the VM.
+    fn read_memory(&self, address: u32) -> Option<u8>;
+}
+
+/// Returns the byte at `address` of the paged memory `memory`, as used by the
+/// witness environments, without allocating any page.
+pub fn read_paged_memory(memory: &[(u32, Vec<u8>)], page_size: u32, address: u32) -> Option<u8> {
+    let page = address / page_size;
+    memory
+        .iter()
+        .find(|(page_index, _)| *page_index == page)
+        .map(|(_, data)| data[(address % page_size) as usize])
+}
+
+/// Trace and debug the execution of a witness environment.
+/// The environment calls [Tracer::start_step] before executing an instruction,
+/// [Tracer::record_lookup] for each of its lookups, and [Tracer::end_step]
+/// after.
+pub struct Tracer {
+    output: Option<(Box<dyn Write>, TraceFormat)>,
+    symbols: Option<Meta>,
+    breakpoints: Vec<Breakpoint>,
+    /// The instruction pointers of the breakpoints on symbols, computed when
+    /// the breakpoints are checked for the first time.
+    symbol_breakpoints: Option<HashSet<u32>>,
+    /// Set when the user steps through the execution in the debugger
+    stepping: bool,
+    debugger_input: Box<dyn BufRead>,
+    debugger_output: Box<dyn Write>,
+    current_step: Option<StepTrace>,
+    registers_before: Vec<u32>,
+}
+
+impl Default for Tracer {
+    fn default() -> Self {
+        Tracer {
+            output: None,
+            symbols: None,
+            breakpoints: vec![],
+            symbol_breakpoints: None,
+            stepping: false,
+            debugger_input: Box::new(io::BufReader::new(io::stdin())),
+            debugger_output: Box::new(io::stdout()),
+            current_step: None,
+            registers_before: vec![],
+        }
+    }
+}
+
+impl Tracer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create the tracer described by the command line options: the trace is
+    /// written to the file `trace` (`-` for the standard output), and the
+    /// execution is interrupted on `breakpoints`. Returns `None` if there is
+    /// nothing to trace nor any breakpoint.
+    pub fn from_options(
+        trace: Option<&str>,
+        format: TraceFormat,
+        breakpoints: Vec<Breakpoint>,
+        symbols: Option<Meta>,
+    ) -> Option<Self> {
+        if trace.is_none() && breakpoints.is_empty() {
+            return None;
+        }
+        let mut tracer = Tracer::new().with_breakpoints(breakpoints);
+        if let Some(symbols) = symbols {
+            tracer = tracer.with_symbols(symbols);
+        }
+        match trace {
+            Some("-") => tracer = tracer.with_output(Box::new(io::stdout()), format),
+            Some(path) => {
+                let file = std::fs::File::create(path)
+                    .unwrap_or_else(|_| panic!("Could not create the trace file {path}"));
+                tracer = tracer.with_output(Box::new(io::BufWriter::new(file)), format)
+            }
+            None => (),
+        }
+        Some(tracer)
+    }
+
+    /// Write the trace of each instruction to `output`.
+    pub fn with_output(mut self, output: Box<dyn Write>, format: TraceFormat) -> Self {
+        self.output = Some((output, format));
+        self
+    }
+
+    /// Use `symbols` to name the instruction pointers.
+    pub fn with_symbols(mut self, symbols: Meta) -> Self {
+        self.symbols = Some(symbols);
+        self.symbol_breakpoints = None;
+        self
+    }
+
+    pub fn with_breakpoints(mut self, breakpoints: Vec<Breakpoint>) -> Self {
+        self.breakpoints.extend(breakpoints);
+        self.symbol_breakpoints = None;
+        self
+    }
+
+    /// Read the commands of the debugger from `input`, and write its answers
+    /// to `output`, instead of using the standard input and output.
+    pub fn with_debugger_io(mut self, input: Box<dyn BufRead>, output: Box<dyn Write>) -> Self {
+        self.debugger_input = input;
+        self.debugger_output = output;
+        self
+    }
+
+    fn symbol(&self, pc: u32) -> Option<String> {
+        self.symbols
+            .as_ref()
+            .and_then(|symbols| symbols.find_address_symbol(pc))
+    }
+
+    fn is_breakpoint(&mut self, step: u64, pc: u32) -> bool {
+        if self.stepping || self.breakpoints.contains(&Breakpoint::Step(step)) {
+            return true;
+        }
+        let Tracer {
+            symbols,
+            breakpoints,
+            symbol_breakpoints,
+            ..
+        } = self;
+        symbol_breakpoints
+            .get_or_insert_with(|| {
+                let symbols = symbols.as_ref().map_or(&[][..], |meta| &meta.symbols);
+                breakpoints
+                    .iter()
+                    .filter_map(|breakpoint| match breakpoint {
+                        Breakpoint::Symbol(name) => Some(name),
+                        Breakpoint::Step(_) => None,
+                    })
+                    .flat_map(|name| {
+                        symbols
+                            .iter()
+                            .filter(move |Symbol { name: n, .. }| n == name)
+                            .map(|Symbol { start, .. }| *start)
+                    })
+                    .collect()
+            })
+            .contains(&pc)
+    }
+
+    /// Start the trace of the instruction `instruction`, encoded as `insn`,
+    /// executed at step `step`. If the instruction is a breakpoint, the
+    /// debugger is run until the user decides to resume or stop the execution.
+    pub fn start_step<I: Debug>(
+        &mut self,
+        env: &impl Inspect,
+        step: u64,
+        pc: u32,
+        insn: u32,
+        instruction: &I,
+    ) -> DebuggerAction {
+        let trace = StepTrace {
+            step,
+            pc,
+            insn,
+            instruction: format!("{instruction:?}"),
+            symbol: self.symbol(pc),
+            registers: vec![],
+            memory: vec![],
+        };
+        self.registers_before = env.registers();
+        let action = if self.is_breakpoint(step, pc) {
+            self.debug(env, &trace)
+        } else {
+            DebuggerAction::Continue
+        };
+        self.current_step = Some(trace);
+        action
+    }
+
+    /// Record the accesses to the memory of the current instruction.
+    pub fn record_lookup(&mut self, lookup: &RAMLookup<u64, LookupTableIDs>) {
+        let Some(trace) = self.current_step.as_mut() else {
+            return;
+        };
+        if lookup.table_id != LookupTableIDs::MemoryLookup || lookup.magnitude == 0 {
+            return;
+        }
+        // The accesses write the content before the access, and read the
+        // content after, see `access_memory` in the interpreters.
+        let (address, value) = (lookup.value[0] as u32, lookup.value[2] as u8);
+        match lookup.mode {
+            LookupMode::Write => trace.memory.push(MemoryAccess {
+                address,
+                before: value,
+                after: value,
+            }),
+            LookupMode::Read => {
+                if let Some(access) = trace
+                    .memory
+                    .iter_mut()
+                    .rev()
+                    .find(|access| access.address == address)
+                {
+                    access.after = value
+                }
+            }
+        }
+    }
+
+    /// End the trace of the current instruction, given the registers after its
+    /// execution, and write it to the output.
+    pub fn end_step(&mut self, registers_after: &[u32]) {
+        let Some(mut trace) = self.current_step.take() else {
+            return;
+        };
+        trace.registers = self
+            .registers_before
+            .iter()
+            .zip(registers_after.iter())
+            .enumerate()
+            .filter(|(_, (before, after))| before != after)
+            .map(|(index, (&before, &after))| RegisterDiff {
+                index,
+                before,
+                after,
+            })
+            .collect();
+        if let Some((output, format)) = self.output.as_mut() {
+            let res = match format {
+                TraceFormat::Text => writeln!(output, "{trace}"),
+                TraceFormat::Jsonl => serde_json::to_writer(&mut *output, &trace)
+                    .map_err(io::Error::from)
+                    .and_then(|()| writeln!(output)),
+            };
+            res.expect("Could not write the trace");
+        }
+    }
+
+    /// Run the debugger before the instruction `trace`.
+    fn debug(&mut self, env: &impl Inspect, trace: &StepTrace) -> DebuggerAction {
+        self.stepping = false;
+        let out = &mut self.debugger_output;
+        let _ = writeln!(
+            out,
+            "Break before step={} pc={:#010x} insn={:#010x} instruction={} name={}",
+            trace.step,
+            trace.pc,
+            trace.insn,
+            trace.instruction,
+            trace.symbol.as_deref().unwrap_or("n/a")
+        );
+        loop {
+            let _ = write!(self.debugger_output, "(o1vm) ");
+            let _ = self.debugger_output.flush();
+            let mut line = String::new();
+            // The execution continues when the input is closed
+            if self.debugger_input.read_line(&mut line).unwrap_or(0) == 0 {
+                return DebuggerAction::Continue;
+            }
+            let mut args = line.split_whitespace();
+            let out = &mut self.debugger_output;
+            match args.next() {
+                Some("s" | "step") => {
+                    self.stepping = true;
+                    return DebuggerAction::Continue;
+                }
+                Some("c" | "continue") => return DebuggerAction::Continue,
+                Some("q" | "quit") => return DebuggerAction::Quit,
+                Some("r" | "regs") => {
+                    for (index, value) in env.registers().iter().enumerate() {
+                        let _ = writeln!(out, "r{index}={value:#010x}");
+                    }
+                }
+                Some("m" | "mem") => {
+                    let address = args.next().and_then(|addr| {
+                        u32::from_str_radix(addr.trim_start_matches("0x"), 16).ok()
+                    });
+                    let len = args.next().map_or(Some(4), |len| len.parse::<u32>().ok());
+                    match (address, len) {
+                        (Some(address), Some(len)) => {
+                            let bytes: Vec<String> = (0..len)
+                                .map(|i| {
+                                    env.read_memory(address.wrapping_add(i))
+                                        .map_or("??".to_string(), |byte| format!("{byte:02x}"))
+                                })
+                                .collect();
+                            let _ = writeln!(out, "{address:#010x}: {}", bytes.join(" "));
+                        }
+                        _ => {
+                            let _ = writeln!(out, "Usage: mem <hexadecimal address> [length]");
+                        }
+                    }
+                }
+                Some("b" | "break") => match args.next().map(Breakpoint::from_str) {
+                    Some(Ok(breakpoint)) => {
+                        self.breakpoints.push(breakpoint);
+                        self.symbol_breakpoints = None;
+                    }
+                    _ => {
+                        let _ = writeln!(out, "Usage: break <step|symbol>");
+                    }
+                },
+                None => (),
+                Some(_) => {
+                    let _ = writeln!(out, "{DEBUGGER_HELP}");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[derive(Debug)]
+    enum DummyInstruction {
+        Store8,
+        Nop,
+    }
+
+    struct DummyEnv {
+        registers: Vec<u32>,
+    }
+
+    impl Inspect for DummyEnv {
+        fn registers(&self) -> Vec<u32> {
+            self.registers.clone()
+        }
+
+        fn read_memory(&self, address: u32) -> Option<u8> {
+            (address < 16).then_some(address as u8)
+        }
+    }
+
+    /// A writer whose content can be read after it is given to the tracer
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.borrow().clone()).unwrap()
+        }
+    }
+
+    fn symbols() -> Meta {
+        Meta {
+            symbols: vec![
+                Symbol {
+                    name: "main".to_string(),
+                    start: 0x100,
+                    size: 8,
+                },
+                Symbol {
+                    name: "f".to_string(),
+                    start: 0x108,
+                    size: 8,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_trace_records_register_diffs_and_memory_accesses() {
+        let output = SharedBuffer::default();
+        let mut tracer = Tracer::new()
+            .with_output(Box::new(output.clone()), TraceFormat::Jsonl)
+            .with_symbols(symbols());
+        let env = DummyEnv {
+            registers: vec![0, 1, 2],
+        };
+        let action = tracer.start_step(&env, 3, 0x104, 0xdeadbeef, &DummyInstruction::Store8);
+        assert_eq!(action, DebuggerAction::Continue);
+        tracer.record_lookup(&RAMLookup::write_one(
+            LookupTableIDs::MemoryLookup,
+            vec![0x10, 2, 0xaa],
+        ));
+        tracer.record_lookup(&RAMLookup::read_one(
+            LookupTableIDs::MemoryLookup,
+            vec![0x10, 4, 0xbb],
+        ));
+        // Accesses to the registers are given by the diffs
+        tracer.record_lookup(&RAMLookup::write_one(
+            LookupTableIDs::RegisterLookup,
+            vec![1, 2, 1],
+        ));
+        tracer.end_step(&[0, 5, 2]);
+
+        let trace: serde_json::Value = serde_json::from_str(output.contents().trim()).unwrap();
+        assert_eq!(
+            trace,
+            serde_json::json!({
+                "step": 3,
+                "pc": 0x104,
+                "insn": 0xdeadbeef_u32,
+                "instruction": "Store8",
+                "symbol": "main",
+                "registers": [{ "index": 1, "before": 1, "after": 5 }],
+                "memory": [{ "address": 0x10, "before": 0xaa, "after": 0xbb }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_debugger_stops_on_breakpoints() {
+        let input = "regs\nmem 0x2 2\nstep\nbreak 10\ncontinue\nquit\n";
+        let output = SharedBuffer::default();
+        let mut tracer = Tracer::new()
+            .with_symbols(symbols())
+            .with_breakpoints(vec!["f".parse().unwrap()])
+            .with_debugger_io(
+                Box::new(io::Cursor::new(input.as_bytes().to_vec())),
+                Box::new(output.clone()),
+            );
+        let env = DummyEnv { registers: vec![7] };
+        let mut run = |step, pc| {
+            let action = tracer.start_step(&env, step, pc, 0, &DummyInstruction::Nop);
+            tracer.end_step(&env.registers);
+            action
+        };
+        // No breakpoint in main
+        assert_eq!(run(0, 0x100), DebuggerAction::Continue);
+        assert_eq!(output.contents(), "");
+        // Entry of f: print the registers and the memory, then step
+        assert_eq!(run(1, 0x108), DebuggerAction::Continue);
+        let contents = output.contents();
+        assert!(contents.contains("Break before step=1 pc=0x00000108"));
+        assert!(contents.contains("r0=0x00000007"));
+        assert!(contents.contains("0x00000002: 02 03"));
+        // The user stepped: add a breakpoint at step 10 and continue
+        assert_eq!(run(2, 0x10c), DebuggerAction::Continue);
+        assert!(output.contents().contains("Break before step=2"));
+        assert_eq!(run(5, 0x10c), DebuggerAction::Continue);
+        assert!(!output.contents().contains("Break before step=5"));
+        assert_eq!(run(10, 0x10c), DebuggerAction::Quit);
+    }
+
+    #[test]
+    fn test_parse_breakpoints() {
+        assert_eq!("42".parse(), Ok(Breakpoint::Step(42)));
+        assert_eq!(
+            "runtime.main".parse(),
+            Ok(Breakpoint::Symbol("runtime.main".to_string()))
+        );
+        assert!("".parse::<Breakpoint>().is_err());
+    }
+}
@@ -17,3 +17,20 @@ fn test_correctly_parsing_elf() {
     // Which is the 17th
     assert_eq!(state.memory[0].index, 17);
 }
+
+#[test]
+fn test_parsing_elf_symbols() {
+    let curr_dir = std::env::current_dir().unwrap();
+    let path = curr_dir.join(std::path::PathBuf::from(
+        "resources/programs/riscv32im/bin/fibonacci",
+    ));
+    let meta = o1vm::elf_loader::parse_riscv32_symbols(&path).unwrap();
+
+    // This is the output we get by running objdump -t fibonacci
+    assert_eq!(meta.find_address_symbol(69932), Some("_start".to_string()));
+    assert_eq!(
+        meta.find_address_symbol(69844),
+        Some("fibonacci".to_string())
+    );
+    assert_eq!(meta.find_address_symbol(42), None);
+}
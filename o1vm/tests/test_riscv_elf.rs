@@ -1,8 +1,11 @@
 use mina_curves::pasta::Fp;
-use o1vm::interpreters::riscv32im::{
-    interpreter::{IInstruction, Instruction, RInstruction},
-    witness::Env,
-    PAGE_SIZE,
+use o1vm::{
+    interpreters::riscv32im::{
+        interpreter::{IInstruction, Instruction, RInstruction},
+        witness::Env,
+        PAGE_SIZE,
+    },
+    tracer::{TraceFormat, Tracer},
 };
 
 #[test]
@@ -50,9 +53,7 @@ fn test_no_action() {
     assert_eq!(witness.registers.general_purpose[17], 42);
 }
 
-// FIXME: stop ignoring when all the instructions are implemented.
 #[test]
-#[ignore]
 fn test_fibonacci_7() {
     let curr_dir = std::env::current_dir().unwrap();
     let path = curr_dir.join(std::path::PathBuf::from(
@@ -74,10 +75,7 @@ fn test_fibonacci_7() {
     }
 }
 
-// FIXME: stop ignore when all the instructions necessary for running this
-// program are implemented.
 #[test]
-#[ignore]
 fn test_sll() {
     let curr_dir = std::env::current_dir().unwrap();
     let path = curr_dir.join(std::path::PathBuf::from(
@@ -90,5 +88,92 @@ fn test_sll() {
         witness.step();
     }
 
-    // FIXME: check the state of the registers after the program has run.
+    // Expected output of the program
+    assert_eq!(witness.registers.general_purpose[5], 0x42 << 14)
+}
+
+#[test]
+fn test_is_prime_naive() {
+    let curr_dir = std::env::current_dir().unwrap();
+    let path = curr_dir.join(std::path::PathBuf::from(
+        "resources/programs/riscv32im/bin/is_prime_naive",
+    ));
+    let state = o1vm::elf_loader::parse_riscv32(&path).unwrap();
+    let mut witness = Env::<Fp>::create(PAGE_SIZE.try_into().unwrap(), state);
+
+    while !witness.halt {
+        witness.step();
+    }
+
+    // The program checks that there are 25 prime numbers below 101, and exits
+    // with 0 on success.
+    assert_eq!(witness.registers.general_purpose[10], 0);
+}
+
+#[test]
+fn test_trace_fibonacci_7() {
+    let curr_dir = std::env::current_dir().unwrap();
+    let path = curr_dir.join(std::path::PathBuf::from(
+        "resources/programs/riscv32im/bin/fibonacci-7",
+    ));
+    let trace_path =
+        std::env::temp_dir().join(format!("o1vm-test-trace-{}.jsonl", std::process::id()));
+    let state = o1vm::elf_loader::parse_riscv32(&path).unwrap();
+    let mut witness = Env::<Fp>::create(PAGE_SIZE.try_into().unwrap(), state);
+    witness.tracer = Tracer::from_options(
+        trace_path.to_str(),
+        TraceFormat::Jsonl,
+        vec![],
+        Some(o1vm::elf_loader::parse_riscv32_symbols(&path).unwrap()),
+    );
+
+    let mut steps = 0;
+    while !witness.halt {
+        witness.step();
+        steps += 1;
+    }
+    // Flush the trace
+    witness.tracer = None;
+
+    let trace = std::fs::read_to_string(&trace_path).unwrap();
+    std::fs::remove_file(&trace_path).unwrap();
+    let trace: Vec<serde_json::Value> = trace
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(trace.len(), steps);
+    assert_eq!(trace[0]["pc"], 69932);
+    assert_eq!(trace[0]["symbol"], "_start");
+    assert!(trace.iter().any(|step| step["symbol"] == "fibonacci"));
+    // The result is stored in a0 by the last iteration
+    assert!(trace.iter().any(|step| step["registers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|diff| diff["index"] == 10 && diff["after"] == 13)));
+}
+
+#[test]
+fn test_debugger_quits_on_symbol() {
+    let curr_dir = std::env::current_dir().unwrap();
+    let path = curr_dir.join(std::path::PathBuf::from(
+        "resources/programs/riscv32im/bin/fibonacci-7",
+    ));
+    let state = o1vm::elf_loader::parse_riscv32(&path).unwrap();
+    let mut witness = Env::<Fp>::create(PAGE_SIZE.try_into().unwrap(), state);
+    witness.tracer = Some(
+        Tracer::new()
+            .with_symbols(o1vm::elf_loader::parse_riscv32_symbols(&path).unwrap())
+            .with_breakpoints(vec!["fibonacci".parse().unwrap()])
+            .with_debugger_io(
+                Box::new(std::io::Cursor::new(b"quit\n".to_vec())),
+                Box::new(std::io::sink()),
+            ),
+    );
+
+    while !witness.halt {
+        witness.step();
+    }
+    // The execution stopped before the first instruction of `fibonacci`
+    assert_eq!(witness.registers.current_instruction_pointer, 69844);
 }
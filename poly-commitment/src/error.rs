@@ -6,4 +6,62 @@ pub enum CommitmentError {
         "the length of the given blinders ({0}) don't match the length of the commitment ({1})"
     )]
     BlindersDontMatch(usize, usize),
+    #[error("the verifier SRS contains {available} elements, but {requested} are needed")]
+    VerifierSrsTooSmall { available: usize, requested: usize },
+}
+
+/// Errors that can occur when loading a KZG SRS from a powers-of-tau
+/// ceremony file, see [crate::kzg::PairingSRS::from_ptau].
+#[derive(Error, Debug)]
+pub enum PtauError {
+    #[error("failed to read the ptau file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("the file is not a ptau file")]
+    InvalidMagic,
+    #[error("the version {0} of the ptau format is not supported")]
+    UnsupportedVersion(u32),
+    #[error("the section {0} of the ptau file is missing")]
+    MissingSection(u32),
+    #[error("the section {0} of the ptau file is malformed")]
+    MalformedSection(u32),
+    #[error("the ptau file is not defined over the base field of the curve")]
+    WrongCurve,
+    #[error("the ptau file contains {available} powers in the section {section}, but {requested} are needed")]
+    NotEnoughPowers {
+        section: u32,
+        available: usize,
+        requested: usize,
+    },
+    #[error("the point {index} of the section {section} is not in the prime order subgroup")]
+    InvalidPoint { section: u32, index: usize },
+    #[error("the first point of the section {0} is not the generator")]
+    InvalidGenerator(u32),
+    #[error("the points of the ptau file are not consistent powers of tau")]
+    InconsistentPowers,
+}
+
+/// Errors that can occur when reading or writing a memory-mapped SRS file or
+/// its cache of Lagrange bases, see [crate::mmap_srs::MmapSRS].
+#[derive(Error, Debug)]
+pub enum SrsFileError {
+    #[error("failed to access the SRS file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to serialize a point: {0}")]
+    Serialization(#[from] ark_serialize::SerializationError),
+    #[error("the file is not a {0} file")]
+    InvalidMagic(&'static str),
+    #[error("the version {0} of the SRS file format is not supported")]
+    UnsupportedVersion(u32),
+    #[error("the points of the file are encoded on {found} bytes, but {expected} are expected for this curve")]
+    WrongPointSize { expected: usize, found: usize },
+    #[error("the file has {found} bytes, but its header describes {expected} bytes")]
+    WrongLength { expected: u64, found: u64 },
+    #[error("the blinding point of the SRS file is not a valid curve point")]
+    InvalidBlinder,
+    #[error("the point {0} of the SRS file is not a valid curve point")]
+    InvalidPoint(usize),
+    #[error("the points of the SRS file do not match the fingerprint of its header")]
+    InvalidFingerprint,
+    #[error("the cached Lagrange basis for the domain size {0} was not computed from this SRS")]
+    StaleLagrangeBasis(usize),
 }
@@ -291,8 +291,6 @@ impl<G: CommitmentCurve> SRS<G> {
     /// This function is unsafe because it creates a trusted setup and the toxic
     /// waste is passed as a parameter.
     pub unsafe fn create_trusted_setup(x: G::ScalarField, depth: usize) -> Self {
-        let m = G::Map::setup();
-
         let mut x_pow = G::ScalarField::one();
         let g: Vec<_> = (0..depth)
             .map(|_| {
@@ -302,6 +300,18 @@ impl<G: CommitmentCurve> SRS<G> {
             })
             .collect();
 
+        Self::from_trusted_setup_powers(g)
+    }
+
+    /// This function creates a trusted-setup SRS instance from the powers
+    /// `g^{x^i}` of some toxic waste `x`, for instance the result of a
+    /// ceremony. The blinder is the same as in `create_trusted_setup`.
+    ///
+    /// The caller is responsible for checking that the given points are
+    /// consistent powers.
+    pub fn from_trusted_setup_powers(g: Vec<G>) -> Self {
+        let m = G::Map::setup();
+
         // Compute a blinder
         let h = {
             let mut h = Blake2b512::new();
@@ -848,18 +858,8 @@ impl<G: CommitmentCurve> SRS<G> {
 
         // For each chunk
         for i in 0..num_elems {
-            // Initialize the vector with zero curve points
-            let mut lg: Vec<<G as AffineRepr>::Group> = vec![<G as AffineRepr>::Group::zero(); n];
-            // Overwrite the terms corresponding to that chunk with the SRS curve points
-            let start_offset = i * srs_size;
-            let num_terms = min((i + 1) * srs_size, n) - start_offset;
-            for j in 0..num_terms {
-                lg[start_offset + j] = self.g[j].into_group()
-            }
-            // Apply the IFFT
-            domain.ifft_in_place(&mut lg);
             // Append the 'partial Langrange polynomials' to the vector of elems chunks
-            chunks.push(<G as AffineRepr>::Group::normalize_batch(lg.as_mut_slice()));
+            chunks.push(lagrange_basis_chunk(&self.g, domain, i));
         }
 
         (0..n)
@@ -870,6 +870,30 @@ impl<G: CommitmentCurve> SRS<G> {
     }
 }
 
+/// Computes the `i`-th chunk of the commitments to the Lagrange polynomials
+/// of `domain`, given the points `g` of an SRS. See `SRS::lagrange_basis` for
+/// the details of the computation. When the domain is smaller than the SRS,
+/// `g` may be truncated to the size of the domain.
+pub(crate) fn lagrange_basis_chunk<G: CommitmentCurve>(
+    g: &[G],
+    domain: D<G::ScalarField>,
+    i: usize,
+) -> Vec<G> {
+    let n = domain.size();
+    let srs_size = g.len();
+    // Initialize the vector with zero curve points
+    let mut lg: Vec<<G as AffineRepr>::Group> = vec![<G as AffineRepr>::Group::zero(); n];
+    // Overwrite the terms corresponding to that chunk with the SRS curve points
+    let start_offset = i * srs_size;
+    let num_terms = min((i + 1) * srs_size, n) - start_offset;
+    for j in 0..num_terms {
+        lg[start_offset + j] = g[j].into_group()
+    }
+    // Apply the IFFT
+    domain.ifft_in_place(&mut lg);
+    <G as AffineRepr>::Group::normalize_batch(lg.as_mut_slice())
+}
+
 #[serde_as]
 #[derive(Clone, Debug, Serialize, Deserialize, Default, PartialEq)]
 #[serde(bound = "G: ark_serialize::CanonicalDeserialize + ark_serialize::CanonicalSerialize")]
@@ -976,6 +1000,226 @@ impl<G: AffineRepr> OpeningProof<G> {
     }
 }
 
+/// The claim, left after the succinct check of an IPA opening proof, that
+/// the final folded commitment base `sg` is the commitment to the polynomial
+/// whose coefficients are given by `b_poly_coefficients(chals)`. Checking it
+/// requires an MSM of the size of the SRS.
+#[derive(Clone, Debug)]
+pub struct DeferredIpaCheck<G: AffineRepr> {
+    /// The challenges of the rounds of the opening proof, mapped with the
+    /// endomorphism
+    pub chals: Vec<G::ScalarField>,
+    /// The final folded commitment base
+    pub sg: G,
+}
+
+impl<G: CommitmentCurve> SRS<G>
+where
+    G::BaseField: PrimeField,
+{
+    /// Perform the part of the verification of an opening proof that takes
+    /// a time logarithmic in the size of the SRS, i.e. check
+    /// ```text
+    /// c Q + delta = z1 (sg + b0 U) + z2 H
+    /// ```
+    /// where `sg` is the final commitment base given by the proof, see
+    /// [SRS::verify]. Returns the remaining claim on `sg`, or `None` if the
+    /// check fails.
+    pub fn succinct_check<EFqSponge>(
+        &self,
+        group_map: &G::Map,
+        proof: &mut BatchEvaluationProof<G, EFqSponge, OpeningProof<G>>,
+    ) -> Option<DeferredIpaCheck<G>>
+    where
+        EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>,
+    {
+        let BatchEvaluationProof {
+            sponge,
+            evaluation_points,
+            polyscale,
+            evalscale,
+            evaluations,
+            opening,
+            combined_inner_product,
+        } = proof;
+
+        if opening.lr.len() != math::ceil_log2(self.g.len()) {
+            return None;
+        }
+
+        let (_, endo_r) = endos::<G>();
+
+        sponge.absorb_fr(&[shift_scalar::<G>(*combined_inner_product)]);
+
+        let u_base: G = {
+            let t = sponge.challenge_fq();
+            let (x, y) = group_map.to_group(t);
+            G::of_coordinates(x, y)
+        };
+
+        let Challenges { chal, chal_inv } = opening.challenges::<EFqSponge>(&endo_r, sponge);
+
+        sponge.absorb_g(&[opening.delta]);
+        let c = ScalarChallenge(sponge.challenge()).to_field(&endo_r);
+
+        let b0 = {
+            let mut scale = G::ScalarField::one();
+            let mut res = G::ScalarField::zero();
+            for &e in evaluation_points.iter() {
+                res += &(scale * b_poly(&chal, e));
+                scale *= *evalscale;
+            }
+            res
+        };
+
+        // c Q + delta - z1 (sg + b0 U) - z2 H, where
+        // Q = sum_j (chal_invs[j] L_j + chals[j] R_j) + P_prime
+        // and P_prime = combined commitment + combined_inner_product * U
+        let mut points = vec![opening.delta, opening.sg, u_base, self.h];
+        let mut scalars = vec![
+            G::ScalarField::one(),
+            -opening.z1,
+            c * *combined_inner_product - opening.z1 * b0,
+            -opening.z2,
+        ];
+        for ((l, r), (u_inv, u)) in opening.lr.iter().zip(chal_inv.iter().zip(chal.iter())) {
+            points.push(*l);
+            scalars.push(c * u_inv);
+            points.push(*r);
+            scalars.push(c * u);
+        }
+        combine_commitments(evaluations, &mut scalars, &mut points, *polyscale, c);
+
+        let scalars: Vec<_> = scalars.iter().map(|x| x.into_bigint()).collect();
+        if G::Group::msm_bigint(&points, &scalars) != G::Group::zero() {
+            return None;
+        }
+
+        Some(DeferredIpaCheck {
+            chals: chal,
+            sg: opening.sg,
+        })
+    }
+}
+
+/// An accumulator of IPA opening proofs, in the style of
+/// [Halo](https://eprint.iacr.org/2019/1021).
+///
+/// The verification of an opening proof is split in two parts: the succinct
+/// check, see [SRS::succinct_check], and the check that the final commitment
+/// base `sg` is correct, which requires an MSM of the size of the SRS. The
+/// accumulator performs the former when absorbing a proof, and keeps a random
+/// linear combination of the latter, which can be decided at once by
+/// [IpaAccumulator::decide]. Accumulators can also be merged, for instance
+/// to distribute the verification of many proofs.
+#[derive(Clone, Debug)]
+pub struct IpaAccumulator<G: CommitmentCurve> {
+    /// The random combination of the coefficients of the polynomials whose
+    /// commitments are claimed to be the `sg`s
+    coefficients: Vec<G::ScalarField>,
+    /// The same random combination of the `sg`s
+    sg: G::Group,
+    /// Whether the succinct checks of the absorbed proofs all succeeded
+    valid: bool,
+    /// The number of absorbed claims
+    len: usize,
+}
+
+impl<G: CommitmentCurve> IpaAccumulator<G>
+where
+    G::BaseField: PrimeField,
+{
+    /// Create an empty accumulator for the SRS `srs`.
+    pub fn new(srs: &SRS<G>) -> Self {
+        Self {
+            coefficients: vec![G::ScalarField::zero(); srs.g.len()],
+            sg: G::Group::zero(),
+            valid: true,
+            len: 0,
+        }
+    }
+
+    /// The number of claims absorbed by the accumulator.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the accumulator has not absorbed any claim.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Absorb an opening proof, with its evaluations and the state of the
+    /// sponge, after performing its succinct check. Returns `false` if the
+    /// succinct check fails, in which case the accumulator will be rejected.
+    pub fn absorb<EFqSponge, RNG>(
+        &mut self,
+        srs: &SRS<G>,
+        group_map: &G::Map,
+        proof: &mut BatchEvaluationProof<G, EFqSponge, OpeningProof<G>>,
+        rng: &mut RNG,
+    ) -> bool
+    where
+        EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>,
+        RNG: RngCore + CryptoRng,
+    {
+        match srs.succinct_check(group_map, proof) {
+            Some(deferred) => {
+                self.absorb_deferred(&deferred, rng);
+                true
+            }
+            None => {
+                self.valid = false;
+                false
+            }
+        }
+    }
+
+    /// Absorb a claim left by a succinct check, for instance the challenges
+    /// and the commitment of a kimchi `RecursionChallenge`.
+    pub fn absorb_deferred<RNG>(&mut self, deferred: &DeferredIpaCheck<G>, rng: &mut RNG)
+    where
+        RNG: RngCore + CryptoRng,
+    {
+        let r = G::ScalarField::rand(rng);
+        let s = b_poly_coefficients(&deferred.chals);
+        self.coefficients
+            .par_iter_mut()
+            .zip(s.par_iter())
+            .for_each(|(acc, s_i)| *acc += r * s_i);
+        self.sg += deferred.sg.mul(r);
+        self.len += 1;
+    }
+
+    /// Merge the accumulator `other` into this one. Both accumulators must
+    /// have been created for the same SRS.
+    pub fn merge<RNG>(&mut self, other: &Self, rng: &mut RNG)
+    where
+        RNG: RngCore + CryptoRng,
+    {
+        assert_eq!(self.coefficients.len(), other.coefficients.len());
+        let r = G::ScalarField::rand(rng);
+        self.coefficients
+            .par_iter_mut()
+            .zip(other.coefficients.par_iter())
+            .for_each(|(acc, c)| *acc += r * c);
+        self.sg += other.sg * r;
+        self.valid &= other.valid;
+        self.len += other.len;
+    }
+
+    /// Decide all the absorbed claims at once, with a single MSM of the size
+    /// of the SRS. Returns `true` if all the absorbed proofs are valid, except
+    /// with negligible probability.
+    pub fn decide(&self, srs: &SRS<G>) -> bool {
+        if !self.valid {
+            return false;
+        }
+        let scalars: Vec<_> = self.coefficients.iter().map(|x| x.into_bigint()).collect();
+        G::Group::msm_bigint(&srs.g, &scalars) == self.sg
+    }
+}
+
 #[cfg(feature = "ocaml_types")]
 pub mod caml {
     use super::OpeningProof;
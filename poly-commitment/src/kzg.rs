@@ -117,14 +117,18 @@ impl<Pair: Pairing> Clone for KZGProof<Pair> {
 /// Define a structured reference string (i.e. SRS) for the KZG protocol.
 /// The SRS consists of powers of an element `g^x` for some toxic waste `x`.
 ///
-/// The SRS is formed using what we call a "trusted setup". For now, the setup
-/// is created using the method `create_trusted_setup`.
+/// The SRS is formed using what we call a "trusted setup". It can be loaded
+/// from the transcript of a powers-of-tau ceremony using the method
+/// `from_ptau`, or created from a given toxic waste for tests using the method
+/// `create_trusted_setup`.
 pub struct PairingSRS<Pair: Pairing> {
     /// The full SRS is the one used by the prover. Can be seen as the "proving
     /// key"/"secret key"
     pub full_srs: SRS<Pair::G1Affine>,
     /// SRS to be used by the verifier. Can be seen as the "verification
     /// key"/"public key".
+    /// Opening a [KZGProof] at `k` points requires `k + 1` elements, while a
+    /// [ShplonkProof] only requires two, whatever the number of points.
     pub verifier_srs: SRS<Pair::G2Affine>,
 }
 
@@ -138,9 +142,23 @@ impl<
     /// Create a trusted setup for the KZG protocol.
     /// The setup is created using a toxic waste `toxic_waste` and a depth
     /// `depth`.
+    /// The verifier SRS has 3 elements, which is enough to verify a [KZGProof]
+    /// opened at up to two points. Use
+    /// [PairingSRS::create_trusted_setup_with_verifier_depth] for more points.
     pub fn create_trusted_setup(toxic_waste: F, depth: usize) -> Self {
+        Self::create_trusted_setup_with_verifier_depth(toxic_waste, depth, 3)
+    }
+
+    /// Create a trusted setup for the KZG protocol, with a verifier SRS of
+    /// `verifier_depth` elements. Verifying a [KZGProof] opened at `k` points
+    /// requires `verifier_depth > k`.
+    pub fn create_trusted_setup_with_verifier_depth(
+        toxic_waste: F,
+        depth: usize,
+        verifier_depth: usize,
+    ) -> Self {
         let full_srs = unsafe { SRS::create_trusted_setup(toxic_waste, depth) };
-        let verifier_srs = unsafe { SRS::create_trusted_setup(toxic_waste, 3) };
+        let verifier_srs = unsafe { SRS::create_trusted_setup(toxic_waste, verifier_depth) };
         Self {
             full_srs,
             verifier_srs,
@@ -220,7 +238,11 @@ impl<
             combined_inner_product: _,
         } in batch.iter()
         {
-            if !opening.verify(srs, evaluations, *polyscale, evaluation_points) {
+            // A verifier SRS too small to check the proof rejects it.
+            if !matches!(
+                opening.verify(srs, evaluations, *polyscale, evaluation_points),
+                Ok(true)
+            ) {
                 return false;
             }
         }
@@ -332,38 +354,38 @@ impl<
     }
 }
 
-/// The polynomial that evaluates to each of `evals` for the respective `elm`s.
-/// For now, only works for 2 evaluations points.
+/// The polynomial that evaluates to each of `evals` for the respective `elm`s,
+/// i.e. the Lagrange interpolation polynomial of degree `elm.len() - 1`.
 /// `elm` is the vector of evaluation points and `evals` is the vector of
-/// evaluations at those points.
+/// evaluations at those points. The evaluation points must be distinct.
 fn eval_polynomial<F: PrimeField>(elm: &[F], evals: &[F]) -> DensePolynomial<F> {
     assert_eq!(elm.len(), evals.len());
-    let (zeta, zeta_omega) = if elm.len() == 2 {
-        (elm[0], elm[1])
-    } else {
-        todo!()
-    };
-    let (eval_zeta, eval_zeta_omega) = if evals.len() == 2 {
-        (evals[0], evals[1])
-    } else {
-        todo!()
-    };
-
-    // The polynomial that evaluates to `p(ζ)` at `ζ` and `p(ζω)` at
-    // `ζω`.
-    // We write `p(x) = a + bx`, which gives
+    // We write `p(x) = \sum_i evals_i * l_i(x)`, where
     // ```text
-    // p(ζ) = a + b * ζ
-    // p(ζω) = a + b * ζω
+    // l_i(x) = \prod_{j != i} (x - elm_j) / (elm_i - elm_j)
     // ```
-    // and so
-    // ```text
-    // b = (p(ζω) - p(ζ)) / (ζω - ζ)
-    // a = p(ζ) - b * ζ
-    // ```
-    let b = (eval_zeta_omega - eval_zeta) / (zeta_omega - zeta);
-    let a = eval_zeta - b * zeta;
-    DensePolynomial::from_coefficients_slice(&[a, b])
+    // is the Lagrange polynomial that is `1` at `elm_i` and `0` at the other
+    // points.
+    elm.iter()
+        .zip(evals.iter())
+        .enumerate()
+        .map(|(i, (elm_i, eval_i))| {
+            let (numerator, denominator) = elm.iter().enumerate().filter(|(j, _)| *j != i).fold(
+                (
+                    DensePolynomial::from_coefficients_slice(&[F::one()]),
+                    F::one(),
+                ),
+                |(numerator, denominator), (_, elm_j)| {
+                    (
+                        &numerator
+                            * &DensePolynomial::from_coefficients_slice(&[-(*elm_j), F::one()]),
+                        denominator * (*elm_i - elm_j),
+                    )
+                },
+            );
+            &numerator * (*eval_i / denominator)
+        })
+        .fold(DensePolynomial::zero(), |acc, p| &acc + &p)
 }
 
 /// The polynomial that evaluates to `0` at the evaluation points.
@@ -388,8 +410,10 @@ impl<
     /// - `plnms`: the list of polynomials to open.
     /// The type is simply an alias to handle the polynomials in evaluations or
     /// coefficients forms.
-    /// - `elm`: vector of evaluation points. Note that it only works for two
-    /// elements for now.
+    /// - `elm`: vector of evaluation points. The verifier needs
+    /// `elm.len() + 1` elements in the verifier SRS, which means that only
+    /// two points are supported with `PairingSRS::create_trusted_setup`. See
+    /// [ShplonkProof] for an arbitrary number of points.
     /// - `polyscale`: a challenge to batch the polynomials.
     pub fn create<D: EvaluationDomain<F>>(
         srs: &PairingSRS<Pair>,
@@ -426,16 +450,24 @@ impl<
         })
     }
 
-    /// Verify a proof. Note that it only works for two elements for now, i.e.
-    /// elm must be of size 2.
-    /// Also, chunking is not supported.
+    /// Verify a proof. Note that the verifier SRS must contain at least
+    /// `elm.len() + 1` elements, see [KZGProof::create], otherwise an error is
+    /// returned.
     pub fn verify(
         &self,
         srs: &PairingSRS<Pair>,           // SRS
         evaluations: &Vec<Evaluation<G>>, // commitments to the polynomials
         polyscale: F,                     // scaling factor for polynoms
         elm: &[F],                        // vector of evaluation points
-    ) -> bool {
+    ) -> Result<bool, CommitmentError> {
+        // The divisor polynomial has degree `elm.len()`, and would be
+        // committed in several chunks otherwise.
+        if srs.verifier_srs.g.len() <= elm.len() {
+            return Err(CommitmentError::VerifierSrsTooSmall {
+                available: srs.verifier_srs.g.len(),
+                requested: elm.len() + 1,
+            });
+        }
         let poly_commitment: G::Group = {
             let mut scalars: Vec<F> = Vec::new();
             let mut points = Vec::new();
@@ -482,6 +514,332 @@ impl<
         // Note that the unwrap cannot fail as the output of a miller loop is non zero
         let res = Pair::multi_pairing(to_loop_left, to_loop_right);
 
+        Ok(res.is_zero())
+    }
+}
+
+/// A KZG opening proof for an arbitrary number of evaluation points,
+/// following the scheme of the paper [Efficient polynomial commitment schemes
+/// for multiple points and polynomials](https://eprint.iacr.org/2020/081)
+/// by Boneh, Drake, Fisch and Gabizon, often referred to as SHPLONK.
+///
+/// The polynomials are given in groups, and each group is opened at its own
+/// set of points. For each group `i`, the polynomials are combined with
+/// `polyscale` into `f_i`, as in [KZGProof], and `r_i` is the polynomial
+/// interpolating `f_i` on the set of points `S_i`. Then, with `T` the union of
+/// the sets of points, `Z_S` the polynomial vanishing on `S`, and `γ` and `z`
+/// two challenges, the proof consists of the commitments to
+/// ```text
+/// h(X) = \sum_i γ^i (f_i(X) - r_i(X)) / Z_{S_i}(X)
+/// L(X) / (X - z)
+/// ```
+/// where
+/// ```text
+/// L(X) = \sum_i γ^i Z_{T \ S_i}(z) (f_i(X) - r_i(z)) - Z_T(z) h(X)
+/// ```
+/// vanishes at `z`. Unlike [KZGProof], the verifier only needs `[1]` and
+/// `[x]` in G2, whatever the number of points.
+///
+/// Since each `f_i` is combined from the chunks of the polynomials, the
+/// commitments can have any number of chunks.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(
+    bound = "Pair::G1Affine: ark_serialize::CanonicalDeserialize + ark_serialize::CanonicalSerialize"
+)]
+pub struct ShplonkProof<Pair: Pairing> {
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    /// The commitment to `h`
+    pub quotient: Pair::G1Affine,
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    /// The commitment to `L(X) / (X - z)`
+    pub opening: Pair::G1Affine,
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    /// The blinding factor of the commitment to `L`
+    pub blinding: <Pair::G1Affine as AffineRepr>::ScalarField,
+}
+
+impl<Pair: Pairing> Default for ShplonkProof<Pair> {
+    fn default() -> Self {
+        Self {
+            quotient: Pair::G1Affine::generator(),
+            opening: Pair::G1Affine::generator(),
+            blinding: <Pair::G1Affine as AffineRepr>::ScalarField::zero(),
+        }
+    }
+}
+
+impl<Pair: Pairing> Clone for ShplonkProof<Pair> {
+    fn clone(&self) -> Self {
+        Self {
+            quotient: self.quotient,
+            opening: self.opening,
+            blinding: self.blinding,
+        }
+    }
+}
+
+impl<
+        F: PrimeField,
+        G: CommitmentCurve<ScalarField = F>,
+        G2: CommitmentCurve<ScalarField = F>,
+        Pair: Pairing<G1Affine = G, G2Affine = G2>,
+    > crate::OpenProof<G> for ShplonkProof<Pair>
+{
+    type SRS = PairingSRS<Pair>;
+
+    /// Open all the polynomials at all the points `elm`. See
+    /// [ShplonkProof::create] to use different sets of points.
+    /// group_maps, rng and evalscale are not used. The parameters are kept to
+    /// fit the trait and to be used generically.
+    fn open<EFqSponge, RNG, D: EvaluationDomain<F>>(
+        srs: &Self::SRS,
+        _group_map: &<G as CommitmentCurve>::Map,
+        plnms: PolynomialsToCombine<G, D>,
+        elm: &[<G as AffineRepr>::ScalarField],
+        polyscale: <G as AffineRepr>::ScalarField,
+        _evalscale: <G as AffineRepr>::ScalarField,
+        mut sponge: EFqSponge,
+        _rng: &mut RNG,
+    ) -> Self
+    where
+        EFqSponge: Clone + FqSponge<<G as AffineRepr>::BaseField, G, F>,
+        RNG: RngCore + CryptoRng,
+    {
+        ShplonkProof::create(srs, &[(plnms, elm)], polyscale, &mut sponge).unwrap()
+    }
+
+    /// Verify all the proofs of the batch with a single pairing check, by
+    /// combining the individual checks with random coefficients.
+    fn verify<EFqSponge, RNG>(
+        srs: &Self::SRS,
+        _group_map: &G::Map,
+        batch: &mut [BatchEvaluationProof<G, EFqSponge, Self>],
+        rng: &mut RNG,
+    ) -> bool
+    where
+        EFqSponge: FqSponge<G::BaseField, G, F>,
+        RNG: RngCore + CryptoRng,
+    {
+        let mut lhs = G::Group::zero();
+        let mut rhs = G::Group::zero();
+        for (i, proof) in batch.iter_mut().enumerate() {
+            let Some(numerator) = proof.opening.numerator_commitment(
+                srs,
+                &[(&proof.evaluations, &proof.evaluation_points)],
+                proof.polyscale,
+                &mut proof.sponge,
+            ) else {
+                return false;
+            };
+            // The first check does not need to be randomized.
+            let r = if i == 0 { F::one() } else { F::rand(rng) };
+            lhs += numerator * r;
+            rhs += proof.opening.opening * r;
+        }
+        Self::pairing_check(srs, lhs, rhs)
+    }
+}
+
+impl<
+        F: PrimeField,
+        G: CommitmentCurve<ScalarField = F>,
+        G2: CommitmentCurve<ScalarField = F>,
+        Pair: Pairing<G1Affine = G, G2Affine = G2>,
+    > ShplonkProof<Pair>
+{
+    /// Create a proof opening each group of polynomials at its own set of
+    /// points.
+    /// Parameters:
+    /// - `srs`: the structured reference string used to commit
+    /// to the polynomials
+    /// - `openings`: the groups of polynomials to open, with the points to
+    /// open them at. The points of a group must be distinct.
+    /// - `polyscale`: a challenge to batch the polynomials of a group.
+    /// - `sponge`: the sponge used to coin the challenges `γ` and `z`. It is
+    /// supposed to have absorbed the commitments and the evaluations, and the
+    /// verifier must use a sponge in the same state.
+    ///
+    /// Returns `None` if one of the polynomials does not agree with the
+    /// interpolation of its evaluations, which cannot happen for honest
+    /// inputs.
+    pub fn create<EFqSponge, D: EvaluationDomain<F>>(
+        srs: &PairingSRS<Pair>,
+        openings: &[(PolynomialsToCombine<G, D>, &[F])],
+        polyscale: F,
+        sponge: &mut EFqSponge,
+    ) -> Option<Self>
+    where
+        EFqSponge: FqSponge<G::BaseField, G, F>,
+    {
+        let gamma = sponge.challenge();
+        let all_points = union_points(openings.iter().map(|(_, elm)| *elm));
+
+        let mut combined = Vec::with_capacity(openings.len());
+        let mut quotient_poly = DensePolynomial::zero();
+        let mut gamma_i = F::one();
+        for (plnms, elm) in openings.iter() {
+            let (p, blinding) = combine_polys::<G, D>(plnms, polyscale, srs.full_srs.g.len());
+            let evals: Vec<_> = elm.iter().map(|pt| p.evaluate(pt)).collect();
+            let eval_polynomial = eval_polynomial(elm, &evals);
+            let (quotient, remainder) = DenseOrSparsePolynomial::divide_with_q_and_r(
+                &(&p - &eval_polynomial).into(),
+                &divisor_polynomial(elm).into(),
+            )?;
+            if !remainder.is_zero() {
+                return None;
+            }
+            quotient_poly += &(&quotient * gamma_i);
+            combined.push((p, blinding, eval_polynomial));
+            gamma_i *= gamma;
+        }
+        let quotient = srs
+            .full_srs
+            .commit_non_hiding(&quotient_poly, 1)
+            .get_first_chunk();
+
+        sponge.absorb_g(&[quotient]);
+        let z = sponge.challenge();
+
+        let mut numerator_poly = &quotient_poly * -vanishing_eval(&all_points, z);
+        let mut blinding = F::zero();
+        let mut gamma_i = F::one();
+        for ((p, p_blinding, eval_polynomial), (_, elm)) in combined.iter().zip(openings.iter()) {
+            let scale = gamma_i * vanishing_eval_except(&all_points, elm, z);
+            numerator_poly +=
+                &(&(p - &DensePolynomial::from_coefficients_slice(
+                    &[eval_polynomial.evaluate(&z)],
+                )) * scale);
+            blinding += *p_blinding * scale;
+            gamma_i *= gamma;
+        }
+        let (opening_poly, remainder) = DenseOrSparsePolynomial::divide_with_q_and_r(
+            &numerator_poly.into(),
+            &divisor_polynomial(&[z]).into(),
+        )?;
+        if !remainder.is_zero() {
+            return None;
+        }
+        let opening = srs
+            .full_srs
+            .commit_non_hiding(&opening_poly, 1)
+            .get_first_chunk();
+
+        Some(ShplonkProof {
+            quotient,
+            opening,
+            blinding,
+        })
+    }
+
+    /// Verify a proof created with [ShplonkProof::create].
+    /// Parameters:
+    /// - `srs`: the structured reference string
+    /// - `openings`: for each group, the commitments to the polynomials with
+    /// their (chunked) evaluations, and the points they are evaluated at
+    /// - `polyscale`: scaling factor for polynoms
+    /// - `sponge`: a sponge in the same state as the one given to the prover
+    pub fn verify<EFqSponge>(
+        &self,
+        srs: &PairingSRS<Pair>,
+        openings: &[(&Vec<Evaluation<G>>, &Vec<F>)],
+        polyscale: F,
+        sponge: &mut EFqSponge,
+    ) -> bool
+    where
+        EFqSponge: FqSponge<G::BaseField, G, F>,
+    {
+        match self.numerator_commitment(srs, openings, polyscale, sponge) {
+            Some(numerator) => Self::pairing_check(srs, numerator, self.opening.into_group()),
+            None => false,
+        }
+    }
+
+    /// Compute the commitment to `L(X) + z L(X) / (X - z)`, which the
+    /// verifier checks to be the commitment to `X L(X) / (X - z)`.
+    /// Returns `None` if the evaluations are malformed.
+    fn numerator_commitment<EFqSponge>(
+        &self,
+        srs: &PairingSRS<Pair>,
+        openings: &[(&Vec<Evaluation<G>>, &Vec<F>)],
+        polyscale: F,
+        sponge: &mut EFqSponge,
+    ) -> Option<G::Group>
+    where
+        EFqSponge: FqSponge<G::BaseField, G, F>,
+    {
+        let gamma = sponge.challenge();
+        sponge.absorb_g(&[self.quotient]);
+        let z = sponge.challenge();
+
+        let all_points = union_points(openings.iter().map(|(_, elm)| elm.as_slice()));
+
+        let mut scalars: Vec<F> = Vec::new();
+        let mut points: Vec<G> = Vec::new();
+        let mut eval_at_z = F::zero();
+        let mut gamma_i = F::one();
+        for (evaluations, elm) in openings.iter() {
+            let evals = combine_evaluations(evaluations, polyscale);
+            if evals.len() != elm.len() {
+                return None;
+            }
+            let scale = gamma_i * vanishing_eval_except(&all_points, elm, z);
+            combine_commitments(evaluations, &mut scalars, &mut points, polyscale, scale);
+            eval_at_z += scale * eval_polynomial(elm, &evals).evaluate(&z);
+            gamma_i *= gamma;
+        }
+        scalars.push(-eval_at_z);
+        points.push(srs.full_srs.g[0]);
+        scalars.push(-vanishing_eval(&all_points, z));
+        points.push(self.quotient);
+        scalars.push(-self.blinding);
+        points.push(srs.full_srs.h);
+        scalars.push(z);
+        points.push(self.opening);
+
+        let scalars: Vec<_> = scalars.iter().map(|x| x.into_bigint()).collect();
+        Some(G::Group::msm_bigint(&points, &scalars))
+    }
+
+    /// Check that `e(numerator, [1]) = e(opening, [x])`.
+    fn pairing_check(srs: &PairingSRS<Pair>, numerator: G::Group, opening: G::Group) -> bool {
+        // We compute the result of the multiplication of two miller loop,
+        // to apply only one final exponentation
+        let to_loop_left = [
+            ark_ec::pairing::prepare_g1::<Pair>(numerator),
+            ark_ec::pairing::prepare_g1::<Pair>(opening.neg()),
+        ];
+        let to_loop_right = [
+            ark_ec::pairing::prepare_g2::<Pair>(srs.verifier_srs.g[0]),
+            ark_ec::pairing::prepare_g2::<Pair>(srs.verifier_srs.g[1]),
+        ];
+        let res = Pair::multi_pairing(to_loop_left, to_loop_right);
+
         res.is_zero()
     }
 }
+
+/// The union of the sets of points, without duplicates.
+fn union_points<'a, F: PrimeField>(sets: impl Iterator<Item = &'a [F]>) -> Vec<F> {
+    let mut all_points: Vec<F> = Vec::new();
+    for pt in sets.flatten() {
+        if !all_points.contains(pt) {
+            all_points.push(*pt);
+        }
+    }
+    all_points
+}
+
+/// The evaluation at `z` of the polynomial that evaluates to `0` at `elm`.
+fn vanishing_eval<F: PrimeField>(elm: &[F], z: F) -> F {
+    elm.iter().map(|pt| z - pt).product()
+}
+
+/// The evaluation at `z` of the polynomial that evaluates to `0` at the
+/// points of `elm` that are not in `except`.
+fn vanishing_eval_except<F: PrimeField>(elm: &[F], except: &[F], z: F) -> F {
+    elm.iter()
+        .filter(|pt| !except.contains(pt))
+        .map(|pt| z - pt)
+        .product()
+}
@@ -4,6 +4,9 @@ pub mod error;
 pub mod hash_map_cache;
 pub mod ipa;
 pub mod kzg;
+#[cfg(unix)]
+pub mod mmap_srs;
+pub mod ptau;
 pub mod utils;
 
 // Exposing property based tests for the SRS trait
@@ -0,0 +1,746 @@
+//! This module implements a compact binary format for the SRS of the IPA
+//! commitment scheme, which can be memory-mapped. It allows to use SRS of size
+//! `2^20` and more without keeping all their points in memory.
+//!
+//! An SRS file starts with a header made of the magic string `o1-srs\0\0`, the
+//! version of the format (`u32`), the size in bytes of a point (`u32`), the
+//! number of points `g` (`u64`), all encoded in little endian, and a
+//! fingerprint of the points. It is followed by the blinding point `h` and the
+//! points `g`, each of them in the uncompressed encoding of
+//! [ark_serialize::CanonicalSerialize]. The fingerprint is a hash of the
+//! points computed when the file is written, so that opening a file does not
+//! require to read all of it.
+//!
+//! The commitments to the Lagrange bases are computed lazily, and cached on
+//! disk in one file `lagrange_<n>.bin` per domain size `n`. Such a file starts
+//! with the magic string `o1-lgr\0\0`, the version, the size of a point, the
+//! domain size and the number of chunks of the commitments (`u64`), and a
+//! fingerprint of the SRS they were computed from. The points follow chunk by
+//! chunk: the `j`-th chunks of the commitments to the `n` Lagrange polynomials
+//! are stored at the indices `j * n..(j + 1) * n`.
+//!
+//! Commitments are computed by streaming the points from the mapped files, by
+//! batches of [MSM_BATCH_SIZE] points.
+//!
+//! The files must not be modified while they are mapped.
+//!
+//! [MmapSRS] implements the [crate::SRS] trait, and can be used wherever an
+//! SRS is expected, e.g. as [crate::ipa::SRS] would be.
+
+use crate::{
+    commitment::{BlindedCommitment, CommitmentCurve},
+    error::{CommitmentError, SrsFileError},
+    hash_map_cache::HashMapCache,
+    ipa::{lagrange_basis_chunk, SRS},
+    PolyComm, SRS as SRSTrait,
+};
+use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_ff::{PrimeField, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
+};
+use blake2::{Blake2b512, Digest};
+use rand::{CryptoRng, RngCore};
+use rayon::prelude::*;
+use std::{
+    cmp::min,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    marker::PhantomData,
+    ops::{AddAssign, Range},
+    os::unix::io::AsRawFd,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+const SRS_MAGIC: [u8; 8] = *b"o1-srs\0\0";
+const LAGRANGE_MAGIC: [u8; 8] = *b"o1-lgr\0\0";
+const VERSION: u32 = 1;
+
+const FINGERPRINT_SIZE: usize = 32;
+const SRS_HEADER_SIZE: usize = 24 + FINGERPRINT_SIZE;
+const LAGRANGE_HEADER_SIZE: usize = 32 + FINGERPRINT_SIZE;
+
+/// The number of points decoded at once when computing a commitment.
+pub const MSM_BATCH_SIZE: usize = 1 << 14;
+
+/// A read-only memory mapping of a whole file.
+#[derive(Debug)]
+struct Mmap {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+// The mapping is read-only, so it can be shared between threads.
+unsafe impl Send for Mmap {}
+unsafe impl Sync for Mmap {}
+
+impl Mmap {
+    /// Maps the first `len` bytes of `file`. `len` must not be zero.
+    fn map(file: &File, len: usize) -> std::io::Result<Self> {
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(Mmap { ptr, len })
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        // This is safe because the mapping lives as long as `self`, and is
+        // never written to.
+        unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr, self.len);
+        }
+    }
+}
+
+fn point_size<G: CommitmentCurve>() -> usize {
+    G::zero().uncompressed_size()
+}
+
+/// Computes the fingerprint of the encodings of the points of an SRS, as they
+/// are written to it.
+struct FingerprintWriter(Blake2b512);
+
+impl FingerprintWriter {
+    fn new() -> Self {
+        FingerprintWriter(Blake2b512::new())
+    }
+
+    fn finalize(self) -> [u8; FINGERPRINT_SIZE] {
+        let mut fingerprint = [0; FINGERPRINT_SIZE];
+        fingerprint.copy_from_slice(&self.0.finalize()[..FINGERPRINT_SIZE]);
+        fingerprint
+    }
+}
+
+impl Write for FingerprintWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+fn write_header<W: Write>(
+    writer: &mut W,
+    magic: &[u8; 8],
+    point_size: usize,
+    fields: &[u64],
+) -> std::io::Result<()> {
+    writer.write_all(magic)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&(point_size as u32).to_le_bytes())?;
+    for field in fields {
+        writer.write_all(&field.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Maps the file at `path`, and checks the common part of the headers: the
+/// magic string, the version, and the size of the points of `G`.
+fn map_file<G: CommitmentCurve>(
+    path: &Path,
+    magic: &[u8; 8],
+    kind: &'static str,
+    header_size: usize,
+) -> Result<Arc<Mmap>, SrsFileError> {
+    let file = File::open(path)?;
+    let len = file.metadata()?.len();
+    if len < header_size as u64 {
+        return Err(SrsFileError::WrongLength {
+            expected: header_size as u64,
+            found: len,
+        });
+    }
+    let mmap = Mmap::map(&file, len as usize)?;
+    let bytes = mmap.as_slice();
+    if &bytes[0..8] != magic {
+        return Err(SrsFileError::InvalidMagic(kind));
+    }
+    let version = read_u32(bytes, 8);
+    if version != VERSION {
+        return Err(SrsFileError::UnsupportedVersion(version));
+    }
+    let found = read_u32(bytes, 12) as usize;
+    if found != point_size::<G>() {
+        return Err(SrsFileError::WrongPointSize {
+            expected: point_size::<G>(),
+            found,
+        });
+    }
+    Ok(Arc::new(mmap))
+}
+
+fn check_length(mmap: &Mmap, expected: usize) -> Result<(), SrsFileError> {
+    if mmap.len != expected {
+        return Err(SrsFileError::WrongLength {
+            expected: expected as u64,
+            found: mmap.len as u64,
+        });
+    }
+    Ok(())
+}
+
+/// A sequence of points stored contiguously in a memory-mapped file. The
+/// points are decoded when they are accessed.
+#[derive(Debug)]
+pub struct MappedPoints<G> {
+    mmap: Arc<Mmap>,
+    offset: usize,
+    len: usize,
+    point_size: usize,
+    _curve: PhantomData<G>,
+}
+
+impl<G> Clone for MappedPoints<G> {
+    fn clone(&self) -> Self {
+        MappedPoints {
+            mmap: self.mmap.clone(),
+            offset: self.offset,
+            len: self.len,
+            point_size: self.point_size,
+            _curve: PhantomData,
+        }
+    }
+}
+
+impl<G: CommitmentCurve> MappedPoints<G> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn bytes(&self, i: usize) -> &[u8] {
+        let start = self.offset + i * self.point_size;
+        &self.mmap.as_slice()[start..start + self.point_size]
+    }
+
+    /// Returns the `i`-th point. Its validity is not checked, see
+    /// [MmapSRS::check].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds, or if the encoding of the point is
+    /// malformed.
+    pub fn get(&self, i: usize) -> G {
+        assert!(i < self.len, "index {i} out of bounds ({})", self.len);
+        G::deserialize_uncompressed_unchecked(self.bytes(i)).unwrap()
+    }
+
+    /// Returns the points with indices in `range`, without decoding them.
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        assert!(range.start <= range.end && range.end <= self.len);
+        MappedPoints {
+            mmap: self.mmap.clone(),
+            offset: self.offset + range.start * self.point_size,
+            len: range.end - range.start,
+            point_size: self.point_size,
+            _curve: PhantomData,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = G> + '_ {
+        (0..self.len).map(|i| self.get(i))
+    }
+
+    /// Decodes all the points.
+    pub fn to_vec(&self) -> Vec<G> {
+        (0..self.len).into_par_iter().map(|i| self.get(i)).collect()
+    }
+
+    /// Computes the multi-scalar multiplication of the first `scalars.len()`
+    /// points with `scalars`, decoding [MSM_BATCH_SIZE] points at a time.
+    pub fn msm_bigint(&self, scalars: &[<G::ScalarField as PrimeField>::BigInt]) -> G::Group {
+        assert!(scalars.len() <= self.len);
+        let mut res = G::Group::zero();
+        for (k, batch) in scalars.chunks(MSM_BATCH_SIZE).enumerate() {
+            let start = k * MSM_BATCH_SIZE;
+            let points = self.slice(start..start + batch.len()).to_vec();
+            res.add_assign(G::Group::msm_bigint(&points, batch));
+        }
+        res
+    }
+
+    /// Checks that all the points are valid points of the curve.
+    fn check(&self) -> Result<(), SrsFileError> {
+        (0..self.len).into_par_iter().try_for_each(|i| {
+            G::deserialize_uncompressed(self.bytes(i))
+                .map(|_| ())
+                .map_err(|_| SrsFileError::InvalidPoint(i))
+        })
+    }
+}
+
+/// The commitments to the Lagrange polynomials of a domain, stored in a
+/// memory-mapped file.
+#[derive(Debug, Clone)]
+pub struct MappedLagrangeBasis<G> {
+    points: MappedPoints<G>,
+    domain_size: usize,
+    num_chunks: usize,
+}
+
+impl<G: CommitmentCurve> MappedLagrangeBasis<G> {
+    pub fn domain_size(&self) -> usize {
+        self.domain_size
+    }
+
+    pub fn num_chunks(&self) -> usize {
+        self.num_chunks
+    }
+
+    /// Returns the `j`-th chunks of the commitments to all the Lagrange
+    /// polynomials.
+    pub fn chunk(&self, j: usize) -> MappedPoints<G> {
+        assert!(j < self.num_chunks);
+        let n = self.domain_size;
+        self.points.slice(j * n..(j + 1) * n)
+    }
+
+    /// Returns the commitment to the `i`-th Lagrange polynomial.
+    pub fn get(&self, i: usize) -> PolyComm<G> {
+        assert!(i < self.domain_size);
+        PolyComm::new(
+            (0..self.num_chunks)
+                .map(|j| self.points.get(j * self.domain_size + i))
+                .collect(),
+        )
+    }
+
+    /// Decodes all the commitments, in the same layout as
+    /// [crate::SRS::get_lagrange_basis].
+    pub fn to_vec(&self) -> Vec<PolyComm<G>> {
+        (0..self.domain_size)
+            .into_par_iter()
+            .map(|i| self.get(i))
+            .collect()
+    }
+}
+
+/// An SRS for the IPA commitment scheme whose points are stored in a
+/// memory-mapped file, see the module documentation for the format.
+#[derive(Debug, Clone)]
+pub struct MmapSRS<G> {
+    g: MappedPoints<G>,
+    h: G,
+    /// The fingerprint read from the header of the SRS file, recorded in the
+    /// cached Lagrange bases
+    fingerprint: [u8; FINGERPRINT_SIZE],
+    /// The directory in which the Lagrange bases are cached
+    lagrange_cache_dir: PathBuf,
+    /// The Lagrange bases which have been mapped, per domain size
+    lagrange_bases: HashMapCache<usize, MappedLagrangeBasis<G>>,
+    /// The Lagrange bases which have been decoded in memory, per domain size,
+    /// see [crate::SRS::get_lagrange_basis]
+    decoded_lagrange_bases: HashMapCache<usize, Vec<PolyComm<G>>>,
+}
+
+impl<G: CommitmentCurve> MmapSRS<G> {
+    /// Writes `srs` to `writer` in the format of this module. The cached
+    /// Lagrange bases of `srs` are not written.
+    pub fn write<W: Write>(srs: &SRS<G>, writer: W) -> Result<(), SrsFileError> {
+        // The points are encoded twice, to compute the fingerprint without
+        // keeping their encodings in memory.
+        let mut fingerprint = FingerprintWriter::new();
+        srs.h.serialize_uncompressed(&mut fingerprint)?;
+        for g in srs.g.iter() {
+            g.serialize_uncompressed(&mut fingerprint)?;
+        }
+
+        let mut writer = BufWriter::new(writer);
+        write_header(
+            &mut writer,
+            &SRS_MAGIC,
+            point_size::<G>(),
+            &[srs.g.len() as u64],
+        )?;
+        writer.write_all(&fingerprint.finalize())?;
+        srs.h.serialize_uncompressed(&mut writer)?;
+        for g in srs.g.iter() {
+            g.serialize_uncompressed(&mut writer)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Maps the SRS file at `path`. Only the blinding point is checked, see
+    /// [MmapSRS::check] to check the other points and the fingerprint.
+    ///
+    /// The Lagrange bases are cached in the directory `<path>.lagrange`, which
+    /// can be changed with [MmapSRS::with_lagrange_cache_dir].
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SrsFileError> {
+        let path = path.as_ref();
+        let point_size = point_size::<G>();
+        let mmap = map_file::<G>(path, &SRS_MAGIC, "SRS", SRS_HEADER_SIZE)?;
+        let bytes = mmap.as_slice();
+        let len = read_u64(bytes, 16) as usize;
+        // The number of points is untrusted, so the expected length saturates
+        // instead of overflowing.
+        check_length(
+            &mmap,
+            len.saturating_add(1)
+                .saturating_mul(point_size)
+                .saturating_add(SRS_HEADER_SIZE),
+        )?;
+
+        let h = G::deserialize_uncompressed(&bytes[SRS_HEADER_SIZE..SRS_HEADER_SIZE + point_size])
+            .map_err(|_| SrsFileError::InvalidBlinder)?;
+        let fingerprint = bytes[24..SRS_HEADER_SIZE].try_into().unwrap();
+        let g = MappedPoints {
+            mmap: mmap.clone(),
+            offset: SRS_HEADER_SIZE + point_size,
+            len,
+            point_size,
+            _curve: PhantomData,
+        };
+
+        let mut lagrange_cache_dir = path.as_os_str().to_owned();
+        lagrange_cache_dir.push(".lagrange");
+
+        Ok(MmapSRS {
+            g,
+            h,
+            fingerprint,
+            lagrange_cache_dir: lagrange_cache_dir.into(),
+            lagrange_bases: HashMapCache::new(),
+            decoded_lagrange_bases: HashMapCache::new(),
+        })
+    }
+
+    /// Caches the Lagrange bases in the directory `dir`, instead of the
+    /// default one.
+    pub fn with_lagrange_cache_dir(self, dir: impl Into<PathBuf>) -> Self {
+        MmapSRS {
+            lagrange_cache_dir: dir.into(),
+            lagrange_bases: HashMapCache::new(),
+            decoded_lagrange_bases: HashMapCache::new(),
+            ..self
+        }
+    }
+
+    pub fn lagrange_cache_dir(&self) -> &Path {
+        &self.lagrange_cache_dir
+    }
+
+    /// The points for committing to polynomials in coefficient form
+    pub fn g(&self) -> &MappedPoints<G> {
+        &self.g
+    }
+
+    /// The group element used for blinding commitments
+    pub fn h(&self) -> G {
+        self.h
+    }
+
+    /// Checks that all the points of the SRS are valid points of the curve,
+    /// and that they match the fingerprint of the header.
+    pub fn check(&self) -> Result<(), SrsFileError> {
+        self.g.check()?;
+        let mut fingerprint = FingerprintWriter::new();
+        fingerprint.write_all(&self.g.mmap.as_slice()[SRS_HEADER_SIZE..])?;
+        if fingerprint.finalize() != self.fingerprint {
+            return Err(SrsFileError::InvalidFingerprint);
+        }
+        Ok(())
+    }
+
+    /// Loads the whole SRS in memory.
+    pub fn to_srs(&self) -> SRS<G> {
+        SRS {
+            g: self.g.to_vec(),
+            h: self.h,
+            lagrange_bases: HashMapCache::new(),
+        }
+    }
+
+    /// Returns the commitments to the Lagrange polynomials of `domain`. They
+    /// are read from the cache directory if they have already been computed,
+    /// and computed and written to it otherwise.
+    pub fn get_mapped_lagrange_basis(
+        &self,
+        domain: D<G::ScalarField>,
+    ) -> Result<&MappedLagrangeBasis<G>, SrsFileError> {
+        let n = domain.size();
+        let basis = if self.lagrange_bases.contains_key(&n) {
+            None
+        } else {
+            Some(self.load_lagrange_basis(domain)?)
+        };
+        // Entries are never removed from the cache, so `basis` is only `None`
+        // if the entry exists.
+        Ok(self.lagrange_bases.get_or_generate(n, || basis.unwrap()))
+    }
+
+    fn lagrange_basis_path(&self, domain_size: usize) -> PathBuf {
+        self.lagrange_cache_dir
+            .join(format!("lagrange_{domain_size}.bin"))
+    }
+
+    fn load_lagrange_basis(
+        &self,
+        domain: D<G::ScalarField>,
+    ) -> Result<MappedLagrangeBasis<G>, SrsFileError> {
+        let n = domain.size();
+        let srs_size = self.g.len();
+        let num_chunks = (n + srs_size - 1) / srs_size;
+        let point_size = point_size::<G>();
+        let path = self.lagrange_basis_path(n);
+
+        if !path.exists() {
+            fs::create_dir_all(&self.lagrange_cache_dir)?;
+            // The basis is written to a temporary file first, so that an
+            // interrupted computation does not leave a truncated file in the
+            // cache.
+            let tmp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+            let mut writer = BufWriter::new(File::create(&tmp_path)?);
+            write_header(
+                &mut writer,
+                &LAGRANGE_MAGIC,
+                point_size,
+                &[n as u64, num_chunks as u64],
+            )?;
+            writer.write_all(&self.fingerprint)?;
+            // Only the first `n` points are needed if the domain is smaller
+            // than the SRS, and only one chunk is kept in memory at a time.
+            let g = self.g.slice(0..min(n, srs_size)).to_vec();
+            for i in 0..num_chunks {
+                for point in lagrange_basis_chunk(&g, domain, i) {
+                    point.serialize_uncompressed(&mut writer)?;
+                }
+            }
+            writer.flush()?;
+            drop(writer);
+            fs::rename(&tmp_path, &path)?;
+        }
+
+        let mmap = map_file::<G>(
+            &path,
+            &LAGRANGE_MAGIC,
+            "Lagrange basis",
+            LAGRANGE_HEADER_SIZE,
+        )?;
+        let bytes = mmap.as_slice();
+        if read_u64(bytes, 16) != n as u64
+            || read_u64(bytes, 24) != num_chunks as u64
+            || bytes[32..LAGRANGE_HEADER_SIZE] != self.fingerprint
+        {
+            return Err(SrsFileError::StaleLagrangeBasis(n));
+        }
+        check_length(&mmap, LAGRANGE_HEADER_SIZE + n * num_chunks * point_size)?;
+
+        Ok(MappedLagrangeBasis {
+            points: MappedPoints {
+                mmap,
+                offset: LAGRANGE_HEADER_SIZE,
+                len: n * num_chunks,
+                point_size,
+                _curve: PhantomData,
+            },
+            domain_size: n,
+            num_chunks,
+        })
+    }
+
+    /// Same as [crate::SRS::commit_evaluations_non_hiding], streaming the
+    /// points of the cached Lagrange basis of `domain`. It fails if the
+    /// Lagrange basis can not be read from or written to the cache directory.
+    pub fn try_commit_evaluations_non_hiding(
+        &self,
+        domain: D<G::ScalarField>,
+        plnm: &Evaluations<G::ScalarField, D<G::ScalarField>>,
+    ) -> Result<PolyComm<G>, SrsFileError> {
+        let basis = self.get_mapped_lagrange_basis(domain)?;
+        let evals: Vec<_> = match domain.size.cmp(&plnm.domain().size) {
+            std::cmp::Ordering::Less => {
+                let s = (plnm.domain().size / domain.size) as usize;
+                (0..domain.size())
+                    .map(|i| plnm.evals[s * i].into_bigint())
+                    .collect()
+            }
+            std::cmp::Ordering::Equal => plnm.evals.iter().map(|e| e.into_bigint()).collect(),
+            std::cmp::Ordering::Greater => {
+                panic!("desired commitment domain size ({}) greater than evaluations' domain size ({}):", domain.size, plnm.domain().size)
+            }
+        };
+        Ok(PolyComm::new(
+            (0..basis.num_chunks())
+                .map(|j| basis.chunk(j).msm_bigint(&evals).into_affine())
+                .collect(),
+        ))
+    }
+}
+
+/// The commitments are computed by streaming the points of the mapped files.
+/// The methods of the trait can not fail, so they panic if a Lagrange basis can
+/// not be read from or written to the cache directory, see
+/// [MmapSRS::try_commit_evaluations_non_hiding] and
+/// [MmapSRS::get_mapped_lagrange_basis] to handle these errors.
+impl<G: CommitmentCurve> SRSTrait<G> for MmapSRS<G> {
+    fn max_poly_size(&self) -> usize {
+        self.g.len()
+    }
+
+    fn blinding_commitment(&self) -> G {
+        self.h
+    }
+
+    fn mask_custom(
+        &self,
+        com: PolyComm<G>,
+        blinders: &PolyComm<G::ScalarField>,
+    ) -> Result<BlindedCommitment<G>, CommitmentError> {
+        let commitment = com
+            .zip(blinders)
+            .ok_or_else(|| CommitmentError::BlindersDontMatch(blinders.len(), com.len()))?
+            .map(|(g, b)| {
+                let mut g_masked = self.h.mul(b);
+                g_masked.add_assign(&g);
+                g_masked.into_affine()
+            });
+        Ok(BlindedCommitment {
+            commitment,
+            blinders: blinders.clone(),
+        })
+    }
+
+    fn commit_non_hiding(
+        &self,
+        plnm: &DensePolynomial<G::ScalarField>,
+        num_chunks: usize,
+    ) -> PolyComm<G> {
+        let mut chunks = vec![];
+        if plnm.is_zero() {
+            chunks.push(G::zero());
+        } else {
+            let coeffs: Vec<_> = plnm.iter().map(|c| c.into_bigint()).collect();
+            coeffs.chunks(self.g.len()).for_each(|coeffs_chunk| {
+                chunks.push(self.g.msm_bigint(coeffs_chunk).into_affine());
+            });
+        }
+
+        for _ in chunks.len()..num_chunks {
+            chunks.push(G::zero());
+        }
+
+        PolyComm::<G>::new(chunks)
+    }
+
+    fn commit(
+        &self,
+        plnm: &DensePolynomial<G::ScalarField>,
+        num_chunks: usize,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> BlindedCommitment<G> {
+        self.mask(self.commit_non_hiding(plnm, num_chunks), rng)
+    }
+
+    fn commit_custom(
+        &self,
+        plnm: &DensePolynomial<G::ScalarField>,
+        num_chunks: usize,
+        blinders: &PolyComm<G::ScalarField>,
+    ) -> Result<BlindedCommitment<G>, CommitmentError> {
+        self.mask_custom(self.commit_non_hiding(plnm, num_chunks), blinders)
+    }
+
+    fn commit_evaluations_non_hiding(
+        &self,
+        domain: D<G::ScalarField>,
+        plnm: &Evaluations<G::ScalarField, D<G::ScalarField>>,
+    ) -> PolyComm<G> {
+        self.try_commit_evaluations_non_hiding(domain, plnm)
+            .unwrap_or_else(|err| panic!("could not commit to the evaluations: {err}"))
+    }
+
+    fn commit_evaluations(
+        &self,
+        domain: D<G::ScalarField>,
+        plnm: &Evaluations<G::ScalarField, D<G::ScalarField>>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> BlindedCommitment<G> {
+        self.mask(self.commit_evaluations_non_hiding(domain, plnm), rng)
+    }
+
+    fn commit_evaluations_custom(
+        &self,
+        domain: D<G::ScalarField>,
+        plnm: &Evaluations<G::ScalarField, D<G::ScalarField>>,
+        blinders: &PolyComm<G::ScalarField>,
+    ) -> Result<BlindedCommitment<G>, CommitmentError> {
+        self.mask_custom(self.commit_evaluations_non_hiding(domain, plnm), blinders)
+    }
+
+    /// Same as [crate::ipa::SRS::create]. The SRS is written to a file in the
+    /// temporary directory, which is removed once mapped. The Lagrange bases
+    /// are cached in the temporary directory too.
+    fn create(depth: usize) -> Self {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "o1-srs-{}-{}.srs",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = File::create(&path)
+            .unwrap_or_else(|err| panic!("could not create the SRS file {path:?}: {err}"));
+        Self::write(&SRS::<G>::create(depth), file)
+            .unwrap_or_else(|err| panic!("could not write the SRS file {path:?}: {err}"));
+        let srs = Self::open(&path)
+            .unwrap_or_else(|err| panic!("could not open the SRS file {path:?}: {err}"));
+        // The mapping remains valid once the file is removed
+        fs::remove_file(&path)
+            .unwrap_or_else(|err| panic!("could not remove the SRS file {path:?}: {err}"));
+        srs
+    }
+
+    /// The commitments are decoded and kept in memory. Committing to
+    /// evaluations does not require them, as the mapped basis is streamed.
+    fn get_lagrange_basis(&self, domain: D<G::ScalarField>) -> &Vec<PolyComm<G>> {
+        self.decoded_lagrange_bases
+            .get_or_generate(domain.size(), || {
+                self.get_mapped_lagrange_basis(domain)
+                    .unwrap_or_else(|err| panic!("could not get the Lagrange basis: {err}"))
+                    .to_vec()
+            })
+    }
+
+    fn get_lagrange_basis_from_domain_size(&self, domain_size: usize) -> &Vec<PolyComm<G>> {
+        self.get_lagrange_basis(D::new(domain_size).unwrap())
+    }
+
+    fn size(&self) -> usize {
+        self.g.len()
+    }
+}
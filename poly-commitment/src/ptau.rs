@@ -0,0 +1,321 @@
+//! This module implements the loading of a structured reference string for
+//! the KZG protocol from the transcript of a powers-of-tau ceremony, in the
+//! `.ptau` format used by [snarkjs](https://github.com/iden3/snarkjs) and the
+//! Perpetual Powers of Tau ceremony.
+//!
+//! A `.ptau` file starts with the magic string `ptau`, a version and a number
+//! of sections, all encoded in little endian. Each section is given by its
+//! type (`u32`), its size in bytes (`u64`) and its content. We only use the
+//! following ones:
+//! - section 1, the header: the size `n8` in bytes of the base field elements,
+//! the modulus of the base field on `n8` bytes, and the power `p` of the
+//! ceremony.
+//! - section 2: the `2^{p+1} - 1` points `g1^{τ^i}`.
+//! - section 3: the `2^p` points `g2^{τ^i}`.
+//!
+//! Points are given by their affine coordinates, and each base field element
+//! is written on `n8` bytes in Montgomery form, i.e. `x * 2^{8 n8}`. An
+//! element of an extension field is written as its coefficients over the base
+//! field.
+
+use crate::{error::PtauError, ipa::SRS, kzg::PairingSRS, SRS as _};
+use ark_ec::{
+    pairing::{prepare_g1, prepare_g2, Pairing},
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr, VariableBaseMSM,
+};
+use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use num_bigint::BigUint;
+use rand::thread_rng;
+use std::io::Read;
+
+/// Header of a ptau file.
+const PTAU_MAGIC: &[u8; 4] = b"ptau";
+/// The only version of the format in use.
+const PTAU_VERSION: u32 = 1;
+/// Section containing the header of the ceremony.
+const SECTION_HEADER: u32 = 1;
+/// Section containing the powers of tau in G1.
+const SECTION_TAU_G1: u32 = 2;
+/// Section containing the powers of tau in G2.
+const SECTION_TAU_G2: u32 = 3;
+/// Number of powers in G2 of the verifier SRS, as in
+/// [PairingSRS::create_trusted_setup].
+const VERIFIER_SRS_DEPTH: usize = 3;
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, PtauError> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, PtauError> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Skip `size` bytes of the reader.
+fn skip<R: Read>(reader: &mut R, size: u64) -> Result<(), PtauError> {
+    let skipped = std::io::copy(&mut reader.take(size), &mut std::io::sink())?;
+    if skipped != size {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    }
+    Ok(())
+}
+
+/// Reader of the field elements and points of a ptau file, for a base field
+/// `F` whose elements are written on `n8` bytes.
+struct PtauReader<F: PrimeField> {
+    n8: usize,
+    /// The inverse of the Montgomery factor `2^{8 n8}`
+    montgomery_inv: F,
+}
+
+impl<F: PrimeField> PtauReader<F> {
+    /// Parse the header section, and check that it corresponds to the field
+    /// `F`. Returns the reader and the power of the ceremony.
+    fn from_header<R: Read>(reader: &mut R, size: u64) -> Result<(Self, u32), PtauError> {
+        let n8 = read_u32(reader)? as usize;
+        if n8 == 0 || size != (n8 + 12) as u64 {
+            return Err(PtauError::MalformedSection(SECTION_HEADER));
+        }
+        let mut modulus = vec![0u8; n8];
+        reader.read_exact(&mut modulus)?;
+        let power = read_u32(reader)?;
+        // The power of the ceremony this one has been prepared from
+        let _ceremony_power = read_u32(reader)?;
+
+        let f_modulus: BigUint = F::MODULUS.into();
+        if BigUint::from_bytes_le(&modulus) != f_modulus {
+            return Err(PtauError::WrongCurve);
+        }
+        let montgomery_inv = F::from(2u64)
+            .pow([8 * n8 as u64])
+            .inverse()
+            .expect("the modulus is odd");
+        Ok((Self { n8, montgomery_inv }, power))
+    }
+
+    /// Read a base field element in Montgomery form.
+    fn read_base<R: Read>(&self, reader: &mut R, section: u32) -> Result<F, PtauError> {
+        let mut buf = vec![0u8; self.n8];
+        reader.read_exact(&mut buf)?;
+        let f_modulus: BigUint = F::MODULUS.into();
+        if BigUint::from_bytes_le(&buf) >= f_modulus {
+            return Err(PtauError::MalformedSection(section));
+        }
+        Ok(F::from_le_bytes_mod_order(&buf) * self.montgomery_inv)
+    }
+
+    /// Read an element of a field over `F`.
+    fn read_field<R: Read, Fe: Field<BasePrimeField = F>>(
+        &self,
+        reader: &mut R,
+        section: u32,
+    ) -> Result<Fe, PtauError> {
+        let elems = (0..Fe::extension_degree())
+            .map(|_| self.read_base(reader, section))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Fe::from_base_prime_field_elems(&elems).expect("the number of elements is correct"))
+    }
+
+    /// Size in bytes of a point on a curve over a field of degree `degree`
+    /// over `F`.
+    fn point_size(&self, degree: u64) -> u64 {
+        2 * degree * self.n8 as u64
+    }
+
+    /// Read `count` points of the section, and skip the others. The points
+    /// are checked to be in the prime order subgroup.
+    fn read_points<R: Read, P: SWCurveConfig>(
+        &self,
+        reader: &mut R,
+        section: u32,
+        size: u64,
+        count: usize,
+    ) -> Result<Vec<Affine<P>>, PtauError>
+    where
+        P::BaseField: Field<BasePrimeField = F>,
+    {
+        let point_size = self.point_size(P::BaseField::extension_degree());
+        if size % point_size != 0 {
+            return Err(PtauError::MalformedSection(section));
+        }
+        let available = (size / point_size) as usize;
+        if available < count {
+            return Err(PtauError::NotEnoughPowers {
+                section,
+                available,
+                requested: count,
+            });
+        }
+        let points = (0..count)
+            .map(|index| {
+                let x: P::BaseField = self.read_field(reader, section)?;
+                let y: P::BaseField = self.read_field(reader, section)?;
+                // The point at infinity is written as (0, 0), and is never a
+                // valid power.
+                let point = Affine::<P>::new_unchecked(x, y);
+                if (x.is_zero() && y.is_zero())
+                    || !point.is_on_curve()
+                    || !point.is_in_correct_subgroup_assuming_on_curve()
+                {
+                    return Err(PtauError::InvalidPoint { section, index });
+                }
+                Ok(point)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        skip(reader, size - count as u64 * point_size)?;
+        Ok(points)
+    }
+}
+
+impl<F, P1, P2, Pair> PairingSRS<Pair>
+where
+    F: PrimeField,
+    P1: SWCurveConfig<BaseField = F> + Clone,
+    P2: SWCurveConfig<ScalarField = P1::ScalarField> + Clone,
+    P2::BaseField: Field<BasePrimeField = F>,
+    Pair: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+{
+    /// Load a structured reference string of `depth` powers from the
+    /// transcript of a powers-of-tau ceremony in the `.ptau` format, see the
+    /// [module documentation](crate::ptau).
+    ///
+    /// The points are checked to be in the prime order subgroups, and to be
+    /// consistent powers `g^{τ^i}` of the generators, i.e.
+    /// `e(g1^{τ^i}, g2) = e(g1^{τ^{i-1}}, g2^τ)`. The pairing checks are
+    /// batched with random coefficients.
+    ///
+    /// If `depth` is a power of two, the Lagrange basis of the domain of size
+    /// `depth` is computed and cached.
+    pub fn from_ptau<R: Read>(mut reader: R, depth: usize) -> Result<Self, PtauError> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != PTAU_MAGIC {
+            return Err(PtauError::InvalidMagic);
+        }
+        let version = read_u32(&mut reader)?;
+        if version != PTAU_VERSION {
+            return Err(PtauError::UnsupportedVersion(version));
+        }
+        let nb_sections = read_u32(&mut reader)?;
+
+        let mut ptau_reader: Option<PtauReader<F>> = None;
+        let mut g1_powers: Option<Vec<Affine<P1>>> = None;
+        let mut g2_powers: Option<Vec<Affine<P2>>> = None;
+        for _ in 0..nb_sections {
+            if g1_powers.is_some() && g2_powers.is_some() {
+                break;
+            }
+            let section = read_u32(&mut reader)?;
+            let size = read_u64(&mut reader)?;
+            match (section, &ptau_reader) {
+                (SECTION_HEADER, _) => {
+                    ptau_reader = Some(PtauReader::from_header(&mut reader, size)?.0);
+                }
+                // snarkjs always writes the header first.
+                (SECTION_TAU_G1 | SECTION_TAU_G2, None) => {
+                    return Err(PtauError::MissingSection(SECTION_HEADER))
+                }
+                (SECTION_TAU_G1, Some(ptau_reader)) => {
+                    g1_powers = Some(ptau_reader.read_points(&mut reader, section, size, depth)?);
+                }
+                (SECTION_TAU_G2, Some(ptau_reader)) => {
+                    g2_powers = Some(ptau_reader.read_points(
+                        &mut reader,
+                        section,
+                        size,
+                        VERIFIER_SRS_DEPTH,
+                    )?);
+                }
+                _ => skip(&mut reader, size)?,
+            }
+        }
+        let g1_powers = g1_powers.ok_or(PtauError::MissingSection(SECTION_TAU_G1))?;
+        let g2_powers = g2_powers.ok_or(PtauError::MissingSection(SECTION_TAU_G2))?;
+
+        check_powers::<P1, P2, Pair>(&g1_powers, &g2_powers)?;
+
+        let srs = PairingSRS {
+            full_srs: SRS::from_trusted_setup_powers(g1_powers),
+            verifier_srs: SRS::from_trusted_setup_powers(g2_powers),
+        };
+        if depth.is_power_of_two() {
+            srs.get_lagrange_basis_from_domain_size(depth);
+        }
+        Ok(srs)
+    }
+}
+
+/// Check that the points are powers of tau for the same tau, starting from
+/// the generators.
+fn check_powers<P1, P2, Pair>(
+    g1_powers: &[Affine<P1>],
+    g2_powers: &[Affine<P2>],
+) -> Result<(), PtauError>
+where
+    P1: SWCurveConfig,
+    P2: SWCurveConfig<ScalarField = P1::ScalarField>,
+    Pair: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+{
+    if g1_powers.len() < 2 {
+        return Err(PtauError::NotEnoughPowers {
+            section: SECTION_TAU_G1,
+            available: g1_powers.len(),
+            requested: 2,
+        });
+    }
+    if g1_powers[0] != Affine::<P1>::generator() {
+        return Err(PtauError::InvalidGenerator(SECTION_TAU_G1));
+    }
+    if g2_powers[0] != Affine::<P2>::generator() {
+        return Err(PtauError::InvalidGenerator(SECTION_TAU_G2));
+    }
+
+    let mut rng = thread_rng();
+    // Checks e(\sum_i r_i g1^{τ^i}, g2) = e(\sum_i r_i g1^{τ^{i-1}}, g2^τ)
+    let scalars: Vec<_> = (1..g1_powers.len())
+        .map(|_| P1::ScalarField::rand(&mut rng))
+        .collect();
+    let g1_shifted = <Affine<P1> as AffineRepr>::Group::msm(&g1_powers[1..], &scalars)
+        .expect("the lengths are equal");
+    let g1_unshifted =
+        <Affine<P1> as AffineRepr>::Group::msm(&g1_powers[..g1_powers.len() - 1], &scalars)
+            .expect("the lengths are equal");
+    // Checks e(g1^τ, \sum_j r_j g2^{τ^{j-1}}) = e(g1, \sum_j r_j g2^{τ^j})
+    let scalars: Vec<_> = (1..g2_powers.len())
+        .map(|_| P1::ScalarField::rand(&mut rng))
+        .collect();
+    let g2_shifted = <Affine<P2> as AffineRepr>::Group::msm(&g2_powers[1..], &scalars)
+        .expect("the lengths are equal");
+    let g2_unshifted =
+        <Affine<P2> as AffineRepr>::Group::msm(&g2_powers[..g2_powers.len() - 1], &scalars)
+            .expect("the lengths are equal");
+
+    let g1_check = Pair::multi_pairing(
+        [
+            prepare_g1::<Pair>(g1_shifted),
+            prepare_g1::<Pair>(-g1_unshifted),
+        ],
+        [
+            prepare_g2::<Pair>(g2_powers[0]),
+            prepare_g2::<Pair>(g2_powers[1]),
+        ],
+    );
+    let g2_check = Pair::multi_pairing(
+        [
+            prepare_g1::<Pair>(g1_powers[1]),
+            prepare_g1::<Pair>(-g1_powers[0].into_group()),
+        ],
+        [
+            prepare_g2::<Pair>(g2_unshifted),
+            prepare_g2::<Pair>(g2_shifted),
+        ],
+    );
+    if !g1_check.is_zero() || !g2_check.is_zero() {
+        return Err(PtauError::InconsistentPowers);
+    }
+    Ok(())
+}
@@ -10,13 +10,16 @@ use mina_poseidon::{
 };
 use o1_utils::ExtendedDensePolynomial;
 use poly_commitment::{
-    commitment::{combined_inner_product, BatchEvaluationProof, CommitmentCurve, Evaluation},
-    ipa::SRS,
+    commitment::{
+        b_poly_coefficients, combined_inner_product, BatchEvaluationProof, CommitmentCurve,
+        Evaluation,
+    },
+    ipa::{DeferredIpaCheck, IpaAccumulator, OpeningProof, SRS},
     pbt_srs,
     utils::DensePolynomialOrEvaluations,
     PolyComm, SRS as _,
 };
-use rand::Rng;
+use rand::{CryptoRng, Rng, RngCore};
 use std::array;
 
 #[test]
@@ -218,6 +221,151 @@ fn test_opening_proof() {
     }
 }
 
+type BaseSponge = DefaultFqSponge<mina_curves::pasta::VestaParameters, SC>;
+
+/// An opening proof of a random polynomial, with its evaluations.
+struct RandomOpening {
+    sponge: BaseSponge,
+    evaluation_points: Vec<Fp>,
+    polyscale: Fp,
+    evalscale: Fp,
+    evaluations: Vec<Evaluation<VestaG>>,
+    opening: OpeningProof<VestaG>,
+    combined_inner_product: Fp,
+}
+
+impl RandomOpening {
+    fn new<RNG: RngCore + CryptoRng>(
+        srs: &SRS<VestaG>,
+        group_map: &<VestaG as CommitmentCurve>::Map,
+        rng: &mut RNG,
+    ) -> Self {
+        let poly = DensePolynomial::<Fp>::rand(srs.g.len() - 1, rng);
+        let commitment = srs.commit(&poly, 1, rng);
+        let (polyscale, evalscale) = (Fp::rand(rng), Fp::rand(rng));
+        let sponge = BaseSponge::new(mina_poseidon::pasta::fq_kimchi::static_params());
+        let evaluation_points = vec![Fp::rand(rng), Fp::rand(rng)];
+        let polys: Vec<(DensePolynomialOrEvaluations<_, D<_>>, _)> = vec![(
+            DensePolynomialOrEvaluations::DensePolynomial(&poly),
+            commitment.blinders,
+        )];
+        let opening = srs.open(
+            group_map,
+            &polys,
+            &evaluation_points,
+            polyscale,
+            evalscale,
+            sponge.clone(),
+            rng,
+        );
+        let evals: Vec<Vec<Fp>> = evaluation_points
+            .iter()
+            .map(|x| vec![poly.evaluate(x)])
+            .collect();
+        let combined_inner_product =
+            combined_inner_product(&polyscale, &evalscale, &[evals.clone()]);
+        Self {
+            sponge,
+            evaluation_points,
+            polyscale,
+            evalscale,
+            evaluations: vec![Evaluation {
+                commitment: commitment.commitment,
+                evaluations: evals,
+            }],
+            opening,
+            combined_inner_product,
+        }
+    }
+
+    fn batch_evaluation_proof(
+        &self,
+    ) -> BatchEvaluationProof<VestaG, BaseSponge, OpeningProof<VestaG>> {
+        BatchEvaluationProof {
+            sponge: self.sponge.clone(),
+            evaluation_points: self.evaluation_points.clone(),
+            polyscale: self.polyscale,
+            evalscale: self.evalscale,
+            evaluations: self
+                .evaluations
+                .iter()
+                .map(|e| Evaluation {
+                    commitment: e.commitment.clone(),
+                    evaluations: e.evaluations.clone(),
+                })
+                .collect(),
+            opening: &self.opening,
+            combined_inner_product: self.combined_inner_product,
+        }
+    }
+}
+
+#[test]
+fn test_ipa_accumulator() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let srs = SRS::<VestaG>::create(1 << 5);
+    let group_map = <VestaG as CommitmentCurve>::Map::setup();
+
+    let openings: Vec<_> = (0..6)
+        .map(|_| RandomOpening::new(&srs, &group_map, &mut rng))
+        .collect();
+
+    // Two accumulators of three proofs each, merged.
+    let mut acc1 = IpaAccumulator::new(&srs);
+    let mut acc2 = IpaAccumulator::new(&srs);
+    for (i, opening) in openings.iter().enumerate() {
+        let acc = if i % 2 == 0 { &mut acc1 } else { &mut acc2 };
+        assert!(acc.absorb(
+            &srs,
+            &group_map,
+            &mut opening.batch_evaluation_proof(),
+            &mut rng
+        ));
+    }
+    assert!(acc1.decide(&srs));
+    acc1.merge(&acc2, &mut rng);
+    assert_eq!(acc1.len(), 6);
+    assert!(acc1.decide(&srs));
+
+    // A deferred check with the correct commitment, as in a recursion
+    // challenge.
+    let chals: Vec<Fp> = (0..5).map(|_| Fp::rand(&mut rng)).collect();
+    let sg = srs
+        .commit_non_hiding(
+            &DensePolynomial::from_coefficients_vec(b_poly_coefficients(&chals)),
+            1,
+        )
+        .get_first_chunk();
+    acc1.absorb_deferred(
+        &DeferredIpaCheck {
+            chals: chals.clone(),
+            sg,
+        },
+        &mut rng,
+    );
+    assert!(acc1.decide(&srs));
+
+    // A deferred check with a wrong commitment is only detected by the
+    // decider.
+    let mut acc3 = acc1.clone();
+    acc3.absorb_deferred(&DeferredIpaCheck { chals, sg: srs.h }, &mut rng);
+    assert!(!acc3.decide(&srs));
+    acc2.merge(&acc3, &mut rng);
+    assert!(!acc2.decide(&srs));
+
+    // A proof with a wrong final commitment base fails the succinct check.
+    let mut opening = RandomOpening::new(&srs, &group_map, &mut rng);
+    opening.opening.sg = openings[0].opening.sg;
+    let mut acc4 = IpaAccumulator::new(&srs);
+    assert!(!acc4.absorb(
+        &srs,
+        &group_map,
+        &mut opening.batch_evaluation_proof(),
+        &mut rng
+    ));
+    assert!(!acc4.decide(&srs));
+}
+
 // Testing how many chunks are generated with different polynomial sizes and
 // different number of chunks requested.
 #[test]
@@ -1,20 +1,29 @@
 use ark_bn254::{Config, Fr as ScalarField, G1Affine as G1, G2Affine as G2};
 use ark_ec::{bn::Bn, AffineRepr};
-use ark_ff::UniformRand;
+use ark_ff::{PrimeField, UniformRand, Zero};
 use ark_poly::{
     univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Polynomial,
     Radix2EvaluationDomain as D,
 };
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use groupmap::GroupMap;
 use mina_curves::pasta::{Fp, Vesta as VestaG};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi as SC, poseidon::ArithmeticSpongeParams,
+    sponge::DefaultFqSponge, FqSponge,
+};
+use num_bigint::BigUint;
+use once_cell::sync::Lazy;
 use poly_commitment::{
-    commitment::Evaluation,
+    commitment::{BatchEvaluationProof, Evaluation},
+    error::{CommitmentError, PtauError},
     ipa::SRS,
-    kzg::{combine_evaluations, KZGProof, PairingSRS},
+    kzg::{combine_evaluations, KZGProof, PairingSRS, ShplonkProof},
     pbt_srs,
     utils::DensePolynomialOrEvaluations,
-    PolyComm, SRS as _,
+    OpenProof, PolyComm, SRS as _,
 };
+use rand::{CryptoRng, RngCore};
 
 #[test]
 fn test_combine_evaluations() {
@@ -203,10 +212,234 @@ fn test_kzg_proof() {
     )
     .unwrap();
 
-    let res = kzg_proof.verify(&srs, &evaluations, polyscale, &evaluation_points);
+    let res = kzg_proof
+        .verify(&srs, &evaluations, polyscale, &evaluation_points)
+        .unwrap();
     assert!(res);
 }
 
+type BN254 = Bn<Config>;
+type BaseSponge = DefaultFqSponge<ark_bn254::g1::Config, SC>;
+
+fn base_sponge() -> BaseSponge {
+    static PARAMS: Lazy<ArithmeticSpongeParams<ark_bn254::Fq>> =
+        Lazy::new(mina_poseidon::dummy_values::kimchi_dummy);
+    BaseSponge::new(&PARAMS)
+}
+
+/// Polynomials with their blinders and evaluations.
+type RandomEvaluations = (
+    Vec<DensePolynomial<ScalarField>>,
+    Vec<PolyComm<ScalarField>>,
+    Vec<Evaluation<G1>>,
+);
+
+/// Random polynomials of `size` coefficients with their (chunked) commitments
+/// and evaluations at `evaluation_points`.
+fn random_evaluations<RNG: RngCore + CryptoRng>(
+    srs: &PairingSRS<BN254>,
+    nb_polys: usize,
+    size: usize,
+    evaluation_points: &[ScalarField],
+    rng: &mut RNG,
+) -> RandomEvaluations {
+    let srs_size = srs.full_srs.g.len();
+    let num_chunks = (size + srs_size - 1) / srs_size;
+    let polynomials: Vec<_> = (0..nb_polys)
+        .map(|_| {
+            let coeffs = (0..size).map(|_| ScalarField::rand(rng)).collect();
+            DensePolynomial::from_coefficients_vec(coeffs)
+        })
+        .collect();
+    let comms: Vec<_> = polynomials
+        .iter()
+        .map(|p| srs.full_srs.commit(p, num_chunks, rng))
+        .collect();
+    let evaluations = polynomials
+        .iter()
+        .zip(comms.iter())
+        .map(|(p, comm)| Evaluation {
+            commitment: comm.commitment.clone(),
+            evaluations: evaluation_points
+                .iter()
+                .map(|x| {
+                    p.coeffs
+                        .chunks(srs_size)
+                        .map(|chunk| DensePolynomial::from_coefficients_slice(chunk).evaluate(x))
+                        .collect()
+                })
+                .collect(),
+        })
+        .collect();
+    let blinders = comms.into_iter().map(|comm| comm.blinders).collect();
+    (polynomials, blinders, evaluations)
+}
+
+type PolynomialsToCombine<'a> = Vec<(
+    DensePolynomialOrEvaluations<'a, ScalarField, D<ScalarField>>,
+    PolyComm<ScalarField>,
+)>;
+
+fn polynomials_to_combine(
+    polynomials: &[DensePolynomial<ScalarField>],
+    blinders: Vec<PolyComm<ScalarField>>,
+) -> PolynomialsToCombine {
+    polynomials
+        .iter()
+        .zip(blinders)
+        .map(|(p, blinders)| (DensePolynomialOrEvaluations::DensePolynomial(p), blinders))
+        .collect()
+}
+
+#[test]
+fn test_kzg_proof_verifier_srs_size() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let x = ScalarField::rand(&mut rng);
+    let evaluation_points: Vec<_> = (0..3).map(|_| ScalarField::rand(&mut rng)).collect();
+    let polyscale = ScalarField::rand(&mut rng);
+
+    // Three points need four elements in the verifier SRS.
+    let srs = PairingSRS::<BN254>::create_trusted_setup_with_verifier_depth(x, 64, 4);
+    let (polys, blinders, evals) = random_evaluations(&srs, 2, 64, &evaluation_points, &mut rng);
+    let plnms = polynomials_to_combine(&polys, blinders);
+    let proof = KZGProof::create(&srs, plnms.as_slice(), &evaluation_points, polyscale).unwrap();
+    assert!(proof
+        .verify(&srs, &evals, polyscale, &evaluation_points)
+        .unwrap());
+
+    // The default verifier SRS only supports two points.
+    let small_srs = PairingSRS::<BN254>::create_trusted_setup(x, 64);
+    assert!(matches!(
+        proof.verify(&small_srs, &evals, polyscale, &evaluation_points),
+        Err(CommitmentError::VerifierSrsTooSmall {
+            available: 3,
+            requested: 4
+        })
+    ));
+}
+
+#[test]
+fn test_shplonk_proof_chunked_multi_point() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let srs = PairingSRS::<BN254>::create(64);
+
+    let shared_point = ScalarField::rand(&mut rng);
+    let points_1: Vec<_> = (0..3)
+        .map(|_| ScalarField::rand(&mut rng))
+        .chain([shared_point])
+        .collect();
+    let points_2 = vec![shared_point];
+    let points_3: Vec<_> = (0..2).map(|_| ScalarField::rand(&mut rng)).collect();
+
+    // Polynomials with 3, 1 and 2 chunks
+    let (polys_1, blinders_1, evals_1) = random_evaluations(&srs, 3, 150, &points_1, &mut rng);
+    let (polys_2, blinders_2, evals_2) = random_evaluations(&srs, 1, 40, &points_2, &mut rng);
+    let (polys_3, blinders_3, mut evals_3) = random_evaluations(&srs, 2, 100, &points_3, &mut rng);
+
+    let plnms_1 = polynomials_to_combine(&polys_1, blinders_1);
+    let plnms_2 = polynomials_to_combine(&polys_2, blinders_2);
+    let plnms_3 = polynomials_to_combine(&polys_3, blinders_3);
+
+    let polyscale = ScalarField::rand(&mut rng);
+
+    let proof = ShplonkProof::<BN254>::create(
+        &srs,
+        &[
+            (plnms_1.as_slice(), points_1.as_slice()),
+            (plnms_2.as_slice(), points_2.as_slice()),
+            (plnms_3.as_slice(), points_3.as_slice()),
+        ],
+        polyscale,
+        &mut base_sponge(),
+    )
+    .unwrap();
+
+    assert!(proof.verify(
+        &srs,
+        &[
+            (&evals_1, &points_1),
+            (&evals_2, &points_2),
+            (&evals_3, &points_3)
+        ],
+        polyscale,
+        &mut base_sponge(),
+    ));
+
+    // Changing one chunk of one evaluation must be detected.
+    evals_3[1].evaluations[0][1] += ScalarField::from(1u64);
+    assert!(!proof.verify(
+        &srs,
+        &[
+            (&evals_1, &points_1),
+            (&evals_2, &points_2),
+            (&evals_3, &points_3)
+        ],
+        polyscale,
+        &mut base_sponge(),
+    ));
+}
+
+#[test]
+fn test_shplonk_open_proof_batch_verify() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let srs = PairingSRS::<BN254>::create(32);
+    let group_map = <G1 as poly_commitment::commitment::CommitmentCurve>::Map::setup();
+
+    let polyscale = ScalarField::rand(&mut rng);
+    let instances: Vec<_> = (0..3)
+        .map(|_| {
+            let points: Vec<_> = (0..5).map(|_| ScalarField::rand(&mut rng)).collect();
+            let (polys, blinders, evals) = random_evaluations(&srs, 4, 70, &points, &mut rng);
+            let plnms = polynomials_to_combine(&polys, blinders);
+            let proof = ShplonkProof::<BN254>::open(
+                &srs,
+                &group_map,
+                &plnms,
+                &points,
+                polyscale,
+                ScalarField::zero(),
+                base_sponge(),
+                &mut rng,
+            );
+            (points, evals, proof)
+        })
+        .collect();
+
+    let (instances, proofs): (Vec<_>, Vec<_>) = instances
+        .into_iter()
+        .map(|(points, evals, proof)| ((points, evals), proof))
+        .unzip();
+    let mut batch: Vec<_> = instances
+        .into_iter()
+        .zip(proofs.iter())
+        .map(|((points, evals), proof)| BatchEvaluationProof {
+            sponge: base_sponge(),
+            evaluations: evals,
+            evaluation_points: points,
+            polyscale,
+            evalscale: ScalarField::zero(),
+            opening: proof,
+            combined_inner_product: ScalarField::zero(),
+        })
+        .collect();
+    assert!(<ShplonkProof<BN254> as OpenProof<G1>>::verify(
+        &srs, &group_map, &mut batch, &mut rng
+    ));
+
+    // A wrong proof in the batch must be detected.
+    let wrong_proof = ShplonkProof {
+        opening: proofs[0].quotient,
+        ..proofs[2].clone()
+    };
+    batch[2].opening = &wrong_proof;
+    for proof in batch.iter_mut() {
+        proof.sponge = base_sponge();
+    }
+    assert!(!<ShplonkProof<BN254> as OpenProof<G1>>::verify(
+        &srs, &group_map, &mut batch, &mut rng
+    ));
+}
+
 /// Our points in G2 are not actually in the correct subgroup and serialize well.
 #[test]
 fn check_srs_g2_valid_and_serializes() {
@@ -246,3 +479,149 @@ fn test_regression_commit_non_hiding_expected_number_of_chunks() {
 
     pbt_srs::test_regression_commit_non_hiding_expected_number_of_chunks::<G1, Srs>();
 }
+
+/// Write a field element of BN254 in the Montgomery form used by ptau files.
+fn write_ptau_field(buf: &mut Vec<u8>, x: ark_bn254::Fq) {
+    let modulus: BigUint = <ark_bn254::Fq as PrimeField>::MODULUS.into();
+    let montgomery: BigUint = (BigUint::from(x) << 256) % modulus;
+    let mut bytes = montgomery.to_bytes_le();
+    bytes.resize(32, 0);
+    buf.extend(bytes);
+}
+
+fn write_ptau_section(buf: &mut Vec<u8>, section: u32, content: &[u8]) {
+    buf.extend(section.to_le_bytes());
+    buf.extend((content.len() as u64).to_le_bytes());
+    buf.extend(content);
+}
+
+/// Write a ptau file of the given `power` for the toxic waste `tau`, with a
+/// custom modulus and custom points in G1.
+fn write_ptau(
+    tau: ScalarField,
+    power: u32,
+    modulus: BigUint,
+    g1_override: &[(usize, G1)],
+) -> Vec<u8> {
+    let mut header = vec![];
+    header.extend(32u32.to_le_bytes());
+    let mut modulus = modulus.to_bytes_le();
+    modulus.resize(32, 0);
+    header.extend(modulus);
+    header.extend(power.to_le_bytes());
+    header.extend(power.to_le_bytes());
+
+    let mut tau_g1 = vec![];
+    let mut tau_pow = ScalarField::from(1u64);
+    for i in 0..(1usize << (power + 1)) - 1 {
+        let point = g1_override
+            .iter()
+            .find(|(j, _)| *j == i)
+            .map_or((G1::generator() * tau_pow).into(), |(_, point)| *point);
+        write_ptau_field(&mut tau_g1, point.x);
+        write_ptau_field(&mut tau_g1, point.y);
+        tau_pow *= tau;
+    }
+
+    let mut tau_g2 = vec![];
+    let mut tau_pow = ScalarField::from(1u64);
+    for _ in 0..1usize << power {
+        let point: G2 = (G2::generator() * tau_pow).into();
+        for coordinate in [point.x, point.y] {
+            write_ptau_field(&mut tau_g2, coordinate.c0);
+            write_ptau_field(&mut tau_g2, coordinate.c1);
+        }
+        tau_pow *= tau;
+    }
+
+    let mut buf = b"ptau".to_vec();
+    buf.extend(1u32.to_le_bytes());
+    buf.extend(5u32.to_le_bytes());
+    write_ptau_section(&mut buf, 1, &header);
+    // A contribution section, which must be skipped
+    write_ptau_section(&mut buf, 7, &[0u8; 17]);
+    write_ptau_section(&mut buf, 2, &tau_g1);
+    write_ptau_section(&mut buf, 3, &tau_g2);
+    write_ptau_section(&mut buf, 4, &[]);
+    buf
+}
+
+fn bn254_base_modulus() -> BigUint {
+    <ark_bn254::Fq as PrimeField>::MODULUS.into()
+}
+
+#[test]
+fn test_pairing_srs_from_ptau() {
+    type BN254 = Bn<Config>;
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let tau = ScalarField::rand(&mut rng);
+
+    let ptau = write_ptau(tau, 4, bn254_base_modulus(), &[]);
+    let srs = PairingSRS::<BN254>::from_ptau(ptau.as_slice(), 16).unwrap();
+
+    let expected = PairingSRS::<BN254>::create_trusted_setup(tau, 16);
+    assert_eq!(srs, expected);
+    assert_eq!(
+        srs.get_lagrange_basis_from_domain_size(16),
+        expected.get_lagrange_basis_from_domain_size(16)
+    );
+
+    // Fewer powers than available can be loaded.
+    let srs = PairingSRS::<BN254>::from_ptau(ptau.as_slice(), 20).unwrap();
+    assert_eq!(srs, PairingSRS::<BN254>::create_trusted_setup(tau, 20));
+}
+
+#[test]
+fn test_pairing_srs_from_ptau_errors() {
+    type BN254 = Bn<Config>;
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let tau = ScalarField::rand(&mut rng);
+
+    let ptau = write_ptau(tau, 2, bn254_base_modulus(), &[]);
+    assert!(matches!(
+        PairingSRS::<BN254>::from_ptau(ptau.as_slice(), 8),
+        Err(PtauError::NotEnoughPowers {
+            section: 2,
+            available: 7,
+            requested: 8
+        })
+    ));
+    assert!(matches!(
+        PairingSRS::<BN254>::from_ptau(&ptau[..ptau.len() - 40], 4),
+        Err(PtauError::Io(_))
+    ));
+    assert!(matches!(
+        PairingSRS::<BN254>::from_ptau(&b"ptaz"[..], 4),
+        Err(PtauError::InvalidMagic)
+    ));
+
+    // A file for another curve
+    let scalar_modulus: BigUint = <ScalarField as PrimeField>::MODULUS.into();
+    let ptau = write_ptau(tau, 2, scalar_modulus, &[]);
+    assert!(matches!(
+        PairingSRS::<BN254>::from_ptau(ptau.as_slice(), 4),
+        Err(PtauError::WrongCurve)
+    ));
+
+    // A valid point, which is not the right power of tau
+    let other_tau = ScalarField::rand(&mut rng);
+    let wrong_point: G1 = (G1::generator() * other_tau).into();
+    let ptau = write_ptau(tau, 2, bn254_base_modulus(), &[(3, wrong_point)]);
+    assert!(matches!(
+        PairingSRS::<BN254>::from_ptau(ptau.as_slice(), 4),
+        Err(PtauError::InconsistentPowers)
+    ));
+    // The point is not loaded, so it is not checked.
+    assert!(PairingSRS::<BN254>::from_ptau(ptau.as_slice(), 3).is_ok());
+
+    // A point not on the curve
+    let invalid_point = G1::new_unchecked(wrong_point.x, wrong_point.x);
+    let ptau = write_ptau(tau, 2, bn254_base_modulus(), &[(1, invalid_point)]);
+    assert!(matches!(
+        PairingSRS::<BN254>::from_ptau(ptau.as_slice(), 4),
+        Err(PtauError::InvalidPoint {
+            section: 2,
+            index: 1
+        })
+    ));
+}
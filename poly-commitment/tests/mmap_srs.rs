@@ -0,0 +1,243 @@
+#![cfg(unix)]
+
+use ark_ff::{UniformRand, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Evaluations,
+    Radix2EvaluationDomain as D,
+};
+use mina_curves::pasta::{Fp, Vesta as VestaG};
+use poly_commitment::{
+    error::SrsFileError,
+    ipa::SRS,
+    mmap_srs::{MmapSRS, MSM_BATCH_SIZE},
+    pbt_srs::test_regression_commit_non_hiding_expected_number_of_chunks,
+    PolyComm, SRS as _,
+};
+use std::{fs, path::PathBuf};
+
+/// Returns an empty temporary directory for the test `name`.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mmap_srs_{name}_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_srs(srs: &SRS<VestaG>, path: &PathBuf) {
+    MmapSRS::write(srs, fs::File::create(path).unwrap()).unwrap();
+}
+
+#[test]
+fn test_mmap_srs_commitments() {
+    let dir = temp_dir("commitments");
+    let path = dir.join("vesta.srs");
+    let srs = SRS::<VestaG>::create(1 << 6);
+    write_srs(&srs, &path);
+
+    let mmap_srs = MmapSRS::<VestaG>::open(&path).unwrap();
+    mmap_srs.check().unwrap();
+    assert_eq!(mmap_srs.max_poly_size(), srs.g.len());
+    assert_eq!(mmap_srs.h(), srs.h);
+    assert_eq!(mmap_srs.g().iter().collect::<Vec<_>>(), srs.g);
+    assert_eq!(mmap_srs.to_srs(), srs);
+
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    for (degree, num_chunks) in [(10, 1), (1 << 6, 1), (200, 4), (200, 6)] {
+        let poly = DensePolynomial::<Fp>::rand(degree, rng);
+        assert_eq!(
+            mmap_srs.commit_non_hiding(&poly, num_chunks),
+            srs.commit_non_hiding(&poly, num_chunks)
+        );
+    }
+    let zero = DensePolynomial::<Fp>::zero();
+    assert_eq!(
+        mmap_srs.commit_non_hiding(&zero, 2),
+        srs.commit_non_hiding(&zero, 2)
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_mmap_srs_streaming_commitment() {
+    let dir = temp_dir("streaming");
+    let path = dir.join("vesta.srs");
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    // An SRS spanning several batches of points
+    let depth = MSM_BATCH_SIZE + 3;
+    let srs = unsafe { SRS::<VestaG>::create_trusted_setup(Fp::rand(rng), depth) };
+    write_srs(&srs, &path);
+
+    let mmap_srs = MmapSRS::<VestaG>::open(&path).unwrap();
+    let poly = DensePolynomial::<Fp>::rand(srs.g.len() - 1, rng);
+    assert_eq!(
+        mmap_srs.commit_non_hiding(&poly, 1),
+        srs.commit_non_hiding(&poly, 1)
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_mmap_srs_lagrange_cache() {
+    let dir = temp_dir("lagrange");
+    let path = dir.join("vesta.srs");
+    let srs = SRS::<VestaG>::create(1 << 5);
+    write_srs(&srs, &path);
+
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let mmap_srs = MmapSRS::<VestaG>::open(&path).unwrap();
+    // Smaller, equal and larger (chunked) domains than the SRS
+    for log_size in [4, 5, 7] {
+        let domain = D::<Fp>::new(1 << log_size).unwrap();
+        let basis = mmap_srs.get_mapped_lagrange_basis(domain).unwrap();
+        assert_eq!(basis.domain_size(), domain.size());
+        assert_eq!(&basis.to_vec(), srs.get_lagrange_basis(domain));
+        assert_eq!(
+            mmap_srs.get_lagrange_basis(domain),
+            srs.get_lagrange_basis(domain)
+        );
+        assert!(mmap_srs
+            .lagrange_cache_dir()
+            .join(format!("lagrange_{}.bin", domain.size()))
+            .exists());
+
+        let evals = Evaluations::from_vec_and_domain(
+            (0..domain.size()).map(|_| Fp::rand(rng)).collect(),
+            domain,
+        );
+        assert_eq!(
+            mmap_srs.commit_evaluations_non_hiding(domain, &evals),
+            srs.commit_evaluations_non_hiding(domain, &evals)
+        );
+    }
+
+    // Evaluations over a larger domain are subsampled
+    let domain = D::<Fp>::new(1 << 4).unwrap();
+    let large_domain = D::<Fp>::new(1 << 6).unwrap();
+    let evals = Evaluations::from_vec_and_domain(
+        (0..large_domain.size()).map(|_| Fp::rand(rng)).collect(),
+        large_domain,
+    );
+    let expected = srs.commit_evaluations_non_hiding(domain, &evals);
+    assert_eq!(
+        mmap_srs.commit_evaluations_non_hiding(domain, &evals),
+        expected
+    );
+
+    // A new instance reads the bases from the cache
+    let mmap_srs = MmapSRS::<VestaG>::open(&path).unwrap();
+    assert_eq!(
+        mmap_srs.commit_evaluations_non_hiding(domain, &evals),
+        expected
+    );
+
+    // The bases of another SRS are rejected
+    let other_path = dir.join("other.srs");
+    write_srs(&SRS::<VestaG>::create(1 << 4), &other_path);
+    let other_srs = MmapSRS::<VestaG>::open(&other_path)
+        .unwrap()
+        .with_lagrange_cache_dir(mmap_srs.lagrange_cache_dir());
+    assert!(matches!(
+        other_srs.get_mapped_lagrange_basis(domain),
+        Err(SrsFileError::StaleLagrangeBasis(16))
+    ));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_mmap_srs_errors() {
+    let dir = temp_dir("errors");
+    let path = dir.join("vesta.srs");
+    let srs = SRS::<VestaG>::create(1 << 3);
+    write_srs(&srs, &path);
+    let bytes = fs::read(&path).unwrap();
+    // The header is made of 24 bytes followed by a fingerprint of 32 bytes
+    let header_size = 24 + 32;
+    let point_size = (bytes.len() - header_size) / 9;
+
+    let open = |bytes: &[u8]| {
+        let path = dir.join("corrupted.srs");
+        fs::write(&path, bytes).unwrap();
+        MmapSRS::<VestaG>::open(&path)
+    };
+
+    let mut corrupted = bytes.clone();
+    corrupted[0] = b'x';
+    assert!(matches!(
+        open(&corrupted),
+        Err(SrsFileError::InvalidMagic(_))
+    ));
+
+    let mut corrupted = bytes.clone();
+    corrupted[8] = 2;
+    assert!(matches!(
+        open(&corrupted),
+        Err(SrsFileError::UnsupportedVersion(2))
+    ));
+
+    let mut corrupted = bytes.clone();
+    corrupted[12] += 1;
+    assert!(matches!(
+        open(&corrupted),
+        Err(SrsFileError::WrongPointSize { .. })
+    ));
+
+    assert!(matches!(
+        open(&bytes[..bytes.len() - 1]),
+        Err(SrsFileError::WrongLength { .. })
+    ));
+    assert!(matches!(
+        open(&bytes[..10]),
+        Err(SrsFileError::WrongLength { .. })
+    ));
+
+    let mut corrupted = bytes.clone();
+    corrupted[header_size..header_size + point_size].fill(0xff);
+    assert!(matches!(
+        open(&corrupted),
+        Err(SrsFileError::InvalidBlinder)
+    ));
+
+    // The points of `g` are only checked on demand
+    let mut corrupted = bytes.clone();
+    let offset = header_size + 4 * point_size;
+    corrupted[offset] ^= 1;
+    let mmap_srs = open(&corrupted).unwrap();
+    assert!(matches!(
+        mmap_srs.check(),
+        Err(SrsFileError::InvalidPoint(3))
+    ));
+
+    // The fingerprint is only checked on demand too
+    let mut corrupted = bytes.clone();
+    corrupted[24] ^= 1;
+    let mmap_srs = open(&corrupted).unwrap();
+    assert!(matches!(
+        mmap_srs.check(),
+        Err(SrsFileError::InvalidFingerprint)
+    ));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_mmap_srs_trait() {
+    test_regression_commit_non_hiding_expected_number_of_chunks::<VestaG, MmapSRS<VestaG>>();
+
+    // Blinded commitments match the ones of the in-memory SRS
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let mmap_srs = MmapSRS::<VestaG>::create(1 << 4);
+    let srs = mmap_srs.to_srs();
+    assert_eq!(srs, SRS::<VestaG>::create(1 << 4));
+    let poly = DensePolynomial::<Fp>::rand(40, rng);
+    let blinders = PolyComm::new(vec![Fp::rand(rng); 3]);
+    assert_eq!(
+        mmap_srs
+            .commit_custom(&poly, 3, &blinders)
+            .unwrap()
+            .commitment,
+        srs.commit_custom(&poly, 3, &blinders).unwrap().commitment
+    );
+}
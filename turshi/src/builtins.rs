@@ -0,0 +1,324 @@
+//! This module implements the builtins of Cairo supported by the runner.
+//!
+//! A builtin is given a segment of the memory, whose cells are split into
+//! instances of a fixed number of cells. The program passes a pointer to the
+//! next free instance of each builtin it uses as an argument of `main`, and
+//! returns the updated pointers. The cells of a builtin segment are either
+//! written by the program, or deduced by the builtin from the other cells of
+//! their instance when they are read, and must satisfy the rules of the builtin.
+//!
+//! The following builtins are supported:
+//! - `output`: a plain segment, whose content is part of the public memory;
+//! - `range_check`: each cell must be in the range `[0, 2^128)`;
+//! - `bitwise`: instances of 5 cells `x`, `y`, `x & y`, `x ^ y`, `x | y`,
+//!   where `x` and `y` are in the range `[0, 2^251)`;
+//! - `pedersen`: instances of 3 cells `x`, `y`, `H(x, y)`, where `x` and `y`
+//!   are in the range `[0, 2^254)` and `H` is the hash of [pedersen_hash];
+//! - `poseidon`: instances of 6 cells, the state given to the permutation of
+//!   [poseidon_permutation] followed by the permuted state.
+//!
+//! The runner executes the programs over the scalar field of a Pasta curve
+//! instead of the field of Cairo (see [CairoField]), so the `pedersen` and
+//! `poseidon` builtins are defined over this field, with the other Pasta curve
+//! and the Poseidon permutation of kimchi, and do not give the same hashes as
+//! Cairo. The `ecdsa`, `ec_op` and `keccak` builtins are not supported.
+
+use crate::word::CairoWord;
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::{Field, PrimeField};
+use mina_curves::pasta::{Fp, Fq, PallasParameters, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    pasta::{fp_kimchi, fq_kimchi},
+    permutation::poseidon_block_cipher,
+    poseidon::ArithmeticSpongeParams,
+};
+use num_bigint::BigUint;
+use o1_utils::FieldHelpers;
+use thiserror::Error;
+
+/// Number of bits of the values accepted by the range check builtin
+pub const RANGE_CHECK_BITS: usize = 128;
+
+/// Number of bits of the inputs of the bitwise builtin
+pub const BITWISE_BITS: usize = 251;
+
+/// Number of bits of the inputs of the pedersen builtin
+pub const PEDERSEN_BITS: usize = 254;
+
+/// Number of bits of the low part of the inputs of the pedersen builtin, see
+/// [pedersen_hash]
+pub const PEDERSEN_LOW_BITS: usize = 250;
+
+/// Width of the state of the permutation of the poseidon builtin
+pub const POSEIDON_WIDTH: usize = 3;
+
+/// The fields over which the programs are executed, which are the scalar
+/// fields of the Pasta curves. They provide the hash functions of the
+/// `pedersen` and `poseidon` builtins.
+pub trait CairoField: PrimeField {
+    /// The curve whose points have their coordinates in the field, on which
+    /// the `pedersen` builtin hashes
+    type Curve: SWCurveConfig<BaseField = Self>;
+
+    /// Returns the parameters of the Poseidon permutation of kimchi over the
+    /// field
+    fn poseidon_params() -> &'static ArithmeticSpongeParams<Self>;
+}
+
+impl CairoField for Fp {
+    type Curve = PallasParameters;
+
+    fn poseidon_params() -> &'static ArithmeticSpongeParams<Fp> {
+        fp_kimchi::static_params()
+    }
+}
+
+impl CairoField for Fq {
+    type Curve = VestaParameters;
+
+    fn poseidon_params() -> &'static ArithmeticSpongeParams<Fq> {
+        fq_kimchi::static_params()
+    }
+}
+
+/// Errors raised when the cells of a builtin segment break its rules
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CairoBuiltinError {
+    #[error("the cell {1} of the {0} segment is out of range")]
+    OutOfRange(&'static str, u64),
+    #[error("the cell {1} of the {0} segment does not match its inputs")]
+    WrongOutput(&'static str, u64),
+}
+
+/// The builtins supported by the runner
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CairoBuiltin {
+    /// Output of the program
+    Output,
+    /// Range check of 128-bit values
+    RangeCheck,
+    /// Bitwise operations on 251-bit values
+    Bitwise,
+    /// Pedersen hash of two 254-bit values
+    Pedersen,
+    /// Poseidon permutation of a state of 3 values
+    Poseidon,
+}
+
+impl CairoBuiltin {
+    /// Returns the builtin named `name` in the compiled programs, if supported
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "output" => Some(CairoBuiltin::Output),
+            "range_check" => Some(CairoBuiltin::RangeCheck),
+            "bitwise" => Some(CairoBuiltin::Bitwise),
+            "pedersen" => Some(CairoBuiltin::Pedersen),
+            "poseidon" => Some(CairoBuiltin::Poseidon),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the builtin in the compiled programs
+    pub fn name(&self) -> &'static str {
+        match self {
+            CairoBuiltin::Output => "output",
+            CairoBuiltin::RangeCheck => "range_check",
+            CairoBuiltin::Bitwise => "bitwise",
+            CairoBuiltin::Pedersen => "pedersen",
+            CairoBuiltin::Poseidon => "poseidon",
+        }
+    }
+
+    /// Returns the number of cells of an instance of the builtin
+    pub fn cells_per_instance(&self) -> u64 {
+        match self {
+            CairoBuiltin::Output | CairoBuiltin::RangeCheck => 1,
+            CairoBuiltin::Bitwise => 5,
+            CairoBuiltin::Pedersen => 3,
+            CairoBuiltin::Poseidon => 2 * POSEIDON_WIDTH as u64,
+        }
+    }
+
+    /// Returns the number of cells of an instance of the builtin that are
+    /// written by the program, the other ones being deduced from them
+    pub fn inputs_per_instance(&self) -> u64 {
+        match self {
+            CairoBuiltin::Output | CairoBuiltin::RangeCheck => 1,
+            CairoBuiltin::Bitwise | CairoBuiltin::Pedersen => 2,
+            CairoBuiltin::Poseidon => POSEIDON_WIDTH as u64,
+        }
+    }
+
+    /// Returns the number of bits of the inputs of the builtin, if they are
+    /// bounded
+    pub fn input_bits(&self) -> Option<usize> {
+        match self {
+            CairoBuiltin::Output | CairoBuiltin::Poseidon => None,
+            CairoBuiltin::RangeCheck => Some(RANGE_CHECK_BITS),
+            CairoBuiltin::Bitwise => Some(BITWISE_BITS),
+            CairoBuiltin::Pedersen => Some(PEDERSEN_BITS),
+        }
+    }
+
+    /// Returns the outputs of an instance of the builtin from its inputs
+    pub fn outputs<F: CairoField>(&self, inputs: &[F]) -> Vec<F> {
+        match self {
+            CairoBuiltin::Output | CairoBuiltin::RangeCheck => vec![],
+            CairoBuiltin::Bitwise => vec![
+                bitwise(&inputs[0], &inputs[1], |a, b| a & b),
+                bitwise(&inputs[0], &inputs[1], |a, b| a ^ b),
+                bitwise(&inputs[0], &inputs[1], |a, b| a | b),
+            ],
+            CairoBuiltin::Pedersen => vec![pedersen_hash(inputs[0], inputs[1])],
+            CairoBuiltin::Poseidon => {
+                poseidon_permutation([inputs[0], inputs[1], inputs[2]]).to_vec()
+            }
+        }
+    }
+
+    /// Deduces the content of the cell at `offset` in the segment of the
+    /// builtin from the other cells of its instance, if possible
+    pub fn deduce<F: CairoField>(
+        &self,
+        offset: u64,
+        segment: &[Option<CairoWord<F>>],
+    ) -> Option<F> {
+        let cells = self.cells_per_instance();
+        let (instance, index) = (offset - offset % cells, offset % cells);
+        let output = index.checked_sub(self.inputs_per_instance())?;
+        let inputs: Vec<F> = (0..self.inputs_per_instance())
+            .map(|i| {
+                segment
+                    .get((instance + i) as usize)
+                    .copied()
+                    .flatten()
+                    .map(|word| word.word())
+            })
+            .collect::<Option<_>>()?;
+        if let Some(bits) = self.input_bits() {
+            if !inputs.iter().all(|input| fits_in(input, bits)) {
+                return None;
+            }
+        }
+        self.outputs(&inputs).get(output as usize).copied()
+    }
+
+    /// Completes the segment of the builtin with whole instances. The missing
+    /// inputs of the instances of the builtins that have outputs are set to
+    /// zero, and their missing outputs are deduced.
+    pub fn complete<F: CairoField>(&self, segment: &mut Vec<Option<CairoWord<F>>>) {
+        let cells = self.cells_per_instance() as usize;
+        let inputs = self.inputs_per_instance() as usize;
+        if cells == inputs {
+            return;
+        }
+        segment.resize((segment.len() + cells - 1) / cells * cells, None);
+        for offset in 0..segment.len() {
+            if segment[offset].is_none() {
+                let value = if offset % cells < inputs {
+                    Some(F::zero())
+                } else {
+                    self.deduce(offset as u64, segment)
+                };
+                segment[offset] = value.map(CairoWord::new);
+            }
+        }
+    }
+
+    /// Checks that the cells of the segment of the builtin follow its rules
+    ///
+    /// # Errors
+    ///
+    /// Will give error if a cell is out of range or does not match the inputs
+    /// of its instance.
+    pub fn check<F: CairoField>(
+        &self,
+        segment: &[Option<CairoWord<F>>],
+    ) -> Result<(), CairoBuiltinError> {
+        for (offset, cell) in segment.iter().enumerate() {
+            let (offset, Some(word)) = (offset as u64, cell) else {
+                continue;
+            };
+            if offset % self.cells_per_instance() < self.inputs_per_instance() {
+                if let Some(bits) = self.input_bits() {
+                    if !fits_in(&word.word(), bits) {
+                        return Err(CairoBuiltinError::OutOfRange(self.name(), offset));
+                    }
+                }
+            } else if self.deduce(offset, segment) != Some(word.word()) {
+                return Err(CairoBuiltinError::WrongOutput(self.name(), offset));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns whether the canonical representation of `x` has at most `bits` bits
+pub(crate) fn fits_in<F: Field>(x: &F, bits: usize) -> bool {
+    x.to_bits().iter().skip(bits).all(|bit| !bit)
+}
+
+/// Applies the bitwise operation `op` to the bytes of `x` and `y`
+fn bitwise<F: Field>(x: &F, y: &F, op: impl Fn(u8, u8) -> u8) -> F {
+    let bytes: Vec<u8> = x
+        .to_bytes()
+        .iter()
+        .zip(y.to_bytes())
+        .map(|(a, b)| op(*a, b))
+        .collect();
+    // the result has as many bits as the inputs, so it is a valid field element
+    F::from_bytes(&bytes).expect("bitwise result out of the field")
+}
+
+/// Returns the points of the curve of `F` used by [pedersen_hash]. The i-th
+/// point is the point of smallest x-coordinate from `(i + 1) * 2^64`, with
+/// the smallest y-coordinate.
+pub fn pedersen_points<F: CairoField>() -> [Affine<F::Curve>; 4] {
+    std::array::from_fn(|i| {
+        let mut x = F::from(i as u64 + 1) * F::from(2u64).pow([64]);
+        loop {
+            if let Some(point) = Affine::<F::Curve>::get_point_from_x_unchecked(x, false) {
+                return point;
+            }
+            x += F::one();
+        }
+    })
+}
+
+/// Returns the Pedersen hash of `x` and `y`, which must be in the range
+/// `[0, 2^254)`. Splitting each input into its 250 low bits `l` and its 4 high
+/// bits `h`, the hash is the x-coordinate of the sum of the points
+/// `[1 + 2^250 + 2 l] P` and `[1 + 2^5 + 2 h] Q` of each input, where `P` and
+/// `Q` are two distinct points of [pedersen_points]. These are the points
+/// computed by the variable base scalar multiplication gates of kimchi with
+/// 250 and 5 bits.
+pub fn pedersen_hash<F: CairoField>(x: F, y: F) -> F {
+    let points = pedersen_points::<F>();
+    let scale = |point: &Affine<F::Curve>, scalar: BigUint, bits: usize| {
+        let one = BigUint::from(1u32);
+        let shifted = &one + (&one << bits) + (scalar << 1);
+        point.mul_bigint(shifted.to_u64_digits())
+    };
+    let low_mask = (BigUint::from(1u32) << PEDERSEN_LOW_BITS) - 1u32;
+    let hash: Projective<F::Curve> = [x, y]
+        .iter()
+        .zip(points.chunks(2))
+        .map(|(input, points)| {
+            let input = input.to_biguint();
+            scale(&points[0], &input & &low_mask, PEDERSEN_LOW_BITS)
+                + scale(&points[1], input >> PEDERSEN_LOW_BITS, 5)
+        })
+        .sum();
+    hash.into_affine().x
+}
+
+/// Returns the permutation of `state` by the Poseidon permutation of kimchi
+pub fn poseidon_permutation<F: CairoField>(state: [F; POSEIDON_WIDTH]) -> [F; POSEIDON_WIDTH] {
+    let mut state = state.to_vec();
+    poseidon_block_cipher::<F, PlonkSpongeConstantsKimchi>(F::poseidon_params(), &mut state);
+    std::array::from_fn(|i| state[i])
+}
@@ -0,0 +1,530 @@
+//! This module implements the execution of the hints of Cairo programs.
+//!
+//! Hints are snippets of Python code attached to the instructions of a compiled
+//! program, which the runner executes right before the instruction at their
+//! program counter. They give the non-deterministic values of the memory that
+//! the program cannot compute by itself, such as the pointers to new segments,
+//! or the quotient and remainder of a division, which the program then checks.
+//!
+//! The runner does not interpret Python: it hands the hints to a
+//! [HintProcessor], which recognizes their code and executes the corresponding
+//! logic through a [HintContext]. The context gives access to the pointers, to
+//! the memory, and to the variables of the program referred to as `ids.<name>`
+//! in the code of the hint. The [BuiltinHintProcessor] implements the hints of
+//! the common library of Cairo listed in this module, and custom processors can
+//! handle other hints before falling back to it.
+
+use std::collections::HashMap;
+
+use ark_ff::{Field, PrimeField};
+use num_bigint::{BigInt, BigUint, Sign};
+use o1_utils::FieldHelpers;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    builtins::{fits_in, CairoField, RANGE_CHECK_BITS},
+    memory::CairoMemory,
+    runner::{CairoState, Pointers},
+};
+
+/// Hint of `alloc`
+pub const ALLOC: &str = "memory[ap] = segments.add()";
+
+/// Hint entering the scope of the loop of `memcpy`
+pub const MEMCPY_ENTER_SCOPE: &str = "vm_enter_scope({'n': ids.len})";
+
+/// Hint of the loop of `memcpy`
+pub const MEMCPY_CONTINUE_COPYING: &str = "n -= 1
+ids.continue_copying = 1 if n > 0 else 0";
+
+/// Hint entering the scope of the loop of `memset`
+pub const MEMSET_ENTER_SCOPE: &str = "vm_enter_scope({'n': ids.n})";
+
+/// Hint of the loop of `memset`
+pub const MEMSET_CONTINUE_LOOP: &str = "n -= 1
+ids.continue_loop = 1 if n > 0 else 0";
+
+/// Hint exiting the scope of a loop
+pub const VM_EXIT_SCOPE: &str = "vm_exit_scope()";
+
+/// Hint of `assert_nn`
+pub const ASSERT_NN: &str = "from starkware.cairo.common.math_utils import assert_integer
+assert_integer(ids.a)
+assert 0 <= ids.a % PRIME < range_check_builtin.bound, f'a = {ids.a} is out of range.'";
+
+/// Hint of `assert_not_zero`
+pub const ASSERT_NOT_ZERO: &str = "from starkware.cairo.common.math_utils import assert_integer
+assert_integer(ids.value)
+assert ids.value % PRIME != 0, f'assert_not_zero failed: {ids.value} = 0.'";
+
+/// Hint of `assert_not_equal`
+pub const ASSERT_NOT_EQUAL: &str =
+    "from starkware.cairo.lang.vm.relocatable import RelocatableValue
+both_ints = isinstance(ids.a, int) and isinstance(ids.b, int)
+both_relocatable = (
+    isinstance(ids.a, RelocatableValue) and isinstance(ids.b, RelocatableValue) and
+    ids.a.segment_index == ids.b.segment_index)
+assert both_ints or both_relocatable, \\
+    f'assert_not_equal failed: non-comparable values: {ids.a}, {ids.b}.'
+assert (ids.a - ids.b) % PRIME != 0, f'assert_not_equal failed: {ids.a} = {ids.b}.'";
+
+/// Hint of `is_nn`, and of the functions based on it such as `is_le`
+pub const IS_NN: &str = "memory[ap] = 0 if 0 <= (ids.a % PRIME) < range_check_builtin.bound else 1";
+
+/// Hint of `is_nn` when `a` is out of range
+pub const IS_NN_OUT_OF_RANGE: &str =
+    "memory[ap] = 0 if 0 <= ((-ids.a - 1) % PRIME) < range_check_builtin.bound else 1";
+
+/// Hint of `unsigned_div_rem`
+pub const UNSIGNED_DIV_REM: &str = "from starkware.cairo.common.math_utils import assert_integer
+assert_integer(ids.div)
+assert 0 < ids.div <= PRIME // range_check_builtin.bound, \\
+    f'div={hex(ids.div)} is out of the valid range.'
+ids.q, ids.r = divmod(ids.value, ids.div)";
+
+/// Hint of `signed_div_rem`
+pub const SIGNED_DIV_REM: &str =
+    "from starkware.cairo.common.math_utils import as_int, assert_integer
+
+assert_integer(ids.div)
+assert 0 < ids.div <= PRIME // range_check_builtin.bound, \\
+    f'div={hex(ids.div)} is out of the valid range.'
+
+assert_integer(ids.bound)
+assert ids.bound <= range_check_builtin.bound // 2, \\
+    f'bound={hex(ids.bound)} is out of the valid range.'
+
+int_value = as_int(ids.value, PRIME)
+q, ids.r = divmod(int_value, ids.div)
+
+assert -ids.bound <= q < ids.bound, \\
+    f'{int_value} / {ids.div} = {q} is out of the range [{-ids.bound}, {ids.bound}).'
+
+ids.biased_q = q + ids.bound";
+
+/// Errors raised while executing a hint
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum HintError {
+    #[error("unknown hint {0:?}")]
+    UnknownHint(String),
+    #[error("the hint does not refer to ids.{0}")]
+    UnknownIdentifier(String),
+    #[error("the value of ids.{0} is not known")]
+    UnknownValue(String),
+    #[error("ids.{0} cannot be accessed by the hint")]
+    InvalidReference(String),
+    #[error("the variable {0} is not defined in the current scope")]
+    UnknownScopeVariable(String),
+    #[error("the hint failed: {0}")]
+    Failed(String),
+}
+
+/// Register a reference is relative to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Register {
+    /// allocation pointer
+    Ap,
+    /// frame pointer
+    Fp,
+}
+
+/// Tracking of the allocation pointer by the compiler: within a group, the
+/// allocation pointer is known to be the one at the start of the group plus
+/// the offset
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct ApTracking {
+    /// group of instructions in which the allocation pointer is tracked
+    pub group: u64,
+    /// offset of the allocation pointer from the start of the group
+    pub offset: u64,
+}
+
+/// A reference to a variable of the program, as compiled by `cairo-compile`:
+/// `[cast(reg + (off), type)]` for a variable stored in memory, or
+/// `cast([reg + (off)] + extra, type)` for a pointer stored in memory, among
+/// other combinations. The outer dereference is optional in both cases.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HintReference {
+    /// register of the address
+    register: Register,
+    /// offset added to the register
+    offset: i64,
+    /// whether the register plus the offset is dereferenced
+    inner_dereference: bool,
+    /// offset added after the inner dereference
+    extra_offset: i64,
+    /// whether the variable is the content of the cell at the address
+    dereference: bool,
+    /// tracking of the allocation pointer where the reference is defined
+    ap_tracking: ApTracking,
+}
+
+impl HintReference {
+    /// Parses the value of a reference of a compiled program, defined where
+    /// the allocation pointer is tracked by `ap_tracking`
+    pub fn parse(value: &str, ap_tracking: ApTracking) -> Option<Self> {
+        let value: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+        let (dereference, cast) = match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            Some(inner) => (true, inner),
+            None => (false, value.as_str()),
+        };
+        // the type of the cast is ignored, the expression does not contain commas
+        let expr = cast.strip_prefix("cast(")?.split(',').next()?;
+        let (inner_dereference, (register, offset), rest) = match expr.strip_prefix('[') {
+            Some(expr) => {
+                let end = expr.find(']')?;
+                (true, parse_register(&expr[..end])?, &expr[end + 1..])
+            }
+            None => (false, parse_register(expr)?, ""),
+        };
+        let extra_offset = match rest {
+            "" => 0,
+            rest => parse_offset(rest.strip_prefix('+')?)?,
+        };
+        Some(HintReference {
+            register,
+            offset,
+            inner_dereference,
+            extra_offset,
+            dereference,
+            ap_tracking,
+        })
+    }
+}
+
+/// A hint of a compiled program
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hint {
+    /// Python code of the hint
+    code: String,
+    /// references to the variables accessible by the hint, by name
+    ids: HashMap<String, HintReference>,
+    /// tracking of the allocation pointer at the hint
+    ap_tracking: ApTracking,
+}
+
+impl Hint {
+    /// Creates a hint from its code, the references to the variables it can
+    /// access, and the tracking of the allocation pointer at the hint
+    pub fn new(code: &str, ids: HashMap<String, HintReference>, ap_tracking: ApTracking) -> Self {
+        Hint {
+            code: code.to_string(),
+            ids,
+            ap_tracking,
+        }
+    }
+
+    /// Returns the Python code of the hint
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+}
+
+/// The state of the execution given to a [HintProcessor] to execute a hint
+pub struct HintContext<'a, F> {
+    /// memory of the execution
+    mem: &'a mut CairoMemory<F>,
+    /// pointers at the instruction of the hint
+    ptrs: CairoState<F>,
+    /// hint to execute
+    hint: &'a Hint,
+}
+
+impl<'a, F: CairoField> HintContext<'a, F> {
+    /// Creates the context to execute `hint` with the pointers `ptrs`
+    pub fn new(mem: &'a mut CairoMemory<F>, ptrs: CairoState<F>, hint: &'a Hint) -> Self {
+        HintContext { mem, ptrs, hint }
+    }
+
+    /// Returns the Python code of the hint
+    pub fn code(&self) -> &str {
+        self.hint.code()
+    }
+
+    /// Returns the memory of the execution
+    pub fn mem(&mut self) -> &mut CairoMemory<F> {
+        self.mem
+    }
+
+    /// Returns the value of the variable `ids.name`
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the hint cannot access the variable, or if its value
+    /// is not in the memory yet.
+    pub fn get(&mut self, name: &str) -> Result<F, HintError> {
+        let (reference, address) = self.resolve(name)?;
+        if reference.dereference {
+            self.mem
+                .read(address)
+                .ok_or_else(|| HintError::UnknownValue(name.to_string()))
+        } else {
+            Ok(address)
+        }
+    }
+
+    /// Writes `value` in the variable `ids.name`
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the hint cannot access the variable, or if the
+    /// variable is not stored in memory.
+    pub fn set(&mut self, name: &str, value: F) -> Result<(), HintError> {
+        match self.resolve(name)? {
+            (reference, address) if reference.dereference => {
+                self.mem.write(address, value);
+                Ok(())
+            }
+            _ => Err(HintError::InvalidReference(name.to_string())),
+        }
+    }
+
+    /// Returns the reference to `ids.name` and the value of the expression
+    /// inside its outer dereference
+    fn resolve(&mut self, name: &str) -> Result<(HintReference, F), HintError> {
+        let reference = *self
+            .hint
+            .ids
+            .get(name)
+            .ok_or_else(|| HintError::UnknownIdentifier(name.to_string()))?;
+        let register = match reference.register {
+            Register::Fp => self.ptrs.fp(),
+            Register::Ap => {
+                // the allocation pointer where the reference was defined is
+                // only known if it is tracked in the same group as the hint
+                let (at_reference, at_hint) = (reference.ap_tracking, self.hint.ap_tracking);
+                if at_reference.group != at_hint.group {
+                    return Err(HintError::InvalidReference(name.to_string()));
+                }
+                self.ptrs.ap() - F::from(at_hint.offset) + F::from(at_reference.offset)
+            }
+        };
+        let mut address = register + from_i64::<F>(reference.offset);
+        if reference.inner_dereference {
+            address = self
+                .mem
+                .read(address)
+                .ok_or_else(|| HintError::UnknownValue(name.to_string()))?
+                + from_i64::<F>(reference.extra_offset);
+        }
+        Ok((reference, address))
+    }
+}
+
+impl<'a, F: Field> Pointers<F> for HintContext<'a, F> {
+    fn pc(&self) -> F {
+        self.ptrs.pc()
+    }
+
+    fn ap(&self) -> F {
+        self.ptrs.ap()
+    }
+
+    fn fp(&self) -> F {
+        self.ptrs.fp()
+    }
+}
+
+/// This trait is implemented by the executors of the hints of Cairo programs.
+/// It is also implemented by closures taking a [HintContext].
+pub trait HintProcessor<F> {
+    /// Executes the hint of the context
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the hint is unknown, or if its execution fails.
+    fn execute(&mut self, ctx: &mut HintContext<F>) -> Result<(), HintError>;
+}
+
+impl<F, T> HintProcessor<F> for T
+where
+    T: FnMut(&mut HintContext<F>) -> Result<(), HintError>,
+{
+    fn execute(&mut self, ctx: &mut HintContext<F>) -> Result<(), HintError> {
+        self(ctx)
+    }
+}
+
+/// Executor of the hints of the common library of Cairo: allocation of
+/// segments, loops of `memcpy` and `memset`, the `assert_nn` family of
+/// comparisons, and divisions
+#[derive(Default)]
+pub struct BuiltinHintProcessor<F> {
+    /// variables of the scopes entered by the hints, innermost last
+    scopes: Vec<HashMap<String, F>>,
+}
+
+impl<F: CairoField> HintProcessor<F> for BuiltinHintProcessor<F> {
+    fn execute(&mut self, ctx: &mut HintContext<F>) -> Result<(), HintError> {
+        match ctx.code().trim() {
+            ALLOC => {
+                let segment = ctx.mem().add_segment();
+                let ap = ctx.ap();
+                ctx.mem().write(ap, segment);
+            }
+            MEMCPY_ENTER_SCOPE => self.enter_scope("n", ctx.get("len")?),
+            MEMSET_ENTER_SCOPE => self.enter_scope("n", ctx.get("n")?),
+            MEMCPY_CONTINUE_COPYING => {
+                let n = self.decrement("n")?;
+                ctx.set("continue_copying", F::from(u64::from(!n.is_zero())))?;
+            }
+            MEMSET_CONTINUE_LOOP => {
+                let n = self.decrement("n")?;
+                ctx.set("continue_loop", F::from(u64::from(!n.is_zero())))?;
+            }
+            VM_EXIT_SCOPE => {
+                self.scopes
+                    .pop()
+                    .ok_or_else(|| HintError::Failed("no scope to exit".to_string()))?;
+            }
+            ASSERT_NN => {
+                let a = ctx.get("a")?;
+                if !fits_in(&a, RANGE_CHECK_BITS) {
+                    return Err(HintError::Failed(format!("a = {a} is out of range.")));
+                }
+            }
+            ASSERT_NOT_ZERO => {
+                let value = ctx.get("value")?;
+                if value.is_zero() {
+                    return Err(HintError::Failed(format!(
+                        "assert_not_zero failed: {value} = 0."
+                    )));
+                }
+            }
+            ASSERT_NOT_EQUAL => {
+                let (a, b) = (ctx.get("a")?, ctx.get("b")?);
+                if a == b {
+                    return Err(HintError::Failed(format!(
+                        "assert_not_equal failed: {a} = {b}."
+                    )));
+                }
+            }
+            IS_NN => {
+                let a = ctx.get("a")?;
+                let ap = ctx.ap();
+                let is_neg = !fits_in(&a, RANGE_CHECK_BITS);
+                ctx.mem().write(ap, F::from(u64::from(is_neg)));
+            }
+            IS_NN_OUT_OF_RANGE => {
+                let a = ctx.get("a")?;
+                let ap = ctx.ap();
+                let is_neg = !fits_in(&(-a - F::one()), RANGE_CHECK_BITS);
+                ctx.mem().write(ap, F::from(u64::from(is_neg)));
+            }
+            UNSIGNED_DIV_REM => {
+                let div = check_divisor(ctx.get("div")?)?;
+                let value = ctx.get("value")?.to_biguint();
+                ctx.set("q", to_field(&(&value / &div)))?;
+                ctx.set("r", to_field(&(&value % &div)))?;
+            }
+            SIGNED_DIV_REM => {
+                let div = BigInt::from(check_divisor(ctx.get("div")?)?);
+                let bound = ctx.get("bound")?.to_biguint();
+                if bound > BigUint::from(1u32) << (RANGE_CHECK_BITS - 1) {
+                    return Err(HintError::Failed(format!(
+                        "bound={bound:#x} is out of the valid range."
+                    )));
+                }
+                let bound = BigInt::from(bound);
+                let value = as_int(ctx.get("value")?);
+                // floor division, the remainder has the sign of the divisor
+                let (mut q, mut r) = (&value / &div, &value % &div);
+                if r.sign() == Sign::Minus {
+                    q -= 1;
+                    r += &div;
+                }
+                if q < -&bound || q >= bound {
+                    return Err(HintError::Failed(format!(
+                        "{value} / {div} = {q} is out of the range [{}, {bound}).",
+                        -&bound
+                    )));
+                }
+                let biased_q = (q + bound).to_biguint().expect("q is at least -bound");
+                ctx.set("r", to_field(&r.to_biguint().expect("r is non-negative")))?;
+                ctx.set("biased_q", to_field(&biased_q))?;
+            }
+            _ => return Err(HintError::UnknownHint(ctx.code().to_string())),
+        }
+        Ok(())
+    }
+}
+
+impl<F: CairoField> BuiltinHintProcessor<F> {
+    /// Enters a new scope, in which `name` is set to `value`
+    fn enter_scope(&mut self, name: &str, value: F) {
+        self.scopes.push(HashMap::from([(name.to_string(), value)]));
+    }
+
+    /// Decrements the variable `name` of the current scope and returns its
+    /// new value
+    fn decrement(&mut self, name: &str) -> Result<F, HintError> {
+        let value = self
+            .scopes
+            .last_mut()
+            .and_then(|scope| scope.get_mut(name))
+            .ok_or_else(|| HintError::UnknownScopeVariable(name.to_string()))?;
+        *value -= F::one();
+        Ok(*value)
+    }
+}
+
+/// Checks that `div` is a valid divisor for the divisions using the range
+/// check builtin, and returns it as an integer
+fn check_divisor<F: PrimeField>(div: F) -> Result<BigUint, HintError> {
+    let div = div.to_biguint();
+    let max = F::modulus_biguint() >> RANGE_CHECK_BITS;
+    if div == BigUint::from(0u32) || div > max {
+        return Err(HintError::Failed(format!(
+            "div={div:#x} is out of the valid range."
+        )));
+    }
+    Ok(div)
+}
+
+/// Returns the integer in `(-p/2, p/2]` represented by `x`
+fn as_int<F: PrimeField>(x: F) -> BigInt {
+    let (x, modulus) = (BigInt::from(x.to_biguint()), F::modulus_biguint());
+    if x > BigInt::from(&modulus >> 1) {
+        x - BigInt::from(modulus)
+    } else {
+        x
+    }
+}
+
+/// Converts an integer smaller than the modulus to a field element
+fn to_field<F: PrimeField>(x: &BigUint) -> F {
+    F::from_biguint(x).expect("integer smaller than the modulus")
+}
+
+/// Converts a signed offset to a field element
+fn from_i64<F: Field>(x: i64) -> F {
+    if x < 0 {
+        -F::from(x.unsigned_abs())
+    } else {
+        F::from(x as u64)
+    }
+}
+
+/// Parses `reg` or `reg+(off)`
+fn parse_register(expr: &str) -> Option<(Register, i64)> {
+    let (register, offset) = match expr.split_once('+') {
+        Some((register, offset)) => (register, parse_offset(offset)?),
+        None => (expr, 0),
+    };
+    match register {
+        "ap" => Some((Register::Ap, offset)),
+        "fp" => Some((Register::Fp, offset)),
+        _ => None,
+    }
+}
+
+/// Parses `off` or `(off)`
+fn parse_offset(offset: &str) -> Option<i64> {
+    offset
+        .strip_prefix('(')
+        .and_then(|offset| offset.strip_suffix(')'))
+        .unwrap_or(offset)
+        .parse()
+        .ok()
+}
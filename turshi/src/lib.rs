@@ -6,13 +6,19 @@
 //! logic which is represented as steps of computation making up the full
 //! program.
 
+pub mod builtins;
 pub mod flags;
 pub mod helper;
+pub mod hints;
+pub mod loader;
 pub mod memory;
 pub mod runner;
 pub mod word;
 
 pub use self::{
+    builtins::{CairoBuiltin, CairoField},
+    hints::{BuiltinHintProcessor, Hint, HintProcessor},
+    loader::CairoCompiledProgram,
     memory::CairoMemory,
     runner::{CairoInstruction, CairoProgram, Pointers},
     word::{FlagBits, Offsets},
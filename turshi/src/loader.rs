@@ -0,0 +1,268 @@
+//! This module loads the programs compiled by `cairo-compile`, given as JSON
+//! files containing the bytecode of the program (`data`), the prime it was
+//! compiled for, the list of builtins it uses, its identifiers, among which
+//! the `main` function, and its hints with the references to the variables
+//! they access.
+//!
+//! The words of the bytecode are elements of the Cairo field. Negative values,
+//! such as relative jumps backwards, are represented by their opposite modulo
+//! the Cairo prime. They are converted to the corresponding negative values in
+//! the field `F` of the runner.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    builtins::CairoBuiltin,
+    hints::{ApTracking, Hint, HintReference},
+    memory::CairoMemory,
+};
+
+/// The value written in the initial frame of the program as the return frame
+/// pointer and the return program counter of `main`. As the runner stops when
+/// the program counter goes past the allocated memory, the execution ends when
+/// `main` returns.
+pub const END_OF_PROGRAM: u64 = 1 << 32;
+
+/// Errors raised while loading a compiled Cairo program
+#[derive(Error, Debug)]
+pub enum CairoLoaderError {
+    #[error("cannot read the program: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("cannot parse the program: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid field element {0}")]
+    InvalidFieldElement(String),
+    #[error("the program does not define the function {0}")]
+    MissingMain(String),
+    #[error("the builtins {0:?} are not supported")]
+    UnsupportedBuiltins(Vec<String>),
+    #[error("invalid program counter {0} of a hint")]
+    InvalidHintPc(String),
+    #[error("invalid reference {0}")]
+    InvalidReference(String),
+}
+
+/// The part of an identifier of a compiled program used by the loader
+#[derive(Deserialize)]
+struct Identifier {
+    #[serde(rename = "type")]
+    typ: String,
+    pc: Option<u64>,
+}
+
+/// The part of the flow tracking data of a hint used by the loader
+#[derive(Deserialize)]
+struct FlowTrackingData {
+    ap_tracking: ApTracking,
+    #[serde(default)]
+    reference_ids: HashMap<String, usize>,
+}
+
+/// The part of a hint of a compiled program used by the loader
+#[derive(Deserialize)]
+struct HintJson {
+    code: String,
+    flow_tracking_data: FlowTrackingData,
+}
+
+/// The part of a reference of a compiled program used by the loader
+#[derive(Deserialize)]
+struct Reference {
+    ap_tracking_data: ApTracking,
+    value: String,
+}
+
+/// The references of a compiled program
+#[derive(Deserialize, Default)]
+struct ReferenceManager {
+    references: Vec<Reference>,
+}
+
+/// The part of a compiled program used by the loader
+#[derive(Deserialize)]
+struct CompiledProgramJson {
+    prime: String,
+    data: Vec<String>,
+    #[serde(default)]
+    builtins: Vec<String>,
+    #[serde(default = "default_main_scope")]
+    main_scope: String,
+    #[serde(default)]
+    identifiers: HashMap<String, Identifier>,
+    #[serde(default)]
+    hints: HashMap<String, Vec<HintJson>>,
+    #[serde(default)]
+    reference_manager: ReferenceManager,
+}
+
+fn default_main_scope() -> String {
+    "__main__".to_string()
+}
+
+/// A program compiled by `cairo-compile`
+pub struct CairoCompiledProgram<F> {
+    /// bytecode of the program
+    data: Vec<F>,
+    /// offset of the `main` function in the bytecode
+    main: u64,
+    /// builtins used by the program, in the order of the arguments of `main`
+    builtins: Vec<String>,
+    /// hints of the program, by program counter in the memory
+    hints: HashMap<u64, Vec<Hint>>,
+}
+
+impl<F: PrimeField> CairoCompiledProgram<F> {
+    /// Loads a compiled program from the content of its JSON file
+    pub fn from_json(json: &str) -> Result<Self, CairoLoaderError> {
+        let program: CompiledProgramJson = serde_json::from_str(json)?;
+        let prime = parse_hex(&program.prime)?;
+        let data = program
+            .data
+            .iter()
+            .map(|word| to_field(&parse_hex(word)?, &prime))
+            .collect::<Result<Vec<F>, _>>()?;
+        let main_name = format!("{}.main", program.main_scope);
+        let main = match program.identifiers.get(&main_name) {
+            Some(Identifier { typ, pc: Some(pc) }) if typ == "function" => *pc,
+            _ => return Err(CairoLoaderError::MissingMain(main_name)),
+        };
+        let references = program
+            .reference_manager
+            .references
+            .iter()
+            .map(|reference| {
+                HintReference::parse(&reference.value, reference.ap_tracking_data)
+                    .ok_or_else(|| CairoLoaderError::InvalidReference(reference.value.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut hints = HashMap::new();
+        for (pc, hints_at_pc) in &program.hints {
+            let offset: u64 = pc
+                .parse()
+                .map_err(|_| CairoLoaderError::InvalidHintPc(pc.clone()))?;
+            let hints_at_pc = hints_at_pc
+                .iter()
+                .map(|hint| to_hint(hint, &references))
+                .collect::<Result<_, _>>()?;
+            // the memory starts with a dummy entry
+            hints.insert(offset + 1, hints_at_pc);
+        }
+        Ok(CairoCompiledProgram {
+            data,
+            main,
+            builtins: program.builtins,
+            hints,
+        })
+    }
+
+    /// Loads a compiled program from its JSON file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, CairoLoaderError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Returns the bytecode of the program
+    pub fn data(&self) -> &[F] {
+        &self.data
+    }
+
+    /// Returns the offset of the `main` function in the bytecode
+    pub fn main(&self) -> u64 {
+        self.main
+    }
+
+    /// Returns the builtins used by the program
+    pub fn builtins(&self) -> &[String] {
+        &self.builtins
+    }
+
+    /// Returns the hints of the program, by program counter of their
+    /// instruction in the memory returned by [Self::memory]
+    pub fn hints(&self) -> &HashMap<u64, Vec<Hint>> {
+        &self.hints
+    }
+
+    /// Returns the address of the first instruction of `main` in the memory
+    /// returned by [Self::memory]
+    pub fn initial_pc(&self) -> u64 {
+        // the memory starts with a dummy entry
+        self.main + 1
+    }
+
+    /// Returns the initial memory to run the program with
+    /// [crate::runner::CairoProgram], starting at [Self::initial_pc]. It
+    /// contains the bytecode followed by the arguments of `main`, the pointers
+    /// to the segments of its builtins, and by its return frame, so that the
+    /// initial allocation and frame pointers are right after it.
+    pub fn memory(&self) -> Result<CairoMemory<F>, CairoLoaderError> {
+        let unsupported: Vec<String> = self
+            .builtins
+            .iter()
+            .filter(|name| CairoBuiltin::from_name(name).is_none())
+            .cloned()
+            .collect();
+        if !unsupported.is_empty() {
+            return Err(CairoLoaderError::UnsupportedBuiltins(unsupported));
+        }
+        let mut mem = CairoMemory::new(self.data.clone());
+        for name in &self.builtins {
+            let builtin = CairoBuiltin::from_name(name).expect("supported builtin");
+            let base = mem.add_builtin_segment(builtin);
+            mem.write(F::from(mem.len()), base);
+        }
+        let end = F::from(END_OF_PROGRAM);
+        // return frame pointer and return program counter of main
+        mem.write(F::from(mem.len()), end);
+        mem.write(F::from(mem.len()), end);
+        Ok(mem)
+    }
+}
+
+/// Converts a hint of a compiled program, whose variables are named after the
+/// last component of their full name
+fn to_hint(hint: &HintJson, references: &[HintReference]) -> Result<Hint, CairoLoaderError> {
+    let ids = hint
+        .flow_tracking_data
+        .reference_ids
+        .iter()
+        .map(|(full_name, &id)| {
+            let name = full_name.rsplit('.').next().unwrap_or(full_name);
+            let reference = references
+                .get(id)
+                .ok_or_else(|| CairoLoaderError::InvalidReference(full_name.clone()))?;
+            Ok((name.to_string(), *reference))
+        })
+        .collect::<Result<_, CairoLoaderError>>()?;
+    Ok(Hint::new(
+        &hint.code,
+        ids,
+        hint.flow_tracking_data.ap_tracking,
+    ))
+}
+
+/// Parses a hexadecimal number prefixed by `0x`
+fn parse_hex(value: &str) -> Result<BigUint, CairoLoaderError> {
+    value
+        .strip_prefix("0x")
+        .and_then(|digits| BigUint::parse_bytes(digits.as_bytes(), 16))
+        .ok_or_else(|| CairoLoaderError::InvalidFieldElement(value.to_string()))
+}
+
+/// Converts an element of the field of modulus `prime` to `F`, mapping the
+/// elements of the upper half of the field to negative values
+fn to_field<F: PrimeField>(value: &BigUint, prime: &BigUint) -> Result<F, CairoLoaderError> {
+    if value >= prime {
+        return Err(CairoLoaderError::InvalidFieldElement(format!(
+            "0x{value:x}"
+        )));
+    }
+    if value > &(prime >> 1) {
+        Ok(-F::from(prime - value))
+    } else {
+        Ok(F::from(value.clone()))
+    }
+}
@@ -1,14 +1,43 @@
 //! This module represents the Cairo memory, containing the
 //! compiled Cairo program that occupies the first few entries
+//!
+//! The memory is made of a main segment, containing the program and the
+//! execution stack, of one segment per builtin used by the program, and of the
+//! segments allocated by the hints. As the size of the segments is only known
+//! at the end of the execution, the cells of the i-th segment are addressed by
+//! `i * 2^128 + offset` (`i` starting at 1) during the execution. The segments
+//! are then relocated right after the main segment by [CairoMemory::relocate],
+//! so that the memory is contiguous.
 
 use std::{
     fmt::{Display, Formatter, Result},
     ops::{Index, IndexMut},
 };
 
-use crate::{helper::*, word::CairoWord};
+use crate::{
+    builtins::{CairoBuiltin, CairoBuiltinError, CairoField},
+    helper::*,
+    word::CairoWord,
+};
 use ark_ff::Field;
 use core::iter::repeat;
+use o1_utils::FieldHelpers;
+
+/// Number of bits of the offsets of the cells of the segments, before their
+/// relocation
+const SEGMENT_SHIFT: u64 = 128;
+
+/// A segment of the memory, used by a builtin or allocated by a hint
+struct Segment<F> {
+    /// the builtin using the segment, if any
+    builtin: Option<CairoBuiltin>,
+    /// content of the segment, moved to the main segment when relocated
+    data: Vec<Option<CairoWord<F>>>,
+    /// address of the first cell of the segment, once relocated
+    base: Option<u64>,
+    /// number of cells of the segment, once relocated
+    size: u64,
+}
 
 /// This data structure stores the memory of the program
 pub struct CairoMemory<F> {
@@ -16,11 +45,16 @@ pub struct CairoMemory<F> {
     codelen: usize,
     /// full memory vector, None if non initialized
     data: Vec<Option<CairoWord<F>>>,
+    /// segments, in the order they were added
+    segments: Vec<Segment<F>>,
 }
 
 impl<F: Field> Index<F> for CairoMemory<F> {
     type Output = Option<CairoWord<F>>;
     fn index(&self, idx: F) -> &Self::Output {
+        if let Some((segment, offset)) = self.segment_address(idx) {
+            return &self.segments[segment].data[offset as usize];
+        }
         // Safely convert idx from F to usize (since this is a memory address
         // idx should not be too big, this should be safe)
         let addr: u64 = idx.to_u64();
@@ -30,8 +64,11 @@ impl<F: Field> Index<F> for CairoMemory<F> {
 
 impl<F: Field> IndexMut<F> for CairoMemory<F> {
     fn index_mut(&mut self, idx: F) -> &mut Self::Output {
+        self.resize(idx); // Resize if necessary
+        if let Some((segment, offset)) = self.segment_address(idx) {
+            return &mut self.segments[segment].data[offset as usize];
+        }
         let addr: u64 = idx.to_u64();
-        self.resize(addr); // Resize if necessary
         &mut self.data[addr as usize]
     }
 }
@@ -61,6 +98,7 @@ impl<F: Field> CairoMemory<F> {
         CairoMemory {
             codelen: aux.len() - 1,
             data: aux.into_iter().map(|i| Some(CairoWord::new(i))).collect(),
+            segments: vec![],
         }
     }
 
@@ -80,7 +118,15 @@ impl<F: Field> CairoMemory<F> {
     }
 
     /// Resizes memory with enough additional None slots if necessary before writing or reading
-    fn resize(&mut self, addr: u64) {
+    fn resize(&mut self, idx: F) {
+        if let Some((segment, offset)) = self.segment_address(idx) {
+            let data = &mut self.segments[segment].data;
+            if let Some(additional) = (offset + 1).checked_sub(data.len() as u64) {
+                data.extend(repeat(None).take(additional as usize));
+            }
+            return;
+        }
+        let addr = idx.to_u64();
         // if you want to access an index of the memory but its size is less or equal than this
         // you will need to extend the vector with enough spaces (taking into account that
         // vectors start by index 0, the 0 address is dummy, and size starts in 1)
@@ -94,9 +140,166 @@ impl<F: Field> CairoMemory<F> {
         self[addr] = Some(CairoWord::new(elem));
     }
 
-    /// Read element in memory address
+    /// Adds a segment for `builtin` and returns the address of its first cell
+    pub fn add_builtin_segment(&mut self, builtin: CairoBuiltin) -> F {
+        self.push_segment(Some(builtin))
+    }
+
+    /// Adds a segment that is not used by a builtin, such as the ones allocated
+    /// by the hints, and returns the address of its first cell
+    pub fn add_segment(&mut self) -> F {
+        self.push_segment(None)
+    }
+
+    fn push_segment(&mut self, builtin: Option<CairoBuiltin>) -> F {
+        self.segments.push(Segment {
+            builtin,
+            data: vec![],
+            base: None,
+            size: 0,
+        });
+        segment_base(self.segments.len())
+    }
+
+    /// Returns the builtins of the segments of the memory, together with the
+    /// address of the first cell of their segment and its number of cells
+    pub fn builtin_segments(&self) -> Vec<(CairoBuiltin, F, u64)> {
+        self.segments
+            .iter()
+            .enumerate()
+            .filter_map(|(i, segment)| {
+                let builtin = segment.builtin?;
+                Some(match segment.base {
+                    Some(base) => (builtin, F::from(base), segment.size),
+                    None => (builtin, segment_base(i + 1), segment.data.len() as u64),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the relocated value of `value`, which is only modified if it is
+    /// a pointer to a relocated segment
+    pub fn relocate_value(&self, value: F) -> F {
+        match split_address(value) {
+            Some((index, offset)) if index <= self.segments.len() => {
+                match self.segments[index - 1] {
+                    Segment {
+                        base: Some(base),
+                        size,
+                        ..
+                    } if offset <= size => F::from(base + offset),
+                    _ => value,
+                }
+            }
+            _ => value,
+        }
+    }
+
+    /// Returns the index of the segment of `addr` in the segments and the
+    /// offset of `addr` in the segment, if it addresses a segment that is not
+    /// relocated
+    fn segment_address(&self, addr: F) -> Option<(usize, u64)> {
+        match split_address(addr) {
+            Some((index, offset))
+                if index <= self.segments.len() && self.segments[index - 1].base.is_none() =>
+            {
+                Some((index - 1, offset))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<F: CairoField> CairoMemory<F> {
+    /// Read element in memory address. The cells of the builtin segments that
+    /// were not written are deduced by their builtin, if possible.
     pub fn read(&mut self, addr: F) -> Option<F> {
-        self.resize(addr.to_u64()); // Resize if necessary
+        self.resize(addr); // Resize if necessary
+        if let (None, Some((segment, offset))) = (self[addr], self.segment_address(addr)) {
+            let segment = &mut self.segments[segment];
+            let deduced = segment
+                .builtin
+                .and_then(|builtin| builtin.deduce(offset, &segment.data));
+            if let Some(value) = deduced {
+                segment.data[offset as usize] = Some(CairoWord::new(value));
+            }
+        }
         self[addr].map(|x| x.word())
     }
+
+    /// Checks that the cells of the builtin segments follow the rules of their
+    /// builtin
+    ///
+    /// # Errors
+    ///
+    /// Will give error if a cell of a builtin segment is invalid.
+    pub fn check_builtins(&self) -> std::result::Result<(), CairoBuiltinError> {
+        for segment in &self.segments {
+            let Some(builtin) = segment.builtin else {
+                continue;
+            };
+            match segment.base {
+                Some(base) => {
+                    builtin.check(&self.data[base as usize..(base + segment.size) as usize])?
+                }
+                None => builtin.check(&segment.data)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Relocates the segments right after the main segment, in the order they
+    /// were added, and replaces the pointers to their cells by their relocated
+    /// addresses. Any value of the memory in the range of the addresses of a
+    /// segment (or right after its last cell) is considered as a pointer to it.
+    /// The builtin segments are first completed with whole instances, see
+    /// [CairoBuiltin::complete].
+    pub fn relocate(&mut self) {
+        let mut base = self.len();
+        for segment in self.segments.iter_mut().filter(|s| s.base.is_none()) {
+            if let Some(builtin) = segment.builtin {
+                builtin.complete(&mut segment.data);
+            }
+            segment.base = Some(base);
+            segment.size = segment.data.len() as u64;
+            base += segment.size;
+        }
+        for i in 0..self.segments.len() {
+            let data = std::mem::take(&mut self.segments[i].data);
+            self.data.extend(data);
+        }
+        for i in 0..self.data.len() {
+            if let Some(word) = self.data[i] {
+                self.data[i] = Some(CairoWord::new(self.relocate_value(word.word())));
+            }
+        }
+    }
+}
+
+/// Returns the address of the first cell of the `index`-th segment before
+/// relocation
+fn segment_base<F: Field>(index: usize) -> F {
+    F::from(index as u64) * F::from(2u64).pow([SEGMENT_SHIFT])
+}
+
+/// Splits `addr` into the index of a segment (starting at 1) and an offset in
+/// the segment, if `addr` has the shape of an address of a segment before
+/// relocation
+fn split_address<F: Field>(addr: F) -> Option<(usize, u64)> {
+    let bytes = addr.to_bytes();
+    let word = |i: usize| {
+        bytes[8 * i..8 * (i + 1)]
+            .iter()
+            .rev()
+            .fold(0u64, |acc, byte| (acc << 8) + *byte as u64)
+    };
+    let shift = (SEGMENT_SHIFT / 64) as usize;
+    if bytes.len() < 8 * (shift + 1) {
+        return None;
+    }
+    let (offset, index) = (word(0), word(shift));
+    let is_split = index != 0
+        && (1..shift).all(|i| word(i) == 0)
+        && bytes[8 * (shift + 1)..].iter().all(|byte| *byte == 0);
+    is_split.then_some((index as usize, offset))
 }
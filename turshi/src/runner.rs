@@ -1,8 +1,13 @@
 //! This module represents a run of a Cairo program as a series of consecutive
 //! execution steps, each of which define the execution logic of Cairo instructions
 
+use std::collections::HashMap;
+
 use crate::{
+    builtins::{CairoBuiltin, CairoField},
     flags::*,
+    helper::CairoFieldHelpers,
+    hints::{Hint, HintContext, HintError, HintProcessor},
     memory::CairoMemory,
     word::{CairoWord, FlagBits, FlagSets, Offsets},
 };
@@ -150,6 +155,31 @@ impl<F: Field> CairoInstruction<F> {
     pub fn adr_op1(&self) -> F {
         self.vars.adr_op1
     }
+
+    /// Returns the instruction with the pointers, addresses and values
+    /// relocated as the segments of `mem`
+    fn relocate(&self, mem: &CairoMemory<F>) -> Self {
+        let relocate = |value: F| mem.relocate_value(value);
+        let vars = &self.vars;
+        CairoInstruction {
+            word: self.word,
+            ptrs: CairoState::new(
+                relocate(self.ptrs.pc),
+                relocate(self.ptrs.ap),
+                relocate(self.ptrs.fp),
+            ),
+            vars: CairoContext {
+                dst: vars.dst.map(relocate),
+                op0: vars.op0.map(relocate),
+                op1: vars.op1.map(relocate),
+                res: vars.res.map(relocate),
+                adr_dst: relocate(vars.adr_dst),
+                adr_op0: relocate(vars.adr_op0),
+                adr_op1: relocate(vars.adr_op1),
+                size: vars.size,
+            },
+        }
+    }
 }
 
 impl<F: Field> Pointers<F> for CairoInstruction<F> {
@@ -259,7 +289,7 @@ pub struct CairoStep<'a, F> {
     pub vars: CairoContext<F>,
 }
 
-impl<'a, F: Field> CairoStep<'a, F> {
+impl<'a, F: CairoField> CairoStep<'a, F> {
     /// Creates a new Cairo execution step from a step index, a Cairo word, and current pointers
     pub fn new(mem: &mut CairoMemory<F>, ptrs: CairoState<F>) -> CairoStep<F> {
         CairoStep {
@@ -501,9 +531,24 @@ pub struct CairoProgram<'a, F> {
     pub trace: Vec<CairoInstruction<F>>,
 }
 
-impl<'a, F: Field> CairoProgram<'a, F> {
+impl<'a, F: CairoField> CairoProgram<'a, F> {
     /// Creates a Cairo execution from the public information (memory and initial pointers)
     pub fn new(mem: &mut CairoMemory<F>, pc: u64) -> CairoProgram<F> {
+        let mut no_hints =
+            |ctx: &mut HintContext<F>| Err(HintError::UnknownHint(ctx.code().into()));
+        Self::new_with_hints(mem, pc, &HashMap::new(), &mut no_hints)
+    }
+
+    /// Creates a Cairo execution from the public information (memory and
+    /// initial pointers), executing the `hints` given by the program counter of
+    /// their instruction in the memory with `processor`.
+    /// Panics if the execution of a hint fails
+    pub fn new_with_hints<'b>(
+        mem: &'b mut CairoMemory<F>,
+        pc: u64,
+        hints: &HashMap<u64, Vec<Hint>>,
+        processor: &mut impl HintProcessor<F>,
+    ) -> CairoProgram<'b, F> {
         let ap = mem.len();
         let mut prog = CairoProgram {
             steps: F::zero(),
@@ -512,33 +557,13 @@ impl<'a, F: Field> CairoProgram<'a, F> {
             fin: CairoState::new(F::zero(), F::zero(), F::zero()),
             trace: Vec::new(),
         };
-        prog.execute();
+        prog.execute(hints, processor);
         prog
     }
 
-    /// Outputs the total number of steps of the execution carried out by the runner
-    pub fn steps(&self) -> F {
-        self.steps
-    }
-
-    /// Outputs the initial value of the pointers after the execution carried out by the runner
-    pub fn ini(&self) -> CairoState<F> {
-        self.ini
-    }
-
-    /// Outputs the final value of the pointers after the execution carried out by the runner
-    pub fn fin(&self) -> CairoState<F> {
-        self.fin
-    }
-
-    /// Returns a reference to the set of instructions
-    pub fn trace(&self) -> &Vec<CairoInstruction<F>> {
-        &self.trace
-    }
-
     /// This function simulates an execution of the Cairo program received as input.
     /// It generates the full memory stack and the execution trace
-    fn execute(&mut self) {
+    fn execute(&mut self, hints: &HashMap<u64, Vec<Hint>>, processor: &mut impl HintProcessor<F>) {
         // set finishing flag to false, as it just started
         let mut end = false;
         // saves local copy of the initial (claimed) pointers of the program
@@ -548,6 +573,13 @@ impl<'a, F: Field> CairoProgram<'a, F> {
         let mut n: u64 = 0;
         // keep executing steps until the end is reached
         while !end {
+            // fill the memory needed by the instruction with its hints
+            for hint in hints.get(&next.pc.to_u64()).into_iter().flatten() {
+                let mut ctx = HintContext::new(self.mem, next, hint);
+                if let Err(err) = processor.execute(&mut ctx) {
+                    panic!("Hint failed at pc {}: {err}", next.pc.to_u64());
+                }
+            }
             // create current step of computation
             let mut step = CairoStep::new(self.mem, next);
             // save current value of the pointers
@@ -572,5 +604,60 @@ impl<'a, F: Field> CairoProgram<'a, F> {
         }
         self.steps = F::from(n);
         self.fin = CairoState::new(curr.pc, curr.ap, curr.fp);
+
+        // the segments are relocated after the main segment once the
+        // size of the latter is known
+        self.mem.relocate();
+        let mem = &*self.mem;
+        self.trace = self.trace.iter().map(|instr| instr.relocate(mem)).collect();
+        if let Err(err) = self.mem.check_builtins() {
+            panic!("Invalid builtin segment: {err}");
+        }
+    }
+}
+
+impl<'a, F: Field> CairoProgram<'a, F> {
+    /// Outputs the total number of steps of the execution carried out by the runner
+    pub fn steps(&self) -> F {
+        self.steps
+    }
+
+    /// Outputs the initial value of the pointers after the execution carried out by the runner
+    pub fn ini(&self) -> CairoState<F> {
+        self.ini
+    }
+
+    /// Outputs the final value of the pointers after the execution carried out by the runner
+    pub fn fin(&self) -> CairoState<F> {
+        self.fin
+    }
+
+    /// Returns a reference to the set of instructions
+    pub fn trace(&self) -> &Vec<CairoInstruction<F>> {
+        &self.trace
+    }
+
+    /// Returns the public memory of the execution, as pairs of an address and
+    /// its content: the bytecode of the program, the cells written before the
+    /// execution between the program and the initial allocation pointer (the
+    /// arguments and the return frame of the entrypoint), and the content of
+    /// the output segment
+    pub fn public_memory(&self) -> Vec<(F, F)> {
+        let outputs = self
+            .mem
+            .builtin_segments()
+            .into_iter()
+            .filter(|(builtin, _, _)| *builtin == CairoBuiltin::Output)
+            .flat_map(|(_, base, size)| {
+                let base = base.to_u64();
+                base..base + size
+            });
+        (1..self.ini.ap.to_u64())
+            .chain(outputs)
+            .filter_map(|addr| {
+                let addr = F::from(addr);
+                self.mem[addr].map(|word| (addr, word.word()))
+            })
+            .collect()
     }
 }
@@ -0,0 +1,96 @@
+use ark_ff::{Field, One, Zero};
+use mina_curves::pasta::Fp as F;
+use turshi::{
+    builtins::{pedersen_hash, poseidon_permutation, CairoBuiltin, CairoBuiltinError},
+    CairoMemory,
+};
+
+#[test]
+fn test_bitwise_builtin() {
+    let mut mem = CairoMemory::<F>::new(vec![]);
+    let base = mem.add_builtin_segment(CairoBuiltin::Bitwise);
+    mem.write(base, F::from(0b1100u32));
+    mem.write(base + F::one(), F::from(0b1010u32));
+    // the outputs are deduced from the inputs
+    assert_eq!(mem.read(base + F::from(2u32)), Some(F::from(0b1000u32)));
+    assert_eq!(mem.read(base + F::from(3u32)), Some(F::from(0b0110u32)));
+    assert_eq!(mem.read(base + F::from(4u32)), Some(F::from(0b1110u32)));
+    // but not the inputs of the next instance
+    assert_eq!(mem.read(base + F::from(5u32)), None);
+    assert_eq!(mem.check_builtins(), Ok(()));
+
+    mem.write(base + F::from(2u32), F::from(0b1001u32));
+    assert_eq!(
+        mem.check_builtins(),
+        Err(CairoBuiltinError::WrongOutput("bitwise", 2))
+    );
+}
+
+#[test]
+fn test_range_check_builtin() {
+    let mut mem = CairoMemory::<F>::new(vec![]);
+    let base = mem.add_builtin_segment(CairoBuiltin::RangeCheck);
+    mem.write(base, F::from(2u32).pow([128]) - F::one());
+    assert_eq!(mem.read(base + F::one()), None);
+    assert_eq!(mem.check_builtins(), Ok(()));
+
+    mem.write(base + F::one(), F::from(2u32).pow([128]));
+    assert_eq!(
+        mem.check_builtins(),
+        Err(CairoBuiltinError::OutOfRange("range_check", 1))
+    );
+}
+
+#[test]
+fn test_pedersen_builtin() {
+    let mut mem = CairoMemory::<F>::new(vec![]);
+    let base = mem.add_builtin_segment(CairoBuiltin::Pedersen);
+    let (x, y) = (F::from(2u32).pow([253]) + F::from(7u32), F::from(42u32));
+    mem.write(base, x);
+    mem.write(base + F::one(), y);
+    let hash = pedersen_hash(x, y);
+    assert_ne!(hash, pedersen_hash(y, x));
+    assert_eq!(mem.read(base + F::from(2u32)), Some(hash));
+    assert_eq!(mem.check_builtins(), Ok(()));
+
+    mem.write(base + F::from(3u32), F::from(2u32).pow([254]));
+    assert_eq!(
+        mem.check_builtins(),
+        Err(CairoBuiltinError::OutOfRange("pedersen", 3))
+    );
+}
+
+#[test]
+fn test_poseidon_builtin() {
+    let mut mem = CairoMemory::<F>::new(vec![]);
+    let base = mem.add_builtin_segment(CairoBuiltin::Poseidon);
+    let state = [F::from(1u32), F::from(2u32), F::from(3u32)];
+    for (i, value) in state.iter().enumerate() {
+        mem.write(base + F::from(i as u64), *value);
+    }
+    let permuted = poseidon_permutation(state);
+    for (i, value) in permuted.iter().enumerate() {
+        assert_eq!(mem.read(base + F::from(3 + i as u64)), Some(*value));
+    }
+    assert_eq!(mem.check_builtins(), Ok(()));
+
+    mem.write(base + F::from(5u32), permuted[0]);
+    assert_eq!(
+        mem.check_builtins(),
+        Err(CairoBuiltinError::WrongOutput("poseidon", 5))
+    );
+}
+
+#[test]
+fn test_complete_builtin_segment() {
+    let mut mem = CairoMemory::<F>::new(vec![F::zero()]);
+    let base = mem.add_builtin_segment(CairoBuiltin::Bitwise);
+    mem.write(base, F::from(0b1100u32));
+    mem.relocate();
+    // the instance is completed with a zero input and its outputs
+    let (_, base, size) = mem.builtin_segments()[0];
+    assert_eq!(size, 5);
+    assert_eq!(mem.read(base + F::one()), Some(F::zero()));
+    assert_eq!(mem.read(base + F::from(4u32)), Some(F::from(0b1100u32)));
+    assert_eq!(mem.check_builtins(), Ok(()));
+}
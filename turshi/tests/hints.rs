@@ -0,0 +1,266 @@
+use ark_ff::{Field, One, Zero};
+use mina_curves::pasta::Fp as F;
+use turshi::{
+    hints::{
+        ApTracking, HintContext, HintError, HintReference, ALLOC, ASSERT_NN,
+        MEMCPY_CONTINUE_COPYING, MEMCPY_ENTER_SCOPE, SIGNED_DIV_REM, UNSIGNED_DIV_REM,
+        VM_EXIT_SCOPE,
+    },
+    runner::CairoState,
+    BuiltinHintProcessor, CairoBuiltin, CairoCompiledProgram, CairoMemory, CairoProgram, Hint,
+    HintProcessor, Pointers,
+};
+
+const HINTS_PROGRAM: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/programs/hints.json");
+
+/// Creates a hint of `code` accessing the variables `ids` given by their
+/// reference
+fn new_hint(code: &str, ids: &[(&str, &str)], ap_tracking: ApTracking) -> Hint {
+    let ids = ids
+        .iter()
+        .map(|(name, value)| {
+            let reference = HintReference::parse(value, ApTracking::default()).unwrap();
+            (name.to_string(), reference)
+        })
+        .collect();
+    Hint::new(code, ids, ap_tracking)
+}
+
+/// Executes the hint of `code` with the builtin hint processor, with the
+/// allocation and frame pointers at the address 10 of `mem`
+fn execute_hint(
+    mem: &mut CairoMemory<F>,
+    code: &str,
+    ids: &[(&str, &str)],
+) -> Result<(), HintError> {
+    let hint = new_hint(code, ids, ApTracking::default());
+    let ptrs = CairoState::new(F::one(), F::from(10u32), F::from(10u32));
+    BuiltinHintProcessor::default().execute(&mut HintContext::new(mem, ptrs, &hint))
+}
+
+#[test]
+fn test_load_hints() {
+    let program = CairoCompiledProgram::<F>::from_file(HINTS_PROGRAM).unwrap();
+    let hints = program.hints();
+    assert_eq!(hints.len(), 6);
+    // the hints are indexed by their program counter in the memory
+    assert_eq!(hints[&1].len(), 1);
+    assert_eq!(hints[&1][0].code(), ALLOC);
+    assert_eq!(hints[&23][0].code(), UNSIGNED_DIV_REM);
+}
+
+#[test]
+fn test_run_program_with_hints() {
+    let program = CairoCompiledProgram::<F>::from_file(HINTS_PROGRAM).unwrap();
+    let mut mem = program.memory().unwrap();
+    let prog = CairoProgram::new_with_hints(
+        &mut mem,
+        program.initial_pc(),
+        program.hints(),
+        &mut BuiltinHintProcessor::default(),
+    );
+    assert_eq!(prog.trace().len(), 59);
+    assert_eq!(prog.fin().ap(), F::from(123u32));
+    assert_eq!(
+        prog.mem.builtin_segments(),
+        [
+            (CairoBuiltin::Output, F::from(123u32), 2),
+            (CairoBuiltin::RangeCheck, F::from(125u32), 4),
+        ]
+    );
+    let read = |mem: &mut CairoMemory<F>, addrs: std::ops::Range<u32>| -> Vec<F> {
+        addrs.map(|addr| mem.read(F::from(addr)).unwrap()).collect()
+    };
+    // the output is the quotient and the remainder of 100 by 7
+    assert_eq!(read(prog.mem, 123..125), [14u32, 2].map(F::from));
+    // the range checked values: 100, the remainder, the quotient, and the
+    // difference between the divisor and the remainder minus 1
+    assert_eq!(read(prog.mem, 125..129), [100u32, 2, 14, 4].map(F::from));
+    // the segments allocated by the hints are relocated after the builtin
+    // segments, the second one being a copy of the first one
+    assert_eq!(read(prog.mem, 129..133), [100u32, 7, 100, 7].map(F::from));
+    // the local variables of main point to them
+    assert_eq!(read(prog.mem, 77..79), [129u32, 131].map(F::from));
+}
+
+#[test]
+fn test_custom_hint_processor() {
+    let program = CairoCompiledProgram::<F>::from_file(HINTS_PROGRAM).unwrap();
+    let mut mem = program.memory().unwrap();
+    let mut builtin = BuiltinHintProcessor::default();
+    let mut allocations = 0;
+    let mut processor = |ctx: &mut HintContext<F>| {
+        if ctx.code() == ALLOC {
+            allocations += 1;
+        }
+        builtin.execute(ctx)
+    };
+    CairoProgram::new_with_hints(
+        &mut mem,
+        program.initial_pc(),
+        program.hints(),
+        &mut processor,
+    );
+    assert_eq!(allocations, 2);
+}
+
+#[test]
+#[should_panic(expected = "Hint failed at pc 1: unknown hint")]
+fn test_run_program_with_unknown_hints() {
+    let program = CairoCompiledProgram::<F>::from_file(HINTS_PROGRAM).unwrap();
+    let mut mem = program.memory().unwrap();
+    let mut processor =
+        |ctx: &mut HintContext<F>| Err(HintError::UnknownHint(ctx.code().to_string()));
+    CairoProgram::new_with_hints(
+        &mut mem,
+        program.initial_pc(),
+        program.hints(),
+        &mut processor,
+    );
+}
+
+#[test]
+fn test_hint_references() {
+    let mut mem = CairoMemory::<F>::new(vec![F::zero(); 12]);
+    mem.write(F::from(7u32), F::from(42u32));
+    mem.write(F::from(8u32), F::from(2u32));
+    mem.write(F::from(11u32), F::from(5u32));
+    // the allocation pointer moved by 2 cells since the references were
+    // defined
+    let ids = [
+        ("value", "[cast(fp + (-3), felt*)]"),
+        ("address", "cast(fp + (-3), felt*)"),
+        ("pointed", "[cast([fp + (-2)] + 1, felt*)]"),
+        ("last", "[cast(ap + (-1), felt*)]"),
+    ];
+    let hint = new_hint(
+        "",
+        &ids,
+        ApTracking {
+            group: 0,
+            offset: 2,
+        },
+    );
+    let ptrs = CairoState::new(F::one(), F::from(14u32), F::from(10u32));
+    let mut ctx = HintContext::new(&mut mem, ptrs, &hint);
+    assert_eq!(ctx.get("value"), Ok(F::from(42u32)));
+    assert_eq!(ctx.get("address"), Ok(F::from(7u32)));
+    assert_eq!(ctx.get("pointed"), Ok(F::from(0u32)));
+    assert_eq!(ctx.get("last"), Ok(F::from(5u32)));
+    assert_eq!(ctx.set("pointed", F::from(9u32)), Ok(()));
+    assert_eq!(ctx.mem().read(F::from(3u32)), Some(F::from(9u32)));
+    assert_eq!(
+        ctx.set("address", F::one()),
+        Err(HintError::InvalidReference("address".to_string()))
+    );
+    assert_eq!(
+        ctx.get("unknown"),
+        Err(HintError::UnknownIdentifier("unknown".to_string()))
+    );
+
+    // the allocation pointer is not tracked across groups
+    let hint = new_hint(
+        "",
+        &ids,
+        ApTracking {
+            group: 1,
+            offset: 0,
+        },
+    );
+    let mut ctx = HintContext::new(&mut mem, ptrs, &hint);
+    assert_eq!(
+        ctx.get("last"),
+        Err(HintError::InvalidReference("last".to_string()))
+    );
+}
+
+#[test]
+fn test_assert_nn_hint() {
+    let mut mem = CairoMemory::<F>::new(vec![F::zero(); 9]);
+    let ids = [("a", "[cast(fp + (-3), felt*)]")];
+    mem.write(F::from(7u32), F::from(2u32).pow([128]) - F::one());
+    assert_eq!(execute_hint(&mut mem, ASSERT_NN, &ids), Ok(()));
+    mem.write(F::from(7u32), -F::one());
+    assert!(matches!(
+        execute_hint(&mut mem, ASSERT_NN, &ids),
+        Err(HintError::Failed(_))
+    ));
+}
+
+#[test]
+fn test_division_hints() {
+    let mut mem = CairoMemory::<F>::new(vec![F::zero(); 9]);
+    mem.write(F::from(5u32), F::from(20u32));
+    mem.write(F::from(6u32), F::from(100u32));
+    mem.write(F::from(7u32), F::from(7u32));
+    let ids = [
+        ("value", "[cast(fp + (-4), felt*)]"),
+        ("div", "[cast(fp + (-3), felt*)]"),
+        ("r", "[cast([fp + (-5)], felt*)]"),
+        ("q", "[cast([fp + (-5)] + 1, felt*)]"),
+    ];
+    assert_eq!(execute_hint(&mut mem, UNSIGNED_DIV_REM, &ids), Ok(()));
+    assert_eq!(mem.read(F::from(20u32)), Some(F::from(2u32)));
+    assert_eq!(mem.read(F::from(21u32)), Some(F::from(14u32)));
+
+    // the quotient of a negative value is rounded down
+    mem.write(F::from(6u32), -F::from(100u32));
+    mem.write(F::from(8u32), F::from(1u64 << 32));
+    let ids = [
+        ("value", "[cast(fp + (-4), felt*)]"),
+        ("div", "[cast(fp + (-3), felt*)]"),
+        ("bound", "[cast(fp + (-2), felt*)]"),
+        ("r", "[cast([fp + (-5)], felt*)]"),
+        ("biased_q", "[cast([fp + (-5)] + 1, felt*)]"),
+    ];
+    assert_eq!(execute_hint(&mut mem, SIGNED_DIV_REM, &ids), Ok(()));
+    assert_eq!(mem.read(F::from(20u32)), Some(F::from(5u32)));
+    assert_eq!(mem.read(F::from(21u32)), Some(F::from((1u64 << 32) - 15)));
+
+    // the divisor cannot be zero
+    mem.write(F::from(7u32), F::zero());
+    assert!(matches!(
+        execute_hint(&mut mem, UNSIGNED_DIV_REM, &ids),
+        Err(HintError::Failed(_))
+    ));
+}
+
+#[test]
+fn test_scope_hints() {
+    let mut mem = CairoMemory::<F>::new(vec![F::zero(); 12]);
+    mem.write(F::from(7u32), F::from(2u32));
+    let ids = [
+        ("len", "[cast(fp + (-3), felt*)]"),
+        ("continue_copying", "[cast(ap + 1, felt*)]"),
+    ];
+    let ptrs = CairoState::new(F::one(), F::from(10u32), F::from(10u32));
+    let mut processor = BuiltinHintProcessor::default();
+    let mut execute = |mem: &mut CairoMemory<F>, code: &str| {
+        let hint = new_hint(code, &ids, ApTracking::default());
+        processor.execute(&mut HintContext::new(mem, ptrs, &hint))
+    };
+    assert_eq!(
+        execute(&mut mem, MEMCPY_CONTINUE_COPYING),
+        Err(HintError::UnknownScopeVariable("n".to_string()))
+    );
+    // copy 2 cells
+    assert_eq!(execute(&mut mem, MEMCPY_ENTER_SCOPE), Ok(()));
+    assert_eq!(execute(&mut mem, MEMCPY_CONTINUE_COPYING), Ok(()));
+    assert_eq!(mem.read(F::from(11u32)), Some(F::one()));
+    assert_eq!(execute(&mut mem, MEMCPY_CONTINUE_COPYING), Ok(()));
+    assert_eq!(mem.read(F::from(11u32)), Some(F::zero()));
+    assert_eq!(execute(&mut mem, VM_EXIT_SCOPE), Ok(()));
+    assert!(matches!(
+        execute(&mut mem, VM_EXIT_SCOPE),
+        Err(HintError::Failed(_))
+    ));
+}
+
+#[test]
+fn test_unknown_hint() {
+    let mut mem = CairoMemory::<F>::new(vec![]);
+    assert_eq!(
+        execute_hint(&mut mem, "print(ids.a)", &[]),
+        Err(HintError::UnknownHint("print(ids.a)".to_string()))
+    );
+}
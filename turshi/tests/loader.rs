@@ -0,0 +1,172 @@
+use mina_curves::pasta::Fp as F;
+use turshi::{
+    builtins::{pedersen_hash, poseidon_permutation},
+    loader::{CairoLoaderError, END_OF_PROGRAM},
+    CairoBuiltin, CairoCompiledProgram, CairoProgram, Pointers,
+};
+
+const DOUBLE_PROGRAM: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/programs/double.json");
+const BUILTINS_PROGRAM: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/programs/builtins.json");
+const HASHES_PROGRAM: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/programs/hashes.json");
+
+#[test]
+fn test_load_compiled_program() {
+    let program = CairoCompiledProgram::<F>::from_file(DOUBLE_PROGRAM).unwrap();
+    assert_eq!(program.main(), 2);
+    assert_eq!(program.initial_pc(), 3);
+    assert!(program.builtins().is_empty());
+    assert_eq!(program.data().len(), 8);
+    assert_eq!(program.data()[3], F::from(10u32));
+    // the offset of the relative call is negative
+    assert_eq!(program.data()[5], -F::from(4u32));
+}
+
+#[test]
+fn test_run_compiled_program() {
+    let program = CairoCompiledProgram::<F>::from_file(DOUBLE_PROGRAM).unwrap();
+    let mut mem = program.memory().unwrap();
+    let prog = CairoProgram::new(&mut mem, program.initial_pc());
+    assert_eq!(prog.trace().len(), 6);
+    assert_eq!(prog.ini().pc(), F::from(3u32));
+    assert_eq!(prog.ini().ap(), F::from(11u32));
+    assert_eq!(prog.fin().pc(), F::from(8u32));
+    assert_eq!(prog.fin().ap(), F::from(16u32));
+    assert_eq!(
+        prog.mem.read(F::from(9u32)).unwrap(),
+        F::from(END_OF_PROGRAM)
+    );
+    assert_eq!(prog.mem.read(F::from(11u32)).unwrap(), F::from(10u32));
+    assert_eq!(prog.mem.read(F::from(14u32)).unwrap(), F::from(20u32));
+    assert_eq!(prog.mem.read(F::from(15u32)).unwrap(), F::from(400u32));
+}
+
+#[test]
+fn test_load_invalid_programs() {
+    let without_main = r#"{"prime": "0x11", "data": ["0x1"], "identifiers": {}}"#;
+    assert!(matches!(
+        CairoCompiledProgram::<F>::from_json(without_main),
+        Err(CairoLoaderError::MissingMain(_))
+    ));
+
+    let out_of_field = r#"{
+        "prime": "0x11",
+        "data": ["0x12"],
+        "identifiers": {"__main__.main": {"pc": 0, "type": "function"}}
+    }"#;
+    assert!(matches!(
+        CairoCompiledProgram::<F>::from_json(out_of_field),
+        Err(CairoLoaderError::InvalidFieldElement(_))
+    ));
+
+    let with_builtins = r#"{
+        "prime": "0x11",
+        "data": ["0x1"],
+        "builtins": ["output", "ecdsa"],
+        "identifiers": {"__main__.main": {"pc": 0, "type": "function"}}
+    }"#;
+    let program = CairoCompiledProgram::<F>::from_json(with_builtins).unwrap();
+    assert_eq!(program.builtins(), ["output", "ecdsa"]);
+    assert!(matches!(
+        program.memory(),
+        Err(CairoLoaderError::UnsupportedBuiltins(builtins)) if builtins == ["ecdsa"]
+    ));
+}
+
+#[test]
+fn test_run_program_with_builtins() {
+    let program = CairoCompiledProgram::<F>::from_file(BUILTINS_PROGRAM).unwrap();
+    let mut mem = program.memory().unwrap();
+    let prog = CairoProgram::new(&mut mem, program.initial_pc());
+    assert_eq!(prog.trace().len(), 16);
+    // the builtin segments are relocated after the main segment, which ends
+    // with the 9 cells allocated by the program
+    assert_eq!(prog.ini().ap(), F::from(28u32));
+    assert_eq!(prog.fin().ap(), F::from(37u32));
+    assert_eq!(
+        prog.mem.builtin_segments(),
+        [
+            (CairoBuiltin::Output, F::from(37u32), 3),
+            (CairoBuiltin::RangeCheck, F::from(40u32), 1),
+            (CairoBuiltin::Bitwise, F::from(41u32), 5),
+        ]
+    );
+    // the arguments of main point to the relocated segments
+    assert_eq!(prog.mem.read(F::from(23u32)).unwrap(), F::from(37u32));
+    assert_eq!(prog.mem.read(F::from(24u32)).unwrap(), F::from(40u32));
+    assert_eq!(prog.mem.read(F::from(25u32)).unwrap(), F::from(41u32));
+    // and so do the pointers returned by main
+    assert_eq!(prog.mem.read(F::from(34u32)).unwrap(), F::from(40u32));
+    assert_eq!(prog.mem.read(F::from(35u32)).unwrap(), F::from(41u32));
+    assert_eq!(prog.mem.read(F::from(36u32)).unwrap(), F::from(46u32));
+    // the range checked value, and the inputs and outputs of the bitwise
+    // builtin
+    assert_eq!(prog.mem.read(F::from(40u32)).unwrap(), F::from(1000u32));
+    let bitwise: Vec<F> = (41..46u32)
+        .map(|addr| prog.mem.read(F::from(addr)).unwrap())
+        .collect();
+    assert_eq!(bitwise, [12u32, 10, 8, 6, 14].map(F::from));
+    // the trace refers to the relocated addresses
+    assert_eq!(prog.trace()[1].adr_op1(), F::from(40u32));
+
+    let public_memory = prog.public_memory();
+    assert_eq!(public_memory.len(), 22 + 5 + 3);
+    assert_eq!(public_memory[0], (F::from(1u32), program.data()[0]));
+    assert_eq!(
+        public_memory[27..],
+        [
+            (F::from(37u32), F::from(8u32)),
+            (F::from(38u32), F::from(6u32)),
+            (F::from(39u32), F::from(14u32)),
+        ]
+    );
+}
+
+#[test]
+fn test_run_program_with_hash_builtins() {
+    let program = CairoCompiledProgram::<F>::from_file(HASHES_PROGRAM).unwrap();
+    let mut mem = program.memory().unwrap();
+    let prog = CairoProgram::new(&mut mem, program.initial_pc());
+    assert_eq!(prog.trace().len(), 14);
+    assert_eq!(prog.fin().ap(), F::from(33u32));
+    assert_eq!(
+        prog.mem.builtin_segments(),
+        [
+            (CairoBuiltin::Pedersen, F::from(33u32), 3),
+            (CairoBuiltin::Poseidon, F::from(36u32), 6),
+        ]
+    );
+    // the pointers returned by main
+    assert_eq!(prog.mem.read(F::from(31u32)).unwrap(), F::from(36u32));
+    assert_eq!(prog.mem.read(F::from(32u32)).unwrap(), F::from(42u32));
+    // the hash of 1 and 2 is the first input of the permutation
+    let hash = pedersen_hash(F::from(1u32), F::from(2u32));
+    let state = [hash, F::from(3u32), F::from(4u32)];
+    let segments: Vec<F> = (33..42u32)
+        .map(|addr| prog.mem.read(F::from(addr)).unwrap())
+        .collect();
+    assert_eq!(segments[..3], [F::from(1u32), F::from(2u32), hash]);
+    assert_eq!(segments[3..6], state);
+    assert_eq!(segments[6..], poseidon_permutation(state));
+}
+
+#[test]
+#[should_panic(expected = "Invalid builtin segment")]
+fn test_range_check_out_of_range() {
+    // [ap] = -1, ap++
+    // [ap - 1] = [[fp - 3]]
+    // ret
+    let program = r#"{
+        "prime": "0x800000000000011000000000000000000000000000000000000000000000001",
+        "data": [
+            "0x480680017fff8000",
+            "0x800000000000011000000000000000000000000000000000000000000000000",
+            "0x400280007ffd7fff",
+            "0x208b7fff7fff7ffe"
+        ],
+        "builtins": ["range_check"],
+        "identifiers": {"__main__.main": {"pc": 0, "type": "function"}}
+    }"#;
+    let program = CairoCompiledProgram::<F>::from_json(program).unwrap();
+    let mut mem = program.memory().unwrap();
+    CairoProgram::new(&mut mem, program.initial_pc());
+}
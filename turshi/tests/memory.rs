@@ -1,6 +1,6 @@
 use ark_ff::One;
 use mina_curves::pasta::Fp as F;
-use turshi::CairoMemory;
+use turshi::{CairoBuiltin, CairoMemory};
 
 #[test]
 fn test_cairo_bytecode() {
@@ -31,3 +31,37 @@ fn test_cairo_bytecode() {
     assert_eq!(6, memory.len() - 1);
     memory.read(F::from(10u32));
 }
+
+#[test]
+fn test_relocate_segments() {
+    let mut memory = CairoMemory::new(vec![F::from(1u32), F::from(2u32)]);
+    let output = memory.add_builtin_segment(CairoBuiltin::Output);
+    let range_check = memory.add_builtin_segment(CairoBuiltin::RangeCheck);
+    // the segments do not overlap before relocation
+    memory.write(output, F::from(10u32));
+    memory.write(output + F::one(), F::from(11u32));
+    memory.write(range_check, F::from(12u32));
+    assert_eq!(memory.read(output), Some(F::from(10u32)));
+    assert_eq!(memory.read(range_check), Some(F::from(12u32)));
+    // pointers to the segments, including to the end of a segment
+    memory.write(F::from(3u32), output + F::from(2u32));
+    memory.write(F::from(4u32), range_check);
+    assert_eq!(memory.len(), 5);
+
+    memory.relocate();
+    assert_eq!(
+        memory.builtin_segments(),
+        [
+            (CairoBuiltin::Output, F::from(5u32), 2),
+            (CairoBuiltin::RangeCheck, F::from(7u32), 1)
+        ]
+    );
+    assert_eq!(memory.len(), 8);
+    assert_eq!(memory.read(F::from(3u32)), Some(F::from(7u32)));
+    assert_eq!(memory.read(F::from(4u32)), Some(F::from(7u32)));
+    let relocated: Vec<_> = (5..8u32).map(|i| memory.read(F::from(i))).collect();
+    assert_eq!(relocated, [10u32, 11, 12].map(|x| Some(F::from(x))));
+    assert_eq!(memory.relocate_value(output + F::one()), F::from(6u32));
+    // other values are left untouched
+    assert_eq!(memory.read(F::one()), Some(F::one()));
+}
@@ -0,0 +1,22 @@
+%builtins output range_check bitwise
+
+from starkware.cairo.common.cairo_builtins import BitwiseBuiltin
+
+func main{output_ptr: felt*, range_check_ptr, bitwise_ptr: BitwiseBuiltin*}() {
+    tempvar a = 1000;
+    assert [range_check_ptr] = a;
+    tempvar x = 12;
+    assert bitwise_ptr.x = x;
+    tempvar y = 10;
+    assert bitwise_ptr.y = y;
+    tempvar x_and_y = bitwise_ptr.x_and_y;
+    assert [output_ptr] = x_and_y;
+    tempvar x_xor_y = bitwise_ptr.x_xor_y;
+    assert [output_ptr + 1] = x_xor_y;
+    tempvar x_or_y = bitwise_ptr.x_or_y;
+    assert [output_ptr + 2] = x_or_y;
+    let output_ptr = output_ptr + 3;
+    let range_check_ptr = range_check_ptr + 1;
+    let bitwise_ptr = bitwise_ptr + BitwiseBuiltin.SIZE;
+    return ();
+}
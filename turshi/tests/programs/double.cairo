@@ -0,0 +1,10 @@
+func double(x: felt) -> felt {
+    return x + x;
+}
+
+func main() {
+    tempvar x = 10;
+    let y = double(x);
+    tempvar z = y * y;
+    return ();
+}
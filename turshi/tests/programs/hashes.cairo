@@ -0,0 +1,20 @@
+%builtins pedersen poseidon
+
+from starkware.cairo.common.cairo_builtins import HashBuiltin, PoseidonBuiltin
+
+func main{pedersen_ptr: HashBuiltin*, poseidon_ptr: PoseidonBuiltin*}() {
+    tempvar x = 1;
+    assert pedersen_ptr.x = x;
+    tempvar y = 2;
+    assert pedersen_ptr.y = y;
+    tempvar h = pedersen_ptr.result;
+    assert poseidon_ptr.input.s0 = h;
+    tempvar s1 = 3;
+    assert poseidon_ptr.input.s1 = s1;
+    tempvar s2 = 4;
+    assert poseidon_ptr.input.s2 = s2;
+    tempvar r = poseidon_ptr.output.s0;
+    let pedersen_ptr = pedersen_ptr + HashBuiltin.SIZE;
+    let poseidon_ptr = poseidon_ptr + PoseidonBuiltin.SIZE;
+    return ();
+}
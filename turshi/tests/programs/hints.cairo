@@ -0,0 +1,93 @@
+%builtins output range_check
+
+// The functions of the common library used by the program are defined here with
+// the same hints, in a simpler form.
+
+func alloc() -> (ptr: felt*) {
+    %{ memory[ap] = segments.add() %}
+    ap += 1;
+    return (ptr=cast([ap - 1], felt*));
+}
+
+func memcpy(dst: felt*, src: felt*, len) {
+    struct LoopFrame {
+        dst: felt*,
+        src: felt*,
+    }
+
+    if (len == 0) {
+        return ();
+    }
+
+    %{ vm_enter_scope({'n': ids.len}) %}
+    tempvar frame = LoopFrame(dst=dst, src=src);
+
+    loop:
+    let frame = [cast(ap - LoopFrame.SIZE, LoopFrame*)];
+    let continue_copying = [ap + 1];
+    tempvar value = [frame.src];
+    assert [frame.dst] = value;
+    %{
+        n -= 1
+        ids.continue_copying = 1 if n > 0 else 0
+    %}
+    let next_frame = cast(ap + 1, LoopFrame*);
+    next_frame.dst = frame.dst + 1, ap++;
+    next_frame.src = frame.src + 1, ap++;
+    jmp loop if continue_copying != 0, ap++;
+    // Assert that the loop executed len times.
+    assert next_frame.src = len + src;
+
+    %{ vm_exit_scope() %}
+    return ();
+}
+
+func assert_nn{range_check_ptr}(a) {
+    %{
+        from starkware.cairo.common.math_utils import assert_integer
+        assert_integer(ids.a)
+        assert 0 <= ids.a % PRIME < range_check_builtin.bound, f'a = {ids.a} is out of range.'
+    %}
+    a = [range_check_ptr];
+    let range_check_ptr = range_check_ptr + 1;
+    return ();
+}
+
+func unsigned_div_rem{range_check_ptr}(value, div) -> (q: felt, r: felt) {
+    let r = [range_check_ptr];
+    let q = [range_check_ptr + 1];
+    %{
+        from starkware.cairo.common.math_utils import assert_integer
+        assert_integer(ids.div)
+        assert 0 < ids.div <= PRIME // range_check_builtin.bound, \
+            f'div={hex(ids.div)} is out of the valid range.'
+        ids.q, ids.r = divmod(ids.value, ids.div)
+    %}
+    // r < div
+    tempvar r = r;
+    tempvar minus_r = r * (-1);
+    tempvar div_minus_r = minus_r + div;
+    tempvar gap = div_minus_r - 1;
+    assert [range_check_ptr + 2] = gap;
+    // value = q * div + r
+    tempvar q = q;
+    tempvar q_div = q * div;
+    assert value = q_div + r;
+    let range_check_ptr = range_check_ptr + 3;
+    return (q=q, r=r);
+}
+
+func main{output_ptr: felt*, range_check_ptr}() {
+    alloc_locals;
+    let (local src: felt*) = alloc();
+    assert src[0] = 100;
+    assert src[1] = 7;
+    let (local dst: felt*) = alloc();
+    memcpy(dst=dst, src=src, len=2);
+    assert_nn(dst[0]);
+    let (q, r) = unsigned_div_rem(dst[0], dst[1]);
+    assert output_ptr[0] = q;
+    assert output_ptr[1] = r;
+    let output_ptr = output_ptr + 2;
+    return ();
+}